@@ -0,0 +1,128 @@
+//! `blazegraph backend-compare` — run several PDF backends on one document
+
+use anyhow::{Context, Result};
+use blazegraph_io_core::backend_compare::{compare_extractions, extract_all, BackendComparison};
+use blazegraph_io_core::preprocessors::PdfBackendImpl;
+use blazegraph_io_core::ParsingConfig;
+
+/// Pages with differing span counts listed in the readable report
+const SHOWN_PAGES: usize = 20;
+
+/// Compare `backends` on `input` (the first is the baseline), writing each
+/// backend's XHTML to `output_dir` when given
+pub fn run(
+    input: &str,
+    backends: &[PdfBackendImpl],
+    config: &ParsingConfig,
+    json: bool,
+    output_dir: Option<&str>,
+) -> Result<()> {
+    let pdf_bytes = std::fs::read(input).with_context(|| format!("Failed to read {}", input))?;
+    let extractions = extract_all(&pdf_bytes, backends);
+    if let Some(dir) = output_dir {
+        std::fs::create_dir_all(dir)?;
+        for extraction in &extractions {
+            if let Ok(xhtml) = &extraction.xhtml {
+                let slug: String = extraction
+                    .backend
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+                    .collect();
+                std::fs::write(format!("{}/{}.xhtml", dir, slug), xhtml)?;
+            }
+        }
+        println!("💾 Backend XHTML written to {}", dir);
+    }
+    let comparison = compare_extractions(&extractions, config)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&comparison)?);
+    } else {
+        print_report(input, &comparison);
+    }
+    Ok(())
+}
+
+fn print_report(input: &str, comparison: &BackendComparison) {
+    println!("\n🔬 {} (baseline: {})\n", input, comparison.baseline);
+    let header = ["backend", "ms", "pages", "spans", "words", "coverage", "extra", "nodes", "depth", "similarity"];
+    let rows: Vec<Vec<String>> = comparison
+        .backends
+        .iter()
+        .map(|result| {
+            if let Some(error) = &result.error {
+                return vec![result.backend.clone(), format!("{:.0}", result.extraction_ms), format!("failed: {}", error)];
+            }
+            vec![
+                result.backend.clone(),
+                format!("{:.0}", result.extraction_ms),
+                result.pages.to_string(),
+                result.spans.to_string(),
+                result.words.to_string(),
+                format!("{:.1}%", result.text_coverage * 100.0),
+                result.extra_words.to_string(),
+                result.graph.nodes.to_string(),
+                result.graph.max_depth.to_string(),
+                result.similarity.as_ref().map_or("-".to_string(), |s| format!("{:.3}", s.score)),
+            ]
+        })
+        .collect();
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .filter(|row| row.len() == header.len())
+                .map(|row| row[i].chars().count())
+                .fold(header[i].len(), usize::max)
+        })
+        .collect();
+    let line = |cells: &[String]| -> String {
+        cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, width))| if i == 0 { format!("{:<width$}", cell) } else { format!("{:>width$}", cell) })
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+    println!("{}", line(&header.map(String::from)));
+    println!("{}", "─".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1)));
+    for row in &rows {
+        match row.len() {
+            3 => println!("{}  {}  {}", line(&row[..1]), row[1], row[2]),
+            _ => println!("{}", line(row)),
+        }
+    }
+
+    // Node type deltas against the baseline
+    let baseline = &comparison.backends[0].graph;
+    for result in comparison.backends.iter().skip(1).filter(|r| r.error.is_none()) {
+        let deltas: Vec<String> = baseline
+            .node_types
+            .keys()
+            .chain(result.graph.node_types.keys())
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .filter_map(|node_type| {
+                let before = baseline.node_types.get(node_type).copied().unwrap_or(0) as i64;
+                let after = result.graph.node_types.get(node_type).copied().unwrap_or(0) as i64;
+                (before != after).then(|| format!("{} {:+}", node_type, after - before))
+            })
+            .collect();
+        if !deltas.is_empty() {
+            println!("\n📊 {} vs {}: {}", result.backend, comparison.baseline, deltas.join(", "));
+        }
+    }
+
+    if comparison.page_differences.is_empty() {
+        println!("\n✅ Every page has the same number of spans");
+        return;
+    }
+    println!("\n📄 {} page(s) with differing span counts:", comparison.page_differences.len());
+    for difference in comparison.page_differences.iter().take(SHOWN_PAGES) {
+        let counts: Vec<String> = difference.spans.iter().map(|n| n.to_string()).collect();
+        println!("   page {:>4}: {}", difference.page, counts.join(" / "));
+    }
+    if comparison.page_differences.len() > SHOWN_PAGES {
+        println!("   … {} more (use --json for all)", comparison.page_differences.len() - SHOWN_PAGES);
+    }
+}
@@ -0,0 +1,27 @@
+//! `blazegraph cache purge` — enforce a retention policy on the cache directory
+
+use anyhow::{bail, Result};
+use blazegraph_io_core::retention::RetentionPolicy;
+use blazegraph_io_core::storage::FileStorage;
+use std::path::Path;
+
+pub fn purge(policy_path: &str, cache_dir: &str, dry_run: bool) -> Result<()> {
+    if !Path::new(cache_dir).is_dir() {
+        bail!("Cache directory not found: {}", cache_dir);
+    }
+    let policy = RetentionPolicy::load(policy_path)?;
+    let report = FileStorage::new(cache_dir)?.with_retention(policy).purge(dry_run)?;
+
+    let verb = if dry_run { "Would delete" } else { "Deleted" };
+    for (area, count) in &report.removed {
+        println!("   {:<11} {} entries", area.name(), count);
+    }
+    println!(
+        "🧹 {} {} cache entries ({:.1} MB) from {}",
+        verb,
+        report.total(),
+        report.bytes as f64 / (1024.0 * 1024.0),
+        cache_dir
+    );
+    Ok(())
+}
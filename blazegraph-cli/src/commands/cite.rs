@@ -0,0 +1,32 @@
+//! `blazegraph cite` — resolve citation ids back to their source location
+
+use anyhow::Result;
+use blazegraph_io_core::DocumentGraph;
+
+pub fn run(graph_path: &str, citations: &[String], json: bool) -> Result<()> {
+    let graph = DocumentGraph::load(graph_path)?;
+    let resolved = citations
+        .iter()
+        .map(|id| graph.resolve_citation(id))
+        .collect::<Result<Vec<_>>>()?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&resolved)?);
+        return Ok(());
+    }
+
+    for citation in resolved {
+        println!("📌 {}  {}", citation.id, citation.label);
+        if !citation.breadcrumbs.is_empty() {
+            println!("   {}", citation.breadcrumbs.join(" > "));
+        }
+        println!("   {} {}", citation.node_type, citation.node_id);
+        if let Some(bbox) = &citation.bounding_box {
+            println!(
+                "   bbox: x={:.1} y={:.1} w={:.1} h={:.1}",
+                bbox.x, bbox.y, bbox.width, bbox.height
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,20 @@
+//! `blazegraph decrypt` — read back an output written with `--encrypt`
+
+use anyhow::{Context, Result};
+use blazegraph_io_core::encryption::Cipher;
+use std::io::Write;
+
+pub fn run(input: &str, output: Option<&str>, cipher: &Cipher) -> Result<()> {
+    let sealed = std::fs::read(input).with_context(|| format!("Failed to read {}", input))?;
+    let plaintext = cipher
+        .open(&sealed)
+        .with_context(|| format!("Failed to decrypt {}", input))?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, plaintext)?;
+            eprintln!("🔓 Decrypted {} to {}", input, path);
+        }
+        None => std::io::stdout().write_all(&plaintext)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,40 @@
+//! `blazegraph graft` — attach a supplement's graph under a node of another
+
+use anyhow::{bail, Result};
+use blazegraph_io_core::graphs::GraftOptions;
+use blazegraph_io_core::{DocumentGraph, NodeId};
+
+pub fn run(graph_path: &str, supplement_path: &str, under: Option<&str>, heading: Option<String>, output: &str) -> Result<()> {
+    let mut graph = DocumentGraph::load(graph_path)?;
+    let supplement = DocumentGraph::load(supplement_path)?;
+    let anchor = match under {
+        Some(node) => find_node(&graph, node)?,
+        None => graph.document_info.root_id,
+    };
+    let record = graph.graft(anchor, &supplement, &GraftOptions { heading })?;
+    graph.save_to_json(output)?;
+
+    let anchor = &graph.nodes[&anchor];
+    println!(
+        "🪢 Grafted {} node(s) from {} under {} \"{}\"",
+        record.node_count,
+        supplement_path,
+        if anchor.location.semantic.path.is_empty() { "the root" } else { &anchor.location.semantic.path },
+        anchor.content.text
+    );
+    println!("💾 Merged graph written to {}", output);
+    Ok(())
+}
+
+/// A node by id or by semantic path
+fn find_node(graph: &DocumentGraph, node: &str) -> Result<NodeId> {
+    if let Ok(id) = node.parse::<NodeId>() {
+        if graph.nodes.contains_key(&id) {
+            return Ok(id);
+        }
+    }
+    match graph.nodes.values().find(|n| n.location.semantic.path == node) {
+        Some(found) => Ok(found.id),
+        None => bail!("No node with id or path '{}' in the graph", node),
+    }
+}
@@ -0,0 +1,21 @@
+//! Subcommands that operate on already-processed graph outputs.
+//!
+//! These don't need a PDF backend — they read a graph.json written by a
+//! previous run and report on it. Graphs are read with `DocumentGraph::load`,
+//! which migrates outputs written by older schema versions. `similar` scores
+//! two graphs against each other, `cite` resolves citation ids from
+//! sequential output back to their source location, `decrypt` turns an output written with
+//! `--encrypt` back into plaintext, `graft` attaches a supplement's graph
+//! under a node of a main document's graph, `cache purge` applies a retention
+//! policy to the cache directory, and `report` summarizes a `--stats-db`.
+//! `backend-compare` is the exception: it runs PDF backends side by side.
+
+pub mod backend_compare;
+pub mod cache;
+pub mod cite;
+pub mod decrypt;
+pub mod graft;
+pub mod report;
+pub mod search;
+pub mod similar;
+pub mod stats;
@@ -0,0 +1,92 @@
+//! `blazegraph report` — quality and failure trends from a stats database
+
+use anyhow::Result;
+use blazegraph_io_core::retention::parse_age;
+use blazegraph_io_core::stats_db::{Group, Period, StatsDb, TrendReport};
+use chrono::Utc;
+
+const HEADERS: [&str; 7] = ["", "docs", "failed", "quality", "tok/node", "p90", "drift"];
+
+/// Print the trend report for the rows in `db` (newer than `since`, an age
+/// like "30d", when given)
+pub fn run(db: &str, period: &str, since: Option<&str>, json: bool) -> Result<()> {
+    let period = Period::parse(period)?;
+    let since = match since {
+        Some(age) => Some(Utc::now() - chrono::Duration::from_std(parse_age(age)?)?),
+        None => None,
+    };
+    let rows = StatsDb::read(db)?;
+    let report = TrendReport::build(&rows, period, since);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("📈 {} document(s) in {}", report.overall.documents, db);
+    println!(
+        "   Failure rate {}, average quality {}, {} tokens per node\n",
+        percent(Some(report.overall.failure_rate())),
+        decimal(report.overall.avg_quality, 2),
+        decimal(report.overall.tokens_per_node, 1)
+    );
+    print_table("period", &report.periods);
+    print_table("document type", &report.by_document_type);
+    print_table("format", &report.by_format);
+    Ok(())
+}
+
+fn percent(value: Option<f32>) -> String {
+    value.map_or("-".to_string(), |v| format!("{:.1}%", v * 100.0))
+}
+
+fn decimal(value: Option<f32>, places: usize) -> String {
+    value.map_or("-".to_string(), |v| format!("{:.*}", places, v))
+}
+
+fn print_table(title: &str, groups: &[Group]) {
+    // Drift is only measured across periods
+    let columns = if groups.iter().any(|g| g.token_drift.is_some()) { HEADERS.len() } else { HEADERS.len() - 1 };
+    let cells: Vec<Vec<String>> = groups
+        .iter()
+        .map(|g| {
+            vec![
+                g.key.clone(),
+                g.totals.documents.to_string(),
+                percent(Some(g.totals.failure_rate())),
+                decimal(g.totals.avg_quality, 2),
+                decimal(g.totals.tokens_per_node, 1),
+                decimal(g.totals.avg_p90_tokens, 0),
+                g.token_drift.map_or(String::new(), |d| format!("{:+.1}%", d * 100.0)),
+            ][..columns]
+                .to_vec()
+        })
+        .collect();
+    let mut header: Vec<String> = HEADERS[..columns].iter().map(|h| h.to_string()).collect();
+    header[0] = title.to_string();
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| cells.iter().map(|c| c[i].chars().count()).fold(header[i].chars().count(), usize::max))
+        .collect();
+
+    let format_row = |cols: &[String]| -> String {
+        cols.iter()
+            .enumerate()
+            .map(|(i, c)| {
+                // Left-align the group name, right-align the numbers
+                if i == 0 {
+                    format!("{:<width$}", c, width = widths[i])
+                } else {
+                    format!("{:>width$}", c, width = widths[i])
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+
+    println!("{}", format_row(&header));
+    println!("{}", "─".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1)));
+    for row in &cells {
+        println!("{}", format_row(row));
+    }
+    println!();
+}
@@ -0,0 +1,45 @@
+//! `blazegraph search` — locate where content landed in a processed graph
+
+use anyhow::Result;
+use blazegraph_io_core::graphs::{SearchHit, SearchOptions};
+use blazegraph_io_core::DocumentGraph;
+
+/// Characters of surrounding text shown on each side of a match
+const SNIPPET_CONTEXT: usize = 60;
+
+pub fn run(graph_path: &str, query: &str, options: &SearchOptions, json: bool) -> Result<()> {
+    let graph = DocumentGraph::load(graph_path)?;
+    let hits = graph.search(query, options)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&hits)?);
+        return Ok(());
+    }
+
+    if hits.is_empty() {
+        println!("🔍 No matches for \"{}\" in {}", query, graph_path);
+        return Ok(());
+    }
+
+    println!("🔍 {} match(es) for \"{}\" in {}\n", hits.len(), query, graph_path);
+    for hit in &hits {
+        print_hit(hit);
+    }
+
+    Ok(())
+}
+
+fn print_hit(hit: &SearchHit) {
+    let trail = if hit.breadcrumbs.is_empty() {
+        "(root)".to_string()
+    } else {
+        hit.breadcrumbs.join(" › ")
+    };
+    let page = hit
+        .page
+        .map(|p| format!("p.{}", p))
+        .unwrap_or_else(|| "p.-".to_string());
+
+    println!("  {} [{}] {} @ {}", hit.node_type, hit.path, page, trail);
+    println!("    {}\n", hit.snippet(SNIPPET_CONTEXT));
+}
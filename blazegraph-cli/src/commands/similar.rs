@@ -0,0 +1,22 @@
+//! `blazegraph similar` — structural and textual similarity of two graphs
+
+use anyhow::Result;
+use blazegraph_io_core::graphs::similarity;
+use blazegraph_io_core::DocumentGraph;
+
+pub fn run(first: &str, second: &str, json: bool) -> Result<()> {
+    let a = DocumentGraph::load(first)?;
+    let b = DocumentGraph::load(second)?;
+    let result = similarity(&a, &b);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    println!("🧬 {} ↔ {}", first, second);
+    println!("   Similarity: {:.3}", result.score);
+    println!("   Structure:  {:.3} (section outline)", result.structure);
+    println!("   Text:       {:.3} (shingle overlap)", result.text);
+    Ok(())
+}
@@ -0,0 +1,170 @@
+//! `blazegraph stats` — structural profile metrics for one or many graphs
+
+use anyhow::Result;
+use blazegraph_io_core::graphs::GraphAnalytics;
+use blazegraph_io_core::{DepthDistribution, DocumentGraph, DocumentNode, NodeTypeDistribution};
+
+/// Token count percentiles reported per graph; the last is the maximum
+const PERCENTILES: [f32; 4] = [50.0, 90.0, 99.0, 100.0];
+
+/// One row of the stats report
+#[derive(Debug, Clone)]
+pub struct GraphStats {
+    pub name: String,
+    pub nodes: usize,
+    pub sections: usize,
+    pub paragraphs: usize,
+    pub max_depth: u32,
+    pub avg_depth: f32,
+    pub total_tokens: usize,
+    pub p50_tokens: usize,
+    pub p90_tokens: usize,
+    pub p99_tokens: usize,
+    pub max_tokens: usize,
+}
+
+impl GraphStats {
+    /// Compute stats over any set of nodes (a single graph or a whole corpus)
+    pub fn from_nodes(name: &str, nodes: &[&DocumentNode]) -> Self {
+        let analytics = GraphAnalytics::compute_analytics(nodes);
+        Self::from_analytics(
+            name,
+            nodes,
+            &analytics.node_type_distribution,
+            &analytics.depth_distribution,
+            analytics.token_distribution.overall.total_tokens,
+        )
+    }
+
+    /// Stats for a graph, reusing the structural profile it was saved with
+    pub fn from_graph(name: &str, graph: &DocumentGraph) -> Self {
+        let nodes: Vec<&DocumentNode> = graph.nodes.values().collect();
+        let profile = &graph.structural_profile;
+        Self::from_analytics(
+            name,
+            &nodes,
+            &profile.node_type_distribution,
+            &profile.depth_distribution,
+            profile.total_tokens,
+        )
+    }
+
+    /// Counts from the distributions; token percentiles still need every node's count
+    fn from_analytics(
+        name: &str,
+        nodes: &[&DocumentNode],
+        node_types: &NodeTypeDistribution,
+        depths: &DepthDistribution,
+        total_tokens: usize,
+    ) -> Self {
+        let percentiles = GraphAnalytics::token_percentiles(nodes, &PERCENTILES);
+        Self {
+            name: name.to_string(),
+            nodes: nodes.len(),
+            sections: node_types.counts.get("Section").copied().unwrap_or(0),
+            paragraphs: node_types.counts.get("Paragraph").copied().unwrap_or(0),
+            max_depth: depths.max_depth,
+            avg_depth: depths.avg_depth,
+            total_tokens,
+            p50_tokens: percentiles[0],
+            p90_tokens: percentiles[1],
+            p99_tokens: percentiles[2],
+            max_tokens: percentiles[3],
+        }
+    }
+
+    fn columns(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.nodes.to_string(),
+            self.sections.to_string(),
+            self.paragraphs.to_string(),
+            self.max_depth.to_string(),
+            format!("{:.2}", self.avg_depth),
+            self.total_tokens.to_string(),
+            self.p50_tokens.to_string(),
+            self.p90_tokens.to_string(),
+            self.p99_tokens.to_string(),
+            self.max_tokens.to_string(),
+        ]
+    }
+}
+
+const HEADERS: [&str; 11] = [
+    "graph", "nodes", "sections", "paragraphs", "max_depth", "avg_depth", "tokens", "p50", "p90",
+    "p99", "max",
+];
+
+/// Print stats for each graph, plus a corpus-wide TOTAL row when given several
+pub fn run(graph_paths: &[String], csv: bool) -> Result<()> {
+    let graphs = graph_paths
+        .iter()
+        .map(|path| DocumentGraph::load(path).map(|g| (path.as_str(), g)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut rows: Vec<GraphStats> = graphs
+        .iter()
+        .map(|(path, graph)| GraphStats::from_graph(path, graph))
+        .collect();
+
+    if graphs.len() > 1 {
+        let corpus: Vec<&DocumentNode> = graphs.iter().flat_map(|(_, g)| g.nodes.values()).collect();
+        rows.push(GraphStats::from_nodes("TOTAL", &corpus));
+    }
+
+    if csv {
+        print_csv(&rows);
+    } else {
+        print_table(&rows, graphs.len());
+    }
+
+    Ok(())
+}
+
+fn print_csv(rows: &[GraphStats]) {
+    println!("{}", HEADERS.join(","));
+    for row in rows {
+        let cols: Vec<String> = row.columns().into_iter().map(|c| csv_escape(&c)).collect();
+        println!("{}", cols.join(","));
+    }
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn print_table(rows: &[GraphStats], graph_count: usize) {
+    let cells: Vec<Vec<String>> = rows.iter().map(|r| r.columns()).collect();
+    let widths: Vec<usize> = HEADERS
+        .iter()
+        .enumerate()
+        .map(|(i, h)| cells.iter().map(|c| c[i].chars().count()).fold(h.len(), usize::max))
+        .collect();
+
+    let format_row = |cols: &[String]| -> String {
+        cols.iter()
+            .enumerate()
+            .map(|(i, c)| {
+                // Left-align the graph name, right-align the numbers
+                if i == 0 {
+                    format!("{:<width$}", c, width = widths[i])
+                } else {
+                    format!("{:>width$}", c, width = widths[i])
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+
+    let header: Vec<String> = HEADERS.iter().map(|h| h.to_string()).collect();
+    println!("📊 Structural stats ({} graph(s))\n", graph_count);
+    println!("{}", format_row(&header));
+    println!("{}", "─".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1)));
+    for row in &cells {
+        println!("{}", format_row(row));
+    }
+}
@@ -4,6 +4,7 @@
 //! Stores JRE in user's data directory for reuse across invocations.
 
 use anyhow::{anyhow, Context, Result};
+use blazegraph_io_core::network::NetworkPolicy;
 use std::fs::{self, File};
 use std::io::{self, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
@@ -19,13 +20,24 @@ const TIKA_JAR_FILENAME: &str = "blazing-tika-jni.jar";
 pub struct JreManager {
     /// Base directory for blazegraph data (e.g., ~/.local/share/blazegraph)
     data_dir: PathBuf,
+    /// Timeouts, retries and proxy for downloads
+    network: NetworkPolicy,
 }
 
 impl JreManager {
     /// Create a new JreManager using the default data directory
     pub fn new() -> Result<Self> {
         let data_dir = Self::get_data_dir()?;
-        Ok(Self { data_dir })
+        Ok(Self {
+            data_dir,
+            network: NetworkPolicy::default(),
+        })
+    }
+
+    /// Download under `network` instead of the default policy
+    pub fn with_network_policy(mut self, network: NetworkPolicy) -> Self {
+        self.network = network;
+        self
     }
 
     /// Get the data directory (~/.local/share/blazegraph on all Unix platforms)
@@ -57,7 +69,7 @@ impl JreManager {
 
     /// Get the path to the Tika JAR file.
     /// Searches local paths first, then auto-downloads to the data directory.
-    pub fn find_jar_path() -> Result<PathBuf> {
+    pub fn find_jar_path(network: &NetworkPolicy) -> Result<PathBuf> {
         // Check various local locations for the JAR
         let candidates = [
             // Core deps path (running from blazegraph-cli directory)
@@ -87,11 +99,11 @@ impl JreManager {
         }
 
         // Not found locally — auto-download to data directory
-        Self::download_tika_jar()
+        Self::download_tika_jar(network)
     }
 
     /// Download the Tika JAR to the data directory
-    fn download_tika_jar() -> Result<PathBuf> {
+    fn download_tika_jar(network: &NetworkPolicy) -> Result<PathBuf> {
         let data_dir = Self::get_data_dir()?;
         fs::create_dir_all(&data_dir)
             .with_context(|| format!("Failed to create data directory: {}", data_dir.display()))?;
@@ -104,7 +116,10 @@ impl JreManager {
         let temp_path = data_dir.join("blazing-tika-jni.jar.tmp");
 
         // Reuse the same download logic as JRE
-        let manager = Self { data_dir: data_dir.clone() };
+        let manager = Self {
+            data_dir: data_dir.clone(),
+            network: network.clone(),
+        };
         manager.download_file(TIKA_JAR_URL, &temp_path)?;
 
         fs::rename(&temp_path, &jar_path)
@@ -195,8 +210,9 @@ impl JreManager {
 
     /// Download a file with progress indication
     fn download_file(&self, url: &str, dest: &Path) -> Result<()> {
-        let response = ureq::get(url)
-            .call()
+        let response = self
+            .network
+            .get(url)
             .with_context(|| format!("Failed to download from {}", url))?;
 
         let total_size = response
@@ -2,10 +2,15 @@
 // This CLI acts as a thin wrapper around the core library
 
 // CLI-specific modules
+#[cfg(feature = "jni-backend")]
 pub mod jre_manager;
+pub mod commands;
+#[cfg(feature = "serve")]
+pub mod server;
 
 // Re-export core types for convenience
 pub use blazegraph_io_core::*;
 
 // Re-export CLI utilities
+#[cfg(feature = "jni-backend")]
 pub use jre_manager::JreManager;
@@ -1,9 +1,24 @@
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
 use std::path::Path;
+#[cfg(feature = "serve")]
+use std::sync::Arc;
 
 // Import from blazegraph-io-core
-use blazegraph_io_core::{DocumentProcessor, DocumentGraph, ParsingConfig, PipelineStages};
+use blazegraph_io_core::{
+    DocumentProcessor, DocumentGraph, FlowType, ParsingConfig, PdfPreprocessor, PipelineStages, Preprocessor,
+    RevisionStatus, RtfPreprocessor, TextElementsPreprocessor, TxtPreprocessor, XhtmlPreprocessor,
+};
+#[cfg(feature = "office")]
+use blazegraph_io_core::{DocxPreprocessor, OdtPreprocessor, PptxPreprocessor, SpreadsheetPreprocessor};
+#[cfg(feature = "html")]
+use blazegraph_io_core::HtmlPreprocessor;
+#[cfg(feature = "markdown")]
+use blazegraph_io_core::MarkdownPreprocessor;
+use blazegraph_io_core::storage::FileStorage;
+use blazegraph_io_core::preprocessors::{CommandBackend, PdfBackendImpl};
+#[cfg(feature = "jni-backend")]
+use blazegraph_io_core::TikaJniBackend;
 
 /// Default config embedded at compile time — guarantees every install has working defaults.
 /// Without this, `cargo install` users get raw parse output (3000+ nodes, 0 sections).
@@ -12,12 +27,33 @@ const DEFAULT_CONFIG_YAML: &str = include_str!("../configs/processing/config.yam
 // Import CLI utilities
 #[cfg(feature = "jni-backend")]
 use blazegraph_io::JreManager;
+use blazegraph_io::commands;
+#[cfg(feature = "serve")]
+use blazegraph_io::server::status::BACKEND_KIND;
+#[cfg(feature = "serve")]
+use blazegraph_io::server::{self, ConfigReloader, ConfigSource, ProcessorFactory, ServeLimits, ServeOptions};
+use blazegraph_io_core::audit::{AuditEntry, AuditLog};
+use blazegraph_io_core::estimate::{CostModel, EstimateBasis, Triage};
+use blazegraph_io_core::cache::CacheLevels;
+use blazegraph_io_core::encryption::{self, Cipher, EnvKey};
+use blazegraph_io_core::retention::RetentionPolicy;
+use blazegraph_io_core::stats_db::{ProfileRow, StatsDb};
+use blazegraph_io_core::fixtures::{CompactFixture, Scrubber};
+#[cfg(feature = "serve")]
+use blazegraph_io_core::rules::RuleEngine;
+use blazegraph_io_core::attachments::{extract_attachments, safe_file_name};
+use blazegraph_io_core::graphs::{parquet, GraftOptions, SearchOptions};
 
 #[derive(Parser)]
 #[command(name = "blazegraph")]
 #[command(about = "A semantic document graph parser with configurable rules")]
 struct Args {
-    /// Path to the PDF file to process
+    /// Optional subcommand operating on processed graphs (default: process a PDF)
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Path to the document to process (PDF, DOCX, PPTX, RTF, ODT, CSV/XLSX, Markdown, HTML or plain text), or an http(s) URL.
+    /// Stage snapshots (Blazegraph XHTML, TextElements JSON) skip extraction; a graph.json is re-exported in --output-format
     #[arg(short, long, default_value = "../sample_pdfs/sample3.pdf")]
     input: String,
 
@@ -25,7 +61,8 @@ struct Args {
     #[arg(short, long)]
     config: Option<String>,
 
-    /// Output format: graph, sequential, or flat
+    /// Output format: graph, sequential, flat, chunks (JSON Lines), jsonld, ntriples, html (a visual report)
+    /// or parquet (a directory of node and edge tables; parquet feature)
     #[arg(short = 'f', long, default_value = "graph")]
     output_format: String,
 
@@ -49,6 +86,30 @@ struct Args {
     #[arg(long)]
     minimal_parse: bool,
 
+    /// Record on each node the source text elements and the rules that produced it
+    #[arg(long)]
+    provenance: bool,
+
+    /// Only process these pages, e.g. "10-25,40" or "100-" (1-based, inclusive);
+    /// overrides the config's page_range
+    #[arg(long, value_name = "PAGES")]
+    pages: Option<String>,
+
+    /// Save the files embedded in a PDF (attachments) to this directory
+    #[arg(long, value_name = "DIR")]
+    attachments: Option<String>,
+
+    /// Also process each saved attachment blazegraph can read (PDF and the
+    /// native formats) and graft its graph under the document root
+    #[arg(long, requires = "attachments")]
+    graft_attachments: bool,
+
+    /// PDF backends to try in order, comma-separated: jni, or cmd:<command>
+    /// (reads the PDF on stdin, prints Blazegraph XHTML). A JVM that can't
+    /// start is skipped when later backends are listed
+    #[arg(long, value_delimiter = ',', default_value = "jni", value_name = "BACKENDS")]
+    pdf_backend: Vec<String>,
+
     /// Path to JRE directory (for JNI backend)
     /// If not specified, JRE will be auto-downloaded on first use
     #[arg(long)]
@@ -59,6 +120,11 @@ struct Args {
     #[arg(long)]
     jar_path: Option<String>,
 
+    /// Network policy (YAML): timeouts, retries, proxy and extra CA roots
+    /// for JRE / Tika JAR downloads and URL inputs
+    #[arg(long, value_name = "PATH")]
+    network_policy: Option<String>,
+
     /// Enable detailed profiling of all pipeline steps
     #[arg(long)]
     profile: bool,
@@ -67,23 +133,286 @@ struct Args {
     #[arg(long)]
     skip_cache: bool,
 
+    /// Cache levels to use, comma-separated: 1 = extraction (XHTML + TextElements),
+    /// 2 = post-rules ParsedElements, 3 = final graph. "none" disables caching.
+    #[arg(long, default_value = "1,2,3")]
+    cache_levels: String,
+
+    /// Also write the reconstructed document text (every node's text in
+    /// document order) to this file; nodes' location.text_span index into it
+    #[arg(long, value_name = "PATH")]
+    text_output: Option<String>,
+
+    /// Also add the graph to this SQLite database (created if missing): one
+    /// queryable file for a whole corpus, with full-text search on node text
+    /// (sqlite feature)
+    #[arg(long, value_name = "PATH")]
+    sqlite: Option<String>,
+
+    /// Graph output of the previous revision of this document. Sections that
+    /// still match it keep their node ids, and document_info.revision maps
+    /// each section to its predecessor
+    #[arg(long, value_name = "GRAPH")]
+    previous: Option<String>,
+
+    /// Estimate processing time and output size without processing anything.
+    /// --input may be a directory (searched recursively); predictions use
+    /// the --audit-log of earlier runs when it exists
+    #[arg(long)]
+    dry_run: bool,
+
     /// Include style_info on each node (font_class, font_size, font_family, bold, italic, color).
     /// Stripped by default to reduce output size (~20%). Useful for authoring parsing configs.
     #[arg(long)]
     include_style_info: bool,
 
     /// Dump all intermediate pipeline stage outputs to a directory
-    /// Captures: XHTML, TextElements, ParsedElements, and final Graph as separate files
+    /// Captures: XHTML, TextElements, ParsedElements, and final Graph as separate files,
+    /// plus what each rule changed (stage2_explain.json)
     #[arg(long)]
     dump_stages: bool,
 
     /// Directory for stage dump output (default: test_outputs/stages)
     #[arg(long, default_value = "test_outputs/stages")]
     stages_dir: String,
+
+    /// Write a compact regression fixture with scrubbed text to this path instead of an output
+    /// (for documents whose content can't be committed; see blazegraph-core/test_fixtures/scrubbed)
+    #[arg(long, value_name = "PATH")]
+    fixture: Option<String>,
+
+    /// Also rasterize each PDF page to PNG in this directory (page-render feature)
+    #[arg(long, value_name = "DIR")]
+    render_pages: Option<String>,
+
+    /// Resolution for --render-pages
+    #[arg(long, default_value_t = blazegraph_io_core::render::DEFAULT_DPI)]
+    render_dpi: u32,
+
+    /// Append an entry per processed document (input/config/output hashes,
+    /// requester, outcome) to this JSONL file; also applies to `serve`
+    #[arg(long, value_name = "PATH")]
+    audit_log: Option<String>,
+
+    /// Append each processed document's structural profile, warnings and
+    /// quality score (or its failure) to this stats database (JSONL); also
+    /// applies to `serve`. Summarize it with `report`
+    #[arg(long, value_name = "PATH")]
+    stats_db: Option<String>,
+
+    /// Encrypt cache entries and the output file with AES-256-GCM (also
+    /// applies to the `serve` cache). The key is 64 hex characters from
+    /// $BLAZEGRAPH_ENCRYPTION_KEY or from --encryption-key-command
+    #[arg(long)]
+    encrypt: bool,
+
+    /// Shell command that prints the encryption key (e.g. a KMS or secret
+    /// manager call); used instead of $BLAZEGRAPH_ENCRYPTION_KEY
+    #[arg(long, value_name = "CMD")]
+    encryption_key_command: Option<String>,
+
+    /// Retention policy (YAML) enforced on the cache: expired entries are
+    /// deleted when read, and documents with a `no_store_tags` tag are never cached
+    #[arg(long, value_name = "PATH")]
+    retention_policy: Option<String>,
+
+    /// Tag the input document (repeatable), e.g. --tag sensitive
+    #[arg(long = "tag", value_name = "TAG")]
+    tags: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Search node text in a processed graph.json and show where matches landed
+    Search {
+        /// Path to a graph.json produced with the graph output format
+        graph: String,
+
+        /// Text to search for (case-insensitive substring by default)
+        query: String,
+
+        /// Interpret the query as a regular expression
+        #[arg(long)]
+        regex: bool,
+
+        /// Match case exactly
+        #[arg(long)]
+        case_sensitive: bool,
+
+        /// Only search nodes of this type (repeatable, e.g. --node-type Section)
+        #[arg(long = "node-type")]
+        node_types: Vec<String>,
+
+        /// Maximum number of matches to show
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Print matches as JSON instead of a readable listing
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Run a long-running HTTP server that keeps processors (and the JVM) warm.
+    /// Uses the top-level --config; SIGHUP or POST /admin/reload reloads it.
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+
+        /// Largest accepted document, in megabytes (larger uploads get 413)
+        #[arg(long, default_value_t = 100)]
+        max_upload_mb: u64,
+
+        /// Documents queued or processing before new ones get 429
+        #[arg(long, default_value_t = 16)]
+        max_queued: usize,
+
+        /// Documents per client IP per minute (unlimited when not set)
+        #[arg(long)]
+        rate_limit: Option<u32>,
+    },
+
+    /// Decrypt an output written with --encrypt (key as for --encrypt)
+    Decrypt {
+        /// Encrypted output file
+        input: String,
+
+        /// Where to write the plaintext (default: stdout)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Manage the on-disk cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// Summarize structural metrics (nodes, sections, depth, token percentiles) for one or many graphs
+    Stats {
+        /// One or more graph.json files; a TOTAL row is added when several are given
+        #[arg(required = true)]
+        graphs: Vec<String>,
+
+        /// Print CSV instead of an aligned table
+        #[arg(long)]
+        csv: bool,
+    },
+
+    /// Score how similar two graphs are (section structure and text overlap), 0.0 to 1.0
+    Similar {
+        /// First graph.json
+        first: String,
+
+        /// Second graph.json
+        second: String,
+
+        /// Print the scores as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Resolve citation ids ("3f9c2a1b:2.3.1", from sequential output) to section, page and bbox
+    Cite {
+        /// The graph.json the citations were issued for
+        graph: String,
+
+        /// One or more citation ids
+        #[arg(required = true)]
+        citations: Vec<String>,
+
+        /// Print the resolved citations as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Attach a supplement's graph (an addendum, exhibit or schedule) under a node of a main graph
+    Graft {
+        /// The main document's graph.json
+        graph: String,
+
+        /// The supplement's graph.json
+        supplement: String,
+
+        /// Node to attach under: a node id or semantic path ("7" or "7.2"); the root when not set
+        #[arg(long, value_name = "NODE")]
+        under: Option<String>,
+
+        /// Put the supplement under a new section with this heading
+        #[arg(long)]
+        heading: Option<String>,
+
+        /// Where to write the merged graph
+        #[arg(short, long)]
+        output: String,
+    },
+
+    /// Run several PDF backends on one document and compare spans, text coverage and graphs.
+    /// Uses the top-level --config; the first backend is the baseline
+    BackendCompare {
+        /// The PDF to extract
+        #[arg(short, long)]
+        input: String,
+
+        /// Comma-separated backends: jni, or cmd:<command> (reads the PDF on stdin, prints Blazegraph XHTML)
+        #[arg(long, value_delimiter = ',', required = true)]
+        backends: Vec<String>,
+
+        /// Also write each backend's XHTML to this directory
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<String>,
+
+        /// Print the comparison as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Quality, token drift and failure trends from a --stats-db
+    Report {
+        /// The stats database written with --stats-db
+        db: String,
+
+        /// Trend bucket: day, week or month
+        #[arg(long, default_value = "day")]
+        by: String,
+
+        /// Only rows newer than this age, e.g. 30d
+        #[arg(long, value_name = "AGE")]
+        since: Option<String>,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Delete cache entries older than a retention policy allows
+    Purge {
+        /// Retention policy (YAML) with max_age per cache level
+        #[arg(long)]
+        policy: String,
+
+        /// Cache directory to purge
+        #[arg(long, default_value = "cache")]
+        cache_dir: String,
+
+        /// Report what would be deleted without deleting it
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
+    let mut args = Args::parse();
+
+    if matches!(args.command, Some(Command::Serve { .. })) {
+        return serve(&args);
+    }
+    if let Some(command) = &args.command {
+        return run_command(command, &args);
+    }
 
     println!("🦀 Blazegraph Document Parser");
 
@@ -92,6 +421,14 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Web pages are downloaded once and processed as a local HTML file
+    if is_url(&args.input) {
+        match fetch_url_input(&args.input, &args)? {
+            Some(path) => args.input = path,
+            None => return Ok(()),
+        }
+    }
+
     // Check if input file exists
     if !Path::new(&args.input).exists() {
         println!("⚠️  Input PDF not found at: {}", args.input);
@@ -99,26 +436,35 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
-    // Create processor based on available backend
-    let mut processor = create_processor(&args)?;
+    if args.dry_run {
+        return dry_run(&args);
+    }
 
-    // Load config: user-specified file > embedded default > ParsingConfig::default()
-    let mut config = if let Some(config_path) = &args.config {
-        let c = ParsingConfig::load_with_fallback(Some(config_path));
-        println!("📋 Loaded config from: {}", config_path);
-        c
-    } else {
-        match serde_yaml::from_str::<ParsingConfig>(DEFAULT_CONFIG_YAML) {
-            Ok(c) => {
-                println!("📋 Using built-in default config");
-                c
-            }
-            Err(e) => {
-                eprintln!("⚠️  Failed to parse embedded config: {e}, using fallback defaults");
-                ParsingConfig::default()
-            }
-        }
+    if is_graph_json(Path::new(&args.input)) {
+        return export_graph(&args);
+    }
+
+    if let Some(render_dir) = &args.render_pages {
+        render_input_pages(&args.input, render_dir, args.render_dpi)?;
+    }
+
+    // Word, PowerPoint, RTF, ODT, spreadsheet, Markdown, HTML and plain-text documents and stage snapshots are parsed natively; everything else goes to the PDF backend
+    let mut processor = match native_format(Path::new(&args.input)) {
+        Some(format) => create_native_processor(format)?,
+        None => create_processor(&args)?,
     };
+    processor.set_cache_levels(CacheLevels::parse(&args.cache_levels)?);
+    let cache = CacheSettings::load(&args)?;
+    cache.apply(&mut processor)?;
+    if cache.cipher.is_some() {
+        println!("🔒 Encrypting cache entries and output");
+    }
+    if !cache.allows_storing(&args.tags) {
+        processor.set_cache_levels(CacheLevels::none());
+        println!("🏷️  Document tagged {}: not cached", args.tags.join(", "));
+    }
+
+    let mut config = load_config(&args);
 
     // Apply CLI overrides to config
     if args.include_raw_tika {
@@ -127,15 +473,51 @@ fn main() -> Result<()> {
     if args.minimal_parse {
         config.minimal_parse = true;
     }
+    if args.provenance {
+        config.provenance = true;
+    }
+    if let Some(pages) = &args.pages {
+        config.page_range = Some(pages.parse().context("Invalid --pages")?);
+    }
 
     println!("📄 Processing: {}", args.input);
 
+    // Opened up front so a bad path fails before any work is done
+    let audit = match &args.audit_log {
+        Some(path) => {
+            let log = AuditLog::open(path)?;
+            let document = std::fs::read(&args.input)?;
+            let entry = AuditEntry::begin("cli", &args.input, &document, &config, &args.output_format)?
+                .with_requester(std::env::var("USER").or_else(|_| std::env::var("USERNAME")).ok());
+            Some((log, entry))
+        }
+        None => None,
+    };
+    let stats_db = args.stats_db.as_deref().map(StatsDb::open).transpose()?;
+
+    if args.output_format == "parquet" && cache.cipher.is_some() {
+        anyhow::bail!("--output-format parquet writes a directory of tables and cannot be combined with --encrypt");
+    }
+    if args.sqlite.is_some() && cache.cipher.is_some() {
+        anyhow::bail!("--sqlite writes a plaintext database and cannot be combined with --encrypt");
+    }
+    if args.attachments.is_some() && cache.cipher.is_some() {
+        anyhow::bail!("--attachments saves the embedded files in plaintext and cannot be combined with --encrypt");
+    }
+
     // Stage dump mode: capture and save all intermediates
+    if args.dump_stages && cache.cipher.is_some() {
+        anyhow::bail!("--dump-stages writes plaintext intermediates and cannot be combined with --encrypt");
+    }
     if args.dump_stages {
         println!("\n🔬 Pipeline stage dump mode");
         match processor.process_document_capture_stages(&args.input, &config) {
             Ok(stages) => {
                 save_stages(&stages, &args.stages_dir)?;
+                let explain = processor.rule_engine().explain(&stages.text_elements, &stages.style_data, &config)?;
+                let explain_path = format!("{}/stage2_explain.json", args.stages_dir);
+                std::fs::write(&explain_path, serde_json::to_string_pretty(&explain)?)?;
+                println!("  💾 {} ({} rules)", explain_path, explain.rules.len());
                 println!("\n✅ All stages dumped to: {}", args.stages_dir);
             }
             Err(e) => {
@@ -149,6 +531,24 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Fixture mode: scrubbed TextElements plus the graph shape they produce
+    if let Some(fixture_path) = &args.fixture {
+        let stages = processor.process_document_capture_stages(&args.input, &config)?;
+        let mut fixture = CompactFixture::from_text_elements(&stages.text_elements, Some(&Scrubber::random()));
+        fixture.record_expected(config.clone())?;
+        fixture.save(fixture_path)?;
+        println!(
+            "🧪 Fixture with {} scrubbed elements ({} nodes expected) written to {}",
+            fixture.elements.len(),
+            fixture.expected.nodes,
+            fixture_path
+        );
+        #[cfg(feature = "jni-backend")]
+        std::process::exit(0);
+        #[cfg(not(feature = "jni-backend"))]
+        return Ok(());
+    }
+
     // Process the document with config flow (and profiling if enabled)
     match processor.process_document_with_config_and_profiling(&args.input, &config, args.profile, args.skip_cache)
     {
@@ -156,6 +556,35 @@ fn main() -> Result<()> {
             println!("✅ Successfully processed document");
             println!("📊 Graph metrics:");
             println!("   - Nodes: {}", graph.nodes.len());
+            if let Some(content_hash) = &graph.document_info.content_hash {
+                println!("   - Content hash: {}", content_hash);
+            }
+
+            if let Some(dir) = &args.attachments {
+                let mut attachments = Attachments {
+                    graft: args.graft_attachments,
+                    processor: &mut processor,
+                    config: &config,
+                    cache: &cache,
+                };
+                attachments.save(&mut graph, &args.input, dir, 0)?;
+            }
+
+            if let Some(previous_path) = &args.previous {
+                let previous = DocumentGraph::load(previous_path)?;
+                let mapping = graph.warm_start(&previous);
+                let count = |status: RevisionStatus| mapping.sections.iter().filter(|s| s.status == status).count();
+                println!(
+                    "🔁 Warm-started from {}: {} unchanged, {} modified, {} renamed, {} added, {} removed sections ({} node ids kept)",
+                    previous_path,
+                    count(RevisionStatus::Unchanged),
+                    count(RevisionStatus::Modified),
+                    count(RevisionStatus::Renamed),
+                    count(RevisionStatus::Added),
+                    count(RevisionStatus::Removed),
+                    mapping.preserved_ids
+                );
+            }
 
             // Strip style_info from output unless explicitly requested
             if !args.include_style_info {
@@ -165,41 +594,671 @@ fn main() -> Result<()> {
             }
 
             // Generate output path
-            let output_path = if let Some(output) = &args.output {
-                output.clone()
-            } else {
-                let input_name = Path::new(&args.input)
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("output");
-                let config_suffix = args
-                    .config
-                    .as_ref()
-                    .and_then(|p| Path::new(p).file_stem())
-                    .and_then(|s| s.to_str())
-                    .map(|s| format!("_{s}"))
-                    .unwrap_or_default();
-                format!("{input_name}{config_suffix}_blazegraph.json")
-            };
+            let output_path = output_path(&args);
 
-            // Save the graph
-            save_graph(&graph, &output_path, &args.output_format)?;
+            // Save the graph. Encrypted output is serialized and sealed in
+            // memory, so no plaintext copy of the document reaches the disk
+            match &cache.cipher {
+                Some(cipher) => {
+                    let output = match args.output_format.as_str() {
+                        "html" => html_report(&graph, &args.input, &output_path, args.render_dpi, true)?.into_bytes(),
+                        format => {
+                            let mut output = Vec::new();
+                            graph.write_with_format(&mut output, format)?;
+                            output
+                        }
+                    };
+                    std::fs::write(&output_path, cipher.seal(&output)?)?;
+                    println!("🔒 Encrypted output saved to: {} (read it with `decrypt {}`)", output_path, output_path);
+                }
+                None if args.output_format == "html" => save_report(&graph, &args.input, &output_path, args.render_dpi)?,
+                None => save_graph(&graph, &output_path, &args.output_format)?,
+            }
+            if let Some(text_path) = &args.text_output {
+                let text = graph.document_text();
+                match &cache.cipher {
+                    Some(cipher) => std::fs::write(text_path, cipher.seal(text.as_bytes())?)?,
+                    None => std::fs::write(text_path, text)?,
+                }
+                println!("💾 Document text saved to: {}", text_path);
+            }
+            if let Some(database) = &args.sqlite {
+                save_to_sqlite(&graph, database)?;
+            }
+            if let Some((log, entry)) = audit {
+                let entry = entry
+                    .with_content_hash(graph.document_info.content_hash.clone())
+                    .with_page_count(Some(graph.document_info.document_metadata.page_count));
+                log.record(&entry.succeeded(&delivered_output(&output_path, &args.output_format)?))?;
+                println!("🧾 Audit entry appended to {}", log.path().display());
+            }
+            if let Some(db) = &stats_db {
+                db.record(&ProfileRow::succeeded("cli", &args.input, &graph))?;
+                println!("📈 Stats row appended to {}", db.path().display());
+            }
             
             // Fast exit - skip JVM shutdown sequence (finalizers, GC)
             // The OS reclaims all memory instantly anyway
             #[cfg(feature = "jni-backend")]
             std::process::exit(0);
+            #[cfg(not(feature = "jni-backend"))]
+            Ok(())
         }
         Err(e) => {
             eprintln!("❌ Processing failed: {e}");
+            if let Some((log, entry)) = audit {
+                log.record(&entry.failed(&format!("{e:#}")))?;
+            }
+            if let Some(db) = &stats_db {
+                db.record(&ProfileRow::failed("cli", &args.input, &format!("{e:#}")))?;
+            }
             std::process::exit(1);
         }
     }
 }
 
-/// Create DocumentProcessor with JNI backend (cross-platform, auto-downloads JRE)
-#[cfg(feature = "jni-backend")]
+/// The output as delivered, for the audit entry: the output file, or for
+/// parquet the directory's tables in a fixed order
+fn delivered_output(output_path: &str, output_format: &str) -> Result<Vec<u8>> {
+    if output_format != "parquet" {
+        return Ok(std::fs::read(output_path)?);
+    }
+    let mut output = Vec::new();
+    for table in [parquet::NODES_FILE, parquet::EDGES_FILE] {
+        output.extend(std::fs::read(Path::new(output_path).join(table))?);
+    }
+    Ok(output)
+}
+
+/// Output path from --output, or derived from the input and config names
+fn output_path(args: &Args) -> String {
+    if let Some(output) = &args.output {
+        return output.clone();
+    }
+    let input_name = Path::new(&args.input)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let config_suffix = args
+        .config
+        .as_ref()
+        .and_then(|p| Path::new(p).file_stem())
+        .and_then(|s| s.to_str())
+        .map(|s| format!("_{s}"))
+        .unwrap_or_default();
+    let extension = match args.output_format.as_str() {
+        "jsonld" => "jsonld",
+        "ntriples" => "nt",
+        "chunks" => "jsonl",
+        // A directory holding nodes.parquet and edges.parquet
+        "parquet" => return format!("{input_name}{config_suffix}_blazegraph"),
+        "html" => "html",
+        _ => "json",
+    };
+    format!("{input_name}{config_suffix}_blazegraph.{extension}")
+}
+
+/// The first bytes of a file (enough to tell snapshot and graph files apart)
+fn file_head(path: &Path) -> Vec<u8> {
+    use std::io::Read;
+    let mut head = Vec::new();
+    if let Ok(file) = std::fs::File::open(path) {
+        let _ = file.take(64 * 1024).read_to_end(&mut head);
+    }
+    head
+}
+
+/// A `.json` input holding a graph (an object) rather than TextElements
+fn is_graph_json(path: &Path) -> bool {
+    let is_json = path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("json"));
+    is_json && file_head(path).iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{')
+}
+
+/// A graph as --input: nothing is processed, the graph is written again in
+/// --output-format (e.g. graph.json → sequential)
+fn export_graph(args: &Args) -> Result<()> {
+    let mut graph = DocumentGraph::load(&args.input)?;
+    println!("📊 Loaded graph with {} nodes from {}", graph.nodes.len(), args.input);
+    if !args.include_style_info {
+        for node in graph.nodes.values_mut() {
+            node.style_info = None;
+        }
+    }
+    save_graph(&graph, &output_path(args), &args.output_format)?;
+    if let Some(database) = &args.sqlite {
+        save_to_sqlite(&graph, database)?;
+    }
+    Ok(())
+}
+
+fn save_to_sqlite(graph: &DocumentGraph, database: &str) -> Result<()> {
+    graph.save_to_sqlite(database)?;
+    println!("🗃️  Added to SQLite database: {}", database);
+    Ok(())
+}
+
+/// Name of the native preprocessor that reads `path`, if this build has it.
+/// Formats whose preprocessor is left out go to the PDF backend (Tika reads
+/// them too)
+fn native_format(path: &Path) -> Option<&'static str> {
+    detected_format(path).filter(|format| missing_format_feature(format).is_none())
+}
+
+/// Name of the native format of `path`, whether or not this build reads it
+fn detected_format(path: &Path) -> Option<&'static str> {
+    let has_extension = |extensions: &[&str]| {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| extensions.iter().any(|candidate| ext.eq_ignore_ascii_case(candidate)))
+    };
+    let is_html = has_extension(&["html", "htm", "xhtml"]);
+    if XhtmlPreprocessor::new().supports_file_type(path) || (is_html && XhtmlPreprocessor::sniff(&file_head(path))) {
+        Some("XHTML")
+    } else if TextElementsPreprocessor::new().supports_file_type(path) && TextElementsPreprocessor::sniff(&file_head(path)) {
+        Some("TextElements")
+    } else if has_extension(&["docx"]) {
+        Some("DOCX")
+    } else if has_extension(&["pptx"]) {
+        Some("PPTX")
+    } else if RtfPreprocessor::new().supports_file_type(path) {
+        Some("RTF")
+    } else if has_extension(&["odt"]) {
+        Some("ODT")
+    } else if has_extension(&["csv", "tsv", "xlsx"]) {
+        Some("spreadsheet")
+    } else if has_extension(&["md", "markdown"]) {
+        Some("Markdown")
+    } else if is_html {
+        Some("HTML")
+    } else if TxtPreprocessor::new().supports_file_type(path) {
+        Some("plain-text")
+    } else {
+        None
+    }
+}
+
+/// The feature that adds the preprocessor of a native format, when this
+/// build leaves it out
+fn missing_format_feature(format: &str) -> Option<&'static str> {
+    match format {
+        "DOCX" | "PPTX" | "ODT" | "spreadsheet" if !cfg!(feature = "office") => Some("office"),
+        "Markdown" if !cfg!(feature = "markdown") => Some("markdown"),
+        "HTML" if !cfg!(feature = "html") => Some("html"),
+        _ => None,
+    }
+}
+
+/// Preprocessor for a format named by `native_format`
+fn native_preprocessor(format: &str) -> Option<Box<dyn Preprocessor>> {
+    Some(match format {
+        #[cfg(feature = "office")]
+        "DOCX" => Box::new(DocxPreprocessor::new()),
+        #[cfg(feature = "office")]
+        "PPTX" => Box::new(PptxPreprocessor::new()),
+        "RTF" => Box::new(RtfPreprocessor::new()),
+        #[cfg(feature = "office")]
+        "ODT" => Box::new(OdtPreprocessor::new()),
+        #[cfg(feature = "office")]
+        "spreadsheet" => Box::new(SpreadsheetPreprocessor::new()),
+        #[cfg(feature = "markdown")]
+        "Markdown" => Box::new(MarkdownPreprocessor::new()),
+        #[cfg(feature = "html")]
+        "HTML" => Box::new(HtmlPreprocessor::new()),
+        "plain-text" => Box::new(TxtPreprocessor::new()),
+        "XHTML" => Box::new(XhtmlPreprocessor::new()),
+        "TextElements" => Box::new(TextElementsPreprocessor::new()),
+        _ => return None,
+    })
+}
+
+/// `--dry-run`: triage each input and predict its cost from earlier runs.
+/// Native formats are extracted (cheap, no JVM) to count spans and pages;
+/// PDFs only have their page tree read
+fn dry_run(args: &Args) -> Result<()> {
+    let input = Path::new(&args.input);
+    let mut paths = Vec::new();
+    let mut pending = vec![input.to_path_buf()];
+    while let Some(path) = pending.pop() {
+        if path.is_dir() {
+            let mut entries: Vec<_> = std::fs::read_dir(&path)?.filter_map(|e| e.ok().map(|e| e.path())).collect();
+            entries.sort();
+            pending.extend(entries.into_iter().rev());
+        } else if path == input || native_format(&path).is_some() || is_pdf(&path) {
+            paths.push(path);
+        }
+    }
+
+    let history = match &args.audit_log {
+        Some(log) if Path::new(log).exists() => AuditLog::read(log)?,
+        _ => Vec::new(),
+    };
+    println!("\n🧮 Dry run: {} input(s), nothing will be processed", paths.len());
+    match &args.audit_log {
+        Some(log) if !history.is_empty() => println!("   Rates from {} audit entries in {}", history.len(), log),
+        _ => println!("   No audit history (--audit-log): using rough built-in rates"),
+    }
+
+    let (mut total_pages, mut total_ms, mut total_bytes) = (0u64, 0u64, 0u64);
+    for path in &paths {
+        let bytes = std::fs::read(path)?;
+        let mut triage = Triage::new(path, &bytes);
+        if let Some(preprocessor) = native_format(path).and_then(native_preprocessor) {
+            match preprocessor.process(&bytes) {
+                Ok(output) => {
+                    triage.spans = Some(output.text_elements.len());
+                    if matches!(preprocessor.flow_type(), FlowType::Fixed) {
+                        triage.pages = output.text_elements.iter().map(|e| e.page_number).max();
+                    }
+                }
+                Err(e) => println!("   ⚠️  {}: extraction failed ({e:#}); estimating from size", triage.path),
+            }
+        }
+
+        let model = CostModel::from_history(&history, &triage.extension);
+        let estimate = model.estimate(&triage);
+        let pages = triage.pages.map_or("? pages".to_string(), |pages| format!("{pages} pages"));
+        let spans = triage.spans.map(|spans| format!(", {spans} spans")).unwrap_or_default();
+        println!(
+            "   {}  {} KB, {}{}  →  ~{:.1}s, ~{} KB{}",
+            triage.path,
+            triage.input_bytes.div_ceil(1024),
+            pages,
+            spans,
+            estimate.duration_ms as f64 / 1000.0,
+            estimate.output_bytes.div_ceil(1024),
+            if estimate.basis == EstimateBasis::InputBytes { " (by size)" } else { "" }
+        );
+        total_pages += u64::from(triage.pages.unwrap_or(0));
+        total_ms += estimate.duration_ms;
+        total_bytes += estimate.output_bytes;
+    }
+
+    println!(
+        "📊 Total: {} input(s), {} known pages, ~{:.1}s processing, ~{} KB output",
+        paths.len(),
+        total_pages,
+        total_ms as f64 / 1000.0,
+        total_bytes.div_ceil(1024)
+    );
+    Ok(())
+}
+
+/// Attachments nested deeper than this are saved but not processed
+const MAX_ATTACHMENT_DEPTH: usize = 3;
+
+/// Save `input`'s embedded files to `dir`; with `graft`, process the ones
+/// blazegraph reads (their own attachments too) and graft each under `graph`'s root
+/// Saves a document's attachments and, with `graft`, processes them the
+/// way the document itself was: same config, cache store and cache levels
+struct Attachments<'a> {
+    graft: bool,
+    processor: &'a mut DocumentProcessor,
+    config: &'a ParsingConfig,
+    cache: &'a CacheSettings,
+}
+
+impl Attachments<'_> {
+    fn save(&mut self, graph: &mut DocumentGraph, input: &str, dir: &str, depth: usize) -> Result<()> {
+        let files = extract_attachments(&std::fs::read(input)?)?;
+        if files.is_empty() {
+            return Ok(());
+        }
+        std::fs::create_dir_all(dir)?;
+        for file in files {
+            let name = &file.info.name;
+            let path = Path::new(dir).join(safe_file_name(name));
+            std::fs::write(&path, &file.data)?;
+            println!("📎 Saved {} ({} bytes) → {}", name, file.info.size, path.display());
+            if !self.graft || depth >= MAX_ATTACHMENT_DEPTH {
+                continue;
+            }
+
+            let path_str = path.to_string_lossy().to_string();
+            let processed = match native_format(&path) {
+                Some(format) => self
+                    .native_processor(format)
+                    .and_then(|mut native| native.process_document_with_config(&path_str, self.config)),
+                None if is_pdf(&path) => self.processor.process_document_with_config(&path_str, self.config),
+                None => {
+                    println!("   ⏭️  Not a format blazegraph reads; listed only");
+                    continue;
+                }
+            };
+            let mut child = match processed {
+                Ok(child) => child,
+                Err(e) => {
+                    println!("   ⚠️  Could not process {}: {}", name, e);
+                    continue;
+                }
+            };
+            let nested_dir = format!("{}.attachments", path_str);
+            if let Err(e) = self.save(&mut child, &path_str, &nested_dir, depth + 1) {
+                println!("   ⚠️  Could not read the attachments of {}: {}", name, e);
+            }
+            let root = graph.document_info.root_id;
+            let options = GraftOptions {
+                heading: Some(format!("Attachment: {}", name)),
+            };
+            let record = graph.graft(root, &child, &options)?;
+            println!("   🌿 Grafted {} nodes under the document root", record.node_count);
+        }
+        Ok(())
+    }
+
+    fn native_processor(&self, format: &str) -> Result<DocumentProcessor> {
+        let mut native = create_native_processor(format)?;
+        native.set_cache_levels(self.processor.cache_levels());
+        self.cache.apply(&mut native)?;
+        Ok(native)
+    }
+}
+
+fn is_pdf(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("pdf"))
+}
+
+/// Create a processor for a format named by `native_format`
+fn create_native_processor(format: &str) -> Result<DocumentProcessor> {
+    println!("📄 Using native {format} preprocessor (no JVM)");
+    match format {
+        #[cfg(feature = "office")]
+        "DOCX" => DocumentProcessor::new_cli_docx(),
+        #[cfg(feature = "office")]
+        "PPTX" => DocumentProcessor::new_cli_pptx(),
+        "RTF" => DocumentProcessor::new_cli_rtf(),
+        #[cfg(feature = "office")]
+        "ODT" => DocumentProcessor::new_cli_odt(),
+        #[cfg(feature = "office")]
+        "spreadsheet" => DocumentProcessor::new_cli_spreadsheet(),
+        #[cfg(feature = "markdown")]
+        "Markdown" => DocumentProcessor::new_cli_markdown(),
+        #[cfg(feature = "html")]
+        "HTML" => DocumentProcessor::new_cli_html(),
+        "plain-text" => DocumentProcessor::new_cli_txt(),
+        "XHTML" => DocumentProcessor::new_cli_xhtml(),
+        "TextElements" => DocumentProcessor::new_cli_text_elements(),
+        _ => Err(anyhow::anyhow!("Unknown native format: {format}")),
+    }
+}
+
+/// Cache storage settings shared by single runs and `serve`
+struct CacheSettings {
+    /// --encrypt, with the key fetched once up front
+    cipher: Option<Cipher>,
+    retention: Option<RetentionPolicy>,
+}
+
+impl CacheSettings {
+    fn load(args: &Args) -> Result<Self> {
+        let cipher = if args.encrypt {
+            Some(encryption_cipher(args.encryption_key_command.as_deref())?)
+        } else {
+            None
+        };
+        let retention = args.retention_policy.as_deref().map(RetentionPolicy::load).transpose()?;
+        Ok(Self { cipher, retention })
+    }
+
+    /// Give `processor` a cache store that encrypts and expires entries as configured
+    fn apply(&self, processor: &mut DocumentProcessor) -> Result<()> {
+        if self.cipher.is_none() && self.retention.is_none() {
+            return Ok(());
+        }
+        let mut storage = FileStorage::new("cache")?;
+        if let Some(cipher) = &self.cipher {
+            storage = storage.with_encryption(cipher.clone());
+        }
+        if let Some(retention) = &self.retention {
+            storage = storage.with_retention(retention.clone());
+        }
+        processor.set_storage(Box::new(storage));
+        Ok(())
+    }
+
+    fn allows_storing(&self, tags: &[String]) -> bool {
+        self.retention.as_ref().is_none_or(|policy| policy.allows_storing(tags))
+    }
+}
+
+/// Cipher keyed from `key_command`'s output, or from $BLAZEGRAPH_ENCRYPTION_KEY
+fn encryption_cipher(key_command: Option<&str>) -> Result<Cipher> {
+    match key_command {
+        Some(command) => {
+            let key_command = || {
+                let output = shell_command(command).output()?;
+                if !output.status.success() {
+                    anyhow::bail!("Encryption key command failed ({})", output.status);
+                }
+                encryption::parse_hex_key(&String::from_utf8_lossy(&output.stdout))
+            };
+            Cipher::from_provider(&key_command)
+        }
+        None => Cipher::from_provider(&EnvKey::default()),
+    }
+}
+
+fn shell_command(command: &str) -> std::process::Command {
+    #[cfg(windows)]
+    let (shell, flag) = ("cmd", "/C");
+    #[cfg(not(windows))]
+    let (shell, flag) = ("sh", "-c");
+    let mut process = std::process::Command::new(shell);
+    process.arg(flag).arg(command);
+    process
+}
+
+/// `serve` builds processors lazily: one per native format, one PDF backend,
+/// all running rules on one shared engine
+#[cfg(feature = "serve")]
+struct CliProcessorFactory<'a> {
+    args: &'a Args,
+    cache: CacheSettings,
+    rule_engine: Arc<RuleEngine>,
+}
+
+#[cfg(feature = "serve")]
+impl ProcessorFactory for CliProcessorFactory<'_> {
+    fn kind(&self, path: &Path) -> &'static str {
+        native_format(path).unwrap_or(BACKEND_KIND)
+    }
+
+    fn create(&self, kind: &'static str) -> Result<DocumentProcessor> {
+        let mut processor = match kind {
+            BACKEND_KIND => create_processor(self.args)?,
+            format => create_native_processor(format)?,
+        };
+        processor.set_cache_levels(CacheLevels::parse(&self.args.cache_levels)?);
+        processor.share_rule_engine(Arc::clone(&self.rule_engine));
+        self.cache.apply(&mut processor)?;
+        Ok(processor)
+    }
+}
+
+/// Run the HTTP daemon with a hot-reloadable config
+#[cfg(feature = "serve")]
+fn serve(args: &Args) -> Result<()> {
+    let Some(Command::Serve { addr, max_upload_mb, max_queued, rate_limit }) = &args.command else {
+        unreachable!("serve is only dispatched for the serve command");
+    };
+    let limits = ServeLimits {
+        max_upload_bytes: max_upload_mb * 1024 * 1024,
+        max_queued: *max_queued,
+        rate_limit_per_minute: *rate_limit,
+    };
+    println!("🦀 Blazegraph Document Parser (serve mode)");
+    let source = match &args.config {
+        Some(path) => ConfigSource::File(path.clone()),
+        None => ConfigSource::Embedded(DEFAULT_CONFIG_YAML),
+    };
+    // Non-capturing, so the flags are re-applied on every reload
+    let overrides: fn(&mut ParsingConfig) = match (args.include_raw_tika, args.minimal_parse) {
+        (false, false) => |_| {},
+        (true, false) => |config| config.include_raw_tika = true,
+        (false, true) => |config| config.minimal_parse = true,
+        (true, true) => |config| {
+            config.include_raw_tika = true;
+            config.minimal_parse = true;
+        },
+    };
+    let reloader = ConfigReloader::new(source, overrides)?;
+    let cache = CacheSettings::load(args)?;
+    if cache.cipher.is_some() {
+        println!("🔒 Encrypting cache entries");
+    }
+    let options = ServeOptions {
+        addr: addr.to_string(),
+        include_style_info: args.include_style_info,
+        limits,
+        audit_log: args.audit_log.as_deref().map(AuditLog::open).transpose()?,
+        stats_db: args.stats_db.as_deref().map(StatsDb::open).transpose()?,
+        retention: cache.retention.clone(),
+    };
+    let factory = CliProcessorFactory {
+        args,
+        cache,
+        rule_engine: Arc::new(RuleEngine::new()?),
+    };
+    server::run(&options, reloader, &factory)
+}
+
+#[cfg(not(feature = "serve"))]
+fn serve(_args: &Args) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "serve mode is not compiled in: this build was compiled without the serve feature.\n\
+         Rebuild with --features serve."
+    ))
+}
+
+/// Dispatch graph-level subcommands (no PDF backend needed)
+fn run_command(command: &Command, args: &Args) -> Result<()> {
+    match command {
+        Command::Search {
+            graph,
+            query,
+            regex,
+            case_sensitive,
+            node_types,
+            limit,
+            json,
+        } => {
+            let options = SearchOptions {
+                regex: *regex,
+                case_sensitive: *case_sensitive,
+                node_types: node_types.clone(),
+                limit: *limit,
+            };
+            commands::search::run(graph, query, &options, *json)
+        }
+        Command::Stats { graphs, csv } => commands::stats::run(graphs, *csv),
+        Command::Similar { first, second, json } => commands::similar::run(first, second, *json),
+        Command::Cite { graph, citations, json } => commands::cite::run(graph, citations, *json),
+        Command::Graft {
+            graph,
+            supplement,
+            under,
+            heading,
+            output,
+        } => commands::graft::run(graph, supplement, under.as_deref(), heading.clone(), output),
+        Command::Report { db, by, since, json } => commands::report::run(db, by, since.as_deref(), *json),
+        Command::BackendCompare {
+            input,
+            backends,
+            output_dir,
+            json,
+        } => {
+            let backends = backends.iter().map(|spec| pdf_backend(spec, args)).collect::<Result<Vec<_>>>()?;
+            commands::backend_compare::run(input, &backends, &load_config(args), *json, output_dir.as_deref())
+        }
+        Command::Cache {
+            action: CacheAction::Purge {
+                policy,
+                cache_dir,
+                dry_run,
+            },
+        } => commands::cache::purge(policy, cache_dir, *dry_run),
+        Command::Decrypt { input, output } => {
+            let cipher = encryption_cipher(args.encryption_key_command.as_deref())?;
+            commands::decrypt::run(input, output.as_deref(), &cipher)
+        }
+        Command::Serve { .. } => unreachable!("serve is dispatched in main"),
+    }
+}
+
+/// Load config: user-specified file > embedded default > ParsingConfig::default()
+fn load_config(args: &Args) -> ParsingConfig {
+    if let Some(config_path) = &args.config {
+        let c = ParsingConfig::load_with_fallback(Some(config_path));
+        println!("📋 Loaded config from: {}", config_path);
+        c
+    } else {
+        match serde_yaml::from_str::<ParsingConfig>(DEFAULT_CONFIG_YAML) {
+            Ok(c) => {
+                println!("📋 Using built-in default config");
+                c
+            }
+            Err(e) => {
+                eprintln!("⚠️  Failed to parse embedded config: {e}, using fallback defaults");
+                ParsingConfig::default()
+            }
+        }
+    }
+}
+
+/// A PDF backend named on the command line: "jni" or "cmd:<command>"
+fn pdf_backend(spec: &str, args: &Args) -> Result<PdfBackendImpl> {
+    if let Some(command) = spec.strip_prefix("cmd:") {
+        return Ok(PdfBackendImpl::Custom(Box::new(CommandBackend::new(command))));
+    }
+    match spec {
+        #[cfg(feature = "jni-backend")]
+        "jni" => {
+            let (jre_path, jar_path) = jni_paths(args)?;
+            Ok(PdfBackendImpl::Jni(TikaJniBackend::new(&jre_path, &jar_path)?))
+        }
+        #[cfg(not(feature = "jni-backend"))]
+        "jni" => {
+            let _ = args;
+            anyhow::bail!("The JNI backend is not compiled in (build with --features jni-backend)")
+        }
+        other => anyhow::bail!("Unknown PDF backend '{}' (use jni or cmd:<command>)", other),
+    }
+}
+
+/// Create the PDF DocumentProcessor from the --pdf-backend chain
 fn create_processor(args: &Args) -> Result<DocumentProcessor> {
+    let (first, rest) = args
+        .pdf_backend
+        .split_first()
+        .context("--pdf-backend needs at least one backend")?;
+    let fallbacks = rest.iter().map(|spec| pdf_backend(spec, args)).collect::<Result<Vec<_>>>()?;
+    let preprocessor = if first == "jni" {
+        jni_or_fallbacks(args, fallbacks)?
+    } else {
+        let mut backends = vec![pdf_backend(first, args)?];
+        backends.extend(fallbacks);
+        PdfPreprocessor::with_backends(backends)?
+    };
+    println!("🚀 Using PDF backends: {}", preprocessor.backend_names().join(", "));
+    DocumentProcessor::new_with_dependencies(Box::new(preprocessor), Box::new(FileStorage::new("cache")?))
+}
+
+/// JNI (cross-platform, auto-downloads JRE) ahead of `fallbacks`, which take
+/// over when the JRE can't be found or the JVM doesn't start
+#[cfg(feature = "jni-backend")]
+fn jni_or_fallbacks(args: &Args, fallbacks: Vec<PdfBackendImpl>) -> Result<PdfPreprocessor> {
+    match jni_paths(args) {
+        Ok((jre_path, jar_path)) => PdfPreprocessor::new_with_jni_or_fallbacks(&jre_path, &jar_path, fallbacks),
+        Err(e) if !fallbacks.is_empty() => {
+            println!("⚠️  JNI backend unavailable, using fallback backends: {:#}", e);
+            PdfPreprocessor::with_backends(fallbacks)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// JRE and Tika JAR for the JNI backend, downloading them when needed
+#[cfg(feature = "jni-backend")]
+fn jni_paths(args: &Args) -> Result<(std::path::PathBuf, std::path::PathBuf)> {
     // Get JRE path - either from args, JAVA_HOME, or auto-download
     let jre_path = if let Some(path) = &args.jre_path {
         // User specified JRE path
@@ -212,12 +1271,12 @@ fn create_processor(args: &Args) -> Result<DocumentProcessor> {
             std::path::PathBuf::from(java_home)
         } else {
             // JAVA_HOME is empty, auto-download
-            let jre_manager = JreManager::new()?;
+            let jre_manager = JreManager::new()?.with_network_policy(network_policy(args)?);
             jre_manager.ensure_jre()?
         }
     } else {
         // Auto-download JRE if not available
-        let jre_manager = JreManager::new()?;
+        let jre_manager = JreManager::new()?.with_network_policy(network_policy(args)?);
         jre_manager.ensure_jre()?
     };
 
@@ -226,49 +1285,216 @@ fn create_processor(args: &Args) -> Result<DocumentProcessor> {
         println!("🔧 Using specified JAR: {}", path);
         std::path::PathBuf::from(path)
     } else {
-        let path = JreManager::find_jar_path()?;
+        let path = JreManager::find_jar_path(&network_policy(args)?)?;
         println!("🔧 Using JAR: {}", path.display());
         path
     };
 
-    println!("🚀 Using JNI backend");
-    DocumentProcessor::new_cli_jni(&jre_path, &jar_path)
+    Ok((jre_path, jar_path))
 }
 
-/// Fallback when no backend is compiled in
+/// Without the JNI backend (the minimal, rules-only build) only the
+/// backends listed after it are usable
 #[cfg(not(feature = "jni-backend"))]
-fn create_processor(_args: &Args) -> Result<DocumentProcessor> {
+fn jni_or_fallbacks(args: &Args, fallbacks: Vec<PdfBackendImpl>) -> Result<PdfPreprocessor> {
+    if !fallbacks.is_empty() {
+        println!("⚠️  JNI backend not compiled in, using fallback backends");
+        return PdfPreprocessor::with_backends(fallbacks);
+    }
+    if let Some(format) = detected_format(Path::new(&args.input)) {
+        if let Some(feature) = missing_format_feature(format) {
+            return Err(anyhow::anyhow!(
+                "This build was compiled without the {feature} feature, which reads {format} input.\n\
+                 Rebuild with --features {feature}."
+            ));
+        }
+    }
     Err(anyhow::anyhow!(
         "No PDF backend compiled in!\n\
-         Compile with: --features jni-backend"
+         This minimal build reads native formats, XHTML / TextElements snapshots and graphs.\n\
+         For PDFs, compile with: --features jni-backend, or pass --pdf-backend cmd:<command>"
     ))
 }
 
+/// --network-policy, or the default policy
+#[cfg(any(feature = "jni-backend", feature = "html-fetch"))]
+fn network_policy(args: &Args) -> Result<blazegraph_io_core::network::NetworkPolicy> {
+    use blazegraph_io_core::network::NetworkPolicy;
+
+    Ok(args.network_policy.as_deref().map(NetworkPolicy::load).transpose()?.unwrap_or_default())
+}
+
+fn is_url(input: &str) -> bool {
+    input.starts_with("http://") || input.starts_with("https://")
+}
+
+/// Download a web page to a temp `.html` file named after the URL's last path segment
+#[cfg(feature = "html-fetch")]
+fn fetch_url_input(url: &str, args: &Args) -> Result<Option<String>> {
+    let html = HtmlPreprocessor::fetch(url, &network_policy(args)?)?;
+
+    let slug: String = url
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(url)
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or("page")
+        .trim_end_matches(".html")
+        .trim_end_matches(".htm")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let dir = std::env::temp_dir().join("blazegraph-fetch");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.html", if slug.is_empty() { "page" } else { &slug }));
+    std::fs::write(&path, html)?;
+    println!("💾 Saved fetched page to {}", path.display());
+
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
+#[cfg(not(feature = "html-fetch"))]
+fn fetch_url_input(url: &str, _args: &Args) -> Result<Option<String>> {
+    println!("⚠️  Cannot fetch {}: this build was compiled without the html-fetch feature", url);
+    println!("   Rebuild with --features html-fetch, or save the page and pass the .html file.");
+    Ok(None)
+}
+
+/// The html report, drawn over the input's pages when it is a PDF and this
+/// build renders pages: the images go beside the report, in `<name>.pages/`
+fn save_report(graph: &DocumentGraph, input: &str, output_path: &str, dpi: u32) -> Result<()> {
+    std::fs::write(output_path, html_report(graph, input, output_path, dpi, false)?)?;
+    println!("💾 HTML report saved to: {}", output_path);
+    Ok(())
+}
+
+/// The html report for `save_report`, with the page images inlined as data
+/// URLs instead when `embed_pages` (an encrypted report leaves no page
+/// images beside it)
+fn html_report(graph: &DocumentGraph, input: &str, output_path: &str, dpi: u32, embed_pages: bool) -> Result<String> {
+    use blazegraph_io_core::graphs::PageBackground;
+    use blazegraph_io_core::render::{default_renderer, save_pages, RenderOptions};
+
+    let mut backgrounds = Vec::new();
+    match default_renderer() {
+        Some(renderer) if is_pdf(Path::new(input)) => {
+            let pages = renderer.render_pages(&std::fs::read(input)?, &RenderOptions { dpi, ..RenderOptions::default() })?;
+            let output = Path::new(output_path);
+            let pages_dir_name = format!("{}.pages", output.file_stem().and_then(|s| s.to_str()).unwrap_or("report"));
+            if embed_pages {
+                println!("🖼️  Rendered {} page image(s) into the report", pages.len());
+            } else {
+                save_pages(&pages, &output.with_file_name(&pages_dir_name))?;
+                println!("🖼️  Rendered {} page image(s) for the report into {}", pages.len(), pages_dir_name);
+            }
+            let points = |pixels: u32| pixels as f32 * 72.0 / dpi as f32;
+            backgrounds.extend(pages.iter().map(|page| PageBackground {
+                page_number: page.page_number,
+                src: match embed_pages {
+                    true => page.data_url(),
+                    false => format!("{}/{}", pages_dir_name, page.file_name()),
+                },
+                width: points(page.width),
+                height: points(page.height),
+            }));
+        }
+        None if is_pdf(Path::new(input)) => {
+            println!("⚠️  Report drawn without page images: this build was compiled without the page-render feature");
+        }
+        _ => {}
+    }
+    Ok(graph.to_html_report(&backgrounds))
+}
+
+/// Rasterize the input PDF's pages into `output_dir` as page-NNNN.png
+fn render_input_pages(input: &str, output_dir: &str, dpi: u32) -> Result<()> {
+    use blazegraph_io_core::render::{default_renderer, save_pages, RenderOptions};
+
+    let Some(renderer) = default_renderer() else {
+        println!("⚠️  Cannot render pages: this build was compiled without the page-render feature");
+        println!("   Rebuild with --features page-render (requires poppler-utils).");
+        return Ok(());
+    };
+    let is_pdf = Path::new(input)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"));
+    if !is_pdf {
+        println!("⚠️  --render-pages only supports PDF input; skipping page images");
+        return Ok(());
+    }
+
+    let options = RenderOptions {
+        dpi,
+        ..RenderOptions::default()
+    };
+    let pages = renderer.render_pages(&std::fs::read(input)?, &options)?;
+    let paths = save_pages(&pages, Path::new(output_dir))?;
+    println!(
+        "🖼️  Rendered {} page(s) at {} DPI with {} → {}",
+        paths.len(),
+        dpi,
+        renderer.name(),
+        output_dir
+    );
+    Ok(())
+}
+
 fn show_help() {
     println!("\n📋 Available Configuration Options:");
     println!("  --config <path>         Load custom config file");
-    println!("  --input <path>          PDF file to process");
+    println!("  --input <path|url>      PDF, DOCX, PPTX, RTF, ODT, CSV/XLSX, Markdown, HTML or text (or http(s) URL)");
+    println!("                          also XHTML / TextElements JSON snapshots (--dump-stages), or a graph.json to re-export");
     println!("  --output <path>         Output file path (auto-generated if not specified)");
-    println!("  --output-format <fmt>   Output format: graph, sequential, or flat");
+    println!("  --output-format <fmt>   Output format: graph, sequential, flat, chunks, jsonld, ntriples, html or parquet");
+    println!("  --sqlite <path>         Also add the graph to a SQLite corpus database with full-text search (sqlite feature)");
     println!("  --include-raw-tika      Include raw Tika XML/HTML output in graph metadata for debugging");
     println!("  --minimal-parse         Enable minimal parse mode (bypass all rule processing)");
     println!("  --jre-path <path>       Path to JRE directory (default: auto-download)");
     println!("  --jar-path <path>       Path to Tika JAR file (default: bundled)");
+    println!("  --network-policy <path>  Timeouts, retries, proxy and CA roots for downloads and URL inputs");
+    println!("  --cache-levels <list>   Cache levels to use: 1=extraction, 2=rules, 3=graph (default: 1,2,3)");
+    println!("  --dry-run               Estimate time and output size (input may be a directory); uses --audit-log history");
+    println!("  --render-pages <dir>    Also save each PDF page as PNG (page-render feature)");
+    println!("  --render-dpi <n>        Resolution for --render-pages (default: 150)");
+    println!("  --encrypt               Encrypt cache entries and output (key: $BLAZEGRAPH_ENCRYPTION_KEY)");
+    println!("  --encryption-key-command <cmd>  Command printing the key (e.g. a KMS call)");
+    println!("  --retention-policy <path>  Expire cache entries by age; never cache --tag'd sensitive documents");
     
     println!("\n📄 Output Formats:");
     println!("  graph       - Full graph structure with nodes and relationships (default)");
     println!("  sequential  - Ordered segments with level info (good for RAG + hierarchy)");
     println!("  flat        - Simple array of text chunks (minimal format)");
+    println!("  chunks      - JSON Lines, one node per line with breadcrumbs, page, tokens and citation id (for vector DBs)");
+    println!("  jsonld      - JSON-LD in the Blazegraph vocabulary (for triple stores)");
+    println!("  ntriples    - The same statements as N-Triples (.nt)");
+    println!("  parquet     - Directory with nodes.parquet and edges.parquet for DuckDB / Spark (parquet feature)");
+    println!("  html        - Report drawing node boxes over each page, colored by type (page images with page-render)");
     
     println!("\n📁 Example config files in ./configs/:");
     println!("  generic-conservative.yaml  - Fewer, higher-confidence sections");
     println!("  generic-balanced.yaml      - Balanced section detection");
     println!("  generic-aggressive.yaml    - More sections, deeper hierarchy");
     
+    println!("\n🔎 Graph Subcommands:");
+    println!("  search <graph.json> <query>   Find nodes containing text (--regex, --case-sensitive)");
+    println!("  stats <graph.json>...         Structural metrics per graph, plus corpus totals (--csv)");
+    println!("  similar <a.json> <b.json>     Structure + text similarity of two graphs (--json)");
+    println!("  cite <graph.json> <id>...     Resolve citation ids to section, page and bbox (--json)");
+    println!("  decrypt <file> [-o out]       Decrypt an output written with --encrypt");
+    println!("  cache purge --policy <yaml>   Delete cache entries older than the policy allows (--dry-run)");
+    println!("  report <stats.jsonl>          Quality, token drift and failure trends from --stats-db (--by week, --since 30d)");
+    println!("  serve [--addr host:port]      HTTP server with warm processors; SIGHUP reloads --config
+                                (--max-upload-mb, --max-queued, --rate-limit)");
+
     println!("\n📝 Usage Examples:");
     println!("  cargo run -- -i document.pdf");
     println!("  cargo run -- -i document.pdf -o /path/to/output.json");
     println!("  cargo run -- -i document.pdf -c config.yaml -f sequential");
+    println!("  cargo run -- search output.json \"channel capacity\"");
+    println!("  cargo run -- stats outputs/*.json --csv");
     
     #[cfg(feature = "jni-backend")]
     {
@@ -308,6 +1534,7 @@ fn save_stages(stages: &PipelineStages, output_dir: &str) -> Result<()> {
     let summary = serde_json::json!({
         "input_pdf": "claude_shannon_paper.pdf",
         "captured_at": chrono::Utc::now().to_rfc3339(),
+        "extraction": stages.graph.document_info.extraction,
         "stage_counts": {
             "xhtml_bytes": stages.xhtml.len(),
             "text_elements": stages.text_elements.len(),
@@ -329,6 +1556,11 @@ fn save_graph(graph: &DocumentGraph, output_path: &str, format: &str) -> Result<
     match format {
         "sequential" => println!("💾 Sequential format results saved to: {}", output_path),
         "flat" => println!("💾 Flat format results saved to: {}", output_path),
+        "chunks" => println!("💾 Chunks saved to: {}", output_path),
+        "jsonld" => println!("💾 JSON-LD results saved to: {}", output_path),
+        "ntriples" => println!("💾 N-Triples results saved to: {}", output_path),
+        "html" => println!("💾 HTML report saved to: {}", output_path),
+        "parquet" => println!("💾 Parquet node and edge tables saved to: {}/", output_path),
         "graph" => println!("💾 Graph format results saved to: {}", output_path),
         _ => {
             println!("⚠️  Unknown output format '{}', using default graph format", format);
@@ -0,0 +1,135 @@
+//! Hot-reloadable processing config for `serve` mode
+//!
+//! The config is re-read from its source on request (SIGHUP or
+//! `POST /admin/reload`), parsed strictly and validated before it replaces
+//! the active one. A bad edit never takes the server down: the previous
+//! config stays active and the error is reported to whoever asked.
+
+use anyhow::{Context, Result};
+use blazegraph_io_core::storage::calculate_config_hash;
+use blazegraph_io_core::ParsingConfig;
+use std::sync::Arc;
+
+/// Hex digits of the config hash shown as the config version
+const VERSION_LENGTH: usize = 12;
+
+/// Where configs are loaded from
+#[derive(Debug, Clone)]
+pub enum ConfigSource {
+    /// A YAML file, re-read on every reload
+    File(String),
+    /// The built-in default config (reloading is a no-op)
+    Embedded(&'static str),
+}
+
+/// The config requests are currently processed with
+#[derive(Debug, Clone)]
+pub struct ActiveConfig {
+    pub config: ParsingConfig,
+    /// Short hash of the config; changes whenever a reload changes anything
+    pub version: String,
+    pub loaded_at: chrono::DateTime<chrono::Utc>,
+}
+
+pub struct ConfigReloader {
+    source: ConfigSource,
+    overrides: fn(&mut ParsingConfig),
+    /// Shared with queued requests, which keep the config they arrived under
+    active: Arc<ActiveConfig>,
+}
+
+impl ConfigReloader {
+    /// Load the initial config; fails rather than falling back to defaults
+    ///
+    /// `overrides` is re-applied after every load (CLI flags such as
+    /// `--minimal-parse` win over the file).
+    pub fn new(source: ConfigSource, overrides: fn(&mut ParsingConfig)) -> Result<Self> {
+        let active = load(&source, overrides)?;
+        Ok(Self {
+            source,
+            overrides,
+            active: Arc::new(active),
+        })
+    }
+
+    pub fn active(&self) -> &Arc<ActiveConfig> {
+        &self.active
+    }
+
+    /// Re-read and validate the config, swapping it in only if it is valid
+    pub fn reload(&mut self) -> Result<&ActiveConfig> {
+        match load(&self.source, self.overrides) {
+            Ok(active) => {
+                if active.version == self.active.version {
+                    println!("🔄 Config reloaded, unchanged (version {})", active.version);
+                } else {
+                    println!(
+                        "🔄 Config reloaded: version {} → {}",
+                        self.active.version, active.version
+                    );
+                }
+                self.active = Arc::new(active);
+                Ok(&self.active)
+            }
+            Err(e) => {
+                eprintln!(
+                    "⚠️  Config reload failed, keeping version {}: {:#}",
+                    self.active.version, e
+                );
+                Err(e)
+            }
+        }
+    }
+}
+
+fn load(source: &ConfigSource, overrides: fn(&mut ParsingConfig)) -> Result<ActiveConfig> {
+    let mut config: ParsingConfig = match source {
+        ConfigSource::File(path) => {
+            let yaml = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read config {}", path))?;
+            serde_yaml::from_str(&yaml).with_context(|| format!("Invalid config {}", path))?
+        }
+        ConfigSource::Embedded(yaml) => {
+            serde_yaml::from_str(yaml).context("Invalid built-in default config")?
+        }
+    };
+    overrides(&mut config);
+    config.validate()?;
+
+    let mut version = calculate_config_hash(&config)?;
+    version.truncate(VERSION_LENGTH);
+    Ok(ActiveConfig {
+        config,
+        version,
+        loaded_at: chrono::Utc::now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_reload_keeps_the_active_config() {
+        let path = std::env::temp_dir().join(format!("blazegraph-reload-{}.yaml", std::process::id()));
+        let default_yaml = include_str!("../../configs/processing/config.yaml");
+        std::fs::write(&path, default_yaml).unwrap();
+
+        let mut reloader =
+            ConfigReloader::new(ConfigSource::File(path.to_string_lossy().into_owned()), |_| {}).unwrap();
+        let original = reloader.active().version.clone();
+
+        std::fs::write(&path, format!("{}\nminimal_parse: true\n", default_yaml)).unwrap();
+        let changed = reloader.reload().unwrap().version.clone();
+        assert_ne!(changed, original);
+        assert!(reloader.active().config.minimal_parse);
+
+        // Parses fine, but fails validation
+        let bad_color = default_yaml.replace("color_rules: []", "color_rules: [{colors: [\"#nope\"], action: strip}]");
+        std::fs::write(&path, bad_color).unwrap();
+        assert!(reloader.reload().is_err());
+        assert_eq!(reloader.active().version, changed);
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,191 @@
+//! Request limits for `serve` mode
+//!
+//! Every document is processed by the same warm processor (and JVM), so one
+//! client can starve or exhaust it for everyone. Limits are checked in the
+//! accept loop, before a document is queued:
+//!
+//! - uploads larger than `max_upload_bytes` are refused with 413
+//! - once `max_queued` documents are waiting or processing, new ones get 429
+//! - each client IP gets `rate_limit_per_minute` documents, refilled
+//!   continuously (token bucket); over the limit it gets 429 and a
+//!   `Retry-After`
+
+use serde_json::json;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Buckets tracked before idle (fully refilled) ones are dropped
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+#[derive(Debug, Clone)]
+pub struct ServeLimits {
+    /// Largest accepted document, in bytes
+    pub max_upload_bytes: u64,
+    /// Documents queued or processing before new ones are refused
+    pub max_queued: usize,
+    /// Documents per client IP per minute (None = unlimited)
+    pub rate_limit_per_minute: Option<u32>,
+}
+
+impl Default for ServeLimits {
+    fn default() -> Self {
+        Self {
+            max_upload_bytes: 100 * 1024 * 1024,
+            max_queued: 16,
+            rate_limit_per_minute: None,
+        }
+    }
+}
+
+/// Why a document was refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rejection {
+    TooLarge { max_upload_bytes: u64 },
+    QueueFull { max_queued: usize },
+    RateLimited { retry_after: Duration },
+}
+
+impl Rejection {
+    pub fn status(&self) -> u16 {
+        match self {
+            Rejection::TooLarge { .. } => 413,
+            Rejection::QueueFull { .. } | Rejection::RateLimited { .. } => 429,
+        }
+    }
+
+    /// Seconds for the `Retry-After` header, when retrying can help
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            Rejection::TooLarge { .. } => None,
+            Rejection::QueueFull { .. } => Some(1),
+            Rejection::RateLimited { retry_after } => Some(retry_after.as_secs_f64().ceil() as u64),
+        }
+    }
+
+    /// JSON error body: a message, a stable `code`, and the limit that was hit
+    pub fn body(&self) -> String {
+        match self {
+            Rejection::TooLarge { max_upload_bytes } => json!({
+                "error": format!("Document exceeds the {} byte upload limit", max_upload_bytes),
+                "code": "upload_too_large",
+                "max_upload_bytes": max_upload_bytes,
+            }),
+            Rejection::QueueFull { max_queued } => json!({
+                "error": format!("Server is busy: {} documents already queued", max_queued),
+                "code": "queue_full",
+                "max_queued": max_queued,
+                "retry_after_secs": self.retry_after_secs(),
+            }),
+            Rejection::RateLimited { .. } => json!({
+                "error": "Rate limit exceeded for this client",
+                "code": "rate_limited",
+                "retry_after_secs": self.retry_after_secs(),
+            }),
+        }
+        .to_string()
+    }
+}
+
+impl ServeLimits {
+    /// Decide whether a document may be queued
+    ///
+    /// Checks run cheapest first, and a rate-limit token is only spent on a
+    /// document that would otherwise be accepted. A body without a
+    /// Content-Length is checked against the size limit while it is read.
+    pub fn admit(
+        &self,
+        limiter: &mut RateLimiter,
+        client: Option<IpAddr>,
+        body_length: Option<usize>,
+        in_flight: usize,
+    ) -> Result<(), Rejection> {
+        if body_length.is_some_and(|length| length as u64 > self.max_upload_bytes) {
+            return Err(Rejection::TooLarge {
+                max_upload_bytes: self.max_upload_bytes,
+            });
+        }
+        if in_flight >= self.max_queued {
+            return Err(Rejection::QueueFull {
+                max_queued: self.max_queued,
+            });
+        }
+        match client {
+            Some(client) => limiter
+                .check(client, Instant::now())
+                .map_err(|retry_after| Rejection::RateLimited { retry_after }),
+            None => Ok(()),
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Per-client token buckets
+pub struct RateLimiter {
+    per_minute: Option<u32>,
+    buckets: HashMap<IpAddr, Bucket>,
+}
+
+impl RateLimiter {
+    pub fn new(per_minute: Option<u32>) -> Self {
+        Self {
+            per_minute,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Take a token for `client`, or return how long until one is available
+    pub fn check(&mut self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let Some(per_minute) = self.per_minute.filter(|&n| n > 0) else {
+            return Ok(());
+        };
+        let capacity = per_minute as f64;
+        let per_second = capacity / 60.0;
+
+        if self.buckets.len() >= MAX_TRACKED_CLIENTS {
+            self.buckets.retain(|_, bucket| {
+                bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * per_second < capacity
+            });
+        }
+
+        let bucket = self.buckets.entry(client).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        bucket.tokens =
+            (bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * per_second).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clients_are_limited_independently_and_refill() {
+        let mut limiter = RateLimiter::new(Some(2));
+        let (a, b): (IpAddr, IpAddr) = ("10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap());
+        let start = Instant::now();
+
+        assert!(limiter.check(a, start).is_ok());
+        assert!(limiter.check(a, start).is_ok());
+        let retry_after = limiter.check(a, start).unwrap_err();
+        assert!((retry_after.as_secs_f64() - 30.0).abs() < 0.01, "{:?}", retry_after);
+        assert!(limiter.check(b, start).is_ok());
+
+        assert!(limiter.check(a, start + Duration::from_secs(31)).is_ok());
+        assert!(RateLimiter::new(None).check(a, start).is_ok());
+    }
+}
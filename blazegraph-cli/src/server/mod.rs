@@ -0,0 +1,257 @@
+//! `serve` mode: a long-running HTTP daemon around warm processors
+//!
+//! Each CLI run starts a JVM and reads its caches from scratch; `serve`
+//! keeps one `DocumentProcessor` per preprocessor alive between requests,
+//! so the JVM and caches stay warm. Documents are processed one at a time
+//! on a worker thread (the JVM-backed preprocessor is not shared across
+//! threads); the accept loop answers everything else, so probes and reloads
+//! stay responsive while a long document is running.
+//!
+//! Endpoints:
+//! - `POST /v1/process/<format>?output_format=graph|sequential|flat|chunks|jsonld|ntriples|html` — the
+//!   request body is the document (`format` is its extension: pdf, docx, ...);
+//!   `&tags=a,b` tags it for the retention policy
+//! - `GET /v1/config` — active config version and load time
+//! - `POST /admin/reload` — re-read the config; also triggered by SIGHUP
+//! - `GET /healthz` — liveness: the server is accepting connections
+//! - `GET /readyz` — readiness: 503 if the worker died or a started
+//!   processor (e.g. the JVM backend) reports unhealthy
+//! - `GET /stats` — versions, uptime, in-flight, processed and rejected
+//!   documents, JVM status, and cache hit rates per processor
+//!
+//! Documents are subject to `ServeLimits` (upload size, queue depth and a
+//! per-client rate); refusals are JSON errors with 413 or 429. With an audit
+//! log, every processed document is recorded before its response is sent.
+//!
+//! Every response carries the config version it was produced with in the
+//! `X-Config-Version` header; processed documents also carry the graph's
+//! content hash in `X-Content-Hash`.
+
+pub mod config_reload;
+pub mod limits;
+pub mod status;
+mod worker;
+
+pub use config_reload::{ActiveConfig, ConfigReloader, ConfigSource};
+pub use limits::ServeLimits;
+
+use anyhow::{anyhow, Result};
+use blazegraph_io_core::audit::AuditLog;
+use blazegraph_io_core::stats_db::StatsDb;
+use blazegraph_io_core::retention::RetentionPolicy;
+use blazegraph_io_core::DocumentProcessor;
+use limits::{RateLimiter, Rejection};
+use status::ServerState;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Method, Request, Response, Server};
+use worker::Job;
+
+/// How often the accept loop wakes up to check for a pending SIGHUP
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Creates processors for `serve`; one processor per kind is kept alive
+///
+/// Processors are created on the worker thread, so the factory is shared
+/// with it.
+pub trait ProcessorFactory: Sync {
+    /// Which processor handles `path` (formats sharing a preprocessor share a kind)
+    fn kind(&self, path: &Path) -> &'static str;
+
+    /// Build the processor for `kind`; called once per kind
+    fn create(&self, kind: &'static str) -> Result<DocumentProcessor>;
+}
+
+pub struct ServeOptions {
+    /// Address to listen on, e.g. "127.0.0.1:8080"
+    pub addr: String,
+    /// Include style_info on nodes (stripped by default, as in the CLI)
+    pub include_style_info: bool,
+    pub limits: ServeLimits,
+    /// Append every processed document to this audit log
+    pub audit_log: Option<AuditLog>,
+    /// Append every processed document's profile to this stats database
+    pub stats_db: Option<StatsDb>,
+    /// Documents tagged with one of its `no_store_tags` bypass the cache
+    pub retention: Option<RetentionPolicy>,
+}
+
+/// Run the server until the process is stopped
+pub fn run(options: &ServeOptions, mut reloader: ConfigReloader, factory: &dyn ProcessorFactory) -> Result<()> {
+    let server = Server::http(&options.addr).map_err(|e| anyhow!("Failed to listen on {}: {}", options.addr, e))?;
+    let reload_requested = Arc::new(AtomicBool::new(false));
+    register_reload_signal(&reload_requested)?;
+
+    println!("🌐 Serving on http://{} (config version {})", options.addr, reloader.active().version);
+    let limits = &options.limits;
+    println!(
+        "🛡️  Limits: {} MB uploads, {} queued documents, {}",
+        limits.max_upload_bytes / (1024 * 1024),
+        limits.max_queued,
+        limits
+            .rate_limit_per_minute
+            .map_or("no rate limit".to_string(), |n| format!("{} documents/min per client", n))
+    );
+    let state = ServerState::default();
+    let mut limiter = RateLimiter::new(options.limits.rate_limit_per_minute);
+    let (jobs, queue) = mpsc::channel();
+    std::thread::scope(|scope| {
+        let state = &state;
+        scope.spawn(move || {
+            worker::run(queue, options, factory, state);
+            state.worker_stopped();
+        });
+
+        loop {
+            if reload_requested.swap(false, Ordering::SeqCst) {
+                println!("🔔 SIGHUP received, reloading config");
+                // Failures are logged by the reloader; the old config stays active
+                let _ = reloader.reload();
+            }
+
+            // On error `jobs` is dropped, which stops the worker before the scope joins it
+            let Some(request) = server.recv_timeout(RELOAD_POLL_INTERVAL)? else {
+                continue;
+            };
+            route(request, options, &mut reloader, &mut limiter, &jobs, state);
+        }
+    })
+}
+
+/// Dispatch one request: documents are queued for the worker, everything
+/// else is answered here
+fn route(
+    request: Request,
+    options: &ServeOptions,
+    reloader: &mut ConfigReloader,
+    limiter: &mut RateLimiter,
+    jobs: &Sender<Job>,
+    state: &ServerState,
+) {
+    let start = Instant::now();
+    let (path, query) = match request.url().split_once('?') {
+        Some((path, query)) => (path.to_string(), query.to_string()),
+        None => (request.url().to_string(), String::new()),
+    };
+
+    let method = request.method().clone();
+    let (status, body) = match (&method, path.as_str()) {
+        (Method::Post, path) if path.starts_with("/v1/process/") => {
+            let client = request.remote_addr().map(|addr| addr.ip());
+            if let Err(rejection) = options.limits.admit(limiter, client, request.body_length(), state.in_flight()) {
+                state.job_rejected();
+                println!("🚫 {} {} refused: {} ({:?})", method, path, rejection.status(), client);
+                reject(request, &rejection, reloader.active());
+                return;
+            }
+            let job = Job {
+                format: path.trim_start_matches("/v1/process/").to_string(),
+                output_format: query_param(&query, "output_format").unwrap_or("graph").to_string(),
+                tags: query_param(&query, "tags")
+                    .map(|tags| tags.split(',').filter(|t| !t.is_empty()).map(str::to_string).collect())
+                    .unwrap_or_default(),
+                config: Arc::clone(reloader.active()),
+                request,
+            };
+            state.job_accepted();
+            match jobs.send(job) {
+                Ok(()) => return,
+                Err(mpsc::SendError(job)) => {
+                    state.job_finished(false);
+                    respond(job.request, 503, error_json("Document worker is not running"), reloader.active());
+                    return;
+                }
+            }
+        }
+        (Method::Post, "/admin/reload") => match reloader.reload() {
+            Ok(active) => (200, config_json(active)),
+            Err(e) => (422, error_json(&format!("{:#}", e))),
+        },
+        (Method::Get, "/v1/config") => (200, config_json(reloader.active())),
+        (Method::Get, "/healthz") => (200, serde_json::json!({ "status": "ok" }).to_string()),
+        (Method::Get, "/readyz") => match state.readiness() {
+            (true, body) => (200, body.to_string()),
+            (false, body) => (503, body.to_string()),
+        },
+        (Method::Get, "/stats") => (200, state.stats(reloader.active()).to_string()),
+        _ => (404, error_json(&format!("No route for {} {}", method, path))),
+    };
+
+    let url = request.url().to_string();
+    respond(request, status, body, reloader.active());
+    // Probes are polled constantly; only log what an operator would look for
+    if !matches!(path.as_str(), "/healthz" | "/readyz") || status != 200 {
+        println!(
+            "↩️  {} {} → {} in {}ms (config {})",
+            method,
+            url,
+            status,
+            start.elapsed().as_millis(),
+            reloader.active().version
+        );
+    }
+}
+
+fn respond(request: Request, status: u16, body: String, active: &ActiveConfig) {
+    send(request, json_response(status, body, active));
+}
+
+fn reject(request: Request, rejection: &Rejection, active: &ActiveConfig) {
+    let mut response = json_response(rejection.status(), rejection.body(), active);
+    if let Some(seconds) = rejection.retry_after_secs() {
+        response.add_header(header("Retry-After", &seconds.to_string()));
+    }
+    send(request, response);
+}
+
+fn json_response(status: u16, body: String, active: &ActiveConfig) -> Response<std::io::Cursor<Vec<u8>>> {
+    Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+        .with_header(header("X-Config-Version", &active.version))
+}
+
+fn send(request: Request, response: Response<std::io::Cursor<Vec<u8>>>) {
+    if let Err(e) = request.respond(response) {
+        eprintln!("⚠️  Failed to send response: {}", e);
+    }
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+fn config_json(active: &ActiveConfig) -> String {
+    serde_json::json!({
+        "config_version": active.version,
+        "loaded_at": active.loaded_at.to_rfc3339(),
+    })
+    .to_string()
+}
+
+fn error_json(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("static header names are valid")
+}
+
+#[cfg(unix)]
+fn register_reload_signal(flag: &Arc<AtomicBool>) -> Result<()> {
+    signal_hook::flag::register(signal_hook::consts::SIGHUP, Arc::clone(flag))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn register_reload_signal(_flag: &Arc<AtomicBool>) -> Result<()> {
+    println!("ℹ️  SIGHUP is not available on this platform; use POST /admin/reload");
+    Ok(())
+}
@@ -0,0 +1,199 @@
+//! Liveness, readiness and runtime statistics for `serve` mode
+//!
+//! Processors live on the worker thread, so the worker publishes a snapshot
+//! of each one (health, extraction stack, cache counters) after every job and
+//! periodically while idle. Status endpoints read the snapshots and never
+//! wait for a document to finish.
+
+use super::ActiveConfig;
+use blazegraph_io_core::cache::{CacheLevelStats, CacheStats};
+use blazegraph_io_core::{DocumentProcessor, ExtractionInfo};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Processor kind that wraps the JVM-backed PDF backend
+pub const BACKEND_KIND: &str = "backend";
+
+/// Last published state of one warm processor
+#[derive(Debug, Clone)]
+pub struct ProcessorStatus {
+    pub healthy: bool,
+    pub extraction: ExtractionInfo,
+    pub cache: CacheStats,
+}
+
+impl ProcessorStatus {
+    pub fn of(processor: &DocumentProcessor) -> Self {
+        Self {
+            healthy: processor.is_healthy(),
+            extraction: processor.extraction_info(),
+            cache: processor.cache_stats(),
+        }
+    }
+}
+
+/// Counters and snapshots shared between the accept loop and the worker
+pub struct ServerState {
+    started_at: Instant,
+    /// Documents accepted but not yet answered (queued or processing)
+    in_flight: AtomicUsize,
+    processed: AtomicU64,
+    failed: AtomicU64,
+    /// Documents refused by a request limit before being queued
+    rejected: AtomicU64,
+    worker_alive: AtomicBool,
+    processors: Mutex<BTreeMap<&'static str, ProcessorStatus>>,
+}
+
+impl Default for ServerState {
+    fn default() -> Self {
+        Self {
+            started_at: Instant::now(),
+            in_flight: AtomicUsize::new(0),
+            processed: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            worker_alive: AtomicBool::new(true),
+            processors: Mutex::new(BTreeMap::new()),
+        }
+    }
+}
+
+impl ServerState {
+    pub fn job_accepted(&self) {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn job_finished(&self, succeeded: bool) {
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        let counter = if succeeded { &self.processed } else { &self.failed };
+        counter.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn job_rejected(&self) {
+        self.rejected.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    pub fn worker_stopped(&self) {
+        self.worker_alive.store(false, Ordering::SeqCst);
+    }
+
+    pub fn publish(&self, kind: &'static str, status: ProcessorStatus) {
+        self.processors.lock().unwrap_or_else(|e| e.into_inner()).insert(kind, status);
+    }
+
+    fn processors(&self) -> BTreeMap<&'static str, ProcessorStatus> {
+        self.processors.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Ready when the worker is running and every started processor is healthy
+    ///
+    /// Processors start lazily, so a fresh server is ready before any
+    /// document (and before the JVM) has been seen.
+    pub fn readiness(&self) -> (bool, Value) {
+        let worker_alive = self.worker_alive.load(Ordering::SeqCst);
+        let unhealthy: Vec<&str> = self
+            .processors()
+            .into_iter()
+            .filter(|(_, status)| !status.healthy)
+            .map(|(kind, _)| kind)
+            .collect();
+        let ready = worker_alive && unhealthy.is_empty();
+        (
+            ready,
+            json!({
+                "status": if ready { "ready" } else { "not_ready" },
+                "worker_alive": worker_alive,
+                "unhealthy_processors": unhealthy,
+            }),
+        )
+    }
+
+    pub fn stats(&self, active: &ActiveConfig) -> Value {
+        let processors = self.processors();
+        let jvm = match processors.get(BACKEND_KIND) {
+            Some(status) => json!({
+                "started": true,
+                "healthy": status.healthy,
+                "version": status.extraction.jvm_version,
+            }),
+            None => json!({ "started": false }),
+        };
+        let processors: BTreeMap<&str, Value> = processors
+            .iter()
+            .map(|(kind, status)| {
+                (
+                    *kind,
+                    json!({
+                        "healthy": status.healthy,
+                        "extraction": status.extraction,
+                        "cache": {
+                            "extraction": level_json(&status.cache.extraction),
+                            "parsed_elements": level_json(&status.cache.parsed_elements),
+                            "graph": level_json(&status.cache.graph),
+                        },
+                    }),
+                )
+            })
+            .collect();
+
+        json!({
+            "versions": {
+                "blazegraph": env!("CARGO_PKG_VERSION"),
+                "config": active.version,
+            },
+            "config_loaded_at": active.loaded_at.to_rfc3339(),
+            "uptime_secs": self.started_at.elapsed().as_secs(),
+            "documents": {
+                "in_flight": self.in_flight.load(Ordering::SeqCst),
+                "processed": self.processed.load(Ordering::SeqCst),
+                "failed": self.failed.load(Ordering::SeqCst),
+                "rejected": self.rejected.load(Ordering::SeqCst),
+            },
+            "jvm": jvm,
+            "processors": processors,
+        })
+    }
+}
+
+fn level_json(level: &CacheLevelStats) -> Value {
+    json!({
+        "hits": level.hits,
+        "misses": level.misses,
+        "hit_rate": level.hit_rate(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readiness_follows_worker_and_processor_health() {
+        let state = ServerState::default();
+        assert!(state.readiness().0, "nothing started yet is still ready");
+
+        let status = |healthy| ProcessorStatus {
+            healthy,
+            extraction: ExtractionInfo::default(),
+            cache: CacheStats::default(),
+        };
+        state.publish("markdown", status(true));
+        state.publish(BACKEND_KIND, status(false));
+        let (ready, body) = state.readiness();
+        assert!(!ready);
+        assert_eq!(body["unhealthy_processors"], json!([BACKEND_KIND]));
+
+        state.publish(BACKEND_KIND, status(true));
+        assert!(state.readiness().0);
+        state.worker_stopped();
+        assert!(!state.readiness().0);
+    }
+}
@@ -0,0 +1,231 @@
+//! The processing thread for `serve` mode
+//!
+//! Owns the warm processors and works through queued documents one at a
+//! time, answering each request itself. Keeping processing off the accept
+//! loop lets probes, stats and reloads answer while a long document runs.
+
+use super::limits::Rejection;
+use super::status::{ProcessorStatus, ServerState};
+use super::{error_json, header, json_response, send, ActiveConfig, ProcessorFactory, ServeOptions};
+use anyhow::Result;
+use blazegraph_io_core::audit::{AuditEntry, AuditLog};
+use blazegraph_io_core::cache::CacheLevels;
+use blazegraph_io_core::stats_db::ProfileRow;
+use blazegraph_io_core::DocumentProcessor;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io::Read;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tiny_http::Request;
+
+/// How often an idle worker re-checks processor health for `/readyz`
+const HEALTH_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A document waiting to be processed
+pub struct Job {
+    pub request: Request,
+    pub format: String,
+    pub output_format: String,
+    /// Tags from `?tags=`, checked against the retention policy
+    pub tags: Vec<String>,
+    /// Config at the time the request was accepted
+    pub config: Arc<ActiveConfig>,
+}
+
+impl Job {
+    /// How the document is named in audit and stats records
+    fn input(&self) -> String {
+        format!("upload.{}", self.format)
+    }
+}
+
+/// Process jobs until the accept loop hangs up
+pub fn run(jobs: Receiver<Job>, options: &ServeOptions, factory: &dyn ProcessorFactory, state: &ServerState) {
+    let mut processors: HashMap<&'static str, DocumentProcessor> = HashMap::new();
+    loop {
+        match jobs.recv_timeout(HEALTH_REFRESH_INTERVAL) {
+            Ok(job) => handle(job, options, factory, state, &mut processors),
+            Err(RecvTimeoutError::Timeout) => {
+                for (kind, processor) in &processors {
+                    state.publish(kind, ProcessorStatus::of(processor));
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+fn handle(
+    mut job: Job,
+    options: &ServeOptions,
+    factory: &dyn ProcessorFactory,
+    state: &ServerState,
+    processors: &mut HashMap<&'static str, DocumentProcessor>,
+) {
+    let start = Instant::now();
+    let (method, url) = (job.request.method().clone(), job.request.url().to_string());
+
+    // Bodies without a Content-Length were not size-checked on arrival
+    let max_upload_bytes = options.limits.max_upload_bytes;
+    let mut document = Vec::new();
+    let read = job.request.as_reader().take(max_upload_bytes + 1).read_to_end(&mut document);
+    let (status, body, content_hash) = match read {
+        Ok(_) if document.len() as u64 > max_upload_bytes => {
+            let rejection = Rejection::TooLarge { max_upload_bytes };
+            (rejection.status(), rejection.body(), None)
+        }
+        Ok(_) => {
+            let entry = options.audit_log.as_ref().map(|_| audit_entry(&job, &document));
+            // A panicking rule must not take the worker (and every later request) down
+            let (status, body, content_hash) = catch_unwind(AssertUnwindSafe(|| {
+                process(&document, &job, options, factory, state, processors)
+            }))
+            .unwrap_or_else(|_| {
+                record_stats(options, || ProfileRow::failed("serve", &job.input(), "Processing panicked"));
+                (500, error_json("Processing panicked; see server log"), None)
+            });
+            let (status, body) = match (&options.audit_log, entry) {
+                (Some(log), Some(entry)) => {
+                    audit(log, entry.map(|e| e.with_content_hash(content_hash.clone())), status, body)
+                }
+                _ => (status, body),
+            };
+            (status, body, content_hash.filter(|_| status == 200))
+        }
+        Err(e) => (400, error_json(&format!("Failed to read request body: {}", e)), None),
+    };
+
+    let mut response = json_response(status, body, &job.config);
+    if let Some(content_hash) = &content_hash {
+        response.add_header(header("X-Content-Hash", content_hash));
+        match job.output_format.as_str() {
+            "chunks" => response.add_header(header("Content-Type", "application/jsonl")),
+            "jsonld" => response.add_header(header("Content-Type", "application/ld+json")),
+            "ntriples" => response.add_header(header("Content-Type", "application/n-triples")),
+            "html" => response.add_header(header("Content-Type", "text/html; charset=utf-8")),
+            _ => {}
+        }
+    }
+    send(job.request, response);
+    state.job_finished(status == 200);
+    println!(
+        "↩️  {} {} → {} in {}ms (config {})",
+        method,
+        url,
+        status,
+        start.elapsed().as_millis(),
+        job.config.version
+    );
+}
+
+/// Start the audit entry for a document as processing begins
+fn audit_entry(job: &Job, document: &[u8]) -> Result<AuditEntry> {
+    let forwarded_for = job
+        .request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("X-Forwarded-For"))
+        .map(|h| h.value.to_string());
+    let requester = match (job.request.remote_addr(), forwarded_for) {
+        (Some(addr), Some(forwarded)) => Some(format!("{} (forwarded for {})", addr.ip(), forwarded)),
+        (Some(addr), None) => Some(addr.ip().to_string()),
+        (None, forwarded) => forwarded,
+    };
+    Ok(AuditEntry::begin("serve", &job.input(), document, &job.config.config, &job.output_format)?.with_requester(requester))
+}
+
+/// Record the document in the audit log before its output is released;
+/// output that could not be audited is withheld
+fn audit(log: &AuditLog, entry: Result<AuditEntry>, status: u16, body: String) -> (u16, String) {
+    let recorded = entry.and_then(|entry| {
+        let entry = if status == 200 {
+            entry.succeeded(body.as_bytes())
+        } else {
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|error| error["error"].as_str().map(str::to_string));
+            entry.failed(message.as_deref().unwrap_or(&body))
+        };
+        log.record(&entry)
+    });
+    match recorded {
+        Ok(()) => (status, body),
+        Err(e) => {
+            eprintln!("⚠️  Audit log write failed: {:#}", e);
+            if status == 200 {
+                (500, error_json("Audit log write failed; output withheld"))
+            } else {
+                (status, body)
+            }
+        }
+    }
+}
+
+fn process(
+    document: &[u8],
+    job: &Job,
+    options: &ServeOptions,
+    factory: &dyn ProcessorFactory,
+    state: &ServerState,
+    processors: &mut HashMap<&'static str, DocumentProcessor>,
+) -> (u16, String, Option<String>) {
+    if !matches!(job.output_format.as_str(), "graph" | "sequential" | "flat" | "chunks" | "jsonld" | "ntriples" | "html") {
+        return (
+            400,
+            error_json(&format!(
+                "Invalid output_format '{}'. Must be one of: chunks, flat, graph, html, jsonld, ntriples, sequential",
+                job.output_format
+            )),
+            None,
+        );
+    }
+
+    let kind = factory.kind(Path::new(&format!("document.{}", job.format)));
+    let processor = match processors.entry(kind) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => match factory.create(kind) {
+            Ok(processor) => entry.insert(processor),
+            Err(e) => return (500, error_json(&format!("Failed to start {} processor: {:#}", kind, e)), None),
+        },
+    };
+
+    if !processor.supports_format(&job.format) {
+        return (415, error_json(&format!("Unsupported document format '{}'", job.format)), None);
+    }
+
+    // Tagged documents that must never be cached skip every level, read or write
+    let no_store = options.retention.as_ref().is_some_and(|policy| !policy.allows_storing(&job.tags));
+    let cache_levels = if no_store { CacheLevels::none() } else { processor.cache_levels() };
+    let result = processor.process_bytes_with_cache_levels(document, Some(&job.format), &job.config.config, cache_levels);
+    let result = result.and_then(|mut graph| {
+        record_stats(options, || ProfileRow::succeeded("serve", &job.input(), &graph));
+        if !options.include_style_info {
+            for node in graph.nodes.values_mut() {
+                node.style_info = None;
+            }
+        }
+        Ok((graph.to_json_with_format(&job.output_format)?, graph.document_info.content_hash))
+    });
+    state.publish(kind, ProcessorStatus::of(processor));
+    match result {
+        Ok((json, content_hash)) => (200, json, content_hash),
+        Err(e) => {
+            record_stats(options, || ProfileRow::failed("serve", &job.input(), &format!("{:#}", e)));
+            (500, error_json(&format!("Processing failed: {:#}", e)), None)
+        }
+    }
+}
+
+/// Append a row to the stats database, if there is one. Unlike the audit
+/// log, a failed write only costs the statistic, not the response
+fn record_stats(options: &ServeOptions, row: impl FnOnce() -> ProfileRow) {
+    if let Some(db) = &options.stats_db {
+        if let Err(e) = db.record(&row()) {
+            eprintln!("⚠️  Stats database write failed: {:#}", e);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! End-to-end runs of the CLI binary on inputs that need no JVM
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A fresh directory to run the CLI in, so its cache and outputs stay out of the tree
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("blazegraph-cli-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn run(dir: &Path, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_blazegraph-io"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[cfg(all(feature = "parquet", feature = "markdown"))]
+#[test]
+fn audit_log_records_parquet_output() {
+    let dir = scratch_dir("audit-parquet");
+    std::fs::write(dir.join("notes.md"), "# Notes\n\nFirst paragraph.\n\n## Details\n\nSecond paragraph.\n").unwrap();
+
+    let output = run(
+        &dir,
+        &["-i", "notes.md", "--output-format", "parquet", "--audit-log", "audit.jsonl", "--skip-cache"],
+    );
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let tables: Vec<u8> = ["nodes.parquet", "edges.parquet"]
+        .iter()
+        .flat_map(|table| std::fs::read(dir.join("notes_blazegraph").join(table)).unwrap())
+        .collect();
+    let log = std::fs::read_to_string(dir.join("audit.jsonl")).unwrap();
+    let entry: serde_json::Value = serde_json::from_str(log.lines().next().unwrap()).unwrap();
+    assert_eq!(entry["status"], "succeeded", "{entry}");
+    assert_eq!(entry["output_bytes"], tables.len());
+    assert_eq!(entry["output_sha256"], blazegraph_io_core::audit::sha256_hex(&tables));
+    assert_eq!(entry["output_format"], "parquet");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn pdf_backend_chain_falls_back_when_the_jvm_is_unavailable() {
+    let dir = scratch_dir("pdf-backend-chain");
+    // `cat` echoes the "PDF" back, so its XHTML stands in for an extraction
+    let xhtml = concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<html xmlns=\"http://www.w3.org/1999/xhtml\"><body>\n",
+        "<div class=\"page\"><p><span class=\"f1\" data-bbox=\"72.0,72.0,300.0,12.0\" data-line=\"0\" data-segment=\"0\">",
+        "Extracted by the fallback.</span></p></div>\n",
+        "<style type=\"text/css\">.f1 { font-family: Times-Roman; font-size: 12.0px; font-weight: normal; color: #000000; }</style>",
+        "</body></html>\n"
+    );
+    std::fs::write(dir.join("scan.pdf"), xhtml).unwrap();
+
+    let chain = [
+        "--pdf-backend", "jni,cmd:cat", "--jre-path", "missing-jre", "--jar-path", "missing.jar",
+    ];
+    let output = run(&dir, &[&chain[..], &["-i", "scan.pdf", "--skip-cache"]].concat());
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("using fallback backends"), "{stdout}");
+    let graph = std::fs::read_to_string(dir.join("scan_blazegraph.json")).unwrap();
+    assert!(graph.contains("Extracted by the fallback."));
+
+    let output = run(&dir, &["--pdf-backend", "jni", "--jre-path", "missing-jre", "--jar-path", "missing.jar", "-i", "scan.pdf"]);
+    assert!(!output.status.success(), "JNI alone has nothing to fall back to");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
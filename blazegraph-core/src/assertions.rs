@@ -0,0 +1,193 @@
+//! Stage-boundary assertions
+//!
+//! Organizations often have contracts for what a parse must look like ("at
+//! least one Section", "no node over 5000 tokens") that used to live in
+//! post-processing scripts. Assertions registered on a `DocumentProcessor`
+//! run inside the pipeline instead, at three boundaries: after extraction
+//! (the spans a preprocessor produced), after the rules (the parsed
+//! elements) and after the graph is built.
+//!
+//! An assertion passes, annotates the run (the note lands in the graph's
+//! `warnings` with code `assertion`) or vetoes it, which fails processing
+//! with the assertion's reason. Assertions are not part of any cache key:
+//! they run on cached stage outputs too, and a graph-cache hit is skipped
+//! when assertions need the stages before it.
+
+use crate::types::{DocumentGraph, ParsedPdfElement, PreprocessorOutput, ProcessingWarning, WarningCode};
+use anyhow::{bail, Result};
+use std::fmt;
+
+/// A pipeline boundary assertions can run at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineStage {
+    /// Spans and metadata from the preprocessor, before page filters and rules
+    Extraction,
+    /// Parsed elements out of the rule pipeline
+    Rules,
+    /// The finished graph
+    Build,
+}
+
+impl fmt::Display for PipelineStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PipelineStage::Extraction => "extraction",
+            PipelineStage::Rules => "rules",
+            PipelineStage::Build => "graph build",
+        })
+    }
+}
+
+/// What an assertion sees at its boundary
+pub enum StageOutput<'a> {
+    Extraction(&'a PreprocessorOutput),
+    Rules(&'a [ParsedPdfElement]),
+    Build(&'a DocumentGraph),
+}
+
+impl StageOutput<'_> {
+    pub fn stage(&self) -> PipelineStage {
+        match self {
+            StageOutput::Extraction(_) => PipelineStage::Extraction,
+            StageOutput::Rules(_) => PipelineStage::Rules,
+            StageOutput::Build(_) => PipelineStage::Build,
+        }
+    }
+}
+
+/// An assertion's outcome
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    /// Let the run continue, recording a note in the graph's warnings
+    Annotate(String),
+    /// Fail the run with this reason
+    Veto(String),
+}
+
+type Check = Box<dyn Fn(&StageOutput) -> Verdict + Send + Sync>;
+
+struct StageAssertion {
+    name: String,
+    stage: PipelineStage,
+    check: Check,
+}
+
+/// Assertions registered on a processor, run in registration order
+#[derive(Default)]
+pub struct StageAssertions {
+    assertions: Vec<StageAssertion>,
+}
+
+impl StageAssertions {
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        stage: PipelineStage,
+        check: impl Fn(&StageOutput) -> Verdict + Send + Sync + 'static,
+    ) {
+        self.assertions.push(StageAssertion {
+            name: name.into(),
+            stage,
+            check: Box::new(check),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assertions.is_empty()
+    }
+
+    /// Whether any assertion needs a stage before the graph build (so a
+    /// cached graph cannot be used as is)
+    pub fn checks_before_build(&self) -> bool {
+        self.assertions.iter().any(|a| a.stage != PipelineStage::Build)
+    }
+
+    /// Run the assertions for `output`'s stage. Annotations are appended to
+    /// `annotations`; the first veto fails with its reason
+    pub fn check(&self, output: StageOutput, annotations: &mut Vec<ProcessingWarning>) -> Result<()> {
+        let stage = output.stage();
+        for assertion in self.assertions.iter().filter(|a| a.stage == stage) {
+            match (assertion.check)(&output) {
+                Verdict::Pass => {}
+                Verdict::Annotate(note) => {
+                    println!("📝 Assertion '{}' after {}: {}", assertion.name, stage, note);
+                    annotations.push(ProcessingWarning::new(
+                        WarningCode::Assertion,
+                        format!("{}: {}", assertion.name, note),
+                    ));
+                }
+                Verdict::Veto(reason) => {
+                    bail!("Assertion '{}' vetoed the run after {}: {}", assertion.name, stage, reason)
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Build-stage check: the graph has at least `min` nodes of `node_type`
+pub fn require_node_type(node_type: &str, min: usize) -> impl Fn(&StageOutput) -> Verdict + Send + Sync + 'static {
+    let node_type = node_type.to_string();
+    move |output| match output {
+        StageOutput::Build(graph) => {
+            let count = graph.nodes.values().filter(|n| n.node_type == node_type).count();
+            if count >= min {
+                Verdict::Pass
+            } else {
+                Verdict::Veto(format!("{} {} node(s), at least {} required", count, node_type, min))
+            }
+        }
+        _ => Verdict::Pass,
+    }
+}
+
+/// Build-stage check: no node holds more than `limit` tokens
+pub fn max_node_tokens(limit: usize) -> impl Fn(&StageOutput) -> Verdict + Send + Sync + 'static {
+    move |output| match output {
+        StageOutput::Build(graph) => {
+            let over = graph.nodes.values().filter(|n| n.token_count > limit).count();
+            if over == 0 {
+                Verdict::Pass
+            } else {
+                Verdict::Veto(format!("{} node(s) exceed {} tokens", over, limit))
+            }
+        }
+        _ => Verdict::Pass,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DocumentNode;
+
+    #[test]
+    fn vetoes_and_annotations_follow_registration() {
+        let mut graph = DocumentGraph::new();
+        let mut node = DocumentNode::new("Paragraph", "A long paragraph".to_string());
+        node.token_count = 6_000;
+        graph.nodes.insert(node.id, node);
+
+        let mut assertions = StageAssertions::default();
+        assertions.register("spans present", PipelineStage::Rules, |output| match output {
+            StageOutput::Rules([]) => Verdict::Annotate("no elements".into()),
+            _ => Verdict::Pass,
+        });
+        assertions.register("token budget", PipelineStage::Build, max_node_tokens(5_000));
+        assertions.register("has sections", PipelineStage::Build, require_node_type("Section", 1));
+        assert!(assertions.checks_before_build());
+
+        let mut annotations = Vec::new();
+        assertions.check(StageOutput::Rules(&[]), &mut annotations).unwrap();
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(annotations[0].code, WarningCode::Assertion);
+        assert_eq!(annotations[0].message, "spans present: no elements");
+
+        let error = assertions.check(StageOutput::Build(&graph), &mut annotations).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Assertion 'token budget' vetoed the run after graph build: 1 node(s) exceed 5000 tokens"
+        );
+    }
+}
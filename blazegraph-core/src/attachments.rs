@@ -0,0 +1,216 @@
+//! Files embedded in PDFs
+//!
+//! Invoices carry their machine-readable XML (ZUGFeRD, Factur-X), filings
+//! carry exhibits and spreadsheets, and portfolios carry whole documents as
+//! embedded files, none of which reach the text layer. They are found in
+//! two places:
+//!
+//! - the catalog's `EmbeddedFiles` name tree (document-level files);
+//! - `FileAttachment` annotations on pages (files pinned to a page).
+//!
+//! The processor lists them in `DocumentMetadata::attachments` (name,
+//! declared MIME type, size and SHA-256). `extract_attachments` returns
+//! their content, for saving them or processing them into graphs of their
+//! own (the CLI's `--attachments` and `--graft-attachments`).
+
+use crate::types::Attachment;
+use anyhow::Result;
+
+/// An embedded file and its content
+#[derive(Debug, Clone)]
+pub struct EmbeddedFile {
+    pub info: Attachment,
+    pub data: Vec<u8>,
+}
+
+/// Every file embedded in `document_bytes`; empty for documents that are not
+/// PDFs or carry none
+#[cfg(feature = "attachments")]
+pub fn extract_attachments(document_bytes: &[u8]) -> Result<Vec<EmbeddedFile>> {
+    use lopdf::{Dictionary, Document, Object};
+    use sha2::{Digest, Sha256};
+    use std::collections::HashSet;
+
+    if !document_bytes.starts_with(b"%PDF") {
+        return Ok(Vec::new());
+    }
+    let document = Document::load_mem(document_bytes)?;
+
+    // Name trees nest through Kids; leaves list alternating names and file specs
+    fn collect_name_tree<'a>(document: &'a Document, node: &'a Dictionary, specs: &mut Vec<&'a Dictionary>, depth: usize) {
+        if depth > 32 {
+            return;
+        }
+        if let Ok(names) = node.get_deref(b"Names", document).and_then(Object::as_array) {
+            for spec in names.iter().skip(1).step_by(2) {
+                if let Ok(spec) = document.dereference(spec).and_then(|(_, spec)| spec.as_dict()) {
+                    specs.push(spec);
+                }
+            }
+        }
+        if let Ok(kids) = node.get_deref(b"Kids", document).and_then(Object::as_array) {
+            for kid in kids {
+                if let Ok(kid) = document.dereference(kid).and_then(|(_, kid)| kid.as_dict()) {
+                    collect_name_tree(document, kid, specs, depth + 1);
+                }
+            }
+        }
+    }
+
+    let mut specs: Vec<(&Dictionary, Option<u32>)> = Vec::new();
+    if let Ok(tree) = document
+        .catalog()
+        .and_then(|catalog| catalog.get_deref(b"Names", &document))
+        .and_then(Object::as_dict)
+        .and_then(|names| names.get_deref(b"EmbeddedFiles", &document))
+        .and_then(Object::as_dict)
+    {
+        let mut tree_specs = Vec::new();
+        collect_name_tree(&document, tree, &mut tree_specs, 0);
+        specs.extend(tree_specs.into_iter().map(|spec| (spec, None)));
+    }
+    for (page_number, page_id) in document.get_pages() {
+        // Annotations may be inline dictionaries as well as references
+        let annotations = document
+            .get_dictionary(page_id)
+            .and_then(|page| page.get_deref(b"Annots", &document))
+            .and_then(Object::as_array)
+            .map(|annotations| annotations.as_slice())
+            .unwrap_or_default();
+        for annotation in annotations {
+            let Ok(annotation) = document.dereference(annotation).and_then(|(_, annotation)| annotation.as_dict()) else {
+                continue;
+            };
+            let is_attachment = annotation
+                .get(b"Subtype")
+                .and_then(Object::as_name)
+                .is_ok_and(|subtype| subtype == b"FileAttachment");
+            if let (true, Ok(spec)) = (is_attachment, annotation.get_deref(b"FS", &document).and_then(Object::as_dict)) {
+                specs.push((spec, Some(page_number)));
+            }
+        }
+    }
+
+    let text = |dict: &Dictionary, key: &[u8]| {
+        dict.get_deref(key, &document)
+            .ok()
+            .and_then(|value| lopdf::decode_text_string(value).ok())
+            .filter(|value| !value.trim().is_empty())
+    };
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+    for (spec, page) in specs {
+        // UF is the Unicode file name, F the legacy one
+        let Some(embedded) = spec.get_deref(b"EF", &document).and_then(Object::as_dict).ok() else {
+            continue;
+        };
+        let Ok((id, stream)) = embedded
+            .get(b"UF")
+            .or_else(|_| embedded.get(b"F"))
+            .and_then(|stream| document.dereference(stream))
+        else {
+            continue;
+        };
+        let Ok(stream) = stream.as_stream() else {
+            continue;
+        };
+        // The same file listed in the name tree and by an annotation counts once
+        if id.is_some_and(|id| !seen.insert(id)) {
+            continue;
+        }
+        let data = if stream.dict.get(b"Filter").is_ok() {
+            stream.decompressed_content()?
+        } else {
+            stream.content.clone()
+        };
+        let mime_type = stream
+            .dict
+            .get(b"Subtype")
+            .and_then(Object::as_name)
+            .ok()
+            .map(|name| String::from_utf8_lossy(name).replace("#2F", "/").replace("#2f", "/"));
+        files.push(EmbeddedFile {
+            info: Attachment {
+                name: text(spec, b"UF")
+                    .or_else(|| text(spec, b"F"))
+                    .unwrap_or_else(|| format!("attachment-{}", files.len() + 1)),
+                description: text(spec, b"Desc"),
+                mime_type,
+                size: data.len(),
+                sha256: format!("{:x}", Sha256::digest(&data)),
+                page,
+            },
+            data,
+        });
+    }
+    Ok(files)
+}
+
+#[cfg(not(feature = "attachments"))]
+pub fn extract_attachments(_document_bytes: &[u8]) -> Result<Vec<EmbeddedFile>> {
+    anyhow::bail!("Extracting attachments needs the `attachments` feature")
+}
+
+/// `name` reduced to a safe file name for saving an attachment
+pub fn safe_file_name(name: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    let safe: String = base
+        .chars()
+        .map(|c| if c.is_control() || ":*?\"<>|".contains(c) { '_' } else { c })
+        .collect();
+    match safe.trim_matches('.').trim() {
+        "" => "attachment".to_string(),
+        _ => safe,
+    }
+}
+
+#[cfg(all(test, feature = "attachments"))]
+mod tests {
+    use super::*;
+    use lopdf::{dictionary, Document, Object, Stream};
+
+    #[test]
+    fn lists_embedded_files_and_annotation_attachments() {
+        let mut document = Document::with_version("1.7");
+        let xml = b"<Invoice><Total>120.00</Total></Invoice>".to_vec();
+        let mut stream = Stream::new(dictionary! { "Type" => "EmbeddedFile", "Subtype" => "text/xml" }, xml.clone());
+        stream.compress().unwrap();
+        let xml_id = document.add_object(stream);
+        let xml_spec = document.add_object(dictionary! {
+            "Type" => "Filespec",
+            "F" => Object::string_literal("factur-x.xml"),
+            "Desc" => Object::string_literal("Invoice data"),
+            "EF" => dictionary! { "F" => xml_id },
+        });
+        let csv_id = document.add_object(Stream::new(dictionary! {}, b"a,b\n1,2\n".to_vec()));
+        let csv_spec = dictionary! { "F" => Object::string_literal("../rates.csv"), "EF" => dictionary! { "F" => csv_id } };
+
+        let pages_id = document.new_object_id();
+        let page_id = document.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            "Annots" => vec![Object::Dictionary(dictionary! { "Subtype" => "FileAttachment", "FS" => csv_spec })],
+        });
+        document.objects.insert(pages_id, Object::Dictionary(dictionary! { "Type" => "Pages", "Kids" => vec![page_id.into()], "Count" => 1 }));
+        let catalog_id = document.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "Names" => dictionary! { "EmbeddedFiles" => dictionary! { "Names" => vec![Object::string_literal("factur-x.xml"), xml_spec.into()] } },
+        });
+        document.trailer.set("Root", catalog_id);
+        let mut bytes = Vec::new();
+        document.save_to(&mut bytes).unwrap();
+
+        let files = extract_attachments(&bytes).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].info.name, "factur-x.xml");
+        assert_eq!(files[0].info.description.as_deref(), Some("Invoice data"));
+        assert_eq!(files[0].info.mime_type.as_deref(), Some("text/xml"));
+        assert_eq!(files[0].data, xml);
+        assert_eq!(files[0].info.size, xml.len());
+        assert_eq!(files[1].info.page, Some(1));
+        assert_eq!(safe_file_name(&files[1].info.name), "rates.csv");
+        assert!(extract_attachments(b"not a pdf").unwrap().is_empty());
+    }
+}
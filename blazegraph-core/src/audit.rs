@@ -0,0 +1,209 @@
+//! Append-only audit log of processed documents
+//!
+//! One JSON object per line, recording who processed what, when, with which
+//! config, and what came out. Documents are identified by the SHA-256 of
+//! their full contents (not the sampled hash used as a cache key), so an
+//! entry can be matched against a stored copy of the input or output later.
+//! Document text never enters the log.
+
+use crate::storage::calculate_config_hash;
+use crate::ParsingConfig;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditStatus {
+    Succeeded,
+    Failed,
+}
+
+/// One processed document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    /// How the document arrived: "cli" or "serve"
+    pub mode: String,
+    /// Who asked: the OS user for the CLI, the client address in serve mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requester: Option<String>,
+    /// Input path, or the declared format for uploads
+    pub input: String,
+    pub input_sha256: String,
+    pub input_bytes: usize,
+    /// Full hash of the effective config (serve mode's config version is its prefix)
+    pub config_hash: String,
+    pub output_format: String,
+    pub status: AuditStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_bytes: Option<usize>,
+    /// Pages in the source document, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_count: Option<u32>,
+    /// `DocumentGraph::content_hash` of the result: matches across inputs
+    /// with the same content, for deduplication
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub blazegraph_version: String,
+}
+
+impl AuditEntry {
+    /// Start an entry when a document arrives; complete it with `succeeded`
+    /// or `failed`
+    pub fn begin(mode: &str, input: &str, document: &[u8], config: &ParsingConfig, output_format: &str) -> Result<Self> {
+        Ok(Self {
+            timestamp: Utc::now(),
+            mode: mode.to_string(),
+            requester: None,
+            input: input.to_string(),
+            input_sha256: sha256_hex(document),
+            input_bytes: document.len(),
+            config_hash: calculate_config_hash(config)?,
+            output_format: output_format.to_string(),
+            status: AuditStatus::Failed,
+            output_sha256: None,
+            output_bytes: None,
+            page_count: None,
+            content_hash: None,
+            error: None,
+            duration_ms: 0,
+            blazegraph_version: env!("CARGO_PKG_VERSION").to_string(),
+        })
+    }
+
+    pub fn with_requester(mut self, requester: Option<String>) -> Self {
+        self.requester = requester;
+        self
+    }
+
+    pub fn with_content_hash(mut self, content_hash: Option<String>) -> Self {
+        self.content_hash = content_hash;
+        self
+    }
+
+    pub fn with_page_count(mut self, page_count: Option<u32>) -> Self {
+        self.page_count = page_count.filter(|&pages| pages > 0);
+        self
+    }
+
+    /// Complete the entry with the output exactly as it was delivered
+    pub fn succeeded(mut self, output: &[u8]) -> Self {
+        self.status = AuditStatus::Succeeded;
+        self.output_sha256 = Some(sha256_hex(output));
+        self.output_bytes = Some(output.len());
+        self.finish()
+    }
+
+    pub fn failed(mut self, error: &str) -> Self {
+        self.status = AuditStatus::Failed;
+        self.error = Some(error.to_string());
+        self.finish()
+    }
+
+    fn finish(mut self) -> Self {
+        self.duration_ms = (Utc::now() - self.timestamp).num_milliseconds().max(0) as u64;
+        self
+    }
+}
+
+/// A JSONL audit file, opened for appending
+pub struct AuditLog {
+    path: PathBuf,
+    file: File,
+}
+
+impl AuditLog {
+    /// Open (or create) the log; existing entries are never rewritten
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create audit log directory {}", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open audit log {}", path.display()))?;
+        Ok(Self { path, file })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every entry of an existing log, oldest first
+    pub fn read(path: impl AsRef<Path>) -> Result<Vec<AuditEntry>> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read audit log {}", path.display()))?;
+        contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("Invalid audit log entry at {}:{}", path.display(), index + 1))
+            })
+            .collect()
+    }
+
+    /// Append one entry as a single line and flush it to disk
+    pub fn record(&self, entry: &AuditEntry) -> Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        let mut file = &self.file;
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.sync_data())
+            .with_context(|| format!("Failed to write audit log {}", self.path.display()))
+    }
+}
+
+/// Hex SHA-256 of the full contents
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_one_line_per_entry() {
+        let path = std::env::temp_dir().join(format!("blazegraph-audit-{}.jsonl", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let config = ParsingConfig::default();
+
+        let log = AuditLog::open(&path).unwrap();
+        let entry = AuditEntry::begin("cli", "contract.pdf", b"%PDF", &config, "graph").unwrap();
+        log.record(&entry.clone().succeeded(b"{}")).unwrap();
+        drop(log);
+        AuditLog::open(&path)
+            .unwrap()
+            .record(&entry.with_requester(Some("10.0.0.7".into())).failed("boom"))
+            .unwrap();
+
+        let lines: Vec<AuditEntry> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].status, AuditStatus::Succeeded);
+        assert_eq!(lines[0].input_sha256, sha256_hex(b"%PDF"));
+        assert_eq!(lines[0].output_sha256.as_deref(), Some(sha256_hex(b"{}").as_str()));
+        assert_eq!(lines[1].requester.as_deref(), Some("10.0.0.7"));
+        assert_eq!(lines[1].error.as_deref(), Some("boom"));
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,100 @@
+//! Automatic minimal parse for simple documents
+//!
+//! On a short memo set in one font, in one column, the rules find nothing to
+//! merge into sections, lists or tables, and the graph they build is the
+//! base conversion's with extra work. With `auto_minimal_parse` enabled,
+//! such documents skip rule processing as `minimal_parse` would, and a
+//! `minimal_parse` warning records why. A document is simple when:
+//!
+//! - it has at most `max_pages` pages;
+//! - its text uses at most `max_font_sizes` font sizes, and none of it bold;
+//! - no page has columns (as `column_detection` finds them);
+//! - the backend marked no structure: no bookmarks, and no paragraph roles
+//!   (tables, lists, figures) or heading levels.
+
+use crate::column_order::has_columns;
+use crate::config::ParsingConfig;
+use crate::types::*;
+use std::collections::BTreeSet;
+
+/// Why rule processing adds nothing to this document, or None if it might
+pub fn minimal_parse_reason(
+    text_elements: &[PdfTextElement],
+    analysis: &DocumentAnalysis,
+    config: &ParsingConfig,
+) -> Option<String> {
+    let settings = &config.auto_minimal_parse;
+    if !settings.enabled || config.minimal_parse || text_elements.is_empty() {
+        return None;
+    }
+    let pages: BTreeSet<u32> = text_elements.iter().map(|e| e.page_number).collect();
+    if pages.len() > settings.max_pages {
+        return None;
+    }
+    let font_sizes = analysis.font_size_counts.len();
+    if font_sizes > settings.max_font_sizes || analysis.bold_counts.0 > 0 {
+        return None;
+    }
+    let marked_structure = text_elements.iter().any(|e| {
+        e.bookmark_match.is_some() || e.paragraph_role().is_some() || e.paragraph_level().is_some()
+    });
+    if marked_structure || has_columns(text_elements, &config.column_detection) {
+        return None;
+    }
+    Some(format!(
+        "Rule processing skipped: {} page(s), {} font size(s), no bold text, single column, no marked structure",
+        pages.len(),
+        font_sizes
+    ))
+}
+
+/// Decide whether to run the rules; records the note when they're skipped
+pub fn skip_rules(
+    text_elements: &[PdfTextElement],
+    analysis: &DocumentAnalysis,
+    config: &ParsingConfig,
+    warnings: &mut Vec<ProcessingWarning>,
+) -> bool {
+    if config.minimal_parse {
+        return true;
+    }
+    let Some(reason) = minimal_parse_reason(text_elements, analysis, config) else {
+        return false;
+    };
+    println!("⚡ Simple document - {}", reason.to_lowercase());
+    warnings.push(ProcessingWarning::new(WarningCode::MinimalParse, reason));
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AutoMinimalParseConfig;
+    use crate::test_utils::{text_elements, FontClassBuilder};
+
+    #[test]
+    fn only_plain_short_documents_skip_rules() {
+        let config = ParsingConfig {
+            auto_minimal_parse: AutoMinimalParseConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            ..ParsingConfig::default()
+        };
+        let memo = text_elements(&["Please remember to lock the front door.", "The office closes at six on Fridays."]);
+        let analysis = DocumentAnalysis::analyze_text_elements(&memo);
+        let mut warnings = Vec::new();
+        assert!(skip_rules(&memo, &analysis, &config, &mut warnings));
+        assert_eq!(warnings[0].code, WarningCode::MinimalParse);
+
+        // A bold heading is structure the rules would find
+        let mut titled = memo.clone();
+        titled[0].style_info = FontClassBuilder::new("f2").size(16.0).bold().build();
+        let analysis = DocumentAnalysis::analyze_text_elements(&titled);
+        assert!(minimal_parse_reason(&titled, &analysis, &config).is_none());
+
+        // Off unless enabled
+        let analysis = DocumentAnalysis::analyze_text_elements(&memo);
+        assert!(!skip_rules(&memo, &analysis, &ParsingConfig::default(), &mut Vec::new()));
+    }
+}
@@ -0,0 +1,267 @@
+//! PDF backend A/B comparison
+//!
+//! Before a deployment moves to another PDF backend, run the backends on the
+//! same documents and compare what each would hand to the rules. Every
+//! backend's XHTML goes through the shared XHTML parser, which normalizes
+//! their markup differences (attribute order, whitespace, class naming),
+//! and then through the same config, so the comparison covers:
+//!
+//! - extraction: pages, spans and words, and the pages whose span counts
+//!   differ between backends;
+//! - text coverage: the share of the baseline's words (the first backend's)
+//!   that a backend also extracted, counting repeats, and the words only it
+//!   extracted;
+//! - the graph: node counts by type, depth, and `graphs::similarity` to the
+//!   baseline's graph.
+
+use crate::config::ParsingConfig;
+use crate::fixtures::FixtureExpectations;
+use crate::graphs::{similarity, Similarity};
+use crate::preprocessors::pdf::xhtml_parser;
+use crate::preprocessors::{PdfBackend, PdfBackendImpl, XhtmlPreprocessor};
+use crate::storage::NoOpStorage;
+use crate::types::PdfTextElement;
+use crate::DocumentProcessor;
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+
+/// One backend's output for a document
+pub struct Extraction {
+    pub backend: String,
+    pub xhtml: Result<String>,
+    pub duration: Duration,
+}
+
+/// Run every backend on `pdf_bytes`, in order
+pub fn extract_all(pdf_bytes: &[u8], backends: &[PdfBackendImpl]) -> Vec<Extraction> {
+    backends
+        .iter()
+        .map(|backend| {
+            println!("🔬 Extracting with {}", backend.name());
+            let start = Instant::now();
+            let xhtml = backend.extract_to_xhtml(pdf_bytes);
+            Extraction {
+                backend: backend.name().to_string(),
+                xhtml,
+                duration: start.elapsed(),
+            }
+        })
+        .collect()
+}
+
+/// What one backend produced, against the baseline
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendResult {
+    pub backend: String,
+    /// Why extraction, parsing or processing failed; the counts are then zero
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub extraction_ms: f64,
+    pub xhtml_bytes: usize,
+    pub pages: usize,
+    /// Text elements with text
+    pub spans: usize,
+    pub words: usize,
+    /// Share of the baseline's words this backend also extracted (1.0 for the baseline)
+    pub text_coverage: f32,
+    /// Words this backend extracted beyond the baseline's
+    pub extra_words: usize,
+    /// Shape of the graph the config built from this backend's output
+    pub graph: FixtureExpectations,
+    /// Similarity of that graph to the baseline's (None for the baseline)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<Similarity>,
+}
+
+/// A page where the backends found different numbers of spans
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PageDifference {
+    pub page: u32,
+    /// Spans per backend, in the comparison's order
+    pub spans: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendComparison {
+    /// The backend the others are measured against
+    pub baseline: String,
+    pub backends: Vec<BackendResult>,
+    pub page_differences: Vec<PageDifference>,
+}
+
+/// Compare extractions of one document; the first is the baseline and must
+/// have succeeded
+pub fn compare_extractions(extractions: &[Extraction], config: &ParsingConfig) -> Result<BackendComparison> {
+    let Some(baseline) = extractions.first() else {
+        bail!("No backends to compare");
+    };
+    if let Err(e) = &baseline.xhtml {
+        bail!("Baseline backend {} failed: {:#}", baseline.backend, e);
+    }
+
+    let mut results = Vec::new();
+    let mut page_spans: Vec<BTreeMap<u32, usize>> = Vec::new();
+    let mut baseline_words: HashMap<String, usize> = HashMap::new();
+    let mut baseline_graph = None;
+    for extraction in extractions {
+        let mut result = BackendResult {
+            backend: extraction.backend.clone(),
+            error: None,
+            extraction_ms: extraction.duration.as_secs_f64() * 1000.0,
+            xhtml_bytes: 0,
+            pages: 0,
+            spans: 0,
+            words: 0,
+            text_coverage: 0.0,
+            extra_words: 0,
+            graph: FixtureExpectations::default(),
+            similarity: None,
+        };
+        let parsed = extraction.xhtml.as_ref().map_err(|e| format!("{:#}", e)).and_then(|xhtml| {
+            result.xhtml_bytes = xhtml.len();
+            let output = xhtml_parser::parse_xhtml(xhtml).map_err(|e| format!("{:#}", e))?;
+            let graph = process_xhtml(xhtml, config).map_err(|e| format!("{:#}", e))?;
+            Ok((output.text_elements, graph))
+        });
+        let (text_elements, graph) = match parsed {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                result.error = Some(error);
+                page_spans.push(BTreeMap::new());
+                results.push(result);
+                continue;
+            }
+        };
+
+        let spans = page_span_counts(&text_elements);
+        result.pages = spans.len();
+        result.spans = spans.values().sum();
+        let words = word_counts(&text_elements);
+        result.words = words.values().sum();
+        if baseline_graph.is_none() {
+            baseline_words = words.iter().map(|(word, count)| (word.to_string(), *count)).collect();
+        }
+        let covered: usize = words
+            .iter()
+            .map(|(word, count)| (*count).min(baseline_words.get(*word).copied().unwrap_or(0)))
+            .sum();
+        let baseline_total: usize = baseline_words.values().sum();
+        result.text_coverage = if baseline_total == 0 { 1.0 } else { covered as f32 / baseline_total as f32 };
+        result.extra_words = result.words - covered;
+        result.graph = FixtureExpectations::of(&graph);
+        match &baseline_graph {
+            None => baseline_graph = Some(graph),
+            Some(baseline) => result.similarity = Some(similarity(baseline, &graph)),
+        }
+        page_spans.push(spans);
+        results.push(result);
+    }
+
+    let pages: BTreeSet<u32> = page_spans.iter().flat_map(|spans| spans.keys().copied()).collect();
+    let page_differences = pages
+        .into_iter()
+        .map(|page| PageDifference {
+            page,
+            spans: page_spans.iter().map(|spans| spans.get(&page).copied().unwrap_or(0)).collect(),
+        })
+        .filter(|difference| difference.spans.windows(2).any(|pair| pair[0] != pair[1]))
+        .collect();
+
+    Ok(BackendComparison {
+        baseline: baseline.backend.clone(),
+        backends: results,
+        page_differences,
+    })
+}
+
+fn process_xhtml(xhtml: &str, config: &ParsingConfig) -> Result<crate::types::DocumentGraph> {
+    let mut processor = DocumentProcessor::new_with_dependencies(
+        Box::new(XhtmlPreprocessor::new()),
+        Box::new(NoOpStorage::new()),
+    )?;
+    processor.process_bytes(xhtml.as_bytes(), None, config)
+}
+
+fn page_span_counts(text_elements: &[PdfTextElement]) -> BTreeMap<u32, usize> {
+    let mut spans = BTreeMap::new();
+    for element in text_elements.iter().filter(|e| !e.text.trim().is_empty()) {
+        *spans.entry(element.page_number).or_insert(0) += 1;
+    }
+    spans
+}
+
+fn word_counts(text_elements: &[PdfTextElement]) -> HashMap<&str, usize> {
+    let mut words = HashMap::new();
+    for word in text_elements.iter().flat_map(|e| e.text.split_whitespace()) {
+        *words.entry(word).or_insert(0) += 1;
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    fn xhtml(pages: &[&[&str]]) -> String {
+        let mut body = String::new();
+        for (index, lines) in pages.iter().enumerate() {
+            body.push_str(&format!(r#"<div class="page" data-page="{}"><p>"#, index + 1));
+            for (line, text) in lines.iter().enumerate() {
+                body.push_str(&format!(
+                    r#"<span class="f1" data-bbox="72,{},300,12" data-line="{}" data-segment="1">{}</span>"#,
+                    100 + line * 14,
+                    line + 1,
+                    text
+                ));
+            }
+            body.push_str("</p></div>");
+        }
+        format!(
+            "<html><head><style>.f1 {{ font-family: Times; font-size: 12px; }}</style></head><body>{}</body></html>",
+            body
+        )
+    }
+
+    fn extraction(backend: &str, xhtml: Result<String>) -> Extraction {
+        Extraction {
+            backend: backend.to_string(),
+            xhtml,
+            duration: Duration::from_millis(5),
+        }
+    }
+
+    #[test]
+    fn backends_are_measured_against_the_baseline() {
+        let extractions = [
+            extraction("jni", Ok(xhtml(&[&["Terms of the agreement", "apply to both parties."], &["Signed on the date below."]]))),
+            // Drops a line on page 2 and splits a line on page 1
+            extraction("candidate", Ok(xhtml(&[&["Terms of the", "agreement", "apply to both parties."], &[]]))),
+            extraction("broken", Err(anyhow!("no text layer"))),
+        ];
+        let comparison = compare_extractions(&extractions, &ParsingConfig::default()).unwrap();
+
+        assert_eq!(comparison.baseline, "jni");
+        let [jni, candidate, broken] = &comparison.backends[..] else {
+            panic!("expected three results");
+        };
+        assert_eq!((jni.spans, jni.words, jni.text_coverage), (3, 13, 1.0));
+        assert!(jni.similarity.is_none());
+        assert_eq!((candidate.spans, candidate.words, candidate.extra_words), (3, 8, 0));
+        assert!((candidate.text_coverage - 8.0 / 13.0).abs() < 1e-6);
+        assert!(candidate.similarity.as_ref().is_some_and(|s| s.text < 1.0));
+        assert_eq!(broken.error.as_deref(), Some("no text layer"));
+        assert_eq!(
+            comparison.page_differences,
+            [
+                PageDifference { page: 1, spans: vec![2, 3, 0] },
+                PageDifference { page: 2, spans: vec![1, 0, 0] },
+            ]
+        );
+
+        let failed_baseline = [extraction("broken", Err(anyhow!("no text layer")))];
+        assert!(compare_extractions(&failed_baseline, &ParsingConfig::default()).is_err());
+    }
+}
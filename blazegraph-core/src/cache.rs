@@ -1,4 +1,5 @@
 use crate::types::*;
+use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -9,7 +10,179 @@ pub mod versions {
     pub const TIKA_INTERFACE_VERSION: &str = "1.0.0";
 }
 
-/// Level 2 Cache Key (Config + XHTML → Graph)
+/// Which cache levels a processor reads and writes
+///
+/// - Level 1: extraction (document bytes → XHTML + TextElements)
+/// - Level 2: rules (XHTML + rule config → ParsedElements)
+/// - Level 3: graph (document + full config → DocumentGraph)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheLevels {
+    pub extraction: bool,
+    pub parsed_elements: bool,
+    pub graph: bool,
+}
+
+impl CacheLevels {
+    pub fn all() -> Self {
+        Self {
+            extraction: true,
+            parsed_elements: true,
+            graph: true,
+        }
+    }
+
+    pub fn none() -> Self {
+        Self {
+            extraction: false,
+            parsed_elements: false,
+            graph: false,
+        }
+    }
+
+    /// Parse a comma-separated level list such as "1,2,3" or "1"; "none" disables caching
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut levels = Self::none();
+        let spec = spec.trim();
+        if spec.is_empty() || spec.eq_ignore_ascii_case("none") {
+            return Ok(levels);
+        }
+        for level in spec.split(',') {
+            match level.trim() {
+                "1" => levels.extraction = true,
+                "2" => levels.parsed_elements = true,
+                "3" => levels.graph = true,
+                other => return Err(anyhow!("Unknown cache level '{}' (expected 1, 2 or 3)", other)),
+            }
+        }
+        Ok(levels)
+    }
+}
+
+impl Default for CacheLevels {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// Hit and miss counts for one cache level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct CacheLevelStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheLevelStats {
+    /// Fraction of lookups that hit, or None before the first lookup
+    pub fn hit_rate(&self) -> Option<f64> {
+        let lookups = self.hits + self.misses;
+        (lookups > 0).then(|| self.hits as f64 / lookups as f64)
+    }
+}
+
+/// Cache lookups per level since a processor was created
+///
+/// Only enabled levels are counted; a disabled level is never looked up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct CacheStats {
+    pub extraction: CacheLevelStats,
+    pub parsed_elements: CacheLevelStats,
+    pub graph: CacheLevelStats,
+}
+
+impl CacheStats {
+    pub(crate) fn record(level: &mut CacheLevelStats, hit: bool) {
+        if hit {
+            level.hits += 1;
+        } else {
+            level.misses += 1;
+        }
+    }
+}
+
+/// Level 2 Cache Key (XHTML + rule config → ParsedElements)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct ParsedElementsCacheKey {
+    pub xhtml_hash: String,
+    pub rules_hash: String,
+    pub blazegraph_version: String,
+    pub processing_version: String,
+}
+
+impl ParsedElementsCacheKey {
+    pub fn new(xhtml_hash: String, rules_hash: String) -> Self {
+        Self {
+            xhtml_hash,
+            rules_hash,
+            blazegraph_version: versions::BLAZEGRAPH_VERSION.to_string(),
+            processing_version: versions::PROCESSING_VERSION.to_string(),
+        }
+    }
+
+    /// Compute cache key hash for storage
+    pub fn to_cache_hash(&self) -> String {
+        use sha2::{Digest, Sha256};
+        let mut hasher = Sha256::new();
+        hasher.update(&self.xhtml_hash);
+        hasher.update(&self.rules_hash);
+        hasher.update(&self.blazegraph_version);
+        hasher.update(&self.processing_version);
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// Level 2 Cache Value (post-rules elements plus what the graph stage needs from stage 2)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedElementsCacheValue {
+    pub parsed_elements: Vec<ParsedPdfElement>,
+    pub document_analysis: DocumentAnalysis,
+    #[serde(default)]
+    pub skipped_pages: Vec<SkippedPage>,
+    #[serde(default)]
+    pub page_reordering: Option<PageReordering>,
+    #[serde(default)]
+    pub page_labels: Vec<PageLabel>,
+    /// Warnings from page filters and rules, replayed on a cache hit
+    #[serde(default)]
+    pub warnings: Vec<ProcessingWarning>,
+    /// The Validation rule's report, when the pipeline ran it
+    #[serde(default)]
+    pub validation: Option<ValidationReport>,
+    pub created_at: DateTime<Utc>,
+    pub processing_time_ms: u64,
+    pub cache_version: String,
+}
+
+impl ParsedElementsCacheValue {
+    pub fn new(
+        parsed_elements: Vec<ParsedPdfElement>,
+        document_analysis: DocumentAnalysis,
+        skipped_pages: Vec<SkippedPage>,
+        page_reordering: Option<PageReordering>,
+        page_labels: Vec<PageLabel>,
+        warnings: Vec<ProcessingWarning>,
+        processing_time_ms: u64,
+    ) -> Self {
+        Self {
+            parsed_elements,
+            document_analysis,
+            skipped_pages,
+            page_reordering,
+            page_labels,
+            warnings,
+            validation: None,
+            created_at: Utc::now(),
+            processing_time_ms,
+            cache_version: versions::BLAZEGRAPH_VERSION.to_string(),
+        }
+    }
+
+    pub fn with_validation(mut self, validation: Option<ValidationReport>) -> Self {
+        self.validation = validation;
+        self
+    }
+}
+
+/// Level 3 Cache Key (Config + PDF → Graph)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct GraphCacheKey {
     pub xhtml_hash: String,
@@ -40,7 +213,7 @@ impl GraphCacheKey {
     }
 }
 
-/// Level 2 Cache Value (Graph with metadata)
+/// Level 3 Cache Value (Graph with metadata)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphCacheValue {
     pub graph: DocumentGraph,
@@ -0,0 +1,189 @@
+//! Color-based semantic hints
+//!
+//! Span color is captured in `FontClass.color` but carries no meaning on its
+//! own. Some documents use it consistently, though: links in blue, headings
+//! in a brand color, watermarks and "DRAFT" stamps in light gray. The
+//! `color_rules` config block maps colors to an action:
+//!
+//! - `strip` removes the text before any rule sees it
+//! - `heading` tags it `role="heading"` (with `level` when configured), so
+//!   section detection treats it as a header
+//! - `link` tags it `role="link"`, so it is never promoted to a header
+//!
+//! Runs between page filtering and span merging. The first matching rule
+//! wins; text that already carries a role from the source format is only
+//! affected by `strip`.
+
+use crate::config::{ColorAction, ColorRule};
+use crate::preprocessors::pdf::css::parse_color;
+use crate::types::*;
+use anyhow::{bail, Result};
+
+/// A color rule with its colors resolved to RGB
+struct CompiledColorRule {
+    colors: Vec<[u8; 3]>,
+    tolerance: u8,
+    action: ColorAction,
+    level: Option<u32>,
+}
+
+impl CompiledColorRule {
+    fn matches(&self, color: &str) -> bool {
+        parse_rgb(color).is_some_and(|rgb| {
+            self.colors.iter().any(|target| {
+                target
+                    .iter()
+                    .zip(rgb)
+                    .all(|(&a, b)| a.abs_diff(b) <= self.tolerance)
+            })
+        })
+    }
+}
+
+/// Apply the configured color rules in place; returns how many elements
+/// were stripped or tagged
+pub fn apply_color_rules(text_elements: &mut Vec<PdfTextElement>, rules: &[ColorRule]) -> Result<usize> {
+    if rules.is_empty() {
+        return Ok(0);
+    }
+    let rules = compile(rules)?;
+
+    let before = text_elements.len();
+    text_elements.retain(|element| {
+        let rule = rules.iter().find(|rule| rule.matches(&element.style_info.color));
+        !rule.is_some_and(|rule| rule.action == ColorAction::Strip)
+    });
+    let stripped = before - text_elements.len();
+
+    let mut tagged = 0;
+    for element in text_elements.iter_mut() {
+        if element.paragraph_role().is_some() {
+            continue;
+        }
+        let Some(rule) = rules.iter().find(|rule| rule.matches(&element.style_info.color)) else {
+            continue;
+        };
+        let role = match rule.action {
+            ColorAction::Strip => continue,
+            ColorAction::Heading => "heading",
+            ColorAction::Link => "link",
+        };
+        element.paragraph_attributes.insert("role".to_string(), role.to_string());
+        if let (ColorAction::Heading, Some(level)) = (rule.action, rule.level) {
+            element.paragraph_attributes.insert("level".to_string(), level.to_string());
+        }
+        tagged += 1;
+    }
+
+    if stripped + tagged > 0 {
+        println!("🎨 Color rules stripped {} and tagged {} element(s)", stripped, tagged);
+    }
+    Ok(stripped + tagged)
+}
+
+/// Check that every rule's colors and level are usable
+pub fn validate_color_rules(rules: &[ColorRule]) -> Result<()> {
+    compile(rules).map(|_| ())
+}
+
+fn compile(rules: &[ColorRule]) -> Result<Vec<CompiledColorRule>> {
+    rules
+        .iter()
+        .map(|rule| {
+            let colors = rule
+                .colors
+                .iter()
+                .map(|color| match parse_rgb(color) {
+                    Some(rgb) => Ok(rgb),
+                    None => bail!("Invalid color in color_rules: {:?}", color),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            if rule.level == Some(0) {
+                bail!("color_rules heading level must be 1 or greater");
+            }
+            Ok(CompiledColorRule {
+                colors,
+                tolerance: rule.tolerance,
+                action: rule.action,
+                level: rule.level,
+            })
+        })
+        .collect()
+}
+
+/// "#rrggbb", "#rgb", "rgb(...)" or a basic CSS color name
+fn parse_rgb(value: &str) -> Option<[u8; 3]> {
+    let value = parse_color(value);
+    let hex = match value.as_str() {
+        "black" => "000000",
+        "white" => "ffffff",
+        "gray" | "grey" => "808080",
+        "silver" => "c0c0c0",
+        "lightgray" | "lightgrey" => "d3d3d3",
+        "red" => "ff0000",
+        "green" => "008000",
+        "blue" => "0000ff",
+        "navy" => "000080",
+        "purple" => "800080",
+        "orange" => "ffa500",
+        other => other.strip_prefix('#')?,
+    };
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    match hex.len() {
+        6 if hex.is_ascii() => Some([channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?]),
+        3 if hex.is_ascii() => {
+            let mut rgb = [0; 3];
+            for (slot, digit) in rgb.iter_mut().zip(hex.chars()) {
+                *slot = channel(&digit.to_string())? * 17;
+            }
+            Some(rgb)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{FontClassBuilder, TextElementBuilder};
+
+    fn colored(text: &str, color: &str) -> PdfTextElement {
+        TextElementBuilder::new(text)
+            .style(FontClassBuilder::new("f1").color(color).build())
+            .build()
+    }
+
+    #[test]
+    fn strips_and_tags_by_color() {
+        let rules: Vec<ColorRule> = serde_yaml::from_str(
+            r##"
+- colors: ["#d9d9d9"]
+  tolerance: 24
+  action: strip
+- colors: ["rgb(5, 99, 193)"]
+  action: link
+- colors: ["#1f4e79"]
+  action: heading
+  level: 2
+"##,
+        )
+        .unwrap();
+        let mut elements = vec![
+            colored("CONFIDENTIAL", "#cccccc"),
+            colored("https://example.com", "#0563c1"),
+            colored("Background", "#1f4e79"),
+            colored("Body text.", "#000000"),
+        ];
+
+        assert_eq!(apply_color_rules(&mut elements, &rules).unwrap(), 3);
+        let texts: Vec<&str> = elements.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, vec!["https://example.com", "Background", "Body text."]);
+        assert_eq!(elements[0].paragraph_role(), Some("link"));
+        assert_eq!(elements[1].paragraph_role(), Some("heading"));
+        assert_eq!(elements[1].paragraph_level(), Some(2));
+        assert_eq!(elements[2].paragraph_role(), None);
+
+        let bad: Vec<ColorRule> = serde_yaml::from_str("- colors: [\"#zzz\"]\n  action: strip").unwrap();
+        assert!(apply_color_rules(&mut elements, &bad).is_err());
+    }
+}
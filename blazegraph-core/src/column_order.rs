@@ -0,0 +1,239 @@
+//! Multi-column layout detection and reading order
+//!
+//! Extraction orders each page top to bottom, left to right, which
+//! interleaves the two columns of an academic paper line by line: the first
+//! line of the left column, then the first line of the right, and so on.
+//! Spatial clustering then merges paragraphs in that order.
+//!
+//! Runs after span merging and before rule processing. Each page's text is
+//! projected onto the x axis; interior runs of (almost) empty x coordinates
+//! wide enough to be a gutter split the page into columns. The page is then
+//! read band by band: elements crossing a gutter (titles, abstracts, figures
+//! set across the page) start a new band, and within a band each column is
+//! read top to bottom before the next column to its right (to its left on
+//! right-to-left pages). Vertical CJK pages are set in columns by nature and
+//! are left alone.
+
+use crate::config::ColumnDetectionConfig;
+use crate::reading_direction::ReadingDirection;
+use crate::types::*;
+use std::collections::BTreeMap;
+
+/// Reorder multi-column pages in place, each page read in `direction`
+/// (detected per page when `Auto`); returns how many pages were reordered
+pub fn order_columns(
+    text_elements: &mut [PdfTextElement],
+    config: &ColumnDetectionConfig,
+    direction: ReadingDirection,
+) -> usize {
+    if !config.enabled {
+        return 0;
+    }
+
+    let mut pages: BTreeMap<u32, Vec<usize>> = BTreeMap::new();
+    for (index, element) in text_elements.iter().enumerate() {
+        pages.entry(element.page_number).or_default().push(index);
+    }
+
+    let mut reordered = 0;
+    for indices in pages.values() {
+        let elements: Vec<&PdfTextElement> = indices.iter().map(|&i| &text_elements[i]).collect();
+        let direction = direction.resolve(elements.iter().map(|e| (e.text.as_str(), &e.bounding_box)));
+        if direction == ReadingDirection::VerticalRl {
+            continue;
+        }
+        let Some(gutters) = find_gutters(&elements, config) else {
+            continue;
+        };
+        let order = column_order(&elements, &gutters, direction);
+
+        // Reuse the page's own reading_order values so other pages are untouched
+        let mut slots: Vec<u32> = elements.iter().map(|e| e.reading_order).collect();
+        slots.sort_unstable();
+        let changed = order
+            .iter()
+            .zip(&slots)
+            .any(|(&position, &slot)| elements[position].reading_order != slot);
+        if !changed {
+            continue;
+        }
+        for (&position, slot) in order.iter().zip(slots) {
+            text_elements[indices[position]].reading_order = slot;
+        }
+        reordered += 1;
+    }
+
+    if reordered > 0 {
+        text_elements.sort_by_key(|e| (e.page_number, e.reading_order));
+        println!("📰 Read {} multi-column page(s) column by column", reordered);
+    }
+    reordered
+}
+
+/// Whether any page is laid out in columns
+pub fn has_columns(text_elements: &[PdfTextElement], config: &ColumnDetectionConfig) -> bool {
+    let mut pages: BTreeMap<u32, Vec<&PdfTextElement>> = BTreeMap::new();
+    for element in text_elements {
+        pages.entry(element.page_number).or_default().push(element);
+    }
+    pages.values().any(|elements| find_gutters(elements, config).is_some())
+}
+
+/// Table cells line up in columns of their own and are never reflowed
+fn is_table_cell(element: &PdfTextElement) -> bool {
+    element.paragraph_role() == Some("table-row")
+}
+
+/// X coordinates of the gutters between columns, or None for a single-column page
+fn find_gutters(elements: &[&PdfTextElement], config: &ColumnDetectionConfig) -> Option<Vec<f32>> {
+    let body: Vec<&PdfTextElement> = elements.iter().copied().filter(|e| !is_table_cell(e) && !e.text.trim().is_empty()).collect();
+    if body.len() < config.min_column_lines * 2 {
+        return None;
+    }
+
+    let left = body.iter().map(|e| e.bounding_box.x).fold(f32::INFINITY, f32::min);
+    let right = body
+        .iter()
+        .map(|e| e.bounding_box.x + e.bounding_box.width)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let width = right - left;
+    let bin_width = config.bin_width.max(0.5);
+    if width <= config.min_gutter_width {
+        return None;
+    }
+
+    // How many elements cover each slice of the page width. Anything wider
+    // than half the text can't sit in one of several columns and is left out
+    let bins = (width / bin_width).ceil() as usize;
+    let mut coverage = vec![0usize; bins];
+    for element in body.iter().filter(|e| e.bounding_box.width <= width / 2.0) {
+        let start = (((element.bounding_box.x - left) / bin_width).floor() as usize).min(bins - 1);
+        let end = ((element.bounding_box.x + element.bounding_box.width - left) / bin_width).ceil() as usize;
+        for count in &mut coverage[start..end.clamp(start + 1, bins)] {
+            *count += 1;
+        }
+    }
+
+    // A few narrower lines (centered headings, page numbers) may still cross a gutter
+    let noise = (body.len() as f32 * config.gutter_noise).floor() as usize;
+    let mut gutters = Vec::new();
+    let mut bin = 0;
+    while bin < bins {
+        if coverage[bin] > noise {
+            bin += 1;
+            continue;
+        }
+        let start = bin;
+        while bin < bins && coverage[bin] <= noise {
+            bin += 1;
+        }
+        let interior = start > 0 && bin < bins;
+        if interior && (bin - start) as f32 * bin_width >= config.min_gutter_width {
+            gutters.push(left + (start + bin) as f32 / 2.0 * bin_width);
+        }
+    }
+    if gutters.is_empty() || gutters.len() + 1 > config.max_columns {
+        return None;
+    }
+
+    // Every column must be substantial: wide, and holding several lines of text
+    let bounds: Vec<f32> = std::iter::once(left)
+        .chain(gutters.iter().copied())
+        .chain(std::iter::once(right))
+        .collect();
+    for column in bounds.windows(2) {
+        if column[1] - column[0] < width * config.min_column_fraction {
+            return None;
+        }
+        let mut lines: Vec<i32> = body
+            .iter()
+            .filter(|e| {
+                let center = e.bounding_box.x + e.bounding_box.width / 2.0;
+                center > column[0] && center < column[1] && !crosses_gutter(e, &gutters)
+            })
+            .map(|e| e.bounding_box.y.round() as i32)
+            .collect();
+        lines.sort_unstable();
+        lines.dedup();
+        if lines.len() < config.min_column_lines {
+            return None;
+        }
+    }
+    Some(gutters)
+}
+
+fn crosses_gutter(element: &PdfTextElement, gutters: &[f32]) -> bool {
+    let x0 = element.bounding_box.x;
+    let x1 = x0 + element.bounding_box.width;
+    gutters.iter().any(|&gutter| x0 < gutter && x1 > gutter)
+}
+
+/// Positions into `elements` in column-by-column reading order
+fn column_order(elements: &[&PdfTextElement], gutters: &[f32], direction: ReadingDirection) -> Vec<usize> {
+    let mut by_position: Vec<usize> = (0..elements.len()).collect();
+    by_position.sort_by(|&a, &b| direction.compare(&elements[a].bounding_box, &elements[b].bounding_box));
+    let right_to_left = direction == ReadingDirection::Rtl;
+
+    let mut order = Vec::with_capacity(elements.len());
+    let mut band: Vec<Vec<usize>> = vec![Vec::new(); gutters.len() + 1];
+    for position in by_position {
+        let element = elements[position];
+        if is_table_cell(element) || crosses_gutter(element, gutters) {
+            flush_band(&mut band, &mut order, right_to_left);
+            order.push(position);
+            continue;
+        }
+        let center = element.bounding_box.x + element.bounding_box.width / 2.0;
+        let column = gutters.iter().filter(|&&gutter| gutter < center).count();
+        band[column].push(position);
+    }
+    flush_band(&mut band, &mut order, right_to_left);
+    order
+}
+
+fn flush_band(band: &mut [Vec<usize>], order: &mut Vec<usize>, right_to_left: bool) {
+    for index in 0..band.len() {
+        let column = if right_to_left { band.len() - 1 - index } else { index };
+        order.append(&mut band[column]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{bbox, TextElementBuilder};
+
+    fn line(text: &str, x: f32, y: f32, width: f32, order: u32) -> PdfTextElement {
+        TextElementBuilder::new(text)
+            .bbox(bbox(x, y, width, 10.0))
+            .reading_order(order)
+            .build()
+    }
+
+    #[test]
+    fn two_column_page_is_read_column_by_column() {
+        // Full-width title, then two columns of five lines, extracted row by row
+        let mut elements = vec![line("Title", 72.0, 60.0, 450.0, 0)];
+        for row in 0..5u32 {
+            let y = 100.0 + row as f32 * 14.0;
+            elements.push(line(&format!("L{}", row), 72.0, y, 210.0, 1 + row * 2));
+            elements.push(line(&format!("R{}", row), 312.0, y, 210.0, 2 + row * 2));
+        }
+        elements.push(line("Conclusion spanning the page", 72.0, 200.0, 450.0, 11));
+
+        let config = ColumnDetectionConfig::default();
+        assert_eq!(order_columns(&mut elements, &config, ReadingDirection::Auto), 1);
+        let texts: Vec<&str> = elements.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            ["Title", "L0", "L1", "L2", "L3", "L4", "R0", "R1", "R2", "R3", "R4", "Conclusion spanning the page"]
+        );
+        assert!(elements.windows(2).all(|w| w[0].reading_order < w[1].reading_order));
+
+        // A single-column page (ragged right edge) is left alone
+        let mut single: Vec<PdfTextElement> = (0..8u32)
+            .map(|row| line("text", 72.0, 100.0 + row as f32 * 14.0, 300.0 + (row % 3) as f32 * 70.0, row))
+            .collect();
+        assert_eq!(order_columns(&mut single, &config, ReadingDirection::Auto), 0);
+    }
+}
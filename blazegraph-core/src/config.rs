@@ -1,7 +1,9 @@
+use crate::page_range::PageRange;
+use crate::reading_direction::ReadingDirection;
 use crate::types::DocumentType;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 
 // Default value functions for serde
@@ -16,6 +18,10 @@ pub struct ParsingConfig {
     pub section_and_hierarchy: SectionAndHierarchyConfig,
     pub spatial_clustering: SpatialClusteringConfig,
     pub section_patterns: Vec<String>,
+    /// Shipped, localized heading patterns to apply by name (`de`, `fr-legal`,
+    /// ...; see `pattern_packs::PATTERN_PACKS`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern_pack: Option<String>,
     /// Include raw Tika XML/HTML output in graph metadata for debugging
     #[serde(default)]
     pub include_raw_tika: bool,
@@ -25,12 +31,89 @@ pub struct ParsingConfig {
     /// List detection configuration
     #[serde(default)]
     pub list_detection: ListDetectionConfig,
+    /// Key-value ("Term: definition", label/value) detection configuration
+    #[serde(default)]
+    pub key_value_detection: KeyValueDetectionConfig,
+    /// Block quote and callout detection configuration
+    #[serde(default)]
+    pub quote_detection: QuoteDetectionConfig,
+    /// Running header/footer (page numbers, running titles) detection configuration
+    #[serde(default)]
+    pub header_footer_detection: HeaderFooterDetectionConfig,
+    /// Repeated boilerplate (confidentiality notices, watermark text) suppression
+    #[serde(default)]
+    pub boilerplate: BoilerplateConfig,
+    /// Table detection (rows/columns from spatial alignment) configuration
+    #[serde(default)]
+    pub table_detection: TableDetectionConfig,
+    /// "Figure 3:" / "Table 2." caption association configuration
+    #[serde(default)]
+    pub caption_association: CaptionAssociationConfig,
+    /// PDF outline (bookmarks) as the section skeleton
+    #[serde(default)]
+    pub bookmark_hierarchy: BookmarkHierarchyConfig,
     /// Size enforcement configuration
     #[serde(default)]
     pub size_enforcer: SizeEnforcerConfig,
+    /// Paragraphs split into sentence child nodes (the SentenceSegmentation rule)
+    #[serde(default)]
+    pub sentence_segmentation: SentenceSegmentationConfig,
+    /// Structural validation (the Validation rule): report only, or repair
+    #[serde(default)]
+    pub validation: ValidationConfig,
     /// Minimal parse mode - bypasses all rule processing and returns only base conversion
     #[serde(default)]
     pub minimal_parse: bool,
+    /// Switch to minimal parse for documents too simple for the rules to add anything
+    #[serde(default)]
+    pub auto_minimal_parse: AutoMinimalParseConfig,
+    /// Record each element's source TextElements and the rules that shaped
+    /// it, on the graph's nodes (costs a diff after every rule)
+    #[serde(default)]
+    pub provenance: bool,
+    /// Keep bold and italic runs inside merged node text, as offsets or as
+    /// Markdown markup (off by default: merged text is plain)
+    #[serde(default)]
+    pub inline_styles: InlineStyleMode,
+    /// Tokenizer for node token counts and SizeEnforcer's `tokens` unit:
+    /// "estimate", "cl100k_base", "o200k_base", or a registered name
+    #[serde(default = "default_tokenizer")]
+    pub tokenizer: String,
+    /// Only process these pages ("10-25,40"); all pages when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_range: Option<PageRange>,
+    /// Ligature expansion, soft-hyphen removal, quote folding and NFC on
+    /// extracted text, before anything else reads it
+    #[serde(default)]
+    pub text_normalization: TextNormalizationConfig,
+    /// Content-based page filters applied before rule processing
+    #[serde(default)]
+    pub page_filters: PageFilterConfig,
+    /// Merging of letter-spaced and word-per-span text applied before rule processing
+    #[serde(default)]
+    pub span_merging: SpanMergeConfig,
+    /// Splitting of degenerate spans holding a whole page or paragraph on one line
+    #[serde(default)]
+    pub giant_spans: GiantSpanConfig,
+    /// Reading direction of each page: detected from its script ("auto"), or
+    /// forced to "ltr", "rtl" or "vertical_rl"
+    #[serde(default)]
+    pub reading_direction: ReadingDirection,
+    /// Defined-term extraction into `DocumentInfo::glossary`
+    #[serde(default)]
+    pub glossary: GlossaryConfig,
+    /// Multi-column layout detection, reading columns in order before rule processing
+    #[serde(default)]
+    pub column_detection: ColumnDetectionConfig,
+    /// Out-of-order page detection for mis-ordered scans, before rule processing
+    #[serde(default)]
+    pub page_order: PageOrderConfig,
+    /// Printed page number detection (page labels), before rule processing
+    #[serde(default)]
+    pub page_labels: PageLabelConfig,
+    /// Color-based semantic hints (strip, heading, link) applied before rule processing
+    #[serde(default)]
+    pub color_rules: Vec<ColorRule>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,20 +129,66 @@ pub struct RuleConfig {
     /// Whether this rule is enabled
     #[serde(default = "default_true")]
     pub enabled: bool,
+    /// Settings for this occurrence of the rule, overriding its config
+    /// section (e.g. `size_enforcer` for SizeEnforcer), so one rule can run
+    /// twice with different settings. Custom rules receive them as is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<serde_yaml::Value>,
+}
+
+impl RuleConfig {
+    /// An enabled rule by name
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            enabled: true,
+            params: None,
+        }
+    }
+
+    /// The same rule with `params` overriding its config section
+    pub fn with_params(mut self, params: serde_yaml::Value) -> Self {
+        self.params = Some(params);
+        self
+    }
+}
+
+/// The config section a built-in rule reads its settings from, which
+/// `RuleConfig::params` override
+pub fn rule_config_section(rule_name: &str) -> Option<&'static str> {
+    Some(match rule_name {
+        "SpatialClustering" => "spatial_clustering",
+        "SectionDetection" => "section_and_hierarchy",
+        "BookmarkHierarchy" => "bookmark_hierarchy",
+        "ListDetection" => "list_detection",
+        "KeyValueDetection" => "key_value_detection",
+        "HeaderFooterDetection" => "header_footer_detection",
+        "BoilerplateSuppression" => "boilerplate",
+        "TableDetection" => "table_detection",
+        "CaptionAssociation" => "caption_association",
+        "QuoteDetection" => "quote_detection",
+        "SizeEnforcer" => "size_enforcer",
+        "SentenceSegmentation" => "sentence_segmentation",
+        "Validation" => "validation",
+        _ => return None,
+    })
+}
+
+impl PipelineConfig {
+    /// Pipeline running the named rules in order, all enabled
+    pub fn from_rule_names(names: &[&str]) -> Self {
+        Self {
+            rules: names.iter().map(|name| RuleConfig::new(name)).collect(),
+        }
+    }
 }
 
 impl Default for PipelineConfig {
     fn default() -> Self {
         Self {
             rules: vec![
-                RuleConfig {
-                    name: "SpatialClustering+StyleAnalysis".to_string(),
-                    enabled: true,
-                },
-                RuleConfig {
-                    name: "Validation".to_string(),
-                    enabled: true,
-                },
+                RuleConfig::new("SpatialClustering+StyleAnalysis"),
+                RuleConfig::new("Validation"),
             ],
         }
     }
@@ -162,6 +291,36 @@ pub struct SpatialClusteringConfig {
     pub sections: ElementClusteringConfig,
     /// Configuration for paragraph clustering
     pub paragraphs: ElementClusteringConfig,
+    /// Derive line height and gap thresholds from the document instead of the fixed values above
+    #[serde(default)]
+    pub adaptive_thresholds: AdaptiveThresholdConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdaptiveThresholdConfig {
+    /// When enabled, min_line_height and vertical_gap_threshold_multiplier are only fallbacks
+    #[serde(default)]
+    pub enabled: bool,
+    /// Estimate thresholds for each page separately (falls back to the document estimate)
+    #[serde(default)]
+    pub per_page: bool,
+    /// Minimum number of measured elements before an estimate is trusted
+    #[serde(default = "default_min_threshold_samples")]
+    pub min_samples: usize,
+}
+
+fn default_min_threshold_samples() -> usize {
+    5
+}
+
+impl Default for AdaptiveThresholdConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            per_page: false,
+            min_samples: default_min_threshold_samples(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -232,6 +391,16 @@ pub struct ListDetectionConfig {
     #[serde(default = "default_sequence_boundary_extension")]
     pub sequence_boundary_extension: usize,
 
+    /// Continue a numbered list across a page or column break when the next
+    /// marker's number follows on (7. at the foot of page 3, 8. atop page 4)
+    #[serde(default = "default_true")]
+    pub continue_across_breaks: bool,
+
+    /// How far apart (in elements) continued markers may be; running headers,
+    /// footers and page numbers sit between them
+    #[serde(default = "default_continuation_lookahead_elements")]
+    pub continuation_lookahead_elements: usize,
+
     /// Phase 2: Content Classification
     /// Y-coordinate tolerance for considering elements on the same line (in points)
     #[serde(default = "default_y_tolerance")]
@@ -280,6 +449,10 @@ fn default_sequence_boundary_extension() -> usize {
     3 // Elements past last marker to include for boundary detection
 }
 
+fn default_continuation_lookahead_elements() -> usize {
+    20 // Room for a footer, page number and running header between items
+}
+
 fn default_max_lookahead_elements() -> usize {
     25 // Increased from 5 to handle more complex list structures
 }
@@ -480,6 +653,8 @@ impl Default for ListDetectionConfig {
             enabled: true,
             sequence_lookahead_elements: default_sequence_lookahead_elements(),
             sequence_boundary_extension: default_sequence_boundary_extension(),
+            continue_across_breaks: true,
+            continuation_lookahead_elements: default_continuation_lookahead_elements(),
             y_tolerance: default_y_tolerance(),
             bullet_patterns: default_bullet_patterns(),
             numbered_patterns: default_numbered_patterns(),
@@ -492,6 +667,369 @@ impl Default for ListDetectionConfig {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyValueDetectionConfig {
+    /// Whether key-value detection is enabled
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Longest key, in words; longer "keys" are sentences with a colon
+    #[serde(default = "default_max_key_words")]
+    pub max_key_words: usize,
+
+    /// Longest key, in characters
+    #[serde(default = "default_max_key_chars")]
+    pub max_key_chars: usize,
+
+    /// Pairs must come in runs of at least this many consecutive lines, so a
+    /// lone "Note: ..." in running prose stays a paragraph
+    #[serde(default = "default_min_consecutive_pairs")]
+    pub min_consecutive_pairs: usize,
+
+    /// Y-coordinate tolerance for a label and its value on the same line (in points)
+    #[serde(default = "default_key_value_y_tolerance")]
+    pub y_tolerance: f32,
+}
+
+fn default_max_key_words() -> usize {
+    5
+}
+
+fn default_max_key_chars() -> usize {
+    40
+}
+
+fn default_min_consecutive_pairs() -> usize {
+    2
+}
+
+fn default_key_value_y_tolerance() -> f32 {
+    3.0
+}
+
+impl Default for KeyValueDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_key_words: default_max_key_words(),
+            max_key_chars: default_max_key_chars(),
+            min_consecutive_pairs: default_min_consecutive_pairs(),
+            y_tolerance: default_key_value_y_tolerance(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteDetectionConfig {
+    /// Whether quote/callout detection is enabled
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Emit Quote nodes for indented italic or quotation-mark blocks
+    #[serde(default = "default_true")]
+    pub detect_quotes: bool,
+
+    /// Emit Callout nodes for boxed (inset and set apart) or labelled blocks
+    #[serde(default = "default_true")]
+    pub detect_callouts: bool,
+
+    /// Minimum inset from the page's body margin to count as indented (in points)
+    #[serde(default = "default_quote_min_indent")]
+    pub min_indent: f32,
+
+    /// Blank space above and below a block for it to count as boxed (in points)
+    #[serde(default = "default_callout_isolation_gap")]
+    pub isolation_gap: f32,
+
+    /// Attribution line patterns (regex); the first capture group is the name
+    #[serde(default = "default_attribution_patterns")]
+    pub attribution_patterns: Vec<String>,
+
+    /// Leading words that mark an indented block as a callout ("Note: ...")
+    #[serde(default = "default_callout_labels")]
+    pub callout_labels: Vec<String>,
+}
+
+fn default_quote_min_indent() -> f32 {
+    18.0 // A quarter inch, the usual block-quote indent
+}
+
+fn default_callout_isolation_gap() -> f32 {
+    12.0
+}
+
+fn default_attribution_patterns() -> Vec<String> {
+    vec![r"^\s*(?:—|–|--|-)\s*(\S.*)$".to_string()]
+}
+
+fn default_callout_labels() -> Vec<String> {
+    ["Note", "Tip", "Important", "Warning", "Caution", "Example"]
+        .iter()
+        .map(|label| label.to_string())
+        .collect()
+}
+
+impl Default for QuoteDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            detect_quotes: true,
+            detect_callouts: true,
+            min_indent: default_quote_min_indent(),
+            isolation_gap: default_callout_isolation_gap(),
+            attribution_patterns: default_attribution_patterns(),
+            callout_labels: default_callout_labels(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeaderFooterDetectionConfig {
+    /// Whether header/footer detection is enabled
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// What to do with detected headers and footers
+    #[serde(default)]
+    pub action: HeaderFooterAction,
+
+    /// Height of the top and bottom bands, as a fraction of the text area height
+    #[serde(default = "default_header_footer_band")]
+    pub band_fraction: f32,
+
+    /// Distinct pages a line must repeat on (digits ignored) to count
+    #[serde(default = "default_header_footer_min_pages")]
+    pub min_pages: usize,
+
+    /// Longer lines are never headers or footers
+    #[serde(default = "default_header_footer_max_chars")]
+    pub max_chars: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeaderFooterAction {
+    /// Keep them as `Header`/`Footer` nodes, out of paragraph content
+    #[default]
+    Tag,
+    /// Drop them entirely
+    Strip,
+}
+
+fn default_header_footer_band() -> f32 {
+    0.08
+}
+
+fn default_header_footer_min_pages() -> usize {
+    3
+}
+
+fn default_header_footer_max_chars() -> usize {
+    120
+}
+
+impl Default for HeaderFooterDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            action: HeaderFooterAction::default(),
+            band_fraction: default_header_footer_band(),
+            min_pages: default_header_footer_min_pages(),
+            max_chars: default_header_footer_max_chars(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoilerplateConfig {
+    /// Whether boilerplate suppression is enabled
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// What to do with repeated blocks
+    #[serde(default)]
+    pub action: BoilerplateAction,
+
+    /// Distinct pages the same text must appear on to count
+    #[serde(default = "default_boilerplate_min_pages")]
+    pub min_pages: usize,
+
+    /// Shorter blocks ("Signed:", "Yes") are never boilerplate
+    #[serde(default = "default_boilerplate_min_chars")]
+    pub min_chars: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoilerplateAction {
+    /// Keep the first occurrence, drop the repeats
+    #[default]
+    Collapse,
+    /// Drop every occurrence
+    Drop,
+}
+
+fn default_boilerplate_min_pages() -> usize {
+    3
+}
+
+fn default_boilerplate_min_chars() -> usize {
+    20
+}
+
+impl Default for BoilerplateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            action: BoilerplateAction::default(),
+            min_pages: default_boilerplate_min_pages(),
+            min_chars: default_boilerplate_min_chars(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableDetectionConfig {
+    /// Whether table detection is enabled
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Rows (lines) a block needs to count as a table
+    #[serde(default = "default_table_min_rows")]
+    pub min_rows: usize,
+
+    /// Columns, separated by gutters empty in every row, a table needs
+    #[serde(default = "default_table_min_columns")]
+    pub min_columns: usize,
+
+    /// Smallest horizontal gap between cells on one line (in points)
+    #[serde(default = "default_table_min_column_gap")]
+    pub min_column_gap: f32,
+
+    /// Largest blank space between rows, in multiples of the line height
+    #[serde(default = "default_table_max_row_gap")]
+    pub max_row_gap: f32,
+
+    /// Largest average cell length in characters; multi-column body text
+    /// has long "cells" and is left alone
+    #[serde(default = "default_table_max_cell_chars")]
+    pub max_cell_chars: f32,
+}
+
+fn default_table_min_rows() -> usize {
+    3
+}
+
+fn default_table_min_columns() -> usize {
+    2
+}
+
+fn default_table_min_column_gap() -> f32 {
+    10.0
+}
+
+fn default_table_max_row_gap() -> f32 {
+    1.5
+}
+
+fn default_table_max_cell_chars() -> f32 {
+    40.0
+}
+
+impl Default for TableDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_rows: default_table_min_rows(),
+            min_columns: default_table_min_columns(),
+            min_column_gap: default_table_min_column_gap(),
+            max_row_gap: default_table_max_row_gap(),
+            max_cell_chars: default_table_max_cell_chars(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptionAssociationConfig {
+    /// Whether caption association is enabled
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Words that start a figure caption ("Figure 3:", "Fig. 2.")
+    #[serde(default = "default_figure_caption_labels")]
+    pub figure_labels: Vec<String>,
+
+    /// Words that start a table caption ("Table 2.")
+    #[serde(default = "default_table_caption_labels")]
+    pub table_labels: Vec<String>,
+
+    /// Largest vertical gap between a caption and its figure or table (in points)
+    #[serde(default = "default_caption_max_distance")]
+    pub max_distance: f32,
+
+    /// Keep captions with nothing nearby as `Caption` elements (otherwise
+    /// they stay paragraphs)
+    #[serde(default = "default_true")]
+    pub standalone_captions: bool,
+}
+
+fn default_figure_caption_labels() -> Vec<String> {
+    ["Figure", "Fig.", "Chart", "Exhibit", "Illustration"]
+        .iter()
+        .map(|label| label.to_string())
+        .collect()
+}
+
+fn default_table_caption_labels() -> Vec<String> {
+    vec!["Table".to_string()]
+}
+
+fn default_caption_max_distance() -> f32 {
+    36.0
+}
+
+impl Default for CaptionAssociationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            figure_labels: default_figure_caption_labels(),
+            table_labels: default_table_caption_labels(),
+            max_distance: default_caption_max_distance(),
+            standalone_captions: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookmarkHierarchyConfig {
+    /// Whether bookmark-driven sections are enabled
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Lowest `bookmark_match` confidence that makes a span a bookmark's heading
+    #[serde(default = "default_bookmark_min_confidence")]
+    pub min_confidence: f32,
+
+    /// Turn font-detected sections that match no bookmark back into
+    /// paragraphs (otherwise they nest below the enclosing bookmark)
+    #[serde(default)]
+    pub demote_unmatched: bool,
+}
+
+fn default_bookmark_min_confidence() -> f32 {
+    0.8
+}
+
+impl Default for BookmarkHierarchyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_confidence: default_bookmark_min_confidence(),
+            demote_unmatched: false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SizeEnforcerConfig {
     /// Whether size enforcement is enabled
@@ -502,7 +1040,8 @@ pub struct SizeEnforcerConfig {
     #[serde(default = "default_max_size")]
     pub max_size: usize,
 
-    /// What to measure: "characters", "words", or "bytes"
+    /// What to measure: "characters", "words", "bytes", or "tokens" (counted
+    /// with the config's `tokenizer`)
     #[serde(default = "default_size_unit")]
     pub size_unit: String,
 
@@ -542,6 +1081,488 @@ impl Default for SizeEnforcerConfig {
     }
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationConfig {
+    /// Repair what can be repaired instead of only reporting it: clamp
+    /// hierarchy jumps, renumber inconsistent reading order, and drop
+    /// elements with zero-area bounding boxes
+    #[serde(default)]
+    pub fix: bool,
+
+    /// Policy per issue kind ("hierarchy_jump", "page_inconsistency", ...);
+    /// kinds not listed are reported (or repaired, with `fix`)
+    #[serde(default)]
+    pub policies: BTreeMap<String, ValidationPolicy>,
+}
+
+/// What the Validation rule does with one kind of issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationPolicy {
+    /// Leave it out of the report and the quality score
+    Ignore,
+    /// Report it
+    Warn,
+    /// Report it at error severity and fail the run
+    FailRun,
+    /// Repair it (hierarchy_jump, reading_order_inconsistency and
+    /// invalid_position only); what can't be repaired is reported
+    AutoRepair,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentenceSegmentationConfig {
+    /// Whether paragraphs are split into sentences
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Fewest sentences a paragraph needs to get sentence children
+    #[serde(default = "default_min_sentences")]
+    pub min_sentences: usize,
+
+    /// Abbreviations that don't end a sentence, on top of the built-in ones
+    /// ("Dr.", "Fig.", "e.g.", ...); written with their trailing period
+    #[serde(default)]
+    pub abbreviations: Vec<String>,
+}
+
+fn default_tokenizer() -> String {
+    "estimate".to_string()
+}
+
+fn default_min_sentences() -> usize {
+    2
+}
+
+impl Default for SentenceSegmentationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_sentences: default_min_sentences(),
+            abbreviations: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoMinimalParseConfig {
+    /// Whether simple documents skip rule processing
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Most pages a simple document has
+    #[serde(default = "default_auto_minimal_max_pages")]
+    pub max_pages: usize,
+
+    /// Most distinct font sizes in a simple document's text
+    #[serde(default = "default_auto_minimal_max_font_sizes")]
+    pub max_font_sizes: usize,
+}
+
+fn default_auto_minimal_max_pages() -> usize {
+    3
+}
+
+fn default_auto_minimal_max_font_sizes() -> usize {
+    1
+}
+
+impl Default for AutoMinimalParseConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_pages: default_auto_minimal_max_pages(),
+            max_font_sizes: default_auto_minimal_max_font_sizes(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageFilterConfig {
+    /// Whether page filtering is enabled
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Skip any page whose full text matches one of these regexes
+    #[serde(default = "default_skip_pages_matching")]
+    pub skip_pages_matching: Vec<String>,
+
+    /// Skip leading pages whose text matches one of these regexes
+    #[serde(default)]
+    pub cover_page_patterns: Vec<String>,
+
+    /// How many leading pages are eligible as cover pages
+    #[serde(default = "default_max_cover_pages")]
+    pub max_cover_pages: usize,
+
+    /// Skip every page after the first page with a span matching one of these regexes
+    #[serde(default)]
+    pub skip_after_patterns: Vec<String>,
+}
+
+fn default_skip_pages_matching() -> Vec<String> {
+    vec![
+        // Optional page number before or after the notice
+        r"(?i)^\s*(\d+\s+)?this page (is )?intentionally (left )?blank\.?(\s+\d+)?\s*$".to_string(),
+    ]
+}
+
+fn default_max_cover_pages() -> usize {
+    2
+}
+
+impl Default for PageFilterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            skip_pages_matching: default_skip_pages_matching(),
+            cover_page_patterns: Vec::new(),
+            max_cover_pages: default_max_cover_pages(),
+            skip_after_patterns: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextNormalizationConfig {
+    /// Whether text normalization is enabled
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Put text in Unicode NFC (precomposed accented letters)
+    #[serde(default = "default_true")]
+    pub nfc: bool,
+
+    /// Expand Latin ligatures ("ﬁ" → "fi")
+    #[serde(default = "default_true")]
+    pub expand_ligatures: bool,
+
+    /// Fold curly quotes to straight ones (‘’ → ', “” → ")
+    #[serde(default)]
+    pub fold_quotes: bool,
+
+    /// Remove soft hyphens (U+00AD)
+    #[serde(default = "default_true")]
+    pub remove_soft_hyphens: bool,
+}
+
+impl Default for TextNormalizationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            nfc: true,
+            expand_ligatures: true,
+            fold_quotes: false,
+            remove_soft_hyphens: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpanMergeConfig {
+    /// Whether span merging is enabled
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Minimum run of single-token spans on one line to merge
+    #[serde(default = "default_span_merge_min_spans")]
+    pub min_spans: usize,
+
+    /// Largest horizontal gap between merged spans, in multiples of the font size
+    /// (wider gaps are table columns)
+    #[serde(default = "default_span_merge_max_gap_em")]
+    pub max_gap_em: f32,
+
+    /// Spans whose tops differ by at most this much share a line (in points)
+    #[serde(default = "default_span_merge_y_tolerance")]
+    pub y_tolerance: f32,
+
+    /// Also collapse letter-spaced text inside one span ("I N T R O" → "INTRO")
+    #[serde(default = "default_true")]
+    pub collapse_spaced_letters: bool,
+
+    /// Minimum spaced-out letters before a span is collapsed
+    #[serde(default = "default_span_merge_min_spaced_letters")]
+    pub min_spaced_letters: usize,
+}
+
+fn default_span_merge_min_spans() -> usize {
+    3
+}
+
+fn default_span_merge_max_gap_em() -> f32 {
+    1.5
+}
+
+fn default_span_merge_y_tolerance() -> f32 {
+    2.0
+}
+
+fn default_span_merge_min_spaced_letters() -> usize {
+    4
+}
+
+impl Default for SpanMergeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_spans: default_span_merge_min_spans(),
+            max_gap_em: default_span_merge_max_gap_em(),
+            y_tolerance: default_span_merge_y_tolerance(),
+            collapse_spaced_letters: true,
+            min_spaced_letters: default_span_merge_min_spaced_letters(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiantSpanConfig {
+    /// Whether giant spans are split
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// A span longer than this many characters is always giant
+    #[serde(default = "default_giant_span_max_chars")]
+    pub max_chars: usize,
+
+    /// ...and so is one holding this many times the text its box can show
+    /// at its font size
+    #[serde(default = "default_giant_span_overflow")]
+    pub overflow: f32,
+
+    /// Length of the pieces a giant span is split into, in characters
+    #[serde(default = "default_giant_span_target_chars")]
+    pub target_chars: usize,
+}
+
+fn default_giant_span_max_chars() -> usize {
+    4000
+}
+
+fn default_giant_span_overflow() -> f32 {
+    4.0
+}
+
+fn default_giant_span_target_chars() -> usize {
+    600
+}
+
+impl Default for GiantSpanConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_chars: default_giant_span_max_chars(),
+            overflow: default_giant_span_overflow(),
+            target_chars: default_giant_span_target_chars(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlossaryConfig {
+    /// Whether defined terms are collected (on in the legal contract config)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Longest term, in words; longer quoted phrases are not terms
+    #[serde(default = "default_glossary_max_term_words")]
+    pub max_term_words: usize,
+}
+
+fn default_glossary_max_term_words() -> usize {
+    6
+}
+
+impl Default for GlossaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_term_words: default_glossary_max_term_words(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnDetectionConfig {
+    /// Whether multi-column pages are read column by column
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Width of the x-coordinate histogram bins (in points)
+    #[serde(default = "default_column_bin_width")]
+    pub bin_width: f32,
+
+    /// Narrowest empty vertical strip that separates two columns (in points)
+    #[serde(default = "default_column_min_gutter_width")]
+    pub min_gutter_width: f32,
+
+    /// Fraction of a page's elements that may cross a gutter (titles, page numbers)
+    #[serde(default = "default_column_gutter_noise")]
+    pub gutter_noise: f32,
+
+    /// Narrowest column, as a fraction of the page's text width
+    #[serde(default = "default_column_min_column_fraction")]
+    pub min_column_fraction: f32,
+
+    /// Fewest lines of text each column must hold
+    #[serde(default = "default_column_min_column_lines")]
+    pub min_column_lines: usize,
+
+    /// Pages that split into more columns than this are left alone
+    #[serde(default = "default_column_max_columns")]
+    pub max_columns: usize,
+}
+
+fn default_column_bin_width() -> f32 {
+    4.0
+}
+
+fn default_column_min_gutter_width() -> f32 {
+    12.0
+}
+
+fn default_column_gutter_noise() -> f32 {
+    0.05
+}
+
+fn default_column_min_column_fraction() -> f32 {
+    0.25
+}
+
+fn default_column_min_column_lines() -> usize {
+    4
+}
+
+fn default_column_max_columns() -> usize {
+    3
+}
+
+impl Default for ColumnDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            bin_width: default_column_bin_width(),
+            min_gutter_width: default_column_min_gutter_width(),
+            gutter_noise: default_column_gutter_noise(),
+            min_column_fraction: default_column_min_column_fraction(),
+            min_column_lines: default_column_min_column_lines(),
+            max_columns: default_column_max_columns(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageOrderConfig {
+    /// Whether pages are checked for scan-order mistakes
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Only report a suggested order, or renumber the pages into it
+    #[serde(default)]
+    pub action: PageOrderAction,
+
+    /// Fraction of pages that must carry a printed page number for the
+    /// numbers to decide the order; below it, sentence continuations do
+    #[serde(default = "default_page_order_min_numbered_fraction")]
+    pub min_numbered_fraction: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageOrderAction {
+    /// Record the suggested order and warn, leaving pages as scanned
+    #[default]
+    Suggest,
+    /// Renumber pages into the suggested order before graph building
+    Apply,
+}
+
+fn default_page_order_min_numbered_fraction() -> f32 {
+    0.5
+}
+
+impl Default for PageOrderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            action: PageOrderAction::default(),
+            min_numbered_fraction: default_page_order_min_numbered_fraction(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InlineStyleMode {
+    /// Merged text is plain
+    #[default]
+    Off,
+    /// Record runs as character offsets in `content.style_runs`
+    Spans,
+    /// Wrap runs in the text itself: `**bold**`, `_italic_`
+    Markdown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageLabelConfig {
+    /// Whether printed page numbers are detected and recorded as page labels
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Remove the page number lines, so they don't become content nodes
+    #[serde(default = "default_true")]
+    pub suppress: bool,
+
+    /// Most unnumbered pages (chapter openers, plates) allowed between two
+    /// numbered pages that confirm each other
+    #[serde(default = "default_page_label_max_gap")]
+    pub max_gap: u32,
+}
+
+fn default_page_label_max_gap() -> u32 {
+    3
+}
+
+impl Default for PageLabelConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            suppress: true,
+            max_gap: default_page_label_max_gap(),
+        }
+    }
+}
+
+/// Treat text in the given colors as a semantic signal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorRule {
+    /// Colors to match: "#rrggbb", "#rgb", "rgb(r, g, b)" or a basic CSS name
+    pub colors: Vec<String>,
+    /// What to do with matching text
+    pub action: ColorAction,
+    /// Largest per-channel difference (0-255) that still counts as a match
+    #[serde(default = "default_color_tolerance")]
+    pub tolerance: u8,
+    /// Heading level for `heading` (omit to derive it from font size)
+    #[serde(default)]
+    pub level: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorAction {
+    /// Drop the text entirely (watermarks, gray stamps)
+    Strip,
+    /// Treat the text as a section heading
+    Heading,
+    /// Treat the text as a link: never a heading
+    Link,
+}
+
+fn default_color_tolerance() -> u8 {
+    16
+}
+
 #[derive(Debug, Clone)]
 pub struct ConfigManager {
     configs: HashMap<DocumentType, ParsingConfig>,
@@ -609,6 +1630,7 @@ impl ConfigManager {
                     min_segment_size: 200,   // Larger minimum for academic content
                     max_segment_size: 12000, // Allow larger segments for detailed methods/results
                 },
+                adaptive_thresholds: AdaptiveThresholdConfig::default(),
             },
             section_patterns: vec![
                 "abstract".to_string(),
@@ -622,8 +1644,39 @@ impl ConfigManager {
             include_raw_tika: false, // Default to false for backward compatibility
             pipeline: PipelineConfig::default(),
             list_detection: ListDetectionConfig::default(),
+            key_value_detection: KeyValueDetectionConfig::default(),
+            quote_detection: QuoteDetectionConfig::default(),
+            header_footer_detection: HeaderFooterDetectionConfig::default(),
+            boilerplate: BoilerplateConfig::default(),
+            table_detection: TableDetectionConfig::default(),
+            bookmark_hierarchy: BookmarkHierarchyConfig::default(),
+            pattern_pack: None,
+            page_order: PageOrderConfig::default(),
+            page_labels: PageLabelConfig::default(),
+            caption_association: CaptionAssociationConfig::default(),
             size_enforcer: SizeEnforcerConfig::default(), // TODO: OPTIMIZATION_DESIGN phase - document type specific tuning
+            validation: ValidationConfig::default(),
+            sentence_segmentation: SentenceSegmentationConfig::default(),
+            tokenizer: default_tokenizer(),
+            page_range: None,
+            text_normalization: TextNormalizationConfig::default(),
+            reading_direction: ReadingDirection::default(),
+            glossary: GlossaryConfig::default(),
+            auto_minimal_parse: AutoMinimalParseConfig::default(),
             minimal_parse: false,
+            provenance: false,
+            inline_styles: InlineStyleMode::Off,
+            page_filters: PageFilterConfig {
+                // Drop reference lists and appendices that follow them
+                skip_after_patterns: vec![
+                    r"(?i)^\s*(\d+\.?\s*)?(references|bibliography)\s*$".to_string(),
+                ],
+                ..PageFilterConfig::default()
+            },
+            span_merging: SpanMergeConfig::default(),
+            giant_spans: GiantSpanConfig::default(),
+            column_detection: ColumnDetectionConfig::default(),
+            color_rules: Vec::new(),
         };
         self.configs
             .insert(DocumentType::AcademicPaper, academic_config);
@@ -660,6 +1713,7 @@ impl ConfigManager {
                     min_segment_size: 50,   // Smaller minimum - legal clauses can be short
                     max_segment_size: 5000, // Moderate maximum - keep clauses digestible
                 },
+                adaptive_thresholds: AdaptiveThresholdConfig::default(),
             },
             section_patterns: vec![
                 "article".to_string(),
@@ -672,8 +1726,40 @@ impl ConfigManager {
             include_raw_tika: false, // Default to false for backward compatibility
             pipeline: PipelineConfig::default(),
             list_detection: ListDetectionConfig::default(),
+            key_value_detection: KeyValueDetectionConfig::default(),
+            // Indented clauses are structure, not asides
+            quote_detection: QuoteDetectionConfig {
+                detect_callouts: false,
+                ..QuoteDetectionConfig::default()
+            },
+            header_footer_detection: HeaderFooterDetectionConfig::default(),
+            boilerplate: BoilerplateConfig::default(),
+            table_detection: TableDetectionConfig::default(),
+            bookmark_hierarchy: BookmarkHierarchyConfig::default(),
+            pattern_pack: None,
+            page_order: PageOrderConfig::default(),
+            page_labels: PageLabelConfig::default(),
+            caption_association: CaptionAssociationConfig::default(),
             size_enforcer: SizeEnforcerConfig::default(), // TODO: OPTIMIZATION_DESIGN phase
+            validation: ValidationConfig::default(),
+            sentence_segmentation: SentenceSegmentationConfig::default(),
+            tokenizer: default_tokenizer(),
+            page_range: None,
+            text_normalization: TextNormalizationConfig::default(),
+            reading_direction: ReadingDirection::default(),
+            glossary: GlossaryConfig {
+                enabled: true,
+                ..GlossaryConfig::default()
+            },
+            auto_minimal_parse: AutoMinimalParseConfig::default(),
             minimal_parse: false,
+            provenance: false,
+            inline_styles: InlineStyleMode::Off,
+            page_filters: PageFilterConfig::default(),
+            span_merging: SpanMergeConfig::default(),
+            giant_spans: GiantSpanConfig::default(),
+            column_detection: ColumnDetectionConfig::default(),
+            color_rules: Vec::new(),
         };
         self.configs
             .insert(DocumentType::LegalContract, legal_config);
@@ -701,6 +1787,7 @@ impl ConfigManager {
                     min_segment_size: 100,  // Minimum 100 chars per segment
                     max_segment_size: 8000, // Maximum 8000 chars per segment
                 },
+                adaptive_thresholds: AdaptiveThresholdConfig::default(),
             },
             section_patterns: vec![
                 // Generic patterns that might indicate sections
@@ -716,8 +1803,33 @@ impl ConfigManager {
             include_raw_tika: false, // Default to false for backward compatibility
             pipeline: PipelineConfig::default(),
             list_detection: ListDetectionConfig::default(),
+            key_value_detection: KeyValueDetectionConfig::default(),
+            quote_detection: QuoteDetectionConfig::default(),
+            header_footer_detection: HeaderFooterDetectionConfig::default(),
+            boilerplate: BoilerplateConfig::default(),
+            table_detection: TableDetectionConfig::default(),
+            bookmark_hierarchy: BookmarkHierarchyConfig::default(),
+            pattern_pack: None,
+            page_order: PageOrderConfig::default(),
+            page_labels: PageLabelConfig::default(),
+            caption_association: CaptionAssociationConfig::default(),
             size_enforcer: SizeEnforcerConfig::default(), // TODO: OPTIMIZATION_DESIGN phase
+            validation: ValidationConfig::default(),
+            sentence_segmentation: SentenceSegmentationConfig::default(),
+            tokenizer: default_tokenizer(),
+            page_range: None,
+            text_normalization: TextNormalizationConfig::default(),
+            reading_direction: ReadingDirection::default(),
+            glossary: GlossaryConfig::default(),
+            auto_minimal_parse: AutoMinimalParseConfig::default(),
             minimal_parse: false,
+            provenance: false,
+            inline_styles: InlineStyleMode::Off,
+            page_filters: PageFilterConfig::default(),
+            span_merging: SpanMergeConfig::default(),
+            giant_spans: GiantSpanConfig::default(),
+            column_detection: ColumnDetectionConfig::default(),
+            color_rules: Vec::new(),
         }
     }
 }
@@ -729,6 +1841,10 @@ impl Default for ConfigManager {
 }
 
 impl ParsingConfig {
+    /// Fields that only affect graph building and output, not rule processing.
+    /// They are left out of the Level 2 (ParsedElements) cache key.
+    pub const GRAPH_STAGE_FIELDS: &'static [&'static str] = &["include_raw_tika"];
+
     /// Load config from file path (functional approach)
     pub fn load_from_file(path: &str) -> Result<Self> {
         let content = std::fs::read_to_string(path)?;
@@ -736,6 +1852,57 @@ impl ParsingConfig {
         Ok(config)
     }
     
+    /// Check for mistakes that would otherwise only surface mid-document
+    /// (bad page filter regexes, unparseable colors, impossible levels)
+    pub fn validate(&self) -> Result<()> {
+        crate::page_filter::PageFilter::from_config(&self.page_filters)?;
+        crate::color_rules::validate_color_rules(&self.color_rules)?;
+        crate::pattern_packs::SectionPatterns::from_config(self)?;
+        let hierarchy = &self.section_and_hierarchy;
+        if hierarchy.starting_section_level == 0 || hierarchy.starting_section_level > hierarchy.max_depth {
+            anyhow::bail!(
+                "section_and_hierarchy.starting_section_level must be between 1 and max_depth ({}), got {}",
+                hierarchy.max_depth,
+                hierarchy.starting_section_level
+            );
+        }
+        // Custom rules interpret their own params; built-in ones must match
+        // their section's fields
+        for rule in &self.pipeline.rules {
+            if let Some(params) = &rule.params {
+                if crate::rules::engine::BUILT_IN_RULES.contains(&rule.name.as_str()) {
+                    self.with_rule_params(&rule.name, params)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// This config with `params` merged over the config section of the
+    /// built-in rule `rule_name`. Unknown keys are errors, so a typo in a
+    /// pipeline entry doesn't silently fall back to the section's value
+    pub fn with_rule_params(&self, rule_name: &str, params: &serde_yaml::Value) -> Result<Self> {
+        use anyhow::{anyhow, bail, Context};
+        use serde_yaml::Value;
+
+        let section = rule_config_section(rule_name)
+            .ok_or_else(|| anyhow!("Rule '{}' takes no params", rule_name))?;
+        let Value::Mapping(params) = params else {
+            bail!("params for rule '{}' must be a mapping", rule_name);
+        };
+        let mut config = serde_yaml::to_value(self)?;
+        let Some(Value::Mapping(settings)) = config.get_mut(section) else {
+            bail!("Config has no '{}' section", section);
+        };
+        for (key, value) in params {
+            if !settings.contains_key(key) {
+                bail!("Unknown param {:?} for rule '{}' (not a field of '{}')", key, rule_name, section);
+            }
+            settings.insert(key.clone(), value.clone());
+        }
+        serde_yaml::from_value(config).with_context(|| format!("Invalid params for rule '{}'", rule_name))
+    }
+
     /// Load config with fallback to default
     pub fn load_with_fallback(path: Option<&str>) -> Self {
         match path {
@@ -770,13 +1937,39 @@ impl Default for ParsingConfig {
                     min_segment_size: 100,
                     max_segment_size: 8000,
                 },
+                adaptive_thresholds: AdaptiveThresholdConfig::default(),
             },
             section_patterns: vec![],
             include_raw_tika: false,
             pipeline: PipelineConfig::default(),
             list_detection: ListDetectionConfig::default(),
+            key_value_detection: KeyValueDetectionConfig::default(),
+            quote_detection: QuoteDetectionConfig::default(),
+            header_footer_detection: HeaderFooterDetectionConfig::default(),
+            boilerplate: BoilerplateConfig::default(),
+            table_detection: TableDetectionConfig::default(),
+            bookmark_hierarchy: BookmarkHierarchyConfig::default(),
+            pattern_pack: None,
+            page_order: PageOrderConfig::default(),
+            page_labels: PageLabelConfig::default(),
+            caption_association: CaptionAssociationConfig::default(),
             size_enforcer: SizeEnforcerConfig::default(),
+            validation: ValidationConfig::default(),
+            sentence_segmentation: SentenceSegmentationConfig::default(),
+            tokenizer: default_tokenizer(),
+            page_range: None,
+            text_normalization: TextNormalizationConfig::default(),
+            reading_direction: ReadingDirection::default(),
+            glossary: GlossaryConfig::default(),
+            auto_minimal_parse: AutoMinimalParseConfig::default(),
             minimal_parse: false,
+            provenance: false,
+            inline_styles: InlineStyleMode::Off,
+            page_filters: PageFilterConfig::default(),
+            span_merging: SpanMergeConfig::default(),
+            giant_spans: GiantSpanConfig::default(),
+            column_detection: ColumnDetectionConfig::default(),
+            color_rules: Vec::new(),
         }
     }
 }
@@ -0,0 +1,157 @@
+//! AES-256-GCM encryption at rest for cache entries and outputs
+//!
+//! Cached extractions hold the full text of every processed document, which
+//! may not sit in plaintext on shared disks. A `Cipher` seals each file on
+//! its own: a magic prefix, a random 96-bit nonce, then the ciphertext and
+//! its authentication tag, so tampered or truncated files fail to open
+//! instead of yielding garbage.
+//!
+//! The key comes from a `KeyProvider`: `EnvKey` reads it from the
+//! environment, and any `Fn() -> Result<[u8; 32]>` works as a callback into
+//! a KMS or secret manager.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, bail, Context, Result};
+
+/// Marks a sealed file (format version 1)
+const MAGIC: &[u8] = b"BGENC1\0";
+const NONCE_LEN: usize = 12;
+
+/// Default environment variable holding the key as 64 hex characters
+pub const KEY_ENV_VAR: &str = "BLAZEGRAPH_ENCRYPTION_KEY";
+
+/// Source of the 256-bit data key
+pub trait KeyProvider {
+    fn key(&self) -> Result<[u8; 32]>;
+}
+
+/// Any callback returning the key, e.g. one that unwraps a data key with a KMS
+impl<F: Fn() -> Result<[u8; 32]>> KeyProvider for F {
+    fn key(&self) -> Result<[u8; 32]> {
+        self()
+    }
+}
+
+/// Key read from an environment variable as 64 hex characters
+pub struct EnvKey {
+    var: String,
+}
+
+impl EnvKey {
+    pub fn new(var: &str) -> Self {
+        Self { var: var.to_string() }
+    }
+}
+
+impl Default for EnvKey {
+    fn default() -> Self {
+        Self::new(KEY_ENV_VAR)
+    }
+}
+
+impl KeyProvider for EnvKey {
+    fn key(&self) -> Result<[u8; 32]> {
+        let value = std::env::var(&self.var)
+            .with_context(|| format!("Encryption key not set: export {} (64 hex characters)", self.var))?;
+        parse_hex_key(&value).with_context(|| format!("Invalid encryption key in {}", self.var))
+    }
+}
+
+/// Parse a 256-bit key written as 64 hex characters (surrounding whitespace ignored)
+pub fn parse_hex_key(hex: &str) -> Result<[u8; 32]> {
+    let hex = hex.trim();
+    if hex.len() != 64 || !hex.is_ascii() {
+        bail!("expected 64 hex characters, got {}", hex.chars().count());
+    }
+    let mut key = [0u8; 32];
+    for (byte, pair) in key.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        let pair = std::str::from_utf8(pair)?;
+        *byte = u8::from_str_radix(pair, 16).map_err(|_| anyhow!("'{}' is not a hex byte", pair))?;
+    }
+    Ok(key)
+}
+
+/// Seals and opens files with one AES-256-GCM key
+#[derive(Clone)]
+pub struct Cipher {
+    aead: Aes256Gcm,
+}
+
+impl std::fmt::Debug for Cipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Cipher(AES-256-GCM)")
+    }
+}
+
+impl Cipher {
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self {
+            aead: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+        }
+    }
+
+    /// Fetch the key once from `provider`
+    pub fn from_provider(provider: &dyn KeyProvider) -> Result<Self> {
+        Ok(Self::new(&provider.key()?))
+    }
+
+    /// Whether `data` was produced by `seal` (with any key)
+    pub fn is_sealed(data: &[u8]) -> bool {
+        data.starts_with(MAGIC)
+    }
+
+    /// Encrypt `plaintext` under a fresh random nonce
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .aead
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| anyhow!("Encryption failed"))?;
+        let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(MAGIC);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    /// Decrypt data produced by `seal`; fails on a wrong key or any modification
+    pub fn open(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        let body = sealed
+            .strip_prefix(MAGIC)
+            .ok_or_else(|| anyhow!("Data is not encrypted"))?;
+        if body.len() < NONCE_LEN {
+            bail!("Encrypted data is truncated");
+        }
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        self.aead
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("Decryption failed: wrong key or corrupted data"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_data_opens_only_with_the_same_key() {
+        let cipher = Cipher::new(&[7u8; 32]);
+        let sealed = cipher.seal(b"confidential text").unwrap();
+        assert!(Cipher::is_sealed(&sealed));
+        assert!(!sealed.windows(12).any(|w| w == b"confidential"));
+        assert_eq!(cipher.open(&sealed).unwrap(), b"confidential text");
+        // Fresh nonce per seal
+        assert_ne!(cipher.seal(b"confidential text").unwrap(), sealed);
+
+        assert!(Cipher::new(&[8u8; 32]).open(&sealed).is_err());
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(cipher.open(&tampered).is_err());
+
+        let provider = || parse_hex_key(&"ab".repeat(32));
+        let sealed = Cipher::new(&[0xab; 32]).seal(b"x").unwrap();
+        assert_eq!(Cipher::from_provider(&provider).unwrap().open(&sealed).unwrap(), b"x");
+        assert!(parse_hex_key("abc").is_err());
+    }
+}
@@ -0,0 +1,227 @@
+//! Dry-run cost estimates
+//!
+//! Lets batch operators budget a run over a large corpus before committing
+//! to it. Nothing is processed: each input is triaged (format, size, and
+//! page count read from the PDF page tree without extraction; native
+//! formats are cheap enough to extract, which also counts their spans),
+//! and processing time and output size are predicted from the audit log of
+//! earlier runs.
+//!
+//! Predictions scale per page when the page count is known, and per input
+//! byte otherwise. Rates are medians over successful audit entries, taken
+//! from entries of the same file type when there are enough of them. With
+//! no usable history, rough built-in rates are used and `CostModel::history`
+//! is 0.
+
+use crate::audit::{AuditEntry, AuditStatus};
+use regex::bytes::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Same-type audit entries needed before they are used on their own
+const MIN_HISTORY: usize = 3;
+
+const DEFAULT_MS_PER_PAGE: f64 = 200.0;
+const DEFAULT_OUTPUT_BYTES_PER_PAGE: f64 = 8_000.0;
+const DEFAULT_MS_PER_INPUT_BYTE: f64 = 0.002;
+const DEFAULT_OUTPUT_BYTES_PER_INPUT_BYTE: f64 = 1.5;
+
+// Page objects ("/Type /Page", not "/Type /Pages") and page tree counts
+static PAGE_OBJECT_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/Type\s*/Page(?:[^s]|$)").unwrap());
+static PAGE_COUNT_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/Count\s+(\d+)").unwrap());
+
+/// What can be learned about an input without processing it
+#[derive(Debug, Clone)]
+pub struct Triage {
+    pub path: String,
+    /// Lowercased file extension, "" when there is none
+    pub extension: String,
+    pub input_bytes: u64,
+    pub pages: Option<u32>,
+    /// Text spans, when the input was cheap enough to extract
+    pub spans: Option<usize>,
+}
+
+impl Triage {
+    /// Size and, for PDFs, page count; spans are left for the caller to sample
+    pub fn new(path: &Path, bytes: &[u8]) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .unwrap_or_default();
+        let pages = if extension == "pdf" || bytes.starts_with(b"%PDF") {
+            pdf_page_count(bytes)
+        } else {
+            None
+        };
+        Self {
+            path: path.display().to_string(),
+            extension,
+            input_bytes: bytes.len() as u64,
+            pages,
+            spans: None,
+        }
+    }
+}
+
+/// Page count from the raw PDF, without extraction. Page objects inside
+/// compressed object streams are invisible, so the page tree's `/Count` is
+/// taken when it is larger
+pub fn pdf_page_count(bytes: &[u8]) -> Option<u32> {
+    let objects = PAGE_OBJECT_REGEX.find_iter(bytes).count() as u32;
+    let tree = PAGE_COUNT_REGEX
+        .captures_iter(bytes)
+        .filter_map(|captures| std::str::from_utf8(&captures[1]).ok()?.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0);
+    Some(objects.max(tree)).filter(|&pages| pages > 0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EstimateBasis {
+    Pages,
+    InputBytes,
+}
+
+/// Predicted cost of processing one input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub duration_ms: u64,
+    pub output_bytes: u64,
+    pub basis: EstimateBasis,
+}
+
+/// Processing rates fitted to earlier runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostModel {
+    pub ms_per_page: f64,
+    pub output_bytes_per_page: f64,
+    pub ms_per_input_byte: f64,
+    pub output_bytes_per_input_byte: f64,
+    /// Audit entries the rates were fitted to (0 = built-in rates)
+    pub history: usize,
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        Self {
+            ms_per_page: DEFAULT_MS_PER_PAGE,
+            output_bytes_per_page: DEFAULT_OUTPUT_BYTES_PER_PAGE,
+            ms_per_input_byte: DEFAULT_MS_PER_INPUT_BYTE,
+            output_bytes_per_input_byte: DEFAULT_OUTPUT_BYTES_PER_INPUT_BYTE,
+            history: 0,
+        }
+    }
+}
+
+impl CostModel {
+    /// Fit rates to successful audit entries, preferring entries whose input
+    /// has the given extension
+    pub fn from_history(entries: &[AuditEntry], extension: &str) -> Self {
+        let succeeded: Vec<&AuditEntry> = entries.iter().filter(|e| e.status == AuditStatus::Succeeded).collect();
+        let same_type: Vec<&AuditEntry> = succeeded
+            .iter()
+            .copied()
+            .filter(|e| {
+                Path::new(&e.input)
+                    .extension()
+                    .and_then(|x| x.to_str())
+                    .is_some_and(|x| x.eq_ignore_ascii_case(extension))
+            })
+            .collect();
+        let entries = if same_type.len() >= MIN_HISTORY { same_type } else { succeeded };
+
+        let defaults = Self::default();
+        let rate = |value: &dyn Fn(&AuditEntry) -> Option<f64>, default: f64| {
+            median(entries.iter().filter_map(|e| value(e)).collect()).unwrap_or(default)
+        };
+        let per_page = |e: &AuditEntry| e.page_count.map(f64::from);
+        let per_byte = |e: &AuditEntry| Some(e.input_bytes as f64).filter(|&bytes| bytes > 0.0);
+
+        Self {
+            ms_per_page: rate(&|e| Some(e.duration_ms as f64 / per_page(e)?), defaults.ms_per_page),
+            output_bytes_per_page: rate(
+                &|e| Some(e.output_bytes? as f64 / per_page(e)?),
+                defaults.output_bytes_per_page,
+            ),
+            ms_per_input_byte: rate(&|e| Some(e.duration_ms as f64 / per_byte(e)?), defaults.ms_per_input_byte),
+            output_bytes_per_input_byte: rate(
+                &|e| Some(e.output_bytes? as f64 / per_byte(e)?),
+                defaults.output_bytes_per_input_byte,
+            ),
+            history: entries.len(),
+        }
+    }
+
+    pub fn estimate(&self, triage: &Triage) -> Estimate {
+        match triage.pages {
+            Some(pages) => Estimate {
+                duration_ms: (pages as f64 * self.ms_per_page).round() as u64,
+                output_bytes: (pages as f64 * self.output_bytes_per_page).round() as u64,
+                basis: EstimateBasis::Pages,
+            },
+            None => Estimate {
+                duration_ms: (triage.input_bytes as f64 * self.ms_per_input_byte).round() as u64,
+                output_bytes: (triage.input_bytes as f64 * self.output_bytes_per_input_byte).round() as u64,
+                basis: EstimateBasis::InputBytes,
+            },
+        }
+    }
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    Some(if values.len() % 2 == 1 {
+        values[middle]
+    } else {
+        (values[middle - 1] + values[middle]) / 2.0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParsingConfig;
+
+    fn entry(input: &str, pages: u32, duration_ms: u64, output_bytes: usize) -> AuditEntry {
+        let mut entry = AuditEntry::begin("cli", input, &vec![0; pages as usize * 1000], &ParsingConfig::default(), "graph")
+            .unwrap()
+            .with_page_count(Some(pages))
+            .succeeded(&vec![b' '; output_bytes]);
+        entry.duration_ms = duration_ms;
+        entry
+    }
+
+    #[test]
+    fn predicts_cost_from_page_count_and_history() {
+        let pdf = b"%PDF-1.7\n1 0 obj << /Type /Pages /Kids [2 0 R 3 0 R] /Count 2 >>\n\
+                    2 0 obj << /Type /Page >>\n3 0 obj << /Type/Page >>\n";
+        let triage = Triage::new(Path::new("scan.pdf"), pdf);
+        assert_eq!(triage.pages, Some(2));
+        assert_eq!(pdf_page_count(b"%PDF-1.7 << /Type /Pages /Count 40 >>"), Some(40));
+        assert_eq!(pdf_page_count(b"not a pdf"), None);
+
+        let history = vec![
+            entry("a.pdf", 10, 1_000, 50_000),
+            entry("b.pdf", 20, 3_000, 100_000),
+            entry("c.pdf", 4, 400, 20_000),
+            entry("notes.md", 1, 10, 2_000),
+        ];
+        let model = CostModel::from_history(&history, "pdf");
+        assert_eq!(model.history, 3, "enough PDFs to leave the Markdown entry out");
+        assert_eq!(model.ms_per_page, 100.0);
+        assert_eq!(model.output_bytes_per_page, 5_000.0);
+        let estimate = model.estimate(&triage);
+        assert_eq!((estimate.duration_ms, estimate.output_bytes), (200, 10_000));
+        assert_eq!(estimate.basis, EstimateBasis::Pages);
+
+        let unpaged = Triage::new(Path::new("page.html"), &[0; 4_000]);
+        assert_eq!(CostModel::default().estimate(&unpaged).basis, EstimateBasis::InputBytes);
+        assert_eq!(CostModel::from_history(&[], "pdf"), CostModel::default());
+    }
+}
@@ -0,0 +1,295 @@
+//! Compact fixtures with scrubbed text
+//!
+//! Documents whose content can't be committed (licensed reports, customer
+//! contracts) can still give the pipeline regression coverage. A compact
+//! fixture keeps a document's TextElements — positions, fonts, pages,
+//! paragraph and line numbers — with every word replaced by a pseudonym of
+//! the same length, plus the graph shape those elements produced:
+//!
+//! - words become letters derived from a hash of the word and a salt that
+//!   is not stored, so repeated words (running headers, boilerplate) stay
+//!   repeated while the text can't be looked up; case is kept per letter
+//! - digits, punctuation, whitespace and bullets are kept, as are
+//!   single letters, short roman numerals and the structural keywords in
+//!   `STRUCTURAL_WORDS`, which numbering, caption and section rules key on
+//!
+//! The fixture also keeps the config the shape was recorded with, so it
+//! checks the rules' code rather than the default config of the day. Font
+//! classes are stored once and referenced by index, and the file is
+//! written without indentation, so a fixture is a fraction of the size of
+//! `stage1b_text_elements.json`. Write one with
+//! `blazegraph-io -i <document> --fixture <path>`.
+
+use crate::config::ParsingConfig;
+use crate::storage::NoOpStorage;
+use crate::types::*;
+use crate::{DocumentProcessor, TextElementsPreprocessor};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+pub const FIXTURE_VERSION: u32 = 1;
+
+/// Words kept as is (compared lowercased)
+pub const STRUCTURAL_WORDS: &[&str] = &[
+    "appendix", "article", "chapter", "clause", "contents", "exhibit", "fig", "figure", "of", "page", "part",
+    "schedule", "section", "table",
+];
+
+/// Replaces words with same-length pseudonyms, consistently per salt
+#[derive(Debug, Clone)]
+pub struct Scrubber {
+    salt: String,
+}
+
+impl Scrubber {
+    pub fn new(salt: impl Into<String>) -> Self {
+        Self { salt: salt.into() }
+    }
+
+    /// A scrubber with a fresh random salt, so pseudonyms can't be matched
+    /// against another fixture's
+    pub fn random() -> Self {
+        Self::new(uuid::Uuid::new_v4().to_string())
+    }
+
+    pub fn scrub(&self, text: &str) -> String {
+        let mut scrubbed = String::with_capacity(text.len());
+        let mut word = String::new();
+        for c in text.chars() {
+            if c.is_alphabetic() {
+                word.push(c);
+            } else {
+                self.push_word(&mut scrubbed, &mut word);
+                scrubbed.push(c);
+            }
+        }
+        self.push_word(&mut scrubbed, &mut word);
+        scrubbed
+    }
+
+    fn push_word(&self, scrubbed: &mut String, word: &mut String) {
+        if word.is_empty() {
+            return;
+        }
+        let lower = word.to_lowercase();
+        let length = word.chars().count();
+        let roman = length <= 4 && lower.chars().all(|c| "ivxlcdm".contains(c));
+        if length == 1 || roman || STRUCTURAL_WORDS.contains(&lower.as_str()) {
+            scrubbed.push_str(word);
+        } else {
+            let digest = Sha256::new()
+                .chain_update(self.salt.as_bytes())
+                .chain_update([0])
+                .chain_update(lower.as_bytes())
+                .finalize();
+            for (i, c) in word.chars().enumerate() {
+                let letter = (b'a' + (digest[i % digest.len()] as usize + i / digest.len()) as u8 % 26) as char;
+                scrubbed.push(if c.is_uppercase() { letter.to_ascii_uppercase() } else { letter });
+            }
+        }
+        word.clear();
+    }
+}
+
+/// A `PdfTextElement` with its font class stored by index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureElement {
+    pub text: String,
+    /// Index into `CompactFixture::font_classes`
+    pub class: usize,
+    /// x, y, width, height
+    pub bbox: [f32; 4],
+    pub page: u32,
+    pub paragraph: u32,
+    pub line: u32,
+    pub segment: u32,
+    pub reading_order: u32,
+    pub tokens: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bookmark_match: Option<BookmarkSection>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub paragraph_attributes: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "ScriptPosition::is_baseline")]
+    pub script: ScriptPosition,
+}
+
+/// The graph shape a fixture's elements are expected to produce
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixtureExpectations {
+    pub nodes: usize,
+    pub node_types: BTreeMap<String, usize>,
+    pub max_depth: u32,
+}
+
+impl FixtureExpectations {
+    pub fn of(graph: &DocumentGraph) -> Self {
+        let mut node_types = BTreeMap::new();
+        for node in graph.nodes.values() {
+            *node_types.entry(node.node_type.clone()).or_insert(0) += 1;
+        }
+        Self {
+            nodes: graph.nodes.len(),
+            node_types,
+            max_depth: graph.nodes.values().map(|n| n.location.semantic.depth).max().unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactFixture {
+    pub version: u32,
+    /// Whether the text was scrubbed (it always is when written by the CLI)
+    pub scrubbed: bool,
+    pub font_classes: Vec<FontClass>,
+    pub elements: Vec<FixtureElement>,
+    /// The config the expectations were recorded with
+    pub config: ParsingConfig,
+    /// Shape of the graph `process` built when the fixture was written
+    pub expected: FixtureExpectations,
+}
+
+impl CompactFixture {
+    /// A fixture of `elements`, scrubbed by `scrubber` when given. The
+    /// config is the default and the expectations are empty until
+    /// `record_expected`
+    pub fn from_text_elements(elements: &[PdfTextElement], scrubber: Option<&Scrubber>) -> Self {
+        let scrub = |text: &str| scrubber.map_or_else(|| text.to_string(), |s| s.scrub(text));
+        let mut font_classes: Vec<FontClass> = Vec::new();
+        let elements = elements
+            .iter()
+            .map(|element| {
+                let class = match font_classes.iter().position(|c| *c == element.style_info) {
+                    Some(class) => class,
+                    None => {
+                        font_classes.push(element.style_info.clone());
+                        font_classes.len() - 1
+                    }
+                };
+                let bbox = &element.bounding_box;
+                FixtureElement {
+                    text: scrub(&element.text),
+                    class,
+                    bbox: [bbox.x, bbox.y, bbox.width, bbox.height],
+                    page: element.page_number,
+                    paragraph: element.paragraph_number,
+                    line: element.line_number,
+                    segment: element.segment_number,
+                    reading_order: element.reading_order,
+                    tokens: element.token_count,
+                    bookmark_match: element.bookmark_match.clone().map(|mut bookmark| {
+                        bookmark.title = scrub(&bookmark.title);
+                        bookmark
+                    }),
+                    paragraph_attributes: element.paragraph_attributes.clone(),
+                    script: element.script,
+                }
+            })
+            .collect();
+        Self {
+            version: FIXTURE_VERSION,
+            scrubbed: scrubber.is_some(),
+            font_classes,
+            elements,
+            config: ParsingConfig::default(),
+            expected: FixtureExpectations::default(),
+        }
+    }
+
+    pub fn to_text_elements(&self) -> Result<Vec<PdfTextElement>> {
+        self.elements
+            .iter()
+            .enumerate()
+            .map(|(i, element)| {
+                let Some(style_info) = self.font_classes.get(element.class) else {
+                    bail!("Fixture element {} refers to missing font class {}", i, element.class);
+                };
+                let [x, y, width, height] = element.bbox;
+                Ok(PdfTextElement {
+                    text: element.text.clone(),
+                    style_info: style_info.clone(),
+                    bounding_box: BoundingBox { x, y, width, height },
+                    page_number: element.page,
+                    paragraph_number: element.paragraph,
+                    line_number: element.line,
+                    segment_number: element.segment,
+                    reading_order: element.reading_order,
+                    bookmark_match: element.bookmark_match.clone(),
+                    token_count: element.tokens,
+                    paragraph_attributes: element.paragraph_attributes.clone(),
+                    script: element.script,
+                })
+            })
+            .collect()
+    }
+
+    /// Run the fixture's elements through the pipeline with its config (as
+    /// a TextElements snapshot would be), without caching
+    pub fn process(&self) -> Result<DocumentGraph> {
+        let json = serde_json::to_vec(&self.to_text_elements()?)?;
+        let mut processor = DocumentProcessor::new_with_dependencies(
+            Box::new(TextElementsPreprocessor::new()),
+            Box::new(NoOpStorage::new()),
+        )?;
+        processor.process_bytes(&json, None, &self.config)
+    }
+
+    /// Keep `config` and set `expected` to what `process` produces with it
+    pub fn record_expected(&mut self, config: ParsingConfig) -> Result<()> {
+        self.config = config;
+        self.expected = FixtureExpectations::of(&self.process()?);
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).with_context(|| format!("Failed to read fixture {}", path.display()))?;
+        let fixture: Self =
+            serde_json::from_str(&json).with_context(|| format!("Invalid fixture {}", path.display()))?;
+        if fixture.version != FIXTURE_VERSION {
+            bail!(
+                "Fixture {} has version {}, expected {}",
+                path.display(),
+                fixture.version,
+                FIXTURE_VERSION
+            );
+        }
+        Ok(fixture)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write fixture {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::text_elements;
+
+    #[test]
+    fn scrubbing_keeps_shape_and_repetition() {
+        let scrubber = Scrubber::new("salt");
+        let scrubbed = scrubber.scrub("Figure 3: Revenue grew 12% in Q4 — revenue, not profit.");
+        assert_eq!(scrubbed.chars().count(), "Figure 3: Revenue grew 12% in Q4 — revenue, not profit.".chars().count());
+        assert!(scrubbed.starts_with("Figure 3: "));
+        assert!(!scrubbed.contains("Revenue") && !scrubbed.contains("profit"));
+        let words: Vec<&str> = scrubbed.split(' ').collect();
+        assert_eq!(words[2].to_lowercase(), words[8].trim_end_matches(',').to_lowercase());
+        assert!(words[2].starts_with(char::is_uppercase));
+        assert_eq!(scrubber.scrub("iv. a) Section 2"), "iv. a) Section 2");
+        assert_ne!(Scrubber::new("other").scrub("Revenue"), scrubber.scrub("Revenue"));
+
+        let elements = text_elements(&["Introduction", "Body text.", "More body text."]);
+        let fixture = CompactFixture::from_text_elements(&elements, Some(&scrubber));
+        assert_eq!(fixture.font_classes.len(), 1);
+        let restored = fixture.to_text_elements().unwrap();
+        assert_eq!(restored.len(), 3);
+        assert_eq!(restored[2].bounding_box.y, elements[2].bounding_box.y);
+        assert_eq!(restored[1].text, scrubber.scrub("Body text."));
+    }
+}
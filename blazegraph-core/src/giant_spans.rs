@@ -0,0 +1,199 @@
+//! Giant span splitting
+//!
+//! Some PDF producers emit a whole paragraph, or a whole page, as a single
+//! span on one line. The element becomes one multi-thousand-token node:
+//! spatial clustering has nothing to cluster and size limits have nothing
+//! to split between.
+//!
+//! Runs with span merging, before rule processing. A span is giant when it
+//! is longer than `max_chars`, or holds `overflow` times the text its box
+//! can show at its font size. It is split into pieces of about
+//! `target_chars`, cut after a sentence (or clause) where there is one in
+//! reach, else between words, else anywhere. Each piece becomes its own
+//! paragraph with a slice of the span's box, and a `GiantSpan` warning is
+//! raised for the page.
+
+use crate::config::GiantSpanConfig;
+use crate::preprocessors::pdf::xhtml_parser::estimate_token_count;
+use crate::types::*;
+use std::collections::HashMap;
+
+/// Average glyph width, as a fraction of the font size
+const GLYPH_WIDTH_EM: f32 = 0.5;
+/// Line height, as a multiple of the font size
+const LINE_HEIGHT_EM: f32 = 1.2;
+
+/// Split giant spans in place; returns how many spans were split
+pub fn split_giant_spans(
+    text_elements: &mut Vec<PdfTextElement>,
+    config: &GiantSpanConfig,
+    warnings: &mut Vec<ProcessingWarning>,
+) -> usize {
+    if !config.enabled || !text_elements.iter().any(|e| is_giant(e, config)) {
+        return 0;
+    }
+
+    // Pieces become paragraphs numbered after the page's last one
+    let mut next_paragraph: HashMap<u32, u32> = HashMap::new();
+    for element in text_elements.iter() {
+        let next = next_paragraph.entry(element.page_number).or_default();
+        *next = (*next).max(element.paragraph_number + 1);
+    }
+
+    let mut split = 0;
+    let elements = std::mem::take(text_elements);
+    for element in elements {
+        if !is_giant(&element, config) {
+            text_elements.push(element);
+            continue;
+        }
+        let pieces = pieces(&element.text, config.target_chars.max(1));
+        warnings.push(
+            ProcessingWarning::new(
+                WarningCode::GiantSpan,
+                format!(
+                    "A {}-character span was split into {} pieces",
+                    element.text.chars().count(),
+                    pieces.len()
+                ),
+            )
+            .on_page(element.page_number),
+        );
+        let total = element.text.len() as f32;
+        let next = next_paragraph.entry(element.page_number).or_default();
+        for (range, text) in pieces {
+            text_elements.push(PdfTextElement {
+                bounding_box: slice(&element.bounding_box, element.style_info.font_size, range.start as f32 / total, range.end as f32 / total),
+                paragraph_number: *next,
+                token_count: estimate_token_count(&text),
+                text,
+                ..element.clone()
+            });
+            *next += 1;
+        }
+        split += 1;
+    }
+
+    // Pieces share their span's reading order; give everything its own again
+    text_elements.sort_by_key(|e| (e.page_number, e.reading_order));
+    for (order, element) in text_elements.iter_mut().enumerate() {
+        element.reading_order = order as u32;
+    }
+    println!("✂️  Split {} giant span(s)", split);
+    split
+}
+
+fn is_giant(element: &PdfTextElement, config: &GiantSpanConfig) -> bool {
+    let chars = element.text.chars().count();
+    if chars > config.max_chars {
+        return true;
+    }
+    if chars <= config.target_chars {
+        return false;
+    }
+    let font_size = element.style_info.font_size.max(1.0);
+    let bbox = &element.bounding_box;
+    let per_line = (bbox.width / (font_size * GLYPH_WIDTH_EM)).max(1.0);
+    let lines = (bbox.height / (font_size * LINE_HEIGHT_EM)).round().max(1.0);
+    chars as f32 > per_line * lines * config.overflow
+}
+
+/// Byte ranges of `text` and their trimmed text, each about `target` characters
+fn pieces(text: &str, target: usize) -> Vec<(std::ops::Range<usize>, String)> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let rest = &text[start..];
+        let end = if rest.chars().count() <= target {
+            text.len()
+        } else {
+            start + cut(rest, target)
+        };
+        let piece = text[start..end].trim();
+        if !piece.is_empty() {
+            pieces.push((start..end, piece.to_string()));
+        }
+        start = end;
+    }
+    pieces
+}
+
+/// Byte offset in `text` to cut at, near `target` characters: after the last
+/// sentence or clause end in the second half of the window, else after the
+/// last space, else at `target` itself
+fn cut(text: &str, target: usize) -> usize {
+    let window_end = text.char_indices().nth(target).map_or(text.len(), |(i, _)| i);
+    let window = &text[..window_end];
+    let half = window.char_indices().nth(target / 2).map_or(0, |(i, _)| i);
+    let after = |pattern: &[char]| {
+        window
+            .char_indices()
+            .filter(|&(i, c)| i >= half && pattern.contains(&c))
+            .map(|(i, c)| i + c.len_utf8())
+            .rfind(|&end| text[end..].starts_with(char::is_whitespace))
+    };
+    after(&['.', '!', '?', '。', '！', '？'])
+        .or_else(|| after(&[';', ':', ',']))
+        .or_else(|| window.rfind(char::is_whitespace).filter(|&i| i > 0))
+        .unwrap_or(window_end)
+}
+
+/// The part of `bbox` holding text from `start_ratio` to `end_ratio` of the
+/// span: a horizontal slice of a one-line box, a vertical one of a taller box
+fn slice(bbox: &BoundingBox, font_size: f32, start_ratio: f32, end_ratio: f32) -> BoundingBox {
+    if bbox.height < font_size.max(1.0) * LINE_HEIGHT_EM * 2.0 {
+        BoundingBox {
+            x: bbox.x + bbox.width * start_ratio,
+            y: bbox.y,
+            width: bbox.width * (end_ratio - start_ratio),
+            height: bbox.height,
+        }
+    } else {
+        BoundingBox {
+            x: bbox.x,
+            y: bbox.y + bbox.height * start_ratio,
+            width: bbox.width,
+            height: bbox.height * (end_ratio - start_ratio),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{bbox, FontClassBuilder, TextElementBuilder};
+
+    #[test]
+    fn splits_a_page_emitted_as_one_span() {
+        let sentence = "The committee reviewed the quarterly results and approved the budget. ";
+        let page_text = sentence.repeat(60);
+        let style = FontClassBuilder::new("f1").size(10.0).build();
+        let mut elements = vec![
+            TextElementBuilder::new("Heading").style(style.clone()).bbox(bbox(72.0, 60.0, 100.0, 12.0)).reading_order(0).build(),
+            TextElementBuilder::new(&page_text).style(style.clone()).bbox(bbox(72.0, 80.0, 450.0, 12.0)).paragraph(1).reading_order(1).build(),
+            TextElementBuilder::new("Page 2").style(style).page(2).reading_order(2).build(),
+        ];
+        let mut warnings = Vec::new();
+        let config = GiantSpanConfig::default();
+        assert_eq!(split_giant_spans(&mut elements, &config, &mut warnings), 1);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, WarningCode::GiantSpan);
+
+        let pieces = &elements[1..elements.len() - 1];
+        assert!(pieces.len() >= 6);
+        for piece in pieces {
+            assert!(piece.text.ends_with("budget."), "{}", piece.text);
+            assert!(piece.text.chars().count() <= config.target_chars);
+        }
+        // Nothing lost, pieces left to right across the line, in reading order
+        let joined: Vec<&str> = pieces.iter().map(|p| p.text.as_str()).collect();
+        assert_eq!(joined.join(" "), page_text.trim());
+        assert!(pieces.windows(2).all(|w| w[0].bounding_box.x < w[1].bounding_box.x));
+        assert!(pieces.windows(2).all(|w| w[0].paragraph_number < w[1].paragraph_number));
+        assert!(elements.windows(2).all(|w| w[0].reading_order < w[1].reading_order));
+
+        // A long but plausible line is left alone
+        let mut normal = vec![TextElementBuilder::new(&sentence.repeat(10)).style(FontClassBuilder::new("f1").size(10.0).build()).bbox(bbox(72.0, 80.0, 450.0, 140.0)).build()];
+        assert_eq!(split_giant_spans(&mut normal, &config, &mut warnings), 0);
+    }
+}
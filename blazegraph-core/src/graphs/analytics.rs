@@ -1,5 +1,5 @@
 use crate::types::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 impl DocumentGraph {
     /// Compute structural profile analytics for the entire graph
@@ -31,6 +31,28 @@ impl GraphAnalytics {
         }
     }
     
+    /// Nearest-rank token count percentiles (e.g. `&[50.0, 90.0, 99.0]`) over
+    /// the given nodes, leaving out document roots (which hold no text)
+    pub fn token_percentiles(nodes: &[&DocumentNode], percentiles: &[f32]) -> Vec<usize> {
+        let mut tokens: Vec<usize> = nodes
+            .iter()
+            .filter(|n| n.parent.is_some())
+            .map(|n| n.token_count)
+            .collect();
+        tokens.sort_unstable();
+
+        percentiles
+            .iter()
+            .map(|&p| {
+                if tokens.is_empty() {
+                    return 0;
+                }
+                let rank = ((p.clamp(0.0, 100.0) / 100.0) * tokens.len() as f32).ceil() as usize;
+                tokens[rank.saturating_sub(1).min(tokens.len() - 1)]
+            })
+            .collect()
+    }
+
     /// Compute histogram-based token distribution with adaptive binning
     fn compute_token_distribution(nodes: &[&DocumentNode]) -> TokenDistribution {
         let mut overall_tokens = Vec::new();
@@ -45,7 +67,7 @@ impl GraphAnalytics {
         }
         
         let overall_histogram = Self::create_histogram(&overall_tokens);
-        let mut type_histograms = HashMap::new();
+        let mut type_histograms = BTreeMap::new();
         
         for (node_type, tokens) in by_type {
             type_histograms.insert(node_type, Self::create_histogram(&tokens));
@@ -97,7 +119,7 @@ impl GraphAnalytics {
         let mean = if total_count > 0 { total_tokens as f32 / total_count as f32 } else { 0.0 };
         let median = if sorted_tokens.is_empty() { 
             0.0 
-        } else if sorted_tokens.len() % 2 == 0 {
+        } else if sorted_tokens.len().is_multiple_of(2) {
             let mid = sorted_tokens.len() / 2;
             (sorted_tokens[mid - 1] + sorted_tokens[mid]) as f32 / 2.0
         } else {
@@ -151,14 +173,14 @@ impl GraphAnalytics {
     
     /// Compute node type distribution with counts and percentages
     fn compute_node_type_distribution(nodes: &[&DocumentNode]) -> NodeTypeDistribution {
-        let mut counts = HashMap::new();
+        let mut counts = BTreeMap::new();
         let total_nodes = nodes.len();
         
         for node in nodes {
             *counts.entry(node.node_type.clone()).or_insert(0) += 1;
         }
         
-        let mut percentages = HashMap::new();
+        let mut percentages = BTreeMap::new();
         for (node_type, count) in &counts {
             let percentage = if total_nodes > 0 { 
                 (*count as f32 / total_nodes as f32) * 100.0 
@@ -176,7 +198,7 @@ impl GraphAnalytics {
     
     /// Compute depth distribution and statistics
     fn compute_depth_distribution(nodes: &[&DocumentNode]) -> DepthDistribution {
-        let mut depth_counts = HashMap::new();
+        let mut depth_counts = BTreeMap::new();
         let mut total_depth = 0u32;
         let mut max_depth = 0u32;
         
@@ -200,4 +222,28 @@ impl GraphAnalytics {
         }
     }
     
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_percentiles_use_nearest_rank_without_the_root() {
+        let root = DocumentNode::new("Document", "Report".to_string());
+        let paragraphs: Vec<DocumentNode> = (1..=10)
+            .map(|tokens| {
+                let mut node = DocumentNode::new("Paragraph", String::new());
+                node.parent = Some(root.id);
+                node.token_count = tokens * 10;
+                node
+            })
+            .collect();
+        let mut nodes: Vec<&DocumentNode> = paragraphs.iter().rev().collect();
+        nodes.push(&root);
+
+        let percentiles = GraphAnalytics::token_percentiles(&nodes, &[0.0, 10.0, 50.0, 55.0, 90.0, 99.0, 100.0]);
+        assert_eq!(percentiles, [10, 10, 50, 60, 90, 100, 100]);
+        assert_eq!(GraphAnalytics::token_percentiles(&[&root], &[50.0]), [0]);
+    }
+}
@@ -0,0 +1,143 @@
+//! Node annotations
+//!
+//! Enrichment passes (NER, summaries, PII detection) attach their results to
+//! a node's `annotations`, keyed by namespace. The reserved namespaces have a
+//! fixed shape, checked whenever a graph is serialized, so consumers can rely
+//! on them across producers:
+//!
+//! - `entities`: array of `{ "text", "label", "start"?, "end"?, "confidence"? }`
+//! - `summary`: string
+//! - `pii`: array of `{ "kind", "text"?, "start"?, "end"?, "confidence"? }`
+//!
+//! `start`/`end` are character offsets into the node's text and
+//! `confidence` is a number in [0, 1]. Anything else goes under
+//! `custom.<name>` with any JSON value; other namespaces are rejected so new
+//! reserved ones can be added later without clashing with existing output.
+
+use crate::types::{DocumentGraph, DocumentNode};
+use anyhow::{bail, Result};
+use serde_json::{Map, Value};
+
+pub const ENTITIES_NAMESPACE: &str = "entities";
+pub const SUMMARY_NAMESPACE: &str = "summary";
+pub const PII_NAMESPACE: &str = "pii";
+/// Prefix of the free-form namespaces
+pub const CUSTOM_PREFIX: &str = "custom.";
+
+/// Check `value` against the schema of `namespace`
+pub fn validate_annotation(namespace: &str, value: &Value) -> Result<()> {
+    match namespace {
+        ENTITIES_NAMESPACE => validate_spans(namespace, value, "label", true),
+        PII_NAMESPACE => validate_spans(namespace, value, "kind", false),
+        SUMMARY_NAMESPACE => match value {
+            Value::String(_) => Ok(()),
+            _ => bail!("'{}' annotation must be a string", namespace),
+        },
+        _ => match namespace.strip_prefix(CUSTOM_PREFIX) {
+            Some(name) if !name.is_empty() => Ok(()),
+            _ => bail!(
+                "Unknown annotation namespace '{}' (reserved: {}, {}, {}; use '{}<name>' for others)",
+                namespace,
+                ENTITIES_NAMESPACE,
+                SUMMARY_NAMESPACE,
+                PII_NAMESPACE,
+                CUSTOM_PREFIX
+            ),
+        },
+    }
+}
+
+/// An array of objects with a string `kind_field` (and `text`, when
+/// required); `start`, `end` and `confidence` are optional but typed
+fn validate_spans(namespace: &str, value: &Value, kind_field: &str, text_required: bool) -> Result<()> {
+    let Value::Array(items) = value else {
+        bail!("'{}' annotation must be an array", namespace);
+    };
+    for (i, item) in items.iter().enumerate() {
+        let Value::Object(item) = item else {
+            bail!("'{}' annotation item {} must be an object", namespace, i);
+        };
+        let string = |field: &str, required: bool| match item.get(field) {
+            Some(Value::String(_)) => Ok(()),
+            None if !required => Ok(()),
+            _ => Err(anyhow::anyhow!("'{}' annotation item {} needs a string '{}'", namespace, i, field)),
+        };
+        string(kind_field, true)?;
+        string("text", text_required)?;
+        for field in ["start", "end"] {
+            if item.get(field).is_some_and(|n| n.as_u64().is_none()) {
+                bail!("'{}' annotation item {}: '{}' must be a character offset", namespace, i, field);
+            }
+        }
+        if let (Some(start), Some(end)) = (offset(item, "start"), offset(item, "end")) {
+            if start > end {
+                bail!("'{}' annotation item {}: start {} is after end {}", namespace, i, start, end);
+            }
+        }
+        if let Some(confidence) = item.get("confidence") {
+            if !confidence.as_f64().is_some_and(|c| (0.0..=1.0).contains(&c)) {
+                bail!("'{}' annotation item {}: confidence must be a number in [0, 1]", namespace, i);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn offset(item: &Map<String, Value>, field: &str) -> Option<u64> {
+    item.get(field).and_then(Value::as_u64)
+}
+
+impl DocumentNode {
+    /// Set the annotation for `namespace`, replacing any previous value
+    pub fn annotate(&mut self, namespace: impl Into<String>, value: Value) -> Result<()> {
+        let namespace = namespace.into();
+        validate_annotation(&namespace, &value)?;
+        self.annotations.insert(namespace, value);
+        Ok(())
+    }
+}
+
+impl DocumentGraph {
+    /// Check every node's annotations against their namespace schema
+    pub fn validate_annotations(&self) -> Result<()> {
+        for node in self.nodes.values() {
+            for (namespace, value) in &node.annotations {
+                validate_annotation(namespace, value)
+                    .map_err(|e| e.context(format!("Invalid annotations on node {}", node.id)))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reserved_namespaces_are_checked_custom_ones_are_free() {
+        let mut graph = DocumentGraph::new();
+        let mut node = DocumentNode::new("Paragraph", "Acme Corp pays John Smith.".to_string());
+        node.annotate("entities", json!([{ "text": "Acme Corp", "label": "ORG", "start": 0, "end": 9 }]))
+            .unwrap();
+        node.annotate("pii", json!([{ "kind": "person", "text": "John Smith", "confidence": 0.93 }]))
+            .unwrap();
+        node.annotate("summary", json!("Acme pays John.")).unwrap();
+        node.annotate("custom.risk", json!({ "score": 7 })).unwrap();
+
+        assert!(node.annotate("summary", json!(["not", "a", "string"])).is_err());
+        assert!(node.annotate("entities", json!([{ "text": "Acme" }])).is_err());
+        assert!(node.annotate("pii", json!([{ "kind": "email", "start": 9, "end": 2 }])).is_err());
+        assert!(node.annotate("pii", json!([{ "kind": "email", "confidence": 1.5 }])).is_err());
+        assert!(node.annotate("sentiment", json!("positive")).is_err());
+        assert!(node.annotate("custom.", json!(1)).is_err());
+        assert_eq!(node.annotations.len(), 4);
+
+        // Direct writes bypass `annotate`; serialization still checks them
+        node.annotations.insert("summary".to_string(), json!(42));
+        graph.nodes.insert(node.id, node);
+        assert!(graph.validate_annotations().is_err());
+        assert!(graph.to_json_with_format("graph").is_err());
+    }
+}
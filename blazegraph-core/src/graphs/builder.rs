@@ -25,6 +25,8 @@ impl GraphBuilder {
         // The root ID is created in DocumentGraph::new() via document_info
         let root_id = graph.document_info.root_id;
         node_stack.push(root_id);
+        // The latest non-section node, parent to any sentences that follow it
+        let mut last_content: Option<NodeId> = None;
 
         // Create the Document root node — same DocumentNode schema as every other node
         let document_node = DocumentNode {
@@ -37,15 +39,18 @@ impl GraphBuilder {
                     breadcrumbs: Vec::new(),
                 },
                 physical: None,
+                text_span: None,
             },
             text_order: None, // Document comes first (None sorts before Some)
-            content: NodeContent {
-                text: "Document".to_string(),
-            },
+            content: NodeContent::new("Document".to_string()),
             style_info: None,
             token_count: 0,
             parent: None,
             children: Vec::new(),
+            references: Vec::new(),
+            links: Vec::new(),
+            annotations: Default::default(),
+            provenance: None,
         };
         graph.nodes.insert(root_id, document_node);
 
@@ -64,8 +69,16 @@ impl GraphBuilder {
             let node = self.create_node_from_group(group, index as u32)?;
             let node_id = node.id;
 
-            // Determine parent based on hierarchy level
-            let parent_id = self.find_parent(&mut node_stack, group.hierarchy_level, root_id);
+            // Sentences hang off the paragraph they were split from; everything
+            // else finds its parent by hierarchy level
+            let is_sentence = group
+                .elements
+                .first()
+                .is_some_and(|e| e.element_type == ParsedElementType::Sentence);
+            let parent_id = match last_content {
+                Some(paragraph_id) if is_sentence => paragraph_id,
+                _ => self.find_parent(&mut node_stack, group.hierarchy_level, root_id),
+            };
 
             // Insert node and create relationships
             let mut final_node = node;
@@ -82,6 +95,10 @@ impl GraphBuilder {
                 parent.children.push(node_id);
             }
 
+            if !is_sentence {
+                last_content = (!matches!(group.group_type, GroupType::Section)).then_some(node_id);
+            }
+
             // Update hierarchy stack for sections
             if matches!(group.group_type, GroupType::Section) {
                 // Remove items at same or higher level
@@ -158,6 +175,15 @@ impl GraphBuilder {
                 crate::types::ParsedElementType::Section => GroupType::Section,
                 crate::types::ParsedElementType::List => GroupType::Paragraph, // Lists are content like paragraphs
                 crate::types::ParsedElementType::ListItem => GroupType::Paragraph, // ListItems are content like paragraphs
+                crate::types::ParsedElementType::KeyValue { .. }
+                | crate::types::ParsedElementType::Quote { .. }
+                | crate::types::ParsedElementType::Callout { .. }
+                | crate::types::ParsedElementType::Table(_)
+                | crate::types::ParsedElementType::Figure { .. }
+                | crate::types::ParsedElementType::Caption
+                | crate::types::ParsedElementType::Header
+                | crate::types::ParsedElementType::Footer
+                | crate::types::ParsedElementType::Sentence => GroupType::Paragraph,
                 crate::types::ParsedElementType::Paragraph => GroupType::Paragraph,
             };
 
@@ -179,6 +205,15 @@ impl GraphBuilder {
                 crate::types::ParsedElementType::Section => "Section",
                 crate::types::ParsedElementType::List => "List",
                 crate::types::ParsedElementType::ListItem => "ListItem",
+                crate::types::ParsedElementType::KeyValue { .. } => "KeyValue",
+                crate::types::ParsedElementType::Quote { .. } => "Quote",
+                crate::types::ParsedElementType::Callout { .. } => "Callout",
+                crate::types::ParsedElementType::Table(_) => "Table",
+                crate::types::ParsedElementType::Figure { .. } => "Figure",
+                crate::types::ParsedElementType::Caption => "Caption",
+                crate::types::ParsedElementType::Header => "Header",
+                crate::types::ParsedElementType::Footer => "Footer",
+                crate::types::ParsedElementType::Sentence => "Sentence",
                 crate::types::ParsedElementType::Paragraph => "Paragraph",
             };
 
@@ -186,6 +221,7 @@ impl GraphBuilder {
             let physical = Some(PhysicalLocation {
                 page: first_element.page_number,
                 bounding_box: first_element.bounding_box.clone(),
+                page_label: None,
             });
 
             (node_type, physical)
@@ -201,6 +237,29 @@ impl GraphBuilder {
         node.location.physical = physical;
         node.text_order = Some(order);
         node.token_count = group.elements.iter().map(|e| e.token_count).sum();
+        node.provenance = group.elements.first().and_then(|e| e.provenance.clone());
+        node.content.style_runs = group.elements.first().map(|e| e.style_runs.clone()).unwrap_or_default();
+        match group.elements.first().map(|e| &e.element_type) {
+            Some(ParsedElementType::KeyValue { key, value }) => {
+                node.content.key = Some(key.clone());
+                node.content.value = Some(value.clone());
+            }
+            Some(ParsedElementType::Quote { attribution }) => {
+                node.content.attribution = attribution.clone();
+            }
+            Some(ParsedElementType::Callout { label }) => {
+                node.content.label = label.clone();
+            }
+            Some(ParsedElementType::Table(table_data)) => {
+                let mut table_data = table_data.clone();
+                node.content.caption = table_data.caption.take();
+                node.content.table_data = Some(table_data);
+            }
+            Some(ParsedElementType::Figure { caption }) => {
+                node.content.caption = caption.clone();
+            }
+            _ => {}
+        }
 
         // Style info from the most prominent element
         if let Some(first_element) = group.elements.first() {
@@ -0,0 +1,179 @@
+//! Citation anchors for RAG answers
+//!
+//! A citation id names one node of one document in a form compact enough to
+//! travel with a retrieved chunk: the first `CITATION_HASH_LEN` hex digits of
+//! the graph's content hash, a colon, and the node's semantic path
+//! (`3f9c2a1b:2.3.1`). Resolving an id against the graph gives back what an
+//! answer needs to show its source: breadcrumbs, page, bounding box and a
+//! short label such as "§2.3, p.14".
+//!
+//! Ids stay valid as long as the document's content and structure do, since
+//! both halves are derived from them rather than from node UUIDs.
+
+use crate::types::*;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// Hex digits of the content hash kept in a citation id
+pub const CITATION_HASH_LEN: usize = 8;
+
+/// A citation id resolved against its graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Citation {
+    pub id: String,
+    pub node_id: NodeId,
+    pub node_type: String,
+    /// The node's semantic path
+    pub path: String,
+    /// Path of the nearest enclosing Section (the node itself for a Section)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section_path: Option<String>,
+    pub breadcrumbs: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    /// The number printed on that page, when known ("xiv")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bounding_box: Option<BoundingBox>,
+    /// Short source reference for display: "§2.3, p.14", using the printed
+    /// page label when the document has them
+    pub label: String,
+}
+
+impl DocumentGraph {
+    /// The document half of citation ids: a prefix of the content hash
+    pub fn citation_prefix(&self) -> String {
+        let hash = match &self.document_info.content_hash {
+            Some(hash) => hash.clone(),
+            None => self.content_hash(),
+        };
+        hash.chars().take(CITATION_HASH_LEN).collect()
+    }
+
+    /// Citation id of a node; the root's id is the bare document prefix
+    pub fn citation_id(&self, node: &DocumentNode) -> String {
+        citation_id(&self.citation_prefix(), node)
+    }
+
+    /// Resolve a citation id back to its node's location
+    pub fn resolve_citation(&self, id: &str) -> Result<Citation> {
+        let (prefix, path) = id.trim().split_once(':').unwrap_or((id.trim(), ""));
+        let own_prefix = self.citation_prefix();
+        if !prefix.eq_ignore_ascii_case(&own_prefix) {
+            bail!(
+                "Citation '{}' belongs to document {}, not this graph ({})",
+                id,
+                prefix,
+                own_prefix
+            );
+        }
+        let node = if path.is_empty() {
+            self.nodes.get(&self.document_info.root_id)
+        } else {
+            self.nodes.values().find(|node| node.location.semantic.path == path)
+        };
+        match node {
+            Some(node) => Ok(self.citation_for(&own_prefix, node)),
+            None => bail!("Citation '{}' names no node: path {} is not in this graph", id, path),
+        }
+    }
+
+    fn citation_for(&self, prefix: &str, node: &DocumentNode) -> Citation {
+        let mut section = Some(node);
+        while let Some(candidate) = section.filter(|n| n.node_type != "Section") {
+            section = candidate.parent.and_then(|parent| self.nodes.get(&parent));
+        }
+        let section_path = section.map(|s| s.location.semantic.path.clone());
+        let physical = node.location.physical.as_ref();
+
+        let mut label = Vec::new();
+        if let Some(path) = &section_path {
+            label.push(format!("§{}", path));
+        }
+        if let Some(physical) = physical {
+            match &physical.page_label {
+                Some(page_label) => label.push(format!("p.{}", page_label)),
+                None => label.push(format!("p.{}", physical.page)),
+            }
+        }
+        if label.is_empty() && !node.location.semantic.path.is_empty() {
+            label.push(format!("¶{}", node.location.semantic.path));
+        }
+
+        Citation {
+            id: citation_id(prefix, node),
+            node_id: node.id,
+            node_type: node.node_type.clone(),
+            path: node.location.semantic.path.clone(),
+            section_path,
+            breadcrumbs: node.location.semantic.breadcrumbs.clone(),
+            page: physical.map(|p| p.page),
+            page_label: physical.and_then(|p| p.page_label.clone()),
+            bounding_box: physical.map(|p| p.bounding_box.clone()),
+            label: label.join(", "),
+        }
+    }
+}
+
+pub(crate) fn citation_id(prefix: &str, node: &DocumentNode) -> String {
+    if node.location.semantic.path.is_empty() {
+        prefix.to_string()
+    } else {
+        format!("{}:{}", prefix, node.location.semantic.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn citation_ids_resolve_to_section_and_page() {
+        let mut graph = DocumentGraph::new();
+        let mut root = DocumentNode::new("Document", "Document".to_string());
+        root.id = graph.document_info.root_id;
+        root.location.semantic.path = String::new();
+        let mut section = DocumentNode::new_with_physical("Section", "Methods".to_string(), Some(13), None);
+        section.location.semantic.path = "2".to_string();
+        section.location.semantic.breadcrumbs = vec!["Methods".to_string()];
+        section.parent = Some(root.id);
+        let mut paragraph = DocumentNode::new_with_physical("Paragraph", "We sampled.".to_string(), Some(14), None);
+        paragraph.location.semantic.path = "2.1".to_string();
+        paragraph.location.semantic.breadcrumbs = vec!["Methods".to_string()];
+        paragraph.parent = Some(section.id);
+        section.children.push(paragraph.id);
+        root.children.push(section.id);
+        let paragraph_id = paragraph.id;
+        for node in [root, section, paragraph] {
+            graph.nodes.insert(node.id, node);
+        }
+        graph.document_info.content_hash = Some(graph.content_hash());
+
+        let id = graph.citation_id(&graph.nodes[&paragraph_id]);
+        let prefix = graph.citation_prefix();
+        assert_eq!(id, format!("{}:2.1", prefix));
+
+        let citation = graph.resolve_citation(&id).unwrap();
+        assert_eq!(citation.node_id, paragraph_id);
+        assert_eq!(citation.section_path.as_deref(), Some("2"));
+        assert_eq!(citation.page, Some(14));
+        assert_eq!(citation.label, "§2, p.14");
+        assert_eq!(graph.resolve_citation(&prefix).unwrap().node_type, "Document");
+
+        // Printed labels win over physical page numbers
+        graph.document_info.page_labels = vec![PageLabel {
+            page: 14,
+            label: "xiv".to_string(),
+            style: PageLabelStyle::Roman,
+            inferred: false,
+        }];
+        graph.apply_page_labels();
+        let citation = graph.resolve_citation(&id).unwrap();
+        assert_eq!((citation.page, citation.page_label.as_deref()), (Some(14), Some("xiv")));
+        assert_eq!(citation.label, "§2, p.xiv");
+
+        assert!(graph.resolve_citation(&format!("{}:9.9", prefix)).is_err());
+        assert!(graph.resolve_citation("00000000:2.1").is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//! Glossary of defined terms
+//!
+//! Contracts, standards and regulations define their vocabulary once and
+//! capitalize it everywhere after. `compute_glossary` collects those
+//! definitions into `DocumentInfo::glossary`: each term with its
+//! definition, the node defining it and the nodes using it.
+//!
+//! A definition is one of:
+//!
+//! - a quoted, capitalized term followed by "means", "shall mean", "refers
+//!   to", "has the meaning" or "includes" (`"Effective Date" means ...`);
+//! - a quoted, capitalized term in parentheses after what it names
+//!   (`Acme Ltd (the "Supplier")`), defined by its sentence;
+//! - an entry of a Definitions, Defined Terms, Glossary or Interpretation
+//!   section: a `KeyValue` node, or a paragraph starting with a capitalized
+//!   term and a colon, dash or "means".
+//!
+//! The first definition of a term wins. Uses are case-sensitive whole-word
+//! mentions of the term, or of its plural, in any other node.
+
+use crate::config::GlossaryConfig;
+use crate::types::*;
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+/// A capitalized term of a few words; "of", "and", ... may join its words
+const TERM: &str = r"[A-Z][\w'’&-]*(?:\s+(?:[A-Z0-9][\w'’&-]*|of|and|or|the|for|in|to|on|under))*";
+
+// `"Term" means ...`: the term; its definition runs to the end of the sentence
+static MEANS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r#"[“"]({TERM})[”"]\s*(?:,\s*)?(?:shall\s+mean|means|shall\s+have\s+the\s+meaning|has\s+the\s+meaning|refers\s+to|shall\s+include|includes)\b\s*:?\s*"#
+    ))
+    .unwrap()
+});
+// `(the "Term")` and `(hereinafter "Term")`: the term named by its sentence
+static PARENTHETICAL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r#"\((?:(?:each|together|collectively|individually),?\s+)?(?:hereinafter(?:\s+referred\s+to\s+as)?\s+|referred\s+to\s+as\s+)?(?:the\s+|a\s+|an\s+)?[“"]({TERM})[”"]\)"#
+    ))
+    .unwrap()
+});
+// A Definitions section entry: `Term: ...`, `Term – ...`, `"Term" ...`, `Term means ...`
+static ENTRY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r#"^\s*(?:\(?[a-z0-9]{{1,3}}[.)]\s+)?[“"]?({TERM})[”"]?\s*(?::|–|—|-|\bmeans\b|[”"])\s*(\S.*)$"#
+    ))
+    .unwrap()
+});
+// Headings of sections listing definitions
+static DEFINITIONS_HEADING_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:(?:article|section|clause)\s+)?(?:[\d.]+|[ivxlc]+\.)?\s*(?:definitions?|defined\s+terms|glossary|interpretation)\b").unwrap()
+});
+
+/// A term and where it was first defined
+struct Definition {
+    term: String,
+    definition: String,
+    node: NodeId,
+}
+
+impl DocumentGraph {
+    /// Set `document_info.glossary` from the definitions found in the graph,
+    /// in order of definition
+    pub fn compute_glossary(&mut self, config: &GlossaryConfig) {
+        self.document_info.glossary.clear();
+        if !config.enabled {
+            return;
+        }
+
+        let nodes = self.preorder();
+        let mut definitions: Vec<Definition> = Vec::new();
+        let mut seen = HashSet::new();
+        let mut add = |term: &str, definition: &str, node: NodeId| {
+            let term = term.trim();
+            let words = term.split_whitespace().count();
+            if words == 0 || words > config.max_term_words || !seen.insert(term.to_string()) {
+                return;
+            }
+            definitions.push(Definition {
+                term: term.to_string(),
+                definition: definition.trim().to_string(),
+                node,
+            });
+        };
+
+        // Depth of the Definitions section being walked, if any
+        let mut definitions_section: Option<u32> = None;
+        for &(node, depth) in &nodes {
+            if definitions_section.is_some_and(|section_depth| depth <= section_depth) {
+                definitions_section = None;
+            }
+            let text = node.content.text.trim();
+            if node.node_type == "Section" {
+                if definitions_section.is_none() && DEFINITIONS_HEADING_REGEX.is_match(text) {
+                    definitions_section = Some(depth);
+                }
+                continue;
+            }
+
+            for captures in MEANS_REGEX.captures_iter(text) {
+                let start = captures.get(0).unwrap().end();
+                add(&captures[1], &text[start..sentence_end(text, start)], node.id);
+            }
+            for captures in PARENTHETICAL_REGEX.captures_iter(text) {
+                let at = captures.get(0).unwrap().start();
+                add(&captures[1], &text[sentence_start(text, at)..sentence_end(text, at)], node.id);
+            }
+            if definitions_section.is_some() {
+                if let (Some(key), Some(value)) = (&node.content.key, &node.content.value) {
+                    add(key.trim_matches(|c| matches!(c, '"' | '“' | '”')), value, node.id);
+                } else if let Some(captures) = ENTRY_REGEX.captures(text) {
+                    add(&captures[1], &captures[2], node.id);
+                }
+            }
+        }
+
+        let glossary = definitions
+            .into_iter()
+            .map(|definition| {
+                let mention = Regex::new(&format!(r"\b{}(?:s|es)?\b", regex::escape(&definition.term))).unwrap();
+                let used_in = nodes
+                    .iter()
+                    .filter(|(node, _)| node.id != definition.node && mention.is_match(&node.content.text))
+                    .map(|(node, _)| node.id)
+                    .collect();
+                GlossaryEntry {
+                    term: definition.term,
+                    definition: definition.definition,
+                    defined_in: definition.node,
+                    used_in,
+                }
+            })
+            .collect();
+        self.document_info.glossary = glossary;
+    }
+}
+
+fn is_sentence_end(text: &str, i: usize, c: char) -> bool {
+    matches!(c, '.' | ';' | '!' | '?') && text[i + c.len_utf8()..].chars().next().is_none_or(char::is_whitespace)
+}
+
+/// Byte offset where the sentence containing `at` starts
+fn sentence_start(text: &str, at: usize) -> usize {
+    text[..at]
+        .char_indices()
+        .rfind(|&(i, c)| is_sentence_end(text, i, c))
+        .map_or(0, |(i, c)| i + c.len_utf8())
+}
+
+/// Byte offset just past the end of the sentence containing `at`
+fn sentence_end(text: &str, at: usize) -> usize {
+    text[at..]
+        .char_indices()
+        .find(|&(i, c)| is_sentence_end(text, at + i, c))
+        .map_or(text.len(), |(i, c)| at + i + c.len_utf8())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(graph: &mut DocumentGraph, parent: NodeId, mut node: DocumentNode) -> NodeId {
+        node.parent = Some(parent);
+        graph.nodes.get_mut(&parent).unwrap().children.push(node.id);
+        let id = node.id;
+        graph.nodes.insert(id, node);
+        id
+    }
+
+    fn text(graph: &mut DocumentGraph, parent: NodeId, node_type: &str, text: &str) -> NodeId {
+        add(graph, parent, DocumentNode::new(node_type, text.to_string()))
+    }
+
+    #[test]
+    fn collects_defined_terms_and_their_uses() {
+        let mut graph = DocumentGraph::new();
+        let mut document = DocumentNode::new("Document", "Document".to_string());
+        document.id = graph.document_info.root_id;
+        let root = document.id;
+        graph.nodes.insert(root, document);
+        let parties = text(
+            &mut graph,
+            root,
+            "Paragraph",
+            r#"This agreement is made between Acme Ltd (the "Supplier") and Beta GmbH (the "Customer")."#,
+        );
+        let definitions = text(&mut graph, root, "Section", "1. Definitions");
+        let effective = text(&mut graph, definitions, "Paragraph", r#""Effective Date" means the date of the last signature. "Fees" means the amounts in Schedule 2."#);
+        let mut services = DocumentNode::new("KeyValue", "Services: the services described in Schedule 1".to_string());
+        services.content.key = Some("Services".to_string());
+        services.content.value = Some("the services described in Schedule 1".to_string());
+        let services_id = add(&mut graph, definitions, services);
+        let terms = text(&mut graph, root, "Section", "2. Term");
+        let body = text(
+            &mut graph,
+            terms,
+            "Paragraph",
+            "From the Effective Date the Supplier shall provide the Services to the Customer for the Fees. Payment: within 30 days.",
+        );
+
+        graph.compute_glossary(&GlossaryConfig::default());
+        assert!(graph.document_info.glossary.is_empty());
+
+        graph.compute_glossary(&GlossaryConfig { enabled: true, ..GlossaryConfig::default() });
+        let glossary = &graph.document_info.glossary;
+        let terms: Vec<&str> = glossary.iter().map(|entry| entry.term.as_str()).collect();
+        assert_eq!(terms, ["Supplier", "Customer", "Effective Date", "Fees", "Services"]);
+        assert_eq!(glossary[0].definition, r#"This agreement is made between Acme Ltd (the "Supplier") and Beta GmbH (the "Customer")."#);
+        assert_eq!(glossary[0].defined_in, parties);
+        assert_eq!(glossary[2].definition, "the date of the last signature.");
+        assert_eq!(glossary[2].defined_in, effective);
+        assert_eq!(glossary[3].definition, "the amounts in Schedule 2.");
+        assert_eq!(glossary[4].defined_in, services_id);
+        for entry in glossary {
+            assert_eq!(entry.used_in, [body], "{}", entry.term);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Grafting supplementary documents into a parent graph
+//!
+//! An addendum, schedule or exhibit is often processed on its own but read
+//! as part of the main document. `DocumentGraph::graft` attaches another
+//! graph's tree under a node of this one (e.g. the contract's "Appendices"
+//! section), optionally under a new heading section, and keeps the graph
+//! consistent:
+//!
+//! - every grafted node gets a fresh id, so grafting the same supplement
+//!   twice, or a supplement sharing ids with the parent, can't collide;
+//!   parent, children and references follow the new ids;
+//! - depths are shifted below the anchor, keeping the supplement's own
+//!   level structure;
+//! - semantic paths and `text_order` are renumbered over the whole graph in
+//!   document order;
+//! - breadcrumbs, text spans, the structural profile and the content hash
+//!   are recomputed.
+//!
+//! Physical locations are kept as they were: they refer to the supplement's
+//! own pages. The graft is recorded in `DocumentInfo::grafts`.
+
+use crate::preprocessors::pdf::xhtml_parser::estimate_token_count;
+use crate::types::*;
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// How `graft` attaches a supplement
+#[derive(Debug, Clone, Default)]
+pub struct GraftOptions {
+    /// Put the supplement under a new section with this heading (e.g.
+    /// "Addendum A") instead of directly under the anchor
+    pub heading: Option<String>,
+}
+
+impl DocumentGraph {
+    /// Attach `supplement`'s tree under `anchor` (a node of this graph),
+    /// after the anchor's existing children
+    pub fn graft(&mut self, anchor: NodeId, supplement: &DocumentGraph, options: &GraftOptions) -> Result<GraftRecord> {
+        let Some(anchor_depth) = self.nodes.get(&anchor).map(|node| node.location.semantic.depth) else {
+            bail!("Node {} is not in this graph", anchor);
+        };
+        let supplement_root = supplement.document_info.root_id;
+        let Some(supplement_top) = supplement.nodes.get(&supplement_root).map(|root| &root.children) else {
+            bail!("The supplement has no root node {}", supplement_root);
+        };
+
+        // The node the supplement's top-level nodes go under, and its depth
+        let (parent, base_depth) = match &options.heading {
+            Some(heading) => {
+                let mut section = DocumentNode::new("Section", heading.clone());
+                section.parent = Some(anchor);
+                section.location.semantic.depth = anchor_depth + 1;
+                section.token_count = estimate_token_count(heading);
+                let id = section.id;
+                self.nodes.insert(id, section);
+                if let Some(anchor) = self.nodes.get_mut(&anchor) {
+                    anchor.children.push(id);
+                }
+                (id, anchor_depth + 1)
+            }
+            None => (anchor, anchor_depth),
+        };
+
+        let mut ids: HashMap<NodeId, NodeId> = supplement
+            .nodes
+            .keys()
+            .filter(|&&id| id != supplement_root)
+            .map(|&id| (id, Uuid::new_v4()))
+            .collect();
+        ids.insert(supplement_root, parent);
+        for node in supplement.nodes.values().filter(|node| node.id != supplement_root) {
+            let mut node = node.clone();
+            node.id = ids[&node.id];
+            node.parent = node.parent.and_then(|id| ids.get(&id).copied());
+            node.children = node.children.iter().filter_map(|id| ids.get(id).copied()).collect();
+            node.references = node.references.iter().filter_map(|id| ids.get(id).copied()).collect();
+            node.location.semantic.depth += base_depth;
+            self.nodes.insert(node.id, node);
+        }
+        let top_level: Vec<NodeId> = supplement_top.iter().filter_map(|id| ids.get(id).copied()).collect();
+        if let Some(parent) = self.nodes.get_mut(&parent) {
+            parent.children.extend(&top_level);
+        }
+
+        self.renumber();
+        self.compute_breadcrumbs();
+        self.compute_text_spans();
+        self.compute_structural_profile();
+        self.structural_profile.total_nodes = self.nodes.len();
+        self.document_info.content_hash = Some(self.content_hash());
+
+        let record = GraftRecord {
+            title: supplement.document_info.document_metadata.title.clone(),
+            content_hash: supplement.document_info.content_hash.clone(),
+            anchor,
+            nodes: if options.heading.is_some() { vec![parent] } else { top_level },
+            node_count: ids.len() - 1 + usize::from(options.heading.is_some()),
+        };
+        self.document_info.grafts.push(record.clone());
+        Ok(record)
+    }
+
+    /// Set semantic paths and `text_order` from each node's place in the tree
+    fn renumber(&mut self) {
+        let root_id = self.document_info.root_id;
+        let mut order = 0;
+        let mut stack = vec![(root_id, String::new())];
+        while let Some((id, path)) = stack.pop() {
+            let Some(node) = self.nodes.get_mut(&id) else {
+                continue;
+            };
+            if id != root_id {
+                node.text_order = Some(order);
+                order += 1;
+            }
+            for (index, &child) in node.children.iter().enumerate().rev() {
+                let child_path = if path.is_empty() {
+                    (index + 1).to_string()
+                } else {
+                    format!("{}.{}", path, index + 1)
+                };
+                stack.push((child, child_path));
+            }
+            node.location.semantic.path = path;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tree_invariants;
+
+    fn add(graph: &mut DocumentGraph, parent: NodeId, node_type: &str, text: &str) -> NodeId {
+        let mut node = DocumentNode::new(node_type, text.to_string());
+        node.parent = Some(parent);
+        node.location.semantic.depth = graph.nodes[&parent].location.semantic.depth + 1;
+        let id = node.id;
+        graph.nodes.get_mut(&parent).unwrap().children.push(id);
+        graph.nodes.insert(id, node);
+        id
+    }
+
+    fn graph(title: &str) -> (DocumentGraph, NodeId) {
+        let mut graph = DocumentGraph::new();
+        let root = graph.document_info.root_id;
+        let mut document = DocumentNode::new("Document", title.to_string());
+        document.id = root;
+        graph.nodes.insert(root, document);
+        graph.document_info.document_metadata.title = Some(title.to_string());
+        (graph, root)
+    }
+
+    #[test]
+    fn grafts_supplement_under_a_section() {
+        let (mut contract, root) = graph("Services Agreement");
+        let fees = add(&mut contract, root, "Section", "4 Fees");
+        add(&mut contract, fees, "Paragraph", "Fees are invoiced monthly.");
+        let appendices = add(&mut contract, root, "Section", "Appendices");
+
+        let (mut addendum, addendum_root) = graph("Addendum A");
+        let rates = add(&mut addendum, addendum_root, "Section", "Rates");
+        let hourly = add(&mut addendum, rates, "Paragraph", "Hourly rates are listed below.");
+        let table = add(&mut addendum, rates, "Table", "Role | Rate");
+        addendum.nodes.get_mut(&hourly).unwrap().references.push(table);
+
+        let options = GraftOptions {
+            heading: Some("Addendum A".to_string()),
+        };
+        let record = contract.graft(appendices, &addendum, &options).unwrap();
+        assert_tree_invariants(&contract);
+        assert_eq!(record.node_count, 4);
+        assert_eq!(contract.nodes.len(), 8);
+        assert_eq!(contract.structural_profile.total_nodes, 8);
+        assert_eq!(contract.document_info.grafts.len(), 1);
+
+        let heading = &contract.nodes[&record.nodes[0]];
+        assert_eq!(heading.location.semantic.path, "2.1");
+        assert_eq!(heading.location.semantic.depth, 2);
+        let grafted_rates = &contract.nodes[&heading.children[0]];
+        assert_ne!(grafted_rates.id, rates);
+        assert_eq!(grafted_rates.location.semantic.path, "2.1.1");
+        assert_eq!(grafted_rates.location.semantic.depth, 3);
+        assert_eq!(grafted_rates.text_order, Some(4));
+
+        // References follow the new ids
+        let grafted_hourly = &contract.nodes[&grafted_rates.children[0]];
+        assert_eq!(grafted_hourly.references, [grafted_rates.children[1]]);
+        assert_eq!(
+            grafted_hourly.location.semantic.breadcrumbs,
+            ["Services Agreement", "Appendices", "Addendum A", "Rates"]
+        );
+
+        // Grafting the same supplement again can't collide
+        contract.graft(root, &addendum, &GraftOptions::default()).unwrap();
+        assert_tree_invariants(&contract);
+        assert_eq!(contract.nodes.len(), 11);
+        assert!(contract.nodes.values().any(|n| n.location.semantic.path == "3.1"));
+    }
+}
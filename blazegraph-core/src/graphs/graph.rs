@@ -1,7 +1,12 @@
 use crate::types::*;
+use crate::tokenizer::Tokenizer;
 use anyhow::Result;
 use std::collections::HashMap;
 use super::analytics::GraphAnalytics;
+use sha2::{Digest, Sha256};
+
+/// Placed between node texts in `DocumentGraph::document_text`
+pub const DOCUMENT_TEXT_SEPARATOR: &str = "\n\n";
 
 impl Default for DocumentGraph {
     fn default() -> Self {
@@ -9,6 +14,17 @@ impl Default for DocumentGraph {
     }
 }
 
+impl From<SortedDocumentGraph> for DocumentGraph {
+    /// Rebuild the id-indexed graph from its serialized (sorted) form
+    fn from(sorted: SortedDocumentGraph) -> Self {
+        Self {
+            nodes: sorted.nodes.into_iter().map(|n| (n.id, n)).collect(),
+            document_info: sorted.document_info,
+            structural_profile: sorted.structural_profile,
+        }
+    }
+}
+
 impl DocumentGraph {
     pub fn new() -> Self {
         use uuid::Uuid;
@@ -19,14 +35,25 @@ impl DocumentGraph {
             root_id: Uuid::new_v4(),
             document_metadata: DocumentMetadata::default(),
             document_analysis: DocumentAnalysis {
-                font_size_counts: std::collections::HashMap::new(),
-                font_family_counts: std::collections::HashMap::new(),
+                font_size_counts: std::collections::BTreeMap::new(),
+                font_family_counts: std::collections::BTreeMap::new(),
                 bold_counts: (0, 0),
                 italic_counts: (0, 0),
                 most_common_font_size: 12.0,
                 most_common_font_family: "unknown".to_string(),
                 all_font_sizes: Vec::new(),
             },
+            page_range: None,
+            skipped_pages: Vec::new(),
+            page_reordering: None,
+            page_labels: Vec::new(),
+            revision: None,
+            grafts: Vec::new(),
+            glossary: Vec::new(),
+            warnings: Vec::new(),
+            extraction: None,
+            page_extraction: Vec::new(),
+            content_hash: None,
         };
 
         Self {
@@ -36,33 +63,169 @@ impl DocumentGraph {
         }
     }
 
+    /// Record the source's flow type; free-flow graphs drop physical locations
+    pub fn apply_flow_type(&mut self, flow_type: FlowType) {
+        if matches!(flow_type, FlowType::Free) {
+            for node in self.nodes.values_mut() {
+                node.location.physical = None;
+            }
+        }
+        self.structural_profile.flow_type = flow_type;
+    }
+
+    /// Canonical hash of the document's content and structure, for deduplication
+    ///
+    /// Walks the tree from the root in child order, hashing each node's depth,
+    /// type and content (text plus structured fields such as table cells).
+    /// Ids, physical locations, style, token counts and document metadata are
+    /// left out, so the same content from differently named files hashes alike.
+    pub fn content_hash(&self) -> String {
+        self.subtree_hash(self.document_info.root_id)
+    }
+
+    /// `content_hash` of the subtree below (and including) one node
+    pub fn subtree_hash(&self, id: NodeId) -> String {
+        let mut hasher = Sha256::new();
+        for (node, depth) in self.subtree_preorder(id) {
+            let record = serde_json::json!([depth, node.node_type, node.content]);
+            hasher.update(record.to_string().as_bytes());
+            hasher.update(b"\n");
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Nodes in document order (depth-first from the root, children in
+    /// order), each with its depth below the root
+    pub fn preorder(&self) -> Vec<(&DocumentNode, u32)> {
+        self.subtree_preorder(self.document_info.root_id)
+    }
+
+    /// `preorder` of the subtree below (and including) one node, with depths
+    /// relative to it
+    pub fn subtree_preorder(&self, id: NodeId) -> Vec<(&DocumentNode, u32)> {
+        let mut nodes = Vec::new();
+        let mut stack = vec![(id, 0u32)];
+        while let Some((id, depth)) = stack.pop() {
+            let Some(node) = self.nodes.get(&id) else {
+                continue;
+            };
+            nodes.push((node, depth));
+            stack.extend(node.children.iter().rev().map(|child| (*child, depth + 1)));
+        }
+        nodes
+    }
+
+    /// Relations between nodes as the table outputs list them: `contains`
+    /// (parent to child, with the child's 1-based position) in document
+    /// order, then `references`
+    #[cfg(any(feature = "parquet", feature = "sqlite"))]
+    pub(crate) fn edges(&self) -> Vec<(NodeId, NodeId, &'static str, Option<u32>)> {
+        let nodes = self.preorder();
+        let contains = nodes.iter().flat_map(|(node, _)| {
+            node.children.iter().enumerate().map(|(position, child)| (node.id, *child, "contains", Some(position as u32 + 1)))
+        });
+        let references = nodes.iter().flat_map(|(node, _)| node.references.iter().map(|target| (node.id, *target, "references", None)));
+        contains.chain(references).collect()
+    }
+
+    /// Physical page number → page number in the graph, for documents whose
+    /// pages were put back in order (`document_info.page_reordering`); pages
+    /// not listed keep their number
+    pub fn repaired_page_numbers(&self) -> HashMap<u32, u32> {
+        match &self.document_info.page_reordering {
+            Some(reordering) if reordering.applied => {
+                let mut numbers = reordering.suggested_order.clone();
+                numbers.sort_unstable();
+                reordering.suggested_order.iter().copied().zip(numbers).collect()
+            }
+            _ => HashMap::new(),
+        }
+    }
+
+    /// The document as one string: every node's text in document order,
+    /// separated by `DOCUMENT_TEXT_SEPARATOR`. `compute_text_spans` places
+    /// each node in this string, so highlighting and citation alignment can
+    /// map text back to nodes without re-tokenizing. Sentence nodes repeat
+    /// their paragraph's text, so they aren't added again: their spans fall
+    /// inside the paragraph's
+    pub fn document_text(&self) -> String {
+        self.text_layout().0
+    }
+
+    /// Set `location.text_span` on every node with text
+    pub fn compute_text_spans(&mut self) {
+        let (_, spans) = self.text_layout();
+        for node in self.nodes.values_mut() {
+            node.location.text_span = None;
+        }
+        for (id, span) in spans {
+            if let Some(node) = self.nodes.get_mut(&id) {
+                node.location.text_span = Some(span);
+            }
+        }
+    }
+
+    fn text_layout(&self) -> (String, Vec<(NodeId, TextSpan)>) {
+        let mut text = String::new();
+        let mut length = 0;
+        let mut spans = Vec::new();
+        // The last node written: its id, where it starts, and how far into
+        // its text (in bytes) its sentences have been found
+        let mut written: Option<(NodeId, usize, usize)> = None;
+        for (node, _) in self.preorder() {
+            if node.id == self.document_info.root_id || node.content.text.is_empty() {
+                continue;
+            }
+            if node.node_type == "Sentence" {
+                let Some((parent_id, parent_start, cursor)) = written.as_mut() else {
+                    continue;
+                };
+                let Some(parent) = self.nodes.get(parent_id).filter(|_| node.parent == Some(*parent_id)) else {
+                    continue;
+                };
+                if let Some(offset) = parent.content.text[*cursor..].find(node.content.text.as_str()) {
+                    let at = *cursor + offset;
+                    let start = *parent_start + parent.content.text[..at].chars().count();
+                    spans.push((node.id, TextSpan { start, end: start + node.content.text.chars().count() }));
+                    *cursor = at + node.content.text.len();
+                }
+                continue;
+            }
+            if !text.is_empty() {
+                text.push_str(DOCUMENT_TEXT_SEPARATOR);
+                length += DOCUMENT_TEXT_SEPARATOR.chars().count();
+            }
+            let start = length;
+            text.push_str(&node.content.text);
+            length += node.content.text.chars().count();
+            spans.push((node.id, TextSpan { start, end: length }));
+            written = Some((node.id, start, 0));
+        }
+        (text, spans)
+    }
+
+    /// Count every node's tokens with `tokenizer`, replacing the estimates
+    /// made while the rules merged and split elements
+    pub fn count_tokens(&mut self, tokenizer: &dyn Tokenizer) {
+        let root_id = self.document_info.root_id;
+        for node in self.nodes.values_mut().filter(|node| node.id != root_id) {
+            node.token_count = tokenizer.count(&node.content.text);
+        }
+    }
+
     pub fn max_depth(&self) -> u32 {
         self.nodes.values().map(|n| n.location.semantic.depth).max().unwrap_or(0)
     }
 
+    /// Write the graph format to `path`, streaming (see `write_with_format`)
     pub fn save_to_json(&self, path: &str) -> Result<()> {
-        let sorted_graph = self.to_sorted_graph();
-        let json = serde_json::to_string_pretty(&sorted_graph)?;
-        std::fs::write(path, json)?;
-        Ok(())
+        self.save_with_format(path, "graph")
     }
 
     pub fn to_sorted_graph(&self) -> SortedDocumentGraph {
-        // Collect all nodes and sort by text_order, with root node first
-        let mut nodes: Vec<&DocumentNode> = self.nodes.values().collect();
-        nodes.sort_by(|a, b| {
-            // Document root (with text_order = None) should come first
-            match (a.text_order, b.text_order) {
-                (None, None) => std::cmp::Ordering::Equal,
-                (None, Some(_)) => std::cmp::Ordering::Less,
-                (Some(_), None) => std::cmp::Ordering::Greater,
-                (Some(a_order), Some(b_order)) => a_order.cmp(&b_order),
-            }
-        });
-
         SortedDocumentGraph {
             schema_version: SCHEMA_VERSION.to_string(),
-            nodes: nodes.into_iter().cloned().collect(),
+            nodes: self.sorted_nodes().into_iter().cloned().collect(),
             document_info: self.document_info.clone(),
             structural_profile: self.structural_profile.clone(),
         }
@@ -161,4 +324,51 @@ impl DocumentGraph {
             }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(texts: &[&str]) -> DocumentGraph {
+        let mut graph = DocumentGraph::new();
+        let mut root = DocumentNode::new("Document", "Document".to_string());
+        root.id = graph.document_info.root_id;
+        for text in texts {
+            let mut node = DocumentNode::new("Paragraph", text.to_string());
+            node.parent = Some(root.id);
+            root.children.push(node.id);
+            graph.nodes.insert(node.id, node);
+        }
+        graph.nodes.insert(root.id, root);
+        graph
+    }
+
+    #[test]
+    fn content_hash_ignores_ids_and_metadata_but_not_order() {
+        let a = graph(&["First paragraph.", "Second paragraph."]);
+        let mut b = graph(&["First paragraph.", "Second paragraph."]);
+        b.document_info.document_metadata.title = Some("renamed.pdf".to_string());
+        assert_eq!(a.content_hash(), b.content_hash());
+
+        assert_ne!(a.content_hash(), graph(&["Second paragraph.", "First paragraph."]).content_hash());
+        assert_ne!(a.content_hash(), graph(&["First paragraph.", "Second paragraph!"]).content_hash());
+    }
+
+    #[test]
+    fn text_spans_index_into_the_document_text() {
+        let mut graph = graph(&["Première partie.", "", "Second part."]);
+        graph.compute_text_spans();
+        let text = graph.document_text();
+        assert_eq!(text, "Première partie.\n\nSecond part.");
+
+        let chars: Vec<char> = text.chars().collect();
+        for node in graph.nodes.values() {
+            match node.location.text_span {
+                Some(TextSpan { start, end }) => {
+                    assert_eq!(chars[start..end].iter().collect::<String>(), node.content.text)
+                }
+                None => assert!(node.id == graph.document_info.root_id || node.content.text.is_empty()),
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Hyperlinks on nodes
+//!
+//! Each extracted link (`PreprocessorOutput::links`, see `crate::links`)
+//! goes to the node holding its anchor: of the nodes on the link's page
+//! whose region meets the anchor's, the smallest whose text contains the
+//! anchor text, or the smallest of them all when none does. Attaching runs
+//! before free-flow graphs drop their physical locations.
+
+use crate::links::overlap_area;
+use crate::types::*;
+
+impl DocumentGraph {
+    /// Add each link to the `links` of the node holding its anchor
+    pub fn attach_links(&mut self, links: &[PageLink]) {
+        if links.is_empty() {
+            return;
+        }
+        // Links keep physical page numbers; nodes carry repaired ones
+        let new_numbers = self.repaired_page_numbers();
+
+        let candidates: Vec<(NodeId, u32, BoundingBox, String)> = self
+            .preorder()
+            .into_iter()
+            .filter(|(node, _)| node.id != self.document_info.root_id)
+            .filter_map(|(node, _)| {
+                let physical = node.location.physical.as_ref()?;
+                Some((node.id, physical.page, physical.bounding_box.clone(), normalize(&node.content.text)))
+            })
+            .collect();
+
+        let mut attached = 0;
+        for link in links {
+            let page = new_numbers.get(&link.page_number).copied().unwrap_or(link.page_number);
+            let anchor = normalize(&link.text);
+            let meeting: Vec<&(NodeId, u32, BoundingBox, String)> = candidates
+                .iter()
+                .filter(|(_, node_page, bbox, _)| *node_page == page && overlap_area(bbox, &link.bounding_box) > 0.0)
+                .collect();
+            let area = |bbox: &BoundingBox| bbox.width * bbox.height;
+            // Later (deeper) nodes win ties with their ancestors
+            let smallest = |nodes: &mut dyn Iterator<Item = &&(NodeId, u32, BoundingBox, String)>| {
+                nodes
+                    .fold(None, |best: Option<&(NodeId, u32, BoundingBox, String)>, node| match best {
+                        Some(best) if area(&best.2) < area(&node.2) => Some(best),
+                        _ => Some(*node),
+                    })
+                    .map(|(id, ..)| *id)
+            };
+            let holder = smallest(&mut meeting.iter().filter(|(.., text)| !anchor.is_empty() && text.contains(&anchor)))
+                .or_else(|| smallest(&mut meeting.iter()));
+            let Some(node) = holder.and_then(|id| self.nodes.get_mut(&id)) else {
+                continue;
+            };
+            let hyperlink = Hyperlink {
+                href: link.href.clone(),
+                text: link.text.clone(),
+            };
+            if !node.links.contains(&hyperlink) {
+                node.links.push(hyperlink);
+                attached += 1;
+            }
+        }
+        if attached > 0 {
+            println!("🔗 Attached {} link(s) to nodes", attached);
+        }
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attaches_links_to_the_smallest_node_holding_the_anchor() {
+        let mut graph = DocumentGraph::new();
+        let mut document = DocumentNode::new("Document", "Document".to_string());
+        document.id = graph.document_info.root_id;
+        let node = |node_type: &str, text: &str, y: f32, width: f32, height: f32| {
+            let bbox = BoundingBox { x: 72.0, y, width, height };
+            DocumentNode::new_with_physical(node_type, text.to_string(), Some(1), Some(bbox))
+        };
+        let text = "Data from the open registry and the annual census.";
+        let mut section = node("Section", "Sources", 72.0, 200.0, 14.0);
+        let mut paragraph = node("Paragraph", text, 90.0, 450.0, 30.0);
+        let mut sentence = node("Sentence", text, 90.0, 450.0, 30.0);
+        let (section_id, paragraph_id, sentence_id) = (section.id, paragraph.id, sentence.id);
+        (section.parent, paragraph.parent, sentence.parent) = (Some(document.id), Some(section_id), Some(paragraph_id));
+        document.children.push(section_id);
+        section.children.push(paragraph_id);
+        paragraph.children.push(sentence_id);
+        for node in [document, section, paragraph, sentence] {
+            graph.nodes.insert(node.id, node);
+        }
+
+        let link = |href: &str, text: &str, page: u32, x: f32| PageLink {
+            page_number: page,
+            bounding_box: BoundingBox { x, y: 92.0, width: 60.0, height: 10.0 },
+            href: href.to_string(),
+            text: text.to_string(),
+        };
+        graph.attach_links(&[
+            link("https://registry.example/", "open registry", 1, 140.0),
+            link("https://registry.example/", "open registry", 1, 140.0),
+            link("https://census.example/", "annual census", 1, 300.0),
+            link("https://elsewhere.example/", "open registry", 2, 140.0),
+        ]);
+
+        let links = |id: NodeId| graph.nodes[&id].links.iter().map(|l| l.href.as_str()).collect::<Vec<_>>();
+        assert_eq!(links(sentence_id), ["https://registry.example/", "https://census.example/"]);
+        assert!(links(paragraph_id).is_empty() && links(section_id).is_empty());
+        assert_eq!(graph.nodes[&sentence_id].links[0].text, "open registry");
+    }
+}
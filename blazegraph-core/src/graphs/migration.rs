@@ -0,0 +1,127 @@
+//! Schema migrations for saved graphs
+//!
+//! Loading reads `schema_version` first, upgrades the raw JSON one step at a
+//! time through `MIGRATIONS`, and only then deserializes into the (strict)
+//! current types. Register a migration whenever `SCHEMA_VERSION` is bumped
+//! for a shape change; patch releases only fix values and load as-is.
+
+use crate::types::SCHEMA_VERSION;
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+
+/// One upgrade step between adjacent schema versions
+pub struct Migration {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub apply: fn(&mut Value) -> Result<()>,
+}
+
+/// Registered upgrades, oldest first. 0.2.0 is the first versioned schema,
+/// so there is nothing to upgrade from yet.
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// Upgrade a graph to the current schema; returns the version it was written with
+pub fn migrate_to_current(graph: &mut Value) -> Result<String> {
+    migrate(graph, MIGRATIONS, SCHEMA_VERSION)
+}
+
+/// Upgrade `graph` in place to `target` using `migrations`; returns the original version
+pub fn migrate(graph: &mut Value, migrations: &[Migration], target: &str) -> Result<String> {
+    let original = graph
+        .get("schema_version")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("Missing schema_version (expected graph output format)"))?
+        .to_string();
+    let target_version = parse_version(target)?;
+
+    let mut current = original.clone();
+    loop {
+        let version = parse_version(&current)?;
+        // Same major.minor: patch releases don't change the shape
+        if version.0 == target_version.0 && version.1 == target_version.1 {
+            break;
+        }
+        if version > target_version {
+            bail!(
+                "Graph was written with schema {}, newer than this build supports ({})",
+                original,
+                target
+            );
+        }
+        let step = migrations
+            .iter()
+            .find(|m| m.from == current)
+            .ok_or_else(|| anyhow!("No migration from schema {} towards {}", current, target))?;
+        (step.apply)(graph)?;
+        current = step.to.to_string();
+    }
+
+    graph["schema_version"] = Value::String(target.to_string());
+    Ok(original)
+}
+
+fn parse_version(version: &str) -> Result<(u32, u32, u32)> {
+    let parts: Vec<u32> = version
+        .split('.')
+        .map(|part| part.parse::<u32>())
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|_| anyhow!("Invalid schema_version: {:?}", version))?;
+    match parts.as_slice() {
+        [major, minor, patch] => Ok((*major, *minor, *patch)),
+        _ => bail!("Invalid schema_version: {:?}", version),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rename_page_number(graph: &mut Value) -> Result<()> {
+        for node in graph["nodes"].as_array_mut().into_iter().flatten() {
+            if let Some(page) = node.as_object_mut().and_then(|n| n.remove("page_number")) {
+                node["page"] = page;
+            }
+        }
+        Ok(())
+    }
+
+    fn add_document_info(graph: &mut Value) -> Result<()> {
+        graph["document_info"] = json!({});
+        Ok(())
+    }
+
+    const TEST_MIGRATIONS: &[Migration] = &[
+        Migration { from: "0.0.1", to: "0.1.0", apply: rename_page_number },
+        Migration { from: "0.1.0", to: "0.2.0", apply: add_document_info },
+    ];
+
+    #[test]
+    fn applies_migrations_in_sequence() {
+        let mut graph = json!({"schema_version": "0.0.1", "nodes": [{"page_number": 3}]});
+        let original = migrate(&mut graph, TEST_MIGRATIONS, "0.2.0").unwrap();
+
+        assert_eq!(original, "0.0.1");
+        assert_eq!(
+            graph,
+            json!({"schema_version": "0.2.0", "nodes": [{"page": 3}], "document_info": {}})
+        );
+    }
+
+    #[test]
+    fn accepts_patch_versions_and_rejects_unreadable_ones() {
+        let mut patch = json!({"schema_version": "0.2.1"});
+        assert!(migrate(&mut patch, TEST_MIGRATIONS, "0.2.0").is_ok());
+        assert_eq!(patch["schema_version"], "0.2.0");
+
+        let mut newer = json!({"schema_version": "0.3.0"});
+        let err = migrate(&mut newer, TEST_MIGRATIONS, "0.2.0").unwrap_err();
+        assert!(err.to_string().contains("newer than this build"));
+
+        let mut unknown = json!({"schema_version": "0.0.5"});
+        let err = migrate(&mut unknown, TEST_MIGRATIONS, "0.2.0").unwrap_err();
+        assert!(err.to_string().contains("No migration from schema 0.0.5"));
+
+        assert!(migrate(&mut json!({"nodes": []}), TEST_MIGRATIONS, "0.2.0").is_err());
+    }
+}
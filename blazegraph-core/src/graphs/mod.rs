@@ -1,7 +1,29 @@
 
 pub mod analytics;
+pub mod annotations;
 pub mod serialization;
 pub mod builder;
+pub mod citation;
+pub mod glossary;
+pub mod graft;
 pub mod graph;
+pub mod links;
+pub mod migration;
+pub mod parquet;
+pub mod prompting;
+pub mod rdf;
+pub mod references;
+pub mod report;
+pub mod revision;
+pub mod search;
+pub mod similarity;
+pub mod sqlite;
 // Re-export for easy access
 pub use analytics::GraphAnalytics;
+pub use citation::{Citation, CITATION_HASH_LEN};
+pub use graft::GraftOptions;
+pub use graph::DOCUMENT_TEXT_SEPARATOR;
+pub use prompting::{PackOptions, PackedContext};
+pub use report::PageBackground;
+pub use search::{SearchHit, SearchOptions};
+pub use similarity::{similarity, similarity_with, Similarity, SimilarityOptions};
@@ -0,0 +1,216 @@
+//! Parquet output
+//!
+//! The "parquet" output format: a directory with two columnar tables, for
+//! analytics over corpora too large for a JSON file per document. Every row
+//! carries its document's `document_id` (the content hash), so the tables
+//! of many documents query as one in DuckDB or Spark
+//! (`read_parquet('out/*/nodes.parquet')`).
+//!
+//! - `nodes.parquet`: one row per node — ids, type, text, tree position,
+//!   page and bounding box, token count and breadcrumbs;
+//! - `edges.parquet`: one row per relation between nodes — `contains`
+//!   (parent to child, with the child's position) and `references`
+//!   (cross-references to sections).
+//!
+//! Writing needs the `parquet` feature.
+
+use crate::types::*;
+use anyhow::Result;
+use std::path::Path;
+
+/// File names of the tables inside the output directory
+pub const NODES_FILE: &str = "nodes.parquet";
+pub const EDGES_FILE: &str = "edges.parquet";
+
+#[cfg(feature = "parquet")]
+mod tables {
+    use super::super::citation::citation_id;
+    use crate::types::*;
+    use anyhow::Result;
+    use arrow_array::builder::{ListBuilder, StringBuilder};
+    use arrow_array::{ArrayRef, Float32Array, RecordBatch, StringArray, UInt32Array, UInt64Array};
+    use arrow_schema::{DataType, Field, Schema};
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+    use std::io::Write;
+    use std::sync::Arc;
+
+    fn write_batch<W: Write + Send>(writer: W, schema: Schema, columns: Vec<ArrayRef>) -> Result<()> {
+        let batch = RecordBatch::try_new(Arc::new(schema), columns)?;
+        let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+        let mut writer = ArrowWriter::try_new(writer, batch.schema(), Some(properties))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+
+    fn text_column(values: Vec<Option<String>>) -> ArrayRef {
+        Arc::new(StringArray::from(values))
+    }
+
+    impl DocumentGraph {
+        fn document_id(&self) -> String {
+            self.document_info.content_hash.clone().unwrap_or_else(|| self.content_hash())
+        }
+
+        /// Write the nodes table, in document order
+        pub fn write_nodes_parquet<W: Write + Send>(&self, writer: W) -> Result<()> {
+            let document_id = self.document_id();
+            let citation_prefix = self.citation_prefix();
+            let nodes: Vec<&DocumentNode> = self.preorder().into_iter().map(|(node, _)| node).collect();
+            let physical = |node: &&DocumentNode| node.location.physical.clone();
+            let bbox = |value: fn(&BoundingBox) -> f32| -> ArrayRef {
+                Arc::new(Float32Array::from(
+                    nodes.iter().map(|node| physical(node).map(|physical| value(&physical.bounding_box))).collect::<Vec<_>>(),
+                ))
+            };
+            let mut breadcrumbs = ListBuilder::new(StringBuilder::new());
+            for node in &nodes {
+                for crumb in &node.location.semantic.breadcrumbs {
+                    breadcrumbs.values().append_value(crumb);
+                }
+                breadcrumbs.append(true);
+            }
+
+            let text = |value: fn(&DocumentNode) -> Option<String>| text_column(nodes.iter().map(|node| value(node)).collect());
+            let columns: Vec<ArrayRef> = vec![
+                text_column(vec![Some(document_id); nodes.len()]),
+                text(|node| Some(node.id.to_string())),
+                text_column(nodes.iter().map(|node| Some(citation_id(&citation_prefix, node))).collect()),
+                text(|node| Some(node.node_type.clone())),
+                text(|node| Some(node.content.text.clone())),
+                text(|node| node.parent.map(|parent| parent.to_string())),
+                text(|node| Some(node.location.semantic.path.clone())),
+                Arc::new(UInt32Array::from(nodes.iter().map(|node| node.location.semantic.depth).collect::<Vec<_>>())),
+                Arc::new(UInt64Array::from(nodes.iter().map(|node| node.text_order.map(|order| order as u64)).collect::<Vec<_>>())),
+                Arc::new(UInt32Array::from(nodes.iter().map(|node| physical(node).map(|physical| physical.page)).collect::<Vec<_>>())),
+                text(|node| node.location.physical.as_ref().and_then(|physical| physical.page_label.clone())),
+                bbox(|bbox| bbox.x),
+                bbox(|bbox| bbox.y),
+                bbox(|bbox| bbox.width),
+                bbox(|bbox| bbox.height),
+                Arc::new(UInt64Array::from(nodes.iter().map(|node| node.token_count as u64).collect::<Vec<_>>())),
+                Arc::new(breadcrumbs.finish()),
+            ];
+            let schema = Schema::new(vec![
+                Field::new("document_id", DataType::Utf8, false),
+                Field::new("node_id", DataType::Utf8, false),
+                Field::new("citation", DataType::Utf8, false),
+                Field::new("node_type", DataType::Utf8, false),
+                Field::new("text", DataType::Utf8, false),
+                Field::new("parent_id", DataType::Utf8, true),
+                Field::new("path", DataType::Utf8, false),
+                Field::new("depth", DataType::UInt32, false),
+                Field::new("text_order", DataType::UInt64, true),
+                Field::new("page", DataType::UInt32, true),
+                Field::new("page_label", DataType::Utf8, true),
+                Field::new("bbox_x", DataType::Float32, true),
+                Field::new("bbox_y", DataType::Float32, true),
+                Field::new("bbox_width", DataType::Float32, true),
+                Field::new("bbox_height", DataType::Float32, true),
+                Field::new("token_count", DataType::UInt64, false),
+                Field::new("breadcrumbs", DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))), false),
+            ]);
+            write_batch(writer, schema, columns)
+        }
+
+        /// Write the edges table: `contains` edges in document order, then
+        /// `references` edges
+        pub fn write_edges_parquet<W: Write + Send>(&self, writer: W) -> Result<()> {
+            let document_id = self.document_id();
+            let edges = self.edges();
+
+            let columns: Vec<ArrayRef> = vec![
+                text_column(vec![Some(document_id); edges.len()]),
+                text_column(edges.iter().map(|edge| Some(edge.0.to_string())).collect()),
+                text_column(edges.iter().map(|edge| Some(edge.1.to_string())).collect()),
+                text_column(edges.iter().map(|edge| Some(edge.2.to_string())).collect()),
+                Arc::new(UInt32Array::from(edges.iter().map(|edge| edge.3).collect::<Vec<_>>())),
+            ];
+            let schema = Schema::new(vec![
+                Field::new("document_id", DataType::Utf8, false),
+                Field::new("source_id", DataType::Utf8, false),
+                Field::new("target_id", DataType::Utf8, false),
+                Field::new("kind", DataType::Utf8, false),
+                Field::new("position", DataType::UInt32, true),
+            ]);
+            write_batch(writer, schema, columns)
+        }
+    }
+}
+
+impl DocumentGraph {
+    /// Write `nodes.parquet` and `edges.parquet` into `dir`, creating it
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet(&self, dir: impl AsRef<Path>) -> Result<()> {
+        use anyhow::Context;
+        use std::fs::File;
+
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let create = |name: &str| {
+            let path = dir.join(name);
+            File::create(&path).with_context(|| format!("Failed to create {}", path.display()))
+        };
+        self.write_nodes_parquet(create(NODES_FILE)?)?;
+        self.write_edges_parquet(create(EDGES_FILE)?)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "parquet"))]
+    pub fn write_parquet(&self, _dir: impl AsRef<Path>) -> Result<()> {
+        anyhow::bail!("Parquet output needs the `parquet` feature")
+    }
+}
+
+#[cfg(all(test, feature = "parquet"))]
+mod tests {
+    use super::*;
+    use arrow_array::{Array, StringArray, UInt32Array};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    #[test]
+    fn writes_node_and_edge_tables() {
+        let mut graph = DocumentGraph::new();
+        let mut document = DocumentNode::new("Document", "Document".to_string());
+        document.id = graph.document_info.root_id;
+        let bbox = BoundingBox { x: 72.0, y: 90.0, width: 400.0, height: 30.0 };
+        let mut section = DocumentNode::new_with_physical("Section", "1. Scope".to_string(), Some(1), Some(bbox.clone()));
+        let mut paragraph = DocumentNode::new_with_physical("Paragraph", "As in Section 1.".to_string(), Some(1), Some(bbox));
+        section.parent = Some(document.id);
+        paragraph.parent = Some(section.id);
+        paragraph.references.push(section.id);
+        paragraph.location.semantic.breadcrumbs = vec!["Document".to_string(), "1. Scope".to_string()];
+        document.children.push(section.id);
+        section.children.push(paragraph.id);
+        let (section_id, paragraph_id) = (section.id, paragraph.id);
+        for node in [document, section, paragraph] {
+            graph.nodes.insert(node.id, node);
+        }
+
+        let dir = std::env::temp_dir().join(format!("blazegraph-parquet-{}", uuid::Uuid::new_v4()));
+        graph.write_parquet(&dir).unwrap();
+        let read = |name: &str| {
+            let file = std::fs::File::open(dir.join(name)).unwrap();
+            let mut reader = ParquetRecordBatchReaderBuilder::try_new(file).unwrap().build().unwrap();
+            reader.next().unwrap().unwrap()
+        };
+
+        let nodes = read(NODES_FILE);
+        assert_eq!(nodes.num_rows(), 3);
+        let column = |name: &str| nodes.column_by_name(name).unwrap().as_any().downcast_ref::<StringArray>().unwrap().clone();
+        assert_eq!(column("node_type").iter().collect::<Vec<_>>(), [Some("Document"), Some("Section"), Some("Paragraph")]);
+        assert_eq!(column("parent_id").value(2), section_id.to_string());
+        assert!(column("parent_id").is_null(0));
+        let pages = nodes.column_by_name("page").unwrap().as_any().downcast_ref::<UInt32Array>().unwrap();
+        assert!(pages.is_null(0) && pages.value(1) == 1);
+
+        let edges = read(EDGES_FILE);
+        let column = |name: &str| edges.column_by_name(name).unwrap().as_any().downcast_ref::<StringArray>().unwrap().clone();
+        assert_eq!(column("kind").iter().collect::<Vec<_>>(), [Some("contains"), Some("contains"), Some("references")]);
+        assert_eq!(column("source_id").value(2), paragraph_id.to_string());
+        assert_eq!(column("target_id").value(2), section_id.to_string());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
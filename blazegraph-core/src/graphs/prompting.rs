@@ -0,0 +1,200 @@
+//! Packing retrieved nodes into a prompt
+//!
+//! Retrieval gives a set of relevant nodes, ranked; a prompt has a token
+//! budget. `pack_context` takes the nodes in rank order while they fit, then
+//! writes the ones it took in document order, so the model reads them as the
+//! document presents them:
+//!
+//! - before each run of nodes from a new section, the section trail
+//!   (`[Agreement > 4 Fees > 4.2 Late payment]`);
+//! - `[…]` where siblings between two taken nodes, or before the first one
+//!   taken from a section, were left out.
+//!
+//! Node text is costed at the node's `token_count`, and the trails and
+//! ellipses the packing adds are estimated and counted against the budget too.
+
+use crate::preprocessors::pdf::xhtml_parser::estimate_token_count;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// How `pack_context` lays out the prompt
+#[derive(Debug, Clone)]
+pub struct PackOptions {
+    /// Most tokens the packed text may cost
+    pub token_budget: usize,
+    /// Head each section's nodes with its trail of section headings
+    pub breadcrumbs: bool,
+    /// Marker for left-out siblings
+    pub ellipsis: String,
+}
+
+impl Default for PackOptions {
+    fn default() -> Self {
+        Self {
+            token_budget: 2000,
+            breadcrumbs: true,
+            ellipsis: "[…]".to_string(),
+        }
+    }
+}
+
+/// A prompt-ready excerpt of the document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackedContext {
+    pub text: String,
+    /// Estimated cost of `text`
+    pub tokens: usize,
+    /// Nodes in the text, in document order
+    pub included: Vec<NodeId>,
+    /// Relevant nodes that did not fit the budget, in rank order
+    pub dropped: Vec<NodeId>,
+}
+
+impl DocumentGraph {
+    /// Pack the `relevant` nodes (most relevant first) into at most
+    /// `options.token_budget` tokens. Ids not in the graph, and the root,
+    /// are ignored
+    pub fn pack_context(&self, relevant: &[NodeId], options: &PackOptions) -> PackedContext {
+        let order: HashMap<NodeId, usize> = self
+            .preorder()
+            .into_iter()
+            .enumerate()
+            .map(|(index, (node, _))| (node.id, index))
+            .collect();
+
+        let mut taken: Vec<NodeId> = Vec::new();
+        let mut seen = HashSet::new();
+        let mut dropped = Vec::new();
+        let mut packed = self.render_packed(&[], options);
+        for &id in relevant {
+            if id == self.document_info.root_id || !order.contains_key(&id) || !seen.insert(id) {
+                continue;
+            }
+            let mut candidate = taken.clone();
+            candidate.push(id);
+            candidate.sort_by_key(|id| order[id]);
+            let attempt = self.render_packed(&candidate, options);
+            if attempt.tokens <= options.token_budget {
+                taken = candidate;
+                packed = attempt;
+            } else {
+                dropped.push(id);
+            }
+        }
+        packed.dropped = dropped;
+        packed
+    }
+
+    /// Lay out `nodes` (in document order) with their trails and ellipses
+    fn render_packed(&self, nodes: &[NodeId], options: &PackOptions) -> PackedContext {
+        let mut blocks: Vec<String> = Vec::new();
+        // Everything the packing adds around node text, costed by estimate
+        let mut scaffolding = String::new();
+        let mut node_tokens = 0;
+        let mut trail: Option<&[String]> = None;
+        let mut previous: Option<&DocumentNode> = None;
+        for node in nodes.iter().filter_map(|id| self.nodes.get(id)) {
+            // A section's own heading is its text, not part of its context
+            let crumbs = &node.location.semantic.breadcrumbs;
+            let context = if node.node_type == "Section" && !crumbs.is_empty() {
+                &crumbs[..crumbs.len() - 1]
+            } else {
+                &crumbs[..]
+            };
+            if options.breadcrumbs && !context.is_empty() && trail != Some(context) {
+                blocks.push(format!("[{}]", context.join(" > ")));
+                scaffolding.push_str(blocks.last().unwrap());
+            }
+            trail = Some(context);
+
+            if self.skips_siblings(previous, node) {
+                blocks.push(options.ellipsis.clone());
+                scaffolding.push_str(&options.ellipsis);
+            }
+            blocks.push(node.content.text.clone());
+            node_tokens += node.token_count;
+            previous = Some(node);
+        }
+
+        const SEPARATOR: &str = "\n\n";
+        scaffolding.push_str(&SEPARATOR.repeat(blocks.len().saturating_sub(1)));
+        PackedContext {
+            text: blocks.join(SEPARATOR),
+            tokens: node_tokens + estimate_token_count(&scaffolding),
+            included: nodes.to_vec(),
+            dropped: Vec::new(),
+        }
+    }
+
+    /// Whether siblings of `node` were left out since `previous`: between the
+    /// two, or before `node` when it starts a new parent's run
+    fn skips_siblings(&self, previous: Option<&DocumentNode>, node: &DocumentNode) -> bool {
+        let Some(parent) = node.parent.and_then(|id| self.nodes.get(&id)) else {
+            return false;
+        };
+        let index = |id: NodeId| parent.children.iter().position(|&child| child == id);
+        let Some(position) = index(node.id) else {
+            return false;
+        };
+        let first_unseen = match previous {
+            Some(previous) if previous.parent == node.parent => index(previous.id).map_or(0, |p| p + 1),
+            _ => 0,
+        };
+        position > first_unseen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(graph: &mut DocumentGraph, parent: NodeId, node_type: &str, text: &str) -> NodeId {
+        let mut node = DocumentNode::new(node_type, text.to_string());
+        node.parent = Some(parent);
+        node.token_count = text.len() / 4;
+        let id = node.id;
+        graph.nodes.get_mut(&parent).unwrap().children.push(id);
+        graph.nodes.insert(id, node);
+        id
+    }
+
+    #[test]
+    fn packs_by_rank_in_document_order() {
+        let mut graph = DocumentGraph::new();
+        let root = graph.document_info.root_id;
+        let mut document = DocumentNode::new("Document", "Document".to_string());
+        document.id = root;
+        graph.nodes.insert(root, document);
+        let fees = add(&mut graph, root, "Section", "4 Fees");
+        let invoiced = add(&mut graph, fees, "Paragraph", "Fees are invoiced monthly in arrears.");
+        add(&mut graph, fees, "Paragraph", "Invoices are sent by email to the billing contact.");
+        let late = add(&mut graph, fees, "Paragraph", "Late payments accrue interest at 1.5% per month.");
+        let term = add(&mut graph, root, "Section", "5 Term");
+        let renewal = add(&mut graph, term, "Paragraph", "The agreement renews yearly unless either party gives notice.");
+        graph.document_info.document_metadata.title = Some("Services Agreement".to_string());
+        graph.compute_breadcrumbs();
+
+        let options = PackOptions::default();
+        let packed = graph.pack_context(&[late, renewal, invoiced, late], &options);
+        assert_eq!(
+            packed.text,
+            "[Services Agreement > 4 Fees]\n\n\
+             Fees are invoiced monthly in arrears.\n\n\
+             […]\n\n\
+             Late payments accrue interest at 1.5% per month.\n\n\
+             [Services Agreement > 5 Term]\n\n\
+             The agreement renews yearly unless either party gives notice."
+        );
+        assert_eq!(packed.included, [invoiced, late, renewal]);
+        assert!(packed.dropped.is_empty());
+
+        // The most relevant nodes win when the budget is tight
+        let tight = PackOptions { token_budget: 30, ..options };
+        let packed = graph.pack_context(&[late, renewal, invoiced], &tight);
+        assert_eq!(packed.included, [late]);
+        assert_eq!(packed.dropped, [renewal, invoiced]);
+        assert!(packed.tokens <= 30);
+        assert!(packed.text.starts_with("[Services Agreement > 4 Fees]\n\n[…]\n\nLate payments"));
+    }
+}
@@ -0,0 +1,242 @@
+//! RDF output
+//!
+//! The graph as JSON-LD (the "jsonld" output format) or N-Triples
+//! ("ntriples"), for loading into triple stores. Both carry the same
+//! statements in the Blazegraph vocabulary, `https://blazegraph.io/ns#`:
+//!
+//! - every node is `urn:uuid:<id>`, typed by its node type (`bg:Document`,
+//!   `bg:Section`, `bg:Paragraph`, ...);
+//! - containment is `bg:hasPart` (parent to child) and `bg:partOf` (child to
+//!   parent), with `bg:position` the 1-based index among siblings;
+//! - `bg:text`, `bg:path`, `bg:depth`, `bg:textOrder`, `bg:page`,
+//!   `bg:tokenCount` and `bg:citation` describe each node;
+//! - cross-references are `bg:refersTo`, hyperlinks `bg:linksTo`;
+//! - the Document node also has the document's `bg:title`, `bg:author`,
+//!   `bg:language`, `bg:pageCount` and `bg:contentHash`.
+
+use super::citation::citation_id;
+use crate::types::*;
+use serde_json::{json, Map, Value};
+
+/// The Blazegraph vocabulary
+pub const VOCABULARY: &str = "https://blazegraph.io/ns#";
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+
+/// Properties that may hold several values; always arrays in JSON-LD
+const MULTI_VALUED: [&str; 3] = ["hasPart", "refersTo", "linksTo"];
+
+enum Object {
+    Resource(String),
+    Text(String),
+    Integer(i64),
+}
+
+/// One statement about a node: its IRI, a property of the vocabulary and
+/// the value
+type Statement = (String, &'static str, Object);
+
+fn node_iri(id: NodeId) -> String {
+    format!("urn:uuid:{id}")
+}
+
+/// An href usable as an IRI: absolute, with none of the characters IRIs
+/// exclude (relative and malformed links are left out)
+fn link_iri(href: &str) -> Option<&str> {
+    let (scheme, rest) = href.split_once(':')?;
+    let valid_scheme = scheme.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    let valid_rest = !rest.is_empty() && !rest.chars().any(|c| c.is_control() || c.is_whitespace() || "<>\"{}|\\^`".contains(c));
+    (valid_scheme && valid_rest).then_some(href)
+}
+
+impl DocumentGraph {
+    /// Statements about every node, in document order
+    fn rdf_statements(&self) -> Vec<Statement> {
+        let citation_prefix = self.citation_prefix();
+        let metadata = &self.document_info.document_metadata;
+        let mut statements = Vec::new();
+        for (node, _) in self.preorder() {
+            let iri = node_iri(node.id);
+            let mut add = |property: &'static str, object: Object| statements.push((iri.clone(), property, object));
+            add("type", Object::Resource(format!("{VOCABULARY}{}", node.node_type)));
+            if !node.content.text.is_empty() && node.id != self.document_info.root_id {
+                add("text", Object::Text(node.content.text.clone()));
+            }
+            if node.id == self.document_info.root_id {
+                let texts = [("title", &metadata.title), ("author", &metadata.author), ("language", &metadata.language)];
+                for (property, value) in texts {
+                    if let Some(value) = value.as_ref().filter(|value| !value.trim().is_empty()) {
+                        add(property, Object::Text(value.clone()));
+                    }
+                }
+                if metadata.page_count > 0 {
+                    add("pageCount", Object::Integer(metadata.page_count.into()));
+                }
+                if let Some(hash) = &self.document_info.content_hash {
+                    add("contentHash", Object::Text(hash.clone()));
+                }
+            }
+            if let Some(parent) = node.parent {
+                add("partOf", Object::Resource(node_iri(parent)));
+                let position = self.nodes.get(&parent).and_then(|parent| parent.children.iter().position(|id| *id == node.id));
+                if let Some(position) = position {
+                    add("position", Object::Integer(position as i64 + 1));
+                }
+            }
+            for child in &node.children {
+                add("hasPart", Object::Resource(node_iri(*child)));
+            }
+            if !node.location.semantic.path.is_empty() {
+                add("path", Object::Text(node.location.semantic.path.clone()));
+            }
+            add("depth", Object::Integer(node.location.semantic.depth.into()));
+            if let Some(order) = node.text_order {
+                add("textOrder", Object::Integer(order as i64));
+            }
+            if let Some(physical) = &node.location.physical {
+                add("page", Object::Integer(physical.page.into()));
+            }
+            add("tokenCount", Object::Integer(node.token_count as i64));
+            add("citation", Object::Text(citation_id(&citation_prefix, node)));
+            for target in &node.references {
+                add("refersTo", Object::Resource(node_iri(*target)));
+            }
+            for link in &node.links {
+                if let Some(iri) = link_iri(&link.href) {
+                    add("linksTo", Object::Resource(iri.to_string()));
+                }
+            }
+        }
+        statements
+    }
+
+    /// The graph as a JSON-LD document: a `@context` mapping the vocabulary
+    /// and an `@graph` of node objects, in document order
+    pub fn to_jsonld(&self) -> Value {
+        let mut context = Map::new();
+        context.insert("@vocab".to_string(), json!(VOCABULARY));
+        context.insert("partOf".to_string(), json!({ "@type": "@id" }));
+        for property in MULTI_VALUED {
+            context.insert(property.to_string(), json!({ "@type": "@id", "@container": "@set" }));
+        }
+
+        let mut nodes: Vec<Map<String, Value>> = Vec::new();
+        for (subject, property, object) in self.rdf_statements() {
+            if nodes.last().is_none_or(|node| node["@id"] != subject.as_str()) {
+                let mut node = Map::new();
+                node.insert("@id".to_string(), json!(subject));
+                nodes.push(node);
+            }
+            let node = nodes.last_mut().unwrap();
+            let (key, value) = match (property, object) {
+                ("type", Object::Resource(class)) => ("@type", json!(class.trim_start_matches(VOCABULARY))),
+                (property, Object::Resource(iri)) => (property, json!(iri)),
+                (property, Object::Text(text)) => (property, json!(text)),
+                (property, Object::Integer(value)) => (property, json!(value)),
+            };
+            if MULTI_VALUED.contains(&key) {
+                let values = node.entry(key).or_insert_with(|| json!([]));
+                values.as_array_mut().unwrap().push(value);
+            } else {
+                node.insert(key.to_string(), value);
+            }
+        }
+        json!({ "@context": context, "@graph": nodes })
+    }
+
+    /// The graph as N-Triples, one statement per line
+    pub fn to_ntriples(&self) -> String {
+        let mut output = String::new();
+        for (subject, property, object) in self.rdf_statements() {
+            let predicate = match property {
+                "type" => RDF_TYPE.to_string(),
+                property => format!("{VOCABULARY}{property}"),
+            };
+            let object = match object {
+                Object::Resource(iri) => format!("<{iri}>"),
+                Object::Text(text) => format!("\"{}\"", escape_literal(&text)),
+                Object::Integer(value) => format!("\"{value}\"^^<{XSD_INTEGER}>"),
+            };
+            output.push_str(&format!("<{subject}> <{predicate}> {object} .\n"));
+        }
+        output
+    }
+}
+
+/// Escape a string for an N-Triples literal
+fn escape_literal(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_containment_and_links_as_jsonld_and_ntriples() {
+        let mut graph = DocumentGraph::new();
+        let mut document = DocumentNode::new("Document", "Document".to_string());
+        document.id = graph.document_info.root_id;
+        graph.document_info.document_metadata.title = Some("Field Guide".to_string());
+        let mut section = DocumentNode::new("Section", "1. Scope".to_string());
+        let mut paragraph = DocumentNode::new("Paragraph", "See \"Annex A\"\nand the site.".to_string());
+        section.parent = Some(document.id);
+        paragraph.parent = Some(section.id);
+        paragraph.references.push(section.id);
+        paragraph.links = vec![
+            Hyperlink { href: "https://example.org/".to_string(), text: "the site".to_string() },
+            Hyperlink { href: "docs/annex.html".to_string(), text: "Annex A".to_string() },
+        ];
+        document.children.push(section.id);
+        section.children.push(paragraph.id);
+        let (root_id, section_id, paragraph_id) = (document.id, section.id, paragraph.id);
+        for node in [document, section, paragraph] {
+            graph.nodes.insert(node.id, node);
+        }
+
+        let jsonld = graph.to_jsonld();
+        assert_eq!(jsonld["@context"]["@vocab"], VOCABULARY);
+        let nodes = jsonld["@graph"].as_array().unwrap();
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(nodes[0]["@type"], "Document");
+        assert_eq!(nodes[0]["title"], "Field Guide");
+        assert_eq!(nodes[0]["hasPart"], json!([node_iri(section_id)]));
+        assert_eq!(nodes[1]["@type"], "Section");
+        assert_eq!(nodes[1]["partOf"], node_iri(root_id));
+        assert_eq!(nodes[1]["position"], 1);
+        assert_eq!(nodes[2]["refersTo"], json!([node_iri(section_id)]));
+        assert_eq!(nodes[2]["linksTo"], json!(["https://example.org/"]));
+
+        let ntriples = graph.to_ntriples();
+        let paragraph = node_iri(paragraph_id);
+        assert!(ntriples.contains(&format!("<{paragraph}> <{RDF_TYPE}> <{VOCABULARY}Paragraph> .\n")));
+        assert!(ntriples.contains(&format!("<{paragraph}> <{VOCABULARY}text> \"See \\\"Annex A\\\"\\nand the site.\" .\n")));
+        assert!(ntriples.contains(&format!("<{paragraph}> <{VOCABULARY}partOf> <{}> .\n", node_iri(section_id))));
+        assert!(!ntriples.contains("annex.html"));
+        assert_eq!(ntriples.lines().count(), jsonld_statement_count(nodes));
+    }
+
+    /// Statements in JSON-LD node objects, counting each array value
+    fn jsonld_statement_count(nodes: &[Value]) -> usize {
+        nodes
+            .iter()
+            .flat_map(|node| node.as_object().unwrap().iter())
+            .filter(|(key, _)| *key != "@id")
+            .map(|(_, value)| value.as_array().map_or(1, Vec::len))
+            .sum()
+    }
+}
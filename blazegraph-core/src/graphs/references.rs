@@ -0,0 +1,129 @@
+//! Cross-references between nodes
+//!
+//! Contracts, standards and manuals point at their own sections ("see
+//! Section 4.2", "as defined in Clause 7", "under § 12"). Resolving those
+//! mentions gives RAG systems navigation edges beyond parent/child: a node's
+//! `references` lists the sections its text points at.
+//!
+//! A mention is a reference keyword (Section, Clause, Article, Chapter,
+//! Part, Appendix, Annex, Schedule, Exhibit, §) followed by a label (`4.2`,
+//! `7a`, `IV`, `B`). It resolves to the Section whose heading carries the
+//! same keyword and label ("Clause 7 Payment"), or, for Section, Clause,
+//! Article, Chapter and §, to one numbered by the label alone ("7. Payment").
+//! Labels more than one section carries are ambiguous and left unresolved.
+
+use crate::types::*;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+const KEYWORDS: &str = r"(?i:sections?|clauses?|articles?|chapters?|parts?|appendix|appendices|annex(?:es)?|schedules?|exhibits?)";
+const LABEL: &str = r"(\d+(?:\.\d+)*[a-z]?|[IVXLC]+|[A-Z])";
+
+// A reference keyword and its label anywhere in a node's text
+static MENTION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"(?:\b({KEYWORDS})\s+|(§§?)\s*){LABEL}\b")).unwrap());
+// The label a section heading starts with, with or without a keyword
+static HEADING_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"^(?:({KEYWORDS})\s+|(§§?)\s*)?{LABEL}(?:[.):]|\s|$)")).unwrap()
+});
+
+/// Keywords whose references may resolve to a heading numbered without one
+const BARE_NUMBER_KINDS: &[&str] = &["section", "clause", "article", "chapter"];
+
+/// (keyword, label), both normalized; keyword None for a bare number
+type Label = (Option<String>, String);
+
+fn label(captures: &regex::Captures) -> Label {
+    let kind = match (captures.get(1), captures.get(2)) {
+        (Some(keyword), _) => Some(normalize_kind(keyword.as_str())),
+        (None, Some(_)) => Some("section".to_string()),
+        (None, None) => None,
+    };
+    (kind, captures[3].to_lowercase())
+}
+
+fn normalize_kind(keyword: &str) -> String {
+    let keyword = keyword.to_lowercase();
+    match keyword.as_str() {
+        "appendices" => "appendix".to_string(),
+        "annexes" => "annex".to_string(),
+        _ => keyword.trim_end_matches('s').to_string(),
+    }
+}
+
+impl DocumentGraph {
+    /// Set `references` on every node: the sections its text mentions, in
+    /// order of first mention (a section mentioning itself is skipped)
+    pub fn compute_references(&mut self) {
+        let mut sections: HashMap<Label, Vec<NodeId>> = HashMap::new();
+        let mut texts = Vec::new();
+        for (node, _) in self.preorder() {
+            if node.node_type == "Section" {
+                if let Some(captures) = HEADING_REGEX.captures(node.content.text.trim()) {
+                    sections.entry(label(&captures)).or_default().push(node.id);
+                }
+            }
+            texts.push((node.id, node.content.text.clone()));
+        }
+
+        let resolve = |(kind, number): Label| -> Option<NodeId> {
+            let unique = |key: &Label| match sections.get(key).map(Vec::as_slice) {
+                Some([id]) => Some(*id),
+                _ => None,
+            };
+            let bare = kind.as_deref().is_some_and(|kind| BARE_NUMBER_KINDS.contains(&kind));
+            unique(&(kind, number.clone())).or_else(|| bare.then(|| unique(&(None, number))).flatten())
+        };
+
+        for (id, text) in texts {
+            let mut references = Vec::new();
+            for captures in MENTION_REGEX.captures_iter(&text) {
+                if let Some(target) = resolve(label(&captures)) {
+                    if target != id && !references.contains(&target) {
+                        references.push(target);
+                    }
+                }
+            }
+            if let Some(node) = self.nodes.get_mut(&id) {
+                node.references = references;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mentions_resolve_to_labelled_sections() {
+        let mut graph = DocumentGraph::new();
+        let mut root = DocumentNode::new("Document", "Document".to_string());
+        root.id = graph.document_info.root_id;
+        let mut add = |node_type: &str, text: &str| {
+            let mut node = DocumentNode::new(node_type, text.to_string());
+            node.parent = Some(root.id);
+            root.children.push(node.id);
+            let id = node.id;
+            graph.nodes.insert(id, node);
+            id
+        };
+        let definitions = add("Section", "1. Definitions");
+        let payment = add("Section", "Clause 7 Payment");
+        let schedule = add("Section", "Schedule A");
+        let scope = add("Section", "4.2 Scope");
+        let body = add(
+            "Paragraph",
+            "Fees are due as defined in Clause 7 and Schedule A; see Section 4.2, \
+             section 1 and Section 9. Sections 4.2 repeats.",
+        );
+        let heading = add("Section", "Section 9 Clause 7 amendments");
+        graph.nodes.insert(root.id, root);
+
+        graph.compute_references();
+        assert_eq!(graph.nodes[&body].references, [payment, schedule, scope, definitions, heading]);
+        assert_eq!(graph.nodes[&heading].references, [payment], "a heading skips itself");
+        assert!(graph.nodes[&definitions].references.is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+//! HTML report
+//!
+//! The "html" output format: a self-contained page drawing every node's
+//! bounding box over its page, colored by node type, for reviewing parse
+//! quality in a browser instead of matching coordinates against the PDF by
+//! hand. Hovering a box shows the node's type, path and text; the legend
+//! toggles node types (Sentence boxes start hidden, they repeat their
+//! paragraph's).
+//!
+//! Boxes are positioned in CSS points, which are PDF points, so they line up
+//! with page images given as `PageBackground`s (the CLI renders them with the
+//! page-render feature). Without images, each page is a blank sheet sized to
+//! hold its boxes.
+
+use crate::preprocessors::xhtml_writer::escape;
+use crate::types::*;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// An image of a page to draw the boxes over
+#[derive(Debug, Clone)]
+pub struct PageBackground {
+    /// Physical page number (1-based)
+    pub page_number: u32,
+    /// Image URL: a path relative to the report, or a data URL
+    pub src: String,
+    /// Page size in PDF points
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Box colors by node type; other types are gray
+const COLORS: [(&str, &str); 14] = [
+    ("Section", "#d62728"),
+    ("Paragraph", "#1f77b4"),
+    ("List", "#2ca02c"),
+    ("ListItem", "#98df8a"),
+    ("KeyValue", "#17becf"),
+    ("Quote", "#9467bd"),
+    ("Callout", "#bcbd22"),
+    ("Table", "#ff7f0e"),
+    ("Figure", "#e377c2"),
+    ("Caption", "#8c564b"),
+    ("Header", "#7f7f7f"),
+    ("Footer", "#7f7f7f"),
+    ("Sentence", "#aec7e8"),
+    ("Document", "#000000"),
+];
+
+fn color(node_type: &str) -> &'static str {
+    COLORS.iter().find(|(name, _)| *name == node_type).map_or("#999999", |(_, color)| color)
+}
+
+/// US Letter, the smallest sheet drawn when a page has no image
+const DEFAULT_PAGE_SIZE: (f32, f32) = (612.0, 792.0);
+
+const STYLE: &str = "body { font-family: sans-serif; background: #eee; margin: 0; padding: 16px; }
+header { margin-bottom: 16px; }
+h1 { font-size: 20px; margin: 0 0 4px; }
+.legend label { display: inline-block; margin-right: 12px; font-size: 13px; cursor: pointer; }
+.swatch { display: inline-block; width: 10px; height: 10px; margin-right: 4px; vertical-align: middle; }
+.warnings { font-size: 13px; color: #8a4b00; }
+.page { position: relative; background: #fff no-repeat top left / 100% 100%; margin: 0 auto 24px; box-shadow: 0 1px 4px rgba(0,0,0,.3); }
+.page-number { position: absolute; top: -18px; left: 0; font-size: 12px; color: #555; }
+.node { position: absolute; box-sizing: border-box; border: 1px solid; }
+.node:hover { outline: 2px solid #000; z-index: 10; }
+.hidden { display: none; }";
+
+const SCRIPT: &str = "document.querySelectorAll('.legend input').forEach(function (input) {
+  input.addEventListener('change', function () {
+    document.querySelectorAll('.node[data-type=\"' + input.value + '\"]').forEach(function (node) {
+      node.classList.toggle('hidden', !input.checked);
+    });
+  });
+});";
+
+impl DocumentGraph {
+    /// The graph as an HTML report: each page with its nodes' boxes drawn
+    /// over it, over the matching background image when there is one
+    pub fn to_html_report(&self, backgrounds: &[PageBackground]) -> String {
+        let repaired = self.repaired_page_numbers();
+        let backgrounds: BTreeMap<u32, &PageBackground> = backgrounds
+            .iter()
+            .map(|background| (repaired.get(&background.page_number).copied().unwrap_or(background.page_number), background))
+            .collect();
+
+        // Nodes with a region, by page, in document order (parents under children)
+        let mut pages: BTreeMap<u32, Vec<(&DocumentNode, &BoundingBox)>> = BTreeMap::new();
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for (node, _) in self.preorder() {
+            if node.id == self.document_info.root_id {
+                continue;
+            }
+            if let Some(physical) = &node.location.physical {
+                pages.entry(physical.page).or_default().push((node, &physical.bounding_box));
+                *counts.entry(node.node_type.as_str()).or_default() += 1;
+            }
+        }
+
+        let metadata = &self.document_info.document_metadata;
+        let title = metadata.title.clone().filter(|title| !title.trim().is_empty()).unwrap_or_else(|| "Document".to_string());
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{} — Blazegraph report</title>\n<style>\n{}\n</style>\n</head>\n<body>\n<header>\n<h1>{}</h1>\n",
+            escape(&title),
+            STYLE,
+            escape(&title)
+        );
+        let _ = writeln!(
+            html,
+            "<p>{} nodes on {} page(s){}</p>",
+            counts.values().sum::<usize>(),
+            pages.len(),
+            self.document_info.content_hash.as_ref().map(|hash| format!(" · content hash {}", &hash[..hash.len().min(12)])).unwrap_or_default()
+        );
+        html.push_str("<div class=\"legend\">\n");
+        for (node_type, count) in &counts {
+            let _ = writeln!(
+                html,
+                "<label><input type=\"checkbox\" value=\"{}\"{}><span class=\"swatch\" style=\"background: {}\"></span>{} ({})</label>",
+                escape(node_type),
+                if *node_type == "Sentence" { "" } else { " checked" },
+                color(node_type),
+                escape(node_type),
+                count
+            );
+        }
+        html.push_str("</div>\n");
+        if !self.document_info.warnings.is_empty() {
+            html.push_str("<ul class=\"warnings\">\n");
+            for warning in &self.document_info.warnings {
+                let page = warning.page.map(|page| format!("p.{page}: ")).unwrap_or_default();
+                let _ = writeln!(html, "<li>{}{}</li>", page, escape(&warning.message));
+            }
+            html.push_str("</ul>\n");
+        }
+        html.push_str("</header>\n");
+
+        if pages.is_empty() {
+            html.push_str("<p>No node has a position on a page (reflowable input), so there is nothing to draw.</p>\n");
+        }
+        for (page, nodes) in &pages {
+            let background = backgrounds.get(page);
+            let (width, height) = match background {
+                Some(background) => (background.width, background.height),
+                None => nodes.iter().fold(DEFAULT_PAGE_SIZE, |(width, height), (_, bbox)| {
+                    (width.max(bbox.x + bbox.width), height.max(bbox.y + bbox.height))
+                }),
+            };
+            let image = background.map(|background| format!(" background-image: url('{}');", escape(&background.src.replace('\'', "%27")))).unwrap_or_default();
+            let _ = writeln!(
+                html,
+                "<section class=\"page\" id=\"page-{page}\" style=\"width: {width:.1}pt; height: {height:.1}pt;{image}\">\n<span class=\"page-number\">Page {page}</span>"
+            );
+            for (node, bbox) in nodes {
+                let color = color(&node.node_type);
+                let text: String = node.content.text.chars().take(300).collect();
+                let _ = writeln!(
+                    html,
+                    "<div class=\"node{}\" data-type=\"{}\" style=\"left: {:.1}pt; top: {:.1}pt; width: {:.1}pt; height: {:.1}pt; border-color: {}; background: {}22;\" title=\"{} {}&#10;{}\"></div>",
+                    if node.node_type == "Sentence" { " hidden" } else { "" },
+                    escape(&node.node_type),
+                    bbox.x,
+                    bbox.y,
+                    bbox.width,
+                    bbox.height,
+                    color,
+                    color,
+                    escape(&node.node_type),
+                    escape(&node.location.semantic.path),
+                    escape(&text)
+                );
+            }
+            html.push_str("</section>\n");
+        }
+        let _ = write!(html, "<script>\n{}\n</script>\n</body>\n</html>\n", SCRIPT);
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_node_boxes_over_their_pages() {
+        let mut graph = DocumentGraph::new();
+        let mut document = DocumentNode::new("Document", "Document".to_string());
+        document.id = graph.document_info.root_id;
+        let bbox = |y: f32| BoundingBox { x: 72.0, y, width: 400.0, height: 20.0 };
+        let mut section = DocumentNode::new_with_physical("Section", "1. Scope".to_string(), Some(1), Some(bbox(72.0)));
+        let mut paragraph = DocumentNode::new_with_physical("Paragraph", "Pumps <& valves>".to_string(), Some(2), Some(bbox(900.0)));
+        section.parent = Some(document.id);
+        paragraph.parent = Some(section.id);
+        document.children.push(section.id);
+        section.children.push(paragraph.id);
+        for node in [document, section, paragraph] {
+            graph.nodes.insert(node.id, node);
+        }
+
+        let background = PageBackground { page_number: 1, src: "report.pages/page-0001.png".to_string(), width: 595.0, height: 842.0 };
+        let html = graph.to_html_report(&[background]);
+        assert!(html.contains("id=\"page-1\" style=\"width: 595.0pt; height: 842.0pt; background-image: url('report.pages/page-0001.png');\""));
+        // Page 2 has no image: a Letter sheet stretched to hold its box
+        assert!(html.contains("id=\"page-2\" style=\"width: 612.0pt; height: 920.0pt;\""));
+        assert!(html.contains("left: 72.0pt; top: 72.0pt; width: 400.0pt; height: 20.0pt; border-color: #d62728;"));
+        assert!(html.contains("title=\"Paragraph &#10;Pumps &lt;&amp; valves&gt;\""));
+        assert!(html.contains("Section (1)</label>"));
+        assert!(!html.contains("data-type=\"Document\""));
+    }
+}
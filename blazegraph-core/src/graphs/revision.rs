@@ -0,0 +1,363 @@
+//! Warm-starting a graph from the previous revision of its document
+//!
+//! Living-document knowledge bases key embeddings, links and annotations by
+//! node id, so re-parsing a revised document should not mint fresh ids for
+//! the parts that did not change. `DocumentGraph::warm_start` takes the
+//! previous revision's graph as a hint:
+//!
+//! - The two section outlines are aligned in document order, anchored on
+//!   the previous revision's section titles. Matching is fuzzy, so a
+//!   renumbered ("2. Scope" → "3. Scope") or lightly edited heading still
+//!   finds its predecessor; a change of depth costs a little.
+//! - A matched section takes over the previous section's id, and so does
+//!   each direct child whose content (nested content included) is
+//!   unchanged. A section whose whole subtree is unchanged keeps every id.
+//! - Sections left unmatched on either side are reported as added or
+//!   removed.
+//!
+//! The mapping is recorded as `DocumentInfo::revision`.
+
+use crate::types::*;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+/// Title similarity below which two sections are never matched
+const MIN_TITLE_SIMILARITY: f32 = 0.7;
+/// Similarity lost when matched sections sit at different depths
+const DEPTH_PENALTY: f32 = 0.1;
+const UNNUMBERED_SIMILARITY: f32 = 0.95;
+
+// "1.", "2.3.1", "IV.", "A)", "Chapter 3:", "Section 2 -" ...
+static NUMBERING_PREFIX_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^\s*(?:(?:chapter|section|part|appendix)\s+)?(?:\d+(?:\.\d+)*\.?|[ivxlcdm]+[.)]|[a-z][.)])\s*[:\-–—]?\s+",
+    )
+    .unwrap()
+});
+
+/// A section of one revision, as seen by the alignment
+struct OutlineEntry<'a> {
+    node: &'a DocumentNode,
+    depth: u32,
+    normalized: String,
+    /// Normalized title with any numbering prefix removed
+    unnumbered: String,
+    /// `subtree_hash` of the section
+    fingerprint: String,
+}
+
+impl DocumentGraph {
+    /// Reuse the previous revision's ids where this graph matches it, and
+    /// record the section-level mapping between the two
+    pub fn warm_start(&mut self, previous: &DocumentGraph) -> RevisionMapping {
+        let (ids, sections) = plan(self, previous);
+
+        self.nodes = std::mem::take(&mut self.nodes)
+            .into_values()
+            .map(|mut node| {
+                node.id = remapped(&ids, node.id);
+                node.parent = node.parent.map(|parent| remapped(&ids, parent));
+                for child in &mut node.children {
+                    *child = remapped(&ids, *child);
+                }
+                (node.id, node)
+            })
+            .collect();
+        self.document_info.root_id = remapped(&ids, self.document_info.root_id);
+        for entry in &mut self.document_info.glossary {
+            entry.defined_in = remapped(&ids, entry.defined_in);
+            for id in &mut entry.used_in {
+                *id = remapped(&ids, *id);
+            }
+        }
+
+        let mapping = RevisionMapping {
+            previous_content_hash: previous.document_info.content_hash.clone(),
+            sections,
+            preserved_ids: ids.len(),
+        };
+        self.document_info.revision = Some(mapping.clone());
+        mapping
+    }
+}
+
+fn remapped(ids: &HashMap<NodeId, NodeId>, id: NodeId) -> NodeId {
+    ids.get(&id).copied().unwrap_or(id)
+}
+
+/// Ids to carry over (current → previous) and the section mapping
+fn plan(current: &DocumentGraph, previous: &DocumentGraph) -> (HashMap<NodeId, NodeId>, Vec<SectionRevision>) {
+    let mut ids = IdMap::default();
+    let (root, previous_root) = (current.document_info.root_id, previous.document_info.root_id);
+    if current.nodes.contains_key(&root) && previous.nodes.contains_key(&previous_root) {
+        ids.claim(root, previous_root);
+        ids.match_children(current, previous, root, previous_root);
+    }
+
+    let current_outline = outline(current);
+    let previous_outline = outline(previous);
+    let mut sections = Vec::new();
+    for pair in align(&current_outline, &previous_outline) {
+        let revision = match pair {
+            (Some(c), Some(p)) => {
+                let (now, before) = (&current_outline[c], &previous_outline[p]);
+                ids.claim(now.node.id, before.node.id);
+                ids.match_children(current, previous, now.node.id, before.node.id);
+                let status = if now.fingerprint == before.fingerprint {
+                    RevisionStatus::Unchanged
+                } else if now.normalized == before.normalized {
+                    RevisionStatus::Modified
+                } else {
+                    RevisionStatus::Renamed
+                };
+                SectionRevision {
+                    status,
+                    node_id: before.node.id,
+                    title: Some(now.node.content.text.clone()),
+                    path: Some(now.node.location.semantic.path.clone()),
+                    previous_title: Some(before.node.content.text.clone()),
+                    previous_path: Some(before.node.location.semantic.path.clone()),
+                }
+            }
+            (Some(c), None) => SectionRevision {
+                status: RevisionStatus::Added,
+                node_id: current_outline[c].node.id,
+                title: Some(current_outline[c].node.content.text.clone()),
+                path: Some(current_outline[c].node.location.semantic.path.clone()),
+                previous_title: None,
+                previous_path: None,
+            },
+            (None, Some(p)) => SectionRevision {
+                status: RevisionStatus::Removed,
+                node_id: previous_outline[p].node.id,
+                title: None,
+                path: None,
+                previous_title: Some(previous_outline[p].node.content.text.clone()),
+                previous_path: Some(previous_outline[p].node.location.semantic.path.clone()),
+            },
+            (None, None) => continue,
+        };
+        sections.push(revision);
+    }
+    (ids.map, sections)
+}
+
+/// Current → previous ids, never handing out a previous id twice
+#[derive(Default)]
+struct IdMap {
+    map: HashMap<NodeId, NodeId>,
+    claimed: HashSet<NodeId>,
+}
+
+impl IdMap {
+    fn claim(&mut self, current: NodeId, previous: NodeId) {
+        if !self.map.contains_key(&current) && self.claimed.insert(previous) {
+            self.map.insert(current, previous);
+        }
+    }
+
+    /// Pair up the non-section children of two matched parents whose
+    /// subtrees are identical, in order, and carry their ids over
+    fn match_children(&mut self, current: &DocumentGraph, previous: &DocumentGraph, parent: NodeId, previous_parent: NodeId) {
+        let content_children = |graph: &DocumentGraph, parent: NodeId| -> Vec<(NodeId, String)> {
+            graph.nodes[&parent]
+                .children
+                .iter()
+                .filter(|child| graph.nodes.get(child).is_some_and(|node| node.node_type != "Section"))
+                .map(|&child| (child, graph.subtree_hash(child)))
+                .collect()
+        };
+        let candidates = content_children(previous, previous_parent);
+        let mut taken = vec![false; candidates.len()];
+        for (child, fingerprint) in content_children(current, parent) {
+            let Some(index) = (0..candidates.len()).find(|&i| !taken[i] && candidates[i].1 == fingerprint) else {
+                continue;
+            };
+            taken[index] = true;
+            let pairs = current.subtree_preorder(child).into_iter().zip(previous.subtree_preorder(candidates[index].0));
+            for ((now, _), (before, _)) in pairs {
+                self.claim(now.id, before.id);
+            }
+        }
+    }
+}
+
+fn outline(graph: &DocumentGraph) -> Vec<OutlineEntry<'_>> {
+    graph
+        .preorder()
+        .into_iter()
+        .filter(|(node, _)| node.node_type == "Section")
+        .map(|(node, depth)| OutlineEntry {
+            node,
+            depth,
+            normalized: normalize(&node.content.text),
+            unnumbered: normalize(&NUMBERING_PREFIX_REGEX.replace(node.content.text.trim(), "")),
+            fingerprint: graph.subtree_hash(node.id),
+        })
+        .collect()
+}
+
+/// Order-preserving alignment of two outlines maximizing total title
+/// similarity. Yields (current, previous) index pairs in document order;
+/// one side is None for added and removed sections
+fn align(current: &[OutlineEntry], previous: &[OutlineEntry]) -> Vec<(Option<usize>, Option<usize>)> {
+    let (n, m) = (current.len(), previous.len());
+    let mut best = vec![vec![0.0f32; m + 1]; n + 1];
+    let mut matched = vec![vec![false; m + 1]; n + 1];
+    for i in 1..=n {
+        for j in 1..=m {
+            best[i][j] = best[i - 1][j].max(best[i][j - 1]);
+            let similarity = title_similarity(&current[i - 1], &previous[j - 1]);
+            if similarity >= MIN_TITLE_SIMILARITY && best[i - 1][j - 1] + similarity > best[i][j] {
+                best[i][j] = best[i - 1][j - 1] + similarity;
+                matched[i][j] = true;
+            }
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && matched[i][j] {
+            pairs.push((Some(i - 1), Some(j - 1)));
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && (j == 0 || best[i - 1][j] >= best[i][j - 1]) {
+            // Walking backwards: added sections land after removed ones
+            pairs.push((Some(i - 1), None));
+            i -= 1;
+        } else {
+            pairs.push((None, Some(j - 1)));
+            j -= 1;
+        }
+    }
+    pairs.reverse();
+    pairs
+}
+
+fn title_similarity(a: &OutlineEntry, b: &OutlineEntry) -> f32 {
+    let similarity = if a.normalized == b.normalized {
+        1.0
+    } else if !a.unnumbered.is_empty() && a.unnumbered == b.unnumbered {
+        UNNUMBERED_SIMILARITY
+    } else {
+        word_overlap(&a.unnumbered, &b.unnumbered).max(char_similarity(&a.unnumbered, &b.unnumbered))
+    };
+    if a.depth == b.depth {
+        similarity
+    } else {
+        similarity - DEPTH_PENALTY
+    }
+}
+
+/// Lowercase, drop punctuation, collapse whitespace
+fn normalize(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Shared words relative to the longer title
+fn word_overlap(a: &str, b: &str) -> f32 {
+    let a: HashSet<&str> = a.split_whitespace().collect();
+    let b: HashSet<&str> = b.split_whitespace().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f32 / longest as f32
+}
+
+/// 1 - edit distance / longer length, over characters (catches OCR slips)
+fn char_similarity(a: &str, b: &str) -> f32 {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 0.0;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, x) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            current[j + 1] = (previous[j] + usize::from(x != y)).min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    1.0 - previous[b.len()] as f32 / longest as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A graph of sections, each holding one paragraph
+    fn graph(sections: &[(&str, &str)]) -> DocumentGraph {
+        let mut graph = DocumentGraph::new();
+        let mut root = DocumentNode::new("Document", "Document".to_string());
+        root.id = graph.document_info.root_id;
+        for (title, text) in sections {
+            let mut section = DocumentNode::new("Section", title.to_string());
+            let mut paragraph = DocumentNode::new("Paragraph", text.to_string());
+            section.parent = Some(root.id);
+            paragraph.parent = Some(section.id);
+            section.children.push(paragraph.id);
+            root.children.push(section.id);
+            graph.nodes.insert(paragraph.id, paragraph);
+            graph.nodes.insert(section.id, section);
+        }
+        graph.nodes.insert(root.id, root);
+        graph
+    }
+
+    fn section<'a>(graph: &'a DocumentGraph, title: &str) -> &'a DocumentNode {
+        graph.nodes.values().find(|n| n.content.text == title).unwrap()
+    }
+
+    #[test]
+    fn warm_start_keeps_ids_of_unchanged_sections() {
+        let previous = graph(&[
+            ("Introduction", "This agreement covers the supply of goods."),
+            ("2. Scope", "Goods listed in Schedule A."),
+            ("Payment", "Invoices are due in thirty days."),
+            ("Warranty", "Goods are warranted for one year."),
+        ]);
+        let mut current = graph(&[
+            ("Introduction", "This agreement covers the supply of goods."),
+            ("3. Scope", "Goods listed in Schedule A."),
+            ("Payment", "Invoices are due in sixty days."),
+            ("Termination", "Either party may terminate with notice."),
+        ]);
+
+        let mapping = current.warm_start(&previous);
+        let statuses: Vec<RevisionStatus> = mapping.sections.iter().map(|s| s.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                RevisionStatus::Unchanged,
+                RevisionStatus::Renamed,
+                RevisionStatus::Modified,
+                RevisionStatus::Removed,
+                RevisionStatus::Added,
+            ]
+        );
+
+        let kept = section(&current, "Introduction");
+        assert_eq!(kept.id, section(&previous, "Introduction").id);
+        assert_eq!(kept.children, section(&previous, "Introduction").children);
+        assert_eq!(section(&current, "3. Scope").id, section(&previous, "2. Scope").id);
+        let payment = section(&current, "Payment");
+        assert_eq!(payment.id, section(&previous, "Payment").id);
+        assert_ne!(payment.children, section(&previous, "Payment").children);
+        assert_eq!(current.document_info.root_id, previous.document_info.root_id);
+        assert!(current.nodes.values().all(|n| n.parent.is_none_or(|p| current.nodes.contains_key(&p))));
+        // Root, three sections and the two unchanged paragraphs
+        assert_eq!(mapping.preserved_ids, 6);
+        assert_eq!(current.document_info.revision.as_ref(), Some(&mapping));
+    }
+}
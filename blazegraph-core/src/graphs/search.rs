@@ -0,0 +1,153 @@
+use crate::types::*;
+use anyhow::{anyhow, Result};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+/// Options controlling how a text query is matched against node content
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// Treat the query as a regular expression instead of a literal substring
+    pub regex: bool,
+    /// Match case exactly (default is case-insensitive)
+    pub case_sensitive: bool,
+    /// Restrict matches to these node types (empty = all types)
+    pub node_types: Vec<String>,
+    /// Maximum number of hits to return (None = unlimited)
+    pub limit: Option<usize>,
+}
+
+/// A single search match with enough context to locate it in the tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub node_id: NodeId,
+    pub node_type: String,
+    pub text_order: Option<u32>,
+    pub path: String,
+    pub breadcrumbs: Vec<String>,
+    pub page: Option<u32>,
+    pub text: String,
+    /// Byte offsets of the first match within `text`
+    pub match_start: usize,
+    pub match_end: usize,
+}
+
+impl SearchHit {
+    /// Short excerpt around the match, with `context` characters on each side
+    pub fn snippet(&self, context: usize) -> String {
+        let before: String = self.text[..self.match_start]
+            .chars()
+            .rev()
+            .take(context)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        let after: String = self.text[self.match_end..].chars().take(context).collect();
+
+        let prefix = if before.len() < self.match_start { "…" } else { "" };
+        let suffix = if self.match_end + after.len() < self.text.len() { "…" } else { "" };
+
+        format!(
+            "{}{}[{}]{}{}",
+            prefix,
+            before,
+            &self.text[self.match_start..self.match_end],
+            after,
+            suffix
+        )
+    }
+}
+
+impl DocumentGraph {
+    /// Search node text for a query, returning hits in reading order.
+    /// Literal queries are escaped so both modes share one matcher.
+    pub fn search(&self, query: &str, options: &SearchOptions) -> Result<Vec<SearchHit>> {
+        let matcher = build_matcher(query, options)?;
+
+        let mut nodes: Vec<&DocumentNode> = self
+            .nodes
+            .values()
+            .filter(|n| n.id != self.document_info.root_id)
+            .filter(|n| options.node_types.is_empty() || options.node_types.contains(&n.node_type))
+            .collect();
+        nodes.sort_by_key(|n| n.text_order);
+
+        let mut hits = Vec::new();
+        for node in nodes {
+            if options.limit.is_some_and(|limit| hits.len() >= limit) {
+                break;
+            }
+            if let Some(m) = matcher.find(&node.content.text) {
+                hits.push(SearchHit {
+                    node_id: node.id,
+                    node_type: node.node_type.clone(),
+                    text_order: node.text_order,
+                    path: node.location.semantic.path.clone(),
+                    breadcrumbs: node.location.semantic.breadcrumbs.clone(),
+                    page: node.location.physical.as_ref().map(|p| p.page),
+                    text: node.content.text.clone(),
+                    match_start: m.start(),
+                    match_end: m.end(),
+                });
+            }
+        }
+
+        Ok(hits)
+    }
+}
+
+fn build_matcher(query: &str, options: &SearchOptions) -> Result<Regex> {
+    if query.is_empty() {
+        return Err(anyhow!("Search query must not be empty"));
+    }
+
+    let pattern = if options.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .map_err(|e| anyhow!("Invalid search pattern '{}': {}", query, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph_with(texts: &[&str]) -> DocumentGraph {
+        let mut graph = DocumentGraph::new();
+        for (i, text) in texts.iter().enumerate() {
+            let mut node = DocumentNode::new("Paragraph", text.to_string());
+            node.text_order = Some(i as u32);
+            graph.nodes.insert(node.id, node);
+        }
+        graph
+    }
+
+    #[test]
+    fn literal_search_is_case_insensitive_and_escaped() {
+        let graph = graph_with(&["The Channel (noisy) case", "unrelated"]);
+        let hits = graph.search("channel (NOISY)", &SearchOptions::default()).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(&hits[0].text[hits[0].match_start..hits[0].match_end], "Channel (noisy)");
+    }
+
+    #[test]
+    fn regex_search_respects_limit() {
+        let graph = graph_with(&["entropy one", "entropies two", "entropy three"]);
+        let options = SearchOptions {
+            regex: true,
+            limit: Some(2),
+            ..Default::default()
+        };
+        let hits = graph.search(r"entrop(y|ies)", &options).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].text_order, Some(0));
+
+        let none = SearchOptions { limit: Some(0), ..options };
+        assert!(graph.search(r"entrop(y|ies)", &none).unwrap().is_empty());
+    }
+}
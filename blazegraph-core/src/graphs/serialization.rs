@@ -1,9 +1,104 @@
+use super::citation::citation_id;
+use super::migration;
 use crate::types::*;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use serde::{Serialize, Serializer};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// `SortedDocumentGraph` borrowing from the graph: serialized identically,
+/// without copying the nodes
+#[derive(Serialize)]
+struct SortedGraphView<'a> {
+    schema_version: &'static str,
+    nodes: Vec<&'a DocumentNode>,
+    document_info: &'a DocumentInfo,
+    structural_profile: &'a StructuralProfile,
+}
+
+/// `SequentialDocument`, its segments built one at a time as they are written
+#[derive(Serialize)]
+struct SequentialView<'a> {
+    format: &'static str,
+    #[serde(serialize_with = "serialize_segments")]
+    segments: &'a DocumentGraph,
+    structural_profile: &'a StructuralProfile,
+}
+
+fn serialize_segments<S: Serializer>(graph: &&DocumentGraph, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(graph.sequential_segments())
+}
+
+/// `FlatDocument`, borrowing the node texts
+#[derive(Serialize)]
+struct FlatView<'a> {
+    format: &'static str,
+    chunks: Vec<&'a str>,
+}
+
+impl SortedDocumentGraph {
+    /// Load a graph written by `DocumentGraph::save_to_json` (the default "graph" format).
+    /// Graphs from older schema versions are migrated first.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let mut value: serde_json::Value =
+            serde_json::from_str(json).context("Invalid blazegraph graph JSON")?;
+        let original_version = migration::migrate_to_current(&mut value)?;
+        if original_version != SCHEMA_VERSION {
+            println!(
+                "🔄 Migrated graph from schema {} to {}",
+                original_version, SCHEMA_VERSION
+            );
+        }
+        serde_json::from_value(value).context("Invalid blazegraph graph JSON")
+    }
+}
+
+impl SequentialDocument {
+    /// Load output written with the "sequential" format
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("Invalid blazegraph sequential JSON")
+    }
+}
+
+impl FlatDocument {
+    /// Load output written with the "flat" format
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("Invalid blazegraph flat JSON")
+    }
+}
+
+impl Chunk {
+    /// Load output written with the "chunks" format, one chunk per line
+    pub fn from_jsonl(jsonl: &str) -> Result<Vec<Self>> {
+        jsonl
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).with_context(|| format!("Invalid blazegraph chunk on line {}", index + 1))
+            })
+            .collect()
+    }
+}
 
 impl DocumentGraph {
-    pub fn to_sequential_format(&self) -> SequentialDocument {
-        // Collect all nodes and sort by text_order, with root node first
+    /// Load a graph.json back into the id-indexed graph
+    pub fn from_json(json: &str) -> Result<Self> {
+        SortedDocumentGraph::from_json(json).map(Self::from)
+    }
+
+    /// Read a graph.json from disk, migrating older schema versions
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read graph file: {}", path.display()))?;
+        Self::from_json(&json)
+            .with_context(|| format!("Failed to load graph file: {}", path.display()))
+    }
+
+    /// Nodes sorted by text_order, with the root first
+    pub(crate) fn sorted_nodes(&self) -> Vec<&DocumentNode> {
         let mut nodes: Vec<&DocumentNode> = self.nodes.values().collect();
         nodes.sort_by(|a, b| {
             // Document root (with text_order = None) should come first
@@ -14,67 +109,176 @@ impl DocumentGraph {
                 (Some(a_order), Some(b_order)) => a_order.cmp(&b_order),
             }
         });
+        nodes
+    }
 
-        let segments: Vec<SequentialSegment> = nodes
+    fn sequential_segments(&self) -> impl Iterator<Item = SequentialSegment> + '_ {
+        let citation_prefix = self.citation_prefix();
+        self.sorted_nodes()
             .into_iter()
             .enumerate()
-            .map(|(index, node)| SequentialSegment {
+            .map(move |(index, node)| SequentialSegment {
                 id: index,
                 node_type: node.node_type.clone(),
                 text: node.content.text.clone(),
                 location: node.location.clone(),
                 style: node.style_info.clone(),
                 tokens: node.token_count,
+                citation: Some(citation_id(&citation_prefix, node)),
             })
-            .collect();
+    }
 
+    /// Texts of the flat format: every node but sentences, which repeat
+    /// their paragraph's text
+    fn flat_chunks(&self) -> Vec<&str> {
+        self.sorted_nodes()
+            .into_iter()
+            .filter(|node| node.node_type != "Sentence")
+            .map(|node| node.content.text.as_str())
+            .collect()
+    }
+
+    pub fn to_sequential_format(&self) -> SequentialDocument {
         SequentialDocument {
             format: "sequential".to_string(),
-            segments,
+            segments: self.sequential_segments().collect(),
             structural_profile: self.structural_profile.clone(),
         }
     }
 
     pub fn to_flat_format(&self) -> FlatDocument {
-        // Collect all nodes and sort by text_order, with root node first
-        let mut nodes: Vec<&DocumentNode> = self.nodes.values().collect();
-        nodes.sort_by(|a, b| {
-            // Document root (with text_order = None) should come first
-            match (a.text_order, b.text_order) {
-                (None, None) => std::cmp::Ordering::Equal,
-                (None, Some(_)) => std::cmp::Ordering::Less,
-                (Some(_), None) => std::cmp::Ordering::Greater,
-                (Some(a_order), Some(b_order)) => a_order.cmp(&b_order),
-            }
-        });
-
-        let chunks: Vec<String> = nodes
-            .into_iter()
-            .map(|node| node.content.text.clone())
-            .collect();
-
         FlatDocument {
             format: "flat".to_string(),
-            chunks,
+            chunks: self.flat_chunks().into_iter().map(str::to_string).collect(),
         }
     }
 
+    /// Every node with text but the root, in document order. Sentences repeat
+    /// their paragraph's text, so they are left out as in the flat format
+    pub fn to_chunks(&self) -> Vec<Chunk> {
+        self.chunks().collect()
+    }
+
+    fn chunks(&self) -> impl Iterator<Item = Chunk> + '_ {
+        let citation_prefix = self.citation_prefix();
+        let document_id = self.document_info.content_hash.clone().unwrap_or_else(|| self.content_hash());
+        self.preorder()
+            .into_iter()
+            .map(|(node, _)| node)
+            .filter(|node| {
+                node.id != self.document_info.root_id && node.node_type != "Sentence" && !node.content.text.trim().is_empty()
+            })
+            .map(move |node| {
+                let physical = node.location.physical.as_ref();
+                Chunk {
+                    id: citation_id(&citation_prefix, node),
+                    document_id: document_id.clone(),
+                    node_id: node.id,
+                    node_type: node.node_type.clone(),
+                    text: node.content.text.clone(),
+                    breadcrumbs: node.location.semantic.breadcrumbs.clone(),
+                    path: node.location.semantic.path.clone(),
+                    page: physical.map(|physical| physical.page),
+                    page_label: physical.and_then(|physical| physical.page_label.clone()),
+                    bounding_box: physical.map(|physical| physical.bounding_box.clone()),
+                    text_span: node.location.text_span,
+                    token_count: node.token_count,
+                }
+            })
+    }
+
+    /// The "chunks" format: `to_chunks` as JSON Lines
+    pub fn to_chunks_jsonl(&self) -> Result<String> {
+        let mut jsonl = Vec::new();
+        self.write_with_format(&mut jsonl, "chunks")?;
+        Ok(String::from_utf8(jsonl)?)
+    }
+
+    /// Write an output format to `path`; "parquet" makes `path` a directory
+    /// of tables (see `write_parquet`). The file is written as it is
+    /// serialized (see `write_with_format`) into a temporary file beside
+    /// `path`, which replaces `path` only once it is complete: a failed save
+    /// leaves an existing output untouched
     pub fn save_with_format(&self, path: &str, format: &str) -> Result<()> {
+        self.validate_annotations()?;
+        if format == "parquet" {
+            return self.write_parquet(path);
+        }
+        let path = Path::new(path);
+        let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("output");
+        let temp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+        let written = File::create(&temp_path)
+            .with_context(|| format!("Failed to create {}", temp_path.display()))
+            .and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                self.write_format(&mut writer, format)?;
+                writer.flush()?;
+                Ok(())
+            });
+        if let Err(e) = written.and_then(|()| {
+            std::fs::rename(&temp_path, path).with_context(|| format!("Failed to write {}", path.display()))
+        }) {
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Serialize in an output format ("graph", "sequential", "flat", "jsonld",
+    /// "chunks" (JSON Lines), or "ntriples" and "html", which aren't JSON;
+    /// anything else falls back to "graph"), as written by `save_with_format`.
+    /// The "html" report has no page images; see `to_html_report`. "parquet"
+    /// is binary and has no string form: it is an error here
+    ///
+    /// Fails when a node's annotations don't match their namespace schema
+    pub fn to_json_with_format(&self, format: &str) -> Result<String> {
+        let mut json = Vec::new();
+        self.write_with_format(&mut json, format)?;
+        Ok(String::from_utf8(json)?)
+    }
+
+    /// Serialize an output format into `writer`, as `to_json_with_format`
+    /// returns it. The graph, sequential, flat and chunks formats are
+    /// streamed: nodes are written as they are serialized, never copied into
+    /// an output struct or a string first, so saving a book-length graph
+    /// doesn't double its memory
+    pub fn write_with_format<W: Write>(&self, writer: W, format: &str) -> Result<()> {
+        self.validate_annotations()?;
+        self.write_format(writer, format)
+    }
+
+    /// `write_with_format` without validating annotations first
+    fn write_format<W: Write>(&self, mut writer: W, format: &str) -> Result<()> {
         match format {
+            "parquet" => anyhow::bail!("Parquet output is a directory of tables: use save_with_format or write_parquet"),
             "sequential" => {
-                let sequential = self.to_sequential_format();
-                let json = serde_json::to_string_pretty(&sequential)?;
-                std::fs::write(path, json)?;
+                let view = SequentialView {
+                    format: "sequential",
+                    segments: self,
+                    structural_profile: &self.structural_profile,
+                };
+                serde_json::to_writer_pretty(writer, &view)?;
             }
-            "flat" => {
-                let flat = self.to_flat_format();
-                let json = serde_json::to_string_pretty(&flat)?;
-                std::fs::write(path, json)?;
+            "flat" => serde_json::to_writer_pretty(writer, &FlatView { format: "flat", chunks: self.flat_chunks() })?,
+            "chunks" => {
+                for chunk in self.chunks() {
+                    serde_json::to_writer(&mut writer, &chunk)?;
+                    writer.write_all(b"\n")?;
+                }
             }
+            "jsonld" => serde_json::to_writer_pretty(writer, &self.to_jsonld())?,
+            "ntriples" => writer.write_all(self.to_ntriples().as_bytes())?,
+            "html" => writer.write_all(self.to_html_report(&[]).as_bytes())?,
             _ => {
-                self.save_to_json(path)?;
+                let view = SortedGraphView {
+                    schema_version: SCHEMA_VERSION,
+                    nodes: self.sorted_nodes(),
+                    document_info: &self.document_info,
+                    structural_profile: &self.structural_profile,
+                };
+                serde_json::to_writer_pretty(writer, &view)?;
             }
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
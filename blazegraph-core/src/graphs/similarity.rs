@@ -0,0 +1,187 @@
+//! Similarity scoring between two document graphs
+//!
+//! For near-duplicate cleanup and grouping versions of the same document.
+//! Two signals are combined:
+//!
+//! - **Structure**: the section outline of each graph (depth and title of
+//!   every Section, in document order) compared by edit distance. This
+//!   approximates tree edit distance over the section tree: inserting,
+//!   deleting or renaming a section costs one, and moving it to another
+//!   depth costs half.
+//! - **Text**: Jaccard overlap of word shingles over all node text, so
+//!   reflowed or re-chunked paragraphs still match.
+//!
+//! Both scores, and their weighted combination, range from 0.0 (unrelated)
+//! to 1.0 (identical).
+
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+#[derive(Debug, Clone)]
+pub struct SimilarityOptions {
+    /// Words per text shingle
+    pub shingle_size: usize,
+    /// Weight of the structure score in the combined score (text gets the rest)
+    pub structure_weight: f32,
+}
+
+impl Default for SimilarityOptions {
+    fn default() -> Self {
+        Self {
+            shingle_size: 5,
+            structure_weight: 0.4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Similarity {
+    /// Weighted combination of `structure` and `text`
+    pub score: f32,
+    /// Section outline similarity
+    pub structure: f32,
+    /// Shingled text overlap
+    pub text: f32,
+}
+
+/// Similarity of two graphs with default options
+pub fn similarity(a: &DocumentGraph, b: &DocumentGraph) -> Similarity {
+    similarity_with(a, b, &SimilarityOptions::default())
+}
+
+pub fn similarity_with(a: &DocumentGraph, b: &DocumentGraph, options: &SimilarityOptions) -> Similarity {
+    let structure = outline_similarity(&outline(a), &outline(b));
+    let text = jaccard(
+        &shingles(a, options.shingle_size),
+        &shingles(b, options.shingle_size),
+    );
+    let weight = options.structure_weight.clamp(0.0, 1.0);
+    Similarity {
+        score: weight * structure + (1.0 - weight) * text,
+        structure,
+        text,
+    }
+}
+
+/// (depth, normalized title) of every section, in document order
+fn outline(graph: &DocumentGraph) -> Vec<(u32, String)> {
+    graph
+        .preorder()
+        .into_iter()
+        .filter(|(node, _)| node.node_type == "Section")
+        .map(|(node, depth)| (depth, words(&node.content.text).join(" ")))
+        .collect()
+}
+
+/// 1 - edit distance / longer outline length
+fn outline_similarity(a: &[(u32, String)], b: &[(u32, String)]) -> f32 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let substitution = |x: &(u32, String), y: &(u32, String)| match (x.0 == y.0, x.1 == y.1) {
+        (true, true) => 0.0,
+        (false, true) => 0.5,
+        _ => 1.0,
+    };
+    // Two-row Levenshtein
+    let mut previous: Vec<f32> = (0..=b.len()).map(|j| j as f32).collect();
+    let mut current = vec![0.0; b.len() + 1];
+    for (i, x) in a.iter().enumerate() {
+        current[0] = (i + 1) as f32;
+        for (j, y) in b.iter().enumerate() {
+            current[j + 1] = (previous[j] + substitution(x, y))
+                .min(previous[j + 1] + 1.0)
+                .min(current[j] + 1.0);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    1.0 - previous[b.len()] / longest as f32
+}
+
+/// Lowercased alphanumeric words
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Hashes of every run of `size` consecutive words across the document text
+fn shingles(graph: &DocumentGraph, size: usize) -> HashSet<u64> {
+    let words: Vec<String> = graph
+        .preorder()
+        .into_iter()
+        .filter(|(node, _)| node.id != graph.document_info.root_id)
+        .flat_map(|(node, _)| words(&node.content.text))
+        .collect();
+    let size = size.max(1).min(words.len().max(1));
+    words
+        .windows(size)
+        .map(|window| {
+            let mut hasher = DefaultHasher::new();
+            window.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A graph of sections, each holding one paragraph
+    fn graph(sections: &[(&str, &str)]) -> DocumentGraph {
+        let mut graph = DocumentGraph::new();
+        let mut root = DocumentNode::new("Document", "Document".to_string());
+        root.id = graph.document_info.root_id;
+        for (title, text) in sections {
+            let mut section = DocumentNode::new("Section", title.to_string());
+            let mut paragraph = DocumentNode::new("Paragraph", text.to_string());
+            section.parent = Some(root.id);
+            paragraph.parent = Some(section.id);
+            section.children.push(paragraph.id);
+            root.children.push(section.id);
+            graph.nodes.insert(paragraph.id, paragraph);
+            graph.nodes.insert(section.id, section);
+        }
+        graph.nodes.insert(root.id, root);
+        graph
+    }
+
+    #[test]
+    fn near_duplicates_score_higher_than_unrelated_documents() {
+        let original = graph(&[
+            ("Introduction", "The parties agree to the terms set out in this agreement below."),
+            ("Payment", "Invoices are payable within thirty days of the invoice date."),
+        ]);
+        let same = similarity(&original, &original);
+        assert_eq!((same.score, same.structure, same.text), (1.0, 1.0, 1.0));
+
+        let revised = graph(&[
+            ("INTRODUCTION", "The parties agree to the terms set out in this agreement below."),
+            ("Payment", "Invoices are payable within forty five days of the invoice date."),
+            ("Termination", "Either party may terminate with notice."),
+        ]);
+        let unrelated = graph(&[("Abstract", "We study channel capacity under noise.")]);
+
+        let near = similarity(&original, &revised);
+        let far = similarity(&original, &unrelated);
+        assert!((near.structure - 2.0 / 3.0).abs() < 1e-6, "{:?}", near);
+        assert!(near.text > 0.4 && near.text < 1.0, "{:?}", near);
+        assert!(far.score < 0.1, "{:?}", far);
+        assert!(near.score > far.score + 0.4);
+    }
+}
@@ -0,0 +1,200 @@
+//! SQLite output
+//!
+//! `DocumentGraph::save_to_sqlite` adds a graph to a SQLite database, so a
+//! corpus is one queryable file rather than thousands of JSON files.
+//! Documents are keyed by their content hash (`document_id`); saving a
+//! document again replaces its rows. Tables:
+//!
+//! - `documents`: one row per document — title, author, language, page and
+//!   node counts, schema version, and the full `metadata` and
+//!   `structural_profile` as JSON;
+//! - `nodes`: one row per node, in document order, with its tree position,
+//!   page, bounding box, token count, citation id and breadcrumbs (JSON);
+//! - `edges`: `contains` (parent to child, with the child's position) and
+//!   `references` relations between nodes;
+//! - `nodes_fts`: an FTS5 index of node text (the root and `Sentence` nodes,
+//!   which repeat their paragraph's text, are left out).
+//!
+//! Writing needs the `sqlite` feature, which compiles SQLite in.
+
+use crate::types::*;
+use anyhow::Result;
+use std::path::Path;
+
+#[cfg(feature = "sqlite")]
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS documents (
+    document_id TEXT PRIMARY KEY,
+    title TEXT,
+    author TEXT,
+    language TEXT,
+    page_count INTEGER NOT NULL,
+    node_count INTEGER NOT NULL,
+    schema_version TEXT NOT NULL,
+    metadata TEXT NOT NULL,
+    structural_profile TEXT NOT NULL,
+    saved_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS nodes (
+    node_id TEXT PRIMARY KEY,
+    document_id TEXT NOT NULL REFERENCES documents(document_id),
+    parent_id TEXT,
+    citation TEXT NOT NULL,
+    node_type TEXT NOT NULL,
+    text TEXT NOT NULL,
+    path TEXT NOT NULL,
+    depth INTEGER NOT NULL,
+    text_order INTEGER,
+    page INTEGER,
+    page_label TEXT,
+    bbox_x REAL,
+    bbox_y REAL,
+    bbox_width REAL,
+    bbox_height REAL,
+    token_count INTEGER NOT NULL,
+    breadcrumbs TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS nodes_document ON nodes(document_id);
+CREATE INDEX IF NOT EXISTS nodes_parent ON nodes(parent_id);
+CREATE TABLE IF NOT EXISTS edges (
+    document_id TEXT NOT NULL REFERENCES documents(document_id),
+    source_id TEXT NOT NULL,
+    target_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    position INTEGER
+);
+CREATE INDEX IF NOT EXISTS edges_document ON edges(document_id);
+CREATE INDEX IF NOT EXISTS edges_source ON edges(source_id);
+CREATE INDEX IF NOT EXISTS edges_target ON edges(target_id);
+CREATE VIRTUAL TABLE IF NOT EXISTS nodes_fts USING fts5(text, node_id UNINDEXED, document_id UNINDEXED);
+";
+
+impl DocumentGraph {
+    /// Add this graph to the SQLite database at `path`, creating the database
+    /// and its tables as needed; a document already saved there is replaced
+    #[cfg(feature = "sqlite")]
+    pub fn save_to_sqlite(&self, path: impl AsRef<Path>) -> Result<()> {
+        use super::citation::citation_id;
+        use anyhow::Context;
+        use rusqlite::{params, Connection};
+
+        let path = path.as_ref();
+        let mut connection = Connection::open(path).with_context(|| format!("Failed to open database: {}", path.display()))?;
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(SCHEMA)?;
+
+        let document_id = self.document_info.content_hash.clone().unwrap_or_else(|| self.content_hash());
+        for table in ["nodes_fts", "edges", "nodes", "documents"] {
+            transaction.execute(&format!("DELETE FROM {table} WHERE document_id = ?1"), params![document_id])?;
+        }
+
+        let metadata = &self.document_info.document_metadata;
+        transaction.execute(
+            "INSERT INTO documents (document_id, title, author, language, page_count, node_count, schema_version, metadata, structural_profile, saved_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                document_id,
+                metadata.title,
+                metadata.author,
+                metadata.language,
+                metadata.page_count,
+                self.nodes.len(),
+                SCHEMA_VERSION,
+                serde_json::to_string(metadata)?,
+                serde_json::to_string(&self.structural_profile)?,
+                chrono::Utc::now().to_rfc3339(),
+            ],
+        )?;
+
+        let citation_prefix = self.citation_prefix();
+        {
+            let mut insert_node = transaction.prepare(
+                "INSERT INTO nodes (node_id, document_id, parent_id, citation, node_type, text, path, depth, text_order, page, page_label, bbox_x, bbox_y, bbox_width, bbox_height, token_count, breadcrumbs)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+            )?;
+            let mut insert_text = transaction.prepare("INSERT INTO nodes_fts (text, node_id, document_id) VALUES (?1, ?2, ?3)")?;
+            for (node, _) in self.preorder() {
+                let physical = node.location.physical.as_ref();
+                let bbox = physical.map(|physical| &physical.bounding_box);
+                insert_node.execute(params![
+                    node.id.to_string(),
+                    document_id,
+                    node.parent.map(|parent| parent.to_string()),
+                    citation_id(&citation_prefix, node),
+                    node.node_type,
+                    node.content.text,
+                    node.location.semantic.path,
+                    node.location.semantic.depth,
+                    node.text_order,
+                    physical.map(|physical| physical.page),
+                    physical.and_then(|physical| physical.page_label.clone()),
+                    bbox.map(|bbox| bbox.x),
+                    bbox.map(|bbox| bbox.y),
+                    bbox.map(|bbox| bbox.width),
+                    bbox.map(|bbox| bbox.height),
+                    node.token_count,
+                    serde_json::to_string(&node.location.semantic.breadcrumbs)?,
+                ])?;
+                let indexed = node.id != self.document_info.root_id && node.node_type != "Sentence";
+                if indexed && !node.content.text.trim().is_empty() {
+                    insert_text.execute(params![node.content.text, node.id.to_string(), document_id])?;
+                }
+            }
+
+            let mut insert_edge =
+                transaction.prepare("INSERT INTO edges (document_id, source_id, target_id, kind, position) VALUES (?1, ?2, ?3, ?4, ?5)")?;
+            for (source, target, kind, position) in self.edges() {
+                insert_edge.execute(params![document_id, source.to_string(), target.to_string(), kind, position])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    pub fn save_to_sqlite(&self, _path: impl AsRef<Path>) -> Result<()> {
+        anyhow::bail!("Saving to SQLite needs the `sqlite` feature")
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    #[test]
+    fn saves_documents_into_one_searchable_database() {
+        let mut graph = DocumentGraph::new();
+        let mut document = DocumentNode::new("Document", "Document".to_string());
+        document.id = graph.document_info.root_id;
+        graph.document_info.document_metadata.title = Some("Pump Manual".to_string());
+        let mut section = DocumentNode::new("Section", "1. Maintenance".to_string());
+        let mut paragraph = DocumentNode::new("Paragraph", "Check the impeller every month.".to_string());
+        section.parent = Some(document.id);
+        paragraph.parent = Some(section.id);
+        document.children.push(section.id);
+        section.children.push(paragraph.id);
+        let paragraph_id = paragraph.id;
+        for node in [document, section, paragraph] {
+            graph.nodes.insert(node.id, node);
+        }
+
+        let path = std::env::temp_dir().join(format!("blazegraph-{}.sqlite", uuid::Uuid::new_v4()));
+        graph.save_to_sqlite(&path).unwrap();
+        // Saving again replaces the document instead of adding it twice
+        graph.save_to_sqlite(&path).unwrap();
+
+        let connection = Connection::open(&path).unwrap();
+        let count = |sql: &str| connection.query_row(sql, [], |row| row.get::<_, i64>(0)).unwrap();
+        assert_eq!(count("SELECT count(*) FROM documents"), 1);
+        assert_eq!(count("SELECT count(*) FROM nodes"), 3);
+        assert_eq!(count("SELECT count(*) FROM edges WHERE kind = 'contains'"), 2);
+        let title: String = connection.query_row("SELECT title FROM documents", [], |row| row.get(0)).unwrap();
+        assert_eq!(title, "Pump Manual");
+        let hit: String = connection
+            .query_row("SELECT node_id FROM nodes_fts WHERE nodes_fts MATCH 'impeller'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(hit, paragraph_id.to_string());
+        std::fs::remove_file(path).unwrap();
+    }
+}
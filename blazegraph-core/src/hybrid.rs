@@ -0,0 +1,264 @@
+//! Scanned-vs-digital page detection
+//!
+//! Hybrid PDFs mix pages with a real text layer and pages that are only a
+//! scanned image. Tika extracts nothing from the latter, so without a check
+//! the graph silently loses those pages. Each page is classified by how much
+//! text its layer contributed, the result is recorded per page in
+//! `DocumentInfo::page_extraction`; scanned pages go through OCR when it is
+//! available, and pages that stay empty are reported.
+
+use crate::ocr::{merge_page_elements, PageOcr};
+use crate::types::*;
+
+/// A page whose text layer has fewer non-whitespace characters is treated as
+/// scanned. Low enough that sparse digital pages (a part title, a page
+/// number) are still text pages, high enough to ignore stray OCR-less stamps.
+pub const MIN_TEXT_LAYER_CHARS: usize = 16;
+
+/// Classify every page of an extraction by its text layer
+///
+/// `page_count` comes from document metadata; pages past the last text
+/// element (trailing scans) are only visible through it.
+pub fn detect_page_extraction(elements: &[PdfTextElement], page_count: u32) -> Vec<PageExtraction> {
+    let last_page = elements
+        .iter()
+        .map(|e| e.page_number)
+        .max()
+        .unwrap_or(0)
+        .max(page_count);
+
+    let mut chars = vec![0usize; last_page as usize];
+    for element in elements {
+        if let Some(count) = chars.get_mut(element.page_number.saturating_sub(1) as usize) {
+            *count += element.text.chars().filter(|c| !c.is_whitespace()).count();
+        }
+    }
+
+    chars
+        .into_iter()
+        .enumerate()
+        .map(|(index, text_chars)| PageExtraction {
+            page_number: index as u32 + 1,
+            method: if text_chars < MIN_TEXT_LAYER_CHARS {
+                ExtractionMethod::Missing
+            } else {
+                ExtractionMethod::TextLayer
+            },
+            text_chars,
+        })
+        .collect()
+}
+
+/// Record per-page methods on `output`, OCR scanned pages, and warn about
+/// pages that still have no text
+///
+/// With `ocr`, each page without a text layer is rendered and recognized;
+/// its recognized lines replace whatever stray text-layer elements it had.
+/// Fully digital documents keep an empty `page_extraction`, so ordinary
+/// graphs don't grow a 1-entry-per-page list.
+pub fn classify_pages(output: &mut PreprocessorOutput, document_bytes: &[u8], ocr: Option<&PageOcr>) {
+    let mut pages = detect_page_extraction(&output.text_elements, output.metadata.page_count);
+    if !pages.iter().any(|page| page.method == ExtractionMethod::Missing) {
+        return;
+    }
+
+    let mut ocr_failed = false;
+    if let Some(ocr) = ocr {
+        let mut recognized = Vec::new();
+        for page in pages.iter_mut().filter(|page| page.method == ExtractionMethod::Missing) {
+            match ocr.recognize_page(document_bytes, page.page_number) {
+                Ok(elements) if !elements.is_empty() => {
+                    page.method = ExtractionMethod::Ocr;
+                    page.text_chars = elements
+                        .iter()
+                        .map(|e| e.text.chars().filter(|c| !c.is_whitespace()).count())
+                        .sum();
+                    recognized.extend(elements);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    println!("⚠️  OCR ({}) failed on page {}: {}", ocr.name(), page.page_number, e);
+                    ocr_failed = true;
+                }
+            }
+        }
+
+        let ocr_pages: Vec<u32> = pages
+            .iter()
+            .filter(|page| page.method == ExtractionMethod::Ocr)
+            .map(|page| page.page_number)
+            .collect();
+        if !ocr_pages.is_empty() {
+            println!("🔎 OCR recognized {} line(s) on {} scanned page(s)", recognized.len(), ocr_pages.len());
+            output.text_elements.retain(|e| !ocr_pages.contains(&e.page_number));
+            merge_page_elements(&mut output.text_elements, recognized);
+        }
+    }
+
+    let missing: Vec<u32> = pages
+        .iter()
+        .filter(|page| page.method == ExtractionMethod::Missing)
+        .map(|page| page.page_number)
+        .collect();
+    if let Some(&first) = missing.first() {
+        let mut message = if missing.len() == pages.len() {
+            format!(
+                "None of the {} page(s) has a text layer (likely a scanned document); no text was extracted",
+                pages.len()
+            )
+        } else {
+            let list: Vec<String> = missing.iter().map(u32::to_string).collect();
+            format!(
+                "{} of {} pages have no text layer (likely scanned) and contributed no text: {}",
+                missing.len(),
+                pages.len(),
+                list.join(", ")
+            )
+        };
+        if ocr.is_none() {
+            message.push_str(" (OCR unavailable: build with the `ocr` feature)");
+        } else if ocr_failed {
+            message.push_str(" (OCR failed)");
+        }
+        println!("⚠️  {}", message);
+        output
+            .warnings
+            .push(ProcessingWarning::new(WarningCode::ScannedPages, message).on_page(first));
+    }
+    output.page_extraction = pages;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr::{OcrBackend, OcrWord};
+    use crate::render::{PageRenderer, RenderOptions, RenderedPage};
+    use crate::test_utils::TextElementBuilder;
+    use anyhow::Result;
+
+    fn element(text: &str, page_number: u32) -> PdfTextElement {
+        TextElementBuilder::new(text).page(page_number).build()
+    }
+
+    fn output(text_elements: Vec<PdfTextElement>, page_count: u32) -> PreprocessorOutput {
+        PreprocessorOutput {
+            text_elements,
+            metadata: DocumentMetadata {
+                page_count,
+                ..DocumentMetadata::default()
+            },
+            style_data: StyleData::default(),
+            bookmark_data: None,
+            warnings: Vec::new(),
+            page_extraction: Vec::new(),
+            links: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn flags_pages_without_a_text_layer() {
+        let body = "A full paragraph of digital text on this page.";
+        let mut hybrid = output(vec![element(body, 1), element("12", 2), element(body, 3)], 4);
+        classify_pages(&mut hybrid, b"", None);
+
+        let methods: Vec<(u32, ExtractionMethod)> = hybrid
+            .page_extraction
+            .iter()
+            .map(|page| (page.page_number, page.method))
+            .collect();
+        assert_eq!(
+            methods,
+            vec![
+                (1, ExtractionMethod::TextLayer),
+                (2, ExtractionMethod::Missing),
+                (3, ExtractionMethod::TextLayer),
+                (4, ExtractionMethod::Missing),
+            ]
+        );
+        assert_eq!(hybrid.warnings.len(), 1);
+        assert_eq!(hybrid.warnings[0].code, WarningCode::ScannedPages);
+        assert_eq!(hybrid.warnings[0].page, Some(2));
+        assert!(hybrid.warnings[0].message.contains("2 of 4 pages"));
+        assert!(hybrid.warnings[0].message.contains("`ocr` feature"));
+
+        // Fully digital documents record nothing
+        let mut digital = output(vec![element(body, 1)], 1);
+        classify_pages(&mut digital, b"", None);
+        assert!(digital.page_extraction.is_empty() && digital.warnings.is_empty());
+    }
+
+    struct BlankRenderer;
+
+    impl PageRenderer for BlankRenderer {
+        fn render_pages(&self, _pdf_bytes: &[u8], options: &RenderOptions) -> Result<Vec<RenderedPage>> {
+            let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+            png.extend_from_slice(&2550u32.to_be_bytes());
+            png.extend_from_slice(&3300u32.to_be_bytes());
+            Ok(vec![RenderedPage::from_png(options.first_page.unwrap(), png)?])
+        }
+
+        fn name(&self) -> &str {
+            "blank"
+        }
+    }
+
+    /// Recognizes one line on page 2 only
+    struct FixedOcr;
+
+    impl OcrBackend for FixedOcr {
+        fn recognize(&self, page: &RenderedPage, _dpi: u32) -> Result<Vec<OcrWord>> {
+            if page.page_number != 2 {
+                return Ok(Vec::new());
+            }
+            let word = |text: &str, left: f32| OcrWord {
+                text: text.to_string(),
+                left,
+                top: 300.0,
+                width: 400.0,
+                height: 50.0,
+                confidence: 90.0,
+                block: 1,
+                paragraph: 1,
+                line: 1,
+            };
+            Ok(vec![word("Recognized", 300.0), word("scan", 750.0)])
+        }
+
+        fn name(&self) -> &str {
+            "fixed"
+        }
+    }
+
+    #[test]
+    fn ocr_fills_in_scanned_pages() {
+        let body = "A full paragraph of digital text on this page.";
+        let mut hybrid = output(vec![element(body, 1), element("12", 2), element(body, 3)], 4);
+        let ocr = PageOcr::new(Box::new(BlankRenderer), Box::new(FixedOcr));
+        classify_pages(&mut hybrid, b"%PDF", Some(&ocr));
+
+        let methods: Vec<ExtractionMethod> = hybrid.page_extraction.iter().map(|page| page.method).collect();
+        assert_eq!(
+            methods,
+            vec![
+                ExtractionMethod::TextLayer,
+                ExtractionMethod::Ocr,
+                ExtractionMethod::TextLayer,
+                ExtractionMethod::Missing,
+            ]
+        );
+
+        // The stray "12" is replaced and the OCR line sits between pages 1 and 3
+        let texts: Vec<(u32, &str)> = hybrid
+            .text_elements
+            .iter()
+            .map(|e| (e.page_number, e.text.as_str()))
+            .collect();
+        assert_eq!(texts, vec![(1, body), (2, "Recognized scan"), (3, body)]);
+        let reading_order: Vec<u32> = hybrid.text_elements.iter().map(|e| e.reading_order).collect();
+        assert_eq!(reading_order, vec![0, 1, 2]);
+
+        // Page 4 had nothing to recognize and is still reported
+        assert_eq!(hybrid.warnings.len(), 1);
+        assert_eq!(hybrid.warnings[0].page, Some(4));
+    }
+}
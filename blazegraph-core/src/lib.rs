@@ -7,15 +7,50 @@ pub mod types;
 pub mod preprocessors;
 pub mod processor;
 pub mod graphs;
+pub mod assertions;
+pub mod attachments;
+pub mod audit;
 pub mod cache;
 pub mod config;
+pub mod encryption;
+pub mod estimate;
+pub mod links;
 pub mod rules;
 pub mod classifier;
+pub mod color_rules;
+pub mod page_filter;
+pub mod page_range;
+pub mod span_merge;
+pub mod giant_spans;
+pub mod text_normalization;
+pub mod reading_direction;
+pub mod column_order;
+pub mod auto_minimal;
+pub mod page_order;
+pub mod page_labels;
+pub mod pattern_packs;
+pub mod hybrid;
+pub mod backend_compare;
+pub mod render;
+pub mod retention;
+pub mod stats_db;
+pub mod fixtures;
+pub mod network;
+pub mod ocr;
 pub mod storage;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+pub mod tokenizer;
 
 // Re-export main types and functions for easy use
 pub use types::*;
-pub use preprocessors::{Preprocessor, PdfPreprocessor, TikaPreprocessor};
+pub use preprocessors::{Preprocessor, PdfPreprocessor, RtfPreprocessor, TextElementsPreprocessor, TikaPreprocessor, TxtPreprocessor, XhtmlPreprocessor};
+#[cfg(feature = "office")]
+pub use preprocessors::{DocxPreprocessor, OdtPreprocessor, PptxPreprocessor, SpreadsheetPreprocessor};
+#[cfg(feature = "html")]
+pub use preprocessors::HtmlPreprocessor;
+#[cfg(feature = "markdown")]
+pub use preprocessors::MarkdownPreprocessor;
 pub use processor::{DocumentProcessor, PipelineStages};
 pub use config::ParsingConfig;
 
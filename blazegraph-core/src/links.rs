@@ -0,0 +1,218 @@
+//! Hyperlinks
+//!
+//! Papers, reports and filings link out to sources, standards and data;
+//! citation analysis wants those URLs kept. Links reach the graph two ways:
+//!
+//! - through the XHTML contract: spans inside an `<a href>` (or holding
+//!   one) are the link's anchor, and an `<a href data-bbox>` declares a link
+//!   region on the page, like a PDF link annotation;
+//! - from the PDF itself: URI link annotations are read here (`pdf-links`
+//!   feature), their anchor text taken from the spans under their rectangle.
+//!
+//! Either way they are `PreprocessorOutput::links`, cached with the
+//! extraction, and the graph attaches each to the node holding its anchor
+//! (`DocumentGraph::attach_links`).
+
+use crate::types::{BoundingBox, PageLink, PdfTextElement, PreprocessorOutput};
+use anyhow::Result;
+
+/// URI link annotations of every page, in PDF points from the page's top
+/// left corner; empty for documents that are not PDFs
+#[cfg(feature = "pdf-links")]
+pub fn extract_link_annotations(document_bytes: &[u8]) -> Result<Vec<PageLink>> {
+    use lopdf::{Document, Object, ObjectId};
+
+    if !document_bytes.starts_with(b"%PDF") {
+        return Ok(Vec::new());
+    }
+    let document = Document::load_mem(document_bytes)?;
+
+    // MediaBox is inheritable from the page tree
+    fn media_box(document: &Document, page_id: ObjectId) -> Option<[f32; 4]> {
+        let mut node = document.get_dictionary(page_id).ok()?;
+        for _ in 0..32 {
+            if let Ok(values) = node.get_deref(b"MediaBox", document).and_then(Object::as_array) {
+                let values: Vec<f32> = values.iter().filter_map(|v| v.as_float().ok()).collect();
+                return values.try_into().ok();
+            }
+            node = node.get_deref(b"Parent", document).and_then(Object::as_dict).ok()?;
+        }
+        None
+    }
+
+    let mut links = Vec::new();
+    for (page_number, page_id) in document.get_pages() {
+        let Some([left, bottom, right, top]) = media_box(&document, page_id) else {
+            continue;
+        };
+        let (left, top) = (left.min(right), top.max(bottom));
+        let annotations = document
+            .get_dictionary(page_id)
+            .and_then(|page| page.get_deref(b"Annots", &document))
+            .and_then(Object::as_array)
+            .map(|annotations| annotations.as_slice())
+            .unwrap_or_default();
+        for annotation in annotations {
+            let Ok(annotation) = document.dereference(annotation).and_then(|(_, annotation)| annotation.as_dict()) else {
+                continue;
+            };
+            if !annotation.get(b"Subtype").and_then(Object::as_name).is_ok_and(|subtype| subtype == b"Link") {
+                continue;
+            }
+            let Some(href) = annotation
+                .get_deref(b"A", &document)
+                .and_then(Object::as_dict)
+                .ok()
+                .filter(|action| action.get(b"S").and_then(Object::as_name).is_ok_and(|kind| kind == b"URI"))
+                .and_then(|action| action.get_deref(b"URI", &document).ok())
+                .and_then(|uri| uri.as_str().ok())
+                .map(|uri| String::from_utf8_lossy(uri).trim().to_string())
+                .filter(|uri| !uri.is_empty())
+            else {
+                continue;
+            };
+            let Some::<[f32; 4]>([x1, y1, x2, y2]) = annotation
+                .get_deref(b"Rect", &document)
+                .and_then(Object::as_array)
+                .ok()
+                .and_then(|rect| rect.iter().filter_map(|v| v.as_float().ok()).collect::<Vec<_>>().try_into().ok())
+            else {
+                continue;
+            };
+            links.push(PageLink {
+                page_number,
+                bounding_box: BoundingBox {
+                    x: x1.min(x2) - left,
+                    y: top - y1.max(y2),
+                    width: (x2 - x1).abs(),
+                    height: (y2 - y1).abs(),
+                },
+                href,
+                text: String::new(),
+            });
+        }
+    }
+    Ok(links)
+}
+
+#[cfg(not(feature = "pdf-links"))]
+pub fn extract_link_annotations(_document_bytes: &[u8]) -> Result<Vec<PageLink>> {
+    anyhow::bail!("Reading link annotations needs the `pdf-links` feature")
+}
+
+/// Add link annotations to `output.links` with the text under them as
+/// anchor text, skipping links the XHTML already carried; returns how many
+/// were added
+pub fn add_link_annotations(output: &mut PreprocessorOutput, annotations: Vec<PageLink>) -> usize {
+    let mut added = 0;
+    for mut link in annotations {
+        let known = output.links.iter().any(|known| {
+            known.page_number == link.page_number
+                && known.href == link.href
+                && overlap_area(&known.bounding_box, &link.bounding_box) > 0.0
+        });
+        if known {
+            continue;
+        }
+        link.text = anchor_text(&output.text_elements, &link);
+        output.links.push(link);
+        added += 1;
+    }
+    added
+}
+
+/// The text of the spans under a link's region: the part of each span
+/// whose share of the span's width the region covers, widened to whole words
+pub(crate) fn anchor_text(elements: &[PdfTextElement], link: &PageLink) -> String {
+    let region = &link.bounding_box;
+    let mut under: Vec<&PdfTextElement> = elements
+        .iter()
+        .filter(|e| e.page_number == link.page_number && overlap_area(&e.bounding_box, region) > 0.0)
+        .collect();
+    under.sort_by_key(|e| e.reading_order);
+
+    let mut words = Vec::new();
+    for element in under {
+        let bbox = &element.bounding_box;
+        let chars: Vec<char> = element.text.chars().collect();
+        let fraction = |x: f32| ((x - bbox.x) / bbox.width.max(f32::EPSILON)).clamp(0.0, 1.0);
+        let mut start = (fraction(region.x) * chars.len() as f32).floor() as usize;
+        let mut end = (fraction(region.x + region.width) * chars.len() as f32).ceil() as usize;
+        while start > 0 && !chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        while end < chars.len() && !chars[end].is_whitespace() {
+            end += 1;
+        }
+        let piece: String = chars[start..end.max(start)].iter().collect();
+        words.extend(piece.split_whitespace().map(str::to_string));
+    }
+    words.join(" ")
+}
+
+/// Area of the intersection of two boxes (0 when they don't meet)
+pub(crate) fn overlap_area(a: &BoundingBox, b: &BoundingBox) -> f32 {
+    let width = (a.x + a.width).min(b.x + b.width) - a.x.max(b.x);
+    let height = (a.y + a.height).min(b.y + b.height) - a.y.max(b.y);
+    width.max(0.0) * height.max(0.0)
+}
+
+#[cfg(all(test, feature = "pdf-links"))]
+mod tests {
+    use super::*;
+    use crate::test_utils::{bbox, TextElementBuilder};
+    use lopdf::{dictionary, Document, Object};
+
+    #[test]
+    fn reads_uri_link_annotations_and_their_anchor_text() {
+        let mut document = Document::with_version("1.7");
+        let pages_id = document.new_object_id();
+        let uri = |uri: &str| dictionary! { "S" => "URI", "URI" => Object::string_literal(uri) };
+        let link_id = document.add_object(dictionary! {
+            "Subtype" => "Link",
+            "Rect" => vec![270.into(), 690.into(), 380.into(), 702.into()],
+            "A" => uri("https://example.org/spec"),
+        });
+        let page_id = document.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Annots" => vec![
+                link_id.into(),
+                // Internal links have no URI
+                Object::Dictionary(dictionary! { "Subtype" => "Link", "Rect" => vec![0.into(), 0.into(), 10.into(), 10.into()], "Dest" => Object::Name(b"intro".to_vec()) }),
+            ],
+        });
+        document.objects.insert(pages_id, Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![page_id.into()],
+            "Count" => 1,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+        }));
+        let catalog_id = document.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        document.trailer.set("Root", catalog_id);
+        let mut bytes = Vec::new();
+        document.save_to(&mut bytes).unwrap();
+
+        let links = extract_link_annotations(&bytes).unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].href, "https://example.org/spec");
+        assert_eq!(links[0].bounding_box, bbox(270.0, 90.0, 110.0, 12.0));
+        assert!(extract_link_annotations(b"not a pdf").unwrap().is_empty());
+
+        // "the specification" sits at 270-380 of a line running 100-500
+        let line = "The format follows the specification closely here.";
+        let mut output = PreprocessorOutput {
+            text_elements: vec![TextElementBuilder::new(line).bbox(bbox(100.0, 91.0, 400.0, 10.0)).build()],
+            metadata: Default::default(),
+            style_data: Default::default(),
+            bookmark_data: None,
+            warnings: Vec::new(),
+            page_extraction: Vec::new(),
+            links: Vec::new(),
+        };
+        assert_eq!(add_link_annotations(&mut output, links.clone()), 1);
+        assert_eq!(output.links[0].text, "the specification");
+        // Already known from the XHTML
+        assert_eq!(add_link_annotations(&mut output, links), 0);
+    }
+}
@@ -0,0 +1,204 @@
+//! Network policy for every outbound request
+//!
+//! JRE and Tika JAR downloads on first run, URL inputs and anything else
+//! that reaches the network share one policy, so corporate networks (proxies,
+//! TLS-intercepting gateways, flaky links) are configured once. A policy is a
+//! small YAML file; every field is optional:
+//!
+//! ```yaml
+//! connect_timeout: 10s
+//! read_timeout: 60s
+//! retries: 3          # Attempts after the first, for connection errors, 429 and 5xx
+//! backoff: 1s         # Wait before the first retry, doubling after each
+//! proxy: http://proxy.corp.example:3128
+//! tls:
+//!   ca_bundle: /etc/ssl/corp-root.pem   # Trusted in addition to the built-in roots
+//! ```
+//!
+//! Without `proxy`, the `HTTPS_PROXY` / `ALL_PROXY` environment variables
+//! are honoured. Durations use the retention policy's format ("30s", "2m").
+
+use crate::retention::parse_age;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TlsOptions {
+    /// PEM file of extra root certificates (e.g. a corporate gateway's CA)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkPolicy {
+    /// Time allowed to establish a connection
+    #[serde(default = "default_connect_timeout")]
+    pub connect_timeout: String,
+    /// Time allowed between reads of a response
+    #[serde(default = "default_read_timeout")]
+    pub read_timeout: String,
+    /// Retries after the first attempt, for connection errors, 429 and 5xx
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+    /// Wait before the first retry; doubles after each
+    #[serde(default = "default_backoff")]
+    pub backoff: String,
+    /// Proxy URL (`http://`, `https://`; credentials as `user:pass@host`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    #[serde(default)]
+    pub tls: TlsOptions,
+}
+
+fn default_connect_timeout() -> String {
+    "10s".to_string()
+}
+
+fn default_read_timeout() -> String {
+    "60s".to_string()
+}
+
+fn default_retries() -> u32 {
+    3
+}
+
+fn default_backoff() -> String {
+    "1s".to_string()
+}
+
+impl Default for NetworkPolicy {
+    fn default() -> Self {
+        Self {
+            connect_timeout: default_connect_timeout(),
+            read_timeout: default_read_timeout(),
+            retries: default_retries(),
+            backoff: default_backoff(),
+            proxy: None,
+            tls: TlsOptions::default(),
+        }
+    }
+}
+
+impl NetworkPolicy {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let yaml = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read network policy {}", path.display()))?;
+        let policy: Self = serde_yaml::from_str(&yaml)
+            .with_context(|| format!("Invalid network policy {}", path.display()))?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    /// Check durations parse and the CA bundle exists, so a typo fails at
+    /// startup rather than on the first download
+    pub fn validate(&self) -> Result<()> {
+        self.connect_timeout()?;
+        self.read_timeout()?;
+        self.backoff(0)?;
+        if let Some(ca_bundle) = &self.tls.ca_bundle {
+            anyhow::ensure!(ca_bundle.is_file(), "tls.ca_bundle {} does not exist", ca_bundle.display());
+        }
+        Ok(())
+    }
+
+    pub fn connect_timeout(&self) -> Result<Duration> {
+        parse_age(&self.connect_timeout).context("Invalid connect_timeout")
+    }
+
+    pub fn read_timeout(&self) -> Result<Duration> {
+        parse_age(&self.read_timeout).context("Invalid read_timeout")
+    }
+
+    /// Wait before retry number `retry` (0-based)
+    pub fn backoff(&self, retry: u32) -> Result<Duration> {
+        let base = parse_age(&self.backoff).context("Invalid backoff")?;
+        Ok(base.saturating_mul(1 << retry.min(16)))
+    }
+}
+
+#[cfg(feature = "network")]
+impl NetworkPolicy {
+    /// An HTTP agent with this policy's timeouts, proxy and TLS roots
+    pub fn agent(&self) -> Result<ureq::Agent> {
+        let mut builder = ureq::AgentBuilder::new()
+            .timeout_connect(self.connect_timeout()?)
+            .timeout_read(self.read_timeout()?)
+            .try_proxy_from_env(true)
+            .user_agent(concat!("blazegraph-io/", env!("CARGO_PKG_VERSION")));
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(ureq::Proxy::new(proxy).with_context(|| format!("Invalid proxy '{}'", proxy))?);
+        }
+        if let Some(ca_bundle) = &self.tls.ca_bundle {
+            builder = builder.tls_config(std::sync::Arc::new(tls_config(ca_bundle)?));
+        }
+        Ok(builder.build())
+    }
+
+    /// GET `url`, retrying connection errors, 429 and 5xx responses with
+    /// exponential backoff. Other error statuses fail immediately
+    pub fn get(&self, url: &str) -> Result<ureq::Response> {
+        let agent = self.agent()?;
+        let mut retry = 0;
+        loop {
+            let error = match agent.get(url).call() {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+            let retryable = match &error {
+                ureq::Error::Status(status, _) => *status == 429 || *status >= 500,
+                ureq::Error::Transport(_) => true,
+            };
+            if !retryable || retry >= self.retries {
+                return Err(error).with_context(|| format!("Request to {} failed after {} attempt(s)", url, retry + 1));
+            }
+            let wait = self.backoff(retry)?;
+            println!("🔁 {} ({}), retrying in {:?}", url, error, wait);
+            std::thread::sleep(wait);
+            retry += 1;
+        }
+    }
+}
+
+/// Built-in roots plus every certificate in `ca_bundle`
+#[cfg(feature = "network")]
+fn tls_config(ca_bundle: &Path) -> Result<rustls::ClientConfig> {
+    use rustls::pki_types::{pem::PemObject, CertificateDer};
+
+    let mut roots = rustls::RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let certs = CertificateDer::pem_file_iter(ca_bundle)
+        .with_context(|| format!("Failed to read tls.ca_bundle {}", ca_bundle.display()))?;
+    for cert in certs {
+        let cert = cert.with_context(|| format!("Invalid certificate in {}", ca_bundle.display()))?;
+        roots
+            .add(cert)
+            .with_context(|| format!("Unusable certificate in {}", ca_bundle.display()))?;
+    }
+    Ok(rustls::ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_defaults_fill_unset_fields() {
+        let policy: NetworkPolicy =
+            serde_yaml::from_str("read_timeout: 2m\nretries: 5\nproxy: http://proxy.corp.example:3128\n").unwrap();
+        assert_eq!(policy.connect_timeout().unwrap(), Duration::from_secs(10));
+        assert_eq!(policy.read_timeout().unwrap(), Duration::from_secs(120));
+        assert_eq!(policy.retries, 5);
+        assert_eq!(policy.backoff(0).unwrap(), Duration::from_secs(1));
+        assert_eq!(policy.backoff(3).unwrap(), Duration::from_secs(8));
+        policy.validate().unwrap();
+
+        let typo: NetworkPolicy = serde_yaml::from_str("connect_timeout: 10 seconds\n").unwrap();
+        assert!(typo.validate().is_err());
+    }
+}
@@ -0,0 +1,325 @@
+//! OCR for pages without a text layer
+//!
+//! Scanned pages are rendered with a `PageRenderer`, recognized by an
+//! `OcrBackend`, and the recognized lines become ordinary `PdfTextElement`s
+//! (bounding boxes converted from pixels to PDF points), so everything after
+//! extraction treats them like text-layer output. `hybrid` decides which
+//! pages go through this path.
+//!
+//! The `ocr` feature enables `TesseractOcr`, which drives the `tesseract`
+//! command-line tool; `default_page_ocr()` pairs it with `PopplerRenderer`.
+
+use crate::preprocessors::pdf::xhtml_parser::estimate_token_count;
+use crate::render::{PageRenderer, RenderOptions, RenderedPage};
+use crate::types::*;
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Scans are usually rasterized for OCR at 300 DPI
+pub const OCR_DPI: u32 = 300;
+/// Words recognized with lower confidence (0-100) are dropped as noise
+pub const MIN_WORD_CONFIDENCE: f32 = 30.0;
+/// Font class given to recognized text; OCR can't see the real font
+pub const OCR_FONT_CLASS: &str = "ocr";
+
+/// One recognized word, positioned in image pixels
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcrWord {
+    pub text: String,
+    pub left: f32,
+    pub top: f32,
+    pub width: f32,
+    pub height: f32,
+    /// 0-100
+    pub confidence: f32,
+    /// Layout grouping from the engine: block → paragraph → line
+    pub block: u32,
+    pub paragraph: u32,
+    pub line: u32,
+}
+
+/// Text recognition over a rendered page image
+pub trait OcrBackend: Send + Sync {
+    /// Recognize the words on one page, in the engine's reading order
+    fn recognize(&self, page: &RenderedPage, dpi: u32) -> Result<Vec<OcrWord>>;
+
+    /// Backend identifier for logging
+    fn name(&self) -> &str;
+}
+
+/// Renderer + OCR engine used for pages that have no text layer
+pub struct PageOcr {
+    renderer: Box<dyn PageRenderer>,
+    backend: Box<dyn OcrBackend>,
+    dpi: u32,
+}
+
+impl PageOcr {
+    pub fn new(renderer: Box<dyn PageRenderer>, backend: Box<dyn OcrBackend>) -> Self {
+        Self {
+            renderer,
+            backend,
+            dpi: OCR_DPI,
+        }
+    }
+
+    pub fn with_dpi(mut self, dpi: u32) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        self.backend.name()
+    }
+
+    /// Render and recognize one page of a PDF
+    pub fn recognize_page(&self, pdf_bytes: &[u8], page_number: u32) -> Result<Vec<PdfTextElement>> {
+        let options = RenderOptions {
+            dpi: self.dpi,
+            first_page: Some(page_number),
+            last_page: Some(page_number),
+        };
+        let mut elements = Vec::new();
+        for page in self.renderer.render_pages(pdf_bytes, &options)? {
+            let words: Vec<OcrWord> = self
+                .backend
+                .recognize(&page, self.dpi)?
+                .into_iter()
+                .filter(|word| word.confidence >= MIN_WORD_CONFIDENCE && !word.text.trim().is_empty())
+                .collect();
+            elements.extend(words_to_elements(&words, page.page_number, self.dpi));
+        }
+        Ok(elements)
+    }
+}
+
+/// The OCR pipeline compiled into this build, if any
+pub fn default_page_ocr() -> Option<PageOcr> {
+    #[cfg(feature = "ocr")]
+    {
+        Some(PageOcr::new(
+            Box::new(crate::render::PopplerRenderer::new()),
+            Box::new(TesseractOcr::new()),
+        ))
+    }
+    #[cfg(not(feature = "ocr"))]
+    {
+        None
+    }
+}
+
+/// (block, paragraph, line) as numbered by the OCR engine
+type LineKey = (u32, u32, u32);
+
+/// Join recognized words into one text element per line
+///
+/// Pixel boxes become PDF points (`72 / dpi`). Paragraph numbers are only
+/// unique within the page; `merge_page_elements` renumbers them.
+pub fn words_to_elements(words: &[OcrWord], page_number: u32, dpi: u32) -> Vec<PdfTextElement> {
+    let scale = 72.0 / dpi as f32;
+    let mut lines: Vec<(LineKey, Vec<&OcrWord>)> = Vec::new();
+    for word in words {
+        let key = (word.block, word.paragraph, word.line);
+        match lines.last_mut() {
+            Some((last, line)) if *last == key => line.push(word),
+            _ => lines.push((key, vec![word])),
+        }
+    }
+
+    let mut line_in_paragraph: HashMap<(u32, u32), u32> = HashMap::new();
+    lines
+        .into_iter()
+        .map(|((block, paragraph, _), line)| {
+            let text = line.iter().map(|w| w.text.trim()).collect::<Vec<_>>().join(" ");
+            let left = line.iter().map(|w| w.left).fold(f32::MAX, f32::min);
+            let top = line.iter().map(|w| w.top).fold(f32::MAX, f32::min);
+            let right = line.iter().map(|w| w.left + w.width).fold(0.0, f32::max);
+            let bottom = line.iter().map(|w| w.top + w.height).fold(0.0, f32::max);
+            let height = (bottom - top) * scale;
+
+            let line_number = line_in_paragraph.entry((block, paragraph)).or_insert(0);
+            let element = PdfTextElement {
+                token_count: estimate_token_count(&text),
+                text,
+                style_info: FontClass {
+                    class_name: OCR_FONT_CLASS.to_string(),
+                    font_family: "OCR".to_string(),
+                    // Box height approximates the font size; whole points keep
+                    // the size distribution from fragmenting
+                    font_size: height.round().max(1.0),
+                    font_style: "normal".to_string(),
+                    font_weight: "normal".to_string(),
+                    color: "#000000".to_string(),
+                },
+                bounding_box: BoundingBox {
+                    x: left * scale,
+                    y: top * scale,
+                    width: (right - left) * scale,
+                    height,
+                },
+                page_number,
+                paragraph_number: block * 1000 + paragraph,
+                line_number: *line_number,
+                segment_number: 0,
+                reading_order: 0,
+                bookmark_match: None,
+                paragraph_attributes: HashMap::from([("source".to_string(), "ocr".to_string())]),
+                script: ScriptPosition::Baseline,
+            };
+            *line_number += 1;
+            element
+        })
+        .collect()
+}
+
+/// Add recognized elements to an extraction, keeping pages in order
+///
+/// Paragraph numbers and reading order are renumbered across the document
+/// so they stay globally unique and increasing, as the XHTML parser makes them.
+pub fn merge_page_elements(elements: &mut Vec<PdfTextElement>, recognized: Vec<PdfTextElement>) {
+    if recognized.is_empty() {
+        return;
+    }
+    elements.extend(recognized);
+    elements.sort_by_key(|e| e.page_number);
+
+    let mut paragraphs: HashMap<(u32, u32), u32> = HashMap::new();
+    for (index, element) in elements.iter_mut().enumerate() {
+        let next = paragraphs.len() as u32;
+        element.paragraph_number = *paragraphs
+            .entry((element.page_number, element.paragraph_number))
+            .or_insert(next);
+        element.reading_order = index as u32;
+    }
+}
+
+/// Parse `tesseract ... tsv` output into words (level 5 rows)
+pub fn parse_tesseract_tsv(tsv: &str) -> Vec<OcrWord> {
+    tsv.lines()
+        .skip(1)
+        .filter_map(|row| {
+            let fields: Vec<&str> = row.splitn(12, '\t').collect();
+            if fields.len() < 12 || fields[0] != "5" {
+                return None;
+            }
+            let number = |index: usize| fields[index].trim().parse::<f32>().ok();
+            Some(OcrWord {
+                text: fields[11].to_string(),
+                left: number(6)?,
+                top: number(7)?,
+                width: number(8)?,
+                height: number(9)?,
+                confidence: number(10)?,
+                block: fields[2].parse().ok()?,
+                paragraph: fields[3].parse().ok()?,
+                line: fields[4].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// OCR through the `tesseract` command-line tool
+#[cfg(feature = "ocr")]
+#[derive(Debug, Clone)]
+pub struct TesseractOcr {
+    binary: std::path::PathBuf,
+    language: String,
+}
+
+#[cfg(feature = "ocr")]
+impl Default for TesseractOcr {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "ocr")]
+impl TesseractOcr {
+    /// `tesseract` from `PATH`, English model
+    pub fn new() -> Self {
+        Self {
+            binary: "tesseract".into(),
+            language: "eng".to_string(),
+        }
+    }
+
+    /// Tesseract language spec, e.g. "deu" or "eng+fra"
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = language.into();
+        self
+    }
+
+    pub fn with_binary(mut self, binary: impl Into<std::path::PathBuf>) -> Self {
+        self.binary = binary.into();
+        self
+    }
+}
+
+#[cfg(feature = "ocr")]
+impl OcrBackend for TesseractOcr {
+    fn recognize(&self, page: &RenderedPage, dpi: u32) -> Result<Vec<OcrWord>> {
+        use anyhow::{bail, Context};
+        use std::process::Command;
+
+        let image = std::env::temp_dir().join(format!(
+            "blazegraph-ocr-{}-{}.png",
+            std::process::id(),
+            page.page_number
+        ));
+        std::fs::write(&image, &page.png)?;
+        let output = Command::new(&self.binary)
+            .arg(&image)
+            .arg("stdout")
+            .args(["--dpi", &dpi.to_string(), "-l", &self.language, "tsv"])
+            .output();
+        std::fs::remove_file(&image).ok();
+
+        let output = output.with_context(|| {
+            format!("Failed to run {} (install tesseract-ocr to OCR scanned pages)", self.binary.display())
+        })?;
+        if !output.status.success() {
+            bail!("tesseract failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(parse_tesseract_tsv(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    fn name(&self) -> &str {
+        "TesseractOcr"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TSV: &str = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext
+1\t1\t0\t0\t0\t0\t0\t0\t2550\t3300\t-1\t
+4\t1\t1\t1\t1\t0\t300\t300\t900\t50\t-1\t
+5\t1\t1\t1\t1\t1\t300\t300\t400\t50\t96.5\tScanned
+5\t1\t1\t1\t1\t2\t750\t300\t450\t50\t95.1\tparagraph
+5\t1\t1\t1\t2\t1\t300\t375\t300\t50\t91.0\tcontinues
+5\t1\t2\t1\t1\t1\t300\t600\t200\t50\t12.0\t~~
+";
+
+    #[test]
+    fn tesseract_lines_become_text_elements_in_points() {
+        let words: Vec<OcrWord> = parse_tesseract_tsv(TSV)
+            .into_iter()
+            .filter(|w| w.confidence >= MIN_WORD_CONFIDENCE)
+            .collect();
+        assert_eq!(words.len(), 3);
+
+        let elements = words_to_elements(&words, 2, 300);
+        let texts: Vec<&str> = elements.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, vec!["Scanned paragraph", "continues"]);
+
+        let first = &elements[0];
+        assert_eq!(first.page_number, 2);
+        assert_eq!((first.bounding_box.x, first.bounding_box.y), (72.0, 72.0));
+        assert_eq!(first.bounding_box.width, 216.0);
+        assert_eq!(first.style_info.font_size, 12.0);
+        assert_eq!(first.paragraph_number, elements[1].paragraph_number);
+        assert_eq!((first.line_number, elements[1].line_number), (0, 1));
+        assert_eq!(first.paragraph_attributes.get("source").map(String::as_str), Some("ocr"));
+    }
+}
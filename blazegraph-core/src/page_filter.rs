@@ -0,0 +1,211 @@
+//! Content-based page filters
+//!
+//! Runs between stage 1b (TextElements) and rule processing. Pages are
+//! dropped when their text matches a configured pattern: blank-page notices,
+//! cover pages at the start of the document, or everything after a boundary
+//! heading such as "References". Dropped pages are reported so they can be
+//! recorded in `DocumentInfo`, along with a warning when the filters would
+//! have dropped every page.
+
+use crate::config::PageFilterConfig;
+use crate::types::*;
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Compiled page filter patterns
+pub struct PageFilter {
+    skip_pages_matching: Vec<Regex>,
+    cover_page_patterns: Vec<Regex>,
+    max_cover_pages: usize,
+    skip_after_patterns: Vec<Regex>,
+}
+
+impl PageFilter {
+    /// Compile the patterns from config; returns None when filtering is disabled
+    pub fn from_config(config: &PageFilterConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            skip_pages_matching: compile(&config.skip_pages_matching)?,
+            cover_page_patterns: compile(&config.cover_page_patterns)?,
+            max_cover_pages: config.max_cover_pages,
+            skip_after_patterns: compile(&config.skip_after_patterns)?,
+        }))
+    }
+
+    /// Decide which pages to skip, without modifying the elements
+    pub fn find_skipped_pages(
+        &self,
+        text_elements: &[PdfTextElement],
+        warnings: &mut Vec<ProcessingWarning>,
+    ) -> Vec<SkippedPage> {
+        let mut pages: BTreeMap<u32, Vec<&str>> = BTreeMap::new();
+        for element in text_elements {
+            pages
+                .entry(element.page_number)
+                .or_default()
+                .push(element.text.trim());
+        }
+        let page_texts: Vec<(u32, String)> = pages
+            .into_iter()
+            .map(|(page, texts)| (page, texts.join(" ")))
+            .collect();
+
+        let mut skipped: BTreeMap<u32, SkippedPage> = BTreeMap::new();
+
+        // Cover pages: leading pages only, stopping at the first that doesn't match
+        for (page, text) in page_texts.iter().take(self.max_cover_pages) {
+            match first_match(&self.cover_page_patterns, text) {
+                Some(pattern) => {
+                    skipped.insert(*page, skipped_page(*page, PageSkipReason::CoverPage, pattern));
+                }
+                None => break,
+            }
+        }
+
+        for (page, text) in &page_texts {
+            if let Some(pattern) = first_match(&self.skip_pages_matching, text) {
+                skipped
+                    .entry(*page)
+                    .or_insert_with(|| skipped_page(*page, PageSkipReason::MatchedPattern, pattern));
+            }
+        }
+
+        // Boundary headings are matched per span, not against the whole page
+        let boundary = text_elements.iter().find_map(|element| {
+            first_match(&self.skip_after_patterns, element.text.trim())
+                .map(|pattern| (element.page_number, pattern))
+        });
+        if let Some((boundary_page, pattern)) = boundary {
+            for (page, _) in page_texts.iter().filter(|(page, _)| *page > boundary_page) {
+                skipped
+                    .entry(*page)
+                    .or_insert_with(|| skipped_page(*page, PageSkipReason::AfterBoundary, pattern));
+            }
+        }
+
+        // Never filter a document down to nothing
+        if !page_texts.is_empty() && skipped.len() == page_texts.len() {
+            println!("⚠️  Page filters matched every page - keeping all pages");
+            warnings.push(ProcessingWarning::new(
+                WarningCode::PageFilterOverridden,
+                format!(
+                    "Page filters matched all {} page(s); kept every page instead",
+                    page_texts.len()
+                ),
+            ));
+            return Vec::new();
+        }
+
+        skipped.into_values().collect()
+    }
+
+    /// Remove skipped pages' elements in place and return what was skipped
+    pub fn apply(
+        &self,
+        text_elements: &mut Vec<PdfTextElement>,
+        warnings: &mut Vec<ProcessingWarning>,
+    ) -> Vec<SkippedPage> {
+        let skipped = self.find_skipped_pages(text_elements, warnings);
+        if skipped.is_empty() {
+            return skipped;
+        }
+
+        let pages: BTreeSet<u32> = skipped.iter().map(|s| s.page_number).collect();
+        text_elements.retain(|element| !pages.contains(&element.page_number));
+        println!(
+            "📄 Page filters skipped {} page(s): {:?}",
+            pages.len(),
+            pages
+        );
+        skipped
+    }
+}
+
+/// Apply the configured page filters to preprocessor output
+pub fn filter_pages(
+    text_elements: &mut Vec<PdfTextElement>,
+    config: &PageFilterConfig,
+    warnings: &mut Vec<ProcessingWarning>,
+) -> Result<Vec<SkippedPage>> {
+    Ok(match PageFilter::from_config(config)? {
+        Some(filter) => filter.apply(text_elements, warnings),
+        None => Vec::new(),
+    })
+}
+
+fn compile(patterns: &[String]) -> Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|p| Regex::new(p).with_context(|| format!("Invalid page filter pattern: {}", p)))
+        .collect()
+}
+
+fn first_match<'a>(patterns: &'a [Regex], text: &str) -> Option<&'a Regex> {
+    patterns.iter().find(|re| re.is_match(text))
+}
+
+fn skipped_page(page_number: u32, reason: PageSkipReason, pattern: &Regex) -> SkippedPage {
+    SkippedPage {
+        page_number,
+        reason,
+        pattern: pattern.as_str().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TextElementBuilder;
+
+    fn page(text: &str, page_number: u32) -> PdfTextElement {
+        TextElementBuilder::new(text).page(page_number).build()
+    }
+
+    #[test]
+    fn skips_blank_cover_and_trailing_pages() {
+        let config = PageFilterConfig {
+            cover_page_patterns: vec![r"(?i)confidential".to_string()],
+            skip_after_patterns: vec![r"(?i)^references$".to_string()],
+            ..PageFilterConfig::default()
+        };
+        let mut elements = vec![
+            page("CONFIDENTIAL DRAFT", 1),
+            page("Introduction", 2),
+            page("This page intentionally left blank", 3),
+            page("References", 4),
+            page("[1] Shannon, 1948", 5),
+        ];
+
+        let mut warnings = Vec::new();
+        let skipped = filter_pages(&mut elements, &config, &mut warnings).unwrap();
+        let reasons: Vec<(u32, PageSkipReason)> =
+            skipped.iter().map(|s| (s.page_number, s.reason)).collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (1, PageSkipReason::CoverPage),
+                (3, PageSkipReason::MatchedPattern),
+                (5, PageSkipReason::AfterBoundary),
+            ]
+        );
+        let remaining: Vec<u32> = elements.iter().map(|e| e.page_number).collect();
+        assert_eq!(remaining, vec![2, 4]);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn keeps_document_when_every_page_matches() {
+        let mut elements = vec![page("This page is intentionally blank.", 1)];
+        let mut warnings = Vec::new();
+        let skipped =
+            filter_pages(&mut elements, &PageFilterConfig::default(), &mut warnings).unwrap();
+        assert!(skipped.is_empty());
+        assert_eq!(elements.len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, WarningCode::PageFilterOverridden);
+    }
+}
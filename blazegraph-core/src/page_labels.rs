@@ -0,0 +1,286 @@
+//! Printed page labels
+//!
+//! Front matter is often numbered i, ii, iii before the body restarts at 1,
+//! so the physical page index in node locations differs from the number a
+//! reader sees and cites. Runs after page order repair. A line at the top or
+//! bottom of a page holding only a page number ("xiv", "- 12 -", "Page 3 of
+//! 40") is a candidate label; it is kept when the nearest candidate before
+//! or after it agrees (same style, numbers as far apart as the pages), so a
+//! lone "I" heading or a table cell holding "12" is not mistaken for one.
+//! Pages between two agreeing labels get inferred labels.
+//!
+//! The labels are recorded in `DocumentInfo::page_labels` and copied to each
+//! node's physical location. With `suppress`, the number lines are removed
+//! before the rules run, so they don't end up as stray paragraphs.
+
+use crate::config::PageLabelConfig;
+use crate::page_order::{NUMBER_LINES, PAGE_NUMBER_REGEX};
+use crate::types::*;
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::LazyLock;
+
+static ROMAN_PAGE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(?:page\s+)?[-–—]?\s*([ivxlcdm]{1,8})\s*[-–—]?$").unwrap());
+
+/// A line that could be its page's printed number
+struct Candidate {
+    page: u32,
+    style: PageLabelStyle,
+    value: u32,
+    /// The number as printed
+    label: String,
+    /// Index of the line in the text elements
+    index: usize,
+}
+
+impl Candidate {
+    /// Whether `next` continues this candidate's numbering
+    fn agrees_with(&self, next: &Candidate, max_gap: u32) -> bool {
+        next.page > self.page
+            && next.page - self.page <= max_gap + 1
+            && next.style == self.style
+            && i64::from(next.value) - i64::from(self.value) == i64::from(next.page - self.page)
+    }
+}
+
+/// Detect printed page numbers, removing their lines when configured
+pub fn label_pages(text_elements: &mut Vec<PdfTextElement>, config: &PageLabelConfig) -> Vec<PageLabel> {
+    if !config.enabled {
+        return Vec::new();
+    }
+
+    let candidates = candidates(text_elements);
+    let confirmed: Vec<&Candidate> = candidates
+        .iter()
+        .enumerate()
+        .filter(|(k, candidate)| {
+            (*k > 0 && candidates[k - 1].agrees_with(candidate, config.max_gap))
+                || candidates.get(k + 1).is_some_and(|next| candidate.agrees_with(next, config.max_gap))
+        })
+        .map(|(_, candidate)| candidate)
+        .collect();
+    if confirmed.is_empty() {
+        return Vec::new();
+    }
+
+    let mut labels = Vec::new();
+    for (k, candidate) in confirmed.iter().enumerate() {
+        labels.push(PageLabel {
+            page: candidate.page,
+            label: candidate.label.clone(),
+            style: candidate.style,
+            inferred: false,
+        });
+        let Some(next) = confirmed.get(k + 1).filter(|next| candidate.agrees_with(next, config.max_gap)) else {
+            continue;
+        };
+        let uppercase = candidate.label.chars().any(char::is_uppercase);
+        for page in candidate.page + 1..next.page {
+            let value = candidate.value + (page - candidate.page);
+            labels.push(PageLabel {
+                page,
+                label: match candidate.style {
+                    PageLabelStyle::Arabic => value.to_string(),
+                    PageLabelStyle::Roman if uppercase => to_roman(value).to_uppercase(),
+                    PageLabelStyle::Roman => to_roman(value),
+                },
+                style: candidate.style,
+                inferred: true,
+            });
+        }
+    }
+
+    if config.suppress {
+        let numbers: HashSet<usize> = confirmed.iter().map(|c| c.index).collect();
+        let mut index = 0;
+        text_elements.retain(|_| {
+            index += 1;
+            !numbers.contains(&(index - 1))
+        });
+    }
+    let verb = if config.suppress { "removed" } else { "kept" };
+    println!(
+        "🔢 Page labels for {} pages ({} printed numbers {})",
+        labels.len(),
+        confirmed.len(),
+        verb
+    );
+    labels
+}
+
+/// The page number line of each page that has one, in page order
+fn candidates(text_elements: &[PdfTextElement]) -> Vec<Candidate> {
+    let mut pages: BTreeMap<u32, Vec<usize>> = BTreeMap::new();
+    for (index, element) in text_elements.iter().enumerate() {
+        if !element.text.trim().is_empty() {
+            pages.entry(element.page_number).or_default().push(index);
+        }
+    }
+
+    pages
+        .into_iter()
+        .filter_map(|(page, mut lines)| {
+            lines.sort_by_key(|&i| text_elements[i].reading_order);
+            let bottom = lines.len().saturating_sub(NUMBER_LINES)..lines.len();
+            let top = 0..NUMBER_LINES.min(lines.len()).min(bottom.start);
+            bottom.rev().chain(top).find_map(|position| {
+                let index = lines[position];
+                let (style, value, label) = parse_label(text_elements[index].text.trim())?;
+                Some(Candidate {
+                    page,
+                    style,
+                    value,
+                    label,
+                    index,
+                })
+            })
+        })
+        .collect()
+}
+
+fn parse_label(text: &str) -> Option<(PageLabelStyle, u32, String)> {
+    if let Some(captures) = PAGE_NUMBER_REGEX.captures(text) {
+        return Some((PageLabelStyle::Arabic, captures[1].parse().ok()?, captures[1].to_string()));
+    }
+    let numeral = ROMAN_PAGE_REGEX.captures(text)?.get(1)?.as_str();
+    Some((PageLabelStyle::Roman, roman_value(numeral)?, numeral.to_string()))
+}
+
+/// Value of a well-formed roman numeral ("xiv" → 14); None for "iiii" or "vx"
+fn roman_value(numeral: &str) -> Option<u32> {
+    let lower = numeral.to_lowercase();
+    let digits: Vec<u32> = lower
+        .chars()
+        .map(|c| match c {
+            'i' => Some(1),
+            'v' => Some(5),
+            'x' => Some(10),
+            'l' => Some(50),
+            'c' => Some(100),
+            'd' => Some(500),
+            'm' => Some(1000),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    let value = digits
+        .iter()
+        .enumerate()
+        .map(|(i, &d)| if digits.get(i + 1).is_some_and(|&next| next > d) { -(d as i64) } else { d as i64 })
+        .sum::<i64>();
+    let value = u32::try_from(value).ok().filter(|&v| v > 0)?;
+    (to_roman(value) == lower).then_some(value)
+}
+
+fn to_roman(mut value: u32) -> String {
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+    let mut numeral = String::new();
+    for (step, letters) in NUMERALS {
+        while value >= step {
+            numeral.push_str(letters);
+            value -= step;
+        }
+    }
+    numeral
+}
+
+impl DocumentInfo {
+    /// The printed label of a physical page, if it has one
+    pub fn page_label(&self, page: u32) -> Option<&str> {
+        self.page_labels.iter().find(|l| l.page == page).map(|l| l.label.as_str())
+    }
+}
+
+impl DocumentGraph {
+    /// Copy `document_info.page_labels` onto the nodes' physical locations
+    pub fn apply_page_labels(&mut self) {
+        if self.document_info.page_labels.is_empty() {
+            return;
+        }
+        let labels: BTreeMap<u32, String> =
+            self.document_info.page_labels.iter().map(|l| (l.page, l.label.clone())).collect();
+        for node in self.nodes.values_mut() {
+            if let Some(physical) = node.location.physical.as_mut() {
+                physical.page_label = labels.get(&physical.page).cloned();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TextElementBuilder;
+
+    fn pages(pages: &[&[&str]]) -> Vec<PdfTextElement> {
+        let mut order = 0;
+        let mut elements = Vec::new();
+        for (index, lines) in pages.iter().enumerate() {
+            for (row, line) in lines.iter().enumerate() {
+                elements.push(
+                    TextElementBuilder::new(line)
+                        .page(index as u32 + 1)
+                        .at(72.0, 100.0 + row as f32 * 14.0)
+                        .reading_order(order)
+                        .build(),
+                );
+                order += 1;
+            }
+        }
+        elements
+    }
+
+    #[test]
+    fn roman_front_matter_and_arabic_body_are_labelled() {
+        let mut elements = pages(&[
+            &["Title page"],
+            &["Preface", "The preface text.", "xi"],
+            &["Contents", "xii"],
+            &["Full-page plate"],
+            &["More contents", "xiv"],
+            &["I", "Introduction begins here.", "1"],
+            &["Body text continues.", "- 2 -"],
+            &["Closing remarks.", "Page 3 of 3"],
+        ]);
+        let labels = label_pages(&mut elements, &PageLabelConfig::default());
+
+        let found: Vec<(u32, &str, bool)> = labels.iter().map(|l| (l.page, l.label.as_str(), l.inferred)).collect();
+        assert_eq!(
+            found,
+            [
+                (2, "xi", false),
+                (3, "xii", false),
+                (4, "xiii", true),
+                (5, "xiv", false),
+                (6, "1", false),
+                (7, "2", false),
+                (8, "3", false),
+            ]
+        );
+        assert_eq!(labels[4].style, PageLabelStyle::Arabic);
+        let texts: Vec<&str> = elements.iter().map(|e| e.text.as_str()).collect();
+        assert!(texts.contains(&"I"), "a lone numeral heading is not a page number");
+        assert!(!texts.iter().any(|t| ["xi", "xii", "xiv", "1", "- 2 -", "Page 3 of 3"].contains(t)));
+
+        // Numbers that don't count up with the pages are content
+        let mut table = pages(&[&["Revenue", "12"], &["Costs", "40"]]);
+        assert!(label_pages(&mut table, &PageLabelConfig::default()).is_empty());
+        assert_eq!(table.len(), 4);
+        assert_eq!(roman_value("iiii"), None);
+        assert_eq!(roman_value("MCMXCIV"), Some(1994));
+    }
+}
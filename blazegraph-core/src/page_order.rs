@@ -0,0 +1,258 @@
+//! Out-of-order page repair
+//!
+//! Scans are sometimes fed through the scanner out of order, and the PDF
+//! keeps the pages in the order they were scanned. Runs after column
+//! detection, so each page's first and last lines are in reading order.
+//! Two kinds of evidence are used:
+//!
+//! - **Printed page numbers**: a line at the top or bottom of a page holding
+//!   only a number ("12", "- 12 -", "Page 12 of 40"). When enough pages
+//!   carry one, pages are sorted by it; unnumbered pages stay behind their
+//!   physical predecessor.
+//! - **Sentence continuation**: otherwise, a page ending mid-sentence should
+//!   be followed by a page starting in lowercase. A page that starts
+//!   mid-sentence without following such a page is moved behind one.
+//!
+//! Any reordering found is recorded in `DocumentInfo`. Pages are only
+//! renumbered when the action is `apply`; `suggest` adds a warning instead.
+
+use crate::config::{PageOrderAction, PageOrderConfig};
+use crate::types::*;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::LazyLock;
+
+pub(crate) static PAGE_NUMBER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:page\s+)?[-–—]?\s*(\d{1,4})\s*[-–—]?(?:\s+of\s+\d+)?$").unwrap()
+});
+
+/// How many lines at the top and bottom of a page may hold its printed number
+pub(crate) const NUMBER_LINES: usize = 2;
+
+/// What a page's edges say about its neighbours
+struct PageEdges {
+    page: u32,
+    printed: Option<u32>,
+    /// The last line stops mid-sentence
+    ends_open: bool,
+    /// The first line starts mid-sentence
+    starts_continued: bool,
+}
+
+/// Detect mis-ordered pages, renumbering them when the action is `apply`
+pub fn repair_page_order(
+    text_elements: &mut [PdfTextElement],
+    config: &PageOrderConfig,
+    warnings: &mut Vec<ProcessingWarning>,
+) -> Option<PageReordering> {
+    if !config.enabled {
+        return None;
+    }
+
+    let edges = page_edges(text_elements);
+    let (order, evidence) = match printed_number_order(&edges, config.min_numbered_fraction) {
+        Some(order) => (order, PageOrderEvidence::PrintedPageNumbers),
+        None => (continuation_order(&edges), PageOrderEvidence::SentenceContinuation),
+    };
+    if order.iter().enumerate().all(|(position, &page)| position == page) {
+        return None;
+    }
+
+    let suggested_order: Vec<u32> = order.iter().map(|&position| edges[position].page).collect();
+    let listed = suggested_order.iter().map(u32::to_string).collect::<Vec<_>>().join(", ");
+    let applied = config.action == PageOrderAction::Apply;
+    if applied {
+        renumber(text_elements, &suggested_order);
+        println!("📑 Reordered pages from {:?}: {}", evidence, listed);
+    } else {
+        println!("📑 Pages look out of order ({:?}); suggested order: {}", evidence, listed);
+        warnings.push(ProcessingWarning::new(
+            WarningCode::PagesOutOfOrder,
+            format!("Pages look out of order; suggested reading order: {}", listed),
+        ));
+    }
+
+    Some(PageReordering {
+        suggested_order,
+        evidence,
+        applied,
+    })
+}
+
+/// First and last lines of every page, in physical order
+fn page_edges(text_elements: &[PdfTextElement]) -> Vec<PageEdges> {
+    let mut pages: BTreeMap<u32, Vec<&PdfTextElement>> = BTreeMap::new();
+    for element in text_elements.iter().filter(|e| !e.text.trim().is_empty()) {
+        pages.entry(element.page_number).or_default().push(element);
+    }
+
+    pages
+        .into_iter()
+        .map(|(page, mut lines)| {
+            lines.sort_by_key(|e| e.reading_order);
+            let mut printed = None;
+            let candidates = (lines.len().saturating_sub(NUMBER_LINES)..lines.len()).rev().chain(0..NUMBER_LINES.min(lines.len()));
+            for index in candidates {
+                let number = PAGE_NUMBER_REGEX
+                    .captures(lines[index].text.trim())
+                    .and_then(|captures| captures[1].parse().ok());
+                if number.is_some() {
+                    printed = number;
+                    lines.remove(index);
+                    break;
+                }
+            }
+
+            let first = lines.first().and_then(|e| e.text.trim().chars().next());
+            let last = lines.last().and_then(|e| e.text.trim().chars().last());
+            PageEdges {
+                page,
+                printed,
+                ends_open: last.is_some_and(|c| c.is_alphabetic() || matches!(c, ',' | '-' | '–')),
+                starts_continued: first.is_some_and(char::is_lowercase),
+            }
+        })
+        .collect()
+}
+
+/// Order by printed page numbers, when enough pages carry distinct ones
+fn printed_number_order(edges: &[PageEdges], min_numbered_fraction: f32) -> Option<Vec<usize>> {
+    let numbers: Vec<u32> = edges.iter().filter_map(|e| e.printed).collect();
+    let mut distinct = numbers.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    if numbers.len() < 2
+        || distinct.len() < numbers.len()
+        || (numbers.len() as f32) < edges.len() as f32 * min_numbered_fraction
+    {
+        return None;
+    }
+
+    // Each numbered page carries the unnumbered pages behind it; leading
+    // unnumbered pages (covers) stay in front
+    let mut runs: Vec<(Option<u32>, Vec<usize>)> = Vec::new();
+    for (position, edge) in edges.iter().enumerate() {
+        match (edge.printed, runs.last_mut()) {
+            (None, Some(run)) => run.1.push(position),
+            (printed, _) => runs.push((printed, vec![position])),
+        }
+    }
+    runs.sort_by_key(|(printed, _)| printed.map_or(0, |number| number as u64 + 1));
+    Some(runs.into_iter().flat_map(|(_, run)| run).collect())
+}
+
+/// Move pages that start mid-sentence behind pages left mid-sentence
+fn continuation_order(edges: &[PageEdges]) -> Vec<usize> {
+    let fits = |a: usize, b: usize| edges[a].ends_open && edges[b].starts_continued;
+    let mut order: Vec<usize> = (0..edges.len()).collect();
+    let mut moved = vec![false; edges.len()];
+
+    for i in 0..order.len().saturating_sub(1) {
+        let (dangling, next) = (order[i], order[i + 1]);
+        if !edges[dangling].ends_open || edges[next].starts_continued {
+            continue;
+        }
+        // An orphan: starts mid-sentence, but not behind a page left mid-sentence
+        let orphan = (0..order.len()).find(|&j| {
+            j != i
+                && j != i + 1
+                && !moved[order[j]]
+                && edges[order[j]].starts_continued
+                && (j == 0 || !fits(order[j - 1], order[j]))
+        });
+        if let Some(j) = orphan {
+            let page = order.remove(j);
+            order.insert(if j < i { i } else { i + 1 }, page);
+            moved[page] = true;
+        }
+    }
+    order
+}
+
+/// Give pages their new numbers, keeping the set of page numbers in use
+fn renumber(text_elements: &mut [PdfTextElement], suggested_order: &[u32]) {
+    let mut numbers = suggested_order.to_vec();
+    numbers.sort_unstable();
+    let new_numbers: HashMap<u32, u32> = suggested_order.iter().copied().zip(numbers).collect();
+
+    let mut slots: Vec<u32> = text_elements.iter().map(|e| e.reading_order).collect();
+    slots.sort_unstable();
+    for element in text_elements.iter_mut() {
+        if let Some(&page) = new_numbers.get(&element.page_number) {
+            element.page_number = page;
+        }
+    }
+    text_elements.sort_by_key(|e| (e.page_number, e.reading_order));
+    for (element, slot) in text_elements.iter_mut().zip(slots) {
+        element.reading_order = slot;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TextElementBuilder;
+
+    fn pages(pages: &[&[&str]]) -> Vec<PdfTextElement> {
+        let mut order = 0;
+        let mut elements = Vec::new();
+        for (index, lines) in pages.iter().enumerate() {
+            for (row, line) in lines.iter().enumerate() {
+                elements.push(
+                    TextElementBuilder::new(line)
+                        .page(index as u32 + 1)
+                        .at(72.0, 100.0 + row as f32 * 14.0)
+                        .reading_order(order)
+                        .build(),
+                );
+                order += 1;
+            }
+        }
+        elements
+    }
+
+    #[test]
+    fn detects_misordered_pages_from_numbers_and_continuations() {
+        let mut config = PageOrderConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let mut warnings = Vec::new();
+
+        // Scanned 1, 3, 2: page 1 breaks off mid-sentence, page 2 picks it up
+        let scanned: &[&[&str]] = &[
+            &["Introduction", "The parties agree that the"],
+            &["Payment", "Invoices are due in thirty days."],
+            &["supplier delivers the goods.", "Delivery is free."],
+        ];
+        let mut elements = pages(scanned);
+        let reordering = repair_page_order(&mut elements, &config, &mut warnings).unwrap();
+        assert_eq!(reordering.suggested_order, [1, 3, 2]);
+        assert_eq!(reordering.evidence, PageOrderEvidence::SentenceContinuation);
+        assert!(!reordering.applied);
+        assert_eq!(warnings[0].code, WarningCode::PagesOutOfOrder);
+        assert_eq!(elements[2].page_number, 2, "suggest leaves pages alone");
+
+        // Printed numbers win, and apply renumbers pages and reading order
+        config.action = PageOrderAction::Apply;
+        let numbered: &[&[&str]] = &[
+            &["Cover"],
+            &["Results", "- 3 -"],
+            &["Methods", "Page 2"],
+            &["Summary", "4"],
+            &["Appendix"],
+        ];
+        let mut elements = pages(numbered);
+        let reordering = repair_page_order(&mut elements, &config, &mut Vec::new()).unwrap();
+        assert_eq!(reordering.suggested_order, [1, 3, 2, 4, 5]);
+        assert_eq!(reordering.evidence, PageOrderEvidence::PrintedPageNumbers);
+        let texts: Vec<(u32, &str)> = elements.iter().map(|e| (e.page_number, e.text.as_str())).collect();
+        assert_eq!(texts[1..3], [(2, "Methods"), (2, "Page 2")]);
+        assert_eq!(texts[3], (3, "Results"));
+        assert!(elements.windows(2).all(|w| w[0].reading_order < w[1].reading_order));
+
+        // In-order pages are not reported
+        let mut elements = pages(&[&["One", "1"], &["Two", "2"]]);
+        assert!(repair_page_order(&mut elements, &config, &mut Vec::new()).is_none());
+    }
+}
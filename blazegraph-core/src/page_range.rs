@@ -0,0 +1,153 @@
+//! Page range selection
+//!
+//! Often only part of a long document is wanted: the first chapter of a
+//! 1,000-page filing, or one schedule. `page_range` in the config (or
+//! `--pages 10-25,40` on the command line) keeps only those pages: their
+//! text elements are selected right after extraction, before
+//! normalization, page filters and rules, so everything downstream costs
+//! what the selected pages cost. Pages keep their physical numbers, and the
+//! graph records the range in `DocumentInfo::page_range`.
+//!
+//! Extraction itself still reads the whole document, once: it is cached
+//! (Level 1) independently of the range, so processing other ranges of the
+//! same document starts from the cached extraction.
+
+use crate::types::PdfTextElement;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// 1-based page numbers: comma-separated pages and inclusive ranges, a
+/// range open at the end running to the last page ("10-25,40,100-")
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PageRange {
+    spans: Vec<(u32, Option<u32>)>,
+}
+
+impl PageRange {
+    pub fn contains(&self, page_number: u32) -> bool {
+        self.spans
+            .iter()
+            .any(|&(first, last)| page_number >= first && last.is_none_or(|last| page_number <= last))
+    }
+}
+
+impl FromStr for PageRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let page = |text: &str| -> Result<u32> {
+            let number: u32 = text
+                .trim()
+                .parse()
+                .with_context(|| format!("'{}' is not a page number", text.trim()))?;
+            if number == 0 {
+                bail!("Page numbers start at 1");
+            }
+            Ok(number)
+        };
+        let mut spans = Vec::new();
+        for part in s.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            let span = match part.split_once('-') {
+                Some((first, last)) if last.trim().is_empty() => (page(first)?, None),
+                Some((first, last)) => {
+                    let (first, last) = (page(first)?, page(last)?);
+                    if last < first {
+                        bail!("Page range {} runs backwards", part);
+                    }
+                    (first, Some(last))
+                }
+                None => {
+                    let number = page(part)?;
+                    (number, Some(number))
+                }
+            };
+            spans.push(span);
+        }
+        if spans.is_empty() {
+            bail!("Empty page range '{}'", s);
+        }
+        Ok(Self { spans })
+    }
+}
+
+impl fmt::Display for PageRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self
+            .spans
+            .iter()
+            .map(|&(first, last)| match last {
+                Some(last) if last == first => first.to_string(),
+                Some(last) => format!("{}-{}", first, last),
+                None => format!("{}-", first),
+            })
+            .collect();
+        f.write_str(&parts.join(","))
+    }
+}
+
+impl TryFrom<String> for PageRange {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<PageRange> for String {
+    fn from(range: PageRange) -> Self {
+        range.to_string()
+    }
+}
+
+/// Keep only the text elements on pages in `range`; returns how many pages
+/// were dropped. Fails when the range selects none of the document's pages
+pub fn select_pages(text_elements: &mut Vec<PdfTextElement>, range: Option<&PageRange>) -> Result<usize> {
+    let Some(range) = range else {
+        return Ok(0);
+    };
+    let pages: BTreeSet<u32> = text_elements.iter().map(|e| e.page_number).collect();
+    let dropped = pages.iter().filter(|&&page| !range.contains(page)).count();
+    if dropped == pages.len() && !pages.is_empty() {
+        bail!(
+            "Page range {} selects none of the document's pages (1-{})",
+            range,
+            pages.last().copied().unwrap_or_default()
+        );
+    }
+    if dropped > 0 {
+        text_elements.retain(|e| range.contains(e.page_number));
+        println!("📑 Page range {}: kept {} of {} pages", range, pages.len() - dropped, pages.len());
+    }
+    Ok(dropped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TextElementBuilder;
+
+    #[test]
+    fn selects_listed_pages_and_ranges() {
+        let range: PageRange = "10-25, 40,100-".parse().unwrap();
+        assert!(range.contains(10) && range.contains(25) && range.contains(40) && range.contains(5000));
+        assert!(!range.contains(9) && !range.contains(26) && !range.contains(99));
+        assert_eq!(range.to_string(), "10-25,40,100-");
+        assert_eq!(serde_yaml::from_str::<PageRange>("\"3\"").unwrap().to_string(), "3");
+        for bad in ["", "0", "5-2", "a-3", "1,,x"] {
+            assert!(bad.parse::<PageRange>().is_err(), "{}", bad);
+        }
+
+        let mut elements: Vec<PdfTextElement> = (1..=5u32)
+            .map(|page| TextElementBuilder::new(&format!("Page {}", page)).page(page).build())
+            .collect();
+        assert_eq!(select_pages(&mut elements, None).unwrap(), 0);
+        assert_eq!(select_pages(&mut elements, Some(&"2-3,5".parse().unwrap())).unwrap(), 2);
+        let pages: Vec<u32> = elements.iter().map(|e| e.page_number).collect();
+        assert_eq!(pages, [2, 3, 5]);
+        assert!(select_pages(&mut elements, Some(&"40-".parse().unwrap())).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+//! Localized section-pattern packs
+//!
+//! Heading conventions differ by language and by legal tradition: German
+//! statutes run "Teil 1", "Abschnitt 2", "§ 12"; French codes "Titre II",
+//! "Chapitre premier", "Article L. 121-1". Instead of every config
+//! hand-writing regex lists, a config names a shipped pack
+//! (`pattern_pack: de-legal`) and `SectionDetection` makes lines matching
+//! one of its patterns sections.
+//!
+//! A pattern may carry a heading level ranking it within its pack ("Teil"
+//! above "Abschnitt" above "§"). Only the levels a document actually uses
+//! count, renumbered from 1, so a contract whose only headings are "§ 1",
+//! "§ 2", ... gets them at the top level. Patterns without a level mark
+//! headings and leave the level to font size, like any other section.
+
+use crate::config::ParsingConfig;
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+
+/// Lines longer than this are body text, whatever they start with
+const MAX_HEADING_WORDS: usize = 12;
+
+/// A named set of heading patterns shipped with blazegraph
+pub struct PatternPack {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Packs whose patterns are tried after this pack's own
+    includes: &'static [&'static str],
+    /// (regex, heading level); the first matching pattern wins
+    patterns: &'static [(&'static str, Option<u32>)],
+}
+
+const ROMAN_OR_NUMBER: &str = r"(?:\d+|[ivxlc]+)";
+
+pub const PATTERN_PACKS: &[PatternPack] = &[
+    PatternPack {
+        name: "de",
+        description: "German headings: Kapitel, Teil, Abschnitt, Anhang, Einleitung, ...",
+        includes: &[],
+        patterns: &[
+            (r"(?i)^(?:kapitel|teil|abschnitt)\s+{N}\b", None),
+            (r"(?i)^(?:anhang|anlage)(?:\s+[a-z0-9]+)?\b", None),
+            (
+                r"(?i)^(?:einleitung|einführung|zusammenfassung|fazit|schlussfolgerungen?|literatur(?:verzeichnis)?|inhaltsverzeichnis|danksagung)$",
+                None,
+            ),
+        ],
+    },
+    PatternPack {
+        name: "de-legal",
+        description: "German statutes and contracts: Teil > Abschnitt/Kapitel > Unterabschnitt > § / Artikel",
+        includes: &["de"],
+        patterns: &[
+            (r"(?i)^buch\s+{N}\b", Some(1)),
+            (r"(?i)^teil\s+{N}\b", Some(2)),
+            (r"(?i)^(?:erst|zweit|dritt|viert|fünft|sechst|siebt|acht|neunt|zehnt)er\s+teil\b", Some(2)),
+            (r"(?i)^(?:abschnitt|kapitel)\s+{N}\b", Some(3)),
+            (r"(?i)^(?:erst|zweit|dritt|viert|fünft|sechst|siebt|acht|neunt|zehnt)er\s+(?:abschnitt|kapitel)\b", Some(3)),
+            (r"(?i)^unterabschnitt\s+{N}\b", Some(4)),
+            (r"^§§?\s*\d+[a-z]?\b", Some(5)),
+            (r"(?i)^(?:art\.|artikel)\s*\d+[a-z]?\b", Some(5)),
+        ],
+    },
+    PatternPack {
+        name: "fr",
+        description: "French headings: Chapitre, Partie, Section, Annexe, Introduction, ...",
+        includes: &[],
+        patterns: &[
+            (r"(?i)^(?:chapitre|partie|section)\s+(?:{N}|premier|première)\b", None),
+            (r"(?i)^annexe(?:\s+[a-z0-9]+)?\b", None),
+            (
+                r"(?i)^(?:introduction|conclusions?|résumé|sommaire|table des matières|bibliographie|remerciements)$",
+                None,
+            ),
+        ],
+    },
+    PatternPack {
+        name: "fr-legal",
+        description: "French codes and contracts: Livre > Titre > Chapitre > Section > Article",
+        includes: &["fr"],
+        patterns: &[
+            (r"(?i)^livre\s+(?:{N}|premier)\b", Some(1)),
+            (r"(?i)^titre\s+(?:{N}|premier|préliminaire)\b", Some(2)),
+            (r"(?i)^chapitre\s+(?:{N}|premier|unique)\b", Some(3)),
+            (r"(?i)^section\s+(?:{N}|première|unique)\b", Some(4)),
+            (r"(?i)^(?:article|art\.)\s*(?:premier|1er|[lrd]\.?\s*\d+(?:-\d+)*|\d+(?:-\d+)*)\b", Some(5)),
+        ],
+    },
+    PatternPack {
+        name: "es",
+        description: "Spanish headings: Capítulo, Parte, Sección, Anexo, Introducción, ...",
+        includes: &[],
+        patterns: &[
+            (r"(?i)^(?:capítulo|capitulo|parte|sección|seccion)\s+(?:{N}|primer[oa]?|únic[oa])\b", None),
+            (r"(?i)^(?:anexo|apéndice)(?:\s+[a-z0-9]+)?\b", None),
+            (
+                r"(?i)^(?:introducción|conclusi(?:ón|ones)|resumen|índice|bibliografía|agradecimientos)$",
+                None,
+            ),
+        ],
+    },
+    PatternPack {
+        name: "es-legal",
+        description: "Spanish laws and contracts: Libro > Título > Capítulo > Sección > Artículo",
+        includes: &["es"],
+        patterns: &[
+            (r"(?i)^libro\s+(?:{N}|primero)\b", Some(1)),
+            (r"(?i)^título\s+(?:{N}|preliminar|primero)\b", Some(2)),
+            (r"(?i)^disposici(?:ón|ones)\s+(?:adicional|transitoria|derogatoria|final)", Some(2)),
+            (r"(?i)^(?:capítulo|capitulo)\s+(?:{N}|primero|único)\b", Some(3)),
+            (r"(?i)^(?:sección|seccion)\s+(?:{N}|primera|única)\b", Some(4)),
+            (r"(?i)^(?:artículo|articulo|art\.)\s*\d+(?:\s*(?:bis|ter))?\b", Some(5)),
+        ],
+    },
+    PatternPack {
+        name: "en-legal",
+        description: "English contracts and statutes: Article > Section / § ; Schedules and Exhibits",
+        includes: &[],
+        patterns: &[
+            (r"(?i)^(?:schedule|exhibit|appendix|annex)\s+[a-z0-9]+\b", Some(1)),
+            (r"(?i)^article\s+{N}\b", Some(1)),
+            (r"(?i)^section\s+\d+(?:\.\d+)*\b", Some(2)),
+            (r"^§§?\s*\d+(?:\.\d+)*\b", Some(2)),
+        ],
+    },
+];
+
+/// A shipped pack by name
+pub fn find_pack(name: &str) -> Option<&'static PatternPack> {
+    PATTERN_PACKS.iter().find(|pack| pack.name.eq_ignore_ascii_case(name.trim()))
+}
+
+/// The configured pack's patterns (its includes after its own), compiled
+pub struct SectionPatterns {
+    patterns: Vec<(Regex, Option<u32>)>,
+}
+
+impl SectionPatterns {
+    /// Compile the configured `pattern_pack`; None when no pack is set
+    pub fn from_config(config: &ParsingConfig) -> Result<Option<Self>> {
+        let Some(name) = &config.pattern_pack else {
+            return Ok(None);
+        };
+        let Some(pack) = find_pack(name) else {
+            let known = PATTERN_PACKS.iter().map(|pack| pack.name).collect::<Vec<_>>().join(", ");
+            bail!("Unknown pattern_pack '{}' (available: {})", name, known);
+        };
+
+        let mut patterns = Vec::new();
+        let included = pack.includes.iter().filter_map(|name| find_pack(name));
+        for pack in std::iter::once(pack).chain(included) {
+            for (pattern, level) in pack.patterns {
+                let pattern = pattern.replace("{N}", ROMAN_OR_NUMBER);
+                let regex = Regex::new(&pattern)
+                    .with_context(|| format!("Invalid pattern in pack '{}': {}", pack.name, pattern))?;
+                patterns.push((regex, *level));
+            }
+        }
+        Ok(Some(Self { patterns }))
+    }
+
+    /// Whether a line is a heading under this pack, and at which pack level.
+    /// `Some(None)` is a heading whose level is left to font size
+    pub fn heading_level(&self, text: &str) -> Option<Option<u32>> {
+        let text = text.trim();
+        if text.is_empty() || text.split_whitespace().count() > MAX_HEADING_WORDS {
+            return None;
+        }
+        self.patterns
+            .iter()
+            .find(|(regex, _)| regex.is_match(text))
+            .map(|(_, level)| *level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(pack: &str) -> SectionPatterns {
+        let config = ParsingConfig {
+            pattern_pack: Some(pack.to_string()),
+            ..ParsingConfig::default()
+        };
+        SectionPatterns::from_config(&config).unwrap().unwrap()
+    }
+
+    #[test]
+    fn packs_compile_and_rank_localized_headings() {
+        for pack in PATTERN_PACKS {
+            patterns(pack.name);
+        }
+
+        let de = patterns("de-legal");
+        assert_eq!(de.heading_level("Teil 2"), Some(Some(2)));
+        assert_eq!(de.heading_level("Erster Abschnitt"), Some(Some(3)));
+        assert_eq!(de.heading_level("§ 12 Haftung"), Some(Some(5)));
+        assert_eq!(de.heading_level("Einleitung"), Some(None), "falls back to the de pack");
+        assert_eq!(de.heading_level("Die Parteien vereinbaren Folgendes"), None);
+        assert_eq!(
+            de.heading_level("§ 3 gilt entsprechend für alle Lieferungen, die nach dem Stichtag an den Käufer erfolgen."),
+            None,
+            "sentences are not headings"
+        );
+
+        let fr = patterns("fr-legal");
+        assert_eq!(fr.heading_level("Chapitre premier"), Some(Some(3)));
+        assert_eq!(fr.heading_level("Article L. 121-1"), Some(Some(5)));
+        assert_eq!(patterns("es-legal").heading_level("Artículo 14 bis"), Some(Some(5)));
+        assert_eq!(patterns("en-legal").heading_level("ARTICLE IV"), Some(Some(1)));
+
+        let config = ParsingConfig {
+            pattern_pack: Some("xx-legal".to_string()),
+            ..ParsingConfig::default()
+        };
+        assert!(SectionPatterns::from_config(&config).is_err());
+        assert!(SectionPatterns::from_config(&ParsingConfig::default()).unwrap().is_none());
+    }
+}
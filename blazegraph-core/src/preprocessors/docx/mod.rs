@@ -0,0 +1,407 @@
+//! DOCX Preprocessor
+//!
+//! Parses Word documents (ZIP + OOXML) natively, without the JVM. The
+//! document body is converted to Blazegraph XHTML with `XhtmlWriter` and then
+//! parsed by the shared `xhtml_parser`, so DOCX output goes through the same
+//! pipeline as PDF output.
+//!
+//! Paragraphs whose style resolves to a heading level (`w:outlineLvl` or the
+//! built-in "Heading N" styles) are emitted with `data-role="heading"` and
+//! `data-level`, which section detection uses directly instead of inferring
+//! levels from font sizes.
+
+mod styles;
+
+use crate::preprocessors::ooxml::{self, attr, end_name, local_name, read_part, toggle};
+use crate::preprocessors::pdf::xhtml_parser;
+use crate::preprocessors::traits::Preprocessor;
+use crate::preprocessors::xhtml_writer::{Block, XhtmlWriter};
+use crate::types::*;
+use anyhow::{Context, Result};
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::Reader;
+use std::io::Cursor;
+use std::path::Path;
+use styles::{RunProps, Styles};
+use zip::ZipArchive;
+
+const DOCUMENT_PART: &str = "word/document.xml";
+const STYLES_PART: &str = "word/styles.xml";
+
+/// Word document preprocessor (pure Rust)
+#[derive(Debug, Default)]
+pub struct DocxPreprocessor;
+
+impl DocxPreprocessor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Convert DOCX bytes to Blazegraph XHTML
+    pub fn docx_to_xhtml(&self, docx_bytes: &[u8]) -> Result<String> {
+        let mut archive =
+            ZipArchive::new(Cursor::new(docx_bytes)).context("Not a DOCX file: invalid ZIP container")?;
+
+        let document = read_part(&mut archive, DOCUMENT_PART)?
+            .with_context(|| format!("Not a DOCX file: missing {}", DOCUMENT_PART))?;
+        let styles = match read_part(&mut archive, STYLES_PART)? {
+            Some(xml) => Styles::parse(&xml).context("Failed to parse DOCX styles")?,
+            None => Styles::default(),
+        };
+
+        let mut writer = XhtmlWriter::new();
+        ooxml::write_document_properties(&mut archive, &mut writer)?;
+
+        let mut walker = DocumentWalker::new(&styles, writer);
+        walker.walk(&document).context("Failed to parse DOCX document body")?;
+        let writer = walker.writer;
+
+        println!("📄 DOCX converted: {} page(s)", writer.page_count());
+        Ok(writer.finish())
+    }
+}
+
+impl Preprocessor for DocxPreprocessor {
+    /// Step 1: Convert DOCX to Blazegraph XHTML
+    fn parse_pdf_to_markup_language(&self, docx_bytes: &[u8]) -> Result<String> {
+        self.docx_to_xhtml(docx_bytes)
+    }
+
+    /// Step 2: Parse XHTML to PreprocessorOutput
+    fn parse_markup_to_preprocessor_output(&self, markup: &str) -> Result<PreprocessorOutput> {
+        xhtml_parser::parse_xhtml(markup)
+    }
+
+    fn name(&self) -> &str {
+        "DocxPreprocessor"
+    }
+
+    fn supports_file_type(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("docx"))
+    }
+}
+
+/// Paragraph being built; paragraphs nest inside text boxes, so these stack
+#[derive(Default)]
+struct ParagraphState {
+    block: Block,
+    style_id: Option<String>,
+    outline_level: Option<u32>,
+    is_list_item: bool,
+    /// Page break seen after the paragraph already had text
+    break_after: bool,
+}
+
+/// Streaming walk over `word/document.xml`
+struct DocumentWalker<'a> {
+    styles: &'a Styles,
+    writer: XhtmlWriter,
+    paragraphs: Vec<ParagraphState>,
+    table_depth: usize,
+    in_paragraph_properties: bool,
+    in_run: bool,
+    in_run_properties: bool,
+    in_text: bool,
+    run_style_id: Option<String>,
+    run_props: RunProps,
+}
+
+impl<'a> DocumentWalker<'a> {
+    fn new(styles: &'a Styles, writer: XhtmlWriter) -> Self {
+        Self {
+            styles,
+            writer,
+            paragraphs: Vec::new(),
+            table_depth: 0,
+            in_paragraph_properties: false,
+            in_run: false,
+            in_run_properties: false,
+            in_text: false,
+            run_style_id: None,
+            run_props: RunProps::default(),
+        }
+    }
+
+    fn walk(&mut self, xml: &str) -> Result<()> {
+        let mut reader = Reader::from_str(xml);
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) => self.start(&e, false),
+                Event::Empty(e) => self.start(&e, true),
+                Event::End(e) => self.end(&e),
+                Event::Text(t) if self.in_text => {
+                    let text = t.unescape()?;
+                    self.push_text(&text);
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn start(&mut self, e: &BytesStart, empty: bool) {
+        let name = local_name(e);
+        match name.as_str() {
+            "p" if !empty => {
+                self.paragraphs.push(ParagraphState::default());
+            }
+            "tbl" if !empty => self.table_depth += 1,
+            "pPr" if !empty => self.in_paragraph_properties = true,
+            "pStyle" if self.in_paragraph_properties => {
+                if let Some(paragraph) = self.paragraphs.last_mut() {
+                    paragraph.style_id = attr(e, "val");
+                }
+            }
+            "outlineLvl" if self.in_paragraph_properties => {
+                if let Some(paragraph) = self.paragraphs.last_mut() {
+                    paragraph.outline_level = attr(e, "val").and_then(|v| v.parse().ok());
+                }
+            }
+            "numPr" if self.in_paragraph_properties => {
+                if let Some(paragraph) = self.paragraphs.last_mut() {
+                    paragraph.is_list_item = true;
+                }
+            }
+            "pageBreakBefore" if self.in_paragraph_properties && toggle(e) => self.page_break(),
+            "r" if !empty => {
+                self.in_run = true;
+                self.run_style_id = None;
+                self.run_props = RunProps::default();
+            }
+            "rPr" if self.in_run && !empty => self.in_run_properties = true,
+            "rStyle" if self.in_run_properties => self.run_style_id = attr(e, "val"),
+            _ if self.in_run_properties => self.run_props.apply(e),
+            "t" if self.in_run && !empty => self.in_text = true,
+            "tab" if self.in_run => self.push_text(" "),
+            "br" if self.in_run => {
+                if attr(e, "type").as_deref() == Some("page") {
+                    self.page_break();
+                } else if let Some(paragraph) = self.paragraphs.last_mut() {
+                    paragraph.block.line_break();
+                }
+            }
+            "lastRenderedPageBreak" => self.page_break(),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, e: &BytesEnd) {
+        match end_name(e).as_str() {
+            "p" => self.finish_paragraph(),
+            "tbl" => self.table_depth = self.table_depth.saturating_sub(1),
+            "pPr" => self.in_paragraph_properties = false,
+            "r" => self.in_run = false,
+            "rPr" => self.in_run_properties = false,
+            "t" => self.in_text = false,
+            _ => {}
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let Some(paragraph) = self.paragraphs.last() else {
+            return;
+        };
+        let paragraph_style = self.styles.paragraph_style_or_default(paragraph.style_id.as_deref());
+        let mut props = self.styles.run_props(paragraph_style);
+        if let Some(character_style) = &self.run_style_id {
+            props.overlay(&self.styles.character_props(character_style));
+        }
+        props.overlay(&self.run_props);
+        let style = props.to_run_style();
+
+        if let Some(paragraph) = self.paragraphs.last_mut() {
+            paragraph.block.push_run(text, &style);
+        }
+    }
+
+    /// Break before the current paragraph if it has no text yet, otherwise after it
+    fn page_break(&mut self) {
+        match self.paragraphs.last_mut() {
+            Some(paragraph) if paragraph.block.has_text() => paragraph.break_after = true,
+            _ => self.writer.page_break(),
+        }
+    }
+
+    fn finish_paragraph(&mut self) {
+        let Some(mut paragraph) = self.paragraphs.pop() else {
+            return;
+        };
+        let style_id = self
+            .styles
+            .paragraph_style_or_default(paragraph.style_id.as_deref())
+            .map(str::to_string);
+        let style_name = style_id.as_deref().and_then(|id| self.styles.name(id));
+
+        // Direct outline level on the paragraph wins over the style's
+        let heading_level = match paragraph.outline_level {
+            Some(level) if level < 9 => Some(level + 1),
+            _ => style_id.as_deref().and_then(|id| self.styles.heading_level(id)),
+        };
+
+        let block = &mut paragraph.block;
+        if let Some(level) = heading_level {
+            block.set_attr("role", "heading");
+            block.set_attr("level", level.to_string());
+        } else if style_name.is_some_and(|name| name.eq_ignore_ascii_case("title")) {
+            block.set_attr("role", "title");
+        } else if self.table_depth > 0 {
+            block.set_attr("role", "table-cell");
+        } else if paragraph.is_list_item {
+            block.set_attr("role", "list-item");
+        }
+        if let Some(name) = style_name {
+            block.set_attr("style", name);
+        }
+
+        self.writer.push_block(paragraph.block);
+        if paragraph.break_after {
+            self.writer.page_break();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    const W_NS: &str = "xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\"";
+
+    fn docx(document_body: &str) -> Vec<u8> {
+        let styles = format!(
+            r#"<w:styles {W_NS}>
+  <w:docDefaults><w:rPrDefault><w:rPr><w:rFonts w:ascii="Calibri"/><w:sz w:val="22"/></w:rPr></w:rPrDefault></w:docDefaults>
+  <w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/></w:style>
+  <w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/>
+    <w:pPr><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:b/><w:sz w:val="32"/></w:rPr></w:style>
+  <w:style w:type="paragraph" w:styleId="Heading2"><w:name w:val="heading 2"/><w:basedOn w:val="Heading1"/>
+    <w:pPr><w:outlineLvl w:val="1"/></w:pPr><w:rPr><w:sz w:val="26"/></w:rPr></w:style>
+  <w:style w:type="paragraph" w:styleId="Custom"><w:name w:val="Custom Heading"/><w:basedOn w:val="Heading2"/></w:style>
+</w:styles>"#
+        );
+        let document = format!(r#"<w:document {W_NS}><w:body>{document_body}</w:body></w:document>"#);
+        let core = r#"<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Field Guide &amp; Notes</dc:title><dc:creator>A. Author</dc:creator></cp:coreProperties>"#;
+
+        let mut buffer = Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut buffer);
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        for (name, content) in [
+            (DOCUMENT_PART, document.as_str()),
+            (STYLES_PART, styles.as_str()),
+            (ooxml::CORE_PROPERTIES_PART, core),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        buffer.into_inner()
+    }
+
+    fn paragraph(style: &str, text: &str) -> String {
+        format!(r#"<w:p><w:pPr><w:pStyle w:val="{style}"/></w:pPr><w:r><w:t>{text}</w:t></w:r></w:p>"#)
+    }
+
+    #[test]
+    fn maps_heading_styles_to_levels() {
+        let body = [
+            paragraph("Heading1", "Introduction"),
+            paragraph("Normal", "Body text &lt;with&gt; markup."),
+            paragraph("Heading2", "Background"),
+            paragraph("Custom", "Inherited heading"),
+            r#"<w:p><w:r><w:t xml:space="preserve">Plain </w:t></w:r><w:r><w:rPr><w:i/></w:rPr><w:t>emphasis</w:t></w:r><w:r><w:br w:type="page"/></w:r></w:p>"#.to_string(),
+            paragraph("Heading1", "Second Chapter"),
+        ]
+        .concat();
+
+        let output = DocxPreprocessor::new().process(&docx(&body)).unwrap();
+        let find = |text: &str| {
+            output
+                .text_elements
+                .iter()
+                .find(|e| e.text == text)
+                .unwrap_or_else(|| panic!("missing element {:?}", text))
+        };
+        let level = |text: &str| find(text).paragraph_attributes.get("level").cloned();
+
+        assert_eq!(find("Introduction").paragraph_role(), Some("heading"));
+        assert_eq!(level("Introduction").as_deref(), Some("1"));
+        assert_eq!(level("Background").as_deref(), Some("2"));
+        assert_eq!(level("Inherited heading").as_deref(), Some("2"));
+        assert_eq!(find("Body text <with> markup.").paragraph_role(), None);
+
+        // Style inheritance: Heading2 is bold via Heading1, sized by its own rPr
+        let background = find("Background");
+        assert_eq!(background.style_info.font_size, 13.0);
+        assert!(background.style_info.font_weight.contains("bold"));
+        assert_eq!(find("emphasis").style_info.font_style, "italic");
+        assert_eq!(find("Plain").style_info.font_size, 11.0);
+
+        // Page break after the run pushes the next heading onto page 2
+        assert_eq!(find("emphasis").page_number, 1);
+        assert_eq!(find("Second Chapter").page_number, 2);
+
+        assert_eq!(output.metadata.title.as_deref(), Some("Field Guide & Notes"));
+        assert_eq!(output.metadata.author.as_deref(), Some("A. Author"));
+    }
+
+    #[test]
+    fn declared_heading_levels_drive_section_hierarchy() {
+        use crate::config::{ParsingConfig, PipelineConfig};
+        use crate::processor::DocumentProcessor;
+        use crate::storage::NoOpStorage;
+
+        let body = [
+            paragraph("Heading1", "Chapter One"),
+            paragraph("Normal", "Opening paragraph of the first chapter."),
+            // Same size as body text: only the declared level marks it as a section
+            r#"<w:p><w:pPr><w:outlineLvl w:val="1"/></w:pPr><w:r><w:t>Quiet subsection</w:t></w:r></w:p>"#.to_string(),
+            paragraph("Normal", "Subsection body text."),
+            paragraph("Heading1", "Chapter Two"),
+        ]
+        .concat();
+        let path = std::env::temp_dir().join(format!("blazegraph-docx-{}.docx", std::process::id()));
+        std::fs::write(&path, docx(&body)).unwrap();
+
+        let config = ParsingConfig {
+            pipeline: PipelineConfig::from_rule_names(&["SectionDetection", "SpatialClustering"]),
+            ..ParsingConfig::default()
+        };
+        let mut processor = DocumentProcessor::new_with_dependencies(
+            Box::new(DocxPreprocessor::new()),
+            Box::new(NoOpStorage),
+        )
+        .unwrap();
+        let stages = processor
+            .process_document_capture_stages(path.to_str().unwrap(), &config)
+            .unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let sections: Vec<(&str, u32)> = stages
+            .parsed_elements
+            .iter()
+            .filter(|e| e.element_type == ParsedElementType::Section)
+            .map(|e| (e.text.as_str(), e.hierarchy_level))
+            .collect();
+        assert_eq!(
+            sections,
+            vec![("Chapter One", 1), ("Quiet subsection", 2), ("Chapter Two", 1)]
+        );
+    }
+
+    #[test]
+    fn rejects_archives_without_a_document_part() {
+        let mut buffer = Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut buffer);
+        zip.start_file("readme.txt", SimpleFileOptions::default()).unwrap();
+        zip.finish().unwrap();
+
+        let error = DocxPreprocessor::new()
+            .process(&buffer.into_inner())
+            .unwrap_err();
+        assert!(error.to_string().contains(DOCUMENT_PART));
+    }
+}
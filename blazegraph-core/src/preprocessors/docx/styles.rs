@@ -0,0 +1,242 @@
+//! `word/styles.xml` — style inheritance and heading levels
+//!
+//! Word resolves formatting through layers: document defaults, then the
+//! paragraph style (and everything it is `basedOn`), then the run's
+//! character style, then direct run formatting. Heading levels come from
+//! `w:outlineLvl` on the style chain, or failing that from the built-in
+//! "heading N" style names.
+
+use crate::preprocessors::ooxml::{attr, end_name, local_name, toggle};
+use crate::preprocessors::xhtml_writer::{heading_level_from_name, RunStyle};
+use crate::types::ScriptPosition;
+use anyhow::Result;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+
+/// Word's built-in default when no size is specified anywhere (10pt)
+const DEFAULT_SIZE: f32 = 10.0;
+/// basedOn chains are shallow in practice; this guards against cycles
+const MAX_CHAIN: usize = 16;
+
+/// Run formatting where every property may be unset (inherit)
+#[derive(Debug, Clone, Default)]
+pub struct RunProps {
+    pub family: Option<String>,
+    pub size: Option<f32>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub color: Option<String>,
+    pub script: Option<ScriptPosition>,
+}
+
+impl RunProps {
+    /// Apply one `w:rPr` child element
+    pub fn apply(&mut self, element: &BytesStart) {
+        match local_name(element).as_str() {
+            "rFonts" => {
+                if let Some(family) = attr(element, "ascii").or_else(|| attr(element, "hAnsi")) {
+                    self.family = Some(family);
+                }
+            }
+            "sz" => {
+                if let Some(half_points) = attr(element, "val").and_then(|v| v.parse::<f32>().ok()) {
+                    self.size = Some(half_points / 2.0);
+                }
+            }
+            "b" => self.bold = Some(toggle(element)),
+            "i" => self.italic = Some(toggle(element)),
+            "vertAlign" => {
+                self.script = attr(element, "val").and_then(|v| ScriptPosition::from_attribute(&v));
+            }
+            "color" => {
+                if let Some(color) = attr(element, "val").filter(|c| c != "auto") {
+                    self.color = Some(format!("#{}", color.to_lowercase()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Layer `other` on top of self
+    pub fn overlay(&mut self, other: &RunProps) {
+        if other.family.is_some() {
+            self.family = other.family.clone();
+        }
+        if other.size.is_some() {
+            self.size = other.size;
+        }
+        if other.bold.is_some() {
+            self.bold = other.bold;
+        }
+        if other.italic.is_some() {
+            self.italic = other.italic;
+        }
+        if other.color.is_some() {
+            self.color = other.color.clone();
+        }
+        if other.script.is_some() {
+            self.script = other.script;
+        }
+    }
+
+    pub fn to_run_style(&self) -> RunStyle {
+        let defaults = RunStyle::default();
+        RunStyle {
+            family: self.family.clone().unwrap_or(defaults.family),
+            size: self.size.unwrap_or(DEFAULT_SIZE),
+            bold: self.bold.unwrap_or(false),
+            italic: self.italic.unwrap_or(false),
+            color: self.color.clone().unwrap_or(defaults.color),
+            script: self.script.unwrap_or_default(),
+            link: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct StyleDef {
+    name: String,
+    based_on: Option<String>,
+    outline_level: Option<u32>,
+    run: RunProps,
+}
+
+/// Parsed style sheet
+#[derive(Debug, Default)]
+pub struct Styles {
+    defaults: RunProps,
+    styles: HashMap<String, StyleDef>,
+    default_paragraph_style: Option<String>,
+}
+
+impl Styles {
+    pub fn parse(xml: &str) -> Result<Self> {
+        let mut reader = Reader::from_str(xml);
+        let mut styles = Styles::default();
+
+        let mut current: Option<(String, StyleDef)> = None;
+        let mut in_defaults_rpr = false;
+        let mut in_rpr = false;
+        let mut in_ppr = false;
+
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) | Event::Empty(e) => match local_name(&e).as_str() {
+                    "style" => {
+                        let id = attr(&e, "styleId").unwrap_or_default();
+                        if attr(&e, "type").as_deref() == Some("paragraph") && toggle_attr(&e, "default") {
+                            styles.default_paragraph_style = Some(id.clone());
+                        }
+                        current = Some((id, StyleDef::default()));
+                    }
+                    "rPrDefault" => in_defaults_rpr = true,
+                    "rPr" => in_rpr = true,
+                    "pPr" => in_ppr = true,
+                    "name" => {
+                        if let (Some((_, style)), Some(name)) = (current.as_mut(), attr(&e, "val")) {
+                            style.name = name;
+                        }
+                    }
+                    "basedOn" => {
+                        if let Some((_, style)) = current.as_mut() {
+                            style.based_on = attr(&e, "val");
+                        }
+                    }
+                    "outlineLvl" if in_ppr => {
+                        if let Some((_, style)) = current.as_mut() {
+                            style.outline_level = attr(&e, "val").and_then(|v| v.parse().ok());
+                        }
+                    }
+                    _ if in_rpr => {
+                        if let Some((_, style)) = current.as_mut() {
+                            style.run.apply(&e);
+                        } else if in_defaults_rpr {
+                            styles.defaults.apply(&e);
+                        }
+                    }
+                    _ => {}
+                },
+                Event::End(e) => match end_name(&e).as_str() {
+                    "style" => {
+                        if let Some((id, style)) = current.take() {
+                            styles.styles.insert(id, style);
+                        }
+                    }
+                    "rPrDefault" => in_defaults_rpr = false,
+                    "rPr" => in_rpr = false,
+                    "pPr" => in_ppr = false,
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(styles)
+    }
+
+    /// Style id chain from the given style up through its `basedOn` ancestors
+    fn chain(&self, style_id: &str) -> Vec<&StyleDef> {
+        let mut chain = Vec::new();
+        let mut next = Some(style_id);
+        while let Some(id) = next {
+            let Some(style) = self.styles.get(id) else {
+                break;
+            };
+            chain.push(style);
+            if chain.len() >= MAX_CHAIN {
+                break;
+            }
+            next = style.based_on.as_deref();
+        }
+        chain
+    }
+
+    /// Paragraph style to use when a paragraph names none
+    pub fn paragraph_style_or_default<'a>(&'a self, style_id: Option<&'a str>) -> Option<&'a str> {
+        style_id.or(self.default_paragraph_style.as_deref())
+    }
+
+    pub fn name(&self, style_id: &str) -> Option<&str> {
+        self.styles
+            .get(style_id)
+            .map(|s| s.name.as_str())
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Heading level (1-based) from outline level or a "heading N" name anywhere in the chain
+    pub fn heading_level(&self, style_id: &str) -> Option<u32> {
+        self.chain(style_id).into_iter().find_map(|style| {
+            match style.outline_level {
+                // Level 9 is "body text"
+                Some(level) if level < 9 => Some(level + 1),
+                _ => heading_level_from_name(&style.name),
+            }
+        })
+    }
+
+    /// Document defaults with the style chain layered on top (base styles first)
+    pub fn run_props(&self, style_id: Option<&str>) -> RunProps {
+        let mut props = self.defaults.clone();
+        if let Some(id) = style_id {
+            for style in self.chain(id).into_iter().rev() {
+                props.overlay(&style.run);
+            }
+        }
+        props
+    }
+
+    /// Formatting from a character style chain only (no defaults)
+    pub fn character_props(&self, style_id: &str) -> RunProps {
+        let mut props = RunProps::default();
+        for style in self.chain(style_id).into_iter().rev() {
+            props.overlay(&style.run);
+        }
+        props
+    }
+}
+
+fn toggle_attr(element: &BytesStart, name: &str) -> bool {
+    matches!(attr(element, name).as_deref(), Some("1" | "true" | "on"))
+}
@@ -0,0 +1,540 @@
+//! HTML Preprocessor
+//!
+//! Converts web pages (raw HTML, or fetched by URL with the `html-fetch`
+//! feature) into Blazegraph XHTML with `XhtmlWriter`, then reuses
+//! `xhtml_parser`. The DOM is walked once:
+//!
+//! - `h1`-`h6` → `data-role="heading"` with `data-level`
+//! - `li` → `data-role="list-item"` with `data-list-level`, text prefixed
+//!   with its marker ("• " or "3. "; `ol start` is honoured)
+//! - `pre` → `data-role="code"`, one line per source line, monospace
+//! - `blockquote` → `data-role="quote"`
+//! - `tr` → `data-role="table-row"`, cells joined with " | "
+//! - `p`, `div` and other block containers → plain paragraphs
+//! - `a href` → a hyperlink on the text it wraps (links within the page
+//!   and `javascript:` links are dropped)
+//!
+//! Whitespace is collapsed the way a browser would, except inside `pre`.
+//! `<title>`, `<meta name="author|description">` and `<html lang>` become
+//! document metadata. Like Markdown, HTML has no fixed layout, so graphs are
+//! `FlowType::Free`.
+
+use crate::preprocessors::pdf::xhtml_parser;
+use crate::preprocessors::traits::Preprocessor;
+use crate::preprocessors::xhtml_writer::{heading_size, Block, RunStyle, XhtmlWriter};
+use crate::types::*;
+use anyhow::Result;
+use scraper::{ElementRef, Html, Selector};
+use std::path::Path;
+
+const BODY_SIZE: f32 = 12.0;
+const CODE_SIZE: f32 = 10.0;
+const CODE_FAMILY: &str = "monospace";
+
+/// Elements whose content is never document text
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "svg", "canvas", "iframe", "object",
+];
+
+/// Containers that start and end a paragraph of their own
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address", "article", "aside", "caption", "dd", "details", "div", "dl", "dt", "fieldset",
+    "figcaption", "figure", "footer", "form", "header", "main", "nav", "section", "summary",
+];
+
+/// HTML preprocessor (pure Rust)
+#[derive(Debug, Default)]
+pub struct HtmlPreprocessor;
+
+impl HtmlPreprocessor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Convert an HTML document to Blazegraph XHTML
+    pub fn html_to_xhtml(&self, html: &str) -> String {
+        let document = Html::parse_document(html);
+        let mut walker = HtmlWalker::default();
+
+        walker.write_metadata(&document);
+        let body = selector("body");
+        match document.select(&body).next() {
+            Some(body) => walker.children(body),
+            None => walker.children(document.root_element()),
+        }
+        walker.close_block();
+        walker.writer.finish()
+    }
+
+    /// Download a page for processing
+    #[cfg(feature = "html-fetch")]
+    pub fn fetch(url: &str, network: &crate::network::NetworkPolicy) -> Result<String> {
+        use anyhow::Context;
+
+        println!("🌐 Fetching {}", url);
+        network
+            .get(url)
+            .with_context(|| format!("Failed to fetch {}", url))?
+            .into_string()
+            .with_context(|| format!("Failed to read response body from {}", url))
+    }
+}
+
+impl Preprocessor for HtmlPreprocessor {
+    /// Step 1: Convert HTML to Blazegraph XHTML
+    fn parse_pdf_to_markup_language(&self, html_bytes: &[u8]) -> Result<String> {
+        let html = String::from_utf8_lossy(html_bytes);
+        Ok(self.html_to_xhtml(&html))
+    }
+
+    /// Step 2: Parse XHTML to PreprocessorOutput
+    fn parse_markup_to_preprocessor_output(&self, markup: &str) -> Result<PreprocessorOutput> {
+        xhtml_parser::parse_xhtml(markup)
+    }
+
+    fn name(&self) -> &str {
+        "HtmlPreprocessor"
+    }
+
+    fn supports_file_type(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "html" | "htm" | "xhtml"))
+    }
+
+    fn flow_type(&self) -> FlowType {
+        FlowType::Free
+    }
+}
+
+fn selector(css: &str) -> Selector {
+    Selector::parse(css).expect("static selector is valid")
+}
+
+/// DOM walk state
+#[derive(Default)]
+struct HtmlWalker {
+    writer: XhtmlWriter,
+    block: Option<Block>,
+    heading_level: Option<u32>,
+    /// Next number for each open list (None = bullet list)
+    lists: Vec<Option<u64>>,
+    item_depth: usize,
+    pre_depth: usize,
+    quote_depth: usize,
+    bold: usize,
+    italic: usize,
+    script: ScriptPosition,
+    /// Target of the enclosing `<a href>`
+    link: Option<String>,
+    code: usize,
+    table_cell: usize,
+    /// Collapsed whitespace waiting to be emitted before the next word
+    pending_space: bool,
+}
+
+impl HtmlWalker {
+    fn write_metadata(&mut self, document: &Html) {
+        if let Some(title) = document.select(&selector("title")).next() {
+            let title: String = title.text().collect();
+            self.writer.meta("dc:title", collapse_whitespace(&title));
+        }
+        for meta in document.select(&selector("meta[name][content]")) {
+            let (Some(name), Some(content)) = (meta.value().attr("name"), meta.value().attr("content"))
+            else {
+                continue;
+            };
+            let meta_name = match name.to_lowercase().as_str() {
+                "author" => "dc:creator",
+                "description" => "dc:description",
+                _ => continue,
+            };
+            self.writer.meta(meta_name, content);
+        }
+        if let Some(lang) = document.root_element().value().attr("lang") {
+            self.writer.meta("dc:language", lang);
+        }
+    }
+
+    fn children(&mut self, element: ElementRef) {
+        for child in element.children() {
+            if let Some(child_element) = ElementRef::wrap(child) {
+                self.element(child_element);
+            } else if let Some(text) = child.value().as_text() {
+                self.text(text);
+            }
+        }
+    }
+
+    fn element(&mut self, element: ElementRef) {
+        let name = element.value().name();
+        match name {
+            _ if SKIPPED_ELEMENTS.contains(&name) => {}
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse::<u32>().unwrap_or(1);
+                self.close_block();
+                self.heading_level = Some(level);
+                let block = self.open_block();
+                block.set_attr("role", "heading");
+                block.set_attr("level", level.to_string());
+                self.children(element);
+                self.close_block();
+                self.heading_level = None;
+            }
+            "p" => {
+                self.open_paragraph();
+                self.children(element);
+                if self.item_depth == 0 {
+                    self.close_block();
+                }
+            }
+            "ul" | "ol" => {
+                // A nested list ends the parent item's own text
+                self.close_block();
+                let start = (name == "ol").then(|| {
+                    element
+                        .value()
+                        .attr("start")
+                        .and_then(|s| s.trim().parse().ok())
+                        .unwrap_or(1)
+                });
+                self.lists.push(start);
+                self.children(element);
+                self.close_block();
+                self.lists.pop();
+            }
+            "li" => {
+                self.close_block();
+                self.item_depth += 1;
+                let depth = self.lists.len().max(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        let marker = format!("{}. ", number);
+                        *number += 1;
+                        marker
+                    }
+                    _ => "• ".to_string(),
+                };
+                self.open_list_item(depth);
+                self.push_run(&marker);
+                self.children(element);
+                self.close_block();
+                self.item_depth -= 1;
+            }
+            "pre" => {
+                self.close_block();
+                self.pre_depth += 1;
+                let language = code_language(element);
+                let block = self.open_block();
+                block.set_attr("role", "code");
+                if let Some(language) = language {
+                    block.set_attr("language", language);
+                }
+                self.children(element);
+                self.close_block();
+                self.pre_depth -= 1;
+            }
+            "blockquote" => {
+                self.close_block();
+                self.quote_depth += 1;
+                self.children(element);
+                self.close_block();
+                self.quote_depth -= 1;
+            }
+            "table" | "thead" | "tbody" | "tfoot" => {
+                self.close_block();
+                self.children(element);
+                self.close_block();
+            }
+            "tr" => {
+                self.close_block();
+                self.table_cell = 0;
+                self.open_block().set_attr("role", "table-row");
+                self.children(element);
+                self.close_block();
+            }
+            "td" | "th" => {
+                if self.table_cell > 0 {
+                    self.pending_space = false;
+                    self.push_run(" | ");
+                }
+                let header = name == "th";
+                self.bold += header as usize;
+                self.children(element);
+                self.bold -= header as usize;
+                self.table_cell += 1;
+            }
+            "br" => {
+                if let Some(block) = self.block.as_mut() {
+                    block.line_break();
+                }
+                self.pending_space = false;
+            }
+            "hr" => self.close_block(),
+            "b" | "strong" => {
+                self.bold += 1;
+                self.children(element);
+                self.bold -= 1;
+            }
+            "i" | "em" | "cite" => {
+                self.italic += 1;
+                self.children(element);
+                self.italic -= 1;
+            }
+            "sup" | "sub" => {
+                let outer = self.script;
+                self.script = if name == "sup" {
+                    ScriptPosition::Superscript
+                } else {
+                    ScriptPosition::Subscript
+                };
+                self.children(element);
+                self.script = outer;
+            }
+            "a" => {
+                let href = element
+                    .value()
+                    .attr("href")
+                    .map(str::trim)
+                    .filter(|href| !href.is_empty() && !href.starts_with('#') && !href.starts_with("javascript:"));
+                let outer = self.link.clone();
+                if let Some(href) = href {
+                    self.link = Some(href.to_string());
+                }
+                self.children(element);
+                self.link = outer;
+            }
+            "code" | "kbd" | "samp" | "tt" => {
+                self.code += 1;
+                self.children(element);
+                self.code -= 1;
+            }
+            _ if BLOCK_ELEMENTS.contains(&name) => {
+                if self.item_depth > 0 {
+                    // Block content inside a list item continues the item
+                    self.open_paragraph();
+                    self.children(element);
+                } else {
+                    self.close_block();
+                    self.children(element);
+                    self.close_block();
+                }
+            }
+            _ => self.children(element),
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.pre_depth > 0 {
+            self.push_code(text);
+            return;
+        }
+
+        let starts_with_space = text.starts_with(char::is_whitespace);
+        let ends_with_space = text.ends_with(char::is_whitespace);
+        let collapsed = collapse_whitespace(text);
+        if collapsed.is_empty() {
+            self.pending_space |= starts_with_space;
+            return;
+        }
+
+        let leading = (self.pending_space || starts_with_space) && self.block_has_text();
+        self.pending_space = false;
+        if leading {
+            self.push_run(" ");
+        }
+        self.push_run(&collapsed);
+        self.pending_space = ends_with_space;
+    }
+
+    fn open_block(&mut self) -> &mut Block {
+        self.pending_space = false;
+        self.block.insert(Block::new())
+    }
+
+    /// A `<p>`: its own block, or a new line of the enclosing list item
+    fn open_paragraph(&mut self) {
+        if self.item_depth > 0 {
+            if let Some(block) = self.block.as_mut().filter(|b| b.has_text()) {
+                block.line_break();
+                self.pending_space = false;
+            } else if self.block.is_none() {
+                self.open_item_continuation();
+            }
+        } else {
+            self.close_block();
+            let quote = self.quote_depth > 0;
+            let block = self.open_block();
+            if quote {
+                block.set_attr("role", "quote");
+            }
+        }
+    }
+
+    fn open_list_item(&mut self, depth: usize) {
+        let block = self.open_block();
+        block.set_attr("role", "list-item");
+        block.set_attr("list-level", depth.to_string());
+    }
+
+    /// Text that follows a nested list inside the same item
+    fn open_item_continuation(&mut self) {
+        self.open_list_item(self.lists.len().max(1));
+    }
+
+    fn close_block(&mut self) {
+        if let Some(block) = self.block.take() {
+            self.writer.push_block(block);
+        }
+        self.pending_space = false;
+    }
+
+    fn block_has_text(&self) -> bool {
+        self.block.as_ref().is_some_and(Block::has_text)
+    }
+
+    /// Append already-normalized text, opening a block for loose text if needed
+    fn push_run(&mut self, text: &str) {
+        if self.block.is_none() {
+            if self.item_depth > 0 {
+                self.open_item_continuation();
+            } else if self.quote_depth > 0 {
+                self.open_block().set_attr("role", "quote");
+            } else {
+                self.open_block();
+            }
+        }
+        let style = self.run_style();
+        if let Some(block) = self.block.as_mut() {
+            block.push_run(text, &style);
+        }
+    }
+
+    fn push_code(&mut self, text: &str) {
+        let style = self.run_style();
+        let Some(block) = self.block.as_mut() else {
+            return;
+        };
+        // html5ever already drops the newline directly after <pre>
+        for (index, line) in text.trim_end_matches('\n').split('\n').enumerate() {
+            if index > 0 {
+                block.line_break();
+            }
+            block.push_run(line, &style);
+        }
+        if text.ends_with('\n') {
+            block.line_break();
+        }
+    }
+
+    fn run_style(&self) -> RunStyle {
+        let mut style = RunStyle {
+            size: BODY_SIZE,
+            bold: self.bold > 0,
+            italic: self.italic > 0,
+            script: self.script,
+            link: self.link.clone(),
+            ..RunStyle::default()
+        };
+        if let Some(level) = self.heading_level {
+            style.size = heading_size(level);
+            style.bold = true;
+        }
+        if self.code > 0 || self.pre_depth > 0 {
+            style.family = CODE_FAMILY.to_string();
+            if self.pre_depth > 0 {
+                style.size = CODE_SIZE;
+            }
+        }
+        style
+    }
+}
+
+/// Language from a `language-*`/`lang-*` class on the `<pre>` or its `<code>`
+fn code_language(pre: ElementRef) -> Option<String> {
+    let code = pre.select(&selector("code")).next();
+    std::iter::once(pre)
+        .chain(code)
+        .flat_map(|element| element.value().classes())
+        .find_map(|class| {
+            class
+                .strip_prefix("language-")
+                .or_else(|| class.strip_prefix("lang-"))
+                .map(str::to_string)
+        })
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <title>Install Guide</title>
+  <meta name="author" content="Docs Team">
+  <style>body { color: red; }</style>
+  <script>var ignored = true;</script>
+</head>
+<body>
+  <h1>Getting   Started</h1>
+  <p>Read the <strong>whole</strong> guide
+     before installing.</p>
+  <h2>Steps</h2>
+  <ol start="3">
+    <li>Download</li>
+    <li>Unpack
+      <ul><li>check the checksum</li></ul>
+    </li>
+  </ol>
+  <pre><code class="language-sh">tar xf pkg.tgz
+cd pkg</code></pre>
+  <table>
+    <tr><th>Option</th><th>Meaning</th></tr>
+    <tr><td>-v</td><td>verbose</td></tr>
+  </table>
+  <blockquote><p>Back up first.<sup>1</sup></p></blockquote>
+</body>
+</html>"#;
+
+    #[test]
+    fn maps_dom_to_paragraph_roles() {
+        let output = HtmlPreprocessor::new().process(SAMPLE.as_bytes()).unwrap();
+        let find = |text: &str| {
+            output
+                .text_elements
+                .iter()
+                .find(|e| e.text == text)
+                .unwrap_or_else(|| panic!("missing element {:?}", text))
+        };
+        let attr = |text: &str, name: &str| find(text).paragraph_attributes.get(name).cloned();
+
+        assert_eq!(find("Getting Started").paragraph_level(), Some(1));
+        assert_eq!(find("Steps").paragraph_role(), Some("heading"));
+        assert_eq!(find("Steps").paragraph_level(), Some(2));
+        assert_eq!(find("whole").style_info.font_weight, "bold");
+        assert!(output.text_elements.iter().any(|e| e.text == "guide before installing."));
+
+        assert_eq!(find("3. Download").paragraph_role(), Some("list-item"));
+        assert_eq!(attr("4. Unpack", "list-level").as_deref(), Some("1"));
+        assert_eq!(attr("• check the checksum", "list-level").as_deref(), Some("2"));
+
+        assert_eq!(find("tar xf pkg.tgz").paragraph_role(), Some("code"));
+        assert_eq!(attr("cd pkg", "language").as_deref(), Some("sh"));
+        assert_eq!(find("cd pkg").style_info.font_family, CODE_FAMILY);
+
+        assert_eq!(find("-v | verbose").paragraph_role(), Some("table-row"));
+        assert_eq!(find("Back up first.").paragraph_role(), Some("quote"));
+        assert_eq!(find("1").script, ScriptPosition::Superscript);
+        assert!(find("Back up first.").script.is_baseline());
+        assert!(!output.text_elements.iter().any(|e| e.text.contains("ignored")));
+
+        assert_eq!(output.metadata.title.as_deref(), Some("Install Guide"));
+        assert_eq!(output.metadata.author.as_deref(), Some("Docs Team"));
+        assert_eq!(output.metadata.language.as_deref(), Some("en"));
+    }
+}
@@ -0,0 +1,435 @@
+//! Markdown Preprocessor
+//!
+//! Parses Markdown (CommonMark plus tables, task lists and YAML front matter)
+//! into Blazegraph XHTML with `XhtmlWriter`, then reuses `xhtml_parser`.
+//!
+//! - ATX/setext headings → `data-role="heading"` with `data-level`
+//! - List items → `data-role="list-item"` with `data-list-level`, text
+//!   prefixed with its marker ("• " or "3. ")
+//! - Code blocks → `data-role="code"`, one line per source line, monospace
+//! - Paragraphs, block quotes (`data-role="quote"`) and table rows
+//! - Links → hyperlinks on their text (`[text](url)`; links within the
+//!   document are dropped)
+//!
+//! Markdown has no layout, so the preprocessor reports `FlowType::Free` and
+//! the graph carries no `PhysicalLocation`. The synthetic geometry from the
+//! writer is only used by the rules (e.g. spatial clustering).
+
+use crate::preprocessors::pdf::xhtml_parser;
+use crate::preprocessors::traits::Preprocessor;
+use crate::preprocessors::xhtml_writer::{heading_size, Block, RunStyle, XhtmlWriter};
+use crate::types::*;
+use anyhow::Result;
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use std::path::Path;
+
+const BODY_SIZE: f32 = 12.0;
+const CODE_SIZE: f32 = 10.0;
+const CODE_FAMILY: &str = "monospace";
+
+/// Markdown preprocessor (pure Rust)
+#[derive(Debug, Default)]
+pub struct MarkdownPreprocessor;
+
+impl MarkdownPreprocessor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Convert Markdown source to Blazegraph XHTML
+    pub fn markdown_to_xhtml(&self, markdown: &str) -> String {
+        let options = Options::ENABLE_TABLES
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_TASKLISTS
+            | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS;
+
+        let mut walker = MarkdownWalker::default();
+        for event in Parser::new_ext(markdown, options) {
+            walker.event(event);
+        }
+        walker.close_block();
+        walker.writer.finish()
+    }
+}
+
+impl Preprocessor for MarkdownPreprocessor {
+    /// Step 1: Convert Markdown to Blazegraph XHTML
+    fn parse_pdf_to_markup_language(&self, markdown_bytes: &[u8]) -> Result<String> {
+        let markdown = String::from_utf8_lossy(markdown_bytes);
+        Ok(self.markdown_to_xhtml(&markdown))
+    }
+
+    /// Step 2: Parse XHTML to PreprocessorOutput
+    fn parse_markup_to_preprocessor_output(&self, markup: &str) -> Result<PreprocessorOutput> {
+        xhtml_parser::parse_xhtml(markup)
+    }
+
+    fn name(&self) -> &str {
+        "MarkdownPreprocessor"
+    }
+
+    fn supports_file_type(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "md" | "markdown"))
+    }
+
+    fn flow_type(&self) -> FlowType {
+        FlowType::Free
+    }
+}
+
+/// Event-driven conversion state
+#[derive(Default)]
+struct MarkdownWalker {
+    writer: XhtmlWriter,
+    block: Option<Block>,
+    heading_level: Option<u32>,
+    /// Next number for each open list (None = bullet list)
+    lists: Vec<Option<u64>>,
+    in_item: bool,
+    in_code_block: bool,
+    quote_depth: usize,
+    bold: usize,
+    italic: usize,
+    /// Target of the enclosing link
+    link: Option<String>,
+    table_cell: usize,
+    front_matter: Option<String>,
+}
+
+impl MarkdownWalker {
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => {
+                if let Some(front_matter) = self.front_matter.as_mut() {
+                    front_matter.push_str(&text);
+                } else if self.in_code_block {
+                    self.push_code(&text);
+                } else {
+                    self.push_text(&text, false);
+                }
+            }
+            Event::Code(text) => self.push_text(&text, true),
+            Event::SoftBreak => self.push_text(" ", false),
+            Event::HardBreak => {
+                if let Some(block) = self.block.as_mut() {
+                    block.line_break();
+                }
+            }
+            Event::TaskListMarker(checked) => {
+                self.push_text(if checked { "[x] " } else { "[ ] " }, false)
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.close_block();
+                let level = level as u32;
+                self.heading_level = Some(level);
+                let block = self.open_block();
+                block.set_attr("role", "heading");
+                block.set_attr("level", level.to_string());
+            }
+            Tag::Paragraph => {
+                if self.in_item {
+                    // Loose list items: later paragraphs continue the item
+                    if let Some(block) = self.block.as_mut().filter(|b| b.has_text()) {
+                        block.line_break();
+                    } else if self.block.is_none() {
+                        self.open_item_continuation();
+                    }
+                } else {
+                    self.close_block();
+                    let quote = self.quote_depth > 0;
+                    let block = self.open_block();
+                    if quote {
+                        block.set_attr("role", "quote");
+                    }
+                }
+            }
+            Tag::List(start) => {
+                // A nested list ends the parent item's own text
+                self.close_block();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.close_block();
+                self.in_item = true;
+                let depth = self.lists.len().max(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        let marker = format!("{}. ", number);
+                        *number += 1;
+                        marker
+                    }
+                    _ => "• ".to_string(),
+                };
+                self.open_list_item(depth);
+                self.push_text(&marker, false);
+            }
+            Tag::CodeBlock(kind) => {
+                self.close_block();
+                self.in_code_block = true;
+                let block = self.open_block();
+                block.set_attr("role", "code");
+                if let CodeBlockKind::Fenced(language) = kind {
+                    if !language.is_empty() {
+                        block.set_attr("language", language.to_string());
+                    }
+                }
+            }
+            Tag::BlockQuote(_) => {
+                self.close_block();
+                self.quote_depth += 1;
+            }
+            Tag::TableHead | Tag::TableRow => {
+                self.close_block();
+                self.table_cell = 0;
+                self.open_block().set_attr("role", "table-row");
+            }
+            Tag::TableCell if self.table_cell > 0 => self.push_text(" | ", false),
+            Tag::Emphasis => self.italic += 1,
+            Tag::Strong => self.bold += 1,
+            Tag::Link { dest_url, .. } if !dest_url.starts_with('#') => self.link = Some(dest_url.to_string()),
+            Tag::MetadataBlock(_) => self.front_matter = Some(String::new()),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Heading(_) => {
+                self.close_block();
+                self.heading_level = None;
+            }
+            // Inside list items paragraphs continue the item's block
+            TagEnd::Paragraph if !self.in_item => self.close_block(),
+            TagEnd::Item => {
+                self.close_block();
+                self.in_item = false;
+            }
+            TagEnd::List(_) => {
+                self.close_block();
+                self.lists.pop();
+                // Still inside the parent item after a nested list
+                self.in_item = !self.lists.is_empty();
+            }
+            TagEnd::CodeBlock => {
+                self.close_block();
+                self.in_code_block = false;
+            }
+            TagEnd::BlockQuote(_) => {
+                self.close_block();
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+            }
+            TagEnd::TableHead | TagEnd::TableRow => self.close_block(),
+            TagEnd::TableCell => self.table_cell += 1,
+            TagEnd::Emphasis => self.italic = self.italic.saturating_sub(1),
+            TagEnd::Strong => self.bold = self.bold.saturating_sub(1),
+            TagEnd::Link => self.link = None,
+            TagEnd::MetadataBlock(_) => {
+                if let Some(yaml) = self.front_matter.take() {
+                    self.write_front_matter(&yaml);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn open_block(&mut self) -> &mut Block {
+        self.block.insert(Block::new())
+    }
+
+    fn open_list_item(&mut self, depth: usize) {
+        let block = self.open_block();
+        block.set_attr("role", "list-item");
+        block.set_attr("list-level", depth.to_string());
+    }
+
+    /// Text that follows a nested list inside the same item
+    fn open_item_continuation(&mut self) {
+        self.open_list_item(self.lists.len().max(1));
+    }
+
+    fn close_block(&mut self) {
+        if let Some(block) = self.block.take() {
+            self.writer.push_block(block);
+        }
+    }
+
+    fn push_text(&mut self, text: &str, code: bool) {
+        if self.block.is_none() {
+            if self.in_item {
+                self.open_item_continuation();
+            } else {
+                self.open_block();
+            }
+        }
+        let style = self.run_style(code);
+        if let Some(block) = self.block.as_mut() {
+            block.push_run(text, &style);
+        }
+    }
+
+    fn push_code(&mut self, text: &str) {
+        let style = self.run_style(true);
+        let Some(block) = self.block.as_mut() else {
+            return;
+        };
+        for (index, line) in text.trim_end_matches('\n').split('\n').enumerate() {
+            if index > 0 {
+                block.line_break();
+            }
+            block.push_run(line, &style);
+        }
+        block.line_break();
+    }
+
+    fn run_style(&self, code: bool) -> RunStyle {
+        let mut style = RunStyle {
+            size: BODY_SIZE,
+            bold: self.bold > 0,
+            italic: self.italic > 0,
+            link: self.link.clone(),
+            ..RunStyle::default()
+        };
+        if let Some(level) = self.heading_level {
+            style.size = heading_size(level);
+            style.bold = true;
+        }
+        if code || self.in_code_block {
+            style.family = CODE_FAMILY.to_string();
+            if self.in_code_block {
+                style.size = CODE_SIZE;
+            }
+        }
+        style
+    }
+
+    /// YAML front matter → document metadata
+    fn write_front_matter(&mut self, yaml: &str) {
+        let Ok(serde_yaml::Value::Mapping(fields)) = serde_yaml::from_str(yaml) else {
+            return;
+        };
+        for (key, meta_name) in [
+            ("title", "dc:title"),
+            ("author", "dc:creator"),
+            ("description", "dc:description"),
+            ("lang", "dc:language"),
+            ("language", "dc:language"),
+            ("date", "dcterms:created"),
+        ] {
+            if let Some(value) = fields.get(key).and_then(yaml_scalar) {
+                self.writer.meta(meta_name, value);
+            }
+        }
+    }
+}
+
+fn yaml_scalar(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"---
+title: Field Notes
+author: A. Author
+---
+
+# Overview
+
+Intro paragraph with *emphasis* and `inline code`.
+
+## Setup
+
+1. Install the tool
+2. Run it
+   - nested bullet
+
+```rust
+fn main() {}
+```
+
+> Quoted text
+"#;
+
+    #[test]
+    fn maps_markdown_blocks_to_paragraph_roles() {
+        let output = MarkdownPreprocessor::new()
+            .process(SAMPLE.as_bytes())
+            .unwrap();
+        let find = |text: &str| {
+            output
+                .text_elements
+                .iter()
+                .find(|e| e.text == text)
+                .unwrap_or_else(|| panic!("missing element {:?}", text))
+        };
+        let attr = |text: &str, name: &str| find(text).paragraph_attributes.get(name).cloned();
+
+        assert_eq!(find("Overview").paragraph_role(), Some("heading"));
+        assert_eq!(find("Overview").paragraph_level(), Some(1));
+        assert_eq!(find("Setup").paragraph_level(), Some(2));
+        assert!(find("Overview").style_info.font_size > find("Setup").style_info.font_size);
+
+        assert_eq!(find("emphasis").style_info.font_style, "italic");
+        assert_eq!(find("inline code").style_info.font_family, CODE_FAMILY);
+
+        assert_eq!(find("1. Install the tool").paragraph_role(), Some("list-item"));
+        assert_eq!(attr("2. Run it", "list-level").as_deref(), Some("1"));
+        assert_eq!(attr("• nested bullet", "list-level").as_deref(), Some("2"));
+
+        let code = find("fn main() {}");
+        assert_eq!(code.paragraph_role(), Some("code"));
+        assert_eq!(attr("fn main() {}", "language").as_deref(), Some("rust"));
+
+        assert_eq!(find("Quoted text").paragraph_role(), Some("quote"));
+        assert_eq!(output.metadata.title.as_deref(), Some("Field Notes"));
+        assert_eq!(output.metadata.author.as_deref(), Some("A. Author"));
+    }
+
+    #[test]
+    fn graphs_are_free_flow_without_physical_locations() {
+        use crate::config::{ParsingConfig, PipelineConfig};
+        use crate::processor::DocumentProcessor;
+        use crate::storage::NoOpStorage;
+
+        let path = std::env::temp_dir().join(format!("blazegraph-md-{}.md", std::process::id()));
+        std::fs::write(&path, SAMPLE).unwrap();
+        let config = ParsingConfig {
+            pipeline: PipelineConfig::from_rule_names(&["SectionDetection"]),
+            ..ParsingConfig::default()
+        };
+        let mut processor = DocumentProcessor::new_with_dependencies(
+            Box::new(MarkdownPreprocessor::new()),
+            Box::new(NoOpStorage),
+        )
+        .unwrap();
+        let graph = processor
+            .process_document_with_config(path.to_str().unwrap(), &config)
+            .unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(graph.structural_profile.flow_type, FlowType::Free));
+        assert!(graph.nodes.values().all(|n| n.location.physical.is_none()));
+
+        let setup = graph.nodes.values().find(|n| n.content.text == "Setup").unwrap();
+        assert_eq!(setup.node_type, "Section");
+        assert_eq!(setup.location.semantic.breadcrumbs.last().map(String::as_str), Some("Setup"));
+        assert_eq!(setup.location.semantic.depth, 2);
+    }
+}
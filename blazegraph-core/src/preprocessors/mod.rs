@@ -6,7 +6,7 @@
 //! ## Architecture
 //!
 //! ```text
-//! Document (PDF, DOCX, MD, etc.)
+//! Document (PDF, DOCX, PPTX, MD, etc.)
 //!     ↓
 //! [Format-specific Preprocessor]
 //!     ↓
@@ -20,15 +20,63 @@
 //! ## Available Preprocessors
 //!
 //! - `PdfPreprocessor` - PDF documents via JNI backend (Apache Tika)
-//! - (Future) `MarkdownPreprocessor` - Markdown files
-//! - (Future) `DocxPreprocessor` - Word documents
+//! - `DocxPreprocessor` - Word documents, parsed natively (no JVM)
+//! - `MarkdownPreprocessor` - Markdown files (free-flow, no physical locations)
+//! - `HtmlPreprocessor` - Web pages (free-flow; can fetch URLs with `html-fetch`)
+//! - `TxtPreprocessor` - Plain text, with heuristic heading and list detection
+//! - `PptxPreprocessor` - PowerPoint decks, one section per slide (no JVM)
+//! - `RtfPreprocessor` - Rich Text Format documents (no JVM)
+//! - `OdtPreprocessor` - OpenDocument Text, e.g. from LibreOffice (no JVM)
+//! - `SpreadsheetPreprocessor` - CSV/TSV and XLSX, one Table node per table (no JVM)
+//! - `XhtmlPreprocessor` / `TextElementsPreprocessor` - earlier extractions
+//!   (stage snapshots), for rules-only runs without a PDF backend
+//!
+//! The Word, PowerPoint, ODT and spreadsheet preprocessors need the `office`
+//! feature, the HTML one `html` and the Markdown one `markdown` (all on by
+//! default; the minimal build leaves them out).
+//!
+//! Formats without fixed layout build Blazegraph XHTML with `xhtml_writer`
+//! and reuse the PDF path's `xhtml_parser` for step 2.
 
 pub mod traits;
 pub mod pdf;
+#[cfg(feature = "office")]
+pub mod docx;
+#[cfg(feature = "office")]
+mod ooxml;
+#[cfg(feature = "markdown")]
+pub mod markdown;
+#[cfg(feature = "html")]
+pub mod html;
+pub mod txt;
+#[cfg(feature = "office")]
+pub mod pptx;
+pub mod rtf;
+#[cfg(feature = "office")]
+pub mod odt;
+#[cfg(feature = "office")]
+pub mod spreadsheet;
+pub mod stages;
+pub mod xhtml_writer;
 
 // Re-export main types
-pub use traits::Preprocessor;
-pub use pdf::{PdfPreprocessor, PdfBackend, PdfBackendImpl};
+pub use traits::{Extraction, Preprocessor};
+pub use pdf::{CommandBackend, PdfPreprocessor, PdfBackend, PdfBackendImpl};
+#[cfg(feature = "office")]
+pub use docx::DocxPreprocessor;
+#[cfg(feature = "markdown")]
+pub use markdown::MarkdownPreprocessor;
+#[cfg(feature = "html")]
+pub use html::HtmlPreprocessor;
+pub use txt::TxtPreprocessor;
+#[cfg(feature = "office")]
+pub use pptx::PptxPreprocessor;
+pub use rtf::RtfPreprocessor;
+#[cfg(feature = "office")]
+pub use odt::OdtPreprocessor;
+#[cfg(feature = "office")]
+pub use spreadsheet::SpreadsheetPreprocessor;
+pub use stages::{TextElementsPreprocessor, XhtmlPreprocessor};
 
 // Re-export backends
 #[cfg(feature = "jni-backend")]
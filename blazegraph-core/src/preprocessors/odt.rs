@@ -0,0 +1,601 @@
+//! ODT Preprocessor
+//!
+//! Parses OpenDocument Text (ZIP + ODF XML, as written by LibreOffice)
+//! natively, without the JVM, into Blazegraph XHTML with `XhtmlWriter`:
+//!
+//! - `text:h` becomes `data-role="heading"` with `data-level` from
+//!   `text:outline-level`; paragraphs styled "Heading N" or "Title" are
+//!   recognised by style name, as in DOCX
+//! - Paragraphs inside `text:list` become list items, `data-list-level`
+//!   from the list nesting; paragraphs inside tables become table cells
+//! - Run styles resolve through `style:parent-style-name` chains across
+//!   `styles.xml` and the automatic styles in `content.xml`
+//! - `text:soft-page-break` and `fo:break-before/after="page"` start pages
+//! - `meta.xml` supplies title, author, dates and generator
+//!
+//! Styles are keyed by name only; ODF lets paragraph and text styles share
+//! a name, but LibreOffice never does.
+
+use crate::preprocessors::ooxml::{attr, end_name, leaf_texts, local_name, read_part};
+use crate::preprocessors::pdf::xhtml_parser;
+use crate::preprocessors::traits::Preprocessor;
+use crate::preprocessors::xhtml_writer::{heading_level_from_name, Block, RunStyle, XhtmlWriter};
+use crate::types::*;
+use anyhow::{Context, Result};
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::Path;
+use zip::ZipArchive;
+
+const CONTENT_PART: &str = "content.xml";
+const STYLES_PART: &str = "styles.xml";
+const META_PART: &str = "meta.xml";
+/// LibreOffice's default text size when no style sets one
+const DEFAULT_SIZE: f32 = 12.0;
+/// Parent chains are shallow in practice; this guards against cycles
+const MAX_CHAIN: usize = 16;
+
+/// OpenDocument Text preprocessor (pure Rust)
+#[derive(Debug, Default)]
+pub struct OdtPreprocessor;
+
+impl OdtPreprocessor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Convert ODT bytes to Blazegraph XHTML
+    pub fn odt_to_xhtml(&self, odt_bytes: &[u8]) -> Result<String> {
+        let mut archive =
+            ZipArchive::new(Cursor::new(odt_bytes)).context("Not an ODT file: invalid ZIP container")?;
+
+        let content = read_part(&mut archive, CONTENT_PART)?
+            .with_context(|| format!("Not an ODT file: missing {}", CONTENT_PART))?;
+        let mut styles = OdfStyles::default();
+        if let Some(xml) = read_part(&mut archive, STYLES_PART)? {
+            styles.parse(&xml).context("Failed to parse ODT styles")?;
+        }
+        styles.parse(&content).context("Failed to parse ODT automatic styles")?;
+
+        let mut writer = XhtmlWriter::new();
+        if let Some(xml) = read_part(&mut archive, META_PART)? {
+            write_meta(&xml, &mut writer)?;
+        }
+
+        let mut walker = BodyWalker::new(&styles, writer);
+        walker.walk(&content).context("Failed to parse ODT document body")?;
+        let writer = walker.writer;
+
+        println!("📄 ODT converted: {} page(s)", writer.page_count());
+        Ok(writer.finish())
+    }
+}
+
+impl Preprocessor for OdtPreprocessor {
+    /// Step 1: Convert ODT to Blazegraph XHTML
+    fn parse_pdf_to_markup_language(&self, odt_bytes: &[u8]) -> Result<String> {
+        self.odt_to_xhtml(odt_bytes)
+    }
+
+    /// Step 2: Parse XHTML to PreprocessorOutput
+    fn parse_markup_to_preprocessor_output(&self, markup: &str) -> Result<PreprocessorOutput> {
+        xhtml_parser::parse_xhtml(markup)
+    }
+
+    fn name(&self) -> &str {
+        "OdtPreprocessor"
+    }
+
+    fn supports_file_type(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("odt"))
+    }
+}
+
+/// `meta.xml` → the same `<meta>` names the PDF backends emit
+///
+/// `meta:initial-creator` is the author; `dc:creator` is whoever saved last.
+fn write_meta(xml: &str, writer: &mut XhtmlWriter) -> Result<()> {
+    let leaves = leaf_texts(xml)?;
+    let has_initial_creator = leaves.iter().any(|(name, _)| name == "initial-creator");
+    for (name, text) in leaves {
+        let meta_name = match name.as_str() {
+            "title" => "dc:title",
+            "initial-creator" => "dc:creator",
+            "creator" if !has_initial_creator => "dc:creator",
+            "language" => "dc:language",
+            "description" => "dc:description",
+            "creation-date" => "dcterms:created",
+            "date" => "dcterms:modified",
+            "generator" => "xmp:CreatorTool",
+            _ => continue,
+        };
+        writer.meta(meta_name, text);
+    }
+    Ok(())
+}
+
+/// Text formatting where every property may be unset (inherit)
+#[derive(Debug, Clone, Default)]
+struct TextProps {
+    family: Option<String>,
+    size: Option<f32>,
+    bold: Option<bool>,
+    italic: Option<bool>,
+    color: Option<String>,
+    script: Option<ScriptPosition>,
+}
+
+impl TextProps {
+    /// Read a `style:text-properties` element
+    fn apply(&mut self, element: &BytesStart) {
+        if let Some(family) = attr(element, "font-name").or_else(|| attr(element, "font-family")) {
+            self.family = Some(family.trim_matches('\'').to_string());
+        }
+        // Percentages are relative to the parent; only absolute sizes are kept
+        if let Some(size) = attr(element, "font-size").and_then(|s| s.strip_suffix("pt")?.parse().ok()) {
+            self.size = Some(size);
+        }
+        if let Some(weight) = attr(element, "font-weight") {
+            self.bold = Some(weight == "bold" || weight.parse::<u32>().is_ok_and(|w| w >= 600));
+        }
+        if let Some(style) = attr(element, "font-style") {
+            self.italic = Some(style == "italic" || style == "oblique");
+        }
+        if let Some(color) = attr(element, "color") {
+            self.color = Some(color.to_lowercase());
+        }
+        // "super 58%", "-33% 58%", "0% 100%": the first value is the shift
+        if let Some(position) = attr(element, "text-position") {
+            let shift = position.split_whitespace().next().unwrap_or("");
+            self.script = Some(match shift {
+                "super" => ScriptPosition::Superscript,
+                "sub" => ScriptPosition::Subscript,
+                _ => match shift.trim_end_matches('%').parse::<f32>() {
+                    Ok(percent) if percent > 0.0 => ScriptPosition::Superscript,
+                    Ok(percent) if percent < 0.0 => ScriptPosition::Subscript,
+                    _ => ScriptPosition::Baseline,
+                },
+            });
+        }
+    }
+
+    /// Layer `other` on top of self
+    fn overlay(&mut self, other: &TextProps) {
+        if other.family.is_some() {
+            self.family = other.family.clone();
+        }
+        if other.size.is_some() {
+            self.size = other.size;
+        }
+        if other.bold.is_some() {
+            self.bold = other.bold;
+        }
+        if other.italic.is_some() {
+            self.italic = other.italic;
+        }
+        if other.color.is_some() {
+            self.color = other.color.clone();
+        }
+        if other.script.is_some() {
+            self.script = other.script;
+        }
+    }
+
+    fn to_run_style(&self) -> RunStyle {
+        let defaults = RunStyle::default();
+        RunStyle {
+            family: self.family.clone().unwrap_or(defaults.family),
+            size: self.size.unwrap_or(DEFAULT_SIZE),
+            bold: self.bold.unwrap_or(false),
+            italic: self.italic.unwrap_or(false),
+            color: self.color.clone().unwrap_or(defaults.color),
+            script: self.script.unwrap_or_default(),
+            link: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct StyleDef {
+    display_name: Option<String>,
+    parent: Option<String>,
+    text: TextProps,
+    break_before: bool,
+    break_after: bool,
+}
+
+/// Named and automatic styles from `styles.xml` and `content.xml`
+#[derive(Debug, Default)]
+struct OdfStyles {
+    defaults: TextProps,
+    styles: HashMap<String, StyleDef>,
+}
+
+impl OdfStyles {
+    fn parse(&mut self, xml: &str) -> Result<()> {
+        let mut reader = Reader::from_str(xml);
+        let mut current: Option<(String, StyleDef)> = None;
+        let mut in_paragraph_default = false;
+
+        loop {
+            let (e, empty) = match reader.read_event()? {
+                Event::Start(e) => (e, false),
+                Event::Empty(e) => (e, true),
+                Event::End(e) => {
+                    match end_name(&e).as_str() {
+                        "style" => {
+                            if let Some((name, style)) = current.take() {
+                                self.styles.insert(name, style);
+                            }
+                        }
+                        "default-style" => in_paragraph_default = false,
+                        _ => {}
+                    }
+                    continue;
+                }
+                Event::Eof => break,
+                _ => continue,
+            };
+            match local_name(&e).as_str() {
+                "style" => {
+                    let name = attr(&e, "name").unwrap_or_default();
+                    let style = StyleDef {
+                        display_name: attr(&e, "display-name"),
+                        parent: attr(&e, "parent-style-name"),
+                        ..StyleDef::default()
+                    };
+                    // A self-closed style has no properties of its own
+                    if empty {
+                        self.styles.insert(name, style);
+                    } else {
+                        current = Some((name, style));
+                    }
+                }
+                "default-style" => {
+                    in_paragraph_default = !empty && attr(&e, "family").as_deref() == Some("paragraph")
+                }
+                "text-properties" => match current.as_mut() {
+                    Some((_, style)) => style.text.apply(&e),
+                    None if in_paragraph_default => self.defaults.apply(&e),
+                    None => {}
+                },
+                "paragraph-properties" => {
+                    if let Some((_, style)) = current.as_mut() {
+                        style.break_before = attr(&e, "break-before").as_deref() == Some("page");
+                        style.break_after = attr(&e, "break-after").as_deref() == Some("page");
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// The style followed by its parent-style ancestors
+    fn chain<'a>(&'a self, name: &'a str) -> Vec<(&'a str, &'a StyleDef)> {
+        let mut chain = Vec::new();
+        let mut next = Some(name);
+        while let Some(name) = next {
+            let Some(style) = self.styles.get(name) else {
+                break;
+            };
+            chain.push((name, style));
+            if chain.len() >= MAX_CHAIN {
+                break;
+            }
+            next = style.parent.as_deref();
+        }
+        chain
+    }
+
+    /// Defaults with the style chain layered on top (base styles first)
+    fn text_props(&self, name: Option<&str>) -> TextProps {
+        let mut props = self.defaults.clone();
+        if let Some(name) = name {
+            for (_, style) in self.chain(name).into_iter().rev() {
+                props.overlay(&style.text);
+            }
+        }
+        props
+    }
+
+    /// Formatting from a text style chain only (no defaults)
+    fn span_props(&self, name: &str) -> TextProps {
+        let mut props = TextProps::default();
+        for (_, style) in self.chain(name).into_iter().rev() {
+            props.overlay(&style.text);
+        }
+        props
+    }
+
+    /// Names along the chain, display names first ("Heading 1" over "Heading_20_1")
+    fn names(&self, name: &str) -> Vec<String> {
+        self.chain(name)
+            .into_iter()
+            .map(|(name, style)| {
+                style
+                    .display_name
+                    .clone()
+                    .unwrap_or_else(|| name.replace("_20_", " "))
+            })
+            .collect()
+    }
+
+    fn breaks(&self, name: Option<&str>) -> (bool, bool) {
+        let chain = name.map(|name| self.chain(name)).unwrap_or_default();
+        (
+            chain.iter().any(|(_, style)| style.break_before),
+            chain.iter().any(|(_, style)| style.break_after),
+        )
+    }
+}
+
+/// Paragraph being built; paragraphs nest inside frames and notes, so these stack
+struct ParagraphState {
+    block: Block,
+    style: Option<String>,
+    outline_level: Option<u32>,
+    /// `text:span` styles currently open
+    spans: Vec<Option<String>>,
+}
+
+/// Streaming walk over the `office:text` body of `content.xml`
+struct BodyWalker<'a> {
+    styles: &'a OdfStyles,
+    writer: XhtmlWriter,
+    paragraphs: Vec<ParagraphState>,
+    in_body: bool,
+    list_depth: usize,
+    table_depth: usize,
+    /// Inside an element whose text isn't content (annotations, note citations)
+    skip_depth: usize,
+}
+
+impl<'a> BodyWalker<'a> {
+    fn new(styles: &'a OdfStyles, writer: XhtmlWriter) -> Self {
+        Self {
+            styles,
+            writer,
+            paragraphs: Vec::new(),
+            in_body: false,
+            list_depth: 0,
+            table_depth: 0,
+            skip_depth: 0,
+        }
+    }
+
+    fn walk(&mut self, xml: &str) -> Result<()> {
+        let mut reader = Reader::from_str(xml);
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) => self.start(&e, false),
+                Event::Empty(e) => self.start(&e, true),
+                Event::End(e) => self.end(&e),
+                Event::Text(t) if self.skip_depth == 0 => {
+                    let text = t.unescape()?;
+                    self.push_text(&text);
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn start(&mut self, e: &BytesStart, empty: bool) {
+        let name = local_name(e);
+        if name == "text" && !empty {
+            self.in_body = true;
+        }
+        if !self.in_body {
+            return;
+        }
+        match name.as_str() {
+            "p" | "h" if !empty => {
+                let style = attr(e, "style-name");
+                if self.styles.breaks(style.as_deref()).0 {
+                    self.writer.page_break();
+                }
+                let outline_level = (name == "h")
+                    .then(|| attr(e, "outline-level").and_then(|level| level.parse().ok()).unwrap_or(1));
+                self.paragraphs.push(ParagraphState {
+                    block: Block::new(),
+                    style,
+                    outline_level,
+                    spans: Vec::new(),
+                });
+            }
+            "span" if !empty => {
+                if let Some(paragraph) = self.paragraphs.last_mut() {
+                    paragraph.spans.push(attr(e, "style-name"));
+                }
+            }
+            "list" if !empty => self.list_depth += 1,
+            "table" if !empty => self.table_depth += 1,
+            "annotation" | "note-citation" | "tracked-changes" if !empty => self.skip_depth += 1,
+            "s" => {
+                let count = attr(e, "c").and_then(|c| c.parse().ok()).unwrap_or(1);
+                self.push_text(&" ".repeat(count));
+            }
+            "tab" => self.push_text(" "),
+            "line-break" => {
+                if let Some(paragraph) = self.paragraphs.last_mut() {
+                    paragraph.block.line_break();
+                }
+            }
+            "soft-page-break" => self.page_break(),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, e: &BytesEnd) {
+        if !self.in_body {
+            return;
+        }
+        match end_name(e).as_str() {
+            "text" => self.in_body = false,
+            "p" | "h" => self.finish_paragraph(),
+            "span" => {
+                if let Some(paragraph) = self.paragraphs.last_mut() {
+                    paragraph.spans.pop();
+                }
+            }
+            "list" => self.list_depth = self.list_depth.saturating_sub(1),
+            "table" => self.table_depth = self.table_depth.saturating_sub(1),
+            "annotation" | "note-citation" | "tracked-changes" => self.skip_depth = self.skip_depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.skip_depth > 0 {
+            return;
+        }
+        let Some(paragraph) = self.paragraphs.last_mut() else {
+            return;
+        };
+        let mut props = self.styles.text_props(paragraph.style.as_deref());
+        for span in paragraph.spans.iter().flatten() {
+            props.overlay(&self.styles.span_props(span));
+        }
+        paragraph.block.push_run(text, &props.to_run_style());
+    }
+
+    /// Soft page breaks sit between paragraphs, or inside one that spans pages
+    fn page_break(&mut self) {
+        if self.paragraphs.last().is_none_or(|paragraph| !paragraph.block.has_text()) {
+            self.writer.page_break();
+        }
+    }
+
+    fn finish_paragraph(&mut self) {
+        let Some(mut paragraph) = self.paragraphs.pop() else {
+            return;
+        };
+        let names = paragraph
+            .style
+            .as_deref()
+            .map(|style| self.styles.names(style))
+            .unwrap_or_default();
+        let heading_level = paragraph
+            .outline_level
+            .or_else(|| names.iter().find_map(|name| heading_level_from_name(name)));
+
+        let block = &mut paragraph.block;
+        if let Some(level) = heading_level {
+            block.set_attr("role", "heading");
+            block.set_attr("level", level.to_string());
+        } else if names.iter().any(|name| name.eq_ignore_ascii_case("title")) {
+            block.set_attr("role", "title");
+        } else if self.table_depth > 0 {
+            block.set_attr("role", "table-cell");
+        } else if self.list_depth > 0 {
+            block.set_attr("role", "list-item");
+            block.set_attr("list-level", self.list_depth.to_string());
+        }
+        if let Some(name) = names.first() {
+            block.set_attr("style", name.clone());
+        }
+
+        let break_after = self.styles.breaks(paragraph.style.as_deref()).1;
+        self.writer.push_block(paragraph.block);
+        if break_after {
+            self.writer.page_break();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    const NS: &str = r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0""#;
+
+    fn odt(body: &str) -> Vec<u8> {
+        let styles = format!(
+            r#"<office:document-styles {NS}><office:styles>
+  <style:default-style style:family="paragraph"><style:text-properties fo:font-size="11pt" style:font-name="Liberation Serif"/></style:default-style>
+  <style:style style:name="Standard" style:family="paragraph"/>
+  <style:style style:name="Heading" style:family="paragraph" style:parent-style-name="Standard"><style:text-properties fo:font-size="14pt" fo:font-weight="bold"/></style:style>
+  <style:style style:name="Heading_20_1" style:display-name="Heading 1" style:family="paragraph" style:parent-style-name="Heading"><style:text-properties fo:font-size="18pt"/></style:style>
+  <style:style style:name="Chapter" style:family="paragraph" style:parent-style-name="Heading_20_1"><style:paragraph-properties fo:break-before="page"/></style:style>
+</office:styles></office:document-styles>"#
+        );
+        let content = format!(
+            r#"<office:document-content {NS}><office:automatic-styles>
+  <style:style style:name="T1" style:family="text"><style:text-properties fo:font-style="italic"/></style:style>
+</office:automatic-styles><office:body><office:text>{body}</office:text></office:body></office:document-content>"#
+        );
+        let meta = format!(
+            r#"<office:document-meta {NS}><office:meta><meta:generator>LibreOffice/7.6</meta:generator><dc:title>Field Guide &amp; Notes</dc:title><meta:initial-creator>A. Author</meta:initial-creator><dc:creator>B. Editor</dc:creator></office:meta></office:document-meta>"#
+        );
+
+        let mut buffer = Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut buffer);
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        for (name, content) in [
+            ("mimetype", "application/vnd.oasis.opendocument.text"),
+            (CONTENT_PART, content.as_str()),
+            (STYLES_PART, styles.as_str()),
+            (META_PART, meta.as_str()),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        buffer.into_inner()
+    }
+
+    #[test]
+    fn converts_headings_lists_and_styles() {
+        let body = r#"<text:h text:style-name="Heading_20_1" text:outline-level="1">Introduction</text:h>
+<text:p text:style-name="Standard">Body<text:s/>text with <text:span text:style-name="T1">emphasis</text:span></text:p>
+<text:p text:style-name="Heading_20_1">Styled heading</text:p>
+<text:list><text:list-item><text:p>First item</text:p>
+  <text:list><text:list-item><text:p>Nested item</text:p></text:list-item></text:list>
+</text:list-item></text:list>
+<table:table><table:table-row><table:table-cell><text:p>Cell text</text:p></table:table-cell></table:table-row></table:table>
+<text:h text:style-name="Chapter" text:outline-level="2">Second Chapter</text:h>"#;
+
+        let output = OdtPreprocessor::new().process(&odt(body)).unwrap();
+        let find = |text: &str| {
+            output
+                .text_elements
+                .iter()
+                .find(|e| e.text == text)
+                .unwrap_or_else(|| panic!("missing element {:?}", text))
+        };
+        let attr = |text: &str, name: &str| find(text).paragraph_attributes.get(name).cloned();
+
+        assert_eq!(find("Introduction").paragraph_role(), Some("heading"));
+        assert_eq!(find("Introduction").paragraph_level(), Some(1));
+        assert_eq!(find("Styled heading").paragraph_level(), Some(1));
+        assert_eq!(attr("Introduction", "style").as_deref(), Some("Heading 1"));
+
+        // Style inheritance: bold from "Heading", size from "Heading 1", family from defaults
+        let intro = find("Introduction");
+        assert_eq!(intro.style_info.font_size, 18.0);
+        assert!(intro.style_info.font_weight.contains("bold"));
+        assert_eq!(find("Body text with").style_info.font_size, 11.0);
+        assert_eq!(find("emphasis").style_info.font_style, "italic");
+
+        assert_eq!(find("First item").paragraph_role(), Some("list-item"));
+        assert_eq!(attr("Nested item", "list-level").as_deref(), Some("2"));
+        assert_eq!(find("Cell text").paragraph_role(), Some("table-cell"));
+
+        // The Chapter style breaks the page before its heading
+        assert_eq!(find("Cell text").page_number, 1);
+        assert_eq!(find("Second Chapter").page_number, 2);
+        assert_eq!(find("Second Chapter").paragraph_level(), Some(2));
+
+        assert_eq!(output.metadata.title.as_deref(), Some("Field Guide & Notes"));
+        assert_eq!(output.metadata.author.as_deref(), Some("A. Author"));
+        assert_eq!(output.metadata.creator_tool.as_deref(), Some("LibreOffice/7.6"));
+    }
+}
@@ -0,0 +1,113 @@
+//! Shared helpers for Office Open XML packages (DOCX, PPTX)
+//!
+//! Both formats are ZIP containers of namespaced XML parts with the same
+//! `docProps` metadata, so part access, name/attribute handling and the
+//! document-properties mapping live here. ODT packages are ZIP + XML too and
+//! reuse the part and XML helpers.
+
+use crate::preprocessors::xhtml_writer::XhtmlWriter;
+use anyhow::{Context, Result};
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::Reader;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+pub const CORE_PROPERTIES_PART: &str = "docProps/core.xml";
+pub const APP_PROPERTIES_PART: &str = "docProps/app.xml";
+
+pub type Package<'a> = ZipArchive<Cursor<&'a [u8]>>;
+
+/// Read a part as UTF-8 XML; `None` if the package doesn't contain it
+pub fn read_part(archive: &mut Package, name: &str) -> Result<Option<String>> {
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to open package part {}", name)),
+    };
+    let mut xml = String::new();
+    file.read_to_string(&mut xml)
+        .with_context(|| format!("Failed to read package part {}", name))?;
+    Ok(Some(xml))
+}
+
+/// Write `docProps/core.xml` and `docProps/app.xml` metadata, when present
+pub fn write_document_properties(archive: &mut Package, writer: &mut XhtmlWriter) -> Result<()> {
+    if let Some(xml) = read_part(archive, CORE_PROPERTIES_PART)? {
+        write_core_properties(&xml, writer)?;
+    }
+    if let Some(xml) = read_part(archive, APP_PROPERTIES_PART)? {
+        write_app_properties(&xml, writer)?;
+    }
+    Ok(())
+}
+
+/// `docProps/core.xml` → Dublin Core `<meta>` tags
+fn write_core_properties(xml: &str, writer: &mut XhtmlWriter) -> Result<()> {
+    for (name, text) in leaf_texts(xml)? {
+        let meta_name = match name.as_str() {
+            "title" => "dc:title",
+            "creator" => "dc:creator",
+            "language" => "dc:language",
+            "description" => "dc:description",
+            "created" => "dcterms:created",
+            "modified" => "dcterms:modified",
+            _ => continue,
+        };
+        writer.meta(meta_name, text);
+    }
+    Ok(())
+}
+
+/// `docProps/app.xml` → producing application
+fn write_app_properties(xml: &str, writer: &mut XhtmlWriter) -> Result<()> {
+    for (name, text) in leaf_texts(xml)? {
+        if name == "Application" {
+            writer.meta("xmp:CreatorTool", text);
+        }
+    }
+    Ok(())
+}
+
+/// (local name, text) for every element that directly contains text
+pub fn leaf_texts(xml: &str) -> Result<Vec<(String, String)>> {
+    let mut reader = Reader::from_str(xml);
+    let mut current: Option<String> = None;
+    let mut leaves = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => current = Some(local_name(&e)),
+            Event::Text(t) => {
+                if let Some(name) = &current {
+                    leaves.push((name.clone(), t.unescape()?.into_owned()));
+                }
+            }
+            Event::End(_) => current = None,
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(leaves)
+}
+
+/// Element name without its namespace prefix (`w:p` → `p`)
+pub fn local_name(element: &BytesStart) -> String {
+    String::from_utf8_lossy(element.local_name().as_ref()).into_owned()
+}
+
+pub fn end_name(element: &BytesEnd) -> String {
+    String::from_utf8_lossy(element.local_name().as_ref()).into_owned()
+}
+
+/// Attribute value by local name (`w:val` → `val`)
+pub fn attr(element: &BytesStart, name: &str) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == name.as_bytes())
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+/// OOXML on/off property: present means on unless `w:val` says otherwise
+pub fn toggle(element: &BytesStart) -> bool {
+    !matches!(attr(element, "val").as_deref(), Some("0" | "false" | "off"))
+}
@@ -0,0 +1,121 @@
+//! Shell command backend
+//!
+//! Runs an external extractor as a shell command: the PDF is written to its
+//! stdin and Blazegraph XHTML is read from its stdout. Lets a candidate
+//! backend (a pure-Rust extractor, a wrapper around an extraction server)
+//! be tried and compared before it is built in.
+
+use super::PdfBackend;
+use anyhow::{Context, Result};
+use std::io::{self, Read, Write};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::thread::JoinHandle;
+
+pub struct CommandBackend {
+    command: String,
+    /// "cmd:<command>", for logging and comparison reports
+    name: String,
+}
+
+impl CommandBackend {
+    pub fn new(command: impl Into<String>) -> Self {
+        let command = command.into();
+        Self {
+            name: format!("cmd:{}", command),
+            command,
+        }
+    }
+}
+
+impl PdfBackend for CommandBackend {
+    fn extract_to_xhtml(&self, pdf_bytes: &[u8]) -> Result<String> {
+        let mut output = Vec::new();
+        self.extract_to_reader(pdf_bytes)?.read_to_end(&mut output)?;
+        String::from_utf8(output).with_context(|| format!("Backend command `{}` printed invalid UTF-8", self.command))
+    }
+
+    /// Stream the command's stdout; its exit status is checked once stdout ends
+    fn extract_to_reader(&self, pdf_bytes: &[u8]) -> Result<Box<dyn Read + '_>> {
+        #[cfg(windows)]
+        let (shell, flag) = ("cmd", "/C");
+        #[cfg(not(windows))]
+        let (shell, flag) = ("sh", "-c");
+        let mut child = Command::new(shell)
+            .args([flag, &self.command])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to run backend command `{}`", self.command))?;
+
+        // Feed stdin and drain stderr from threads so a command that writes
+        // before reading everything, or fills stderr, can't deadlock. A
+        // command that exits without reading all of stdin is judged by its
+        // exit status
+        let mut stdin = child.stdin.take().context("Backend command has no stdin")?;
+        let input = pdf_bytes.to_vec();
+        std::thread::spawn(move || stdin.write_all(&input));
+        let mut stderr = child.stderr.take().context("Backend command has no stderr")?;
+        let stderr = std::thread::spawn(move || {
+            let mut output = Vec::new();
+            let _ = stderr.read_to_end(&mut output);
+            output
+        });
+        let stdout = child.stdout.take().context("Backend command has no stdout")?;
+        Ok(Box::new(CommandOutput {
+            command: &self.command,
+            child,
+            stdout,
+            stderr: Some(stderr),
+        }))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_healthy(&self) -> bool {
+        true
+    }
+}
+
+/// A running command's stdout, failing at its end if the command did
+struct CommandOutput<'a> {
+    command: &'a str,
+    child: Child,
+    stdout: ChildStdout,
+    stderr: Option<JoinHandle<Vec<u8>>>,
+}
+
+impl Read for CommandOutput<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.stdout.read(buf)?;
+        if read > 0 || buf.is_empty() {
+            return Ok(read);
+        }
+        let Some(stderr) = self.stderr.take() else {
+            return Ok(0);
+        };
+        let status = self.child.wait()?;
+        let stderr = stderr.join().unwrap_or_default();
+        if !status.success() {
+            return Err(io::Error::other(format!(
+                "Backend command `{}` failed ({}): {}",
+                self.command,
+                status,
+                String::from_utf8_lossy(&stderr).trim()
+            )));
+        }
+        Ok(0)
+    }
+}
+
+impl Drop for CommandOutput<'_> {
+    /// A reader dropped early (the parser gave up) stops the command
+    fn drop(&mut self) {
+        if self.stderr.is_some() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
@@ -13,8 +13,10 @@
 //! and lives for the lifetime of the process.
 
 use super::PdfBackend;
+use crate::types::ExtractionInfo;
 use anyhow::{anyhow, Result};
-use jni::{InitArgsBuilder, JNIVersion, JavaVM};
+use jni::objects::JValue;
+use jni::{InitArgsBuilder, JNIEnv, JNIVersion, JavaVM};
 use std::path::Path;
 use std::sync::Arc;
 
@@ -36,6 +38,8 @@ use std::sync::Arc;
 pub struct TikaJniBackend {
     jvm: Arc<JavaVM>,
     _jar_path: std::path::PathBuf,
+    /// Versions of the JAR, Tika, PDFBox and JVM, probed once at startup
+    extraction_info: ExtractionInfo,
 }
 
 // JNI works correctly across threads when properly attached
@@ -140,12 +144,76 @@ impl TikaJniBackend {
 
         println!("✅ JVM created successfully");
 
+        let extraction_info = Self::probe_versions(&jvm, jar_path);
+        println!(
+            "   Tika {} / PDFBox {} / JVM {}",
+            extraction_info.tika_version.as_deref().unwrap_or("unknown"),
+            extraction_info.pdfbox_version.as_deref().unwrap_or("unknown"),
+            extraction_info.jvm_version.as_deref().unwrap_or("unknown"),
+        );
+
         Ok(Self {
             jvm: Arc::new(jvm),
             _jar_path: jar_path.to_path_buf(),
+            extraction_info,
         })
     }
 
+    /// Ask the JVM which Tika, PDFBox and Java it is running
+    ///
+    /// Any probe that fails (class missing, no version metadata in the shaded
+    /// JAR) is recorded as unknown rather than failing backend startup.
+    fn probe_versions(jvm: &JavaVM, jar_path: &Path) -> ExtractionInfo {
+        let mut info = ExtractionInfo::new("TikaJniBackend");
+        info.jar_version = Self::jar_version(jar_path);
+
+        let Ok(mut env) = jvm.attach_current_thread() else {
+            return info;
+        };
+        info.jvm_version = env
+            .new_string("java.version")
+            .ok()
+            .and_then(|key| {
+                call_static_string(
+                    &mut env,
+                    "java/lang/System",
+                    "getProperty",
+                    "(Ljava/lang/String;)Ljava/lang/String;",
+                    &[(&key).into()],
+                )
+            });
+        info.pdfbox_version = call_static_string(
+            &mut env,
+            "org/apache/pdfbox/util/Version",
+            "getVersion",
+            "()Ljava/lang/String;",
+            &[],
+        );
+        // Tika.getString() is "Apache Tika <version>", or just "Apache Tika"
+        // when the shaded JAR dropped the pom.properties it reads from
+        info.tika_version = call_static_string(
+            &mut env,
+            "org/apache/tika/Tika",
+            "getString",
+            "()Ljava/lang/String;",
+            &[],
+        )
+        .and_then(|s| {
+            let version = s.trim_start_matches("Apache Tika").trim();
+            (!version.is_empty() && version != "null").then(|| version.to_string())
+        });
+        info
+    }
+
+    /// JAR file name plus a short content hash, since the bundled JAR carries no version
+    fn jar_version(jar_path: &Path) -> Option<String> {
+        use sha2::{Digest, Sha256};
+        let bytes = std::fs::read(jar_path).ok()?;
+        let hash = format!("{:x}", Sha256::digest(&bytes));
+        let name = jar_path.file_name()?.to_string_lossy();
+        Some(format!("{}@{}", name, &hash[..12]))
+    }
+
     /// Leak the JVM to skip slow shutdown sequence
     ///
     /// Call this before process exit for instant termination.
@@ -306,8 +374,32 @@ impl PdfBackend for TikaJniBackend {
         "TikaJniBackend"
     }
 
+    fn extraction_info(&self) -> ExtractionInfo {
+        self.extraction_info.clone()
+    }
+
     fn is_healthy(&self) -> bool {
         // Try to attach thread as a health check
         self.jvm.attach_current_thread().is_ok()
     }
 }
+
+/// Call a static Java method returning a String, treating any failure as unknown
+fn call_static_string(
+    env: &mut JNIEnv,
+    class: &str,
+    method: &str,
+    signature: &str,
+    args: &[JValue],
+) -> Option<String> {
+    let result = env.call_static_method(class, method, signature, args);
+    if env.exception_check().unwrap_or(false) {
+        let _ = env.exception_clear();
+        return None;
+    }
+    let object = result.ok()?.l().ok()?;
+    if object.is_null() {
+        return None;
+    }
+    env.get_string((&object).into()).ok().map(Into::into)
+}
@@ -3,13 +3,21 @@
 //! Defines the interface that all PDF extraction backends must implement.
 //! All backends produce the same Blazegraph XHTML intermediate format.
 
+use crate::types::ExtractionInfo;
 use anyhow::Result;
+use std::io::{Cursor, Read};
 
 /// Backend trait for PDF extraction
 ///
 /// All backends must produce the same Blazegraph XHTML format with:
 /// - Page divs with data-page attributes
 /// - Spans with data-bbox, data-line, data-segment attributes
+/// - Optional data-script="super|sub" on spans the backend knows are raised or
+///   lowered; without it the parser infers scripts from size and baseline
+/// - Optional data-* hints on <p> (e.g. data-role="heading|caption|footnote"),
+///   passed through to PdfTextElement::paragraph_attributes
+/// - Optional <a href> around spans (or inside one) for hyperlinks, and
+///   positioned <a href data-bbox="..."/> link regions
 /// - CSS font classes in <style> block
 /// - Bookmark list in <ul> (if available)
 ///
@@ -18,16 +26,35 @@ pub trait PdfBackend: Send + Sync {
     /// Extract PDF bytes to Blazegraph XHTML format
     fn extract_to_xhtml(&self, pdf_bytes: &[u8]) -> Result<String>;
 
+    /// Extract PDF bytes to Blazegraph XHTML, read as it is produced
+    ///
+    /// The preprocessor parses from this reader, so a backend that streams
+    /// its output keeps a huge document's markup out of memory. The default
+    /// reads from `extract_to_xhtml`'s string. Errors the backend reports
+    /// after its output has started (a failing exit status) surface as read
+    /// errors.
+    fn extract_to_reader(&self, pdf_bytes: &[u8]) -> Result<Box<dyn Read + '_>> {
+        Ok(Box::new(Cursor::new(self.extract_to_xhtml(pdf_bytes)?.into_bytes())))
+    }
+
     /// Backend identifier for logging/debugging
     fn name(&self) -> &str;
 
     /// Check if backend is healthy/ready
     fn is_healthy(&self) -> bool;
+
+    /// Which extraction stack this backend runs, recorded in graph metadata
+    fn extraction_info(&self) -> ExtractionInfo {
+        ExtractionInfo::new(self.name())
+    }
 }
 
 // Re-export backends
+pub mod command;
 #[cfg(feature = "jni-backend")]
 pub mod jni;
 
+pub use command::CommandBackend;
+
 #[cfg(feature = "jni-backend")]
 pub use jni::TikaJniBackend;
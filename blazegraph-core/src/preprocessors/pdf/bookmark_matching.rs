@@ -0,0 +1,266 @@
+//! Bookmark-to-span matching
+//!
+//! Bookmark titles rarely equal a span's text exactly: case and whitespace
+//! differ, headings carry numbering the outline omits (or vice versa), and
+//! long headings are split across several spans. Matching is tried in
+//! decreasing order of strictness and each tier carries a confidence:
+//!
+//! | Tier                                   | Confidence      |
+//! |----------------------------------------|-----------------|
+//! | exact (trimmed) text                   | 1.0             |
+//! | normalized (case/punctuation/spacing)  | 0.95            |
+//! | normalized, numbering prefix stripped  | 0.9             |
+//! | joined spans of one line / paragraph   | 0.85            |
+//! | word overlap ≥ 80%                     | 0.8 × overlap   |
+//!
+//! A bookmark that targets a different page than the span is penalized.
+
+use crate::types::*;
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+/// Matches below this confidence are discarded
+pub const MIN_CONFIDENCE: f32 = 0.6;
+
+const NORMALIZED_CONFIDENCE: f32 = 0.95;
+const UNNUMBERED_CONFIDENCE: f32 = 0.9;
+const JOINED_CONFIDENCE: f32 = 0.85;
+const OVERLAP_THRESHOLD: f32 = 0.8;
+const OVERLAP_SCALE: f32 = 0.8;
+const WRONG_PAGE_PENALTY: f32 = 0.7;
+
+// "1.", "2.3.1", "IV.", "A)", "Chapter 3:", "Section 2 -" ...
+static NUMBERING_PREFIX_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^\s*(?:(?:chapter|section|part|appendix)\s+)?(?:\d+(?:\.\d+)*\.?|[ivxlcdm]+[.)]|[a-z][.)])\s*[:\-–—]?\s+",
+    )
+    .unwrap()
+});
+
+struct Candidate<'a> {
+    section: &'a BookmarkSection,
+    normalized: String,
+    unnumbered: String,
+    words: HashSet<String>,
+}
+
+/// Precomputed bookmark titles for matching against spans
+pub struct BookmarkMatcher<'a> {
+    candidates: Vec<Candidate<'a>>,
+}
+
+impl<'a> BookmarkMatcher<'a> {
+    pub fn new(sections: &'a [BookmarkSection]) -> Self {
+        let candidates = sections
+            .iter()
+            .map(|section| {
+                let normalized = normalize(&section.title);
+                let unnumbered = normalize(&strip_numbering(&section.title));
+                let words = unnumbered.split(' ').map(str::to_string).collect();
+                Candidate {
+                    section,
+                    normalized,
+                    unnumbered,
+                    words,
+                }
+            })
+            .filter(|c| !c.normalized.is_empty())
+            .collect();
+        Self { candidates }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.candidates.is_empty()
+    }
+
+    /// Best bookmark for a piece of text on the given page, with its confidence
+    pub fn best_match(&self, text: &str, page_number: u32) -> Option<(&'a BookmarkSection, f32)> {
+        self.matches(text, page_number)
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.order.cmp(&a.0.order)))
+    }
+
+    /// Every bookmark the text matches with at least `MIN_CONFIDENCE`, in
+    /// outline order
+    pub fn matches(&self, text: &str, page_number: u32) -> Vec<(&'a BookmarkSection, f32)> {
+        let trimmed = text.trim();
+        let normalized = normalize(trimmed);
+        if normalized.is_empty() {
+            return Vec::new();
+        }
+        let unnumbered = normalize(&strip_numbering(trimmed));
+        let words: HashSet<&str> = unnumbered.split(' ').collect();
+
+        self.candidates
+            .iter()
+            .filter_map(|candidate| {
+                let score = if candidate.section.title.trim() == trimmed {
+                    1.0
+                } else if candidate.normalized == normalized {
+                    NORMALIZED_CONFIDENCE
+                } else if candidate.unnumbered == unnumbered {
+                    UNNUMBERED_CONFIDENCE
+                } else {
+                    let overlap = word_overlap(&candidate.words, &words);
+                    if overlap >= OVERLAP_THRESHOLD {
+                        overlap * OVERLAP_SCALE
+                    } else {
+                        return None;
+                    }
+                };
+                Some((candidate.section, score * page_factor(candidate.section, page_number)))
+            })
+            .filter(|(_, score)| *score >= MIN_CONFIDENCE)
+            .collect()
+    }
+
+    /// Assign bookmark matches to a paragraph's spans.
+    ///
+    /// Spans are matched individually first; headings split across spans are
+    /// then matched by joining each line, and finally the whole paragraph.
+    pub fn assign(&self, paragraph: &mut [PdfTextElement]) {
+        if self.is_empty() || paragraph.is_empty() {
+            return;
+        }
+
+        for element in paragraph.iter_mut() {
+            if let Some((section, confidence)) = self.best_match(&element.text, element.page_number) {
+                element.bookmark_match = Some(with_confidence(section, confidence));
+            }
+        }
+
+        if paragraph.iter().any(|e| e.bookmark_match.is_some()) {
+            return;
+        }
+
+        // Split headings: try each multi-span line, then the whole paragraph
+        let mut lines: Vec<u32> = paragraph.iter().map(|e| e.line_number).collect();
+        lines.dedup();
+        for line in lines {
+            let indices: Vec<usize> = (0..paragraph.len())
+                .filter(|&i| paragraph[i].line_number == line)
+                .collect();
+            if indices.len() > 1 && self.assign_joined(paragraph, &indices) {
+                return;
+            }
+        }
+
+        if paragraph.len() > 1 {
+            let all: Vec<usize> = (0..paragraph.len()).collect();
+            self.assign_joined(paragraph, &all);
+        }
+    }
+
+    fn assign_joined(&self, paragraph: &mut [PdfTextElement], indices: &[usize]) -> bool {
+        let mut ordered = indices.to_vec();
+        ordered.sort_by_key(|&i| (paragraph[i].line_number, paragraph[i].segment_number));
+
+        let joined = ordered
+            .iter()
+            .map(|&i| paragraph[i].text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let page_number = paragraph[ordered[0]].page_number;
+
+        match self.best_match(&joined, page_number) {
+            Some((section, score)) => {
+                let confidence = score.min(JOINED_CONFIDENCE);
+                for &i in &ordered {
+                    paragraph[i].bookmark_match = Some(with_confidence(section, confidence));
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn with_confidence(section: &BookmarkSection, confidence: f32) -> BookmarkSection {
+    BookmarkSection {
+        confidence: Some(confidence),
+        ..section.clone()
+    }
+}
+
+fn page_factor(section: &BookmarkSection, page_number: u32) -> f32 {
+    match section.page {
+        Some(page) if page != page_number => WRONG_PAGE_PENALTY,
+        _ => 1.0,
+    }
+}
+
+/// Lowercase, drop punctuation, collapse whitespace
+fn normalize(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn strip_numbering(text: &str) -> String {
+    NUMBERING_PREFIX_REGEX.replace(text.trim(), "").to_string()
+}
+
+/// Shared words relative to the larger of the two word sets
+fn word_overlap(a: &HashSet<String>, b: &HashSet<&str>) -> f32 {
+    let larger = a.len().max(b.len());
+    if larger == 0 {
+        return 0.0;
+    }
+    let shared = b.iter().filter(|w| a.contains(**w)).count();
+    shared as f32 / larger as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(title: &str, order: u32) -> BookmarkSection {
+        BookmarkSection {
+            title: title.to_string(),
+            order,
+            level: 1,
+            parent: None,
+            page: None,
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn matches_across_case_and_numbering() {
+        let sections = vec![bookmark("Introduction", 0), bookmark("2.1 Channel Capacity", 1)];
+        let matcher = BookmarkMatcher::new(&sections);
+
+        let (section, confidence) = matcher.best_match("INTRODUCTION", 1).unwrap();
+        assert_eq!(section.order, 0);
+        assert_eq!(confidence, NORMALIZED_CONFIDENCE);
+
+        let (section, confidence) = matcher.best_match("Channel capacity", 1).unwrap();
+        assert_eq!(section.order, 1);
+        assert_eq!(confidence, UNNUMBERED_CONFIDENCE);
+
+        assert!(matcher.best_match("A Mathematical Theory", 1).is_none());
+    }
+
+    #[test]
+    fn joins_headings_split_across_spans() {
+        use crate::test_utils::TextElementBuilder;
+
+        let sections = vec![bookmark("A Mathematical Theory of Communication", 0)];
+        let matcher = BookmarkMatcher::new(&sections);
+        let mut paragraph = vec![
+            TextElementBuilder::new("A Mathematical Theory").line(0, 0).build(),
+            TextElementBuilder::new("of Communication").line(0, 1).build(),
+        ];
+
+        matcher.assign(&mut paragraph);
+        for element in &paragraph {
+            let matched = element.bookmark_match.as_ref().unwrap();
+            assert_eq!(matched.confidence, Some(JOINED_CONFIDENCE));
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Tolerant CSS parsing for backend font classes
+//!
+//! Backends don't agree on how they write font classes: property order
+//! varies, some properties are omitted, some use the `font` shorthand or
+//! `rgb()` colors. Rather than matching one exact layout, this parses each
+//! `.class { ... }` rule as a list of declarations and fills in defaults for
+//! anything missing, so a CSS variation degrades one property instead of
+//! dropping the whole class.
+
+use crate::types::FontClass;
+use std::collections::HashMap;
+
+const DEFAULT_FAMILY: &str = "unknown";
+const DEFAULT_SIZE: f32 = 12.0;
+const DEFAULT_STYLE: &str = "normal";
+const DEFAULT_WEIGHT: &str = "normal";
+const DEFAULT_COLOR: &str = "#000000";
+
+/// Parse all class rules in a stylesheet into FontClasses keyed by class name
+pub fn parse_font_classes(css: &str) -> HashMap<String, FontClass> {
+    let css = strip_comments(css);
+    let mut font_classes = HashMap::new();
+
+    let mut rest = css.as_str();
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}') else {
+            break;
+        };
+        let selectors = &rest[..open];
+        let body = &rest[open + 1..open + close];
+        rest = &rest[open + close + 1..];
+
+        let declarations = parse_declarations(body);
+        for class_name in class_selectors(selectors) {
+            let font_class = build_font_class(&class_name, &declarations);
+            font_classes.insert(class_name, font_class);
+        }
+    }
+
+    font_classes
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut out = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        match rest[start + 2..].find("*/") {
+            Some(end) => rest = &rest[start + 2 + end + 2..],
+            None => return out,
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Class names from a selector list like `.f1, p .f2` (only simple class selectors count)
+fn class_selectors(selectors: &str) -> Vec<String> {
+    selectors
+        .split(',')
+        .filter_map(|selector| {
+            let last = selector.split_whitespace().last()?;
+            let name = last.strip_prefix('.')?;
+            let valid = !name.is_empty()
+                && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+            valid.then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Split a rule body into lowercase property names and raw values
+fn parse_declarations(body: &str) -> Vec<(String, String)> {
+    body.split(';')
+        .filter_map(|decl| {
+            let (property, value) = decl.split_once(':')?;
+            let value = value.trim().trim_end_matches("!important").trim();
+            if value.is_empty() {
+                return None;
+            }
+            Some((property.trim().to_lowercase(), value.to_string()))
+        })
+        .collect()
+}
+
+fn build_font_class(class_name: &str, declarations: &[(String, String)]) -> FontClass {
+    let mut font_class = FontClass {
+        class_name: class_name.to_string(),
+        font_family: DEFAULT_FAMILY.to_string(),
+        font_size: DEFAULT_SIZE,
+        font_style: DEFAULT_STYLE.to_string(),
+        font_weight: DEFAULT_WEIGHT.to_string(),
+        color: DEFAULT_COLOR.to_string(),
+    };
+
+    // Declarations apply in order, so longhands after a shorthand override it
+    for (property, value) in declarations {
+        match property.as_str() {
+            "font" => apply_font_shorthand(&mut font_class, value),
+            "font-family" => font_class.font_family = parse_family(value),
+            "font-size" => {
+                if let Some(size) = parse_size(value) {
+                    font_class.font_size = size;
+                }
+            }
+            "font-style" => font_class.font_style = parse_style(value),
+            "font-weight" => font_class.font_weight = parse_weight(value),
+            "color" => font_class.color = parse_color(value),
+            _ => {}
+        }
+    }
+
+    font_class
+}
+
+/// `font: [style] [variant] [weight] size[/line-height] family[, family]*`
+fn apply_font_shorthand(font_class: &mut FontClass, value: &str) {
+    let mut tokens = value.split_whitespace();
+
+    for token in tokens.by_ref() {
+        match token.to_lowercase().as_str() {
+            "italic" | "oblique" => font_class.font_style = "italic".to_string(),
+            "bold" | "bolder" => font_class.font_weight = "bold".to_string(),
+            // A bare number before the size is a weight (sizes in shorthand carry units)
+            t if t.parse::<u32>().is_ok() => font_class.font_weight = parse_weight(t),
+            t => {
+                if let Some(size) = parse_size(t.split('/').next().unwrap_or(t)) {
+                    font_class.font_size = size;
+                    break;
+                }
+            }
+        }
+    }
+
+    let family: Vec<&str> = tokens.collect();
+    if !family.is_empty() {
+        font_class.font_family = parse_family(&family.join(" "));
+    }
+}
+
+/// First family in the list, unquoted
+fn parse_family(value: &str) -> String {
+    let first = value.split(',').next().unwrap_or(value).trim();
+    let unquoted = first.trim_matches(|c| c == '"' || c == '\'').trim();
+    if unquoted.is_empty() {
+        DEFAULT_FAMILY.to_string()
+    } else {
+        unquoted.to_string()
+    }
+}
+
+/// Sizes in px/pt are taken as-is (backends emit PDF points); em/rem scale the default
+fn parse_size(value: &str) -> Option<f32> {
+    let value = value.trim().to_lowercase();
+    let (number, scale) = if let Some(n) = value.strip_suffix("px") {
+        (n, 1.0)
+    } else if let Some(n) = value.strip_suffix("pt") {
+        (n, 1.0)
+    } else if let Some(n) = value.strip_suffix("rem") {
+        (n, DEFAULT_SIZE)
+    } else if let Some(n) = value.strip_suffix("em") {
+        (n, DEFAULT_SIZE)
+    } else {
+        (value.as_str(), 1.0)
+    };
+
+    number
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|n| n.is_finite() && *n >= 0.0)
+        .map(|n| n * scale)
+}
+
+fn parse_style(value: &str) -> String {
+    match value.to_lowercase().as_str() {
+        "italic" | "oblique" => "italic".to_string(),
+        _ => DEFAULT_STYLE.to_string(),
+    }
+}
+
+/// Normalize to "bold"/"normal", treating numeric weights of 600+ as bold
+fn parse_weight(value: &str) -> String {
+    let value = value.to_lowercase();
+    let bold = match value.as_str() {
+        "bold" | "bolder" => true,
+        v => v.parse::<u32>().map(|w| w >= 600).unwrap_or(false),
+    };
+    if bold { "bold" } else { DEFAULT_WEIGHT }.to_string()
+}
+
+/// Normalize rgb()/rgba() to #rrggbb; hex and named colors pass through lowercase
+pub(crate) fn parse_color(value: &str) -> String {
+    let value = value.trim().to_lowercase();
+    let inner = value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb("))
+        .and_then(|v| v.strip_suffix(')'));
+
+    if let Some(inner) = inner {
+        let channels: Vec<u8> = inner
+            .split([',', ' ', '/'])
+            .filter(|c| !c.is_empty())
+            .take(3)
+            .filter_map(|c| {
+                if let Some(pct) = c.strip_suffix('%') {
+                    pct.parse::<f32>().ok().map(|p| (p.clamp(0.0, 100.0) * 2.55).round() as u8)
+                } else {
+                    c.parse::<f32>().ok().map(|v| v.clamp(0.0, 255.0).round() as u8)
+                }
+            })
+            .collect();
+        if channels.len() == 3 {
+            return format!("#{:02x}{:02x}{:02x}", channels[0], channels[1], channels[2]);
+        }
+        return DEFAULT_COLOR.to_string();
+    }
+
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_reordered_and_missing_properties() {
+        let classes = parse_font_classes(
+            ".f1 { color: #333333; font-weight: 700; font-family: 'Arial', sans-serif; }",
+        );
+        let f1 = &classes["f1"];
+        assert_eq!(f1.font_family, "Arial");
+        assert_eq!(f1.font_weight, "bold");
+        assert_eq!(f1.font_size, DEFAULT_SIZE);
+        assert_eq!(f1.color, "#333333");
+    }
+
+    #[test]
+    fn parses_font_shorthand_and_rgb_colors() {
+        let classes = parse_font_classes(
+            "/* heading */ .h1, .h2 { font: italic bold 18pt/1.2 \"Times New Roman\", serif; color: rgb(26, 26, 26) }",
+        );
+        for name in ["h1", "h2"] {
+            let class = &classes[name];
+            assert_eq!(class.font_family, "Times New Roman");
+            assert_eq!(class.font_size, 18.0);
+            assert_eq!(class.font_style, "italic");
+            assert_eq!(class.font_weight, "bold");
+            assert_eq!(class.color, "#1a1a1a");
+        }
+    }
+}
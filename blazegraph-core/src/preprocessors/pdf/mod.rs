@@ -4,14 +4,18 @@
 //! PDF content to Blazegraph XHTML, then parses into PreprocessorOutput.
 
 pub mod backends;
+pub mod bookmark_matching;
+pub mod css;
 pub mod xhtml_parser;
 
-use crate::preprocessors::traits::Preprocessor;
+use crate::preprocessors::traits::{Extraction, Preprocessor};
+use crate::storage::XhtmlHashReader;
 use crate::types::*;
-use anyhow::Result;
+use anyhow::{bail, Result};
+use std::io::{BufReader, Read};
 use std::path::Path;
 
-pub use backends::PdfBackend;
+pub use backends::{CommandBackend, PdfBackend};
 
 #[cfg(feature = "jni-backend")]
 pub use backends::TikaJniBackend;
@@ -20,6 +24,8 @@ pub use backends::TikaJniBackend;
 pub enum PdfBackendImpl {
     #[cfg(feature = "jni-backend")]
     Jni(TikaJniBackend),
+    /// Any other `PdfBackend` (an extraction server, a pure-Rust extractor)
+    Custom(Box<dyn PdfBackend>),
 }
 
 impl PdfBackend for PdfBackendImpl {
@@ -27,6 +33,15 @@ impl PdfBackend for PdfBackendImpl {
         match self {
             #[cfg(feature = "jni-backend")]
             PdfBackendImpl::Jni(backend) => backend.extract_to_xhtml(pdf_bytes),
+            PdfBackendImpl::Custom(backend) => backend.extract_to_xhtml(pdf_bytes),
+        }
+    }
+
+    fn extract_to_reader(&self, pdf_bytes: &[u8]) -> Result<Box<dyn Read + '_>> {
+        match self {
+            #[cfg(feature = "jni-backend")]
+            PdfBackendImpl::Jni(backend) => backend.extract_to_reader(pdf_bytes),
+            PdfBackendImpl::Custom(backend) => backend.extract_to_reader(pdf_bytes),
         }
     }
 
@@ -34,6 +49,7 @@ impl PdfBackend for PdfBackendImpl {
         match self {
             #[cfg(feature = "jni-backend")]
             PdfBackendImpl::Jni(backend) => backend.name(),
+            PdfBackendImpl::Custom(backend) => backend.name(),
         }
     }
 
@@ -41,6 +57,15 @@ impl PdfBackend for PdfBackendImpl {
         match self {
             #[cfg(feature = "jni-backend")]
             PdfBackendImpl::Jni(backend) => backend.is_healthy(),
+            PdfBackendImpl::Custom(backend) => backend.is_healthy(),
+        }
+    }
+
+    fn extraction_info(&self) -> ExtractionInfo {
+        match self {
+            #[cfg(feature = "jni-backend")]
+            PdfBackendImpl::Jni(backend) => backend.extraction_info(),
+            PdfBackendImpl::Custom(backend) => backend.extraction_info(),
         }
     }
 }
@@ -50,11 +75,35 @@ impl PdfBackend for PdfBackendImpl {
 /// Processes PDF documents through two stages:
 /// 1. Backend extraction: PDF bytes → Blazegraph XHTML
 /// 2. XHTML parsing: Blazegraph XHTML → PreprocessorOutput
+///
+/// Backends form an ordered fallback chain (e.g. JNI → server → pure-Rust):
+/// extraction uses the first backend that is healthy and succeeds. The last
+/// backend is always attempted, so a single-backend chain behaves as before.
+///
+/// `process` and `extract` parse the backend's XHTML as it is read
+/// (`PdfBackend::extract_to_reader`), so the markup of a huge document is
+/// never held whole; a backend whose output fails to parse counts as failed.
+/// Each extraction picks its backend afresh and returns which one it used
+/// (`Extraction::info`), so concurrent extractions can't mix up backends.
 pub struct PdfPreprocessor {
-    backend: PdfBackendImpl,
+    backends: Vec<PdfBackendImpl>,
 }
 
 impl PdfPreprocessor {
+    /// Create PdfPreprocessor from an ordered backend chain
+    pub fn with_backends(backends: Vec<PdfBackendImpl>) -> Result<Self> {
+        if backends.is_empty() {
+            bail!("PdfPreprocessor needs at least one backend");
+        }
+        Ok(Self { backends })
+    }
+
+    /// Append a backend to try when the earlier ones are unhealthy or fail
+    pub fn with_fallback(mut self, backend: PdfBackendImpl) -> Self {
+        self.backends.push(backend);
+        self
+    }
+
     /// Create PdfPreprocessor with JNI backend (default JVM settings)
     ///
     /// # Arguments
@@ -62,9 +111,29 @@ impl PdfPreprocessor {
     /// * `jar_path` - Path to blazing-tika.jar
     #[cfg(feature = "jni-backend")]
     pub fn new_with_jni(jre_path: &Path, jar_path: &Path) -> Result<Self> {
-        Ok(Self {
-            backend: PdfBackendImpl::Jni(TikaJniBackend::new(jre_path, jar_path)?),
-        })
+        Self::with_backends(vec![PdfBackendImpl::Jni(TikaJniBackend::new(jre_path, jar_path)?)])
+    }
+
+    /// Create PdfPreprocessor with JNI first, falling back to `fallbacks`
+    ///
+    /// A JVM that fails to start is skipped (with a warning) rather than
+    /// failing construction, as long as there is a fallback to use instead.
+    #[cfg(feature = "jni-backend")]
+    pub fn new_with_jni_or_fallbacks(
+        jre_path: &Path,
+        jar_path: &Path,
+        fallbacks: Vec<PdfBackendImpl>,
+    ) -> Result<Self> {
+        let mut backends = Vec::with_capacity(fallbacks.len() + 1);
+        match TikaJniBackend::new(jre_path, jar_path) {
+            Ok(jni) => backends.push(PdfBackendImpl::Jni(jni)),
+            Err(e) if !fallbacks.is_empty() => {
+                println!("⚠️  JNI backend unavailable, using fallback backends: {:#}", e);
+            }
+            Err(e) => return Err(e),
+        }
+        backends.extend(fallbacks);
+        Self::with_backends(backends)
     }
 
     /// Create PdfPreprocessor with JNI backend and custom JVM arguments
@@ -86,28 +155,77 @@ impl PdfPreprocessor {
     /// ```
     #[cfg(feature = "jni-backend")]
     pub fn new_with_jni_args(jre_path: &Path, jar_path: &Path, jvm_args: &[String]) -> Result<Self> {
-        Ok(Self {
-            backend: PdfBackendImpl::Jni(TikaJniBackend::new_with_args(
-                jre_path, jar_path, jvm_args,
-            )?),
-        })
+        Self::with_backends(vec![PdfBackendImpl::Jni(TikaJniBackend::new_with_args(
+            jre_path, jar_path, jvm_args,
+        )?)])
     }
 
-    /// Get the backend name for logging
+    /// Name of the backend the next extraction tries first (the first
+    /// healthy one); extractions report the backend they actually used
     pub fn backend_name(&self) -> &str {
-        self.backend.name()
+        self.first_healthy_backend().name()
+    }
+
+    /// Names of the chain's backends, in fallback order
+    pub fn backend_names(&self) -> Vec<&str> {
+        self.backends.iter().map(|backend| backend.name()).collect()
     }
 
-    /// Check if the backend is healthy
+    /// Check if any backend in the chain is healthy
     pub fn is_healthy(&self) -> bool {
-        self.backend.is_healthy()
+        self.backends.iter().any(|backend| backend.is_healthy())
+    }
+
+    /// Run `extract` with the first backend that is healthy and succeeds,
+    /// returning its result and the backend
+    fn with_first_working_backend<T>(
+        &self,
+        extract: impl Fn(&PdfBackendImpl) -> Result<T>,
+    ) -> Result<(T, &PdfBackendImpl)> {
+        let last = self.backends.len() - 1;
+        let mut failures = Vec::new();
+        for (index, backend) in self.backends.iter().enumerate() {
+            if index < last && !backend.is_healthy() {
+                println!("⚠️  PDF backend {} is unhealthy, trying the next one", backend.name());
+                failures.push(format!("{}: unhealthy", backend.name()));
+                continue;
+            }
+            match extract(backend) {
+                Ok(extracted) => {
+                    if index > 0 {
+                        println!("🔁 Extracted with fallback PDF backend {}", backend.name());
+                    }
+                    return Ok((extracted, backend));
+                }
+                Err(e) if self.backends.len() == 1 => return Err(e),
+                Err(e) => {
+                    println!("⚠️  PDF backend {} failed: {:#}", backend.name(), e);
+                    failures.push(format!("{}: {:#}", backend.name(), e));
+                }
+            }
+        }
+        bail!("All PDF backends failed ({})", failures.join("; "))
+    }
+
+    /// The backend an extraction starts with; the last is always attempted
+    fn first_healthy_backend(&self) -> &PdfBackendImpl {
+        let last = self.backends.len() - 1;
+        self.backends[..last]
+            .iter()
+            .find(|backend| backend.is_healthy())
+            .unwrap_or(&self.backends[last])
     }
 }
 
 impl Preprocessor for PdfPreprocessor {
-    /// Step 1: Extract PDF to XHTML via backend
+    /// Step 1: Extract PDF to XHTML via the first backend that works
     fn parse_pdf_to_markup_language(&self, pdf_bytes: &[u8]) -> Result<String> {
-        self.backend.extract_to_xhtml(pdf_bytes)
+        Ok(self.extract_markup(pdf_bytes)?.0)
+    }
+
+    fn extract_markup(&self, pdf_bytes: &[u8]) -> Result<(String, ExtractionInfo)> {
+        let (xhtml, backend) = self.with_first_working_backend(|backend| backend.extract_to_xhtml(pdf_bytes))?;
+        Ok((xhtml, backend.extraction_info()))
     }
 
     /// Step 2: Parse XHTML to PreprocessorOutput
@@ -115,10 +233,37 @@ impl Preprocessor for PdfPreprocessor {
         xhtml_parser::parse_xhtml(markup)
     }
 
+    /// Both steps, parsing the XHTML as the backend produces it
+    fn extract(&self, pdf_bytes: &[u8]) -> Result<Extraction> {
+        let ((output, markup_hash), backend) = self.with_first_working_backend(|backend| {
+            let mut reader = BufReader::new(XhtmlHashReader::new(backend.extract_to_reader(pdf_bytes)?));
+            let output = xhtml_parser::parse_xhtml_reader(&mut reader)?;
+            Ok((output, reader.into_inner().finish()))
+        })?;
+        Ok(Extraction {
+            output,
+            markup_hash,
+            info: backend.extraction_info(),
+        })
+    }
+
     fn name(&self) -> &str {
         "PdfPreprocessor"
     }
 
+    /// The stack of the backend the next extraction tries first
+    fn extraction_info(&self) -> ExtractionInfo {
+        self.first_healthy_backend().extraction_info()
+    }
+
+    fn is_healthy(&self) -> bool {
+        PdfPreprocessor::is_healthy(self)
+    }
+
+    fn may_contain_scans(&self) -> bool {
+        true
+    }
+
     fn supports_file_type(&self, path: &Path) -> bool {
         if let Some(extension) = path.extension() {
             matches!(
@@ -133,3 +278,133 @@ impl Preprocessor for PdfPreprocessor {
 
 // Legacy type alias for backwards compatibility
 pub type TikaPreprocessor = PdfPreprocessor;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StubBackend {
+        name: &'static str,
+        healthy: bool,
+        result: Option<&'static str>,
+    }
+
+    impl PdfBackend for StubBackend {
+        fn extract_to_xhtml(&self, _pdf_bytes: &[u8]) -> Result<String> {
+            match self.result {
+                Some(xhtml) => Ok(xhtml.to_string()),
+                None => bail!("{} crashed", self.name),
+            }
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn is_healthy(&self) -> bool {
+            self.healthy
+        }
+    }
+
+    fn stub(name: &'static str, healthy: bool, result: Option<&'static str>) -> PdfBackendImpl {
+        PdfBackendImpl::Custom(Box::new(StubBackend { name, healthy, result }))
+    }
+
+    #[test]
+    fn falls_back_past_unhealthy_and_failing_backends() {
+        let preprocessor = PdfPreprocessor::with_backends(vec![stub("jni", false, Some("<jni/>"))])
+            .unwrap()
+            .with_fallback(stub("server", true, None))
+            .with_fallback(stub("rust", true, Some("<rust/>")));
+
+        let (xhtml, info) = preprocessor.extract_markup(b"%PDF").unwrap();
+        assert_eq!((xhtml.as_str(), info.backend.as_str()), ("<rust/>", "rust"));
+        assert_eq!(preprocessor.extract(b"%PDF").unwrap().info.backend, "rust");
+        // The preprocessor itself only knows where the next extraction starts
+        assert_eq!(preprocessor.backend_name(), "server");
+        assert_eq!(preprocessor.extraction_info().backend, "server");
+
+        let broken = PdfPreprocessor::with_backends(vec![stub("jni", false, None), stub("server", false, None)]).unwrap();
+        let error = broken.parse_pdf_to_markup_language(b"%PDF").unwrap_err().to_string();
+        assert!(error.contains("jni: unhealthy") && error.contains("server crashed"), "{error}");
+
+        assert!(PdfPreprocessor::with_backends(Vec::new()).is_err());
+    }
+
+    #[cfg(feature = "jni-backend")]
+    #[test]
+    fn jni_that_cannot_start_hands_over_to_the_fallbacks() {
+        let missing = Path::new("/nonexistent/blazegraph-jre");
+        let preprocessor =
+            PdfPreprocessor::new_with_jni_or_fallbacks(missing, missing, vec![stub("rust", true, Some("<rust/>"))]).unwrap();
+        assert_eq!(preprocessor.backend_names(), ["rust"]);
+        assert_eq!(preprocessor.extract_markup(b"%PDF").unwrap().1.backend, "rust");
+
+        let Err(error) = PdfPreprocessor::new_with_jni_or_fallbacks(missing, missing, Vec::new()) else {
+            panic!("JNI without fallbacks should fail");
+        };
+        assert!(error.to_string().contains("JRE not found"), "{error}");
+    }
+
+    /// Extracts only the "PDF" that is its own name
+    struct PickyBackend(&'static str);
+
+    impl PdfBackend for PickyBackend {
+        fn extract_to_xhtml(&self, pdf_bytes: &[u8]) -> Result<String> {
+            if pdf_bytes != self.0.as_bytes() {
+                bail!("{} can't read this PDF", self.0);
+            }
+            Ok("<html><body></body></html>".to_string())
+        }
+
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn is_healthy(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn concurrent_extractions_report_their_own_backend() {
+        let picky = |name| PdfBackendImpl::Custom(Box::new(PickyBackend(name)));
+        let preprocessor = PdfPreprocessor::with_backends(vec![picky("first"), picky("second")]).unwrap();
+        std::thread::scope(|scope| {
+            let runs: Vec<_> = (0..8)
+                .map(|run| {
+                    let (preprocessor, pdf) = (&preprocessor, if run % 2 == 0 { "first" } else { "second" });
+                    scope.spawn(move || (pdf, preprocessor.extract(pdf.as_bytes()).unwrap().info.backend))
+                })
+                .collect();
+            for run in runs {
+                let (pdf, backend) = run.join().unwrap();
+                assert_eq!(backend, pdf);
+            }
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_backends_stream_into_the_parser() {
+        use crate::storage::calculate_xhtml_hash;
+        use crate::test_utils::conformance::BASIC_XHTML;
+
+        // `cat` echoes the "PDF" back, so it stands in for an extractor
+        let command = |command: &str| PdfBackendImpl::Custom(Box::new(CommandBackend::new(command)));
+        let preprocessor = PdfPreprocessor::with_backends(vec![command("cat")]).unwrap();
+        let extraction = preprocessor.extract(BASIC_XHTML.as_bytes()).unwrap();
+        let text: Vec<&str> = extraction.output.text_elements.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(text, ["Conformance Heading", "Body text left.", "Body text right.", "Second page text."]);
+        assert_eq!(extraction.markup_hash, calculate_xhtml_hash(BASIC_XHTML));
+
+        // A failure after the output started still fails the extraction, and falls back
+        let failing = command("cat; echo 'font missing' >&2; exit 3");
+        let error = failing.extract_to_xhtml(BASIC_XHTML.as_bytes()).unwrap_err().to_string();
+        assert!(error.contains("exit status: 3") && error.contains("font missing"), "{error}");
+        let preprocessor = PdfPreprocessor::with_backends(vec![failing, command("cat")]).unwrap();
+        let extraction = preprocessor.extract(BASIC_XHTML.as_bytes()).unwrap();
+        assert_eq!(extraction.output.text_elements.len(), 4);
+        assert_eq!(extraction.info.backend, "cmd:cat");
+    }
+}
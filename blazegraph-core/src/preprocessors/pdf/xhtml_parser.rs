@@ -1,47 +1,52 @@
 //! Blazegraph XHTML Parser
 //!
 //! Parses the Blazegraph XHTML intermediate format produced by PDF backends
-//! into PreprocessorOutput. This parser is shared across all PDF backends.
+//! into PreprocessorOutput. This parser is shared across all PDF backends,
+//! and by reflowable formats that render through `xhtml_writer`.
 //!
 //! The Blazegraph XHTML format includes:
 //! - Page divs with data-page attributes
-//! - Spans with data-bbox, data-line, data-segment attributes
+//! - Spans with data-bbox, data-line, data-segment attributes, and an optional
+//!   data-script="super|sub" for superscripts and subscripts
+//! - Optional data-* hints on paragraphs (e.g. data-role="heading")
+//! - Image regions: `<img data-bbox="..." alt="..."/>`, or a
+//!   `<figure data-bbox="...">` with an optional `<figcaption>`. Each becomes
+//!   one element with role "figure" whose text is the caption (or alt text).
+//!   Images without a data-bbox are ignored
+//! - Hyperlinks: spans inside an `<a href>`, or holding one, are its anchor;
+//!   an `<a href data-bbox="...">` declares a link region on the page (as PDF
+//!   link annotations do), anchored to the text under it
 //! - CSS font classes in <style> block
 //! - Document metadata in <meta> tags
 //! - Bookmarks/TOC in <ul> structure
+//!
+//! The markup is read as a stream of XML events (`parse_xhtml_reader`), so
+//! attributes may come in any order, divs may nest inside pages, and the
+//! document is never held in memory as a whole. Backend output is XHTML-ish
+//! rather than strict XML: end tag names are not checked against their start
+//! tags, HTML-style attributes are accepted, and unknown entities are kept
+//! literally.
 
+use super::bookmark_matching::BookmarkMatcher;
+use super::css;
+use crate::links::anchor_text;
+use crate::reading_direction::ReadingDirection;
 use crate::types::*;
-use anyhow::Result;
-use regex::Regex;
-use std::collections::HashMap;
-use std::sync::LazyLock;
-
-// Pre-compiled regexes for XHTML parsing performance
-static PAGE_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r#"(?s)<div class="page"[^>]*>(.*?)</div>"#).unwrap());
-
-static PARAGRAPH_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?s)<p[^>]*>(.*?)</p>").unwrap());
-
-static SPAN_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r#"<span[^>]*class="([^"]*)"[^>]*data-bbox="([^"]*)"[^>]*data-line="([^"]*)"[^>]*data-segment="([^"]*)"[^>]*>([^<]*)</span>"#).unwrap()
-});
-
-static META_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r#"<meta\s+name="([^"]*)"[^>]*content="([^"]*)"[^>]*/?>"#).unwrap()
-});
-
-static STYLE_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?s)<style[^>]*>(.*?)</style>").unwrap());
-
-static FONT_CLASS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"\.(\w+)\s*\{\s*font-family:\s*([^;]+);\s*font-size:\s*([^;]+);\s*font-style:\s*([^;]+);\s*font-weight:\s*([^;]+);\s*color:\s*([^;]+);\s*\}").unwrap()
-});
+use anyhow::{Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::io::BufRead;
 
-static LIST_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<ul>(.*?)</ul>").unwrap());
-
-static LIST_ITEM_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"<li>([^<]+)</li>").unwrap());
+/// A span at most this fraction of its neighbour's font size can be a script
+const SCRIPT_SIZE_RATIO: f32 = 0.85;
+/// Longest text inferred as a script (footnote markers, exponents, indices)
+const MAX_SCRIPT_CHARS: usize = 8;
+/// Bottom-edge shift, in the neighbour's font size, that marks a superscript
+const SUPERSCRIPT_SHIFT_EM: f32 = 0.2;
+/// ...and a subscript (baselines sit lower, so less shift is needed)
+const SUBSCRIPT_SHIFT_EM: f32 = 0.1;
 
 /// Parse Blazegraph XHTML into PreprocessorOutput
 ///
@@ -51,190 +56,775 @@ static LIST_ITEM_REGEX: LazyLock<Regex> =
 /// - Style data (font classes)
 /// - Bookmark data (if present)
 pub fn parse_xhtml(xhtml: &str) -> Result<PreprocessorOutput> {
-    let (text_elements, metadata, style_data, bookmark_data) = parse_xhtml_content(xhtml)?;
-
-    Ok(PreprocessorOutput {
-        text_elements,
-        metadata,
-        style_data,
-        bookmark_data,
-    })
+    parse_xhtml_reader(xhtml.as_bytes())
+}
+
+/// Parse Blazegraph XHTML from a reader, one event at a time
+///
+/// Metadata, styles and the outline may all follow the pages, so spans are
+/// collected with their class names and resolved against the styles once
+/// the whole document has been read.
+pub fn parse_xhtml_reader<R: BufRead>(reader: R) -> Result<PreprocessorOutput> {
+    let mut reader = Reader::from_reader(reader);
+    reader.check_end_names(false);
+
+    let mut collector = XhtmlCollector::default();
+    let mut buf = Vec::new();
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .with_context(|| format!("Malformed XHTML near byte {}", reader.buffer_position()))?;
+        match event {
+            Event::Start(tag) => collector.open(&tag, false),
+            Event::Empty(tag) => collector.open(&tag, true),
+            Event::End(tag) => collector.close(tag.local_name().as_ref()),
+            Event::Text(text) => collector.text(&decode_entities(utf8(&text)?)),
+            Event::CData(text) => collector.text(utf8(&text)?),
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    collector.finish()
+}
+
+fn utf8(bytes: &[u8]) -> Result<&str> {
+    std::str::from_utf8(bytes).context("XHTML is not valid UTF-8")
+}
+
+/// A `<div class="page">` being read; `div_depth` counts divs nested inside it
+struct OpenPage {
+    number: u32,
+    start: usize,
+    div_depth: usize,
+}
+
+struct OpenParagraph {
+    start: usize,
+    /// `data-*` hints on the `<p>`, keyed without the prefix
+    attributes: HashMap<String, String>,
+    /// Whether any span in it carries `data-script`
+    explicit_scripts: bool,
+}
+
+/// A `<figure>` (or a lone `<img>`) being read; text inside its
+/// `<figcaption>` is the caption rather than body text
+#[derive(Default)]
+struct OpenFigure {
+    bbox: Option<String>,
+    alt: Option<String>,
+    caption: String,
+    caption_depth: usize,
+    attributes: HashMap<String, String>,
+}
+
+impl OpenFigure {
+    fn new(tag: &BytesStart) -> Self {
+        let mut figure = Self {
+            attributes: data_attributes(tag),
+            ..Self::default()
+        };
+        figure.image(tag);
+        figure
+    }
+
+    /// An `<img>` supplies the region and alt text the figure doesn't declare itself
+    fn image(&mut self, tag: &BytesStart) {
+        if self.bbox.is_none() {
+            self.bbox = attribute(tag, b"data-bbox");
+        }
+        if self.alt.is_none() {
+            self.alt = attribute(tag, b"alt").filter(|alt| !alt.trim().is_empty());
+        }
+    }
 }
 
-/// Parse XHTML content into structured components
-fn parse_xhtml_content(
-    xhtml: &str,
-) -> Result<(
-    Vec<PdfTextElement>,
-    DocumentMetadata,
-    StyleData,
-    Option<BookmarkData>,
-)> {
-    // Extract enhanced metadata from <meta> tags
-    let metadata = extract_enhanced_metadata(xhtml)?;
-
-    // Extract style data from CSS
-    let style_data = extract_style_data(xhtml)?;
-
-    // Extract bookmark data
-    let bookmark_data = extract_bookmark_data(xhtml)?;
-
-    // Extract text elements with full resolution (needs style and bookmark data)
-    let text_elements = extract_text_elements(xhtml, &style_data, &bookmark_data)?;
-
-    println!(
-        "✅ XHTML parsing complete: {} text elements, {} font classes, {} bookmarks",
-        text_elements.len(),
-        style_data.font_classes.len(),
-        bookmark_data
-            .as_ref()
-            .map(|b| b.sections.len())
-            .unwrap_or(0)
-    );
-
-    Ok((text_elements, metadata, style_data, bookmark_data))
+/// A positioned text span being read; `depth` counts spans nested inside it,
+/// whose text becomes part of this one
+struct OpenSpan {
+    class: String,
+    bbox: String,
+    line: Option<String>,
+    segment: Option<String>,
+    script: Option<String>,
+    /// Target of the `<a href>` the span is in, or holds
+    href: Option<String>,
+    text: String,
+    depth: usize,
 }
 
-/// Extract text elements with hierarchical parsing: pages → paragraphs → spans
-fn extract_text_elements(
-    xhtml: &str,
-    style_data: &StyleData,
-    bookmark_data: &Option<BookmarkData>,
-) -> Result<Vec<PdfTextElement>> {
-    // Pre-allocate capacity based on estimated element count
-    let estimated_elements = xhtml.matches("<span").count();
-    let mut text_elements = Vec::with_capacity(estimated_elements);
-    let mut global_paragraph_number = 0u32;
-    let mut global_reading_order = 0u32;
-
-    // Create bookmark lookup
-    let bookmark_sections: Vec<BookmarkSection> = bookmark_data
-        .as_ref()
-        .map(|bd| bd.sections.clone())
-        .unwrap_or_default();
-
-    let mut total_pages = 0;
-    for (page_index, page_cap) in PAGE_REGEX.captures_iter(xhtml).enumerate() {
-        let page_number = (page_index + 1) as u32;
-        total_pages = page_number;
-        let mut page_elements = Vec::new();
-
-        if let Some(page_content) = page_cap.get(1) {
-            let page_html = page_content.as_str();
-
-            for p_cap in PARAGRAPH_REGEX.captures_iter(page_html) {
-                if let Some(p_content) = p_cap.get(1) {
-                    let paragraph_html = p_content.as_str();
-
-                    extract_spans_from_paragraph(
-                        paragraph_html,
-                        page_number,
-                        global_paragraph_number,
-                        style_data,
-                        &bookmark_sections,
-                        &mut page_elements,
-                    )?;
-
-                    global_paragraph_number += 1;
+/// An `<a href>` open inside a page; the spans in it are its anchor
+struct OpenLink {
+    href: String,
+    /// Index in `links` of the link its spans so far make up
+    index: Option<usize>,
+}
+
+/// Routes XML events to pages, styles, metadata and the outline
+#[derive(Default)]
+struct XhtmlCollector {
+    pages: PageCollector,
+    page: Option<OpenPage>,
+    paragraph: Option<OpenParagraph>,
+    span: Option<OpenSpan>,
+    figure: Option<OpenFigure>,
+    /// Images met inside a paragraph, added once it closes
+    inline_figures: Vec<OpenFigure>,
+    link: Option<OpenLink>,
+    links: Vec<PageLink>,
+    /// CSS of the `<style>` block being read
+    style: Option<String>,
+    /// Classes from `<style>` blocks outside the page divs
+    font_classes: HashMap<String, FontClass>,
+    global_style_blocks: usize,
+    metadata: DocumentMetadata,
+    outline: OutlineBuilder,
+}
+
+impl XhtmlCollector {
+    fn open(&mut self, tag: &BytesStart, empty: bool) {
+        if let Some(span) = &mut self.span {
+            match tag.local_name().as_ref() {
+                b"span" if !empty => span.depth += 1,
+                b"a" => span.href = span.href.take().or_else(|| href(tag)),
+                _ => {}
+            }
+            return;
+        }
+
+        match tag.local_name().as_ref() {
+            b"div" => match &mut self.page {
+                Some(page) => page.div_depth += usize::from(!empty),
+                None if attribute(tag, b"class").is_some_and(|c| c.split_whitespace().any(|c| c == "page")) => {
+                    self.page = Some(OpenPage {
+                        number: self.pages.pages.len() as u32 + 1,
+                        start: self.pages.elements.len(),
+                        div_depth: 1,
+                    });
+                    if empty {
+                        self.close_page();
+                    }
+                }
+                None => {}
+            },
+            b"figure" if self.page.is_some() && self.figure.is_none() => {
+                self.close_paragraph();
+                self.figure = Some(OpenFigure::new(tag));
+                if empty {
+                    self.close_figure();
+                }
+            }
+            b"figcaption" if !empty => {
+                if let Some(figure) = &mut self.figure {
+                    figure.caption_depth += 1;
+                }
+            }
+            b"img" if self.page.is_some() => match (&mut self.figure, &self.paragraph) {
+                (Some(figure), _) => figure.image(tag),
+                (None, Some(_)) => self.inline_figures.push(OpenFigure::new(tag)),
+                (None, None) => {
+                    self.figure = Some(OpenFigure::new(tag));
+                    self.close_figure();
                 }
+            },
+            // Text inside a figure is only read as its caption
+            b"p" | b"span" if self.figure.is_some() => {}
+            b"p" if self.page.is_some() && !empty => {
+                self.close_paragraph();
+                self.paragraph = Some(OpenParagraph {
+                    start: self.pages.elements.len(),
+                    attributes: data_attributes(tag),
+                    explicit_scripts: false,
+                });
             }
+            // Spans without a bounding box are wrappers; their text is not positioned
+            b"span" if !empty => {
+                let (Some(paragraph), Some(bbox)) = (&mut self.paragraph, attribute(tag, b"data-bbox")) else {
+                    return;
+                };
+                let script = attribute(tag, b"data-script");
+                paragraph.explicit_scripts |= script.is_some();
+                self.span = Some(OpenSpan {
+                    class: attribute(tag, b"class").unwrap_or_default(),
+                    bbox,
+                    line: attribute(tag, b"data-line"),
+                    segment: attribute(tag, b"data-segment"),
+                    script,
+                    href: self.link.as_ref().map(|link| link.href.clone()),
+                    text: String::new(),
+                    depth: 0,
+                });
+            }
+            b"style" if !empty => self.style = Some(String::new()),
+            b"meta" if self.page.is_none() => {
+                if let (Some(name), Some(content)) = (attribute(tag, b"name"), attribute(tag, b"content")) {
+                    apply_meta(&mut self.metadata, &name, content);
+                }
+            }
+            b"a" if self.page.is_some() => self.open_link(tag, empty),
+            b"ul" | b"li" | b"a" if self.page.is_none() => self.outline.open(tag, empty),
+            _ => {}
+        }
+    }
 
-            // Sort page elements by spatial position: Y first (top to bottom), then X (left to right)
-            page_elements.sort_unstable_by(|a, b| {
-                a.bounding_box
-                    .y
-                    .total_cmp(&b.bounding_box.y)
-                    .then_with(|| a.bounding_box.x.total_cmp(&b.bounding_box.x))
+    fn open_link(&mut self, tag: &BytesStart, empty: bool) {
+        let (Some(page), Some(href)) = (&self.page, href(tag)) else {
+            return;
+        };
+        // A positioned anchor is a link region; its text is found in `finish`
+        if let Some([x, y, width, height]) = attribute(tag, b"data-bbox").as_deref().and_then(parse_bbox) {
+            self.links.push(PageLink {
+                page_number: page.number,
+                bounding_box: BoundingBox { x, y, width, height },
+                href,
+                text: String::new(),
             });
+        } else if !empty {
+            self.link = Some(OpenLink { href, index: None });
+        }
+    }
+
+    fn close(&mut self, name: &[u8]) {
+        match name {
+            b"span" => match &mut self.span {
+                Some(span) if span.depth > 0 => span.depth -= 1,
+                Some(_) => self.close_span(),
+                None => {}
+            },
+            b"p" => self.close_paragraph(),
+            b"a" => self.link = None,
+            b"figure" => self.close_figure(),
+            b"figcaption" => {
+                if let Some(figure) = &mut self.figure {
+                    figure.caption_depth = figure.caption_depth.saturating_sub(1);
+                }
+            }
+            b"div" => {
+                if let Some(page) = &mut self.page {
+                    page.div_depth -= 1;
+                    if page.div_depth == 0 {
+                        self.close_page();
+                    }
+                }
+            }
+            b"style" => {
+                let Some(css) = self.style.take() else {
+                    return;
+                };
+                let classes = css::parse_font_classes(&css);
+                match &self.page {
+                    Some(page) => {
+                        self.pages.style_blocks += 1;
+                        self.pages.page_font_classes.entry(page.number).or_default().extend(classes);
+                    }
+                    None => {
+                        self.global_style_blocks += 1;
+                        self.font_classes.extend(classes);
+                    }
+                }
+            }
+            b"ul" | b"li" if self.page.is_none() => self.outline.close(name),
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some(css) = &mut self.style {
+            css.push_str(text);
+        } else if let Some(span) = &mut self.span {
+            span.text.push_str(text);
+        } else if let Some(figure) = self.figure.as_mut().filter(|f| f.caption_depth > 0) {
+            figure.caption.push(' ');
+            figure.caption.push_str(text);
+        } else if self.page.is_none() {
+            self.outline.text(text);
+        }
+    }
+
+    fn close_span(&mut self) {
+        let (Some(span), Some(page)) = (self.span.take(), &self.page) else {
+            return;
+        };
+        let page_number = page.number;
+        if let Some(href) = &span.href {
+            self.link_span(href, &span, page_number);
+        }
+        self.pages
+            .push_span(span, page_number, self.pages.paragraphs.len() as u32);
+    }
+
+    /// Record a span as (part of) a link's anchor; the spans of one `<a>`
+    /// on one page make a single link
+    fn link_span(&mut self, href: &str, span: &OpenSpan, page_number: u32) {
+        let (Some([x, y, width, height]), text) = (parse_bbox(&span.bbox), span.text.trim()) else {
+            return;
+        };
+        if text.is_empty() {
+            return;
+        }
+        let bounding_box = BoundingBox { x, y, width, height };
+        let open = self.link.as_mut().filter(|link| link.href == href);
+        if let Some(link) = open.as_ref().and_then(|open| open.index).map(|index| &mut self.links[index]) {
+            if link.page_number == page_number {
+                let right = (link.bounding_box.x + link.bounding_box.width).max(x + width);
+                let bottom = (link.bounding_box.y + link.bounding_box.height).max(y + height);
+                link.bounding_box.x = link.bounding_box.x.min(x);
+                link.bounding_box.y = link.bounding_box.y.min(y);
+                link.bounding_box.width = right - link.bounding_box.x;
+                link.bounding_box.height = bottom - link.bounding_box.y;
+                link.text.push(' ');
+                link.text.push_str(text);
+                return;
+            }
+        }
+        self.links.push(PageLink {
+            page_number,
+            bounding_box,
+            href: href.to_string(),
+            text: text.to_string(),
+        });
+        if let Some(open) = open {
+            open.index = Some(self.links.len() - 1);
+        }
+    }
+
+    fn close_paragraph(&mut self) {
+        self.close_span();
+        if let Some(paragraph) = self.paragraph.take() {
+            self.pages.push_paragraph(paragraph);
+        }
+        for figure in std::mem::take(&mut self.inline_figures) {
+            self.figure = Some(figure);
+            self.close_figure();
+        }
+    }
+
+    fn close_figure(&mut self) {
+        let (Some(figure), Some(page)) = (self.figure.take(), &self.page) else {
+            return;
+        };
+        self.pages.push_figure(figure, page.number);
+    }
+
+    fn close_page(&mut self) {
+        self.close_figure();
+        self.close_paragraph();
+        if let Some(page) = self.page.take() {
+            self.pages.pages.push(page.start..self.pages.elements.len());
+        }
+    }
+
+    fn finish(mut self) -> Result<PreprocessorOutput> {
+        // An unterminated last page still counts
+        self.close_page();
+
+        let style_data = build_style_data(
+            self.font_classes,
+            std::mem::take(&mut self.pages.page_font_classes),
+            self.global_style_blocks + self.pages.style_blocks,
+        );
+        let mut warnings = Vec::new();
+        if style_data.font_classes.is_empty() && style_data.page_font_classes.is_empty() {
+            warnings.push(ProcessingWarning::new(
+                WarningCode::MissingStyles,
+                "No CSS styles found in XHTML; all text uses default styling",
+            ));
+        }
+
+        let bookmark_data = self.outline.finish();
+
+        // Resolve text elements (needs style and bookmark data)
+        let text_elements = self.pages.finish(&style_data, &bookmark_data, &mut warnings);
+
+        println!(
+            "✅ XHTML parsing complete: {} text elements, {} font classes, {} bookmarks",
+            text_elements.len(),
+            style_data.font_classes.len(),
+            bookmark_data
+                .as_ref()
+                .map(|b| b.sections.len())
+                .unwrap_or(0)
+        );
+
+        let mut links = self.links;
+        for link in links.iter_mut().filter(|link| link.text.is_empty()) {
+            link.text = anchor_text(&text_elements, link);
+        }
+
+        Ok(PreprocessorOutput {
+            text_elements,
+            metadata: self.metadata,
+            style_data,
+            bookmark_data,
+            warnings,
+            page_extraction: Vec::new(),
+            links,
+        })
+    }
+}
+
+/// Pages → paragraphs → spans, collected as pages stream past
+///
+/// Styles and bookmarks may only be known after the last page, so font
+/// resolution, script inference, bookmark matching and reading order wait
+/// for `finish`.
+#[derive(Default)]
+struct PageCollector {
+    elements: Vec<PdfTextElement>,
+    /// Element range per page, in page order
+    pages: Vec<std::ops::Range<usize>>,
+    /// Element range per paragraph, and whether it marks scripts explicitly
+    paragraphs: Vec<(std::ops::Range<usize>, bool)>,
+    /// Page → classes from `<style>` blocks inside that page's div
+    page_font_classes: HashMap<u32, HashMap<String, FontClass>>,
+    style_blocks: usize,
+    issues: SpanIssues,
+}
+
+impl PageCollector {
+    /// Add a span's text element; it keeps its class name in a placeholder
+    /// style until `finish`
+    fn push_span(&mut self, span: OpenSpan, page_number: u32, paragraph_number: u32) {
+        let text_content = span.text.trim();
+        if text_content.is_empty() {
+            return;
+        }
+
+        let Some([x, y, width, height]) = parse_bbox(&span.bbox) else {
+            self.issues.dropped_span(page_number);
+            return;
+        };
+        let number = |value: Option<String>| value.and_then(|v| v.parse::<u32>().ok()).unwrap_or(0);
+
+        self.elements.push(PdfTextElement {
+            text: text_content.to_string(),
+            style_info: fallback_font(&span.class),
+            bounding_box: BoundingBox {
+                x,
+                y,
+                width,
+                height,
+            },
+            page_number,
+            paragraph_number,
+            line_number: number(span.line),
+            segment_number: number(span.segment),
+            reading_order: 0, // Will be assigned during spatial sorting
+            bookmark_match: None, // Assigned per paragraph by BookmarkMatcher
+            token_count: estimate_token_count(text_content),
+            paragraph_attributes: HashMap::new(),
+            script: span
+                .script
+                .as_deref()
+                .and_then(ScriptPosition::from_attribute)
+                .unwrap_or_default(),
+        });
+    }
+
+    /// Add a figure as a paragraph of its own, so clustering never merges it with text
+    fn push_figure(&mut self, figure: OpenFigure, page_number: u32) {
+        let Some(bbox) = figure.bbox else {
+            return;
+        };
+        let Some([x, y, width, height]) = parse_bbox(&bbox) else {
+            self.issues.dropped_span(page_number);
+            return;
+        };
+
+        let caption = figure.caption.split_whitespace().collect::<Vec<_>>().join(" ");
+        let mut attributes = figure.attributes;
+        attributes.insert("role".to_string(), "figure".to_string());
+        if !caption.is_empty() {
+            attributes.insert("caption".to_string(), caption.clone());
+        }
+        let text = if caption.is_empty() {
+            figure.alt.unwrap_or_default().trim().to_string()
+        } else {
+            caption
+        };
+
+        let start = self.elements.len();
+        self.elements.push(PdfTextElement {
+            token_count: estimate_token_count(&text),
+            text,
+            style_info: fallback_font("figure"),
+            bounding_box: BoundingBox {
+                x,
+                y,
+                width,
+                height,
+            },
+            page_number,
+            paragraph_number: self.paragraphs.len() as u32,
+            line_number: 0,
+            segment_number: 0,
+            reading_order: 0,
+            bookmark_match: None,
+            paragraph_attributes: attributes,
+            script: ScriptPosition::Baseline,
+        });
+        self.paragraphs.push((start..self.elements.len(), false));
+    }
+
+    fn push_paragraph(&mut self, paragraph: OpenParagraph) {
+        // Backend hints on the <p> apply to every span inside it
+        if !paragraph.attributes.is_empty() {
+            for element in &mut self.elements[paragraph.start..] {
+                element.paragraph_attributes = paragraph.attributes.clone();
+            }
+        }
+        self.paragraphs
+            .push((paragraph.start..self.elements.len(), paragraph.explicit_scripts));
+    }
+
+    fn finish(
+        mut self,
+        style_data: &StyleData,
+        bookmark_data: &Option<BookmarkData>,
+        warnings: &mut Vec<ProcessingWarning>,
+    ) -> Vec<PdfTextElement> {
+        // Resolve font classes from style_data (figures carry no text style)
+        for element in self.elements.iter_mut().filter(|e| !e.is_figure()) {
+            match style_data.resolve(&element.style_info.class_name, element.page_number) {
+                Some(font_class) => element.style_info = font_class.clone(),
+                None => self
+                    .issues
+                    .fallback_font(&element.style_info.class_name, element.page_number),
+            }
+        }
+
+        // Create bookmark lookup
+        let bookmark_matcher = BookmarkMatcher::new(
+            bookmark_data
+                .as_ref()
+                .map(|bd| bd.sections.as_slice())
+                .unwrap_or_default(),
+        );
+        for (range, explicit_scripts) in &self.paragraphs {
+            let paragraph = &mut self.elements[range.clone()];
+            // Backends that mark scripts explicitly are trusted as-is
+            if !explicit_scripts {
+                infer_script_positions(paragraph);
+            }
+            bookmark_matcher.assign(paragraph);
+        }
+
+        let mut global_reading_order = 0u32;
+        for (page_index, range) in self.pages.iter().enumerate() {
+            let page_elements = &mut self.elements[range.clone()];
+
+            // Sort page elements by spatial position in the page's detected reading
+            // direction: top to bottom then left to right for most text
+            let direction = ReadingDirection::Auto
+                .resolve(page_elements.iter().map(|e| (e.text.as_str(), &e.bounding_box)));
+            page_elements.sort_unstable_by(|a, b| direction.compare(&a.bounding_box, &b.bounding_box));
 
             // Assign global reading order to sorted elements
-            for element in &mut page_elements {
+            for element in page_elements.iter_mut() {
                 element.reading_order = global_reading_order;
                 global_reading_order += 1;
             }
 
-            text_elements.extend(page_elements);
+            if page_elements.is_empty() {
+                warnings.push(
+                    ProcessingWarning::new(WarningCode::EmptyPage, "Page produced no text elements")
+                        .on_page(page_index as u32 + 1),
+                );
+            }
         }
+
+        let styles_missing =
+            style_data.font_classes.is_empty() && style_data.page_font_classes.is_empty();
+        warnings.extend(self.issues.into_warnings(styles_missing));
+
+        println!(
+            "📊 Total extraction: {} text elements from {} paragraphs across {} pages",
+            self.elements.len(),
+            self.paragraphs.len(),
+            self.pages.len()
+        );
+
+        self.elements
     }
+}
 
-    println!(
-        "📊 Total extraction: {} text elements from {} paragraphs across {} pages",
-        text_elements.len(),
-        global_paragraph_number,
-        total_pages
-    );
+/// A tag's attribute by name, with entities decoded
+fn attribute(tag: &BytesStart, name: &[u8]) -> Option<String> {
+    tag.html_attributes()
+        .with_checks(false)
+        .flatten()
+        .find(|attr| attr.key.as_ref() == name)
+        .and_then(|attr| Some(decode_entities(std::str::from_utf8(&attr.value).ok()?).into_owned()))
+}
 
-    Ok(text_elements)
+/// Parse a bounding box: "x,y,width,height"
+/// A link's target; links within the document (`#...`) are not kept
+fn href(tag: &BytesStart) -> Option<String> {
+    attribute(tag, b"href")
+        .map(|href| href.trim().to_string())
+        .filter(|href| !href.is_empty() && !href.starts_with('#'))
 }
 
-/// Extract spans from a single paragraph with proper page and paragraph context
-fn extract_spans_from_paragraph(
-    paragraph_html: &str,
-    page_number: u32,
-    paragraph_number: u32,
-    style_data: &StyleData,
-    bookmark_sections: &[BookmarkSection],
-    text_elements: &mut Vec<PdfTextElement>,
-) -> Result<()> {
-    for cap in SPAN_REGEX.captures_iter(paragraph_html) {
-        if let (Some(class), Some(bbox_str), Some(line_str), Some(segment_str), Some(text)) =
-            (cap.get(1), cap.get(2), cap.get(3), cap.get(4), cap.get(5))
-        {
-            let text_content = text.as_str().trim();
-            if text_content.is_empty() {
-                continue;
-            }
+fn parse_bbox(bbox: &str) -> Option<[f32; 4]> {
+    let parts: Vec<f32> = bbox.split(',').map_while(|part| part.trim().parse().ok()).collect();
+    parts.try_into().ok()
+}
 
-            // Parse bounding box: "x,y,width,height"
-            let bbox_parts: Vec<&str> = bbox_str.as_str().split(',').collect();
-            if bbox_parts.len() == 4 {
-                if let (Ok(x), Ok(y), Ok(width), Ok(height)) = (
-                    bbox_parts[0].parse::<f32>(),
-                    bbox_parts[1].parse::<f32>(),
-                    bbox_parts[2].parse::<f32>(),
-                    bbox_parts[3].parse::<f32>(),
-                ) {
-                    let line_number = line_str.as_str().parse::<u32>().unwrap_or(0);
-                    let segment_number = segment_str.as_str().parse::<u32>().unwrap_or(0);
-
-                    // Resolve font class from style_data
-                    let font_class_name = class.as_str();
-                    let resolved_font_class =
-                        if let Some(font_class) = style_data.font_classes.get(font_class_name) {
-                            font_class.clone()
-                        } else {
-                            fallback_font(font_class_name)
-                        };
-
-                    // Check for bookmark match
-                    let bookmark_match = bookmark_sections
-                        .iter()
-                        .find(|section| section.title.trim() == text_content)
-                        .cloned();
-
-                    text_elements.push(PdfTextElement {
-                        text: text_content.to_string(),
-                        style_info: resolved_font_class,
-                        bounding_box: BoundingBox {
-                            x,
-                            y,
-                            width,
-                            height,
-                        },
-                        page_number,
-                        paragraph_number,
-                        line_number,
-                        segment_number,
-                        reading_order: 0, // Will be assigned during spatial sorting
-                        bookmark_match,
-                        token_count: estimate_token_count(text_content),
-                    });
-                }
+/// `data-*` attributes of a tag, keyed without the prefix
+fn data_attributes(tag: &BytesStart) -> HashMap<String, String> {
+    tag.html_attributes()
+        .with_checks(false)
+        .flatten()
+        .filter_map(|attr| {
+            let key = std::str::from_utf8(attr.key.as_ref()).ok()?.strip_prefix("data-")?;
+            let value = std::str::from_utf8(&attr.value).ok()?;
+            Some((key.to_string(), decode_entities(value).into_owned()))
+        })
+        .collect()
+}
+
+/// Flag small spans raised above or dropped below the span they attach to:
+/// the previous span, or the next one for a leading marker ("¹ See ...")
+fn infer_script_positions(elements: &mut [PdfTextElement]) {
+    let inferred: Vec<(usize, ScriptPosition)> = (0..elements.len())
+        .filter_map(|index| {
+            let candidate = &elements[index];
+            let previous = index.checked_sub(1).and_then(|i| elements.get(i));
+            previous
+                .and_then(|reference| script_against(candidate, reference))
+                .or_else(|| {
+                    elements
+                        .get(index + 1)
+                        .and_then(|reference| script_against(candidate, reference))
+                })
+                .map(|script| (index, script))
+        })
+        .collect();
+    for (index, script) in inferred {
+        elements[index].script = script;
+    }
+}
+
+fn script_against(candidate: &PdfTextElement, reference: &PdfTextElement) -> Option<ScriptPosition> {
+    let size = reference.style_info.font_size;
+    let (c, r) = (&candidate.bounding_box, &reference.bounding_box);
+    let horizontal_gap = (c.x - (r.x + r.width)).max(r.x - (c.x + c.width));
+    let plausible = candidate.text.chars().count() <= MAX_SCRIPT_CHARS
+        && candidate.style_info.font_size > 0.0
+        && candidate.style_info.font_size <= size * SCRIPT_SIZE_RATIO
+        && candidate.page_number == reference.page_number
+        && c.y < r.y + r.height
+        && c.y + c.height > r.y
+        && horizontal_gap < size;
+    if !plausible {
+        return None;
+    }
+    let shift = (r.y + r.height) - (c.y + c.height);
+    if shift >= SUPERSCRIPT_SHIFT_EM * size {
+        Some(ScriptPosition::Superscript)
+    } else if -shift >= SUBSCRIPT_SHIFT_EM * size {
+        Some(ScriptPosition::Subscript)
+    } else {
+        None
+    }
+}
+
+/// Per-document tallies of spans the parser had to work around
+#[derive(Default)]
+struct SpanIssues {
+    /// Unresolved class → (span count, first page seen)
+    fallback_fonts: BTreeMap<String, (usize, u32)>,
+    /// Page → spans and images dropped for an unparseable bounding box
+    dropped_spans: BTreeMap<u32, usize>,
+}
+
+impl SpanIssues {
+    fn fallback_font(&mut self, class_name: &str, page_number: u32) {
+        self.fallback_fonts
+            .entry(class_name.to_string())
+            .or_insert((0, page_number))
+            .0 += 1;
+    }
+
+    fn dropped_span(&mut self, page_number: u32) {
+        *self.dropped_spans.entry(page_number).or_default() += 1;
+    }
+
+    /// One warning per unresolved class and per page with dropped spans.
+    /// Fallback fonts are not reported individually when the XHTML had no
+    /// styles at all - that is already a single `MissingStyles` warning.
+    fn into_warnings(self, styles_missing: bool) -> Vec<ProcessingWarning> {
+        let mut warnings = Vec::new();
+        if !styles_missing {
+            for (class_name, (count, first_page)) in self.fallback_fonts {
+                warnings.push(
+                    ProcessingWarning::new(
+                        WarningCode::FallbackFont,
+                        format!(
+                            "Font class '{}' has no CSS definition; default styling used for {} span(s)",
+                            class_name, count
+                        ),
+                    )
+                    .on_page(first_page),
+                );
             }
         }
+        for (page_number, count) in self.dropped_spans {
+            warnings.push(
+                ProcessingWarning::new(
+                    WarningCode::DroppedSpan,
+                    format!("Dropped {} span(s) or image(s) with an unparseable bounding box", count),
+                )
+                .on_page(page_number),
+            );
+        }
+        warnings
+    }
+}
+
+/// Decode the XML entities backends escape in span text (`&amp;`, `&lt;`, `&#8217;`, ...)
+fn decode_entities(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
     }
 
-    Ok(())
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let entity = after.find(';').filter(|&end| end <= 10).map(|end| &after[..end]);
+        let replacement = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                    u32::from_str_radix(hex, 16).ok()
+                } else {
+                    entity.strip_prefix('#').and_then(|dec| dec.parse().ok())
+                };
+                code.and_then(char::from_u32)
+            }
+        });
+
+        match (entity, replacement) {
+            (Some(entity), Some(c)) => {
+                decoded.push(c);
+                rest = &after[entity.len() + 1..];
+            }
+            _ => {
+                // Not an entity we know - keep the ampersand literally
+                decoded.push('&');
+                rest = after;
+            }
+        }
+    }
+    decoded.push_str(rest);
+    Cow::Owned(decoded)
 }
 
 fn fallback_font(font_class_name: &str) -> FontClass {
@@ -248,144 +838,278 @@ fn fallback_font(font_class_name: &str) -> FontClass {
     }
 }
 
-fn estimate_token_count(text: &str) -> usize {
+pub(crate) fn estimate_token_count(text: &str) -> usize {
     text.len() / 4 // Rough estimation: ~4 characters per token
 }
 
-/// Extract enhanced metadata from <meta> tags
-fn extract_enhanced_metadata(xhtml: &str) -> Result<DocumentMetadata> {
-    let mut metadata = DocumentMetadata::default();
-
-    for cap in META_REGEX.captures_iter(xhtml) {
-        if let (Some(name), Some(content)) = (cap.get(1), cap.get(2)) {
-            let name_str = name.as_str();
-            let content_str = content.as_str().to_string();
-
-            match name_str {
-                "dc:title" => metadata.title = Some(content_str),
-                "dc:creator" => metadata.author = Some(content_str),
-                "dc:language" => metadata.language = Some(content_str),
-                "xmp:dc:publisher" | "dc:publisher" => metadata.publisher = Some(content_str),
-                "xmp:CreatorTool" => metadata.creator_tool = Some(content_str),
-                "pdf:producer" => metadata.producer = Some(content_str),
-                "pdf:PDFVersion" => metadata.pdf_version = Some(content_str),
-                "dcterms:created" => metadata.created = Some(content_str),
-                "dcterms:modified" => metadata.modified = Some(content_str),
-                "dc:description" => metadata.description = Some(content_str),
-                "pdf:encrypted" => metadata.encrypted = Some(content_str == "true"),
-                "pdf:hasMarkedContent" => metadata.has_marked_content = Some(content_str == "true"),
-                "xmpTPg:NPages" => {
-                    if let Ok(pages) = content_str.parse::<u32>() {
-                        metadata.page_count = pages;
-                    }
-                }
-                _ => {}
+/// Record a `<meta name=".." content="..">` value
+fn apply_meta(metadata: &mut DocumentMetadata, name: &str, content: String) {
+    match name {
+        "dc:title" => metadata.title = Some(content),
+        "dc:creator" => metadata.author = Some(content),
+        "dc:language" => metadata.language = Some(content),
+        "xmp:dc:publisher" | "dc:publisher" => metadata.publisher = Some(content),
+        "xmp:CreatorTool" => metadata.creator_tool = Some(content),
+        "pdf:producer" => metadata.producer = Some(content),
+        "pdf:PDFVersion" => metadata.pdf_version = Some(content),
+        "dcterms:created" => metadata.created = Some(content),
+        "dcterms:modified" => metadata.modified = Some(content),
+        "dc:description" => metadata.description = Some(content),
+        "pdf:encrypted" => metadata.encrypted = Some(content == "true"),
+        "pdf:hasMarkedContent" => metadata.has_marked_content = Some(content == "true"),
+        "xmpTPg:NPages" => {
+            if let Ok(pages) = content.parse::<u32>() {
+                metadata.page_count = pages;
             }
         }
+        _ => {}
     }
+}
 
-    Ok(metadata)
+/// Build style data from the CSS `<style>` blocks
+///
+/// Blocks outside page divs were merged into the global classes (later
+/// blocks win on conflicts); blocks inside a page div stay scoped to that
+/// page.
+fn build_style_data(
+    font_classes: HashMap<String, FontClass>,
+    page_font_classes: HashMap<u32, HashMap<String, FontClass>>,
+    block_count: usize,
+) -> StyleData {
+    let style_data = StyleData {
+        font_classes,
+        page_font_classes,
+    };
+
+    if style_data.font_classes.is_empty() && style_data.page_font_classes.is_empty() {
+        println!("⚠️  No CSS styles found in XHTML - returning empty StyleData");
+    } else if block_count > 1 {
+        println!(
+            "🎨 Merged {} style blocks ({} global classes, {} page-scoped blocks)",
+            block_count,
+            style_data.font_classes.len(),
+            style_data.page_font_classes.len()
+        );
+    }
+
+    style_data
 }
 
-/// Extract style data from CSS <style> block
-fn extract_style_data(xhtml: &str) -> Result<StyleData> {
-    if let Some(style_start) = xhtml.rfind("<style") {
-        if let Some(style_end) = xhtml[style_start..].find("</style>") {
-            let style_block = &xhtml[style_start..style_start + style_end + 8];
-
-            if let Some(style_cap) = STYLE_REGEX.captures(style_block) {
-                if let Some(css_content) = style_cap.get(1) {
-                    let css = css_content.as_str();
-
-                    let mut font_classes = HashMap::new();
-
-                    for cap in FONT_CLASS_REGEX.captures_iter(css) {
-                        if let (
-                            Some(class_name),
-                            Some(family),
-                            Some(size_str),
-                            Some(style),
-                            Some(weight),
-                            Some(color),
-                        ) = (
-                            cap.get(1),
-                            cap.get(2),
-                            cap.get(3),
-                            cap.get(4),
-                            cap.get(5),
-                            cap.get(6),
-                        ) {
-                            let class_name_str = class_name.as_str().to_string();
-
-                            let size_text = size_str.as_str().trim();
-                            let size = size_text
-                                .trim_end_matches("px")
-                                .parse::<f32>()
-                                .unwrap_or(12.0);
-
-                            let font_class = FontClass {
-                                class_name: class_name_str.clone(),
-                                font_family: family.as_str().trim().to_string(),
-                                font_size: size,
-                                font_style: style.as_str().trim().to_string(),
-                                font_weight: weight.as_str().trim().to_string(),
-                                color: color.as_str().trim().to_string(),
-                            };
-
-                            font_classes.insert(class_name_str, font_class);
-                        }
-                    }
+/// An outline entry whose title is still being read
+struct PendingItem {
+    title: String,
+    page: Option<u32>,
+    level: u32,
+    emitted: bool,
+}
+
+/// Bookmark data from the outline's nested <ul><li> structure
+///
+/// The outline is the last top-level `<ul>` outside the pages. Nested lists
+/// become deeper levels, and each entry records its enclosing entry's
+/// `order` as `parent`; entries are flattened in document order. A page
+/// target is read from `data-page` on the `<li>` or an `<a href="#page=N">`.
+#[derive(Default)]
+struct OutlineBuilder {
+    sections: Vec<BookmarkSection>,
+    open_items: Vec<PendingItem>,
+    list_depth: u32,
+    /// Sections of the last completed top-level list
+    outline: Vec<BookmarkSection>,
+}
 
-                    if !font_classes.is_empty() {
-                        return Ok(StyleData { font_classes });
+impl OutlineBuilder {
+    fn open(&mut self, tag: &BytesStart, empty: bool) {
+        match tag.local_name().as_ref() {
+            b"ul" if !empty => {
+                if self.list_depth == 0 {
+                    self.sections.clear();
+                    self.open_items.clear();
+                } else if let Some(item) = self.open_items.last_mut() {
+                    // A nested list finishes the parent's title, so the parent precedes its children
+                    Self::emit(item, &mut self.sections);
+                }
+                self.list_depth += 1;
+            }
+            b"li" if self.list_depth > 0 && !empty => self.open_items.push(PendingItem {
+                title: String::new(),
+                page: attribute(tag, b"data-page").and_then(|page| page.parse().ok()),
+                level: self.list_depth,
+                emitted: false,
+            }),
+            b"a" => {
+                if let Some(item) = self.open_items.last_mut() {
+                    item.page = item.page.or_else(|| {
+                        let href = attribute(tag, b"href")?;
+                        let page = href.strip_prefix("#page")?;
+                        page.strip_prefix('=').unwrap_or(page).parse().ok()
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, name: &[u8]) {
+        match name {
+            b"li" => {
+                if let Some(mut item) = self.open_items.pop() {
+                    Self::emit(&mut item, &mut self.sections);
+                }
+            }
+            b"ul" if self.list_depth > 0 => {
+                self.list_depth -= 1;
+                if self.list_depth == 0 {
+                    for mut item in std::mem::take(&mut self.open_items) {
+                        Self::emit(&mut item, &mut self.sections);
                     }
+                    self.outline = std::mem::take(&mut self.sections);
                 }
             }
+            _ => {}
+        }
+    }
+
+    /// Text belongs to the innermost item until its sublist opens
+    fn text(&mut self, text: &str) {
+        if let Some(item) = self.open_items.last_mut() {
+            if !item.emitted {
+                item.title.push_str(text);
+            }
+        }
+    }
+
+    fn emit(item: &mut PendingItem, sections: &mut Vec<BookmarkSection>) {
+        if item.emitted {
+            return;
+        }
+        item.emitted = true;
+        let title = item.title.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !title.is_empty() {
+            // Parents are emitted before their children, so the enclosing
+            // bookmark is the last one emitted at a shallower level
+            let parent = sections.iter().rev().find(|s| s.level < item.level).map(|s| s.order);
+            sections.push(BookmarkSection {
+                title,
+                order: sections.len() as u32,
+                level: item.level,
+                parent,
+                page: item.page,
+                confidence: None,
+            });
         }
     }
 
-    println!("⚠️  No CSS styles found in XHTML - returning empty StyleData");
-    Ok(StyleData {
-        font_classes: HashMap::new(),
-    })
+    fn finish(self) -> Option<BookmarkData> {
+        if self.outline.is_empty() {
+            None
+        } else {
+            Some(BookmarkData {
+                sections: self.outline,
+            })
+        }
+    }
 }
 
-/// Extract bookmark data from <ul><li> structure
-fn extract_bookmark_data(xhtml: &str) -> Result<Option<BookmarkData>> {
-    if let Some(ul_start) = xhtml.rfind("<ul>") {
-        if let Some(ul_end) = xhtml[ul_start..].find("</ul>") {
-            let ul_block = &xhtml[ul_start..ul_start + ul_end + 5];
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            if let Some(list_cap) = LIST_REGEX.captures(ul_block) {
-                if let Some(list_content) = list_cap.get(1) {
-                    let content = list_content.as_str();
+    #[test]
+    fn reports_fallback_fonts_dropped_spans_and_empty_pages() {
+        let xhtml = r#"<html><head><style>.f1 { font-family: Times; font-size: 12px; }</style></head><body>
+<div class="page" data-page="1"><p><span class="f1" data-bbox="10,10,50,12" data-line="1" data-segment="1">Known</span><span class="f9" data-bbox="10,30,50,12" data-line="2" data-segment="1">Unknown</span><span class="f9" data-bbox="10,50,50,12" data-line="3" data-segment="1">Again</span></p></div>
+<div class="page" data-page="2"><p><span class="f1" data-bbox="10,oops,50,12" data-line="1" data-segment="1">Broken</span></p></div>
+</body></html>"#;
 
-                    let mut sections = Vec::new();
+        let output = parse_xhtml(xhtml).unwrap();
+        assert_eq!(output.text_elements.len(), 3);
 
-                    for cap in LIST_ITEM_REGEX.captures_iter(content) {
-                        if let Some(title_match) = cap.get(1) {
-                            let title = title_match.as_str().trim().to_string();
+        let summary: Vec<(WarningCode, Option<u32>)> =
+            output.warnings.iter().map(|w| (w.code, w.page)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (WarningCode::EmptyPage, Some(2)),
+                (WarningCode::FallbackFont, Some(1)),
+                (WarningCode::DroppedSpan, Some(2)),
+            ]
+        );
+        assert!(output.warnings[1].message.contains("'f9'"));
+        assert!(output.warnings[1].message.contains("2 span(s)"));
+    }
 
-                            if title.is_empty() {
-                                continue;
-                            }
+    #[test]
+    fn streams_pages_with_trailing_styles_and_nested_divs() {
+        let xhtml = r#"<html><body>
+<div class="page" data-page="1"><div class="column"><p><span class="f1" data-bbox="10,10,50,12" data-line="1" data-segment="1">Inside a column</span></p></div><p><span class="f2" data-bbox="10,40,50,9" data-line="2" data-segment="1">After the column &amp; more</span></p></div>
+<div class="page" data-page="2"><style>.f1 { font-size: 20px; }</style><p><span class="f1" data-bbox="10,10,50,20" data-line="1" data-segment="1">Page-scoped style</span></p></div>
+<style>.f1 { font-family: Times; font-size: 12px; } .f2 { font-size: 9px; }</style>
+</body></html>"#;
 
-                            let order = sections.len() as u32;
+        // A tiny read buffer forces tags to straddle reads
+        let output = parse_xhtml_reader(std::io::BufReader::with_capacity(7, xhtml.as_bytes())).unwrap();
+        let summary: Vec<(&str, u32, f32)> = output
+            .text_elements
+            .iter()
+            .map(|e| (e.text.as_str(), e.page_number, e.style_info.font_size))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Inside a column", 1, 12.0),
+                ("After the column & more", 1, 9.0),
+                ("Page-scoped style", 2, 20.0),
+            ]
+        );
+        assert!(output.warnings.is_empty(), "{:?}", output.warnings);
+    }
 
-                            sections.push(BookmarkSection {
-                                title: title.clone(),
-                                order,
-                            });
-                        }
-                    }
+    #[test]
+    fn reads_spans_regardless_of_attribute_order_and_entities() {
+        let xhtml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<html><head><meta content="Caf&#233; &amp; Co" name="dc:title" /><style>.f1 { font-size: 12px; }</style></head><body>
+<div data-page="1" class="page first"><p data-role="heading">
+<span data-segment="2" data-line="1" data-bbox="10,10,50,12" class="f1">R&amp;D&nbsp;costs</span>
+<span class="f1" data-bbox="70,10,30,12" data-line="1" data-segment="3"><span lang="en">&lt;nested&gt;</span> text</span>
+<span class="wrapper"><span data-bbox="10,30,50,12" class="f1" data-line="2" data-segment="1">Wrapped</span></span>
+</p></div>
+</body></html>"#;
 
-                    if !sections.is_empty() {
-                        return Ok(Some(BookmarkData { sections }));
-                    }
-                }
-            }
-        }
+        let output = parse_xhtml(xhtml).unwrap();
+        let texts: Vec<&str> = output.text_elements.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, vec!["R&D\u{a0}costs", "<nested> text", "Wrapped"]);
+        assert_eq!(output.text_elements[0].segment_number, 2);
+        assert_eq!(output.text_elements[1].line_number, 1);
+        assert!(output.text_elements.iter().all(|e| e.paragraph_role() == Some("heading")));
+        assert_eq!(output.metadata.title.as_deref(), Some("Café & Co"));
+        assert!(output.warnings.is_empty(), "{:?}", output.warnings);
     }
 
-    Ok(None)
+    #[test]
+    fn reads_links_around_spans_inside_spans_and_as_regions() {
+        let xhtml = r##"<html><head><style>.f1 { font-size: 10px; }</style></head><body>
+<div class="page"><p>
+<span class="f1" data-bbox="10,10,40,10">See</span>
+<a href="https://example.org/a"><span class="f1" data-bbox="55,10,40,10">the first</span>
+<span class="f1" data-bbox="10,22,40,10">source</span></a>
+<span class="f1" data-bbox="55,22,60,10">and <a href="https://example.org/b">this</a></span>
+<span class="f1" data-bbox="10,34,100,10">then read the appendix</span>
+<a href="https://example.org/c" data-bbox="60,34,50,10"/>
+<a href="#p2"><span class="f1" data-bbox="10,46,40,10">local</span></a>
+</p></div></body></html>"##;
+
+        let output = parse_xhtml(xhtml).unwrap();
+        let links: Vec<(&str, &str)> = output.links.iter().map(|l| (l.href.as_str(), l.text.as_str())).collect();
+        assert_eq!(
+            links,
+            [
+                ("https://example.org/a", "the first source"),
+                ("https://example.org/b", "and this"),
+                ("https://example.org/c", "the appendix"),
+            ]
+        );
+        assert_eq!(output.links[0].bounding_box, BoundingBox { x: 10.0, y: 10.0, width: 85.0, height: 22.0 });
+        assert_eq!(output.text_elements.len(), 6);
+    }
 }
@@ -0,0 +1,447 @@
+//! PPTX Preprocessor
+//!
+//! Parses PowerPoint decks (ZIP + PresentationML) natively, without the JVM,
+//! into Blazegraph XHTML with `XhtmlWriter`:
+//!
+//! - Slides are read in presentation order (`p:sldIdLst`), one page each, so
+//!   node page numbers are slide numbers
+//! - The slide's title placeholder becomes `data-role="heading"` level 1 and
+//!   opens the slide's section; untitled slides get a "Slide N" heading so
+//!   every slide still maps to one Section
+//! - Every other text box becomes one paragraph, its `a:p` paragraphs as lines
+//! - Table rows become `data-role="table-row"`, cells joined with " | "
+//!
+//! Date, footer and slide-number placeholders are repeated boilerplate and
+//! are skipped, as are speaker notes.
+
+use crate::preprocessors::ooxml::{self, attr, end_name, local_name, read_part};
+use crate::preprocessors::pdf::xhtml_parser;
+use crate::preprocessors::traits::Preprocessor;
+use crate::preprocessors::xhtml_writer::{heading_size, Block, RunStyle, XhtmlWriter};
+use crate::types::*;
+use anyhow::{anyhow, Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::Path;
+use zip::ZipArchive;
+
+const PRESENTATION_PART: &str = "ppt/presentation.xml";
+const PRESENTATION_RELS_PART: &str = "ppt/_rels/presentation.xml.rels";
+/// PowerPoint's default body text size when a run doesn't set one
+const BODY_SIZE: f32 = 18.0;
+const TITLE_PLACEHOLDERS: &[&str] = &["title", "ctrTitle"];
+const BOILERPLATE_PLACEHOLDERS: &[&str] = &["dt", "ftr", "sldNum"];
+
+/// PowerPoint deck preprocessor (pure Rust)
+#[derive(Debug, Default)]
+pub struct PptxPreprocessor;
+
+impl PptxPreprocessor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Convert PPTX bytes to Blazegraph XHTML
+    pub fn pptx_to_xhtml(&self, pptx_bytes: &[u8]) -> Result<String> {
+        let mut archive =
+            ZipArchive::new(Cursor::new(pptx_bytes)).context("Not a PPTX file: invalid ZIP container")?;
+
+        let slides = slide_parts(&mut archive)?;
+        let mut writer = XhtmlWriter::new();
+        ooxml::write_document_properties(&mut archive, &mut writer)?;
+
+        for (index, part) in slides.iter().enumerate() {
+            let xml = read_part(&mut archive, part)?
+                .with_context(|| format!("PPTX is missing slide part {}", part))?;
+            writer.page_break();
+            let mut walker = SlideWalker::default();
+            walker
+                .walk(&xml)
+                .with_context(|| format!("Failed to parse PPTX slide {}", index + 1))?;
+            walker.write(index + 1, &mut writer);
+        }
+
+        println!("📄 PPTX converted: {} slide(s)", slides.len());
+        Ok(writer.finish())
+    }
+}
+
+impl Preprocessor for PptxPreprocessor {
+    /// Step 1: Convert PPTX to Blazegraph XHTML
+    fn parse_pdf_to_markup_language(&self, pptx_bytes: &[u8]) -> Result<String> {
+        self.pptx_to_xhtml(pptx_bytes)
+    }
+
+    /// Step 2: Parse XHTML to PreprocessorOutput
+    fn parse_markup_to_preprocessor_output(&self, markup: &str) -> Result<PreprocessorOutput> {
+        xhtml_parser::parse_xhtml(markup)
+    }
+
+    fn name(&self) -> &str {
+        "PptxPreprocessor"
+    }
+
+    fn supports_file_type(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("pptx"))
+    }
+}
+
+/// Slide part names in presentation order
+fn slide_parts(archive: &mut ooxml::Package) -> Result<Vec<String>> {
+    let presentation = read_part(archive, PRESENTATION_PART)?
+        .with_context(|| format!("Not a PPTX file: missing {}", PRESENTATION_PART))?;
+    let rels = read_part(archive, PRESENTATION_RELS_PART)?
+        .with_context(|| format!("Not a PPTX file: missing {}", PRESENTATION_RELS_PART))?;
+
+    // Relationship id → part name, targets are relative to ppt/
+    let mut targets = HashMap::new();
+    let mut reader = Reader::from_str(&rels);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if local_name(&e) == "Relationship" => {
+                if let (Some(id), Some(target)) = (attr(&e, "Id"), attr(&e, "Target")) {
+                    let part = match target.strip_prefix('/') {
+                        Some(absolute) => absolute.to_string(),
+                        None => format!("ppt/{}", target),
+                    };
+                    targets.insert(id, part);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let mut slides = Vec::new();
+    let mut reader = Reader::from_str(&presentation);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if local_name(&e) == "sldId" => {
+                let id = relationship_id(&e).ok_or_else(|| anyhow!("PPTX slide entry without r:id"))?;
+                let part = targets
+                    .get(&id)
+                    .with_context(|| format!("PPTX slide relationship {} has no target", id))?;
+                slides.push(part.clone());
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(slides)
+}
+
+/// The prefixed `r:id` attribute (`sldId` also has a plain numeric `id`)
+fn relationship_id(element: &BytesStart) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|a| a.key.prefix().is_some() && a.key.local_name().as_ref() == b"id")
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+/// Text shape being built
+#[derive(Default)]
+struct ShapeState {
+    placeholder: Option<String>,
+    block: Block,
+    /// The current `a:p` already has text, so the next one starts a new line
+    paragraph_has_text: bool,
+}
+
+impl ShapeState {
+    fn is_title(&self) -> bool {
+        self.placeholder
+            .as_deref()
+            .is_some_and(|kind| TITLE_PLACEHOLDERS.contains(&kind))
+    }
+}
+
+/// Run formatting from `a:rPr`; unset properties use the shape's defaults
+#[derive(Default)]
+struct RunProps {
+    size: Option<f32>,
+    bold: bool,
+    italic: bool,
+    family: Option<String>,
+}
+
+/// Streaming walk over one `ppt/slides/slideN.xml`
+#[derive(Default)]
+struct SlideWalker {
+    title: Option<Block>,
+    blocks: Vec<Block>,
+    shape: Option<ShapeState>,
+    /// Cells of the table row being read
+    row: Option<Vec<String>>,
+    cell: String,
+    in_run_properties: bool,
+    in_text: bool,
+    run_props: RunProps,
+}
+
+impl SlideWalker {
+    fn walk(&mut self, xml: &str) -> Result<()> {
+        let mut reader = Reader::from_str(xml);
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) => self.start(&e, false),
+                Event::Empty(e) => self.start(&e, true),
+                Event::End(e) => self.end(&end_name(&e)),
+                Event::Text(t) if self.in_text => {
+                    let text = t.unescape()?;
+                    self.push_text(&text);
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn start(&mut self, e: &BytesStart, empty: bool) {
+        match local_name(e).as_str() {
+            "sp" if !empty => self.shape = Some(ShapeState::default()),
+            "ph" => {
+                if let Some(shape) = self.shape.as_mut() {
+                    // A placeholder without a type is the body placeholder
+                    shape.placeholder = Some(attr(e, "type").unwrap_or_else(|| "body".to_string()));
+                }
+            }
+            "tr" if !empty => self.row = Some(Vec::new()),
+            "tc" if !empty => self.cell.clear(),
+            "p" if !empty => {
+                if let Some(shape) = self.shape.as_mut() {
+                    shape.paragraph_has_text = false;
+                }
+            }
+            "r" | "fld" if !empty => self.run_props = RunProps::default(),
+            "rPr" => {
+                self.run_props.size = attr(e, "sz")
+                    .and_then(|v| v.parse::<f32>().ok())
+                    .map(|hundredths| hundredths / 100.0);
+                self.run_props.bold = attr(e, "b").as_deref() == Some("1");
+                self.run_props.italic = attr(e, "i").as_deref() == Some("1");
+                self.in_run_properties = !empty;
+            }
+            "latin" if self.in_run_properties => self.run_props.family = attr(e, "typeface"),
+            "t" if !empty => self.in_text = true,
+            "br" => self.line_break(),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        match name {
+            "sp" => self.finish_shape(),
+            "tc" => {
+                if let Some(row) = self.row.as_mut() {
+                    row.push(self.cell.trim().to_string());
+                }
+            }
+            "tr" => {
+                if let Some(row) = self.row.take().filter(|row| row.iter().any(|c| !c.is_empty())) {
+                    let mut block = Block::new().attr("role", "table-row");
+                    block.push_run(&row.join(" | "), &self.body_style());
+                    self.blocks.push(block);
+                }
+            }
+            "p" if self.row.is_some() => self.cell.push(' '),
+            "rPr" => self.in_run_properties = false,
+            "t" => self.in_text = false,
+            _ => {}
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.row.is_some() {
+            self.cell.push_str(text);
+            return;
+        }
+        let style = match &self.shape {
+            Some(shape) if shape.is_title() => self.title_style(),
+            Some(_) => self.body_style(),
+            None => return,
+        };
+        let Some(shape) = self.shape.as_mut() else {
+            return;
+        };
+        if !shape.paragraph_has_text && shape.block.has_text() {
+            // Title paragraphs read as one line; text box paragraphs as lines
+            if shape.is_title() {
+                shape.block.push_run(" ", &style);
+            } else {
+                shape.block.line_break();
+            }
+        }
+        shape.paragraph_has_text = true;
+        shape.block.push_run(text, &style);
+    }
+
+    fn line_break(&mut self) {
+        match self.shape.as_mut() {
+            Some(shape) if !shape.is_title() => shape.block.line_break(),
+            _ => self.push_text(" "),
+        }
+    }
+
+    fn finish_shape(&mut self) {
+        let Some(shape) = self.shape.take() else {
+            return;
+        };
+        let boilerplate = shape
+            .placeholder
+            .as_deref()
+            .is_some_and(|kind| BOILERPLATE_PLACEHOLDERS.contains(&kind));
+        if boilerplate || !shape.block.has_text() {
+            return;
+        }
+        if shape.is_title() && self.title.is_none() {
+            self.title = Some(shape.block.attr("role", "heading").attr("level", "1"));
+        } else {
+            self.blocks.push(shape.block);
+        }
+    }
+
+    /// Emit the slide: its heading first, then text boxes in reading order
+    fn write(self, slide_number: usize, writer: &mut XhtmlWriter) {
+        let heading = self.title.unwrap_or_else(|| {
+            let mut block = Block::new().attr("role", "heading").attr("level", "1");
+            let style = RunStyle {
+                size: heading_size(1),
+                bold: true,
+                ..RunStyle::default()
+            };
+            block.push_run(&format!("Slide {}", slide_number), &style);
+            block
+        });
+        writer.push_block(heading.attr("slide", slide_number.to_string()));
+        for block in self.blocks {
+            writer.push_block(block);
+        }
+    }
+
+    fn body_style(&self) -> RunStyle {
+        RunStyle {
+            family: self.run_props.family.clone().unwrap_or_else(|| RunStyle::default().family),
+            size: self.run_props.size.unwrap_or(BODY_SIZE),
+            bold: self.run_props.bold,
+            italic: self.run_props.italic,
+            ..RunStyle::default()
+        }
+    }
+
+    fn title_style(&self) -> RunStyle {
+        RunStyle {
+            size: self.run_props.size.unwrap_or(heading_size(1)),
+            bold: true,
+            ..self.body_style()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    const NS: &str = r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main""#;
+
+    fn shape(placeholder: Option<&str>, paragraphs: &[&str]) -> String {
+        let ph = placeholder
+            .map(|kind| format!(r#"<p:nvPr><p:ph type="{kind}"/></p:nvPr>"#))
+            .unwrap_or_else(|| "<p:nvPr/>".to_string());
+        let body: String = paragraphs
+            .iter()
+            .map(|text| format!(r#"<a:p><a:r><a:rPr lang="en-US" sz="2000"/><a:t>{text}</a:t></a:r></a:p>"#))
+            .collect();
+        format!(r#"<p:sp><p:nvSpPr><p:cNvPr id="2" name="Shape"/><p:cNvSpPr/>{ph}</p:nvSpPr><p:txBody><a:bodyPr/>{body}</p:txBody></p:sp>"#)
+    }
+
+    fn slide(shapes: &str) -> String {
+        format!(r#"<p:sld {NS}><p:cSld><p:spTree>{shapes}</p:spTree></p:cSld></p:sld>"#)
+    }
+
+    /// Slide parts are deliberately numbered against presentation order
+    fn pptx() -> Vec<u8> {
+        let presentation = format!(
+            r#"<p:presentation {NS}><p:sldIdLst><p:sldId id="256" r:id="rId3"/><p:sldId id="257" r:id="rId2"/></p:sldIdLst></p:presentation>"#
+        );
+        let rels = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId2" Type="slide" Target="slides/slide1.xml"/><Relationship Id="rId3" Type="slide" Target="slides/slide2.xml"/></Relationships>"#;
+        let first = slide(&[
+            shape(Some("sldNum"), &["1"]),
+            shape(None, &["Revenue grew in every region.", "Costs were flat."]),
+            shape(Some("title"), &["Quarterly Review"]),
+        ]
+        .concat());
+        let table = r#"<p:graphicFrame><a:graphic><a:graphicData><a:tbl>
+            <a:tr><a:tc><a:txBody><a:p><a:r><a:t>Region</a:t></a:r></a:p></a:txBody></a:tc><a:tc><a:txBody><a:p><a:r><a:t>Growth</a:t></a:r></a:p></a:txBody></a:tc></a:tr>
+            <a:tr><a:tc><a:txBody><a:p><a:r><a:t>EMEA</a:t></a:r></a:p></a:txBody></a:tc><a:tc><a:txBody><a:p><a:r><a:t>12%</a:t></a:r></a:p></a:txBody></a:tc></a:tr>
+            </a:tbl></a:graphicData></a:graphic></p:graphicFrame>"#;
+        let second = slide(table);
+
+        let mut buffer = Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut buffer);
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        for (name, content) in [
+            (PRESENTATION_PART, presentation.as_str()),
+            (PRESENTATION_RELS_PART, rels),
+            ("ppt/slides/slide1.xml", second.as_str()),
+            ("ppt/slides/slide2.xml", first.as_str()),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        buffer.into_inner()
+    }
+
+    #[test]
+    fn slides_become_sections_with_slide_page_numbers() {
+        use crate::config::{ParsingConfig, PipelineConfig};
+        use crate::processor::DocumentProcessor;
+        use crate::storage::NoOpStorage;
+
+        let path = std::env::temp_dir().join(format!("blazegraph-pptx-{}.pptx", std::process::id()));
+        std::fs::write(&path, pptx()).unwrap();
+        let config = ParsingConfig {
+            pipeline: PipelineConfig::from_rule_names(&["SectionDetection", "SpatialClustering"]),
+            ..ParsingConfig::default()
+        };
+        let mut processor = DocumentProcessor::new_with_dependencies(
+            Box::new(PptxPreprocessor::new()),
+            Box::new(NoOpStorage),
+        )
+        .unwrap();
+        let stages = processor
+            .process_document_capture_stages(path.to_str().unwrap(), &config)
+            .unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let find = |text: &str| {
+            stages
+                .text_elements
+                .iter()
+                .find(|e| e.text == text)
+                .unwrap_or_else(|| panic!("missing element {:?}", text))
+        };
+        assert_eq!(find("Quarterly Review").page_number, 1);
+        assert_eq!(find("Revenue grew in every region.").page_number, 1);
+        assert_eq!(find("Costs were flat.").page_number, 1);
+        assert_eq!(find("EMEA | 12%").paragraph_role(), Some("table-row"));
+        assert_eq!(find("EMEA | 12%").page_number, 2);
+        assert!(stages.text_elements.iter().all(|e| e.text != "1"), "slide number kept");
+
+        let sections: Vec<(&str, u32)> = stages
+            .parsed_elements
+            .iter()
+            .filter(|e| e.element_type == ParsedElementType::Section)
+            .map(|e| (e.text.as_str(), e.page_number))
+            .collect();
+        assert_eq!(sections, vec![("Quarterly Review", 1), ("Slide 2", 2)]);
+    }
+}
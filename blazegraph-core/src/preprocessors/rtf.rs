@@ -0,0 +1,453 @@
+//! RTF Preprocessor
+//!
+//! Rich Text Format is a stream of control words and `{}` groups; this
+//! walks it once, natively (no JVM), and writes Blazegraph XHTML with
+//! `XhtmlWriter`:
+//!
+//! - `\par` ends a paragraph; `\page` starts a new page
+//! - `\outlinelevelN`, or a `\stylesheet` entry named "heading N", makes a
+//!   paragraph `data-role="heading"` with `data-level`
+//! - Paragraphs in a list (`\ls`, level from `\ilvl`) become list items and
+//!   paragraphs in a table (`\intbl`) table cells
+//! - `\b`, `\i`, `\fs` and `\plain` set run styles; `\u` and `\'hh` escapes
+//!   are decoded (the latter as Windows-1252)
+//! - `{\info}` supplies title, author and description metadata
+//!
+//! Destinations that aren't body text (font/color tables, pictures, headers
+//! and footers, field instructions, list numbering text) are skipped.
+
+use crate::preprocessors::pdf::xhtml_parser;
+use crate::preprocessors::traits::Preprocessor;
+use crate::preprocessors::xhtml_writer::{heading_level_from_name, Block, RunStyle, XhtmlWriter};
+use crate::types::*;
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// RTF's default font size (`\fs24`) when a document doesn't set one
+const DEFAULT_SIZE: f32 = 12.0;
+
+/// Destinations whose text is not document content
+const SKIPPED_DESTINATIONS: &[&str] = &[
+    "fonttbl", "colortbl", "filetbl", "listtable", "listoverridetable", "revtbl", "rsidtbl",
+    "generator", "latentstyles", "themedata", "colorschememapping", "datastore", "xmlnstbl",
+    "pict", "object", "nonshppict", "shp", "header", "headerl", "headerr", "headerf", "footer",
+    "footerl", "footerr", "footerf", "footnote", "fldinst", "pntext", "pntxta", "pntxtb",
+    "listtext", "xe", "tc", "bkmkstart", "bkmkend",
+];
+
+/// RTF document preprocessor (pure Rust)
+#[derive(Debug, Default)]
+pub struct RtfPreprocessor;
+
+impl RtfPreprocessor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Convert RTF bytes to Blazegraph XHTML
+    pub fn rtf_to_xhtml(&self, rtf_bytes: &[u8]) -> Result<String> {
+        let start = rtf_bytes.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(0);
+        if !rtf_bytes[start..].starts_with(b"{\\rtf") {
+            bail!("Not an RTF file: missing {{\\rtf header");
+        }
+
+        let mut walker = RtfWalker::new();
+        walker.walk(&rtf_bytes[start..]);
+        walker.finish_paragraph();
+
+        let mut writer = walker.writer;
+        for (name, content) in walker.metadata {
+            writer.meta(name, content.trim());
+        }
+        println!("📄 RTF converted: {} page(s)", writer.page_count());
+        Ok(writer.finish())
+    }
+}
+
+impl Preprocessor for RtfPreprocessor {
+    /// Step 1: Convert RTF to Blazegraph XHTML
+    fn parse_pdf_to_markup_language(&self, rtf_bytes: &[u8]) -> Result<String> {
+        self.rtf_to_xhtml(rtf_bytes)
+    }
+
+    /// Step 2: Parse XHTML to PreprocessorOutput
+    fn parse_markup_to_preprocessor_output(&self, markup: &str) -> Result<PreprocessorOutput> {
+        xhtml_parser::parse_xhtml(markup)
+    }
+
+    fn name(&self) -> &str {
+        "RtfPreprocessor"
+    }
+
+    fn supports_file_type(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("rtf"))
+    }
+}
+
+/// Where the text of the current group goes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Destination {
+    Body,
+    Skip,
+    Stylesheet,
+    Info,
+    /// An `\info` field, recorded under this `<meta>` name
+    InfoField(&'static str),
+}
+
+/// Character formatting and destination; saved and restored by `{` / `}`
+#[derive(Debug, Clone)]
+struct GroupState {
+    destination: Destination,
+    bold: bool,
+    italic: bool,
+    size: f32,
+    script: ScriptPosition,
+    /// Fallback characters to drop after each `\uN` (`\ucN`)
+    unicode_skip: usize,
+}
+
+impl Default for GroupState {
+    fn default() -> Self {
+        Self {
+            destination: Destination::Body,
+            bold: false,
+            italic: false,
+            size: DEFAULT_SIZE,
+            script: ScriptPosition::Baseline,
+            unicode_skip: 1,
+        }
+    }
+}
+
+/// Paragraph formatting; persists across `\par` until `\pard`
+#[derive(Debug, Clone, Default)]
+struct ParagraphProps {
+    style: Option<i32>,
+    outline_level: Option<u32>,
+    in_list: bool,
+    list_level: u32,
+    in_table: bool,
+}
+
+struct RtfWalker {
+    writer: XhtmlWriter,
+    state: GroupState,
+    stack: Vec<GroupState>,
+    paragraph: ParagraphProps,
+    block: Block,
+    /// Page break seen after the paragraph already had text
+    break_after: bool,
+    /// Style sheet: `\sN` index → style name
+    styles: HashMap<i32, String>,
+    style_entry: (i32, String),
+    metadata: Vec<(&'static str, String)>,
+    /// Fallback characters still to drop after a `\uN`
+    pending_skip: usize,
+}
+
+impl RtfWalker {
+    fn new() -> Self {
+        Self {
+            writer: XhtmlWriter::new(),
+            state: GroupState::default(),
+            stack: Vec::new(),
+            paragraph: ParagraphProps::default(),
+            block: Block::new(),
+            break_after: false,
+            styles: HashMap::new(),
+            style_entry: (0, String::new()),
+            metadata: Vec::new(),
+            pending_skip: 0,
+        }
+    }
+
+    fn walk(&mut self, rtf: &[u8]) {
+        let mut index = 0;
+        while index < rtf.len() {
+            match rtf[index] {
+                b'{' => {
+                    self.stack.push(self.state.clone());
+                    if self.state.destination == Destination::Stylesheet {
+                        self.style_entry = (0, String::new());
+                    }
+                    index += 1;
+                }
+                b'}' => {
+                    if let Some(state) = self.stack.pop() {
+                        self.state = state;
+                    }
+                    index += 1;
+                }
+                b'\\' => index = self.control(rtf, index + 1),
+                b'\r' | b'\n' => index += 1,
+                byte => {
+                    self.push_char(decode_cp1252(byte));
+                    index += 1;
+                }
+            }
+        }
+    }
+
+    /// Handle the control word or symbol after a backslash; returns the next index
+    fn control(&mut self, rtf: &[u8], start: usize) -> usize {
+        let Some(&first) = rtf.get(start) else {
+            return start;
+        };
+
+        if !first.is_ascii_alphabetic() {
+            match first {
+                b'\\' | b'{' | b'}' => self.push_char(first as char),
+                b'\'' => {
+                    let byte = rtf
+                        .get(start + 1..start + 3)
+                        .and_then(|hex| std::str::from_utf8(hex).ok())
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                    if let Some(byte) = byte {
+                        self.push_char(decode_cp1252(byte));
+                    }
+                    return start + 3;
+                }
+                // Unknown destinations behind \* are optional by definition
+                b'*' => self.state.destination = Destination::Skip,
+                b'~' => self.push_char(' '),
+                b'_' => self.push_char('-'),
+                b'\r' | b'\n' => self.end_paragraph(),
+                _ => {}
+            }
+            return start + 1;
+        }
+
+        let mut end = start;
+        while rtf.get(end).is_some_and(u8::is_ascii_alphabetic) {
+            end += 1;
+        }
+        let word = std::str::from_utf8(&rtf[start..end]).unwrap_or_default();
+        let param_start = end;
+        if rtf.get(end) == Some(&b'-') {
+            end += 1;
+        }
+        while rtf.get(end).is_some_and(u8::is_ascii_digit) {
+            end += 1;
+        }
+        let param = std::str::from_utf8(&rtf[param_start..end])
+            .ok()
+            .and_then(|digits| digits.parse::<i32>().ok());
+        if rtf.get(end) == Some(&b' ') {
+            end += 1;
+        }
+
+        self.control_word(word, param);
+        end
+    }
+
+    fn control_word(&mut self, word: &str, param: Option<i32>) {
+        if SKIPPED_DESTINATIONS.contains(&word) {
+            self.state.destination = Destination::Skip;
+            return;
+        }
+        let on = param != Some(0);
+        match word {
+            "stylesheet" => self.state.destination = Destination::Stylesheet,
+            "info" => self.state.destination = Destination::Info,
+            "title" | "author" | "doccomm" if self.state.destination == Destination::Info => {
+                self.state.destination = Destination::InfoField(match word {
+                    "title" => "dc:title",
+                    "author" => "dc:creator",
+                    _ => "dc:description",
+                });
+            }
+            "s" if self.state.destination == Destination::Stylesheet => {
+                self.style_entry.0 = param.unwrap_or(0);
+            }
+            "s" => self.paragraph.style = param,
+            "pard" => self.paragraph = ParagraphProps::default(),
+            "outlinelevel" => self.paragraph.outline_level = param.map(|level| level.max(0) as u32),
+            "ls" => self.paragraph.in_list = true,
+            "ilvl" => self.paragraph.list_level = param.unwrap_or(0).max(0) as u32,
+            "intbl" => self.paragraph.in_table = true,
+            "plain" => {
+                self.state.bold = false;
+                self.state.italic = false;
+                self.state.size = DEFAULT_SIZE;
+                self.state.script = ScriptPosition::Baseline;
+            }
+            "b" => self.state.bold = on,
+            "i" => self.state.italic = on,
+            "super" => self.state.script = ScriptPosition::Superscript,
+            "sub" => self.state.script = ScriptPosition::Subscript,
+            "nosupersub" => self.state.script = ScriptPosition::Baseline,
+            // Raised/lowered by N half-points without shrinking
+            "up" if param.unwrap_or(6) > 0 => self.state.script = ScriptPosition::Superscript,
+            "dn" if param.unwrap_or(6) > 0 => self.state.script = ScriptPosition::Subscript,
+            "up" | "dn" => self.state.script = ScriptPosition::Baseline,
+            "fs" => self.state.size = param.map_or(DEFAULT_SIZE, |half_points| half_points as f32 / 2.0),
+            "uc" => self.state.unicode_skip = param.unwrap_or(1).max(0) as usize,
+            "u" => {
+                if let Some(code) = param {
+                    // Code points past 32767 are written as negative numbers
+                    let code = if code < 0 { code + 65536 } else { code };
+                    self.push_char(char::from_u32(code as u32).unwrap_or('\u{fffd}'));
+                    self.pending_skip = self.state.unicode_skip;
+                }
+            }
+            "par" | "cell" => self.end_paragraph(),
+            "line" => self.block.line_break(),
+            "tab" => self.push_char(' '),
+            "page" => self.page_break(),
+            "emdash" => self.push_char('—'),
+            "endash" => self.push_char('–'),
+            "bullet" => self.push_char('•'),
+            "lquote" => self.push_char('‘'),
+            "rquote" => self.push_char('’'),
+            "ldblquote" => self.push_char('“'),
+            "rdblquote" => self.push_char('”'),
+            _ => {}
+        }
+    }
+
+    fn push_char(&mut self, ch: char) {
+        if self.pending_skip > 0 {
+            self.pending_skip -= 1;
+            return;
+        }
+        match self.state.destination {
+            Destination::Body => {
+                let style = RunStyle {
+                    size: self.state.size,
+                    bold: self.state.bold,
+                    italic: self.state.italic,
+                    script: self.state.script,
+                    ..RunStyle::default()
+                };
+                self.block.push_run(ch.encode_utf8(&mut [0; 4]), &style);
+            }
+            Destination::Stylesheet => {
+                if ch == ';' {
+                    let (index, name) = std::mem::take(&mut self.style_entry);
+                    self.styles.insert(index, name.trim().to_string());
+                } else {
+                    self.style_entry.1.push(ch);
+                }
+            }
+            Destination::InfoField(name) => match self.metadata.last_mut() {
+                Some((last, text)) if *last == name => text.push(ch),
+                _ => self.metadata.push((name, ch.to_string())),
+            },
+            Destination::Skip | Destination::Info => {}
+        }
+    }
+
+    /// Break before the current paragraph if it has no text yet, otherwise after it
+    fn page_break(&mut self) {
+        if self.block.has_text() {
+            self.break_after = true;
+        } else {
+            self.writer.page_break();
+        }
+    }
+
+    fn end_paragraph(&mut self) {
+        if self.state.destination == Destination::Body {
+            self.finish_paragraph();
+        }
+    }
+
+    fn finish_paragraph(&mut self) {
+        let mut block = std::mem::take(&mut self.block);
+        let style_name = self.paragraph.style.and_then(|index| self.styles.get(&index));
+
+        // Direct outline level on the paragraph wins over the style's name
+        let heading_level = match self.paragraph.outline_level {
+            Some(level) if level < 9 => Some(level + 1),
+            _ => style_name.and_then(|name| heading_level_from_name(name)),
+        };
+
+        if let Some(level) = heading_level {
+            block.set_attr("role", "heading");
+            block.set_attr("level", level.to_string());
+        } else if style_name.is_some_and(|name| name.eq_ignore_ascii_case("title")) {
+            block.set_attr("role", "title");
+        } else if self.paragraph.in_table {
+            block.set_attr("role", "table-cell");
+        } else if self.paragraph.in_list {
+            block.set_attr("role", "list-item");
+            block.set_attr("list-level", (self.paragraph.list_level + 1).to_string());
+        }
+        if let Some(name) = style_name.filter(|name| !name.is_empty()) {
+            block.set_attr("style", name.clone());
+        }
+
+        self.writer.push_block(block);
+        if std::mem::take(&mut self.break_after) {
+            self.writer.page_break();
+        }
+    }
+}
+
+/// Windows-1252, the code page of almost every `\ansi` document
+fn decode_cp1252(byte: u8) -> char {
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+        '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+    ];
+    match byte {
+        0x80..=0x9f => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"{\rtf1\ansi\ansicpg1252\deff0
+{\fonttbl{\f0\froman Times New Roman;}}
+{\colortbl;\red0\green0\blue0;}
+{\stylesheet{\s0 Normal;}{\s1\b\fs32 heading 1;}{\s2\b\fs28 Heading 2;}{\*\cs10 Default Paragraph Font;}}
+{\info{\title Pump Station Report}{\author A. Author}}
+\pard\plain\s1\b\fs32 Introduction\par
+\pard\plain\s0\fs24 Caf\'e9 visits are logged {\i weekly}\emdash see \u8364?5 fees.\par
+{\*\bkmkstart scope}\pard\plain\outlinelevel1\b Scope\par
+\pard\plain\ls1\ilvl0 {\listtext\'95\tab}First item\par
+{\listtext\'95\tab}Second item\par
+\pard\plain\page Appendix text\par
+}"#;
+
+    #[test]
+    fn converts_rtf_structure_and_styles() {
+        let output = RtfPreprocessor::new().process(SAMPLE.as_bytes()).unwrap();
+        let find = |text: &str| {
+            output
+                .text_elements
+                .iter()
+                .find(|e| e.text == text)
+                .unwrap_or_else(|| panic!("missing element {:?}", text))
+        };
+        let attr = |text: &str, name: &str| find(text).paragraph_attributes.get(name).cloned();
+
+        assert_eq!(find("Introduction").paragraph_role(), Some("heading"));
+        assert_eq!(find("Introduction").paragraph_level(), Some(1));
+        assert_eq!(find("Introduction").style_info.font_size, 16.0);
+        assert_eq!(find("Scope").paragraph_level(), Some(2));
+
+        assert_eq!(find("Café visits are logged").paragraph_role(), None);
+        assert_eq!(find("weekly").style_info.font_style, "italic");
+        // The space after a control word is its delimiter; \u drops its "?" fallback
+        assert_eq!(find("—see €5 fees.").style_info.font_style, "normal");
+
+        // List numbering text is skipped; items keep their level
+        assert_eq!(find("First item").paragraph_role(), Some("list-item"));
+        assert_eq!(attr("Second item", "list-level").as_deref(), Some("1"));
+
+        assert_eq!(find("Appendix text").page_number, 2);
+        assert!(!output.text_elements.iter().any(|e| e.text.contains("Times") || e.text.contains("Normal")));
+        assert_eq!(output.metadata.title.as_deref(), Some("Pump Station Report"));
+        assert_eq!(output.metadata.author.as_deref(), Some("A. Author"));
+
+        assert!(RtfPreprocessor::new().process(b"plain text").is_err());
+    }
+}
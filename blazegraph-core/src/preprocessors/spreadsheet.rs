@@ -0,0 +1,653 @@
+//! Spreadsheet Preprocessor
+//!
+//! Parses CSV/TSV files and Excel workbooks (XLSX: ZIP + SpreadsheetML)
+//! natively, without the JVM, into Blazegraph XHTML with `XhtmlWriter`:
+//!
+//! - Each visible worksheet becomes one page, opened by a level-1 heading
+//!   with the sheet name (CSV files have a single, unnamed sheet)
+//! - Runs of non-empty rows form one table; blank rows separate tables
+//! - Every row becomes `data-role="table-row"` with a `data-table` id and
+//!   its cells joined by `join_table_cells`, so base conversion reassembles
+//!   the rows into one `Table` element with row/column structure intact
+//! - A first row of non-numeric labels is marked `data-header="true"`
+//!
+//! Cell values are written as stored: shared and inline strings, booleans as
+//! TRUE/FALSE, formulas as their cached result. Numbers in date formats
+//! become ISO dates. The CSV delimiter (comma, semicolon, tab or pipe) is
+//! sniffed from the first line.
+//!
+//! Spreadsheets have no page layout, so graphs are `FlowType::Free`.
+
+use crate::preprocessors::ooxml::{self, attr, end_name, local_name, read_part};
+use crate::preprocessors::pdf::xhtml_parser;
+use crate::preprocessors::traits::Preprocessor;
+use crate::preprocessors::xhtml_writer::{heading_size, Block, RunStyle, XhtmlWriter};
+use crate::types::*;
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveDate};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Cursor;
+use std::path::Path;
+use zip::ZipArchive;
+
+const WORKBOOK_PART: &str = "xl/workbook.xml";
+const WORKBOOK_RELS_PART: &str = "xl/_rels/workbook.xml.rels";
+const SHARED_STRINGS_PART: &str = "xl/sharedStrings.xml";
+const STYLES_PART: &str = "xl/styles.xml";
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const CSV_DELIMITERS: [char; 4] = [',', ';', '\t', '|'];
+/// Built-in number formats that display dates or times
+const BUILTIN_DATE_FORMATS: &[u32] = &[14, 15, 16, 17, 18, 19, 20, 21, 22, 45, 46, 47];
+
+/// CSV/TSV and XLSX preprocessor (pure Rust)
+#[derive(Debug, Default)]
+pub struct SpreadsheetPreprocessor;
+
+impl SpreadsheetPreprocessor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Convert CSV or XLSX bytes to Blazegraph XHTML
+    pub fn spreadsheet_to_xhtml(&self, bytes: &[u8]) -> Result<String> {
+        let mut writer = XhtmlWriter::new();
+        let sheets = if bytes.starts_with(ZIP_MAGIC) {
+            read_workbook(bytes, &mut writer)?
+        } else {
+            vec![Sheet {
+                name: None,
+                rows: parse_delimited(&String::from_utf8_lossy(bytes)),
+            }]
+        };
+
+        let mut table_count = 0;
+        for sheet in &sheets {
+            writer.page_break();
+            if let Some(name) = &sheet.name {
+                let mut heading = Block::new().attr("role", "heading").attr("level", "1");
+                let style = RunStyle {
+                    size: heading_size(1),
+                    bold: true,
+                    ..RunStyle::default()
+                };
+                heading.push_run(name, &style);
+                writer.push_block(heading);
+            }
+            for table in split_tables(&sheet.rows) {
+                table_count += 1;
+                write_table(table, table_count, &mut writer);
+            }
+        }
+
+        println!(
+            "📊 Spreadsheet converted: {} sheet(s), {} table(s)",
+            sheets.len(),
+            table_count
+        );
+        Ok(writer.finish())
+    }
+}
+
+impl Preprocessor for SpreadsheetPreprocessor {
+    /// Step 1: Convert the spreadsheet to Blazegraph XHTML
+    fn parse_pdf_to_markup_language(&self, bytes: &[u8]) -> Result<String> {
+        self.spreadsheet_to_xhtml(bytes)
+    }
+
+    /// Step 2: Parse XHTML to PreprocessorOutput
+    fn parse_markup_to_preprocessor_output(&self, markup: &str) -> Result<PreprocessorOutput> {
+        xhtml_parser::parse_xhtml(markup)
+    }
+
+    fn name(&self) -> &str {
+        "SpreadsheetPreprocessor"
+    }
+
+    fn supports_file_type(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "csv" | "tsv" | "xlsx"))
+    }
+
+    fn flow_type(&self) -> FlowType {
+        FlowType::Free
+    }
+}
+
+/// One worksheet as dense rows of cell text
+struct Sheet {
+    name: Option<String>,
+    rows: Vec<Vec<String>>,
+}
+
+/// Blank rows separate tables; empty trailing columns are dropped and line
+/// breaks inside cells become spaces
+fn split_tables(rows: &[Vec<String>]) -> Vec<Vec<Vec<String>>> {
+    let is_blank = |row: &Vec<String>| row.iter().all(|cell| cell.trim().is_empty());
+    rows.split(is_blank)
+        .filter(|table| !table.is_empty())
+        .map(|table| {
+            let width = table
+                .iter()
+                .map(|row| row.iter().rposition(|cell| !cell.trim().is_empty()).map_or(0, |i| i + 1))
+                .max()
+                .unwrap_or(0);
+            table
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .take(width)
+                        .map(|cell| cell.split_whitespace().collect::<Vec<_>>().join(" "))
+                        .collect()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// A first row of distinct, non-numeric labels above at least one more row
+fn has_header_row(table: &[Vec<String>]) -> bool {
+    let Some(first) = table.first() else {
+        return false;
+    };
+    let mut seen = HashSet::new();
+    table.len() > 1
+        && first
+            .iter()
+            .all(|cell| !cell.is_empty() && cell.parse::<f64>().is_err() && seen.insert(cell.as_str()))
+}
+
+fn write_table(table: Vec<Vec<String>>, table_id: usize, writer: &mut XhtmlWriter) {
+    let header = has_header_row(&table);
+    for (index, row) in table.iter().enumerate() {
+        let mut block = Block::new()
+            .attr("role", "table-row")
+            .attr("table", table_id.to_string());
+        let style = if header && index == 0 {
+            block.set_attr("header", "true");
+            RunStyle {
+                bold: true,
+                ..RunStyle::default()
+            }
+        } else {
+            RunStyle::default()
+        };
+        block.push_run(&join_table_cells(row), &style);
+        writer.push_block(block);
+    }
+}
+
+// ===== CSV =====
+
+/// RFC 4180 records: quoted fields may hold delimiters, newlines and `""`
+fn parse_delimited(text: &str) -> Vec<Vec<String>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let delimiter = sniff_delimiter(text);
+
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c if c == delimiter => row.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+/// The candidate delimiter that occurs most often on the first line
+fn sniff_delimiter(text: &str) -> char {
+    let first_line = text.lines().find(|line| !line.trim().is_empty()).unwrap_or("");
+    let mut in_quotes = false;
+    let mut counts = [0usize; CSV_DELIMITERS.len()];
+    for c in first_line.chars() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        } else if !in_quotes {
+            if let Some(index) = CSV_DELIMITERS.iter().position(|&d| d == c) {
+                counts[index] += 1;
+            }
+        }
+    }
+    counts
+        .iter()
+        .enumerate()
+        .filter(|(_, &count)| count > 0)
+        .max_by_key(|(index, &count)| (count, std::cmp::Reverse(*index)))
+        .map_or(',', |(index, _)| CSV_DELIMITERS[index])
+}
+
+// ===== XLSX =====
+
+/// Visible worksheets in workbook order; also writes document properties
+fn read_workbook(bytes: &[u8], writer: &mut XhtmlWriter) -> Result<Vec<Sheet>> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(bytes)).context("Not an XLSX file: invalid ZIP container")?;
+    ooxml::write_document_properties(&mut archive, writer)?;
+
+    let workbook = read_part(&mut archive, WORKBOOK_PART)?
+        .with_context(|| format!("Not an XLSX file: missing {}", WORKBOOK_PART))?;
+    let rels = read_part(&mut archive, WORKBOOK_RELS_PART)?
+        .with_context(|| format!("Not an XLSX file: missing {}", WORKBOOK_RELS_PART))?;
+    let shared_strings = match read_part(&mut archive, SHARED_STRINGS_PART)? {
+        Some(xml) => parse_shared_strings(&xml).context("Failed to parse XLSX shared strings")?,
+        None => Vec::new(),
+    };
+    let date_styles = match read_part(&mut archive, STYLES_PART)? {
+        Some(xml) => parse_date_styles(&xml).context("Failed to parse XLSX styles")?,
+        None => HashSet::new(),
+    };
+
+    // Relationship id → part name, targets are relative to xl/
+    let mut targets = HashMap::new();
+    let mut reader = Reader::from_str(&rels);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if local_name(&e) == "Relationship" => {
+                if let (Some(id), Some(target)) = (attr(&e, "Id"), attr(&e, "Target")) {
+                    let part = match target.strip_prefix('/') {
+                        Some(absolute) => absolute.to_string(),
+                        None => format!("xl/{}", target),
+                    };
+                    targets.insert(id, part);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let mut sheets = Vec::new();
+    let mut reader = Reader::from_str(&workbook);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if local_name(&e) == "sheet" => {
+                if attr(&e, "state").is_some_and(|state| state != "visible") {
+                    continue;
+                }
+                let name = attr(&e, "name").unwrap_or_default();
+                let Some(part) = attr(&e, "id").and_then(|id| targets.get(&id)) else {
+                    continue;
+                };
+                let xml = read_part(&mut archive, part)?
+                    .with_context(|| format!("XLSX is missing worksheet part {}", part))?;
+                let rows = parse_worksheet(&xml, &shared_strings, &date_styles)
+                    .with_context(|| format!("Failed to parse XLSX worksheet {:?}", name))?;
+                sheets.push(Sheet {
+                    name: Some(name),
+                    rows,
+                });
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(sheets)
+}
+
+/// `xl/sharedStrings.xml`: one string per `si`, rich-text runs concatenated
+/// and phonetic guides (`rPh`) skipped
+fn parse_shared_strings(xml: &str) -> Result<Vec<String>> {
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    let mut in_phonetic = false;
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match local_name(&e).as_str() {
+                "si" => current.clear(),
+                "t" => in_text = !in_phonetic,
+                "rPh" => in_phonetic = true,
+                _ => {}
+            },
+            Event::Empty(e) if local_name(&e) == "si" => strings.push(String::new()),
+            Event::Text(t) if in_text => current.push_str(&t.unescape()?),
+            Event::End(e) => match end_name(&e).as_str() {
+                "si" => strings.push(std::mem::take(&mut current)),
+                "t" => in_text = false,
+                "rPh" => in_phonetic = false,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(strings)
+}
+
+/// Indexes into `cellXfs` (the cell `s` attribute) whose number format is a date
+fn parse_date_styles(xml: &str) -> Result<HashSet<usize>> {
+    let mut custom_dates = HashSet::new();
+    let mut date_styles = HashSet::new();
+    let mut in_cell_xfs = false;
+    let mut xf_index = 0;
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) => match local_name(&e).as_str() {
+                "numFmt" => {
+                    if let (Some(id), Some(code)) = (attr(&e, "numFmtId"), attr(&e, "formatCode")) {
+                        if is_date_format(&code) {
+                            custom_dates.extend(id.parse::<u32>());
+                        }
+                    }
+                }
+                "cellXfs" => in_cell_xfs = true,
+                "xf" if in_cell_xfs => {
+                    let format = attr(&e, "numFmtId").and_then(|id| id.parse::<u32>().ok());
+                    if format.is_some_and(|id| BUILTIN_DATE_FORMATS.contains(&id) || custom_dates.contains(&id)) {
+                        date_styles.insert(xf_index);
+                    }
+                    xf_index += 1;
+                }
+                _ => {}
+            },
+            Event::End(e) if end_name(&e) == "cellXfs" => in_cell_xfs = false,
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(date_styles)
+}
+
+/// Format codes with day/month/year or hour placeholders outside quoted
+/// literals and `[...]` sections (colors, locales, elapsed time)
+fn is_date_format(code: &str) -> bool {
+    let mut in_quotes = false;
+    let mut in_brackets = false;
+    let mut chars = code.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '[' if !in_quotes => in_brackets = true,
+            ']' if !in_quotes => in_brackets = false,
+            '\\' => {
+                chars.next();
+            }
+            'd' | 'D' | 'm' | 'M' | 'y' | 'Y' | 'h' | 'H' if !in_quotes && !in_brackets => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Excel serial date (1900 date system) → ISO date, with time if not midnight
+fn serial_to_iso(serial: f64) -> Option<String> {
+    if !(0.0..2_958_466.0).contains(&serial) {
+        return None;
+    }
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)?;
+    let seconds = (serial * 86_400.0).round() as i64;
+    let datetime = epoch.and_hms_opt(0, 0, 0)? + Duration::seconds(seconds);
+    Some(if seconds % 86_400 == 0 {
+        datetime.format("%Y-%m-%d").to_string()
+    } else {
+        datetime.format("%Y-%m-%d %H:%M:%S").to_string()
+    })
+}
+
+/// Zero-based column index from a cell reference ("C12" → 2)
+fn column_index(reference: &str) -> Option<usize> {
+    let letters: String = reference.chars().take_while(char::is_ascii_alphabetic).collect();
+    if letters.is_empty() {
+        return None;
+    }
+    let column = letters
+        .chars()
+        .fold(0usize, |acc, c| acc * 26 + (c.to_ascii_uppercase() as usize - 'A' as usize + 1));
+    Some(column - 1)
+}
+
+/// Cell being read inside `sheetData`
+#[derive(Default)]
+struct CellState {
+    column: usize,
+    kind: Option<String>,
+    style: Option<usize>,
+    value: String,
+}
+
+/// `xl/worksheets/sheetN.xml` → dense rows (gaps become empty cells)
+fn parse_worksheet(
+    xml: &str,
+    shared_strings: &[String],
+    date_styles: &HashSet<usize>,
+) -> Result<Vec<Vec<String>>> {
+    let mut cells: BTreeMap<usize, BTreeMap<usize, String>> = BTreeMap::new();
+    let mut row_index = 0;
+    let mut next_column = 0;
+    let mut cell: Option<CellState> = None;
+    let mut in_value = false;
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if local_name(&e) == "row" => {
+                row_index = attr(&e, "r")
+                    .and_then(|r| r.parse::<usize>().ok())
+                    .map_or(row_index + 1, |r| r);
+                next_column = 0;
+            }
+            Event::Start(e) if local_name(&e) == "c" => {
+                let column = attr(&e, "r")
+                    .and_then(|r| column_index(&r))
+                    .unwrap_or(next_column);
+                next_column = column + 1;
+                cell = Some(CellState {
+                    column,
+                    kind: attr(&e, "t"),
+                    style: attr(&e, "s").and_then(|s| s.parse().ok()),
+                    value: String::new(),
+                });
+            }
+            Event::Empty(e) if local_name(&e) == "c" => {
+                next_column = attr(&e, "r")
+                    .and_then(|r| column_index(&r))
+                    .unwrap_or(next_column)
+                    + 1;
+            }
+            Event::Start(e) if matches!(local_name(&e).as_str(), "v" | "t") => in_value = cell.is_some(),
+            Event::Text(t) if in_value => {
+                if let Some(cell) = cell.as_mut() {
+                    cell.value.push_str(&t.unescape()?);
+                }
+            }
+            Event::End(e) => match end_name(&e).as_str() {
+                "v" | "t" => in_value = false,
+                "c" => {
+                    if let Some(cell) = cell.take() {
+                        let text = cell_text(&cell, shared_strings, date_styles);
+                        if !text.is_empty() {
+                            cells.entry(row_index).or_default().insert(cell.column, text);
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    // Keep blank rows between populated ones so they still separate tables
+    let (Some(&first), Some(&last)) = (cells.keys().next(), cells.keys().next_back()) else {
+        return Ok(Vec::new());
+    };
+    Ok((first..=last)
+        .map(|index| {
+            let Some(row) = cells.get(&index) else {
+                return Vec::new();
+            };
+            let width = row.keys().next_back().map_or(0, |last| last + 1);
+            (0..width).map(|column| row.get(&column).cloned().unwrap_or_default()).collect()
+        })
+        .collect())
+}
+
+fn cell_text(cell: &CellState, shared_strings: &[String], date_styles: &HashSet<usize>) -> String {
+    let value = cell.value.trim();
+    match cell.kind.as_deref() {
+        Some("s") => value
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| shared_strings.get(index))
+            .cloned()
+            .unwrap_or_default(),
+        Some("b") => if value == "1" { "TRUE" } else { "FALSE" }.to_string(),
+        Some("inlineStr" | "str" | "e") => cell.value.clone(),
+        _ => {
+            let is_date = cell.style.is_some_and(|style| date_styles.contains(&style));
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|_| is_date)
+                .and_then(serial_to_iso)
+                .unwrap_or_else(|| value.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    const MAIN_NS: &str = r#"xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships""#;
+
+    fn xlsx() -> Vec<u8> {
+        let workbook = format!(
+            r#"<workbook {MAIN_NS}><sheets>
+  <sheet name="Q3 Sales" sheetId="1" r:id="rId1"/>
+  <sheet name="Lookup" sheetId="2" state="hidden" r:id="rId2"/>
+</sheets></workbook>"#
+        );
+        let rels = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId1" Type="worksheet" Target="worksheets/sheet1.xml"/>
+  <Relationship Id="rId2" Type="worksheet" Target="/xl/worksheets/sheet2.xml"/>
+</Relationships>"#;
+        let shared = format!(
+            r#"<sst {MAIN_NS}><si><t>Region</t></si><si><t>Closed</t></si><si><t>Revenue</t></si>
+<si><r><t>EMEA</t></r><r><rPr><b/></rPr><t xml:space="preserve"> | North</t></r><rPh><t>x</t></rPh></si></sst>"#
+        );
+        let styles = format!(
+            r#"<styleSheet {MAIN_NS}><numFmts><numFmt numFmtId="164" formatCode="[$-409]d\-mmm\-yyyy"/></numFmts>
+<cellXfs><xf numFmtId="0"/><xf numFmtId="164"/><xf numFmtId="4"/></cellXfs></styleSheet>"#
+        );
+        let sheet1 = format!(
+            r#"<worksheet {MAIN_NS}><sheetData>
+  <row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c><c r="C1" t="s"><v>2</v></c></row>
+  <row r="2"><c r="A2" t="s"><v>3</v></c><c r="B2" s="1"><v>45200</v></c><c r="C2" s="2"><v>1250.5</v></c></row>
+  <row r="3"><c r="A3" t="inlineStr"><is><t>APAC</t></is></c><c r="C3"><v>980</v></c><c r="D3" t="b"><v>1</v></c></row>
+  <row r="5"><c r="B5" t="str"><f>SUM(C2:C3)</f><v>Total 2230.5</v></c></row>
+</sheetData></worksheet>"#
+        );
+
+        let mut buffer = Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut buffer);
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        for (name, content) in [
+            (WORKBOOK_PART, workbook.as_str()),
+            (WORKBOOK_RELS_PART, rels),
+            (SHARED_STRINGS_PART, shared.as_str()),
+            (STYLES_PART, styles.as_str()),
+            ("xl/worksheets/sheet1.xml", sheet1.as_str()),
+            ("xl/worksheets/sheet2.xml", "<worksheet/>"),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        buffer.into_inner()
+    }
+
+    #[test]
+    fn workbook_sheets_become_tables_with_structure() {
+        use crate::config::{ParsingConfig, PipelineConfig};
+        use crate::processor::DocumentProcessor;
+        use crate::storage::NoOpStorage;
+
+        let path = std::env::temp_dir().join(format!("blazegraph-sheet-{}.xlsx", std::process::id()));
+        std::fs::write(&path, xlsx()).unwrap();
+        let config = ParsingConfig {
+            pipeline: PipelineConfig::from_rule_names(&["SectionDetection", "SpatialClustering"]),
+            ..ParsingConfig::default()
+        };
+        let mut processor = DocumentProcessor::new_with_dependencies(
+            Box::new(SpreadsheetPreprocessor::new()),
+            Box::new(NoOpStorage),
+        )
+        .unwrap();
+        let graph = processor
+            .process_document_with_config(path.to_str().unwrap(), &config)
+            .unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let section = graph.nodes.values().find(|n| n.node_type == "Section").unwrap();
+        assert_eq!(section.content.text, "Q3 Sales");
+        assert!(!graph.nodes.values().any(|n| n.content.text.contains("Lookup")));
+
+        let mut tables: Vec<&DocumentNode> = graph.nodes.values().filter(|n| n.node_type == "Table").collect();
+        tables.sort_by_key(|n| n.text_order);
+        assert_eq!(tables.len(), 2);
+        assert!(tables.iter().all(|n| n.parent == Some(section.id)));
+
+        let data = tables[0].content.table_data.as_ref().unwrap();
+        assert_eq!(data.header_rows, 1);
+        let rows: Vec<Vec<&str>> = data
+            .rows
+            .iter()
+            .map(|row| row.iter().map(String::as_str).collect())
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["Region", "Closed", "Revenue"],
+                vec!["EMEA | North", "2023-10-01", "1250.5"],
+                vec!["APAC", "", "980", "TRUE"],
+            ]
+        );
+        assert_eq!(
+            tables[1].content.table_data.as_ref().unwrap().rows,
+            vec![vec!["".to_string(), "Total 2230.5".to_string()]]
+        );
+    }
+
+    #[test]
+    fn csv_fields_keep_quoted_delimiters_and_newlines() {
+        let rows = parse_delimited("\u{feff}name;note\r\n\"Smith; J\";\"said \"\"hi\"\"\nthen left\"\r\nLee;\n");
+        assert_eq!(
+            rows,
+            vec![
+                vec!["name", "note"],
+                vec!["Smith; J", "said \"hi\"\nthen left"],
+                vec!["Lee", ""],
+            ]
+        );
+        assert_eq!(sniff_delimiter("a\tb\tc"), '\t');
+        assert_eq!(sniff_delimiter("\"x,y\";z"), ';');
+    }
+}
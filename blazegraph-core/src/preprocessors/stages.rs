@@ -0,0 +1,167 @@
+//! Stage-snapshot Preprocessors
+//!
+//! Rules-only workflows (CI checks of a config change, an analyst tuning
+//! rules on a laptop) start from an earlier extraction instead of a PDF:
+//!
+//! - `XhtmlPreprocessor` reads Blazegraph XHTML, e.g. the `stage1a_xhtml.html`
+//!   written by `--dump-stages`, or any backend's output saved as `.xhtml`
+//! - `TextElementsPreprocessor` reads a TextElements JSON array, e.g. the
+//!   `stage1b_text_elements.json` written by `--dump-stages`
+//!
+//! Neither needs a PDF backend. TextElements snapshots carry each span's
+//! resolved font class but no metadata or outline, so graphs built from
+//! them have default metadata and no bookmarks.
+
+use crate::preprocessors::pdf::xhtml_parser;
+use crate::preprocessors::traits::Preprocessor;
+use crate::types::*;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// How much of a `.html` file is searched for Blazegraph XHTML markers
+const SNIFF_BYTES: usize = 64 * 1024;
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default()
+}
+
+/// Blazegraph XHTML preprocessor (pure Rust, no extraction)
+#[derive(Debug, Default)]
+pub struct XhtmlPreprocessor;
+
+impl XhtmlPreprocessor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Whether `bytes` (the start of a file is enough) look like Blazegraph
+    /// XHTML rather than a web page: positioned spans carry `data-bbox`
+    pub fn sniff(bytes: &[u8]) -> bool {
+        let head = &bytes[..bytes.len().min(SNIFF_BYTES)];
+        head.windows(b"data-bbox=".len()).any(|w| w == b"data-bbox=")
+    }
+}
+
+impl Preprocessor for XhtmlPreprocessor {
+    /// Step 1: the input already is the markup
+    fn parse_pdf_to_markup_language(&self, xhtml_bytes: &[u8]) -> Result<String> {
+        String::from_utf8(xhtml_bytes.to_vec()).context("XHTML input is not valid UTF-8")
+    }
+
+    /// Step 2: Parse XHTML to PreprocessorOutput
+    fn parse_markup_to_preprocessor_output(&self, markup: &str) -> Result<PreprocessorOutput> {
+        xhtml_parser::parse_xhtml(markup)
+    }
+
+    fn name(&self) -> &str {
+        "XhtmlPreprocessor"
+    }
+
+    /// `.xhtml` files; `.html` snapshots are told apart from web pages with
+    /// `sniff`
+    fn supports_file_type(&self, path: &Path) -> bool {
+        extension(path) == "xhtml"
+    }
+}
+
+/// TextElements JSON preprocessor (pure Rust, no extraction)
+#[derive(Debug, Default)]
+pub struct TextElementsPreprocessor;
+
+impl TextElementsPreprocessor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Whether `bytes` hold a JSON array (TextElements) rather than an
+    /// object (a graph or another output)
+    pub fn sniff(bytes: &[u8]) -> bool {
+        bytes.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'[')
+    }
+}
+
+impl Preprocessor for TextElementsPreprocessor {
+    /// Step 1: the input already is the markup
+    fn parse_pdf_to_markup_language(&self, json_bytes: &[u8]) -> Result<String> {
+        String::from_utf8(json_bytes.to_vec()).context("TextElements input is not valid UTF-8")
+    }
+
+    /// Step 2: Read the elements; their font classes make up the style data
+    fn parse_markup_to_preprocessor_output(&self, markup: &str) -> Result<PreprocessorOutput> {
+        let text_elements: Vec<PdfTextElement> =
+            serde_json::from_str(markup).context("Input is not a TextElements JSON array")?;
+
+        // A class seen with other values on a later page was page-scoped there
+        let mut style_data = StyleData::default();
+        for element in &text_elements {
+            let style = &element.style_info;
+            match style_data.font_classes.get(&style.class_name) {
+                None => {
+                    style_data.font_classes.insert(style.class_name.clone(), style.clone());
+                }
+                Some(global) if global != style => {
+                    style_data
+                        .page_font_classes
+                        .entry(element.page_number)
+                        .or_insert_with(HashMap::new)
+                        .insert(style.class_name.clone(), style.clone());
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(PreprocessorOutput {
+            text_elements,
+            metadata: DocumentMetadata::default(),
+            style_data,
+            bookmark_data: None,
+            warnings: Vec::new(),
+            page_extraction: Vec::new(),
+            links: Vec::new(),
+        })
+    }
+
+    fn name(&self) -> &str {
+        "TextElementsPreprocessor"
+    }
+
+    fn supports_file_type(&self, path: &Path) -> bool {
+        extension(path) == "json"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{FontClassBuilder, TextElementBuilder};
+
+    #[test]
+    fn text_elements_round_trip_with_their_styles() {
+        let body = FontClassBuilder::new("f1").size(10.0).build();
+        let heading = FontClassBuilder::new("f2").size(16.0).build();
+        let elements = vec![
+            TextElementBuilder::new("Introduction").style(heading.clone()).build(),
+            TextElementBuilder::new("Body text.").style(body.clone()).build(),
+            TextElementBuilder::new("Larger on page 2.")
+                .style(FontClassBuilder::new("f1").size(12.0).build())
+                .page(2)
+                .build(),
+        ];
+        let json = serde_json::to_string(&elements).unwrap();
+        assert!(TextElementsPreprocessor::sniff(json.as_bytes()));
+        assert!(!TextElementsPreprocessor::sniff(b"{\"schema_version\": \"0.2.0\"}"));
+
+        let output = TextElementsPreprocessor::new().process(json.as_bytes()).unwrap();
+        assert_eq!(output.text_elements.len(), 3);
+        assert_eq!(output.style_data.resolve("f2", 1), Some(&heading));
+        assert_eq!(output.style_data.resolve("f1", 1), Some(&body));
+        assert_eq!(output.style_data.resolve("f1", 2).map(|f| f.font_size), Some(12.0));
+
+        assert!(XhtmlPreprocessor::sniff(b"<div class=\"page\"><span data-bbox=\"0 0 1 1\">"));
+        assert!(!XhtmlPreprocessor::sniff(b"<html><body><p>A web page</p></body></html>"));
+    }
+}
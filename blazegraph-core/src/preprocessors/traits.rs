@@ -4,10 +4,22 @@
 // and semantic processing (TextElements -> Graph). The preprocessor abstraction allows
 // for different document parsing backends while maintaining a consistent interface.
 
+use crate::storage::calculate_xhtml_hash;
 use crate::types::*;
 use anyhow::Result;
 use std::path::Path;
 
+/// What `Preprocessor::extract` produced
+#[derive(Debug)]
+pub struct Extraction {
+    pub output: PreprocessorOutput,
+    /// `calculate_xhtml_hash` of the markup
+    pub markup_hash: String,
+    /// The extraction stack that produced it (in a backend chain, the
+    /// backend that succeeded)
+    pub info: ExtractionInfo,
+}
+
 /// Preprocessor trait - converts documents to PreprocessorOutput
 /// 
 /// This is the key abstraction boundary in blazegraph. Preprocessors handle:
@@ -27,6 +39,15 @@ pub trait Preprocessor: Send + Sync {
     /// For other preprocessors: DOC bytes -> HTML, etc.
     /// This step handles the raw document format conversion.
     fn parse_pdf_to_markup_language(&self, pdf_bytes: &[u8]) -> Result<String>;
+
+    /// Step 1, also reporting the extraction stack that produced the markup
+    ///
+    /// Preprocessors that choose a backend per document override this (and
+    /// `extract`), so the choice is returned with the result rather than
+    /// kept on the preprocessor.
+    fn extract_markup(&self, document_bytes: &[u8]) -> Result<(String, ExtractionInfo)> {
+        Ok((self.parse_pdf_to_markup_language(document_bytes)?, self.extraction_info()))
+    }
     
     /// Step 2: Convert markup language to structured output
     /// 
@@ -35,13 +56,26 @@ pub trait Preprocessor: Send + Sync {
     /// This step is format-agnostic after step 1.
     fn parse_markup_to_preprocessor_output(&self, markup: &str) -> Result<PreprocessorOutput>;
     
+    /// Both steps at once, also hashing the markup (the Level 2 cache key)
+    ///
+    /// The default holds the markup in memory between the steps;
+    /// preprocessors that can parse markup as it is produced override this
+    /// so it never is.
+    fn extract(&self, document_bytes: &[u8]) -> Result<Extraction> {
+        let (markup, info) = self.extract_markup(document_bytes)?;
+        Ok(Extraction {
+            output: self.parse_markup_to_preprocessor_output(&markup)?,
+            markup_hash: calculate_xhtml_hash(&markup),
+            info,
+        })
+    }
+
     /// Convenience method: Full document processing (combines both steps)
     /// 
     /// This is the main entry point for document processing.
-    /// Default implementation calls the two steps in sequence.
+    /// Default implementation runs `extract` and drops the hash.
     fn process(&self, document_bytes: &[u8]) -> Result<PreprocessorOutput> {
-        let markup = self.parse_pdf_to_markup_language(document_bytes)?;
-        self.parse_markup_to_preprocessor_output(&markup)
+        Ok(self.extract(document_bytes)?.output)
     }
     
     /// Convenience method: Process from file path
@@ -57,4 +91,38 @@ pub trait Preprocessor: Send + Sync {
     
     /// Check if preprocessor supports the given file type
     fn supports_file_type(&self, path: &Path) -> bool;
+
+    /// Whether the source format has a fixed page layout
+    ///
+    /// Reflowable formats return `FlowType::Free`, and their graphs carry no
+    /// physical locations.
+    fn flow_type(&self) -> FlowType {
+        FlowType::Fixed
+    }
+
+    /// Whether pages can be scanned images with no text layer
+    ///
+    /// When true, the processor checks each page's text density and records
+    /// per-page extraction methods (see `hybrid`).
+    fn may_contain_scans(&self) -> bool {
+        false
+    }
+
+    /// Whether the preprocessor can currently take work
+    ///
+    /// Pure-Rust preprocessors always can; backends wrapping an external
+    /// engine (a JVM, a server) report its health.
+    fn is_healthy(&self) -> bool {
+        true
+    }
+
+    /// The extraction stack behind this preprocessor
+    ///
+    /// Pure-Rust preprocessors only need their name; backends wrapping an
+    /// external engine override this to report its versions too. Graph
+    /// metadata records the stack an extraction returned (`extract`), which
+    /// in a backend chain can differ from this.
+    fn extraction_info(&self) -> ExtractionInfo {
+        ExtractionInfo::new(self.name())
+    }
 }
@@ -0,0 +1,269 @@
+//! Plain-text Preprocessor
+//!
+//! Legacy text exports carry no markup, so structure is recovered from layout
+//! conventions and written out as Blazegraph XHTML with `XhtmlWriter`:
+//!
+//! - Blank lines separate paragraphs; wrapped lines inside one are joined
+//! - Form feeds (`\f`) start a new page
+//! - A short single-line paragraph in ALL CAPS, or starting with section
+//!   numbering ("2.", "3.1", "4.2.1"), becomes `data-role="heading"`. Numbered
+//!   headings take their level from the depth of the numbering.
+//! - Paragraphs whose first line starts with a bullet ("-", "*", "•") or a
+//!   number ("3.", "3)") become one `data-role="list-item"` per marker, with
+//!   `data-list-level` from how far the marker is indented
+//!
+//! Plain text has no fixed layout, so graphs are `FlowType::Free`.
+
+use crate::preprocessors::pdf::xhtml_parser;
+use crate::preprocessors::traits::Preprocessor;
+use crate::preprocessors::xhtml_writer::{heading_size, Block, RunStyle, XhtmlWriter};
+use crate::types::*;
+use anyhow::Result;
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+const BODY_SIZE: f32 = 12.0;
+/// Longest line still considered a heading
+const MAX_HEADING_CHARS: usize = 80;
+
+/// "2 Scope", "3.1. Methods", "4.2.1 Results" → (numbering, title)
+static NUMBERED_HEADING_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d{1,3}(?:\.\d{1,3})*)\.?\s+(\p{Lu}.*)$").unwrap());
+
+/// "- item", "* item", "• item", "3. item", "3) item" → (marker, text)
+static LIST_MARKER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([-*•]|\d{1,3}[.)])\s+(.*)$").unwrap());
+
+/// Plain-text preprocessor (pure Rust)
+#[derive(Debug, Default)]
+pub struct TxtPreprocessor;
+
+impl TxtPreprocessor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Convert plain text to Blazegraph XHTML
+    pub fn text_to_xhtml(&self, text: &str) -> String {
+        let text = text.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+        let mut writer = XhtmlWriter::new();
+
+        for (index, page) in text.split('\u{c}').enumerate() {
+            if index > 0 {
+                writer.page_break();
+            }
+            for paragraph in paragraphs(page) {
+                for block in blocks_for(&paragraph) {
+                    writer.push_block(block);
+                }
+            }
+        }
+        writer.finish()
+    }
+}
+
+impl Preprocessor for TxtPreprocessor {
+    /// Step 1: Convert plain text to Blazegraph XHTML
+    fn parse_pdf_to_markup_language(&self, text_bytes: &[u8]) -> Result<String> {
+        let text = String::from_utf8_lossy(text_bytes);
+        Ok(self.text_to_xhtml(&text))
+    }
+
+    /// Step 2: Parse XHTML to PreprocessorOutput
+    fn parse_markup_to_preprocessor_output(&self, markup: &str) -> Result<PreprocessorOutput> {
+        xhtml_parser::parse_xhtml(markup)
+    }
+
+    fn name(&self) -> &str {
+        "TxtPreprocessor"
+    }
+
+    fn supports_file_type(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "txt" | "text"))
+    }
+
+    fn flow_type(&self) -> FlowType {
+        FlowType::Free
+    }
+}
+
+/// Group non-blank lines into blank-line-separated paragraphs
+fn paragraphs(page: &str) -> Vec<Vec<&str>> {
+    let mut paragraphs = Vec::new();
+    let mut current = Vec::new();
+    for line in page.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(line.trim_end());
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs
+}
+
+fn blocks_for(lines: &[&str]) -> Vec<Block> {
+    if let [line] = lines {
+        if let Some(level) = heading_level(line.trim()) {
+            let style = RunStyle {
+                size: heading_size(level),
+                bold: true,
+                ..RunStyle::default()
+            };
+            let mut block = Block::new()
+                .attr("role", "heading")
+                .attr("level", level.to_string());
+            block.push_run(line.trim(), &style);
+            return vec![block];
+        }
+    }
+
+    let body = RunStyle {
+        size: BODY_SIZE,
+        ..RunStyle::default()
+    };
+    if LIST_MARKER_REGEX.is_match(lines[0].trim_start()) {
+        return list_items(lines, &body);
+    }
+
+    let mut block = Block::new();
+    block.push_run(&join_wrapped(lines), &body);
+    vec![block]
+}
+
+/// One block per list marker; unmarked lines continue the previous item
+fn list_items(lines: &[&str], style: &RunStyle) -> Vec<Block> {
+    let mut items: Vec<(usize, String)> = Vec::new();
+    // Indentation of each open nesting level
+    let mut indents: Vec<usize> = Vec::new();
+    for line in lines {
+        let trimmed = line.trim_start();
+        match LIST_MARKER_REGEX.captures(trimmed) {
+            Some(caps) => {
+                let indent = line.len() - trimmed.len();
+                while indents.last().is_some_and(|&open| open > indent) {
+                    indents.pop();
+                }
+                if indents.last().is_none_or(|&open| indent > open) {
+                    indents.push(indent);
+                }
+                let marker = match &caps[1] {
+                    "-" | "*" | "•" => "•".to_string(),
+                    numbered => format!("{}.", numbered.trim_end_matches(['.', ')'])),
+                };
+                items.push((indents.len(), format!("{} {}", marker, &caps[2])));
+            }
+            None => {
+                if let Some((_, text)) = items.last_mut() {
+                    text.push(' ');
+                    text.push_str(trimmed);
+                }
+            }
+        }
+    }
+
+    items
+        .into_iter()
+        .map(|(level, text)| {
+            let mut block = Block::new()
+                .attr("role", "list-item")
+                .attr("list-level", level.to_string());
+            block.push_run(&text, style);
+            block
+        })
+        .collect()
+}
+
+/// Join hard-wrapped lines, undoing end-of-line hyphenation
+fn join_wrapped(lines: &[&str]) -> String {
+    let mut text = String::new();
+    for line in lines {
+        let line = line.trim();
+        if text.ends_with('-') && line.starts_with(char::is_lowercase) {
+            text.pop();
+        } else if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(line);
+    }
+    text
+}
+
+/// Heading level for a single-line paragraph, if it looks like one
+fn heading_level(line: &str) -> Option<u32> {
+    if line.chars().count() > MAX_HEADING_CHARS || line.ends_with(['.', ',', ';', ':']) {
+        return None;
+    }
+    if let Some(caps) = NUMBERED_HEADING_REGEX.captures(line) {
+        // A title, not a numbered sentence
+        if caps[2].split_whitespace().count() <= 12 {
+            return Some(caps[1].split('.').count() as u32);
+        }
+    }
+    let has_letters = line.chars().any(char::is_alphabetic);
+    let all_caps = !line.chars().any(char::is_lowercase);
+    (has_letters && all_caps && !LIST_MARKER_REGEX.is_match(line)).then_some(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// CRLF line endings, as legacy exports usually have
+    const SAMPLE: &[&str] = &[
+        "ANNUAL MAINTENANCE REPORT",
+        "",
+        "This report covers the pumping station and was exported",
+        "from the legacy system without any formatting. Inspec-",
+        "tions ran monthly.",
+        "",
+        "2.1 Findings",
+        "",
+        "- Valve seals replaced",
+        "- Motor bearings worn,",
+        "  scheduled for March",
+        "    - spare ordered",
+        "\u{c}APPENDIX",
+    ];
+
+    #[test]
+    fn detects_headings_paragraphs_and_lists() {
+        let output = TxtPreprocessor::new()
+            .process(SAMPLE.join("\r\n").as_bytes())
+            .unwrap();
+        let find = |text: &str| {
+            output
+                .text_elements
+                .iter()
+                .find(|e| e.text == text)
+                .unwrap_or_else(|| panic!("missing element {:?}", text))
+        };
+        let attr = |text: &str, name: &str| find(text).paragraph_attributes.get(name).cloned();
+
+        assert_eq!(find("ANNUAL MAINTENANCE REPORT").paragraph_role(), Some("heading"));
+        assert_eq!(find("ANNUAL MAINTENANCE REPORT").paragraph_level(), Some(1));
+        assert_eq!(find("2.1 Findings").paragraph_level(), Some(2));
+
+        let body = find(
+            "This report covers the pumping station and was exported from the legacy \
+             system without any formatting. Inspections ran monthly.",
+        );
+        assert_eq!(body.paragraph_role(), None);
+
+        assert_eq!(find("• Valve seals replaced").paragraph_role(), Some("list-item"));
+        assert_eq!(
+            attr("• Motor bearings worn, scheduled for March", "list-level").as_deref(),
+            Some("1")
+        );
+        assert_eq!(attr("• spare ordered", "list-level").as_deref(), Some("2"));
+
+        assert_eq!(find("APPENDIX").page_number, 2);
+    }
+}
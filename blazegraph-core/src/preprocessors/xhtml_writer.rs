@@ -0,0 +1,324 @@
+//! Blazegraph XHTML writer for reflowable formats
+//!
+//! PDF backends emit Blazegraph XHTML with real page geometry. Formats
+//! without fixed layout (DOCX, Markdown, ...) build the same intermediate
+//! format here so they share `xhtml_parser` and everything after it.
+//!
+//! Geometry is synthetic: blocks are stacked top-to-bottom on a US Letter
+//! page with 1" margins, runs are laid out left-to-right with widths
+//! estimated from character count, and long runs wrap onto extra rows.
+//! Pages only change on explicit page breaks.
+
+use crate::types::ScriptPosition;
+
+const PAGE_LEFT: f32 = 72.0;
+const PAGE_TOP: f32 = 72.0;
+const TEXT_WIDTH: f32 = 468.0;
+const LINE_HEIGHT_FACTOR: f32 = 1.2;
+/// Average glyph width as a fraction of the font size
+const CHAR_WIDTH_FACTOR: f32 = 0.5;
+const PARAGRAPH_SPACING: f32 = 6.0;
+/// Font sizes for heading levels 1-6 in formats that only declare a level
+const HEADING_SIZES: [f32; 6] = [24.0, 20.0, 16.0, 14.0, 13.0, 12.0];
+
+/// Font properties of a run; identical styles share one CSS class
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunStyle {
+    pub family: String,
+    pub size: f32,
+    pub bold: bool,
+    pub italic: bool,
+    pub color: String,
+    /// Written as `data-script` on the span; not part of the CSS class
+    pub script: ScriptPosition,
+    /// Hyperlink target, written as an `<a href>` around the span
+    pub link: Option<String>,
+}
+
+impl Default for RunStyle {
+    fn default() -> Self {
+        Self {
+            family: "unknown".to_string(),
+            size: 12.0,
+            bold: false,
+            italic: false,
+            color: "#000000".to_string(),
+            script: ScriptPosition::Baseline,
+            link: None,
+        }
+    }
+}
+
+/// A paragraph-level block: lines of styled runs plus `data-*` attributes
+#[derive(Debug, Clone)]
+pub struct Block {
+    lines: Vec<Vec<(String, RunStyle)>>,
+    attributes: Vec<(String, String)>,
+}
+
+impl Default for Block {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Block {
+    pub fn new() -> Self {
+        Self {
+            lines: vec![Vec::new()],
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Add a `data-{name}` attribute to the paragraph
+    pub fn attr(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.set_attr(name, value);
+        self
+    }
+
+    pub fn set_attr(&mut self, name: &str, value: impl Into<String>) {
+        self.attributes.push((name.to_string(), value.into()));
+    }
+
+    /// Append text to the current line, merging with the previous run if the style matches
+    pub fn push_run(&mut self, text: &str, style: &RunStyle) {
+        if text.is_empty() {
+            return;
+        }
+        let line = self.lines.last_mut().expect("block always has a line");
+        match line.last_mut() {
+            Some((last_text, last_style)) if last_style == style => last_text.push_str(text),
+            _ => line.push((text.to_string(), style.clone())),
+        }
+    }
+
+    pub fn line_break(&mut self) {
+        self.lines.push(Vec::new());
+    }
+
+    pub fn has_text(&self) -> bool {
+        self.lines
+            .iter()
+            .flatten()
+            .any(|(text, _)| !text.trim().is_empty())
+    }
+}
+
+/// Accumulates pages of blocks and renders Blazegraph XHTML
+#[derive(Debug)]
+pub struct XhtmlWriter {
+    metadata: Vec<(String, String)>,
+    pages: Vec<Vec<Block>>,
+    classes: Vec<RunStyle>,
+}
+
+impl Default for XhtmlWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XhtmlWriter {
+    pub fn new() -> Self {
+        Self {
+            metadata: Vec::new(),
+            pages: vec![Vec::new()],
+            classes: Vec::new(),
+        }
+    }
+
+    /// Add a `<meta>` tag (same names as the PDF backends, e.g. `dc:title`)
+    pub fn meta(&mut self, name: &str, content: impl Into<String>) {
+        let content = content.into();
+        if !content.trim().is_empty() {
+            self.metadata.push((name.to_string(), content));
+        }
+    }
+
+    /// Start a new page; a no-op if the current page is still empty
+    pub fn page_break(&mut self) {
+        if self.pages.last().is_some_and(|page| !page.is_empty()) {
+            self.pages.push(Vec::new());
+        }
+    }
+
+    /// Add a block to the current page (blocks without text are dropped)
+    pub fn push_block(&mut self, block: Block) {
+        if block.has_text() {
+            self.pages
+                .last_mut()
+                .expect("writer always has a page")
+                .push(block);
+        }
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.iter().filter(|page| !page.is_empty()).count()
+    }
+
+    /// Render the document
+    pub fn finish(mut self) -> String {
+        if self.pages.len() > 1 && self.pages.last().is_some_and(|page| page.is_empty()) {
+            self.pages.pop();
+        }
+        let page_count = self.pages.len();
+        let pages = std::mem::take(&mut self.pages);
+
+        let mut body = String::new();
+        for page in &pages {
+            body.push_str("<div class=\"page\">\n");
+            let mut y = PAGE_TOP;
+            for block in page {
+                y = self.render_block(block, y, &mut body) + PARAGRAPH_SPACING;
+            }
+            body.push_str("</div>\n");
+        }
+
+        let mut out = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<html xmlns=\"http://www.w3.org/1999/xhtml\">\n<head>\n",
+        );
+        for (name, content) in &self.metadata {
+            out.push_str(&format!(
+                "<meta name=\"{}\" content=\"{}\" />\n",
+                escape(name),
+                escape(content)
+            ));
+        }
+        out.push_str(&format!(
+            "<meta name=\"xmpTPg:NPages\" content=\"{}\" />\n</head>\n<body>",
+            page_count
+        ));
+        out.push_str(&body);
+        out.push_str("<style type=\"text/css\">\n");
+        for (index, style) in self.classes.iter().enumerate() {
+            out.push_str(&format!(
+                "  .f{} {{ font-family: {}; font-size: {:.1}px; font-style: {}; font-weight: {}; color: {}; }}\n",
+                index + 1,
+                style.family,
+                style.size,
+                if style.italic { "italic" } else { "normal" },
+                if style.bold { "bold" } else { "normal" },
+                style.color
+            ));
+        }
+        out.push_str("</style></body></html>\n");
+        out
+    }
+
+    /// Lay out one block starting at `y`; returns the y below it
+    fn render_block(&mut self, block: &Block, mut y: f32, out: &mut String) -> f32 {
+        out.push_str("<p");
+        for (name, value) in &block.attributes {
+            out.push_str(&format!(" data-{}=\"{}\"", escape(name), escape(value)));
+        }
+        out.push_str(">\n");
+
+        let mut line_number = 0;
+        for line in &block.lines {
+            let runs: Vec<&(String, RunStyle)> =
+                line.iter().filter(|(text, _)| !text.trim().is_empty()).collect();
+            if runs.is_empty() {
+                continue;
+            }
+            let line_height = runs
+                .iter()
+                .map(|(_, style)| style.size * LINE_HEIGHT_FACTOR)
+                .fold(0.0, f32::max);
+
+            let mut offset = 0.0;
+            for (segment, (text, style)) in runs.iter().enumerate() {
+                let estimated = text.chars().count() as f32 * style.size * CHAR_WIDTH_FACTOR;
+                if offset > 0.0 && offset + estimated.min(TEXT_WIDTH) > TEXT_WIDTH {
+                    // Visual wrap before this run
+                    y += line_height;
+                    offset = 0.0;
+                }
+                let rows = (estimated / TEXT_WIDTH).ceil().max(1.0);
+                let width = estimated.min(TEXT_WIDTH - offset);
+                let height = style.size * LINE_HEIGHT_FACTOR * rows;
+                let class = self.class_for(style);
+
+                let script = style
+                    .script
+                    .attribute()
+                    .map(|value| format!(" data-script=\"{}\"", value))
+                    .unwrap_or_default();
+                if let Some(href) = &style.link {
+                    out.push_str(&format!("<a href=\"{}\">", escape(href)));
+                }
+                out.push_str(&format!(
+                    "<span class=\"f{}\" data-bbox=\"{:.1},{:.1},{:.1},{:.1}\" data-line=\"{}\" data-segment=\"{}\"{}>{}</span>\n",
+                    class,
+                    PAGE_LEFT + offset,
+                    y,
+                    width,
+                    height,
+                    line_number,
+                    segment,
+                    script,
+                    escape(text.trim())
+                ));
+                if style.link.is_some() {
+                    out.push_str("</a>\n");
+                }
+
+                if rows > 1.0 {
+                    y += line_height * (rows - 1.0);
+                    offset = 0.0;
+                } else {
+                    offset += width;
+                }
+            }
+            y += line_height;
+            line_number += 1;
+        }
+
+        out.push_str("</p>\n");
+        y
+    }
+
+    fn class_for(&mut self, style: &RunStyle) -> usize {
+        let style = RunStyle {
+            script: ScriptPosition::Baseline,
+            link: None,
+            ..style.clone()
+        };
+        match self.classes.iter().position(|s| *s == style) {
+            Some(index) => index + 1,
+            None => {
+                self.classes.push(style);
+                self.classes.len()
+            }
+        }
+    }
+}
+
+/// Escape text for element content and attribute values
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Display size for a heading level (levels past 6 use the level-6 size)
+pub fn heading_size(level: u32) -> f32 {
+    HEADING_SIZES[(level as usize).clamp(1, HEADING_SIZES.len()) - 1]
+}
+
+/// Map heading-like style names ("Heading 2", "heading2", "h3") to a level
+pub fn heading_level_from_name(name: &str) -> Option<u32> {
+    let lower = name.to_lowercase().replace([' ', '_', '-'], "");
+    let digits = lower
+        .strip_prefix("heading")
+        .or_else(|| lower.strip_prefix('h'))?;
+    digits.parse::<u32>().ok().filter(|level| (1..=9).contains(level))
+}
+
@@ -1,13 +1,43 @@
-use crate::cache::{GraphCacheKey, GraphCacheValue};
+use crate::attachments::extract_attachments;
+use crate::links::{add_link_annotations, extract_link_annotations};
+use crate::assertions::{PipelineStage, StageAssertions, StageOutput, Verdict};
+use crate::cache::{CacheLevels, CacheStats, GraphCacheKey, GraphCacheValue, ParsedElementsCacheKey, ParsedElementsCacheValue};
 use crate::classifier::DocumentClassifier;
-use crate::config::ParsingConfig;
+use crate::config::{ConfigManager, ParsingConfig};
 use crate::graphs::builder::GraphBuilder;
-use crate::preprocessors::{Preprocessor, TikaPreprocessor};
-use crate::rules::{engine::DebugConfig, RuleEngine};
-use crate::storage::{calculate_config_hash, calculate_pdf_hash, DocumentStorage, FileStorage};
+use crate::hybrid;
+use crate::ocr::{self, PageOcr};
+use crate::color_rules::apply_color_rules;
+use crate::auto_minimal::skip_rules;
+use crate::column_order::order_columns;
+use crate::reading_direction::apply_reading_direction;
+use crate::page_labels::label_pages;
+use crate::page_order::repair_page_order;
+use crate::page_filter::filter_pages;
+use crate::page_range::select_pages;
+use crate::span_merge::merge_spans;
+use crate::giant_spans::split_giant_spans;
+use crate::text_normalization::normalize_text;
+use crate::preprocessors::{Extraction, Preprocessor, RtfPreprocessor, TextElementsPreprocessor, TxtPreprocessor, XhtmlPreprocessor};
+#[cfg(feature = "office")]
+use crate::preprocessors::{DocxPreprocessor, OdtPreprocessor, PptxPreprocessor, SpreadsheetPreprocessor};
+#[cfg(feature = "html")]
+use crate::preprocessors::HtmlPreprocessor;
+#[cfg(feature = "markdown")]
+use crate::preprocessors::MarkdownPreprocessor;
+#[cfg(feature = "jni-backend")]
+use crate::preprocessors::TikaPreprocessor;
+use crate::rules::{engine::DebugConfig, validation::ValidationRule, ParseRuleFactory, RuleEngine};
+use crate::storage::{
+    calculate_config_hash, calculate_pdf_hash, calculate_rules_hash,
+    DocumentStorage, FileStorage,
+};
+use crate::tokenizer::Tokenizer;
 use crate::types::*;
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use std::io::Read;
 use std::path::Path;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 /// Captured intermediate outputs from each pipeline stage
@@ -16,6 +46,8 @@ use std::time::{Duration, Instant};
 pub struct PipelineStages {
     pub xhtml: String,
     pub text_elements: Vec<PdfTextElement>,
+    /// Font classes the rules resolved `text_elements` against
+    pub style_data: StyleData,
     pub parsed_elements: Vec<ParsedPdfElement>,
     pub graph: DocumentGraph,
 }
@@ -77,8 +109,13 @@ pub struct DocumentProcessor {
     preprocessor: Box<dyn Preprocessor>,
     storage: Box<dyn DocumentStorage + Send + Sync>,
     classifier: DocumentClassifier,
-    rule_engine: RuleEngine,
+    rule_engine: Arc<RuleEngine>,
+    config_manager: ConfigManager,
     graph_builder: GraphBuilder,
+    cache_levels: CacheLevels,
+    cache_stats: CacheStats,
+    ocr: Option<PageOcr>,
+    assertions: StageAssertions,
 }
 
 impl DocumentProcessor {
@@ -91,11 +128,121 @@ impl DocumentProcessor {
             preprocessor,
             storage,
             classifier: DocumentClassifier::new(),
-            rule_engine: RuleEngine::new()?,
+            rule_engine: Arc::new(RuleEngine::new()?),
+            config_manager: ConfigManager::new()?,
             graph_builder: GraphBuilder::new(),
+            cache_levels: CacheLevels::default(),
+            cache_stats: CacheStats::default(),
+            ocr: ocr::default_page_ocr(),
+            assertions: StageAssertions::default(),
         })
     }
 
+    /// Choose which cache levels are read and written (all three by default)
+    pub fn set_cache_levels(&mut self, cache_levels: CacheLevels) {
+        self.cache_levels = cache_levels;
+    }
+
+    pub fn cache_levels(&self) -> CacheLevels {
+        self.cache_levels
+    }
+
+    /// Replace where cache entries are kept (e.g. a `FileStorage` with
+    /// encryption enabled)
+    pub fn set_storage(&mut self, storage: Box<dyn DocumentStorage + Send + Sync>) {
+        self.storage = storage;
+    }
+
+    /// Cache hits and misses per level since this processor was created
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache_stats
+    }
+
+    /// Whether the preprocessor can take work (for PDF backends: any backend
+    /// in the chain is healthy, e.g. the JVM can attach a thread)
+    pub fn is_healthy(&self) -> bool {
+        self.preprocessor.is_healthy()
+    }
+
+    /// The extraction stack behind this processor (backend and versions)
+    pub fn extraction_info(&self) -> ExtractionInfo {
+        self.preprocessor.extraction_info()
+    }
+
+    /// The rule engine, to hand to other processors
+    pub fn rule_engine(&self) -> Arc<RuleEngine> {
+        Arc::clone(&self.rule_engine)
+    }
+
+    /// Run rules on a shared engine (e.g. one per server instead of one per
+    /// processor); runs only read the engine, so it can serve every thread
+    pub fn share_rule_engine(&mut self, rule_engine: Arc<RuleEngine>) {
+        self.rule_engine = rule_engine;
+    }
+
+    /// Make a custom rule available to pipelines by name (see
+    /// `RuleEngine::register_rule`). An engine already shared with other
+    /// processors can't change: register on it before sharing
+    pub fn register_rule(&mut self, name: impl Into<String>, factory: impl ParseRuleFactory + 'static) -> Result<()> {
+        match Arc::get_mut(&mut self.rule_engine) {
+            Some(rule_engine) => rule_engine.register_rule(name, factory),
+            None => bail!("The rule engine is shared with other processors; register rules before sharing it"),
+        }
+    }
+
+    /// Make a custom tokenizer available to configs by name (see
+    /// `RuleEngine::register_tokenizer`); like rules, register before
+    /// sharing the engine
+    pub fn register_tokenizer(&mut self, name: impl Into<String>, tokenizer: impl Tokenizer + 'static) -> Result<()> {
+        match Arc::get_mut(&mut self.rule_engine) {
+            Some(rule_engine) => rule_engine.register_tokenizer(name, tokenizer),
+            None => bail!("The rule engine is shared with other processors; register tokenizers before sharing it"),
+        }
+    }
+
+    /// The built-in config for a document type, used by
+    /// `process_document_with_options` (which takes no config)
+    pub fn config_for(&self, document_type: &DocumentType) -> &ParsingConfig {
+        self.config_manager.get_config(document_type)
+    }
+
+    /// Replace the OCR used for scanned pages (`None` disables OCR; the
+    /// `ocr` feature's Poppler + Tesseract pipeline is the default)
+    pub fn set_ocr(&mut self, ocr: Option<PageOcr>) {
+        self.ocr = ocr;
+    }
+
+    /// Check the run at a pipeline boundary: pass, annotate the graph's
+    /// warnings, or veto processing (see `assertions`)
+    pub fn register_assertion(
+        &mut self,
+        name: impl Into<String>,
+        stage: PipelineStage,
+        check: impl Fn(&StageOutput) -> Verdict + Send + Sync + 'static,
+    ) {
+        self.assertions.register(name, stage, check);
+    }
+
+    /// A graph-cache hit skips extraction and rules, so it is only usable
+    /// when no assertion needs them
+    fn graph_cache_usable(&self, cache_levels: CacheLevels) -> bool {
+        if cache_levels.graph && self.assertions.checks_before_build() {
+            println!("🔒 Skipping graph cache lookup: assertions check earlier stages");
+            return false;
+        }
+        cache_levels.graph
+    }
+
+    /// Run build-stage assertions and attach every annotation to the graph
+    fn finish_assertions(&self, graph: &mut DocumentGraph, mut annotations: Vec<ProcessingWarning>) -> Result<()> {
+        if self.assertions.is_empty() {
+            return Ok(());
+        }
+        self.assertions.check(StageOutput::Build(graph), &mut annotations)?;
+        graph.document_info.warnings.extend(annotations);
+        Ok(())
+    }
+
     /// Convenience constructor for CLI usage with JNI backend (cross-platform)
     ///
     /// # Arguments
@@ -108,6 +255,82 @@ impl DocumentProcessor {
         Self::new_with_dependencies(preprocessor, storage)
     }
 
+    /// Convenience constructor for CLI processing of Word documents (no JVM required)
+    #[cfg(feature = "office")]
+    pub fn new_cli_docx() -> Result<Self> {
+        let preprocessor = Box::new(DocxPreprocessor::new());
+        let storage = Box::new(FileStorage::new("cache")?);
+        Self::new_with_dependencies(preprocessor, storage)
+    }
+
+    /// Convenience constructor for CLI processing of PowerPoint decks (no JVM required)
+    #[cfg(feature = "office")]
+    pub fn new_cli_pptx() -> Result<Self> {
+        let preprocessor = Box::new(PptxPreprocessor::new());
+        let storage = Box::new(FileStorage::new("cache")?);
+        Self::new_with_dependencies(preprocessor, storage)
+    }
+
+    /// Convenience constructor for CLI processing of RTF documents (no JVM required)
+    pub fn new_cli_rtf() -> Result<Self> {
+        let preprocessor = Box::new(RtfPreprocessor::new());
+        let storage = Box::new(FileStorage::new("cache")?);
+        Self::new_with_dependencies(preprocessor, storage)
+    }
+
+    /// Convenience constructor for CLI processing of OpenDocument Text (no JVM required)
+    #[cfg(feature = "office")]
+    pub fn new_cli_odt() -> Result<Self> {
+        let preprocessor = Box::new(OdtPreprocessor::new());
+        let storage = Box::new(FileStorage::new("cache")?);
+        Self::new_with_dependencies(preprocessor, storage)
+    }
+
+    /// Convenience constructor for CLI processing of CSV/TSV and XLSX spreadsheets (no JVM required)
+    #[cfg(feature = "office")]
+    pub fn new_cli_spreadsheet() -> Result<Self> {
+        let preprocessor = Box::new(SpreadsheetPreprocessor::new());
+        let storage = Box::new(FileStorage::new("cache")?);
+        Self::new_with_dependencies(preprocessor, storage)
+    }
+
+    /// Convenience constructor for CLI processing of Markdown (no JVM required)
+    #[cfg(feature = "markdown")]
+    pub fn new_cli_markdown() -> Result<Self> {
+        let preprocessor = Box::new(MarkdownPreprocessor::new());
+        let storage = Box::new(FileStorage::new("cache")?);
+        Self::new_with_dependencies(preprocessor, storage)
+    }
+
+    /// Convenience constructor for CLI processing of HTML pages (no JVM required)
+    #[cfg(feature = "html")]
+    pub fn new_cli_html() -> Result<Self> {
+        let preprocessor = Box::new(HtmlPreprocessor::new());
+        let storage = Box::new(FileStorage::new("cache")?);
+        Self::new_with_dependencies(preprocessor, storage)
+    }
+
+    /// Convenience constructor for CLI processing of plain text (no JVM required)
+    pub fn new_cli_txt() -> Result<Self> {
+        let preprocessor = Box::new(TxtPreprocessor::new());
+        let storage = Box::new(FileStorage::new("cache")?);
+        Self::new_with_dependencies(preprocessor, storage)
+    }
+
+    /// Convenience constructor for CLI runs from a Blazegraph XHTML snapshot (no backend required)
+    pub fn new_cli_xhtml() -> Result<Self> {
+        let preprocessor = Box::new(XhtmlPreprocessor::new());
+        let storage = Box::new(FileStorage::new("cache")?);
+        Self::new_with_dependencies(preprocessor, storage)
+    }
+
+    /// Convenience constructor for CLI runs from a TextElements JSON snapshot (no backend required)
+    pub fn new_cli_text_elements() -> Result<Self> {
+        let preprocessor = Box::new(TextElementsPreprocessor::new());
+        let storage = Box::new(FileStorage::new("cache")?);
+        Self::new_with_dependencies(preprocessor, storage)
+    }
+
     /// Convenience constructor for CLI with JNI backend and custom cache directory
     #[cfg(feature = "jni-backend")]
     pub fn new_cli_jni_with_cache(
@@ -163,36 +386,115 @@ impl DocumentProcessor {
         &mut self,
         input_path: &str,
         config: &ParsingConfig,
+    ) -> Result<DocumentGraph> {
+        let pdf_bytes = std::fs::read(input_path)?;
+        self.process_bytes_with_label(&pdf_bytes, input_path, config, self.cache_levels)
+    }
+
+    /// Process an in-memory document (e.g. an upload) without a temp file
+    ///
+    /// `format_hint` is a file extension or name ("docx", "report.docx");
+    /// when given, it must be a format this processor's preprocessor reads.
+    /// Caching works exactly as for `process_document_with_config`.
+    pub fn process_bytes(
+        &mut self,
+        bytes: &[u8],
+        format_hint: Option<&str>,
+        config: &ParsingConfig,
+    ) -> Result<DocumentGraph> {
+        self.process_bytes_with_cache_levels(bytes, format_hint, config, self.cache_levels)
+    }
+
+    /// `process_bytes` reading and writing only the given cache levels for
+    /// this one call, e.g. none for a document that must never be stored
+    pub fn process_bytes_with_cache_levels(
+        &mut self,
+        bytes: &[u8],
+        format_hint: Option<&str>,
+        config: &ParsingConfig,
+        cache_levels: CacheLevels,
+    ) -> Result<DocumentGraph> {
+        if let Some(hint) = format_hint {
+            if !self.supports_format(hint) {
+                bail!(
+                    "Format '{}' is not supported by the {} preprocessor",
+                    hint,
+                    self.preprocessor.name()
+                );
+            }
+        }
+        let label = format!("<{} bytes in memory>", bytes.len());
+        self.process_bytes_with_label(bytes, &label, config, cache_levels)
+    }
+
+    /// Process a document read to the end from `reader` (see `process_bytes`)
+    pub fn process_reader<R: Read>(
+        &mut self,
+        mut reader: R,
+        format_hint: Option<&str>,
+        config: &ParsingConfig,
+    ) -> Result<DocumentGraph> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .context("Failed to read document from reader")?;
+        self.process_bytes(&bytes, format_hint, config)
+    }
+
+    /// Whether this processor's preprocessor reads `format_hint` (an
+    /// extension or file name, as for `process_bytes`)
+    pub fn supports_format(&self, format_hint: &str) -> bool {
+        let extension = format_hint.rsplit('.').next().unwrap_or(format_hint).trim();
+        let probe = format!("document.{}", extension);
+        self.preprocessor.supports_file_type(Path::new(&probe))
+    }
+
+    /// Shared path/bytes entry point; `label` is only used for logging
+    fn process_bytes_with_label(
+        &mut self,
+        pdf_bytes: &[u8],
+        label: &str,
+        config: &ParsingConfig,
+        cache_levels: CacheLevels,
     ) -> Result<DocumentGraph> {
         let start_time = Instant::now();
 
-        // Read PDF and calculate hash
-        let pdf_bytes = std::fs::read(input_path)?;
-        let pdf_hash = calculate_pdf_hash(&pdf_bytes);
+        // Calculate document hash
+        let pdf_hash = calculate_pdf_hash(pdf_bytes);
 
         // Calculate config hash for Level 2 cache
         let config_hash = calculate_config_hash(config)?;
         let cache_key = GraphCacheKey::new(pdf_hash.clone(), config_hash);
 
-        // Check Level 2 cache: Config + PDF → Graph
-        if let Some(cached) = self.storage.get_graph_output(&cache_key)? {
-            println!("🎯 Cache hit: Found graph for PDF + config combination");
-            println!(
-                "⏱️  Total processing time: {:.3}s (cached)",
-                start_time.elapsed().as_secs_f64()
-            );
-            return Ok(cached.graph);
+        // Check Level 3 cache: Config + PDF → Graph
+        if self.graph_cache_usable(cache_levels) {
+            let cached = self.storage.get_graph_output(&cache_key)?;
+            CacheStats::record(&mut self.cache_stats.graph, cached.is_some());
+            if let Some(cached) = cached {
+                println!("🎯 Cache hit: Found graph for PDF + config combination");
+                let mut graph = cached.graph;
+                self.finish_assertions(&mut graph, Vec::new())?;
+                println!(
+                    "⏱️  Total processing time: {:.3}s (cached)",
+                    start_time.elapsed().as_secs_f64()
+                );
+                return Ok(graph);
+            }
         }
 
-        println!("📄 Processing document with config: {}", input_path);
+        println!("📄 Processing document with config: {}", label);
 
         // Process with config flow
-        let graph = self.process_with_config_flow(input_path, config)?;
-
-        // Store in Level 2 cache
-        let processing_time = start_time.elapsed().as_millis() as u64;
-        let cache_value = GraphCacheValue::new(graph.clone(), processing_time);
-        self.storage.store_graph_output(&cache_key, &cache_value)?;
+        let mut annotations = Vec::new();
+        let mut graph = self.process_with_config_flow(pdf_bytes, config, cache_levels, &mut annotations)?;
+
+        // Store in Level 3 cache
+        if cache_levels.graph {
+            let processing_time = start_time.elapsed().as_millis() as u64;
+            let cache_value = GraphCacheValue::new(graph.clone(), processing_time);
+            self.storage.store_graph_output(&cache_key, &cache_value)?;
+        }
+        self.finish_assertions(&mut graph, annotations)?;
 
         println!(
             "⏱️  Total processing time: {:.3}s",
@@ -212,47 +514,63 @@ impl DocumentProcessor {
         let start_time = Instant::now();
 
         // Check cache first (timed)
-        let (_pdf_hash, cache_key) = profiler.time_step("Cache Key Generation", || {
-            let pdf_bytes = std::fs::read(input_path)?;
+        let pdf_bytes = std::fs::read(input_path)?;
+        let cache_key = profiler.time_step("Cache Key Generation", || {
             let pdf_hash = calculate_pdf_hash(&pdf_bytes);
             let config_hash = calculate_config_hash(config)?;
-            let cache_key = GraphCacheKey::new(pdf_hash.clone(), config_hash);
-            Ok::<(String, GraphCacheKey), anyhow::Error>((pdf_hash, cache_key))
+            Ok::<GraphCacheKey, anyhow::Error>(GraphCacheKey::new(pdf_hash, config_hash))
         })?;
 
-        let cached_result = if skip_cache {
+        let cache_levels = if skip_cache {
             println!("🚫 Skipping cache lookup (--skip-cache enabled)");
-            None
+            CacheLevels::none()
+        } else {
+            self.cache_levels
+        };
+
+        let cached_result = if self.graph_cache_usable(cache_levels) {
+            let cached = profiler.time_step("Cache Lookup", || self.storage.get_graph_output(&cache_key))?;
+            CacheStats::record(&mut self.cache_stats.graph, cached.is_some());
+            cached
         } else {
-            profiler.time_step("Cache Lookup", || self.storage.get_graph_output(&cache_key))?
+            None
         };
 
         if let Some(cached) = cached_result {
             println!("🎯 Cache hit: Found graph for PDF + config combination");
+            let mut graph = cached.graph;
+            self.finish_assertions(&mut graph, Vec::new())?;
             profiler.print_summary();
             println!(
                 "⏱️  Total processing time: {:.0}ms (cached)",
                 start_time.elapsed().as_millis()
             );
-            return Ok(cached.graph);
+            return Ok(graph);
         }
 
         println!("📄 Processing document with config: {}", input_path);
 
         // Process with detailed profiling
-        let graph =
-            self.process_with_config_flow_and_profiler(input_path, config, &mut profiler)?;
+        let mut annotations = Vec::new();
+        let mut graph = self.process_with_config_flow_and_profiler(
+            &pdf_bytes,
+            config,
+            cache_levels,
+            &mut profiler,
+            &mut annotations,
+        )?;
 
         // Store in cache (timed) unless skipping cache
-        if !skip_cache {
+        if skip_cache {
+            println!("🚫 Skipping cache storage (--skip-cache enabled)");
+        } else if cache_levels.graph {
             profiler.time_step("Cache Storage", || {
                 let processing_time = start_time.elapsed().as_millis() as u64;
                 let cache_value = GraphCacheValue::new(graph.clone(), processing_time);
                 self.storage.store_graph_output(&cache_key, &cache_value)
             })?;
-        } else {
-            println!("🚫 Skipping cache storage (--skip-cache enabled)");
         }
+        self.finish_assertions(&mut graph, annotations)?;
 
         profiler.print_summary();
         println!(
@@ -265,14 +583,16 @@ impl DocumentProcessor {
     /// Internal processing with config flow through all pipeline stages
     fn process_with_config_flow(
         &mut self,
-        input_path: &str,
+        pdf_bytes: &[u8],
         config: &ParsingConfig,
+        cache_levels: CacheLevels,
+        annotations: &mut Vec<ProcessingWarning>,
     ) -> Result<DocumentGraph> {
+        let mut profiler = StepProfiler::new(false);
         let stage1_start = Instant::now();
 
-        // Stage 1: Preprocessing (PDF → TextElements)
-        let input_path = Path::new(input_path);
-        let preprocessor_output = self.preprocessor.process_file(input_path)?;
+        // Stage 1: Preprocessing (PDF → TextElements), Level 1 cache
+        let extraction = self.extract_with_cache(pdf_bytes, cache_levels, &mut profiler)?;
         println!(
             "⏱️  Preprocessing: {:.3}s",
             stage1_start.elapsed().as_secs_f64()
@@ -280,106 +600,233 @@ impl DocumentProcessor {
 
         let stage2_start = Instant::now();
 
-        // Stage 2: Classification
-        let classification = self.classifier.classify(&preprocessor_output)?;
-        println!("📋 Document classified as: {:?}", classification);
-        println!(
-            "⏱️  Classification: {:.3}s",
-            stage2_start.elapsed().as_secs_f64()
-        );
-
-        let stage3_start = Instant::now();
-
-        // Compute document analysis once (used by rules and stored in DocumentInfo)
-        let document_analysis =
-            DocumentAnalysis::analyze_text_elements(&preprocessor_output.text_elements);
-
-        // Stage 3: Rule processing with config (TextElements + Config → ParsedElements)
-        let parsed_elements = if config.minimal_parse {
-            println!("🔄 Minimal parse mode - skipping rule processing");
-            self.rule_engine
-                .convert_text_elements_to_parsed(&preprocessor_output.text_elements)
-        } else {
-            let font_size_analysis = self.rule_engine.analyze_font_sizes(
-                &preprocessor_output.text_elements,
-                &preprocessor_output.style_data,
-            );
-
-            // Apply rules with config guiding behavior
-            self.rule_engine.apply_rules_with_config(
-                &preprocessor_output.text_elements,
-                &classification,
-                &document_analysis,
-                &font_size_analysis,
-                &preprocessor_output.style_data,
-                config, // Config flows through rule engine
-            )?
-        };
-
+        // Stages 1c-3: Page filters, classification and rules, Level 2 cache
+        let xhtml_hash = extraction.xhtml_hash.clone();
+        let extraction_info = extraction.extraction.clone();
+        let mut preprocessor_output = into_preprocessor_output(extraction);
+        self.assertions
+            .check(StageOutput::Extraction(&preprocessor_output), annotations)?;
+        let rules_output = self.apply_rules_with_cache(
+            &xhtml_hash,
+            &mut preprocessor_output,
+            config,
+            cache_levels,
+            &mut profiler,
+        )?;
+        self.assertions
+            .check(StageOutput::Rules(&rules_output.parsed_elements), annotations)?;
         println!(
             "⏱️  Rule processing: {:.3}s",
-            stage3_start.elapsed().as_secs_f64()
+            stage2_start.elapsed().as_secs_f64()
         );
 
         let stage4_start = Instant::now();
 
-        // Infer title from content before elements are consumed by graph builder
-        let inferred_title = infer_title(&parsed_elements);
-
         // Stage 4: Graph building (ParsedElements + Config → Graph)
-        let mut graph = self.graph_builder.build_graph(parsed_elements)?;
+        let tokenizer = self.rule_engine.tokenizer(&config.tokenizer)?;
+        let graph = self.build_graph_with_metadata(rules_output, preprocessor_output, extraction_info, tokenizer.as_ref(), config)?;
         println!(
             "⏱️  Graph construction: {:.3}s",
             stage4_start.elapsed().as_secs_f64()
         );
 
-        // Stage 5: Wire metadata and compute post-processing
-        if let Some(title) = inferred_title {
-            graph.document_info.document_metadata.title = Some(title);
-        }
-        graph.document_info.document_metadata.merge_extracted(preprocessor_output.metadata);
-        graph.document_info.document_analysis = document_analysis;
-        graph.compute_structural_profile();
-        graph.compute_breadcrumbs();
-
         Ok(graph)
     }
 
     /// Internal processing with detailed profiling
     fn process_with_config_flow_and_profiler(
         &mut self,
-        input_path: &str,
+        pdf_bytes: &[u8],
         config: &ParsingConfig,
+        cache_levels: CacheLevels,
         profiler: &mut StepProfiler,
+        annotations: &mut Vec<ProcessingWarning>,
     ) -> Result<DocumentGraph> {
-        // Stage 1: Preprocessing with sub-steps
-        let input_path = Path::new(input_path);
-        let pdf_bytes = std::fs::read(input_path)?;
-        let markup = profiler.time_step("1. PDF → Markup", || {
-            self.preprocessor.parse_pdf_to_markup_language(&pdf_bytes)
+        let extraction = self.extract_with_cache(pdf_bytes, cache_levels, profiler)?;
+
+        let xhtml_hash = extraction.xhtml_hash.clone();
+        let extraction_info = extraction.extraction.clone();
+        let mut preprocessor_output = into_preprocessor_output(extraction);
+        self.assertions
+            .check(StageOutput::Extraction(&preprocessor_output), annotations)?;
+        let rules_output = self.apply_rules_with_cache(
+            &xhtml_hash,
+            &mut preprocessor_output,
+            config,
+            cache_levels,
+            profiler,
+        )?;
+        self.assertions
+            .check(StageOutput::Rules(&rules_output.parsed_elements), annotations)?;
+
+        let tokenizer = self.rule_engine.tokenizer(&config.tokenizer)?;
+        profiler.time_step("5. Graph Construction", || {
+            self.build_graph_with_metadata(rules_output, preprocessor_output, extraction_info, tokenizer.as_ref(), config)
+        })
+    }
+
+    /// Stage 1: document bytes → XHTML → TextElements, through the Level 1 cache
+    fn extract_with_cache(
+        &mut self,
+        pdf_bytes: &[u8],
+        cache_levels: CacheLevels,
+        profiler: &mut StepProfiler,
+    ) -> Result<TikaOutput> {
+        let pdf_hash = calculate_pdf_hash(pdf_bytes);
+        if cache_levels.extraction {
+            let cached = self.storage.get_tika_output(&pdf_hash)?;
+            CacheStats::record(&mut self.cache_stats.extraction, cached.is_some());
+            if let Some(cached) = cached {
+                println!("🎯 Cache hit: Found extraction for PDF (level 1)");
+                return Ok(cached);
+            }
+        }
+
+        // The markup is parsed as it is extracted, so the two steps are timed together
+        let Extraction { mut output, markup_hash, info } = profiler.time_step("1. PDF → Markup → TextElements", || {
+            self.preprocessor.extract(pdf_bytes)
         })?;
+        self.classify_pages(&mut output, pdf_bytes);
+        list_attachments(&mut output, pdf_bytes);
+        read_link_annotations(&mut output, pdf_bytes);
+
+        let extraction = TikaOutput {
+            xhtml_hash: markup_hash,
+            xhtml_content: String::new(),
+            metadata: output.metadata,
+            text_elements: output.text_elements,
+            style_data: output.style_data,
+            bookmark_data: output.bookmark_data,
+            warnings: output.warnings,
+            extraction: Some(info),
+            page_extraction: output.page_extraction,
+            links: output.links,
+        };
+        if cache_levels.extraction {
+            self.storage.store_tika_output(&pdf_hash, &extraction)?;
+        }
+        Ok(extraction)
+    }
 
-        let preprocessor_output = profiler.time_step("2. Markup → TextElements", || {
-            self.preprocessor
-                .parse_markup_to_preprocessor_output(&markup)
+    /// Stages 1c-3: page filters, classification and rules, through the Level 2 cache
+    ///
+    /// The key covers the XHTML and only the rule-stage part of the config, so
+    /// graph-only option changes reuse the cached ParsedElements.
+    fn apply_rules_with_cache(
+        &mut self,
+        xhtml_hash: &str,
+        preprocessor_output: &mut PreprocessorOutput,
+        config: &ParsingConfig,
+        cache_levels: CacheLevels,
+        profiler: &mut StepProfiler,
+    ) -> Result<ParsedElementsCacheValue> {
+        let cache_key =
+            ParsedElementsCacheKey::new(xhtml_hash.to_string(), calculate_rules_hash(config)?);
+        if cache_levels.parsed_elements {
+            let cached = self.storage.get_parsed_elements(&cache_key)?;
+            CacheStats::record(&mut self.cache_stats.parsed_elements, cached.is_some());
+            if let Some(cached) = cached {
+                println!(
+                    "🎯 Cache hit: Found {} ParsedElements for XHTML + rules (level 2)",
+                    cached.parsed_elements.len()
+                );
+                return Ok(cached);
+            }
+        }
+        let cache_value =
+            self.run_rule_stages(preprocessor_output, config, None, &DebugConfig::disabled(), profiler)?;
+        if cache_levels.parsed_elements {
+            self.storage.store_parsed_elements(&cache_key, &cache_value)?;
+        }
+        Ok(cache_value)
+    }
+
+    /// Stages 1c-3, shared by every processing path: the config's page range,
+    /// text normalization, page filters, color rules, span merging, reading
+    /// order, page order and labels, then classification and rules.
+    /// `minimal_parse` overrides whether the rules are skipped (the config's
+    /// `minimal_parse` and auto-detection decide when it is `None`)
+    fn run_rule_stages(
+        &self,
+        preprocessor_output: &mut PreprocessorOutput,
+        config: &ParsingConfig,
+        minimal_parse: Option<bool>,
+        debug_config: &DebugConfig,
+        profiler: &mut StepProfiler,
+    ) -> Result<ParsedElementsCacheValue> {
+        let start_time = Instant::now();
+
+        profiler.time_step("2a. Page Range", || {
+            select_pages(&mut preprocessor_output.text_elements, config.page_range.as_ref())
         })?;
+        profiler.time_step("2b. Text Normalization", || {
+            normalize_text(&mut preprocessor_output.text_elements, &config.text_normalization)
+        });
+        let mut warnings = Vec::new();
+        let skipped_pages = profiler.time_step("2c. Page Filters", || {
+            filter_pages(
+                &mut preprocessor_output.text_elements,
+                &config.page_filters,
+                &mut warnings,
+            )
+        })?;
+        profiler.time_step("2d. Color Rules", || {
+            apply_color_rules(&mut preprocessor_output.text_elements, &config.color_rules)
+        })?;
+        profiler.time_step("2e. Span Merging", || {
+            merge_spans(&mut preprocessor_output.text_elements, &config.span_merging);
+            split_giant_spans(
+                &mut preprocessor_output.text_elements,
+                &config.giant_spans,
+                &mut warnings,
+            )
+        });
+        profiler.time_step("2f. Reading Order", || {
+            apply_reading_direction(&mut preprocessor_output.text_elements, config.reading_direction);
+            order_columns(
+                &mut preprocessor_output.text_elements,
+                &config.column_detection,
+                config.reading_direction,
+            )
+        });
+        let page_reordering = profiler.time_step("2g. Page Order", || {
+            repair_page_order(
+                &mut preprocessor_output.text_elements,
+                &config.page_order,
+                &mut warnings,
+            )
+        });
+        let page_labels = profiler.time_step("2h. Page Labels", || {
+            label_pages(&mut preprocessor_output.text_elements, &config.page_labels)
+        });
 
         // Stage 2: Classification
         let classification = profiler.time_step("3. Classification", || {
-            self.classifier.classify(&preprocessor_output)
+            self.classifier.classify(preprocessor_output)
         })?;
+        println!("📋 Document classified as: {:?}", classification);
 
         // Compute document analysis once (used by rules and stored in DocumentInfo)
         let document_analysis = profiler.time_step("4a. Document Analysis", || {
             DocumentAnalysis::analyze_text_elements(&preprocessor_output.text_elements)
         });
 
-        // Stage 3: Rule processing with detailed timing
-        let parsed_elements = if config.minimal_parse {
-            profiler.time_step("4. Minimal Parse", || {
+        // Stage 3: Rule processing with config (TextElements + Config → ParsedElements)
+        let minimal_parse = minimal_parse.unwrap_or_else(|| {
+            skip_rules(
+                &preprocessor_output.text_elements,
+                &document_analysis,
+                config,
+                &mut warnings,
+            )
+        });
+        let (parsed_elements, validation) = if minimal_parse {
+            println!("🔄 Minimal parse mode - skipping rule processing");
+            let elements = profiler.time_step("4. Minimal Parse", || {
                 self.rule_engine
                     .convert_text_elements_to_parsed(&preprocessor_output.text_elements)
-            })
+            });
+            (elements, None)
         } else {
             let font_size_analysis = profiler.time_step("4b. Font Analysis", || {
                 self.rule_engine.analyze_font_sizes(
@@ -388,34 +835,75 @@ impl DocumentProcessor {
                 )
             });
 
-            profiler.time_step("4c. Rules Processing", || {
-                self.rule_engine.apply_rules_with_config(
+            let run = profiler.time_step("4c. Rules Processing", || {
+                self.rule_engine.run_pipeline(
                     &preprocessor_output.text_elements,
                     &classification,
                     &document_analysis,
                     &font_size_analysis,
                     &preprocessor_output.style_data,
-                    config,
+                    config, // Config flows through rule engine
+                    &config.pipeline.rules,
+                    debug_config,
                 )
-            })?
+            })?;
+            warnings.extend(run.warnings);
+            let mut validation = run.validation;
+            check_outline(&run.elements, validation.as_mut(), preprocessor_output.bookmark_data.as_ref(), config)?;
+            (run.elements, validation)
         };
 
+        Ok(ParsedElementsCacheValue::new(
+            parsed_elements,
+            document_analysis,
+            skipped_pages,
+            page_reordering,
+            page_labels,
+            warnings,
+            start_time.elapsed().as_millis() as u64,
+        )
+        .with_validation(validation))
+    }
+
+    /// Stages 4-5: graph building, then wire metadata and post-processing
+    fn build_graph_with_metadata(
+        &self,
+        rules_output: ParsedElementsCacheValue,
+        extraction: PreprocessorOutput,
+        extraction_info: Option<ExtractionInfo>,
+        tokenizer: &dyn Tokenizer,
+        config: &ParsingConfig,
+    ) -> Result<DocumentGraph> {
         // Infer title from content before elements are consumed by graph builder
-        let inferred_title = infer_title(&parsed_elements);
+        let inferred_title = infer_title(&rules_output.parsed_elements);
 
-        // Stage 4: Graph building
-        let mut graph = profiler.time_step("5. Graph Construction", || {
-            self.graph_builder.build_graph(parsed_elements)
-        })?;
+        let mut graph = self.graph_builder.build_graph(rules_output.parsed_elements)?;
+        graph.count_tokens(tokenizer);
 
-        // Stage 5: Wire metadata and compute post-processing
         if let Some(title) = inferred_title {
             graph.document_info.document_metadata.title = Some(title);
         }
-        graph.document_info.document_metadata.merge_extracted(preprocessor_output.metadata);
-        graph.document_info.document_analysis = document_analysis;
+        graph.document_info.document_metadata.merge_extracted(extraction.metadata);
+        graph.document_info.document_analysis = rules_output.document_analysis;
+        graph.document_info.page_range = config.page_range.clone();
+        graph.document_info.skipped_pages = rules_output.skipped_pages;
+        graph.document_info.page_reordering = rules_output.page_reordering;
+        graph.document_info.page_labels = rules_output.page_labels;
+        graph.apply_page_labels();
+        graph.document_info.warnings = extraction.warnings;
+        graph.document_info.warnings.extend(rules_output.warnings);
+        let validation = rules_output.validation;
+        graph.document_info.extraction = extraction_info;
+        graph.document_info.page_extraction = extraction.page_extraction;
+        graph.attach_links(&extraction.links);
+        graph.apply_flow_type(self.preprocessor.flow_type());
         graph.compute_structural_profile();
+        graph.structural_profile.validation = validation;
+        graph.document_info.content_hash = Some(graph.content_hash());
         graph.compute_breadcrumbs();
+        graph.compute_text_spans();
+        graph.compute_references();
+        graph.compute_glossary(&config.glossary);
 
         Ok(graph)
     }
@@ -434,11 +922,10 @@ impl DocumentProcessor {
         println!("📄 Processing document: {}", input_path);
 
         // Step 1: Use preprocessor to extract and parse document
-        let preprocessor_output = if include_raw_tika || output_dir.is_some() {
+        let pdf_bytes = std::fs::read(Path::new(input_path))?;
+        let (mut preprocessor_output, extraction_info) = if include_raw_tika || output_dir.is_some() {
             // For now, handle raw output options by doing two-step process manually
-            let input_path = Path::new(input_path);
-            let pdf_bytes = std::fs::read(input_path)?;
-            let markup = self.preprocessor.parse_pdf_to_markup_language(&pdf_bytes)?;
+            let (markup, info) = self.preprocessor.extract_markup(&pdf_bytes)?;
 
             // Save raw markup if requested
             if include_raw_tika {
@@ -453,67 +940,42 @@ impl DocumentProcessor {
                 }
             }
 
-            self.preprocessor
-                .parse_markup_to_preprocessor_output(&markup)?
+            (self.preprocessor.parse_markup_to_preprocessor_output(&markup)?, info)
         } else {
-            // Standard processing - use the convenience method
-            let input_path = Path::new(input_path);
-            self.preprocessor.process_file(input_path)?
+            // Standard processing - parse the markup as it is extracted
+            let extraction = self.preprocessor.extract(&pdf_bytes)?;
+            (extraction.output, extraction.info)
         };
+        self.classify_pages(&mut preprocessor_output, &pdf_bytes);
+        list_attachments(&mut preprocessor_output, &pdf_bytes);
+        read_link_annotations(&mut preprocessor_output, &pdf_bytes);
 
         println!(
             "⏱️  Preprocessing complete: {:.3}s",
             start_time.elapsed().as_secs_f64()
         );
 
-        let step2_start = Instant::now();
-
-        // Step 2: Document classification
+        // Step 2: Resolve the config for the document's type once; every
+        // stage from the page range on runs with it
         let classification = self.classifier.classify(&preprocessor_output)?;
-        println!("📋 Document classified as: {:?}", classification);
-
-        // Step 3: Get text elements (already parsed by preprocessor)
-        println!(
-            "⏱️  Text parsing: {:.3}s",
-            step2_start.elapsed().as_secs_f64()
-        );
+        let config = self.config_manager.get_config(&classification.document_type);
+        let debug_config = if debug_output {
+            DebugConfig::new(true, debug_filters.to_vec())
+        } else {
+            DebugConfig::disabled()
+        };
 
         let step3_start = Instant::now();
 
-        // Compute document analysis once (used by rules and stored in DocumentInfo)
-        let document_analysis =
-            DocumentAnalysis::analyze_text_elements(&preprocessor_output.text_elements);
-
-        // Step 4: Apply rules (skip if minimal parse requested)
-        let parsed_elements = if minimal_parse.unwrap_or(false) {
-            println!("🔄 Minimal parse mode - skipping rule processing");
-            // Convert text elements to parsed elements without processing
-            self.rule_engine
-                .convert_text_elements_to_parsed(&preprocessor_output.text_elements)
-        } else {
-            // Set up debug config
-            if debug_output {
-                let debug_config = DebugConfig {
-                    enabled: true,
-                    filter_patterns: debug_filters.to_vec(),
-                };
-                self.rule_engine.set_debug_config(debug_config);
-            }
-
-            let font_size_analysis = self.rule_engine.analyze_font_sizes(
-                &preprocessor_output.text_elements,
-                &preprocessor_output.style_data,
-            );
-
-            // Apply rules to get processed elements
-            self.rule_engine.apply_rules(
-                &preprocessor_output.text_elements,
-                &classification,
-                &document_analysis,
-                &font_size_analysis,
-                &preprocessor_output.style_data,
-            )?
-        };
+        // Step 3: Page preparation, classification and rules (skipped if
+        // minimal parse is requested or configured)
+        let rules_output = self.run_rule_stages(
+            &mut preprocessor_output,
+            config,
+            minimal_parse,
+            &debug_config,
+            &mut StepProfiler::new(false),
+        )?;
 
         println!(
             "⏱️  Rule processing: {:.3}s",
@@ -522,20 +984,15 @@ impl DocumentProcessor {
 
         let step4_start = Instant::now();
 
-        // Infer title from content before elements are consumed by graph builder
-        let inferred_title = infer_title(&parsed_elements);
-
-        // Step 5: Build graph from processed elements
-        let mut graph = self.graph_builder.build_graph(parsed_elements)?;
-
-        // Step 6: Wire metadata and compute post-processing
-        if let Some(title) = inferred_title {
-            graph.document_info.document_metadata.title = Some(title);
-        }
-        graph.document_info.document_metadata.merge_extracted(preprocessor_output.metadata);
-        graph.document_info.document_analysis = document_analysis;
-        graph.compute_structural_profile();
-        graph.compute_breadcrumbs();
+        // Step 4: Build graph from processed elements, then wire metadata and post-processing
+        let tokenizer = self.rule_engine.tokenizer(&config.tokenizer)?;
+        let graph = self.build_graph_with_metadata(
+            rules_output,
+            preprocessor_output,
+            Some(extraction_info),
+            tokenizer.as_ref(),
+            config,
+        )?;
 
         println!(
             "⏱️  Graph construction: {:.3}s",
@@ -560,57 +1017,44 @@ impl DocumentProcessor {
         let pdf_bytes = std::fs::read(input_path_ref)?;
 
         // Stage 1a: PDF → XHTML
-        let xhtml = self.preprocessor.parse_pdf_to_markup_language(&pdf_bytes)?;
+        let (xhtml, extraction_info) = self.preprocessor.extract_markup(&pdf_bytes)?;
         println!("📋 Stage 1a: XHTML captured ({} bytes)", xhtml.len());
 
         // Stage 1b: XHTML → TextElements
-        let preprocessor_output = self
+        let mut preprocessor_output = self
             .preprocessor
             .parse_markup_to_preprocessor_output(&xhtml)?;
+        self.classify_pages(&mut preprocessor_output, &pdf_bytes);
+        list_attachments(&mut preprocessor_output, &pdf_bytes);
+        read_link_annotations(&mut preprocessor_output, &pdf_bytes);
+
+        // Stage 2: Page preparation, classification + rules → ParsedElements.
+        // The page stages run before capture so text_elements is exactly what the rules see
+        let rules_output = self.run_rule_stages(
+            &mut preprocessor_output,
+            config,
+            None,
+            &DebugConfig::disabled(),
+            &mut StepProfiler::new(false),
+        )?;
         let text_elements = preprocessor_output.text_elements.clone();
+        let style_data = preprocessor_output.style_data.clone();
         println!("📋 Stage 1b: {} TextElements captured", text_elements.len());
-
-        // Stage 2: Classification + Rules → ParsedElements
-        let classification = self.classifier.classify(&preprocessor_output)?;
-        let document_analysis =
-            DocumentAnalysis::analyze_text_elements(&preprocessor_output.text_elements);
-
-        let parsed_elements = if config.minimal_parse {
-            self.rule_engine
-                .convert_text_elements_to_parsed(&preprocessor_output.text_elements)
-        } else {
-            let font_size_analysis = self.rule_engine.analyze_font_sizes(
-                &preprocessor_output.text_elements,
-                &preprocessor_output.style_data,
-            );
-            self.rule_engine.apply_rules_with_config(
-                &preprocessor_output.text_elements,
-                &classification,
-                &document_analysis,
-                &font_size_analysis,
-                &preprocessor_output.style_data,
-                config,
-            )?
-        };
+        let parsed_elements = rules_output.parsed_elements.clone();
         println!(
             "📋 Stage 2: {} ParsedElements captured",
             parsed_elements.len()
         );
 
-        // Infer title from content before graph build
-        let inferred_title = infer_title(&parsed_elements);
-
         // Stage 3: ParsedElements → DocumentGraph
-        let mut graph = self.graph_builder.build_graph(parsed_elements.clone())?;
-
-        // Wire metadata and compute post-processing
-        if let Some(title) = inferred_title {
-            graph.document_info.document_metadata.title = Some(title);
-        }
-        graph.document_info.document_metadata.merge_extracted(preprocessor_output.metadata);
-        graph.document_info.document_analysis = document_analysis;
-        graph.compute_structural_profile();
-        graph.compute_breadcrumbs();
+        let tokenizer = self.rule_engine.tokenizer(&config.tokenizer)?;
+        let graph = self.build_graph_with_metadata(
+            rules_output,
+            preprocessor_output,
+            Some(extraction_info),
+            tokenizer.as_ref(),
+            config,
+        )?;
 
         println!(
             "📋 Stage 3: Graph captured ({} nodes)",
@@ -620,11 +1064,20 @@ impl DocumentProcessor {
         Ok(PipelineStages {
             xhtml,
             text_elements,
+            style_data,
             parsed_elements,
             graph,
         })
     }
 
+    /// Stage 1b: record per-page extraction methods for formats that can hold
+    /// scans, OCRing pages without a text layer when OCR is available
+    fn classify_pages(&self, output: &mut PreprocessorOutput, document_bytes: &[u8]) {
+        if self.preprocessor.may_contain_scans() {
+            hybrid::classify_pages(output, document_bytes, self.ocr.as_ref());
+        }
+    }
+
     /// Simple document processing function using default config
     pub fn process_document(&mut self, input_path: &str) -> Result<DocumentGraph> {
         let default_config = ParsingConfig::default();
@@ -641,3 +1094,63 @@ impl DocumentProcessor {
         self.process_document_with_config(input_path, &config)
     }
 }
+
+/// List the files embedded in the document in its metadata
+fn list_attachments(output: &mut PreprocessorOutput, document_bytes: &[u8]) {
+    if !cfg!(feature = "attachments") {
+        return;
+    }
+    match extract_attachments(document_bytes) {
+        Ok(files) => {
+            if !files.is_empty() {
+                println!("📎 {} embedded file(s)", files.len());
+            }
+            output.metadata.attachments = files.into_iter().map(|file| file.info).collect();
+        }
+        Err(e) => println!("⚠️  Could not read embedded files: {}", e),
+    }
+}
+
+/// Add the PDF's URI link annotations to the extracted links, anchored to
+/// the text under them
+fn read_link_annotations(output: &mut PreprocessorOutput, document_bytes: &[u8]) {
+    if !cfg!(feature = "pdf-links") {
+        return;
+    }
+    match extract_link_annotations(document_bytes) {
+        Ok(annotations) => {
+            let added = add_link_annotations(output, annotations);
+            if added > 0 {
+                println!("🔗 {} link annotation(s)", added);
+            }
+        }
+        Err(e) => println!("⚠️  Could not read link annotations: {}", e),
+    }
+}
+
+/// Cross-check the final sections against the PDF outline, when the
+/// Validation rule ran and the document has one
+fn check_outline(
+    elements: &[ParsedPdfElement],
+    validation: Option<&mut ValidationReport>,
+    bookmarks: Option<&BookmarkData>,
+    config: &ParsingConfig,
+) -> Result<()> {
+    match (validation, bookmarks) {
+        (Some(report), Some(bookmarks)) => ValidationRule::new(config).check_outline(elements, bookmarks, report),
+        _ => Ok(()),
+    }
+}
+
+/// Split a Level 1 extraction back into preprocessor output
+fn into_preprocessor_output(extraction: TikaOutput) -> PreprocessorOutput {
+    PreprocessorOutput {
+        text_elements: extraction.text_elements,
+        metadata: extraction.metadata,
+        style_data: extraction.style_data,
+        bookmark_data: extraction.bookmark_data,
+        warnings: extraction.warnings,
+        page_extraction: extraction.page_extraction,
+        links: extraction.links,
+    }
+}
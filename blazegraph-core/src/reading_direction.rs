@@ -0,0 +1,184 @@
+//! Reading direction: right-to-left scripts and vertical CJK
+//!
+//! Ordering spans top to bottom, then left to right, is right for Latin
+//! text and scrambles everything else: an Arabic or Hebrew line is read from
+//! its right edge, and vertical Japanese or Chinese is set in columns read
+//! top to bottom, right to left. Each page's direction is detected from its
+//! text, unless `reading_direction` in the config forces one:
+//!
+//! - `rtl` when most letters are Hebrew or Arabic;
+//! - `vertical_rl` when most letters are CJK and most multi-character spans
+//!   are taller than they are wide;
+//! - `ltr` otherwise.
+//!
+//! Extraction sorts each page in its direction; column detection reads RTL
+//! columns right to left and leaves vertical pages alone; spatial
+//! clustering measures adjacency across vertical lines instead of down the
+//! page, and joins CJK lines without a space.
+
+use crate::types::{BoundingBox, PdfTextElement};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadingDirection {
+    /// Detected per page
+    #[default]
+    Auto,
+    /// Left to right, top to bottom
+    Ltr,
+    /// Right to left, top to bottom (Arabic, Hebrew)
+    Rtl,
+    /// Top to bottom in columns read right to left (vertical CJK)
+    VerticalRl,
+}
+
+/// A span is vertical when this much taller than wide
+const VERTICAL_ASPECT: f32 = 1.5;
+
+fn is_rtl(c: char) -> bool {
+    matches!(c, '\u{0590}'..='\u{08ff}' | '\u{fb1d}'..='\u{fdff}' | '\u{fe70}'..='\u{feff}')
+}
+
+/// Han, kana and Hangul
+pub fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30ff}' | '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}' | '\u{ac00}'..='\u{d7af}' | '\u{f900}'..='\u{faff}'
+    )
+}
+
+impl ReadingDirection {
+    /// The direction of a page with these spans: this one when forced, else
+    /// detected from their script and shape
+    pub fn resolve<'a>(self, spans: impl IntoIterator<Item = (&'a str, &'a BoundingBox)>) -> ReadingDirection {
+        if self != ReadingDirection::Auto {
+            return self;
+        }
+        let (mut letters, mut rtl, mut cjk) = (0usize, 0usize, 0usize);
+        let (mut spans_measured, mut vertical) = (0usize, 0usize);
+        for (text, bbox) in spans {
+            for c in text.chars().filter(|c| c.is_alphabetic()) {
+                letters += 1;
+                rtl += usize::from(is_rtl(c));
+                cjk += usize::from(is_cjk(c));
+            }
+            if text.trim().chars().count() > 1 {
+                spans_measured += 1;
+                vertical += usize::from(bbox.height > bbox.width * VERTICAL_ASPECT);
+            }
+        }
+        if rtl * 2 > letters {
+            ReadingDirection::Rtl
+        } else if cjk * 2 > letters && vertical * 2 > spans_measured {
+            ReadingDirection::VerticalRl
+        } else {
+            ReadingDirection::Ltr
+        }
+    }
+
+    /// Reading order of two spans on a page read in this direction
+    pub fn compare(self, a: &BoundingBox, b: &BoundingBox) -> Ordering {
+        let right = |bbox: &BoundingBox| bbox.x + bbox.width;
+        match self {
+            ReadingDirection::Auto | ReadingDirection::Ltr => a.y.total_cmp(&b.y).then_with(|| a.x.total_cmp(&b.x)),
+            ReadingDirection::Rtl => a.y.total_cmp(&b.y).then_with(|| right(b).total_cmp(&right(a))),
+            ReadingDirection::VerticalRl => right(b).total_cmp(&right(a)).then_with(|| a.y.total_cmp(&b.y)),
+        }
+    }
+}
+
+/// A vertical-rl box turned so its lines run left to right and stack top to
+/// bottom, for code that measures line gaps down the page
+pub fn transpose(bbox: &BoundingBox) -> BoundingBox {
+    BoundingBox {
+        x: bbox.y,
+        y: -(bbox.x + bbox.width),
+        width: bbox.height,
+        height: bbox.width,
+    }
+}
+
+/// What goes between two merged pieces of text: nothing between CJK
+/// characters, which are not separated by spaces, else a space
+pub fn joiner(before: &str, after: &str) -> &'static str {
+    match (before.trim_end().chars().last(), after.trim_start().chars().next()) {
+        (Some(last), Some(first)) if is_cjk(last) && is_cjk(first) => "",
+        _ => " ",
+    }
+}
+
+/// Re-sort every page in `direction` (a no-op for `Auto`: extraction
+/// already sorted each page in its detected direction); returns how many
+/// pages changed
+pub fn apply_reading_direction(text_elements: &mut [PdfTextElement], direction: ReadingDirection) -> usize {
+    if direction == ReadingDirection::Auto {
+        return 0;
+    }
+    let mut pages: BTreeMap<u32, Vec<usize>> = BTreeMap::new();
+    for (index, element) in text_elements.iter().enumerate() {
+        pages.entry(element.page_number).or_default().push(index);
+    }
+
+    let mut reordered = 0;
+    for indices in pages.values() {
+        let mut order = indices.clone();
+        order.sort_by(|&a, &b| direction.compare(&text_elements[a].bounding_box, &text_elements[b].bounding_box));
+        // Reuse the page's own reading_order values so other pages are untouched
+        let mut slots: Vec<u32> = indices.iter().map(|&i| text_elements[i].reading_order).collect();
+        slots.sort_unstable();
+        if order.iter().zip(&slots).all(|(&i, &slot)| text_elements[i].reading_order == slot) {
+            continue;
+        }
+        for (&i, slot) in order.iter().zip(slots) {
+            text_elements[i].reading_order = slot;
+        }
+        reordered += 1;
+    }
+
+    if reordered > 0 {
+        text_elements.sort_by_key(|e| (e.page_number, e.reading_order));
+        println!("↔️  Re-read {} page(s) as {:?}", reordered, direction);
+    }
+    reordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{bbox, TextElementBuilder};
+
+    #[test]
+    fn detects_and_orders_rtl_and_vertical_pages() {
+        // Two Hebrew words on one line, then a second line
+        let hebrew = [("שלום", bbox(300.0, 100.0, 40.0, 12.0)), ("עולם", bbox(250.0, 100.0, 40.0, 12.0)), ("שורה", bbox(290.0, 120.0, 50.0, 12.0))];
+        let direction = ReadingDirection::Auto.resolve(hebrew.iter().map(|(t, b)| (*t, b)));
+        assert_eq!(direction, ReadingDirection::Rtl);
+        assert_eq!(direction.compare(&hebrew[0].1, &hebrew[1].1), Ordering::Less);
+        assert_eq!(direction.compare(&hebrew[1].1, &hebrew[2].1), Ordering::Less);
+
+        // Vertical Japanese: columns right to left
+        let japanese = [("縦書きの文章", bbox(400.0, 50.0, 12.0, 72.0)), ("次の行です", bbox(380.0, 50.0, 12.0, 60.0))];
+        let direction = ReadingDirection::Auto.resolve(japanese.iter().map(|(t, b)| (*t, b)));
+        assert_eq!(direction, ReadingDirection::VerticalRl);
+        assert_eq!(direction.compare(&japanese[0].1, &japanese[1].1), Ordering::Less);
+        // Horizontal Japanese stays left to right
+        let horizontal = [("横書きの文章", bbox(50.0, 50.0, 72.0, 12.0))];
+        assert_eq!(ReadingDirection::Auto.resolve(horizontal.iter().map(|(t, b)| (*t, b))), ReadingDirection::Ltr);
+        assert_eq!(joiner("文章", "次の"), "");
+        assert_eq!(joiner("end.", "Next"), " ");
+
+        // Forcing a direction re-sorts each page
+        let mut elements: Vec<PdfTextElement> = hebrew
+            .iter()
+            .enumerate()
+            .map(|(order, (text, b))| TextElementBuilder::new(text).bbox(b.clone()).reading_order(order as u32).build())
+            .collect();
+        assert_eq!(apply_reading_direction(&mut elements, ReadingDirection::Auto), 0);
+        assert_eq!(apply_reading_direction(&mut elements, ReadingDirection::Ltr), 1);
+        let texts: Vec<&str> = elements.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, ["עולם", "שלום", "שורה"]);
+    }
+}
@@ -0,0 +1,230 @@
+//! Page rendering
+//!
+//! Rasterizes document pages to PNG. Several consumers need page images
+//! (the visual report, OCR of scanned pages, ML layout models), so they all
+//! go through the `PageRenderer` trait rather than each shelling out on
+//! their own.
+//!
+//! Renderers are optional: the `page-render` feature enables
+//! `PopplerRenderer`, which drives Poppler's `pdftoppm`. Without it,
+//! `default_renderer()` returns `None` and callers skip their image step.
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+/// DPI used when the caller doesn't choose one
+pub const DEFAULT_DPI: u32 = 150;
+
+/// Which pages to render, and how finely
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    pub dpi: u32,
+    /// First page to render (1-based, inclusive); `None` = from the start
+    pub first_page: Option<u32>,
+    /// Last page to render (1-based, inclusive); `None` = to the end
+    pub last_page: Option<u32>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            dpi: DEFAULT_DPI,
+            first_page: None,
+            last_page: None,
+        }
+    }
+}
+
+/// One rasterized page
+#[derive(Debug, Clone)]
+pub struct RenderedPage {
+    /// 1-based, matching `PdfTextElement::page_number`
+    pub page_number: u32,
+    pub width: u32,
+    pub height: u32,
+    pub png: Vec<u8>,
+}
+
+impl RenderedPage {
+    /// Wrap PNG bytes, reading the pixel size from the IHDR chunk
+    pub fn from_png(page_number: u32, png: Vec<u8>) -> Result<Self> {
+        const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+        if png.len() < 24 || !png.starts_with(SIGNATURE) || &png[12..16] != b"IHDR" {
+            bail!("Page {} render is not a PNG image", page_number);
+        }
+        let width = u32::from_be_bytes(png[16..20].try_into()?);
+        let height = u32::from_be_bytes(png[20..24].try_into()?);
+        Ok(Self {
+            page_number,
+            width,
+            height,
+            png,
+        })
+    }
+
+    /// File name used when saving: `page-0001.png`
+    pub fn file_name(&self) -> String {
+        format!("page-{:04}.png", self.page_number)
+    }
+
+    /// The image as a `data:image/png;base64,...` URL, for pages carried
+    /// inside another file rather than beside it
+    pub fn data_url(&self) -> String {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+        format!("data:image/png;base64,{}", STANDARD.encode(&self.png))
+    }
+}
+
+/// Rasterizes pages of a document
+pub trait PageRenderer: Send + Sync {
+    /// Render the selected pages of a PDF, in page order
+    fn render_pages(&self, pdf_bytes: &[u8], options: &RenderOptions) -> Result<Vec<RenderedPage>>;
+
+    /// Renderer identifier for logging
+    fn name(&self) -> &str;
+}
+
+/// The renderer compiled into this build, if any
+pub fn default_renderer() -> Option<Box<dyn PageRenderer>> {
+    #[cfg(feature = "page-render")]
+    {
+        Some(Box::new(PopplerRenderer::new()))
+    }
+    #[cfg(not(feature = "page-render"))]
+    {
+        None
+    }
+}
+
+/// Write pages into `output_dir` as `page-NNNN.png`; returns the paths written
+pub fn save_pages(pages: &[RenderedPage], output_dir: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create {}", output_dir.display()))?;
+    pages
+        .iter()
+        .map(|page| {
+            let path = output_dir.join(page.file_name());
+            std::fs::write(&path, &page.png)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Renders through Poppler's `pdftoppm` (poppler-utils)
+#[cfg(feature = "page-render")]
+#[derive(Debug, Clone)]
+pub struct PopplerRenderer {
+    binary: PathBuf,
+}
+
+#[cfg(feature = "page-render")]
+impl Default for PopplerRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "page-render")]
+impl PopplerRenderer {
+    /// Use `pdftoppm` from `PATH`
+    pub fn new() -> Self {
+        Self::with_binary("pdftoppm")
+    }
+
+    /// Use a specific `pdftoppm` executable
+    pub fn with_binary(binary: impl Into<PathBuf>) -> Self {
+        Self {
+            binary: binary.into(),
+        }
+    }
+}
+
+#[cfg(feature = "page-render")]
+impl PageRenderer for PopplerRenderer {
+    fn render_pages(&self, pdf_bytes: &[u8], options: &RenderOptions) -> Result<Vec<RenderedPage>> {
+        use std::process::Command;
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH)?.subsec_nanos();
+        let work_dir = std::env::temp_dir().join(format!("blazegraph-render-{}-{}", std::process::id(), nanos));
+        std::fs::create_dir_all(&work_dir)?;
+        let input = work_dir.join("input.pdf");
+        std::fs::write(&input, pdf_bytes)?;
+
+        let mut command = Command::new(&self.binary);
+        command.arg("-png").arg("-r").arg(options.dpi.to_string());
+        if let Some(first) = options.first_page {
+            command.arg("-f").arg(first.to_string());
+        }
+        if let Some(last) = options.last_page {
+            command.arg("-l").arg(last.to_string());
+        }
+        command.arg(&input).arg(work_dir.join("page"));
+
+        let result = command.output();
+        let pages = result
+            .with_context(|| {
+                format!(
+                    "Failed to run {} (install poppler-utils to render pages)",
+                    self.binary.display()
+                )
+            })
+            .and_then(|output| {
+                if !output.status.success() {
+                    bail!(
+                        "pdftoppm failed: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    );
+                }
+                collect_pages(&work_dir)
+            });
+        std::fs::remove_dir_all(&work_dir).ok();
+        pages
+    }
+
+    fn name(&self) -> &str {
+        "PopplerRenderer"
+    }
+}
+
+/// Read `page-1.png` / `page-01.png` ... back in page order
+#[cfg(feature = "page-render")]
+fn collect_pages(dir: &Path) -> Result<Vec<RenderedPage>> {
+    let mut pages = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let page_number = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.strip_prefix("page-"))
+            .and_then(|number| number.parse::<u32>().ok());
+        if let (Some(page_number), Some("png")) = (page_number, path.extension().and_then(|e| e.to_str())) {
+            pages.push(RenderedPage::from_png(page_number, std::fs::read(&path)?)?);
+        }
+    }
+    pages.sort_by_key(|page| page.page_number);
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_png_size_and_saves_numbered_files() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend_from_slice(&1275u32.to_be_bytes());
+        png.extend_from_slice(&1650u32.to_be_bytes());
+        let page = RenderedPage::from_png(3, png).unwrap();
+        assert_eq!((page.width, page.height), (1275, 1650));
+        assert!(page.data_url().starts_with("data:image/png;base64,iVBORw0KGgo"));
+
+        let dir = std::env::temp_dir().join(format!("blazegraph-render-test-{}", std::process::id()));
+        let paths = save_pages(&[page], &dir).unwrap();
+        assert_eq!(paths, vec![dir.join("page-0003.png")]);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert!(RenderedPage::from_png(1, b"GIF89a".to_vec()).is_err());
+    }
+}
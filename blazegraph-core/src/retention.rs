@@ -0,0 +1,178 @@
+//! Retention policy for cached document content
+//!
+//! Every cache level holds text derived from the documents processed, so
+//! legal holds and erasure requests need control over how long it stays on
+//! disk. A policy is a small YAML file:
+//!
+//! ```yaml
+//! max_age:
+//!   extraction: 7d   # Level 1: XHTML + TextElements
+//!   parsed: 30d      # Level 2: ParsedElements
+//!   graph: 90d       # Level 3: graphs
+//! no_store_tags: ["sensitive"]
+//! ```
+//!
+//! `FileStorage::with_retention` enforces ages on every read (an expired
+//! entry is deleted and counts as a miss) and `FileStorage::purge` deletes
+//! everything expired in one sweep. Documents carrying a `no_store_tags` tag
+//! are processed without touching the cache at all.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+/// How long each cache level keeps entries; unset levels are kept forever
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetentionAges {
+    /// Stored source documents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub documents: Option<String>,
+    /// Level 1: extracted XHTML and TextElements
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extraction: Option<String>,
+    /// Level 2: post-rules ParsedElements
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parsed: Option<String>,
+    /// Level 3: final graphs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graph: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Maximum entry age per level, as "90d", "12h", "30m" or "45s"
+    #[serde(default)]
+    pub max_age: RetentionAges,
+    /// Documents tagged with any of these are never written to the cache
+    #[serde(default)]
+    pub no_store_tags: Vec<String>,
+}
+
+/// Cache level an entry belongs to, named by its cache subdirectory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheArea {
+    Documents,
+    Extraction,
+    Parsed,
+    Graph,
+}
+
+impl CacheArea {
+    pub const ALL: [CacheArea; 4] = [
+        CacheArea::Documents,
+        CacheArea::Extraction,
+        CacheArea::Parsed,
+        CacheArea::Graph,
+    ];
+
+    /// Subdirectories of the cache holding this level's entries
+    pub fn dirs(self) -> &'static [&'static str] {
+        match self {
+            CacheArea::Documents => &["pdfs"],
+            CacheArea::Extraction => &["tika", "preprocessor"],
+            CacheArea::Parsed => &["parsed"],
+            CacheArea::Graph => &["graph"],
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CacheArea::Documents => "documents",
+            CacheArea::Extraction => "extraction",
+            CacheArea::Parsed => "parsed",
+            CacheArea::Graph => "graph",
+        }
+    }
+}
+
+impl RetentionPolicy {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let yaml = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read retention policy {}", path.display()))?;
+        let policy: Self = serde_yaml::from_str(&yaml)
+            .with_context(|| format!("Invalid retention policy {}", path.display()))?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    /// Check every age parses, so a typo fails at startup rather than on first read
+    pub fn validate(&self) -> Result<()> {
+        for area in CacheArea::ALL {
+            self.max_age(area)?;
+        }
+        Ok(())
+    }
+
+    /// Maximum age for entries of `area`, or None to keep them
+    pub fn max_age(&self, area: CacheArea) -> Result<Option<Duration>> {
+        let age = match area {
+            CacheArea::Documents => &self.max_age.documents,
+            CacheArea::Extraction => &self.max_age.extraction,
+            CacheArea::Parsed => &self.max_age.parsed,
+            CacheArea::Graph => &self.max_age.graph,
+        };
+        age.as_deref()
+            .map(parse_age)
+            .transpose()
+            .with_context(|| format!("Invalid max_age.{}", area.name()))
+    }
+
+    /// Whether a document with these tags may be cached
+    pub fn allows_storing(&self, tags: &[String]) -> bool {
+        !tags.iter().any(|tag| self.no_store_tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    }
+}
+
+/// Parse an age such as "90d", "12h", "30m" or "45s"
+pub fn parse_age(age: &str) -> Result<Duration> {
+    let age = age.trim();
+    let split = age.find(|c: char| !c.is_ascii_digit()).unwrap_or(age.len());
+    let (number, unit) = age.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| anyhow!("'{}' is not an age like 90d, 12h, 30m or 45s", age))?;
+    let seconds = match unit.trim() {
+        "d" => 86_400,
+        "h" => 3_600,
+        "m" => 60,
+        "s" => 1,
+        other => bail!("Unknown age unit '{}' in '{}' (use d, h, m or s)", other, age),
+    };
+    Ok(Duration::from_secs(number * seconds))
+}
+
+/// Entries removed by a purge, per level
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PurgeReport {
+    pub removed: Vec<(CacheArea, usize)>,
+    pub bytes: u64,
+}
+
+impl PurgeReport {
+    pub fn total(&self) -> usize {
+        self.removed.iter().map(|(_, count)| count).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_policy_ages_and_tags() {
+        let policy: RetentionPolicy = serde_yaml::from_str(
+            "max_age:\n  extraction: 7d\n  graph: 90d\nno_store_tags: [sensitive]\n",
+        )
+        .unwrap();
+        assert_eq!(policy.max_age(CacheArea::Extraction).unwrap(), Some(Duration::from_secs(7 * 86_400)));
+        assert_eq!(policy.max_age(CacheArea::Parsed).unwrap(), None);
+        assert!(!policy.allows_storing(&["Sensitive".to_string()]));
+        assert!(policy.allows_storing(&["public".to_string()]));
+
+        assert_eq!(parse_age("30m").unwrap(), Duration::from_secs(1800));
+        assert!(parse_age("7 weeks").is_err());
+        assert!(parse_age("d").is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use super::engine::ParseRule;
+use crate::config::{BoilerplateAction, BoilerplateConfig};
+use crate::types::{ParsedElementType, ParsedPdfElement};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+/// Repeated boilerplate suppression
+///
+/// Confidentiality notices, watermark text and legends repeated on every
+/// page of a contract otherwise end up as dozens of identical nodes that
+/// dominate embeddings. A block is boilerplate when the same text (ignoring
+/// case and whitespace) appears on at least `min_pages` pages, anywhere on
+/// the page. With `action: collapse` the first occurrence is kept and the
+/// repeats dropped; `action: drop` removes every occurrence.
+///
+/// Blocks shorter than `min_chars` are never boilerplate, and `Header` /
+/// `Footer` elements are left to `HeaderFooterDetection`. Place after
+/// `SpatialClustering`, so multi-line notices compare as one block.
+pub struct BoilerplateRule<'a> {
+    config: &'a BoilerplateConfig,
+}
+
+impl<'a> BoilerplateRule<'a> {
+    pub fn new(config: &'a BoilerplateConfig) -> Self {
+        Self { config }
+    }
+
+    /// Comparison key for an element, or None when it can't be boilerplate
+    fn signature(&self, element: &ParsedPdfElement) -> Option<String> {
+        if matches!(element.element_type, ParsedElementType::Header | ParsedElementType::Footer) {
+            return None;
+        }
+        let signature = element.text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        (signature.chars().count() >= self.config.min_chars).then_some(signature)
+    }
+}
+
+impl ParseRule for BoilerplateRule<'_> {
+    fn apply(&self, elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+        if !self.config.enabled || elements.is_empty() {
+            return Ok(elements);
+        }
+
+        println!("🧹 APPLYING BOILERPLATE SUPPRESSION...");
+        let signatures: Vec<Option<String>> = elements.iter().map(|e| self.signature(e)).collect();
+        let mut pages: HashMap<&str, HashSet<u32>> = HashMap::new();
+        for (element, signature) in elements.iter().zip(&signatures) {
+            if let Some(signature) = signature {
+                pages.entry(signature).or_default().insert(element.page_number);
+            }
+        }
+
+        let mut kept: HashSet<&str> = HashSet::new();
+        let mut dropped = 0;
+        let mut result = Vec::with_capacity(elements.len());
+        for (element, signature) in elements.into_iter().zip(&signatures) {
+            let boilerplate = signature
+                .as_deref()
+                .filter(|signature| pages[signature].len() >= self.config.min_pages);
+            let keep = match boilerplate {
+                None => true,
+                Some(signature) => self.config.action == BoilerplateAction::Collapse && kept.insert(signature),
+            };
+            if keep {
+                result.push(element);
+            } else {
+                dropped += 1;
+            }
+        }
+
+        let blocks = pages.values().filter(|p| p.len() >= self.config.min_pages).count();
+        println!("   ✅ Dropped {} repeats of {} boilerplate blocks", dropped, blocks);
+        Ok(result)
+    }
+
+    fn name(&self) -> &str {
+        "BoilerplateSuppression"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{bbox, ParsedElementBuilder};
+
+    fn block(text: &str, page: u32) -> ParsedPdfElement {
+        ParsedElementBuilder::new(text).level(1).bbox(bbox(72.0, 400.0, 400.0, 24.0)).page(page).build()
+    }
+
+    #[test]
+    fn repeated_notices_collapse_to_the_first() {
+        let notice = "CONFIDENTIAL - Subject to the NDA dated 1 March 2024.";
+        let mut elements = Vec::new();
+        for page in 1..=4 {
+            elements.push(block(&format!("Clause {} sets out the terms.", page), page));
+            elements.push(block(if page == 2 { "Confidential -  subject to the NDA dated 1 March 2024." } else { notice }, page));
+        }
+        // Short repeated text is not boilerplate
+        for page in 1..=4 {
+            elements.push(block("Signed:", page));
+        }
+
+        let config = BoilerplateConfig::default();
+        let result = BoilerplateRule::new(&config).apply(elements.clone()).unwrap();
+        assert_eq!(result.len(), 9);
+        assert_eq!(result.iter().filter(|e| e.text == notice).count(), 1);
+        assert_eq!(result[1].text, notice, "the first occurrence stays in place");
+
+        let config = BoilerplateConfig {
+            action: BoilerplateAction::Drop,
+            ..BoilerplateConfig::default()
+        };
+        let result = BoilerplateRule::new(&config).apply(elements).unwrap();
+        assert_eq!(result.len(), 8);
+        assert!(result.iter().all(|e| !e.text.to_lowercase().starts_with("confidential")));
+    }
+}
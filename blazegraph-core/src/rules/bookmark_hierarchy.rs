@@ -0,0 +1,231 @@
+use super::engine::ParseRule;
+use crate::config::{BookmarkHierarchyConfig, ParsingConfig, SectionAndHierarchyConfig};
+use crate::types::{BoundingBox, ParsedElementType, ParsedPdfElement};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Bookmark-driven hierarchy
+///
+/// A PDF outline is the author's own table of contents, so when a document
+/// has one it beats font heuristics. Each bookmark claims one run of
+/// consecutive elements matched to it (`bookmark_match`, at least
+/// `min_confidence`; the most confident run wins, and ties go to the later
+/// run, since a printed table of contents precedes the headings it lists).
+/// The run becomes a single `Section`
+/// at the bookmark's nesting depth, whatever `SectionDetection` made of it.
+///
+/// Font-detected sections matching no bookmark nest below the enclosing
+/// bookmark, or become paragraphs with `demote_unmatched`. Content is
+/// re-levelled under its section. Elements before the first bookmark are
+/// left alone. Place after `SectionDetection` and before
+/// `SpatialClustering`.
+pub struct BookmarkHierarchyRule<'a> {
+    config: &'a BookmarkHierarchyConfig,
+    hierarchy: &'a SectionAndHierarchyConfig,
+}
+
+impl<'a> BookmarkHierarchyRule<'a> {
+    pub fn new(config: &'a ParsingConfig) -> Self {
+        Self {
+            config: &config.bookmark_hierarchy,
+            hierarchy: &config.section_and_hierarchy,
+        }
+    }
+
+    /// The bookmark's order, when the element is a confident match
+    fn bookmark(&self, element: &ParsedPdfElement) -> Option<u32> {
+        element
+            .bookmark_match
+            .as_ref()
+            .filter(|b| b.confidence.unwrap_or(1.0) >= self.config.min_confidence)
+            .map(|b| b.order)
+    }
+
+    /// The element run each bookmark claims, keyed by run start
+    fn claimed_runs(&self, elements: &[ParsedPdfElement]) -> HashMap<usize, Range<usize>> {
+        // Best run per bookmark order: (confidence, run)
+        let mut best: HashMap<u32, (f32, Range<usize>)> = HashMap::new();
+        let mut start = 0;
+        while start < elements.len() {
+            let Some(order) = self.bookmark(&elements[start]) else {
+                start += 1;
+                continue;
+            };
+            let mut end = start + 1;
+            while end < elements.len() && self.bookmark(&elements[end]) == Some(order) {
+                end += 1;
+            }
+            let confidence = elements[start..end]
+                .iter()
+                .filter_map(|e| e.bookmark_match.as_ref()?.confidence)
+                .fold(1.0f32, f32::min);
+            match best.get(&order) {
+                Some((existing, _)) if *existing > confidence => {}
+                _ => {
+                    best.insert(order, (confidence, start..end));
+                }
+            }
+            start = end;
+        }
+        best.into_values().map(|(_, run)| (run.start, run)).collect()
+    }
+
+    /// Tree level for a bookmark nesting depth (1 = top of the outline)
+    fn level_for_depth(&self, depth: u32) -> u32 {
+        self.clamp((self.hierarchy.starting_section_level + depth.max(1) - 1).max(1))
+    }
+
+    fn clamp(&self, level: u32) -> u32 {
+        if self.hierarchy.enforce_max_depth {
+            level.min(self.hierarchy.max_depth.max(1))
+        } else {
+            level
+        }
+    }
+}
+
+impl<'a> ParseRule for BookmarkHierarchyRule<'a> {
+    fn apply(&self, elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+        if !self.config.enabled {
+            return Ok(elements);
+        }
+
+        let runs = self.claimed_runs(&elements);
+        if runs.is_empty() {
+            println!("   ⏭️  No bookmark matches, keeping detected hierarchy");
+            return Ok(elements);
+        }
+        println!("📑 APPLYING BOOKMARK HIERARCHY ({} bookmarked sections)...", runs.len());
+
+        let mut result = Vec::with_capacity(elements.len());
+        // Level of the current section, and of the bookmark enclosing it
+        let mut current: Option<(u32, u32)> = None;
+        let mut skip_until = 0;
+        let mut demoted = 0;
+
+        for (index, mut element) in elements.into_iter().enumerate() {
+            if index < skip_until {
+                // Later piece of a bookmarked heading, merged into its section
+                let section: &mut ParsedPdfElement = result.last_mut().expect("run start precedes its pieces");
+                section.text.push(' ');
+                section.text.push_str(element.text.trim());
+                section.bounding_box = union(&section.bounding_box, &element.bounding_box);
+                section.token_count += element.token_count;
+                continue;
+            }
+
+            if let Some(run) = runs.get(&index) {
+                let depth = element.bookmark_match.as_ref().map_or(1, |b| b.level);
+                let level = self.level_for_depth(depth);
+                element.element_type = ParsedElementType::Section;
+                element.hierarchy_level = level;
+                element.text = element.text.trim().to_string();
+                current = Some((level, level));
+                skip_until = run.end;
+            } else if let Some((section_level, bookmark_level)) = current {
+                if element.element_type == ParsedElementType::Section {
+                    if self.config.demote_unmatched {
+                        element.element_type = ParsedElementType::Paragraph;
+                        element.hierarchy_level = section_level + 1;
+                        demoted += 1;
+                    } else {
+                        let level = self.clamp(element.hierarchy_level.max(bookmark_level + 1));
+                        element.hierarchy_level = level;
+                        current = Some((level, bookmark_level));
+                    }
+                } else {
+                    element.hierarchy_level = section_level + 1;
+                }
+            }
+            result.push(element);
+        }
+
+        if demoted > 0 {
+            println!("   📉 Demoted {demoted} sections that match no bookmark");
+        }
+        Ok(result)
+    }
+
+    fn name(&self) -> &str {
+        "BookmarkHierarchy"
+    }
+}
+
+fn union(a: &BoundingBox, b: &BoundingBox) -> BoundingBox {
+    let (x0, y0) = (a.x.min(b.x), a.y.min(b.y));
+    BoundingBox {
+        x: x0,
+        y: y0,
+        width: (a.x + a.width).max(b.x + b.width) - x0,
+        height: (a.y + a.height).max(b.y + b.height) - y0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{bbox, ParsedElementBuilder};
+    use crate::types::BookmarkSection;
+
+    fn element(element_type: ParsedElementType, text: &str, level: u32, bookmark: Option<(u32, u32, f32)>) -> ParsedPdfElement {
+        let builder = ParsedElementBuilder::new(text).element_type(element_type).level(level).bbox(bbox(72.0, 100.0, 300.0, 12.0));
+        match bookmark {
+            Some((order, level, confidence)) => builder.bookmark(BookmarkSection {
+                title: text.to_string(),
+                order,
+                level,
+                parent: None,
+                page: None,
+                confidence: Some(confidence),
+            }),
+            None => builder,
+        }
+        .build()
+    }
+
+    #[test]
+    fn bookmarks_override_font_sections() {
+        use ParsedElementType::{Paragraph, Section};
+        let elements = vec![
+            // Printed table of contents
+            element(Paragraph, "1 Introduction", 2, Some((0, 1, 0.85))),
+            element(Paragraph, "1.1 Scope of the study", 2, Some((1, 2, 0.85))),
+            element(Paragraph, "Some text.", 2, None),
+            element(Section, "1 Introduction", 1, Some((0, 1, 1.0))),
+            element(Paragraph, "Some text.", 2, None),
+            element(Paragraph, "1.1 Scope of", 2, Some((1, 2, 0.85))),
+            element(Paragraph, "the study", 2, Some((1, 2, 0.85))),
+            element(Paragraph, "Details.", 2, None),
+            element(Section, "Bold run-in", 1, None),
+            element(Paragraph, "More details.", 2, None),
+        ];
+
+        let config = ParsingConfig::default();
+        let result = BookmarkHierarchyRule::new(&config).apply(elements.clone()).unwrap();
+        let summary: Vec<(&str, bool, u32)> = result
+            .iter()
+            .map(|e| (e.text.as_str(), e.element_type == Section, e.hierarchy_level))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("1 Introduction", false, 2),
+                ("1.1 Scope of the study", false, 2),
+                ("Some text.", false, 2),
+                ("1 Introduction", true, 1),
+                ("Some text.", false, 2),
+                ("1.1 Scope of the study", true, 2),
+                ("Details.", false, 3),
+                ("Bold run-in", true, 3),
+                ("More details.", false, 4),
+            ]
+        );
+
+        let mut config = ParsingConfig::default();
+        config.bookmark_hierarchy.demote_unmatched = true;
+        let result = BookmarkHierarchyRule::new(&config).apply(elements).unwrap();
+        assert_eq!(result[7].element_type, Paragraph);
+        assert_eq!(result[7].hierarchy_level, 3);
+    }
+}
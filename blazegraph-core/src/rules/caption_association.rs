@@ -0,0 +1,262 @@
+use super::engine::ParseRule;
+use crate::config::CaptionAssociationConfig;
+use crate::types::{BoundingBox, ParsedElementType, ParsedPdfElement};
+use anyhow::Result;
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+
+/// Caption association
+///
+/// A paragraph opening with a label and a number ("Figure 3:", "Fig. 2.",
+/// "Table 2.") is a caption. Its lines (elements sharing its paragraph) are
+/// attached to the nearest uncaptioned `Figure` (figure labels) or `Table`
+/// (table labels) on the same page that overlaps it horizontally and lies
+/// within `max_distance` above or below it. Attached captions leave the
+/// element stream; the rest become `Caption` elements when
+/// `standalone_captions` is set. Running before `SectionDetection` keeps
+/// bold caption labels from turning into headings.
+pub struct CaptionAssociationRule<'a> {
+    config: &'a CaptionAssociationConfig,
+    figure_pattern: Option<Regex>,
+    table_pattern: Option<Regex>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CaptionKind {
+    Figure,
+    Table,
+}
+
+/// A detected caption: its elements in reading order
+struct Caption {
+    kind: CaptionKind,
+    members: Vec<usize>,
+    text: String,
+    bounding_box: BoundingBox,
+}
+
+impl<'a> CaptionAssociationRule<'a> {
+    pub fn new(config: &'a CaptionAssociationConfig) -> Self {
+        Self {
+            config,
+            figure_pattern: label_pattern(&config.figure_labels),
+            table_pattern: label_pattern(&config.table_labels),
+        }
+    }
+
+    fn kind(&self, text: &str) -> Option<CaptionKind> {
+        let text = text.trim_start();
+        if self.figure_pattern.as_ref().is_some_and(|p| p.is_match(text)) {
+            Some(CaptionKind::Figure)
+        } else if self.table_pattern.as_ref().is_some_and(|p| p.is_match(text)) {
+            Some(CaptionKind::Table)
+        } else {
+            None
+        }
+    }
+
+    fn find_captions(&self, elements: &[ParsedPdfElement]) -> Vec<Caption> {
+        let mut paragraphs: BTreeMap<(u32, u32), Vec<usize>> = BTreeMap::new();
+        for (index, element) in elements.iter().enumerate() {
+            if element.element_type == ParsedElementType::Paragraph {
+                paragraphs
+                    .entry((element.page_number, element.paragraph_number))
+                    .or_default()
+                    .push(index);
+            }
+        }
+
+        let mut captions = Vec::new();
+        for mut members in paragraphs.into_values() {
+            members.sort_by_key(|&index| elements[index].reading_order);
+            let Some(kind) = self.kind(&elements[members[0]].text) else {
+                continue;
+            };
+            let text = members
+                .iter()
+                .map(|&index| elements[index].text.trim())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let bounding_box = union(members.iter().map(|&index| &elements[index].bounding_box));
+            captions.push(Caption {
+                kind,
+                members,
+                text,
+                bounding_box,
+            });
+        }
+        captions
+    }
+
+    /// Nearest free figure or table for a caption
+    fn target(&self, caption: &Caption, elements: &[ParsedPdfElement], claimed: &HashSet<usize>) -> Option<usize> {
+        let page = elements[caption.members[0]].page_number;
+        elements
+            .iter()
+            .enumerate()
+            .filter(|(index, element)| {
+                element.page_number == page
+                    && !claimed.contains(index)
+                    && match (&element.element_type, caption.kind) {
+                        (ParsedElementType::Figure { caption }, CaptionKind::Figure) => caption.is_none(),
+                        (ParsedElementType::Table(table), CaptionKind::Table) => table.caption.is_none(),
+                        _ => false,
+                    }
+                    && horizontal_overlap(&element.bounding_box, &caption.bounding_box) > 0.0
+            })
+            .map(|(index, element)| (index, vertical_gap(&element.bounding_box, &caption.bounding_box)))
+            .filter(|(_, gap)| *gap <= self.config.max_distance)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| index)
+    }
+}
+
+impl<'a> ParseRule for CaptionAssociationRule<'a> {
+    fn apply(&self, mut elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+        if !self.config.enabled {
+            return Ok(elements);
+        }
+
+        println!("🏷️  APPLYING CAPTION ASSOCIATION...");
+        let captions = self.find_captions(&elements);
+        let mut claimed = HashSet::new();
+        let mut removed = HashSet::new();
+        let mut standalone = 0;
+        for caption in &captions {
+            match self.target(caption, &elements, &claimed) {
+                Some(index) => {
+                    claimed.insert(index);
+                    removed.extend(caption.members.iter().copied());
+                    let tokens: usize = caption.members.iter().map(|&m| elements[m].token_count).sum();
+                    let target = &mut elements[index];
+                    match &mut target.element_type {
+                        ParsedElementType::Figure { caption: slot } => {
+                            *slot = Some(caption.text.clone());
+                            target.text = caption.text.clone();
+                            target.token_count = tokens;
+                        }
+                        ParsedElementType::Table(table) => {
+                            table.caption = Some(caption.text.clone());
+                            target.token_count += tokens;
+                        }
+                        _ => {}
+                    }
+                }
+                None if self.config.standalone_captions => {
+                    standalone += 1;
+                    for &member in &caption.members {
+                        elements[member].element_type = ParsedElementType::Caption;
+                    }
+                }
+                None => {}
+            }
+        }
+
+        println!(
+            "   ✅ Attached {} caption(s) to figures and tables, {} standalone",
+            claimed.len(),
+            standalone
+        );
+        Ok(elements
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !removed.contains(index))
+            .map(|(_, element)| element)
+            .collect())
+    }
+
+    fn name(&self) -> &str {
+        "CaptionAssociation"
+    }
+}
+
+/// "<label> <number><separator>", e.g. "Figure 3:", "Fig. 2.", "Table IV -"
+fn label_pattern(labels: &[String]) -> Option<Regex> {
+    if labels.is_empty() {
+        return None;
+    }
+    let labels = labels.iter().map(|label| regex::escape(label.trim())).collect::<Vec<_>>().join("|");
+    Regex::new(&format!(
+        r"(?i)^(?:{})\s*(?:\d+(?:[.\-–]\d+)*[a-z]?|[ivxlc]+|[a-z])\s*(?:[:.|\-–—]|$)",
+        labels
+    ))
+    .ok()
+}
+
+fn horizontal_overlap(a: &BoundingBox, b: &BoundingBox) -> f32 {
+    (a.x + a.width).min(b.x + b.width) - a.x.max(b.x)
+}
+
+/// Blank space between two boxes stacked vertically (0 when they overlap)
+fn vertical_gap(a: &BoundingBox, b: &BoundingBox) -> f32 {
+    (a.y.max(b.y) - (a.y + a.height).min(b.y + b.height)).max(0.0)
+}
+
+fn union<'b>(boxes: impl Iterator<Item = &'b BoundingBox>) -> BoundingBox {
+    let (mut x0, mut y0, mut x1, mut y1) = (f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for bbox in boxes {
+        x0 = x0.min(bbox.x);
+        y0 = y0.min(bbox.y);
+        x1 = x1.max(bbox.x + bbox.width);
+        y1 = y1.max(bbox.y + bbox.height);
+    }
+    BoundingBox {
+        x: x0,
+        y: y0,
+        width: x1 - x0,
+        height: y1 - y0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{bbox, ParsedElementBuilder};
+    use crate::types::TableData;
+
+    fn element(element_type: ParsedElementType, text: &str, y: f32, height: f32, paragraph: u32) -> ParsedPdfElement {
+        ParsedElementBuilder::new(text)
+            .element_type(element_type)
+            .level(1)
+            .bbox(bbox(72.0, y, 300.0, height))
+            .paragraph(paragraph)
+            .order(paragraph)
+            .build()
+    }
+
+    #[test]
+    fn attaches_captions_to_the_nearest_figure_or_table() {
+        let table = ParsedElementType::Table(TableData {
+            rows: vec![vec!["Region".into(), "Revenue".into()]],
+            header_rows: 1,
+            caption: None,
+        });
+        let elements = vec![
+            element(ParsedElementType::Figure { caption: None }, "Bar chart", 100.0, 200.0, 0),
+            element(ParsedElementType::Paragraph, "Figure 1: Revenue", 305.0, 10.0, 1),
+            element(ParsedElementType::Paragraph, "by region.", 317.0, 10.0, 1),
+            element(ParsedElementType::Paragraph, "Table 2 shows costs in detail.", 340.0, 10.0, 2),
+            element(ParsedElementType::Paragraph, "Table 2. Costs", 400.0, 10.0, 3),
+            element(table, "Region | Revenue", 415.0, 40.0, 4),
+            element(ParsedElementType::Paragraph, "Fig. 3 - Not extracted", 600.0, 10.0, 5),
+        ];
+
+        let config = CaptionAssociationConfig::default();
+        let result = CaptionAssociationRule::new(&config).apply(elements).unwrap();
+        let summary: Vec<&str> = result.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(
+            summary,
+            ["Figure 1: Revenue by region.", "Table 2 shows costs in detail.", "Region | Revenue", "Fig. 3 - Not extracted"]
+        );
+        assert_eq!(
+            result[0].element_type,
+            ParsedElementType::Figure { caption: Some("Figure 1: Revenue by region.".into()) }
+        );
+        let ParsedElementType::Table(table) = &result[2].element_type else {
+            panic!("expected a table");
+        };
+        assert_eq!(table.caption.as_deref(), Some("Table 2. Costs"));
+        assert_eq!(result[1].element_type, ParsedElementType::Paragraph);
+        assert_eq!(result[3].element_type, ParsedElementType::Caption);
+    }
+}
@@ -1,17 +1,32 @@
-use crate::config::{ConfigManager, ParsingConfig};
+use crate::config::{rule_config_section, ConfigManager, InlineStyleMode, ParsingConfig, RuleConfig};
+use crate::tokenizer::{built_in_tokenizer, Tokenizer, BUILT_IN_TOKENIZERS};
 use crate::types::*;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
 
 // Import rule types (only active rules)
+use super::boilerplate::BoilerplateRule;
+use super::explain::{ExplainReport, RuleExplanation};
+use super::inline_styles::apply_inline_styles;
+use super::provenance::{seed_provenance, trace_provenance};
+use super::bookmark_hierarchy::BookmarkHierarchyRule;
+use super::caption_association::CaptionAssociationRule;
+use super::key_value_detection::KeyValueDetectionRule;
+use super::list_detection::ListDetectionRule;
+use super::header_footer_detection::HeaderFooterDetectionRule;
+use super::quote_detection::QuoteDetectionRule;
 use super::section_detection::SectionAndHierarchyDetectionRule;
+use super::size_enforcer::SizeEnforcerRule;
+use super::sentence_segmentation::SentenceSegmentationRule;
 use super::spatial_clustering::SpatialClusteringRule;
+use super::table_detection::TableDetectionRule;
 use super::validation::ValidationRule;
 
 // Disabled rules (will be rewritten):
-// use super::list_detection::ListDetectionRule;
 // use super::pattern_detection::PatternBasedSectionDetectionRule;
-// use super::size_enforcer::SizeEnforcerRule;
 
 // Debug configuration for pipeline tracing
 #[derive(Debug, Clone)]
@@ -86,48 +101,194 @@ pub fn debug_pipeline_elements(
     }
 }
 
+/// The built-in configs, loaded on first use
+fn built_in_configs() -> Result<&'static ConfigManager> {
+    if let Some(configs) = BUILT_IN_CONFIGS.get() {
+        return Ok(configs);
+    }
+    let configs = ConfigManager::new()?;
+    Ok(BUILT_IN_CONFIGS.get_or_init(|| configs))
+}
+
+/// What one rule pipeline run produced
+///
+/// Timings and warnings belong to the run rather than the engine, so one
+/// engine can serve concurrent runs (`RuleEngine` is `Send + Sync`).
+#[derive(Debug, Default)]
+pub struct RuleRun {
+    pub elements: Vec<ParsedPdfElement>,
+    /// Time spent in each rule, in pipeline order
+    pub rule_timings: Vec<(String, std::time::Duration)>,
+    /// Warnings raised by the rules
+    pub warnings: Vec<ProcessingWarning>,
+    /// Report of the last Validation rule in the pipeline, if it ran
+    pub validation: Option<ValidationReport>,
+}
+
+/// Rule names `apply_rule_by_name` handles itself
+pub const BUILT_IN_RULES: &[&str] = &[
+    "SpatialClustering",
+    "Validation",
+    "SectionDetection",
+    "BookmarkHierarchy",
+    "PatternBasedSectionDetection",
+    "ListDetection",
+    "KeyValueDetection",
+    "HeaderFooterDetection",
+    "BoilerplateSuppression",
+    "TableDetection",
+    "CaptionAssociation",
+    "QuoteDetection",
+    "SizeEnforcer",
+    "SentenceSegmentation",
+];
+
+/// What a custom rule can read when it is built for a run
+pub struct RuleContext<'a> {
+    pub config: &'a ParsingConfig,
+    /// The pipeline entry's `params`, if any
+    pub params: Option<&'a serde_yaml::Value>,
+    /// The spans the pipeline started from
+    pub text_elements: &'a [PdfTextElement],
+    pub document_analysis: &'a DocumentAnalysis,
+    pub font_size_analysis: &'a FontSizeAnalysis,
+    pub style_data: &'a StyleData,
+}
+
+/// Builds a custom rule each time a pipeline reaches its name
+///
+/// Closures taking a `&RuleContext` and returning a boxed rule implement
+/// this, so `engine.register_rule("Redact", |_: &RuleContext| Ok(Box::new(RedactRule) as _))`
+/// is enough for rules that own their settings.
+pub trait ParseRuleFactory: Send + Sync {
+    fn create<'a>(&self, context: &RuleContext<'a>) -> Result<Box<dyn ParseRule + 'a>>;
+}
+
+impl<F> ParseRuleFactory for F
+where
+    F: Fn(&RuleContext) -> Result<Box<dyn ParseRule>> + Send + Sync,
+{
+    fn create<'a>(&self, context: &RuleContext<'a>) -> Result<Box<dyn ParseRule + 'a>> {
+        self(context).map(|rule| rule as Box<dyn ParseRule + 'a>)
+    }
+}
+
+/// Built-in configs for the deprecated `RuleEngine::apply_rules`, built once
+static BUILT_IN_CONFIGS: OnceLock<ConfigManager> = OnceLock::new();
+
+/// Runs rule pipelines. Every run is given its config, so one engine can be
+/// shared across threads; the settings kept for the deprecated
+/// `apply_rules` are the only configuration an engine holds.
+///
+/// Besides the built-in rules, a pipeline can name rules registered with
+/// `register_rule`. Cached rule outputs are keyed by the config, not a
+/// custom rule's code: rename the rule (or skip the rules cache) when its
+/// behavior changes. Tokenizers registered with `register_tokenizer` can
+/// likewise be named by the config's `tokenizer`.
+#[derive(Default)]
 pub struct RuleEngine {
-    config_manager: ConfigManager,
-    debug_config: DebugConfig,
+    custom_rules: HashMap<String, Box<dyn ParseRuleFactory>>,
+    custom_tokenizers: HashMap<String, Arc<dyn Tokenizer>>,
+    /// Configs loaded with the deprecated `load_custom_config`
+    custom_configs: Option<ConfigManager>,
     minimal_parse_override: Option<bool>,
-    pub rule_timings: std::cell::RefCell<Vec<(String, std::time::Duration)>>,
 }
 
-impl RuleEngine {
-    pub fn new() -> Result<Self> {
-        let config_manager = ConfigManager::new()?;
-
-        Ok(Self {
-            config_manager,
-            debug_config: DebugConfig::disabled(),
-            minimal_parse_override: None,
-            rule_timings: std::cell::RefCell::new(Vec::new()),
-        })
+impl fmt::Debug for RuleEngine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&String> = self.custom_rules.keys().collect();
+        names.sort();
+        let mut tokenizers: Vec<&String> = self.custom_tokenizers.keys().collect();
+        tokenizers.sort();
+        f.debug_struct("RuleEngine")
+            .field("custom_rules", &names)
+            .field("custom_tokenizers", &tokenizers)
+            .field("custom_configs", &self.custom_configs.is_some())
+            .field("minimal_parse_override", &self.minimal_parse_override)
+            .finish()
     }
+}
 
-    pub fn set_debug_config(&mut self, debug_config: DebugConfig) {
-        self.debug_config = debug_config;
+impl RuleEngine {
+    pub fn new() -> Result<Self> {
+        Ok(Self::default())
     }
 
+    #[deprecated(note = "set the config's `minimal_parse`, or pass it to `DocumentProcessor::process_document_with_options`")]
     pub fn set_minimal_parse_override(&mut self, minimal_parse: bool) {
         self.minimal_parse_override = Some(minimal_parse);
     }
 
+    #[deprecated(note = "load the config with `ParsingConfig::load_from_file` and call `apply_rules_with_config`")]
     pub fn load_custom_config(&mut self, config_path: &str) -> Result<()> {
         println!("📁 Loading custom config from: {config_path}");
-        self.config_manager.load_config_from_file(config_path)?;
+        let mut configs = match self.custom_configs.take() {
+            Some(configs) => configs,
+            None => built_in_configs()?.clone(),
+        };
+        configs.load_config_from_file(config_path)?;
+        self.custom_configs = Some(configs);
         println!("✅ Custom config loaded successfully");
         Ok(())
     }
 
     /// Get the current configuration for cache key generation (requires document type)
-    pub fn get_config_for_cache(
-        &self,
-        doc_type: &crate::types::DocumentType,
-    ) -> &crate::config::ParsingConfig {
-        self.config_manager.get_config(doc_type)
+    #[deprecated(note = "use `DocumentProcessor::config_for`")]
+    pub fn get_config_for_cache(&self, doc_type: &DocumentType) -> &ParsingConfig {
+        match &self.custom_configs {
+            Some(configs) => configs.get_config(doc_type),
+            None => built_in_configs()
+                .expect("built-in configs always load")
+                .get_config(doc_type),
+        }
     }
 
+    /// Make `name` usable in `PipelineConfig` rule lists. Built-in names
+    /// can't be replaced, and each name is registered once
+    pub fn register_rule(&mut self, name: impl Into<String>, factory: impl ParseRuleFactory + 'static) -> Result<()> {
+        let name = name.into();
+        if BUILT_IN_RULES.contains(&name.as_str()) {
+            bail!("'{}' is a built-in rule and can't be replaced", name);
+        }
+        if self.custom_rules.contains_key(&name) {
+            bail!("A rule named '{}' is already registered", name);
+        }
+        self.custom_rules.insert(name, Box::new(factory));
+        Ok(())
+    }
+
+    /// Make `name` usable as the config's `tokenizer`. Built-in names can't
+    /// be replaced, and each name is registered once
+    pub fn register_tokenizer(&mut self, name: impl Into<String>, tokenizer: impl Tokenizer + 'static) -> Result<()> {
+        let name = name.into();
+        if BUILT_IN_TOKENIZERS.contains(&name.as_str()) {
+            bail!("'{}' is a built-in tokenizer and can't be replaced", name);
+        }
+        if self.custom_tokenizers.contains_key(&name) {
+            bail!("A tokenizer named '{}' is already registered", name);
+        }
+        self.custom_tokenizers.insert(name, Arc::new(tokenizer));
+        Ok(())
+    }
+
+    /// The tokenizer called `name`: registered, or built in
+    pub fn tokenizer(&self, name: &str) -> Result<Arc<dyn Tokenizer>> {
+        match self.custom_tokenizers.get(name) {
+            Some(tokenizer) => Ok(Arc::clone(tokenizer)),
+            None => built_in_tokenizer(name),
+        }
+    }
+
+    /// Names of the registered custom rules, sorted
+    pub fn custom_rule_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.custom_rules.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Run the pipeline of the built-in config for the document's type,
+    /// tracing elements matching `debug_config` after each rule
+    #[deprecated(note = "resolve a config (e.g. `DocumentProcessor::config_for`) and call `apply_rules_with_config`")]
     pub fn apply_rules(
         &self,
         text_elements: &[PdfTextElement],
@@ -135,73 +296,148 @@ impl RuleEngine {
         document_analysis: &DocumentAnalysis,
         font_size_analysis: &FontSizeAnalysis,
         style_data: &StyleData,
-    ) -> Result<Vec<ParsedPdfElement>> {
-        // Create a minimal StyleData from the text elements for backward compatibility
-        println!(
-            "⚙️  Applying enhanced parsing rules with SEQUENTIAL PIPELINE for: {:?}",
-            classification.document_type
-        );
-        println!("📊 Available text elements: {}", text_elements.len());
-
-        // Get the appropriate config for this document type
-        let config = self
-            .config_manager
-            .get_config(&classification.document_type);
-        println!(
-            "📝 Using config thresholds: large={:.1}%, medium={:.1}%, small={:.1}%",
-            config.section_and_hierarchy.large_header_threshold * 100.0,
-            config.section_and_hierarchy.medium_header_threshold * 100.0,
-            config.section_and_hierarchy.small_header_threshold * 100.0
-        );
-
-        // STEP 1: Always do base conversion first (TextElement → ParsedElement)
-        println!("🔧 Applying BaseConversion...");
-        // Use enhanced conversion pipeline for rich semantic data
-        let mut elements = self.convert_text_elements_to_parsed(text_elements);
-        debug_pipeline_elements("BaseConversion", &elements, &self.debug_config);
-        println!("   ✅ {} elements after BaseConversion", elements.len());
-
-        // STEP 2: Check for minimal parse bypass (CLI override takes precedence)
-        let minimal_parse = self.minimal_parse_override.unwrap_or(config.minimal_parse);
-        if minimal_parse {
+        debug_config: &DebugConfig,
+    ) -> Result<RuleRun> {
+        let config = match &self.custom_configs {
+            Some(configs) => configs.get_config(&classification.document_type),
+            None => built_in_configs()?.get_config(&classification.document_type),
+        };
+        if self.minimal_parse_override.unwrap_or(config.minimal_parse) {
             println!("⚡ Minimal parse mode enabled - bypassing all rule processing");
-            return Ok(elements);
+            return Ok(RuleRun {
+                elements: self.convert_text_elements_to_parsed(text_elements),
+                ..RuleRun::default()
+            });
         }
+        self.run_pipeline(
+            text_elements,
+            classification,
+            document_analysis,
+            font_size_analysis,
+            style_data,
+            config,
+            &config.pipeline.rules,
+            debug_config,
+        )
+    }
 
-        // STEP 3: Apply rules in sequence based on config
-        println!("🔗 Executing config-driven rule pipeline...");
-
-        // Clear previous timings
-        self.rule_timings.borrow_mut().clear();
+    /// Apply rules with explicit config (new config flow pattern)
+    pub fn apply_rules_with_config(
+        &self,
+        text_elements: &[PdfTextElement],
+        classification: &ClassificationResult,
+        document_analysis: &DocumentAnalysis,
+        font_size_analysis: &FontSizeAnalysis,
+        style_data: &StyleData,
+        config: &ParsingConfig,
+    ) -> Result<RuleRun> {
+        self.apply_rules_with_pipeline(
+            text_elements,
+            classification,
+            document_analysis,
+            font_size_analysis,
+            style_data,
+            config,
+            &config.pipeline.rules,
+        )
+    }
 
-        for rule_config in &config.pipeline.rules {
-            if !rule_config.enabled {
-                println!("   ⏭️  Skipping disabled rule: {}", rule_config.name);
-                continue;
-            }
+    /// Apply an explicit ordered rule list instead of `config.pipeline`.
+    ///
+    /// Lets callers compose a pipeline per invocation (skip Validation, run
+    /// SpatialClustering twice, ...) without mutating a shared config. The
+    /// config still supplies every rule's parameters.
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply_rules_with_pipeline(
+        &self,
+        text_elements: &[PdfTextElement],
+        classification: &ClassificationResult,
+        document_analysis: &DocumentAnalysis,
+        font_size_analysis: &FontSizeAnalysis,
+        style_data: &StyleData,
+        config: &ParsingConfig,
+        rules: &[RuleConfig],
+    ) -> Result<RuleRun> {
+        self.run_pipeline(
+            text_elements,
+            classification,
+            document_analysis,
+            font_size_analysis,
+            style_data,
+            config,
+            rules,
+            &DebugConfig::disabled(),
+        )
+    }
 
-            println!("🔧 Applying rule: {}", rule_config.name);
-            elements = self.apply_rule_by_name(
-                &rule_config.name,
-                elements,
-                text_elements,
-                config,
-                document_analysis,
-                font_size_analysis,
-                style_data,
-            )?;
-            println!(
-                "   ✅ {} elements after {}",
-                elements.len(),
-                rule_config.name
-            );
-        }
+    /// Dry-run `config`'s pipeline over `text_elements`, recording what
+    /// each rule created, removed, merged, split or retyped (see
+    /// `rules::explain`). Runs even when `config.minimal_parse` is set, so a
+    /// config can be tuned before it is switched on.
+    pub fn explain(
+        &self,
+        text_elements: &[PdfTextElement],
+        style_data: &StyleData,
+        config: &ParsingConfig,
+    ) -> Result<ExplainReport> {
+        let document_analysis = DocumentAnalysis::analyze_text_elements(text_elements);
+        let font_size_analysis = self.analyze_font_sizes(text_elements, style_data);
+        // The config is explicit, so the type is only used for logging
+        let classification = ClassificationResult {
+            document_type: DocumentType::Generic,
+            _confidence: 1.0,
+        };
+        let mut explanations = Vec::new();
+        let run = self.run_rules(
+            text_elements,
+            &classification,
+            &document_analysis,
+            &font_size_analysis,
+            style_data,
+            config,
+            &config.pipeline.rules,
+            &DebugConfig::disabled(),
+            Some(&mut explanations),
+        )?;
+        Ok(ExplainReport {
+            input_elements: text_elements.len(),
+            output_elements: run.elements.len(),
+            rules: explanations,
+            elements: run.elements,
+        })
+    }
 
-        Ok(elements)
+    /// The one rule loop every entry point goes through, tracing elements
+    /// matching `debug_config` after each rule
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn run_pipeline(
+        &self,
+        text_elements: &[PdfTextElement],
+        classification: &ClassificationResult,
+        document_analysis: &DocumentAnalysis,
+        font_size_analysis: &FontSizeAnalysis,
+        style_data: &StyleData,
+        config: &ParsingConfig,
+        rules: &[RuleConfig],
+        debug_config: &DebugConfig,
+    ) -> Result<RuleRun> {
+        self.run_rules(
+            text_elements,
+            classification,
+            document_analysis,
+            font_size_analysis,
+            style_data,
+            config,
+            rules,
+            debug_config,
+            None,
+        )
     }
 
-    /// Apply rules with explicit config (new config flow pattern)
-    pub fn apply_rules_with_config(
+    /// `run_pipeline`, also diffing the elements around each rule into
+    /// `explanations` when given
+    #[allow(clippy::too_many_arguments)]
+    fn run_rules(
         &self,
         text_elements: &[PdfTextElement],
         classification: &ClassificationResult,
@@ -209,7 +445,10 @@ impl RuleEngine {
         font_size_analysis: &FontSizeAnalysis,
         style_data: &StyleData,
         config: &ParsingConfig,
-    ) -> Result<Vec<ParsedPdfElement>> {
+        rules: &[RuleConfig],
+        debug_config: &DebugConfig,
+        mut explanations: Option<&mut Vec<RuleExplanation>>,
+    ) -> Result<RuleRun> {
         println!(
             "⚙️  Applying rules with config flow for: {:?}",
             classification.document_type
@@ -218,58 +457,99 @@ impl RuleEngine {
 
         // Convert text elements to parsed elements as starting point
         let mut elements = self.convert_text_elements_to_parsed(text_elements);
+        // Inline styles are found through the elements' sources too
+        let trace = config.provenance || config.inline_styles != InlineStyleMode::Off;
+        if trace {
+            seed_provenance(&mut elements, text_elements);
+        }
+        debug_pipeline_elements("BaseConversion", &elements, debug_config);
+        let mut run = RuleRun::default();
 
-        // Apply each enabled rule from the config
-        for rule_config in &config.pipeline.rules {
+        // Apply each enabled rule in order
+        for rule_config in rules {
             if !rule_config.enabled {
                 println!("   ⏭️ Skipping disabled rule: {}", rule_config.name);
                 continue;
             }
 
             println!("   🔄 Applying rule: {}", rule_config.name);
+            // Params override the rule's config section for this occurrence only
+            let rule_params_config;
+            let rule_scoped_config = match &rule_config.params {
+                Some(params) if rule_config_section(&rule_config.name).is_some() => {
+                    rule_params_config = config.with_rule_params(&rule_config.name, params)?;
+                    &rule_params_config
+                }
+                _ => config,
+            };
+            let before = (explanations.is_some() || trace).then(|| elements.clone());
             elements = self.apply_rule_by_name(
-                &rule_config.name,
+                rule_config,
                 elements,
                 text_elements,
-                config,
+                rule_scoped_config,
                 document_analysis,
                 font_size_analysis,
                 style_data,
+                debug_config,
+                &mut run,
             )?;
             println!(
                 "   ✅ {} elements after {}",
                 elements.len(),
                 rule_config.name
             );
+            let Some(before) = before else {
+                continue;
+            };
+            if trace {
+                trace_provenance(&rule_config.name, &before, &mut elements);
+            }
+            if let Some(explanations) = explanations.as_deref_mut() {
+                let duration = run.rule_timings.last().map(|(_, d)| *d).unwrap_or_default();
+                explanations.push(RuleExplanation::new(&rule_config.name, &before, &elements, duration));
+            }
         }
 
-        Ok(elements)
+        apply_inline_styles(&mut elements, text_elements, config.inline_styles);
+        if trace && !config.provenance {
+            for element in &mut elements {
+                element.provenance = None;
+            }
+        }
+        run.elements = elements;
+        Ok(run)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn apply_rule_by_name(
         &self,
-        rule_name: &str,
+        rule_config: &RuleConfig,
         elements: Vec<ParsedPdfElement>,
         text_elements: &[PdfTextElement],
         config: &ParsingConfig,
         document_analysis: &DocumentAnalysis,
         font_size_analysis: &FontSizeAnalysis,
         style_data: &StyleData,
+        debug_config: &DebugConfig,
+        run: &mut RuleRun,
     ) -> Result<Vec<ParsedPdfElement>> {
+        let rule_name = rule_config.name.as_str();
         let rule_start = std::time::Instant::now();
         let result = match rule_name {
             "SpatialClustering" => {
                 println!("🧩 APPLYING SPATIAL CLUSTERING...");
                 let spatial_rule = SpatialClusteringRule::new(config);
                 let result = spatial_rule.apply(elements)?;
-                debug_pipeline_elements("SpatialClustering", &result, &self.debug_config);
+                debug_pipeline_elements("SpatialClustering", &result, debug_config);
                 Ok(result)
             }
             "Validation" => {
                 println!("🔍 APPLYING VALIDATION...");
                 let validation_rule = ValidationRule::new(config);
-                let result = validation_rule.apply(elements)?;
-                debug_pipeline_elements("Validation", &result, &self.debug_config);
+                let (result, report) = validation_rule.validate(elements)?;
+                run.validation = Some(report);
+                debug_pipeline_elements("Validation", &result, debug_config);
                 Ok(result)
             }
             "SectionDetection" => {
@@ -281,9 +561,15 @@ impl RuleEngine {
                     document_analysis,
                     font_size_analysis,
                     style_data,
-                );
+                )?;
                 let result = section_rule.apply(elements)?;
-                debug_pipeline_elements("SectionDetection", &result, &self.debug_config);
+                debug_pipeline_elements("SectionDetection", &result, debug_config);
+                Ok(result)
+            }
+            "BookmarkHierarchy" => {
+                let bookmark_rule = BookmarkHierarchyRule::new(config);
+                let result = bookmark_rule.apply(elements)?;
+                debug_pipeline_elements("BookmarkHierarchy", &result, debug_config);
                 Ok(result)
             }
             "PatternBasedSectionDetection" => {
@@ -295,31 +581,85 @@ impl RuleEngine {
                 Ok(elements)
             }
             "ListDetection" => {
-                println!("📝 LIST DETECTION (DISABLED - WILL BE REWRITTEN)");
-                println!(
-                    "   ⏭️  Passing through {} elements unchanged",
-                    elements.len()
-                );
-                Ok(elements)
+                let list_rule = ListDetectionRule::new(&config.list_detection);
+                let result = list_rule.apply(elements)?;
+                debug_pipeline_elements("ListDetection", &result, debug_config);
+                Ok(result)
+            }
+            "KeyValueDetection" => {
+                let key_value_rule = KeyValueDetectionRule::new(&config.key_value_detection);
+                let result = key_value_rule.apply(elements)?;
+                debug_pipeline_elements("KeyValueDetection", &result, debug_config);
+                Ok(result)
+            }
+            "HeaderFooterDetection" => {
+                let header_footer_rule = HeaderFooterDetectionRule::new(&config.header_footer_detection);
+                let result = header_footer_rule.apply(elements)?;
+                debug_pipeline_elements("HeaderFooterDetection", &result, debug_config);
+                Ok(result)
+            }
+            "BoilerplateSuppression" => {
+                let boilerplate_rule = BoilerplateRule::new(&config.boilerplate);
+                let result = boilerplate_rule.apply(elements)?;
+                debug_pipeline_elements("BoilerplateSuppression", &result, debug_config);
+                Ok(result)
+            }
+            "TableDetection" => {
+                let table_rule = TableDetectionRule::new(&config.table_detection);
+                let result = table_rule.apply(elements)?;
+                debug_pipeline_elements("TableDetection", &result, debug_config);
+                Ok(result)
+            }
+            "CaptionAssociation" => {
+                let caption_rule = CaptionAssociationRule::new(&config.caption_association);
+                let result = caption_rule.apply(elements)?;
+                debug_pipeline_elements("CaptionAssociation", &result, debug_config);
+                Ok(result)
+            }
+            "QuoteDetection" => {
+                let quote_rule = QuoteDetectionRule::new(&config.quote_detection);
+                let result = quote_rule.apply(elements)?;
+                debug_pipeline_elements("QuoteDetection", &result, debug_config);
+                Ok(result)
             }
             "SizeEnforcer" => {
-                println!("🔪 SIZE ENFORCEMENT (DISABLED - WILL BE REWRITTEN)");
-                println!(
-                    "   ⏭️  Passing through {} elements unchanged",
-                    elements.len()
-                );
-                Ok(elements)
+                let size_rule = SizeEnforcerRule::new(&config.size_enforcer).with_tokenizer(self.tokenizer(&config.tokenizer)?);
+                let result = size_rule.apply(elements)?;
+                debug_pipeline_elements("SizeEnforcer", &result, debug_config);
+                Ok(result)
+            }
+            "SentenceSegmentation" => {
+                let sentence_rule = SentenceSegmentationRule::new(&config.sentence_segmentation);
+                let result = sentence_rule.apply(elements)?;
+                debug_pipeline_elements("SentenceSegmentation", &result, debug_config);
+                Ok(result)
+            }
+            _ if self.custom_rules.contains_key(rule_name) => {
+                let context = RuleContext {
+                    config,
+                    params: rule_config.params.as_ref(),
+                    text_elements,
+                    document_analysis,
+                    font_size_analysis,
+                    style_data,
+                };
+                let custom_rule = self.custom_rules[rule_name].create(&context)?;
+                let result = custom_rule.apply(elements)?;
+                debug_pipeline_elements(rule_name, &result, debug_config);
+                Ok(result)
             }
             _ => {
                 println!("⚠️  Unknown rule: {rule_name}. Skipping...");
+                run.warnings.push(ProcessingWarning::new(
+                    WarningCode::UnknownRule,
+                    format!("Pipeline rule '{rule_name}' is not known to the engine and was skipped"),
+                ));
                 Ok(elements)
             }
         };
 
         let rule_duration = rule_start.elapsed();
-        self.rule_timings
-            .borrow_mut()
-            .push((rule_name.to_string(), rule_duration));
+        run.rule_timings.push((rule_name.to_string(), rule_duration));
         result
     }
 
@@ -332,7 +672,7 @@ impl RuleEngine {
     ) -> FontSizeAnalysis {
         // STEP 1: Count frequency of each font class used in text elements (single pass)
         let mut class_usage_counts = std::collections::HashMap::new();
-        for element in text_elements {
+        for element in text_elements.iter().filter(|e| !e.is_figure()) {
             *class_usage_counts
                 .entry(element.style_info.class_name.clone())
                 .or_insert(0) += 1;
@@ -343,25 +683,25 @@ impl RuleEngine {
         let mut font_sizes = Vec::new();
         let mut size_to_count_vec: Vec<(f32, usize)> = Vec::new(); // (size, count) pairs
 
-        for (class_name, usage_count) in &class_usage_counts {
-            if let Some(font_class) = style_data.font_classes.get(class_name) {
+        // Resolve per element so page-scoped classes use their own size
+        for element in text_elements.iter().filter(|e| !e.is_figure()) {
+            if let Some(font_class) =
+                style_data.resolve(&element.style_info.class_name, element.page_number)
+            {
                 let size_key = format!("{:.1}", font_class.font_size); // Convert to string key
-                *size_frequency_map.entry(size_key).or_insert(0) += usage_count;
+                *size_frequency_map.entry(size_key).or_insert(0) += 1;
 
                 // Update size_to_count_vec
                 if let Some(existing) = size_to_count_vec
                     .iter_mut()
                     .find(|(size, _)| (size - font_class.font_size).abs() < 0.01)
                 {
-                    existing.1 += usage_count;
+                    existing.1 += 1;
                 } else {
-                    size_to_count_vec.push((font_class.font_size, *usage_count));
+                    size_to_count_vec.push((font_class.font_size, 1));
                 }
 
-                // Add font size multiple times based on its frequency in the document
-                for _ in 0..*usage_count {
-                    font_sizes.push(font_class.font_size);
-                }
+                font_sizes.push(font_class.font_size);
             }
         }
 
@@ -472,15 +812,77 @@ impl RuleEngine {
         &self,
         text_elements: &[PdfTextElement],
     ) -> Vec<ParsedPdfElement> {
-        let mut elements = Vec::new();
+        let mut elements: Vec<ParsedPdfElement> = Vec::new();
+        let mut open_table: Option<&str> = None;
 
         // Convert each enhanced TextElement to ParsedElementNew
         for (position, text_element) in text_elements.iter().enumerate() {
+            // Figures become Figure elements, even without caption or alt text
+            if text_element.is_figure() {
+                open_table = None;
+                elements.push(ParsedPdfElement {
+                    element_type: ParsedElementType::Figure {
+                        caption: text_element.paragraph_attributes.get("caption").cloned(),
+                    },
+                    text: text_element.text.trim().to_string(),
+                    hierarchy_level: 1,
+                    position,
+                    style_info: text_element.style_info.clone(),
+                    bounding_box: text_element.bounding_box.clone(),
+                    page_number: text_element.page_number,
+                    paragraph_number: text_element.paragraph_number,
+                    reading_order: text_element.reading_order,
+                    bookmark_match: text_element.bookmark_match.clone(),
+                    token_count: text_element.token_count,
+                    provenance: None,
+                    style_runs: Vec::new(),
+                });
+                continue;
+            }
+
             // Skip empty text elements
             if text_element.text.trim().is_empty() {
                 continue;
             }
 
+            // Rows the preprocessor tagged with a table id become one Table element
+            if let Some(table_id) = text_element.table_id() {
+                let cells = split_table_cells(text_element.text.trim());
+                let is_header = text_element
+                    .paragraph_attributes
+                    .get("header")
+                    .is_some_and(|value| value == "true");
+                match elements.last_mut() {
+                    Some(table) if open_table == Some(table_id) => {
+                        append_table_row(table, text_element, cells, is_header);
+                    }
+                    _ => {
+                        elements.push(ParsedPdfElement {
+                            element_type: ParsedElementType::Table(TableData {
+                                rows: vec![cells],
+                                header_rows: usize::from(is_header),
+                                caption: None,
+                            }),
+                            text: text_element.text.trim().to_string(),
+                            hierarchy_level: 1,
+                            position,
+                            style_info: text_element.style_info.clone(),
+                            bounding_box: text_element.bounding_box.clone(),
+                            page_number: text_element.page_number,
+                            paragraph_number: text_element.paragraph_number,
+                            reading_order: text_element.reading_order,
+                            bookmark_match: text_element.bookmark_match.clone(),
+                            token_count: text_element.token_count,
+                            provenance: None,
+                            style_runs: Vec::new(),
+                        });
+                        open_table = Some(table_id);
+                    }
+                }
+                continue;
+            }
+            open_table = None;
+
             let paragraph_element = ParsedPdfElement {
                 element_type: ParsedElementType::Paragraph,
                 text: text_element.text.trim().to_string(),
@@ -493,6 +895,8 @@ impl RuleEngine {
                 reading_order: text_element.reading_order,       // Spatial ordering
                 bookmark_match: text_element.bookmark_match.clone(), // Section context
                 token_count: text_element.token_count,           // Use pre-calculated token count
+                provenance: None,
+                style_runs: Vec::new(),
             };
 
             elements.push(paragraph_element);
@@ -502,6 +906,36 @@ impl RuleEngine {
     }
 }
 
+/// Add a row to a Table element built by base conversion
+fn append_table_row(
+    table: &mut ParsedPdfElement,
+    row: &PdfTextElement,
+    cells: Vec<String>,
+    is_header: bool,
+) {
+    if let ParsedElementType::Table(data) = &mut table.element_type {
+        if is_header && data.header_rows == data.rows.len() {
+            data.header_rows += 1;
+        }
+        data.rows.push(cells);
+    }
+    table.text.push('\n');
+    table.text.push_str(row.text.trim());
+    table.token_count += row.token_count;
+    if row.page_number == table.page_number {
+        let (a, b) = (&table.bounding_box, &row.bounding_box);
+        let (min_x, min_y) = (a.x.min(b.x), a.y.min(b.y));
+        let max_x = (a.x + a.width).max(b.x + b.width);
+        let max_y = (a.y + a.height).max(b.y + b.height);
+        table.bounding_box = BoundingBox {
+            x: min_x,
+            y: min_y,
+            width: max_x - min_x,
+            height: max_y - min_y,
+        };
+    }
+}
+
 // Shared types and utilities
 // TODO: Move to types?
 #[derive(Debug, Clone)]
@@ -553,3 +987,230 @@ pub trait ParseRule {
     fn apply(&self, elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>>;
     fn name(&self) -> &str;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PipelineConfig;
+    use crate::test_utils::text_elements;
+
+    #[test]
+    fn explicit_pipeline_overrides_config() {
+        let engine = RuleEngine::new().unwrap();
+        let elements = text_elements(&["Introduction", "Body text.", "More body text."]);
+        let analysis = DocumentAnalysis::analyze_text_elements(&elements);
+        let style_data = StyleData::default();
+        let font_sizes = engine.analyze_font_sizes(&elements, &style_data);
+        let classification = ClassificationResult {
+            document_type: DocumentType::Generic,
+            _confidence: 1.0,
+        };
+        let config = ParsingConfig::default();
+        let pipeline = PipelineConfig::from_rule_names(&["SpatialClustering", "SpatialClustering"]);
+
+        let run = engine
+            .apply_rules_with_pipeline(
+                &elements,
+                &classification,
+                &analysis,
+                &font_sizes,
+                &style_data,
+                &config,
+                &pipeline.rules,
+            )
+            .unwrap();
+
+        let ran: Vec<String> = run.rule_timings.iter().map(|(name, _)| name.clone()).collect();
+        assert_eq!(ran, vec!["SpatialClustering", "SpatialClustering"]);
+        assert_eq!(config.pipeline.rules.len(), PipelineConfig::default().rules.len());
+    }
+
+    struct DropMatching(String);
+
+    impl ParseRule for DropMatching {
+        fn apply(&self, elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+            Ok(elements.into_iter().filter(|e| !e.text.contains(&self.0)).collect())
+        }
+
+        fn name(&self) -> &str {
+            "DropDrafts"
+        }
+    }
+
+    /// A factory whose rules borrow the run's config
+    struct TruncateFactory;
+
+    struct Truncate<'a>(&'a ParsingConfig);
+
+    impl ParseRule for Truncate<'_> {
+        fn apply(&self, mut elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+            elements.truncate(self.0.size_enforcer.max_size);
+            Ok(elements)
+        }
+
+        fn name(&self) -> &str {
+            "Truncate"
+        }
+    }
+
+    impl ParseRuleFactory for TruncateFactory {
+        fn create<'a>(&self, context: &RuleContext<'a>) -> Result<Box<dyn ParseRule + 'a>> {
+            Ok(Box::new(Truncate(context.config)))
+        }
+    }
+
+    #[test]
+    fn registered_rules_run_by_name() {
+        let mut engine = RuleEngine::new().unwrap();
+        engine
+            .register_rule("DropDrafts", |_: &RuleContext| Ok(Box::new(DropMatching("DRAFT".into())) as _))
+            .unwrap();
+        engine.register_rule("Truncate", TruncateFactory).unwrap();
+        assert!(engine.register_rule("SizeEnforcer", TruncateFactory).is_err());
+        assert!(engine.register_rule("Truncate", TruncateFactory).is_err());
+        assert_eq!(engine.custom_rule_names(), ["DropDrafts", "Truncate"]);
+
+        let elements = text_elements(&["Introduction", "DRAFT - not for circulation", "Body text.", "More."]);
+        let analysis = DocumentAnalysis::analyze_text_elements(&elements);
+        let style_data = StyleData::default();
+        let font_sizes = engine.analyze_font_sizes(&elements, &style_data);
+        let classification = ClassificationResult {
+            document_type: DocumentType::Generic,
+            _confidence: 1.0,
+        };
+        let mut config = ParsingConfig::default();
+        config.size_enforcer.max_size = 2;
+        let pipeline = PipelineConfig::from_rule_names(&["DropDrafts", "Truncate"]);
+
+        let run = engine
+            .apply_rules_with_pipeline(
+                &elements,
+                &classification,
+                &analysis,
+                &font_sizes,
+                &style_data,
+                &config,
+                &pipeline.rules,
+            )
+            .unwrap();
+        let texts: Vec<&str> = run.elements.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, ["Introduction", "Body text."]);
+        assert!(run.warnings.is_empty());
+    }
+
+    #[test]
+    fn rule_params_override_the_config_section_per_occurrence() {
+        let engine = RuleEngine::new().unwrap();
+        let elements = text_elements(&["Introduction", "Body text."]);
+        let analysis = DocumentAnalysis::analyze_text_elements(&elements);
+        let style_data = StyleData::default();
+        let font_sizes = engine.analyze_font_sizes(&elements, &style_data);
+        let classification = ClassificationResult {
+            document_type: DocumentType::Generic,
+            _confidence: 1.0,
+        };
+        let mut config = ParsingConfig::default();
+        let params = |yaml: &str| serde_yaml::from_str::<serde_yaml::Value>(yaml).unwrap();
+        config.pipeline.rules = vec![
+            RuleConfig::new("BoilerplateSuppression"),
+            RuleConfig::new("BoilerplateSuppression").with_params(params("{min_pages: 1, min_chars: 12, action: drop}")),
+        ];
+        config.validate().unwrap();
+
+        let run = engine
+            .apply_rules_with_config(&elements, &classification, &analysis, &font_sizes, &style_data, &config)
+            .unwrap();
+        let texts: Vec<&str> = run.elements.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, ["Body text."], "only the second occurrence drops single-page text");
+        assert_eq!(config.boilerplate.min_pages, 3, "the shared section is untouched");
+
+        config.pipeline.rules[1] = RuleConfig::new("BoilerplateSuppression").with_params(params("{min_page: 1}"));
+        assert!(config.validate().unwrap_err().to_string().contains("Unknown param"));
+    }
+
+    #[test]
+    fn validation_params_set_policies() {
+        use crate::config::ValidationPolicy;
+
+        let mut config = ParsingConfig::default();
+        let params: serde_yaml::Value = serde_yaml::from_str("{policies: {hierarchy_jump: fail_run}}").unwrap();
+        config.pipeline.rules = vec![RuleConfig::new("Validation").with_params(params.clone())];
+        config.validate().unwrap();
+
+        let validation = config.with_rule_params("Validation", &params).unwrap().validation;
+        assert_eq!(validation.policies.get("hierarchy_jump"), Some(&ValidationPolicy::FailRun));
+    }
+
+    #[test]
+    fn one_engine_serves_concurrent_runs() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<RuleEngine>();
+
+        let engine = RuleEngine::new().unwrap();
+        let elements = text_elements(&["Introduction", "Body text."]);
+        let analysis = DocumentAnalysis::analyze_text_elements(&elements);
+        let style_data = StyleData::default();
+        let font_sizes = engine.analyze_font_sizes(&elements, &style_data);
+        let classification = ClassificationResult {
+            document_type: DocumentType::Generic,
+            _confidence: 1.0,
+        };
+        let config = ParsingConfig::default();
+
+        std::thread::scope(|scope| {
+            let runs: Vec<_> = (1..=4)
+                .map(|unknown_rules| {
+                    let pipeline = PipelineConfig::from_rule_names(&vec!["NoSuchRule"; unknown_rules]);
+                    let (engine, elements, analysis) = (&engine, &elements, &analysis);
+                    let (font_sizes, style_data, classification, config) = (&font_sizes, &style_data, &classification, &config);
+                    scope.spawn(move || {
+                        let run = engine
+                            .apply_rules_with_pipeline(
+                                elements,
+                                classification,
+                                analysis,
+                                font_sizes,
+                                style_data,
+                                config,
+                                &pipeline.rules,
+                            )
+                            .unwrap();
+                        (unknown_rules, run.warnings.len(), run.rule_timings.len())
+                    })
+                })
+                .collect();
+            for run in runs {
+                let (unknown_rules, warnings, timings) = run.join().unwrap();
+                assert_eq!((warnings, timings), (unknown_rules, unknown_rules), "runs keep their own warnings");
+            }
+        });
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_settings_still_apply_to_apply_rules() {
+        let mut engine = RuleEngine::new().unwrap();
+        let mut custom = ParsingConfig::default();
+        custom.size_enforcer.max_size = 123;
+        let path = std::env::temp_dir().join(format!("blazegraph-config-{}.yaml", uuid::Uuid::new_v4()));
+        std::fs::write(&path, serde_yaml::to_string(&custom).unwrap()).unwrap();
+        engine.load_custom_config(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(engine.get_config_for_cache(&custom.document_type).size_enforcer.max_size, 123);
+
+        let elements = text_elements(&["Introduction", "Body text."]);
+        let analysis = DocumentAnalysis::analyze_text_elements(&elements);
+        let style_data = StyleData::default();
+        let font_sizes = engine.analyze_font_sizes(&elements, &style_data);
+        let classification = ClassificationResult {
+            document_type: custom.document_type.clone(),
+            _confidence: 1.0,
+        };
+        engine.set_minimal_parse_override(true);
+        let run = engine
+            .apply_rules(&elements, &classification, &analysis, &font_sizes, &style_data, &DebugConfig::disabled())
+            .unwrap();
+        assert_eq!(run.elements.len(), 2);
+        assert!(run.rule_timings.is_empty(), "minimal parse skips the rules");
+    }
+}
@@ -0,0 +1,312 @@
+//! Pipeline dry runs: what each rule did to the elements
+//!
+//! `RuleEngine::explain` runs a pipeline and, after every rule, compares the
+//! elements before and after it. Rules don't carry element identities, so
+//! the comparison matches elements by page and text:
+//!
+//! - an element whose text and page are unchanged is the same element
+//!   (`retyped` when its type or hierarchy level changed);
+//! - otherwise an element draws from the unmatched elements on its page
+//!   whose text it contains (or is contained in) and whose box overlaps it:
+//!   several sources make a `merged` element, one source claimed by several
+//!   elements is `split`, one source for one element is `modified`;
+//! - elements with no source are `created`, sources nothing drew from are
+//!   `removed`.
+//!
+//! Rewrites that change the wording entirely (e.g. de-hyphenation across a
+//! merge) show up as a removal plus a creation.
+
+use crate::types::{BoundingBox, ParsedElementType, ParsedPdfElement};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+/// Longest text shown for an element in a report
+const PREVIEW_CHARS: usize = 80;
+/// Slack (in points) when checking whether two boxes overlap
+const OVERLAP_TOLERANCE: f32 = 1.0;
+
+/// An element as it was at one point of the pipeline
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ElementRef {
+    /// Position in the element list before (or after) the rule
+    pub index: usize,
+    pub element_type: ParsedElementType,
+    pub hierarchy_level: u32,
+    pub page: u32,
+    /// The text, cut to the first 80 characters
+    pub text: String,
+}
+
+impl ElementRef {
+    fn new(index: usize, element: &ParsedPdfElement) -> Self {
+        let mut text: String = element.text.chars().take(PREVIEW_CHARS).collect();
+        if element.text.chars().count() > PREVIEW_CHARS {
+            text.push('…');
+        }
+        Self {
+            index,
+            element_type: element.element_type.clone(),
+            hierarchy_level: element.hierarchy_level,
+            page: element.page_number,
+            text,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ElementChange {
+    Created { after: ElementRef },
+    Removed { before: ElementRef },
+    Merged { before: Vec<ElementRef>, after: ElementRef },
+    Split { before: ElementRef, after: Vec<ElementRef> },
+    /// Same text, different type or hierarchy level
+    Retyped { before: ElementRef, after: ElementRef },
+    /// Text rewritten in place
+    Modified { before: ElementRef, after: ElementRef },
+}
+
+/// Number of changes of each kind
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ChangeCounts {
+    pub created: usize,
+    pub removed: usize,
+    pub merged: usize,
+    pub split: usize,
+    pub retyped: usize,
+    pub modified: usize,
+    pub unchanged: usize,
+}
+
+/// What one pipeline entry did
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleExplanation {
+    pub rule: String,
+    pub elements_before: usize,
+    pub elements_after: usize,
+    pub duration_ms: f64,
+    pub counts: ChangeCounts,
+    pub changes: Vec<ElementChange>,
+}
+
+impl RuleExplanation {
+    pub fn new(rule: &str, before: &[ParsedPdfElement], after: &[ParsedPdfElement], duration: std::time::Duration) -> Self {
+        let (changes, unchanged) = diff_elements(before, after);
+        let mut counts = ChangeCounts {
+            unchanged: unchanged.len(),
+            ..ChangeCounts::default()
+        };
+        for change in &changes {
+            match change {
+                ElementChange::Created { .. } => counts.created += 1,
+                ElementChange::Removed { .. } => counts.removed += 1,
+                ElementChange::Merged { .. } => counts.merged += 1,
+                ElementChange::Split { .. } => counts.split += 1,
+                ElementChange::Retyped { .. } => counts.retyped += 1,
+                ElementChange::Modified { .. } => counts.modified += 1,
+            }
+        }
+        Self {
+            rule: rule.to_string(),
+            elements_before: before.len(),
+            elements_after: after.len(),
+            duration_ms: duration.as_secs_f64() * 1000.0,
+            counts,
+            changes,
+        }
+    }
+}
+
+/// A pipeline dry run, rule by rule
+#[derive(Debug, Clone, Serialize)]
+pub struct ExplainReport {
+    /// Elements before the first rule (one per text span)
+    pub input_elements: usize,
+    pub output_elements: usize,
+    /// One entry per enabled pipeline entry, in order
+    pub rules: Vec<RuleExplanation>,
+    /// The pipeline's output
+    #[serde(skip)]
+    pub elements: Vec<ParsedPdfElement>,
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn overlaps(a: &BoundingBox, b: &BoundingBox) -> bool {
+    a.x <= b.x + b.width + OVERLAP_TOLERANCE
+        && b.x <= a.x + a.width + OVERLAP_TOLERANCE
+        && a.y <= b.y + b.height + OVERLAP_TOLERANCE
+        && b.y <= a.y + a.height + OVERLAP_TOLERANCE
+}
+
+/// Changes from `before` to `after`, and the (before, after) index pairs of
+/// the elements left unchanged
+pub fn diff_elements(
+    before: &[ParsedPdfElement],
+    after: &[ParsedPdfElement],
+) -> (Vec<ElementChange>, Vec<(usize, usize)>) {
+    let before_text: Vec<String> = before.iter().map(|e| normalize(&e.text)).collect();
+    let after_text: Vec<String> = after.iter().map(|e| normalize(&e.text)).collect();
+    let mut changes = Vec::new();
+    let mut unchanged = Vec::new();
+
+    // Same page and text: the same element, in order of appearance
+    let mut by_key: HashMap<(u32, &str), VecDeque<usize>> = HashMap::new();
+    for (i, element) in before.iter().enumerate() {
+        by_key.entry((element.page_number, &before_text[i])).or_default().push_back(i);
+    }
+    let mut before_matched = vec![false; before.len()];
+    let mut unmatched_after = Vec::new();
+    for (j, element) in after.iter().enumerate() {
+        let Some(i) = by_key.get_mut(&(element.page_number, after_text[j].as_str())).and_then(VecDeque::pop_front) else {
+            unmatched_after.push(j);
+            continue;
+        };
+        before_matched[i] = true;
+        let source = &before[i];
+        if source.element_type == element.element_type && source.hierarchy_level == element.hierarchy_level {
+            unchanged.push((i, j));
+        } else {
+            changes.push(ElementChange::Retyped {
+                before: ElementRef::new(i, source),
+                after: ElementRef::new(j, element),
+            });
+        }
+    }
+
+    // The rest draw from unmatched elements on their page sharing text and area
+    let mut unmatched_before: HashMap<u32, Vec<usize>> = HashMap::new();
+    for i in (0..before.len()).filter(|&i| !before_matched[i]) {
+        unmatched_before.entry(before[i].page_number).or_default().push(i);
+    }
+    let sources: Vec<(usize, Vec<usize>)> = unmatched_after
+        .into_iter()
+        .map(|j| {
+            let (element, text) = (&after[j], after_text[j].as_str());
+            let candidates = unmatched_before.get(&element.page_number).map_or(&[][..], Vec::as_slice);
+            let from = candidates
+                .iter()
+                .copied()
+                .filter(|&i| {
+                    let source_text = before_text[i].as_str();
+                    !text.is_empty()
+                        && !source_text.is_empty()
+                        && (text.contains(source_text) || source_text.contains(text))
+                        && overlaps(&before[i].bounding_box, &element.bounding_box)
+                })
+                .collect();
+            (j, from)
+        })
+        .collect();
+
+    let mut claims: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (j, from) in &sources {
+        for &i in from {
+            claims.entry(i).or_default().push(*j);
+        }
+    }
+    for (j, from) in &sources {
+        let after_ref = ElementRef::new(*j, &after[*j]);
+        match from.as_slice() {
+            [] => changes.push(ElementChange::Created { after: after_ref }),
+            [i] if claims[i].len() == 1 => changes.push(ElementChange::Modified {
+                before: ElementRef::new(*i, &before[*i]),
+                after: after_ref,
+            }),
+            // A part of a split, reported with its source below
+            [_] => {}
+            _ => changes.push(ElementChange::Merged {
+                before: from.iter().map(|&i| ElementRef::new(i, &before[i])).collect(),
+                after: after_ref,
+            }),
+        }
+    }
+    let mut split_or_removed: Vec<usize> = unmatched_before.into_values().flatten().collect();
+    split_or_removed.sort_unstable();
+    for i in split_or_removed {
+        let before_ref = ElementRef::new(i, &before[i]);
+        match claims.get(&i).map(Vec::as_slice) {
+            None => changes.push(ElementChange::Removed { before: before_ref }),
+            Some(parts) if parts.len() > 1 => changes.push(ElementChange::Split {
+                before: before_ref,
+                after: parts.iter().map(|&j| ElementRef::new(j, &after[j])).collect(),
+            }),
+            Some(_) => {}
+        }
+    }
+    (changes, unchanged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ParsingConfig, PipelineConfig};
+    use crate::rules::{ParseRule, RuleContext, RuleEngine};
+    use crate::test_utils::text_elements;
+    use crate::types::StyleData;
+    use anyhow::Result;
+
+    struct Edit(fn(&mut Vec<ParsedPdfElement>));
+
+    impl ParseRule for Edit {
+        fn apply(&self, mut elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+            (self.0)(&mut elements);
+            Ok(elements)
+        }
+
+        fn name(&self) -> &str {
+            "Edit"
+        }
+    }
+
+    #[test]
+    fn each_rule_reports_its_changes() {
+        let mut engine = RuleEngine::new().unwrap();
+        engine
+            .register_rule("Regroup", |_: &RuleContext| {
+                Ok(Box::new(Edit(|elements| {
+                    // Merge the first two lines, split the third, drop the last
+                    let second = elements.remove(1);
+                    elements[0].text = format!("{} {}", elements[0].text, second.text);
+                    elements[0].bounding_box.height += 14.0;
+                    let mut tail = elements[1].clone();
+                    elements[1].text = "More body".to_string();
+                    tail.text = "text.".to_string();
+                    elements.insert(2, tail);
+                    elements.pop();
+                })) as Box<dyn ParseRule>)
+            })
+            .unwrap();
+        engine
+            .register_rule("Promote", |_: &RuleContext| {
+                Ok(Box::new(Edit(|elements| elements[0].element_type = ParsedElementType::Section)) as Box<dyn ParseRule>)
+            })
+            .unwrap();
+        let config = ParsingConfig {
+            pipeline: PipelineConfig::from_rule_names(&["Regroup", "Promote"]),
+            minimal_parse: true,
+            ..ParsingConfig::default()
+        };
+
+        let elements = text_elements(&["Introduction", "to the terms.", "More body text.", "Footnote"]);
+        let report = engine.explain(&elements, &StyleData::default(), &config).unwrap();
+        assert_eq!((report.input_elements, report.output_elements), (4, 3));
+
+        let regroup = &report.rules[0];
+        assert_eq!(regroup.counts, ChangeCounts { merged: 1, split: 1, removed: 1, ..ChangeCounts::default() });
+        let ElementChange::Merged { before, after } = &regroup.changes[0] else {
+            panic!("expected a merge, got {:?}", regroup.changes[0]);
+        };
+        assert_eq!(before.iter().map(|e| e.index).collect::<Vec<_>>(), [0, 1]);
+        assert_eq!(after.text, "Introduction to the terms.");
+
+        let promote = &report.rules[1];
+        assert_eq!(promote.counts, ChangeCounts { retyped: 1, unchanged: 2, ..ChangeCounts::default() });
+        assert!(matches!(&promote.changes[0], ElementChange::Retyped { after, .. } if after.element_type == ParsedElementType::Section));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["rules"][0]["changes"][0]["kind"], "merged");
+    }
+}
@@ -0,0 +1,180 @@
+use super::engine::ParseRule;
+use crate::config::{HeaderFooterAction, HeaderFooterDetectionConfig};
+use crate::types::{ParsedElementType, ParsedPdfElement};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+/// Running header and footer detection
+///
+/// Page numbers, running titles and confidentiality notices repeat in the
+/// same place on every page, and otherwise merge into the first and last
+/// paragraph of each page. A line is a header (footer) when it sits in the
+/// top (bottom) band of the text area and the same text, ignoring digits,
+/// appears in that band on at least `min_pages` pages. Detected lines become
+/// `Header`/`Footer` elements, or are dropped with `action: strip`.
+///
+/// Works on lines, so it belongs before `SpatialClustering`.
+pub struct HeaderFooterDetectionRule<'a> {
+    config: &'a HeaderFooterDetectionConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Band {
+    Top,
+    Bottom,
+}
+
+impl<'a> HeaderFooterDetectionRule<'a> {
+    pub fn new(config: &'a HeaderFooterDetectionConfig) -> Self {
+        Self { config }
+    }
+
+    /// The band each element repeats in, if it is a header or footer
+    fn detect(&self, elements: &[ParsedPdfElement]) -> Vec<Option<Band>> {
+        let top = elements.iter().map(|e| e.bounding_box.y).fold(f32::INFINITY, f32::min);
+        let bottom = elements
+            .iter()
+            .map(|e| e.bounding_box.y + e.bounding_box.height)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let band_height = (bottom - top) * self.config.band_fraction;
+
+        let candidates: Vec<Option<(Band, String)>> = elements
+            .iter()
+            .map(|element| {
+                let text = element.text.trim();
+                if text.is_empty() || text.chars().count() > self.config.max_chars {
+                    return None;
+                }
+                let bbox = &element.bounding_box;
+                let band = if bbox.y <= top + band_height {
+                    Band::Top
+                } else if bbox.y + bbox.height >= bottom - band_height {
+                    Band::Bottom
+                } else {
+                    return None;
+                };
+                Some((band, signature(text)))
+            })
+            .collect();
+
+        let mut pages: HashMap<&(Band, String), HashSet<u32>> = HashMap::new();
+        for (element, candidate) in elements.iter().zip(&candidates) {
+            if let Some(key) = candidate {
+                pages.entry(key).or_default().insert(element.page_number);
+            }
+        }
+
+        candidates
+            .iter()
+            .map(|candidate| {
+                candidate
+                    .as_ref()
+                    .filter(|key| pages[key].len() >= self.config.min_pages)
+                    .map(|(band, _)| *band)
+            })
+            .collect()
+    }
+}
+
+/// Text with digit runs (and a lone roman numeral) collapsed, so "Page 3 of
+/// 12" matches "Page 4 of 12" and "iv" matches "17"
+fn signature(text: &str) -> String {
+    let lower = text.to_lowercase();
+    if lower.len() <= 6 && lower.chars().all(|c| "ivxlcdm".contains(c)) {
+        return "#".to_string();
+    }
+    let mut signature = String::with_capacity(lower.len());
+    for word in lower.split_whitespace() {
+        if !signature.is_empty() {
+            signature.push(' ');
+        }
+        for c in word.chars() {
+            if !c.is_ascii_digit() {
+                signature.push(c);
+            } else if !signature.ends_with('#') {
+                signature.push('#');
+            }
+        }
+    }
+    signature
+}
+
+impl ParseRule for HeaderFooterDetectionRule<'_> {
+    fn apply(&self, elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+        if !self.config.enabled || elements.is_empty() {
+            return Ok(elements);
+        }
+
+        println!("📑 APPLYING HEADER/FOOTER DETECTION...");
+        let bands = self.detect(&elements);
+        let (mut headers, mut footers) = (0, 0);
+        let mut result = Vec::with_capacity(elements.len());
+        for (mut element, band) in elements.into_iter().zip(bands) {
+            match band {
+                Some(Band::Top) => headers += 1,
+                Some(Band::Bottom) => footers += 1,
+                None => {
+                    result.push(element);
+                    continue;
+                }
+            }
+            if self.config.action == HeaderFooterAction::Tag {
+                element.element_type = match band {
+                    Some(Band::Top) => ParsedElementType::Header,
+                    _ => ParsedElementType::Footer,
+                };
+                result.push(element);
+            }
+        }
+
+        let verb = match self.config.action {
+            HeaderFooterAction::Tag => "Tagged",
+            HeaderFooterAction::Strip => "Stripped",
+        };
+        println!("   ✅ {} {} header and {} footer lines", verb, headers, footers);
+        Ok(result)
+    }
+
+    fn name(&self) -> &str {
+        "HeaderFooterDetection"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{bbox, ParsedElementBuilder};
+
+    fn line(text: &str, page: u32, y: f32) -> ParsedPdfElement {
+        ParsedElementBuilder::new(text).bbox(bbox(72.0, y, 400.0, 12.0)).page(page).build()
+    }
+
+    #[test]
+    fn tags_running_titles_and_page_numbers() {
+        let mut elements = Vec::new();
+        for page in 1..=4 {
+            elements.push(line("Master Services Agreement", page, 30.0));
+            elements.push(line(&format!("Body text unique to page {} of the contract.", page), page, 100.0));
+            elements.push(line(&format!("Clause {} continues down the page.", page * 7), page, 400.0));
+            elements.push(line(&format!("Page {} of 4", page), page, 760.0));
+        }
+        // Only on two pages: not a running header
+        elements[2].text = "Confidential".to_string();
+        elements[2].bounding_box.y = 35.0;
+
+        let config = HeaderFooterDetectionConfig::default();
+        let result = HeaderFooterDetectionRule::new(&config).apply(elements.clone()).unwrap();
+        let count = |t: ParsedElementType| result.iter().filter(|e| e.element_type == t).count();
+        assert_eq!(count(ParsedElementType::Header), 4);
+        assert_eq!(count(ParsedElementType::Footer), 4);
+        assert_eq!(result[2].element_type, ParsedElementType::Paragraph);
+
+        let config = HeaderFooterDetectionConfig {
+            action: HeaderFooterAction::Strip,
+            ..HeaderFooterDetectionConfig::default()
+        };
+        let result = HeaderFooterDetectionRule::new(&config).apply(elements).unwrap();
+        assert_eq!(result.len(), 8);
+        assert!(result.iter().all(|e| !e.text.starts_with("Page ") && e.text != "Master Services Agreement"));
+    }
+}
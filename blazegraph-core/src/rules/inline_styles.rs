@@ -0,0 +1,185 @@
+//! Inline emphasis in merged text
+//!
+//! Extraction gives each bold or italic stretch of a line its own
+//! TextElement, and the rules merge those into paragraphs with one style
+//! for the whole element, so a defined term ("the **Effective Date** means")
+//! loses its emphasis. With `inline_styles` set, the rule engine traces each
+//! element's source TextElements (as for `provenance`) and, after the last
+//! rule, finds every bold or italic source's text in the element's text:
+//!
+//! - `spans` records the runs as character offsets in `style_runs`;
+//! - `markdown` wraps them in the text: `**bold**`, `_italic_`, `**_both_**`.
+//!
+//! Elements styled uniformly (a bold heading) get no runs; their style is in
+//! `style_info` already. A source whose text no longer appears verbatim
+//! (e.g. de-hyphenated across lines) is skipped.
+
+use crate::config::InlineStyleMode;
+use crate::types::{FontClass, ParsedPdfElement, PdfTextElement, StyleRun};
+
+/// Add style runs (or markup) to elements that mix emphasis with plain text
+pub fn apply_inline_styles(elements: &mut [ParsedPdfElement], text_elements: &[PdfTextElement], mode: InlineStyleMode) {
+    if mode == InlineStyleMode::Off {
+        return;
+    }
+    let mut styled = 0;
+    for element in elements.iter_mut() {
+        let Some(provenance) = &element.provenance else {
+            continue;
+        };
+        element.text = element.text.trim().to_string();
+        let sources = provenance.source_elements.iter().filter_map(|&i| text_elements.get(i));
+        let runs = style_runs(&element.text, sources);
+        if runs.is_empty() {
+            continue;
+        }
+        styled += 1;
+        match mode {
+            InlineStyleMode::Spans => element.style_runs = runs,
+            InlineStyleMode::Markdown => element.text = to_markdown(&element.text, &runs),
+            InlineStyleMode::Off => {}
+        }
+    }
+    println!("✍️  Inline emphasis kept in {} elements", styled);
+}
+
+fn emphasis(style: &FontClass) -> (bool, bool) {
+    (
+        style.font_weight.to_lowercase().contains("bold"),
+        style.font_style.to_lowercase().contains("italic"),
+    )
+}
+
+/// Runs of `text` set in bold or italic, from the sources it was merged from
+fn style_runs<'a>(text: &str, sources: impl Iterator<Item = &'a PdfTextElement>) -> Vec<StyleRun> {
+    // Byte offsets while searching, converted to characters at the end
+    let mut runs: Vec<StyleRun> = Vec::new();
+    let mut cursor = 0;
+    for source in sources {
+        let needle = source.text.trim();
+        if needle.is_empty() {
+            continue;
+        }
+        let Some(start) = find_word(text, cursor, needle) else {
+            continue;
+        };
+        cursor = start + needle.len();
+        let (bold, italic) = emphasis(&source.style_info);
+        if !bold && !italic {
+            continue;
+        }
+        match runs.last_mut() {
+            // Consecutive words of one style are one run
+            Some(last) if (last.bold, last.italic) == (bold, italic) && text[last.end..start].trim().is_empty() => {
+                last.end = cursor;
+            }
+            _ => runs.push(StyleRun {
+                start,
+                end: cursor,
+                bold,
+                italic,
+            }),
+        }
+    }
+    if matches!(runs.as_slice(), [run] if run.start == 0 && run.end == text.len()) {
+        return Vec::new();
+    }
+    for run in &mut runs {
+        run.end = text[..run.end].chars().count();
+        run.start = text[..run.start].chars().count();
+    }
+    runs
+}
+
+/// First occurrence of `needle` in `text` after `from` that doesn't start or
+/// end inside a word, so an italic variable "b" isn't found in "be"
+fn find_word(text: &str, from: usize, needle: &str) -> Option<usize> {
+    let is_word = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+    text[from..].match_indices(needle).map(|(offset, _)| from + offset).find(|&start| {
+        let end = start + needle.len();
+        let starts_inside = is_word(text[..start].chars().next_back()) && is_word(needle.chars().next());
+        let ends_inside = is_word(text[end..].chars().next()) && is_word(needle.chars().next_back());
+        !starts_inside && !ends_inside
+    })
+}
+
+fn to_markdown(text: &str, runs: &[StyleRun]) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut marked = String::with_capacity(text.len() + runs.len() * 6);
+    let mut position = 0;
+    for run in runs {
+        let (open, close) = match (run.bold, run.italic) {
+            (true, true) => ("**_", "_**"),
+            (true, false) => ("**", "**"),
+            _ => ("_", "_"),
+        };
+        marked.extend(&chars[position..run.start]);
+        marked.push_str(open);
+        marked.extend(&chars[run.start..run.end]);
+        marked.push_str(close);
+        position = run.end;
+    }
+    marked.extend(&chars[position..]);
+    marked
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{InlineStyleMode, ParsingConfig, PipelineConfig};
+    use crate::rules::{ParseRule, RuleContext, RuleEngine};
+    use crate::test_utils::text_elements;
+    use crate::types::{ParsedPdfElement, StyleData, StyleRun};
+    use anyhow::Result;
+
+    /// Joins every element into one paragraph, as clustering would
+    struct JoinAll;
+
+    impl ParseRule for JoinAll {
+        fn apply(&self, elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+            let mut joined = elements[0].clone();
+            joined.text = elements.iter().map(|e| e.text.as_str()).collect::<Vec<_>>().join(" ");
+            joined.bounding_box.height = 100.0;
+            Ok(vec![joined])
+        }
+
+        fn name(&self) -> &str {
+            "JoinAll"
+        }
+    }
+
+    #[test]
+    fn emphasis_survives_merging() {
+        let mut engine = RuleEngine::new().unwrap();
+        engine
+            .register_rule("JoinAll", |_: &RuleContext| Ok(Box::new(JoinAll) as Box<dyn ParseRule>))
+            .unwrap();
+        let mut elements = text_elements(&["In this Agreement the", "Effective Date", "means the", "date of signing", "."]);
+        elements[1].style_info.font_weight = "bold".to_string();
+        elements[3].style_info.font_style = "italic".to_string();
+        let config = ParsingConfig {
+            pipeline: PipelineConfig::from_rule_names(&["JoinAll"]),
+            inline_styles: InlineStyleMode::Spans,
+            ..ParsingConfig::default()
+        };
+
+        let report = engine.explain(&elements, &StyleData::default(), &config).unwrap();
+        let element = &report.elements[0];
+        assert_eq!(
+            element.style_runs,
+            [
+                StyleRun { start: 22, end: 36, bold: true, italic: false },
+                StyleRun { start: 47, end: 62, bold: false, italic: true },
+            ]
+        );
+        assert!(element.provenance.is_none(), "tracing for styles isn't reported as provenance");
+
+        let config = ParsingConfig { inline_styles: InlineStyleMode::Markdown, ..config };
+        let report = engine.explain(&elements, &StyleData::default(), &config).unwrap();
+        assert_eq!(report.elements[0].text, "In this Agreement the **Effective Date** means the _date of signing_ .");
+
+        // An italic variable "b" isn't found inside "be"
+        let mut variable = text_elements(&["b"]);
+        variable[0].style_info.font_style = "italic".to_string();
+        assert!(super::style_runs("messages to be sent", variable.iter()).is_empty());
+    }
+}
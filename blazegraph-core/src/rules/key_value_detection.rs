@@ -0,0 +1,221 @@
+use super::engine::ParseRule;
+use crate::config::KeyValueDetectionConfig;
+use crate::types::{BoundingBox, ParsedElementType, ParsedPdfElement};
+use anyhow::Result;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// "Term: definition" on one line; the colon must be followed by whitespace
+/// so times (10:30), ratios and URLs don't split
+static INLINE_PAIR_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?s)\s*([^:]+?)\s*:\s+(\S.*?)\s*$").unwrap());
+
+/// Key-value detection - definition lists and label/value blocks
+///
+/// Spec sheets, forms and contract party blocks lay facts out as
+/// "Term: definition" lines or as a "Label:" with its value set off to the
+/// right on the same line. Runs of such lines become `KeyValue` elements
+/// carrying the key and value separately, so consumers don't have to re-split
+/// the flattened text. Element text is left as written.
+pub struct KeyValueDetectionRule<'a> {
+    config: &'a KeyValueDetectionConfig,
+}
+
+/// A pair found at some index, and how many elements it spans (1 inline, 2 laid out)
+struct PairCandidate {
+    key: String,
+    value: String,
+    span: usize,
+}
+
+impl<'a> KeyValueDetectionRule<'a> {
+    pub fn new(config: &'a KeyValueDetectionConfig) -> Self {
+        Self { config }
+    }
+
+    /// Short label-like text: a few words, starting with a letter, not a sentence
+    fn is_key(&self, key: &str) -> bool {
+        let key = key.trim();
+        key.chars().next().is_some_and(char::is_alphabetic)
+            && key.chars().count() <= self.config.max_key_chars
+            && key.split_whitespace().count() <= self.config.max_key_words
+            && !key.ends_with(['.', ',', ';', '!', '?'])
+    }
+
+    fn candidate_at(&self, elements: &[ParsedPdfElement], index: usize) -> Option<PairCandidate> {
+        let element = &elements[index];
+        if element.element_type != ParsedElementType::Paragraph {
+            return None;
+        }
+        let text = element.text.trim();
+
+        // Label ending in a colon, value to its right on the same line
+        if let (Some(label), Some(next)) = (text.strip_suffix(':'), elements.get(index + 1)) {
+            let (a, b) = (&element.bounding_box, &next.bounding_box);
+            if self.is_key(label)
+                && next.element_type == ParsedElementType::Paragraph
+                && next.page_number == element.page_number
+                && (a.y - b.y).abs() <= self.config.y_tolerance
+                && b.x >= a.x + a.width - self.config.y_tolerance
+                && !next.text.trim().is_empty()
+            {
+                return Some(PairCandidate {
+                    key: label.trim().to_string(),
+                    value: next.text.trim().to_string(),
+                    span: 2,
+                });
+            }
+        }
+
+        let captures = INLINE_PAIR_REGEX.captures(text)?;
+        self.is_key(&captures[1]).then(|| PairCandidate {
+            key: captures[1].to_string(),
+            value: captures[2].to_string(),
+            span: 1,
+        })
+    }
+
+    fn create_key_value(&self, parts: &[ParsedPdfElement], candidate: PairCandidate) -> ParsedPdfElement {
+        let first = &parts[0];
+        let text = parts
+            .iter()
+            .map(|e| e.text.trim())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let bounding_box = parts[1..]
+            .iter()
+            .fold(first.bounding_box.clone(), |bbox, e| merge_bounding_boxes(&bbox, &e.bounding_box));
+
+        ParsedPdfElement {
+            element_type: ParsedElementType::KeyValue {
+                key: candidate.key,
+                value: candidate.value,
+            },
+            text,
+            bounding_box,
+            token_count: parts.iter().map(|e| e.token_count).sum(),
+            ..first.clone()
+        }
+    }
+
+    fn detect_key_values(&self, elements: Vec<ParsedPdfElement>) -> Vec<ParsedPdfElement> {
+        let mut result = Vec::with_capacity(elements.len());
+        let mut index = 0;
+
+        while index < elements.len() {
+            // Collect the run of consecutive pairs starting here
+            let mut run = Vec::new();
+            let mut next = index;
+            while next < elements.len() {
+                let Some(candidate) = self.candidate_at(&elements, next) else {
+                    break;
+                };
+                let span = candidate.span;
+                run.push((next, candidate));
+                next += span;
+            }
+
+            if run.is_empty() || run.len() < self.config.min_consecutive_pairs {
+                result.push(elements[index].clone());
+                index += 1;
+                continue;
+            }
+
+            for (start, candidate) in run {
+                let parts = &elements[start..start + candidate.span];
+                result.push(self.create_key_value(parts, candidate));
+            }
+            index = next;
+        }
+
+        result
+    }
+}
+
+/// Smallest box containing both
+fn merge_bounding_boxes(a: &BoundingBox, b: &BoundingBox) -> BoundingBox {
+    let min_x = a.x.min(b.x);
+    let min_y = a.y.min(b.y);
+    let max_x = (a.x + a.width).max(b.x + b.width);
+    let max_y = (a.y + a.height).max(b.y + b.height);
+    BoundingBox {
+        x: min_x,
+        y: min_y,
+        width: max_x - min_x,
+        height: max_y - min_y,
+    }
+}
+
+impl ParseRule for KeyValueDetectionRule<'_> {
+    fn apply(&self, elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+        if !self.config.enabled {
+            return Ok(elements);
+        }
+
+        println!("🔑 APPLYING KEY-VALUE DETECTION...");
+        let input_count = elements.len();
+        let processed = self.detect_key_values(elements);
+        let pairs = processed
+            .iter()
+            .filter(|e| matches!(e.element_type, ParsedElementType::KeyValue { .. }))
+            .count();
+        println!(
+            "   ✅ Detected {} key-value pairs ({} → {} elements)",
+            pairs,
+            input_count,
+            processed.len()
+        );
+
+        Ok(processed)
+    }
+
+    fn name(&self) -> &str {
+        "KeyValueDetection"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{bbox, ParsedElementBuilder};
+
+    fn element(text: &str, x: f32, y: f32, width: f32) -> ParsedPdfElement {
+        ParsedElementBuilder::new(text).bbox(bbox(x, y, width, 12.0)).build()
+    }
+
+    #[test]
+    fn detects_inline_and_laid_out_pairs_in_runs() {
+        let elements = vec![
+            element("Note: the meeting starts at 10:30 sharp.", 72.0, 50.0, 300.0),
+            element("This Agreement is made between:", 72.0, 80.0, 300.0),
+            element("Seller: Acme Widgets Ltd", 72.0, 100.0, 200.0),
+            element("Buyer: Globex Corporation", 72.0, 114.0, 200.0),
+            element("Effective Date:", 72.0, 128.0, 80.0),
+            element("1 March 2024", 200.0, 128.0, 80.0),
+            element("The parties agree as follows.", 72.0, 160.0, 300.0),
+        ];
+        let config = KeyValueDetectionConfig::default();
+        let result = KeyValueDetectionRule::new(&config).apply(elements).unwrap();
+
+        let pairs: Vec<(&str, &str, &str)> = result
+            .iter()
+            .filter_map(|e| match &e.element_type {
+                ParsedElementType::KeyValue { key, value } => Some((key.as_str(), value.as_str(), e.text.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("Seller", "Acme Widgets Ltd", "Seller: Acme Widgets Ltd"),
+                ("Buyer", "Globex Corporation", "Buyer: Globex Corporation"),
+                ("Effective Date", "1 March 2024", "Effective Date: 1 March 2024"),
+            ]
+        );
+        // A lone "Note:" line and the sentence ending in a colon stay paragraphs
+        assert_eq!(result.len(), 6);
+        assert_eq!(result[0].element_type, ParsedElementType::Paragraph);
+        assert_eq!(result[1].element_type, ParsedElementType::Paragraph);
+        assert_eq!(result[4].bounding_box.width, 208.0);
+    }
+}
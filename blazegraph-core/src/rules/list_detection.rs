@@ -1,9 +1,11 @@
 use crate::config::{ListDetectionConfig, ListValidationConfig, SequentialNumberingConfig, MathematicalContextConfig, HyphenContextConfig};
-use crate::types::ListSequence;
+use crate::types::{BoundingBox, ListSequence, ParsedElementType, ParsedPdfElement};
 use anyhow::Result;
 use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
 
-use super::engine::{ParseRule, ParsedElement, ParsedElementType};
+use super::engine::ParseRule;
 
 // ============================================================================
 // LIST VALIDATION FRAMEWORK - False Positive Elimination
@@ -11,7 +13,7 @@ use super::engine::{ParseRule, ParsedElement, ParsedElementType};
 
 /// Trait for implementing list validation rules
 trait ListValidationRule {
-    fn validate(&self, list_items: &[ParsedElement]) -> bool;
+    fn validate(&self, list_items: &[ParsedPdfElement]) -> bool;
     fn name(&self) -> &str;
 }
 
@@ -19,7 +21,7 @@ trait ListValidationRule {
 struct MinimumSizeRule;
 
 impl ListValidationRule for MinimumSizeRule {
-    fn validate(&self, list_items: &[ParsedElement]) -> bool {
+    fn validate(&self, list_items: &[ParsedPdfElement]) -> bool {
         list_items.len() > 1
     }
 
@@ -32,7 +34,7 @@ impl ListValidationRule for MinimumSizeRule {
 struct FirstItemRule;
 
 impl ListValidationRule for FirstItemRule {
-    fn validate(&self, list_items: &[ParsedElement]) -> bool {
+    fn validate(&self, list_items: &[ParsedPdfElement]) -> bool {
         if let Some(first_item) = list_items.first() {
             self.starts_with_first_value(&first_item.text)
         } else {
@@ -96,7 +98,7 @@ impl FirstItemRule {
 struct ParentheticalContextRule;
 
 impl ListValidationRule for ParentheticalContextRule {
-    fn validate(&self, list_items: &[ParsedElement]) -> bool {
+    fn validate(&self, list_items: &[ParsedPdfElement]) -> bool {
         // Check if any item uses parenthetical numbering format
         let has_parenthetical = list_items.iter()
             .any(|item| self.is_parenthetical_number(&item.text));
@@ -125,7 +127,7 @@ impl ParentheticalContextRule {
         }
     }
 
-    fn first_item_is_parenthetical_one(&self, list_items: &[ParsedElement]) -> bool {
+    fn first_item_is_parenthetical_one(&self, list_items: &[ParsedPdfElement]) -> bool {
         if let Some(first_item) = list_items.first() {
             let text = first_item.text.trim();
             if let Ok(regex) = Regex::new(r"^\((\d+)\)") {
@@ -145,8 +147,8 @@ struct SequentialNumberingRule<'a> {
     config: &'a SequentialNumberingConfig,
 }
 
-impl<'a> ListValidationRule for SequentialNumberingRule<'a> {
-    fn validate(&self, list_items: &[ParsedElement]) -> bool {
+impl ListValidationRule for SequentialNumberingRule<'_> {
+    fn validate(&self, list_items: &[ParsedPdfElement]) -> bool {
         // Extract numbers from list items
         let numbers = self.extract_numbers(list_items);
         
@@ -168,7 +170,7 @@ impl<'a> SequentialNumberingRule<'a> {
         Self { config }
     }
     
-    fn extract_numbers(&self, list_items: &[ParsedElement]) -> Vec<u32> {
+    fn extract_numbers(&self, list_items: &[ParsedPdfElement]) -> Vec<u32> {
         let mut numbers = Vec::new();
         
         for item in list_items {
@@ -253,8 +255,8 @@ struct MathematicalContextRule<'a> {
     config: &'a MathematicalContextConfig,
 }
 
-impl<'a> ListValidationRule for MathematicalContextRule<'a> {
-    fn validate(&self, list_items: &[ParsedElement]) -> bool {
+impl ListValidationRule for MathematicalContextRule<'_> {
+    fn validate(&self, list_items: &[ParsedPdfElement]) -> bool {
         // Check if any list items use mathematical symbols
         let uses_math_symbols = list_items.iter()
             .any(|item| self.contains_mathematical_symbols(&item.text));
@@ -282,7 +284,7 @@ impl<'a> MathematicalContextRule<'a> {
             .any(|symbol| text.contains(symbol))
     }
     
-    fn is_mathematical_context(&self, list_items: &[ParsedElement]) -> bool {
+    fn is_mathematical_context(&self, list_items: &[ParsedPdfElement]) -> bool {
         // Look for mathematical context indicators in the text
         list_items.iter().any(|item| {
             let text = item.text.to_lowercase();
@@ -320,8 +322,8 @@ struct HyphenContextRule<'a> {
     config: &'a HyphenContextConfig,
 }
 
-impl<'a> ListValidationRule for HyphenContextRule<'a> {
-    fn validate(&self, list_items: &[ParsedElement]) -> bool {
+impl ListValidationRule for HyphenContextRule<'_> {
+    fn validate(&self, list_items: &[ParsedPdfElement]) -> bool {
         // Check if any items start with hyphen
         let uses_hyphens = list_items.iter()
             .any(|item| self.starts_with_hyphen(&item.text));
@@ -348,7 +350,7 @@ impl<'a> HyphenContextRule<'a> {
         text.trim().starts_with('-')
     }
     
-    fn validate_hyphen_context(&self, list_items: &[ParsedElement]) -> bool {
+    fn validate_hyphen_context(&self, list_items: &[ParsedPdfElement]) -> bool {
         match self.config.strategy.as_str() {
             "reject" => false, // Never allow hyphen lists
             
@@ -404,14 +406,14 @@ impl<'a> HyphenContextRule<'a> {
         }
     }
     
-    fn is_word_continuation_context(&self, list_items: &[ParsedElement]) -> bool {
+    fn is_word_continuation_context(&self, list_items: &[ParsedPdfElement]) -> bool {
         // Check if this appears to be word continuation context
         list_items.iter().any(|item| {
             self.looks_like_word_continuation(&item.text)
         })
     }
     
-    fn is_mathematical_minus_context(&self, list_items: &[ParsedElement]) -> bool {
+    fn is_mathematical_minus_context(&self, list_items: &[ParsedPdfElement]) -> bool {
         // Check for mathematical context with minus signs
         list_items.iter().any(|item| {
             self.looks_like_mathematical_minus(&item.text)
@@ -430,63 +432,26 @@ impl<'a> ListValidator<'a> {
     }
 
     /// Validate a list using all enabled validation rules
-    fn validate_list(&self, list_items: &[ParsedElement]) -> bool {
+    fn validate_list(&self, list_items: &[ParsedPdfElement]) -> bool {
         if !self.config.enabled {
             return true; // Validation disabled - accept all lists
         }
 
-        // Apply minimum size rule
-        if self.config.minimum_size_check {
-            let rule = MinimumSizeRule;
-            if !rule.validate(list_items) {
-                // println!("   ❌ List rejected by {}: {} items", rule.name(), list_items.len());
-                return false;
-            }
-        }
-
-        // Apply first item validation rule
-        if self.config.first_item_validation {
-            let rule = FirstItemRule;
-            if !rule.validate(list_items) {
-                if let Some(_first_item) = list_items.first() {
-                    // println!("   ❌ List rejected by {}: starts with '{}'", rule.name(), first_item.text.trim());
-                }
-                return false;
-            }
-        }
-
-        // Apply parenthetical context rule
-        if self.config.parenthetical_context_check {
-            let rule = ParentheticalContextRule;
-            if !rule.validate(list_items) {
-                // println!("   ❌ List rejected by {}: invalid parenthetical context", rule.name());
-                return false;
-            }
-        }
-
-        // Apply sequential numbering rule
-        if self.config.sequential_numbering_check {
-            let rule = SequentialNumberingRule::new(&self.config.sequential_numbering);
-            if !rule.validate(list_items) {
-                // println!("   ❌ List rejected by {}: sequence gap detected", rule.name());
-                return false;
-            }
-        }
-
-        // Apply mathematical context rule
-        if self.config.mathematical_context_check {
-            let rule = MathematicalContextRule::new(&self.config.mathematical_context);
-            if !rule.validate(list_items) {
-                // println!("   ❌ List rejected by {}: mathematical context detected", rule.name());
-                return false;
-            }
-        }
+        let sequential_numbering = SequentialNumberingRule::new(&self.config.sequential_numbering);
+        let mathematical_context = MathematicalContextRule::new(&self.config.mathematical_context);
+        let hyphen_context = HyphenContextRule::new(&self.config.hyphen_context);
+        let rules: [(bool, &dyn ListValidationRule); 6] = [
+            (self.config.minimum_size_check, &MinimumSizeRule),
+            (self.config.first_item_validation, &FirstItemRule),
+            (self.config.parenthetical_context_check, &ParentheticalContextRule),
+            (self.config.sequential_numbering_check, &sequential_numbering),
+            (self.config.mathematical_context_check, &mathematical_context),
+            (self.config.hyphen_context_check, &hyphen_context),
+        ];
 
-        // Apply hyphen context rule
-        if self.config.hyphen_context_check {
-            let rule = HyphenContextRule::new(&self.config.hyphen_context);
-            if !rule.validate(list_items) {
-                // println!("   ❌ List rejected by {}: invalid hyphen context", rule.name());
+        for (enabled, rule) in rules {
+            if enabled && !rule.validate(list_items) {
+                println!("   ❌ List rejected by {} ({} items)", rule.name(), list_items.len());
                 return false;
             }
         }
@@ -496,42 +461,60 @@ impl<'a> ListValidator<'a> {
     }
 }
 
+/// Ordinal list markers: `7.`, `7)`, `(7)`, `g.`, `G)`
+static ORDINAL_MARKER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\()?(\d+|[a-z]|[A-Z])([.)])").unwrap());
+
+/// Style and value of an ordinal marker; `("(#)", 7)` for "(7) ...", `("a.", 3)` for "c. ..."
+fn marker_ordinal(text: &str) -> Option<(String, u32)> {
+    let captures = ORDINAL_MARKER_REGEX.captures(text.trim())?;
+    let open = if captures.get(1).is_some() { "(" } else { "" };
+    let close = &captures[3];
+    let ordinal = &captures[2];
+    let (kind, value) = match ordinal.chars().next()? {
+        letter @ 'a'..='z' => ("a", (letter as u8 - b'a' + 1) as u32),
+        letter @ 'A'..='Z' => ("A", (letter as u8 - b'A' + 1) as u32),
+        _ => ("#", ordinal.parse().ok()?),
+    };
+    // "(7." isn't a marker style; only "(7)" pairs with an opening paren
+    if !open.is_empty() && close != ")" {
+        return None;
+    }
+    Some((format!("{open}{kind}{close}"), value))
+}
+
 // Enhanced List Detection Rule - config-driven with improved spatial detection
 pub struct ListDetectionRule<'a> {
     config: &'a ListDetectionConfig,
+    numbered_patterns: Vec<Regex>,
 }
 
 impl<'a> ListDetectionRule<'a> {
     pub fn new(config: &'a ListDetectionConfig) -> Self {
-        Self { config }
+        let numbered_patterns = config
+            .numbered_patterns
+            .iter()
+            .filter_map(|pattern| Regex::new(pattern).ok())
+            .collect();
+        Self {
+            config,
+            numbered_patterns,
+        }
     }
 
     /// Detect if text starts with a bullet point pattern based on config
     fn is_bullet_item(&self, text: &str) -> bool {
         let text = text.trim();
-
-        for pattern in &self.config.bullet_patterns {
-            if text.starts_with(pattern) {
-                return true;
-            }
-        }
-
-        false
+        self.config
+            .bullet_patterns
+            .iter()
+            .any(|pattern| text.starts_with(pattern.as_str()))
     }
 
     /// Detect if text starts with a numbered list pattern based on config
     fn is_numbered_item(&self, text: &str) -> bool {
         let text = text.trim();
-
-        for pattern_str in &self.config.numbered_patterns {
-            if let Ok(regex) = Regex::new(pattern_str) {
-                if regex.is_match(text) {
-                    return true;
-                }
-            }
-        }
-
-        false
+        self.numbered_patterns.iter().any(|regex| regex.is_match(text))
     }
 
     /// Check if text might be a list item based on config patterns
@@ -539,26 +522,38 @@ impl<'a> ListDetectionRule<'a> {
         self.is_bullet_item(text) || self.is_numbered_item(text)
     }
 
+    /// `next` picks up where `previous` left off in reading order but not on
+    /// the page: a later page, or higher up in a column to the right
+    fn crosses_flow_boundary(&self, previous: &ParsedPdfElement, next: &ParsedPdfElement) -> bool {
+        if next.page_number != previous.page_number {
+            return next.page_number > previous.page_number;
+        }
+        let (a, b) = (&previous.bounding_box, &next.bounding_box);
+        b.y + self.config.y_tolerance < a.y && b.x > a.x + a.width / 2.0
+    }
 
-    // NOTE: Old sequential list detection methods removed in favor of two-phase approach
-    // The two-phase implementation provides better handling of complex list structures
+    /// `next` carries the ordinal right after `previous`, in the same marker style
+    fn continues_numbering(&self, previous: &ParsedPdfElement, next: &ParsedPdfElement) -> bool {
+        match (marker_ordinal(&previous.text), marker_ordinal(&next.text)) {
+            (Some((style, value)), Some((next_style, next_value))) => {
+                style == next_style && next_value == value + 1
+            }
+            _ => false,
+        }
+    }
 
     /// Create a complete ListItem from marker and content parts
     fn create_list_item(
         &self,
-        elements: &[ParsedElement],
+        elements: &[ParsedPdfElement],
         marker_index: usize,
         content_indices: &[usize],
-    ) -> ParsedElement {
+    ) -> ParsedPdfElement {
         let marker_element = &elements[marker_index];
 
-        // Combine marker and content text efficiently
-        let marker_text = marker_element.text.trim();
-        let estimated_capacity = marker_text.len() + content_indices.len() * 100; // Rough estimate
-        let mut combined_text = String::with_capacity(estimated_capacity);
-
-        combined_text.push_str(marker_text);
-
+        let mut combined_text = marker_element.text.trim().to_string();
+        let mut token_count = marker_element.token_count;
+        let mut bounding_box = marker_element.bounding_box.clone();
         for &content_idx in content_indices {
             let content = &elements[content_idx];
             let content_text = content.text.trim();
@@ -568,291 +563,177 @@ impl<'a> ListDetectionRule<'a> {
                 }
                 combined_text.push_str(content_text);
             }
+            token_count += content.token_count;
+            // Content continuing on the next page/column doesn't stretch the box
+            if content.page_number == marker_element.page_number {
+                bounding_box = merge_bounding_boxes(&bounding_box, &content.bounding_box);
+            }
         }
 
-        // OWNERSHIP: Calculate bounding box for entire list item (marker + content)
-        let mut item_elements = vec![marker_element];
-        for &idx in content_indices {
-            item_elements.push(&elements[idx]);
-        }
-        let item_bbox = self.calculate_aggregate_bounding_box_from_refs(&item_elements);
-
-        ParsedElement {
+        ParsedPdfElement {
             element_type: ParsedElementType::ListItem,
             text: combined_text,
-            hierarchy_level: marker_element.hierarchy_level,
-            position: marker_element.position,
-            style_info: marker_element.style_info.clone(), // Strategic clone - style is small
-            bounding_box: item_bbox,
-            page_number: marker_element.page_number,
+            bounding_box,
+            token_count,
+            ..marker_element.clone()
         }
     }
 
-    /// Create a List container from multiple ListItem elements (if configured)
-    fn create_list_container(&self, list_items: Vec<ParsedElement>) -> ParsedElement {
-        if list_items.is_empty() {
-            panic!("Cannot create list container from empty items");
-        }
-
-        // Combine all list item texts efficiently
+    /// Create a List container from multiple ListItem elements
+    ///
+    /// A list continued across pages keeps its first page's number and the
+    /// bounding box of the items on that page.
+    fn create_list_container(&self, list_items: Vec<ParsedPdfElement>) -> ParsedPdfElement {
         let combined_text = list_items
             .iter()
-            .map(|item| item.text.as_str()) // OWNERSHIP: Borrow strings instead of cloning
+            .map(|item| item.text.as_str())
             .collect::<Vec<&str>>()
             .join("\n");
+        let token_count = list_items.iter().map(|item| item.token_count).sum();
 
-        // Use properties from the first item for the container
-        let first_item = &list_items[0];
+        let mut items = list_items.into_iter();
+        let first_item = items.next().expect("list container needs at least one item");
+        let bounding_box = items
+            .filter(|item| item.page_number == first_item.page_number)
+            .fold(first_item.bounding_box.clone(), |bbox, item| {
+                merge_bounding_boxes(&bbox, &item.bounding_box)
+            });
 
-        // OWNERSHIP: Calculate aggregate bounding box for the entire list
-        let aggregate_bbox = self.calculate_aggregate_bounding_box(&list_items);
-
-        ParsedElement {
+        ParsedPdfElement {
             element_type: ParsedElementType::List,
             text: combined_text,
-            hierarchy_level: first_item.hierarchy_level,
-            position: first_item.position,
-            style_info: first_item.style_info.clone(), // Strategic clone - style is small
-            bounding_box: aggregate_bbox,
-            page_number: first_item.page_number,
-        }
-    }
-
-    /// OWNERSHIP: Efficiently calculate aggregate bounding box from list items
-    fn calculate_aggregate_bounding_box(
-        &self,
-        list_items: &[ParsedElement],
-    ) -> Option<crate::types::BoundingBox> {
-        // Data flow: [ParsedElement] → filter(has_bbox) → aggregate(min/max coords) → BoundingBox
-        let mut min_x = f32::INFINITY;
-        let mut min_y = f32::INFINITY;
-        let mut max_x = f32::NEG_INFINITY;
-        let mut max_y = f32::NEG_INFINITY;
-        let mut found_any = false;
-
-        // Single-pass aggregation - efficient for large lists
-        for item in list_items {
-            if let Some(ref bbox) = item.bounding_box {
-                found_any = true;
-                min_x = min_x.min(bbox.x);
-                min_y = min_y.min(bbox.y);
-                max_x = max_x.max(bbox.x + bbox.width);
-                max_y = max_y.max(bbox.y + bbox.height);
-            }
-        }
-
-        if found_any {
-            Some(crate::types::BoundingBox {
-                x: min_x,
-                y: min_y,
-                width: max_x - min_x,
-                height: max_y - min_y,
-            })
-        } else {
-            None
-        }
-    }
-
-    /// OWNERSHIP: Helper for calculating bounding box from element references
-    fn calculate_aggregate_bounding_box_from_refs(
-        &self,
-        elements: &[&ParsedElement],
-    ) -> Option<crate::types::BoundingBox> {
-        let mut min_x = f32::INFINITY;
-        let mut min_y = f32::INFINITY;
-        let mut max_x = f32::NEG_INFINITY;
-        let mut max_y = f32::NEG_INFINITY;
-        let mut found_any = false;
-
-        // Single-pass aggregation - efficient for mixed element types
-        for element in elements {
-            if let Some(ref bbox) = element.bounding_box {
-                found_any = true;
-                min_x = min_x.min(bbox.x);
-                min_y = min_y.min(bbox.y);
-                max_x = max_x.max(bbox.x + bbox.width);
-                max_y = max_y.max(bbox.y + bbox.height);
-            }
-        }
-
-        if found_any {
-            Some(crate::types::BoundingBox {
-                x: min_x,
-                y: min_y,
-                width: max_x - min_x,
-                height: max_y - min_y,
-            })
-        } else {
-            None
+            bounding_box,
+            token_count,
+            ..first_item
         }
     }
 
     /// SANITY CHECK: Detect if a list item contains only a marker (bullet, number, etc.)
-    fn is_marker_only_list_item(&self, list_item: &ParsedElement) -> bool {
+    fn is_marker_only_list_item(&self, list_item: &ParsedPdfElement) -> bool {
         let text = list_item.text.trim();
-        
+
         // Check if it's just a bullet marker
-        for pattern in &self.config.bullet_patterns {
-            if text == pattern {
-                return true;
-            }
+        if self.config.bullet_patterns.iter().any(|pattern| text == pattern) {
+            return true;
         }
-        
-        // Check if it's just a numbered marker (e.g., "1.", "a)", etc.)
-        for pattern_str in &self.config.numbered_patterns {
-            if let Ok(regex) = regex::Regex::new(pattern_str) {
-                if regex.is_match(text) && text.len() <= 4 { // Short markers only
-                    return true;
-                }
-            }
-        }
-        
-        false
+
+        // Check if it's just a numbered marker (e.g., "1.", "a)", etc.) - short markers only
+        text.len() <= 4 && self.is_numbered_item(text)
     }
-    
+
     /// SANITY CHECK: Try to merge marker-only list item with adjacent content
     fn try_merge_with_adjacent_content(
         &self,
-        elements: &[ParsedElement],
+        elements: &[ParsedPdfElement],
         marker_index: usize,
-        consumed_indices: &mut std::collections::HashSet<usize>,
-        marker_list_item: &ParsedElement,
-    ) -> Option<ParsedElement> {
-        // Look for the next non-consumed element that could be content
-        for next_idx in (marker_index + 1)..elements.len() {
-            if consumed_indices.contains(&next_idx) {
-                continue;
-            }
-            
-            let next_element = &elements[next_idx];
-            
-            // Skip other list markers
-            if self.is_potential_list_item(&next_element.text) {
-                break;
-            }
-            
-            // Check if it's on roughly the same horizontal line
-            if self.are_on_same_horizontal_line(&marker_list_item, next_element) {
-                // Merge the content
-                let combined_text = format!("{} {}", 
-                    marker_list_item.text.trim(), 
-                    next_element.text.trim()
-                );
-                
-                // Mark the next element as consumed
-                consumed_indices.insert(next_idx);
-                
-                // Create enhanced list item with combined content
-                return Some(ParsedElement {
-                    element_type: ParsedElementType::ListItem,
-                    text: combined_text,
-                    hierarchy_level: marker_list_item.hierarchy_level,
-                    position: marker_list_item.position,
-                    style_info: marker_list_item.style_info.clone(),
-                    bounding_box: self.merge_bounding_boxes(&marker_list_item.bounding_box, &next_element.bounding_box),
-                    page_number: marker_list_item.page_number,
-                });
-            }
-            
-            // If we found an element but it's not on the same line, stop looking
-            break;
+        consumed_indices: &mut HashSet<usize>,
+        marker_list_item: &ParsedPdfElement,
+    ) -> Option<ParsedPdfElement> {
+        // Look at the next non-consumed element that could be content
+        let next_idx = ((marker_index + 1)..elements.len()).find(|idx| !consumed_indices.contains(idx))?;
+        let next_element = &elements[next_idx];
+
+        // Other list markers and content on another line aren't this item's text
+        if self.is_potential_list_item(&next_element.text)
+            || !self.are_on_same_horizontal_line(marker_list_item, next_element)
+        {
+            return None;
         }
-        
-        None
+
+        consumed_indices.insert(next_idx);
+        Some(ParsedPdfElement {
+            text: format!("{} {}", marker_list_item.text.trim(), next_element.text.trim()),
+            bounding_box: merge_bounding_boxes(&marker_list_item.bounding_box, &next_element.bounding_box),
+            token_count: marker_list_item.token_count + next_element.token_count,
+            ..marker_list_item.clone()
+        })
     }
-    
+
     /// Helper: Check if two elements are on roughly the same horizontal line
-    fn are_on_same_horizontal_line(&self, elem1: &ParsedElement, elem2: &ParsedElement) -> bool {
-        if let (Some(bbox1), Some(bbox2)) = (&elem1.bounding_box, &elem2.bounding_box) {
-            let y_diff = (bbox1.y - bbox2.y).abs();
-            y_diff <= self.config.y_tolerance
-        } else {
-            // Fallback: assume consecutive elements might be on same line
-            elem1.page_number == elem2.page_number
-        }
-    }
-    
-    /// Helper: Merge two bounding boxes
-    fn merge_bounding_boxes(
-        &self,
-        bbox1: &Option<crate::types::BoundingBox>,
-        bbox2: &Option<crate::types::BoundingBox>,
-    ) -> Option<crate::types::BoundingBox> {
-        match (bbox1, bbox2) {
-            (Some(b1), Some(b2)) => {
-                let min_x = b1.x.min(b2.x);
-                let min_y = b1.y.min(b2.y);
-                let max_x = (b1.x + b1.width).max(b2.x + b2.width);
-                let max_y = (b1.y + b1.height).max(b2.y + b2.height);
-                
-                Some(crate::types::BoundingBox {
-                    x: min_x,
-                    y: min_y,
-                    width: max_x - min_x,
-                    height: max_y - min_y,
-                })
-            }
-            (Some(b), None) | (None, Some(b)) => Some(b.clone()),
-            (None, None) => None,
-        }
+    fn are_on_same_horizontal_line(&self, elem1: &ParsedPdfElement, elem2: &ParsedPdfElement) -> bool {
+        elem1.page_number == elem2.page_number
+            && (elem1.bounding_box.y - elem2.bounding_box.y).abs() <= self.config.y_tolerance
     }
 
     /// PHASE 1: Find possible list sequences using regex-based detection
     /// This identifies regions that likely contain lists without expensive spatial calculations
-    fn find_possible_list_sequences(&self, elements: &[ParsedElement]) -> Vec<ListSequence> {
+    ///
+    /// Markers further apart than `sequence_lookahead_elements` normally start
+    /// a new sequence. When the gap is a page or column break (running
+    /// headers, footers, page numbers in between), the next marker still
+    /// joins if its number continues the sequence: item 8 at the top of page 4
+    /// follows item 7 at the bottom of page 3.
+    fn find_possible_list_sequences(&self, elements: &[ParsedPdfElement]) -> Vec<ListSequence> {
         let mut sequences = Vec::new();
         let mut current_sequence: Option<ListSequence> = None;
 
         for (i, element) in elements.iter().enumerate() {
-            if self.is_potential_list_item(&element.text) {
-                match &mut current_sequence {
-                    Some(sequence) => {
-                        // Check if this marker is within the lookahead distance of the last marker
-                        if let Some(&last_marker_index) = sequence.marker_indices.last() {
-                            let gap = i - last_marker_index;
-                            if gap <= self.config.sequence_lookahead_elements {
-                                // Still within the same sequence
-                                sequence.marker_indices.push(i);
-                                continue;
-                            }
-                        }
-                        
-                        // Too far from last marker - finalize current sequence and start new one
-                        sequence.end_index = sequence.marker_indices.last().cloned()
-                            .map(|idx| (idx + self.config.sequence_boundary_extension).min(elements.len() - 1))
-                            .unwrap_or(sequence.start_index);
-                        sequences.push(current_sequence.take().unwrap());
-                    }
-                    None => {
-                        // No current sequence - this is a potential start
-                    }
+            if !self.is_potential_list_item(&element.text) {
+                continue;
+            }
+
+            if let Some(sequence) = &mut current_sequence {
+                // Check if this marker is within the lookahead distance of the last marker
+                let last_marker_index = *sequence.marker_indices.last().unwrap_or(&sequence.start_index);
+                let gap = i - last_marker_index;
+                let last_marker = &elements[last_marker_index];
+                let continues_after_break = self.config.continue_across_breaks
+                    && gap <= self.config.continuation_lookahead_elements
+                    && self.crosses_flow_boundary(last_marker, element)
+                    && self.continues_numbering(last_marker, element);
+                if gap <= self.config.sequence_lookahead_elements || continues_after_break {
+                    // Still within the same sequence
+                    sequence.marker_indices.push(i);
+                    continue;
+                }
+
+                // Too far from last marker - finalize current sequence and start new one
+                if let Some(sequence) = current_sequence.take() {
+                    sequences.push(self.finalize_sequence(sequence, i));
                 }
-                
-                // Start new sequence
-                current_sequence = Some(ListSequence {
-                    start_index: i,
-                    end_index: i, // Will be updated when sequence ends
-                    marker_indices: vec![i],
-                });
             }
+
+            // Start new sequence
+            current_sequence = Some(ListSequence {
+                start_index: i,
+                end_index: i, // Will be updated when sequence ends
+                marker_indices: vec![i],
+            });
         }
 
         // Finalize any remaining sequence
-        if let Some(mut sequence) = current_sequence {
-            sequence.end_index = sequence.marker_indices.last().cloned()
-                .map(|idx| (idx + self.config.sequence_boundary_extension).min(elements.len() - 1))
-                .unwrap_or(sequence.start_index);
-            sequences.push(sequence);
+        if let Some(sequence) = current_sequence {
+            sequences.push(self.finalize_sequence(sequence, elements.len()));
         }
 
         sequences
     }
 
+    /// Extend a sequence past its last marker for boundary detection, stopping
+    /// before `limit` (the next sequence's first marker, or the element count)
+    fn finalize_sequence(&self, mut sequence: ListSequence, limit: usize) -> ListSequence {
+        sequence.end_index = sequence
+            .marker_indices
+            .last()
+            .map(|idx| (idx + self.config.sequence_boundary_extension).min(limit - 1))
+            .unwrap_or(sequence.start_index);
+        sequence
+    }
+
     /// PHASE 2: Process content within identified list sequences using spatial validation
     /// This focuses expensive spatial calculations only on regions likely to contain lists
-    fn process_list_sequence(&self, elements: &[ParsedElement], sequence: &ListSequence) -> Vec<ParsedElement> {
+    ///
+    /// Returns the list items and the indices of elements in the sequence's
+    /// range that no item took: page furniture between items continued across
+    /// a page/column break, and trailing elements past the last item. The
+    /// caller keeps those after the list.
+    fn process_list_sequence(
+        &self,
+        elements: &[ParsedPdfElement],
+        sequence: &ListSequence,
+    ) -> (Vec<ParsedPdfElement>, Vec<usize>) {
         let mut result = Vec::new();
-        let mut consumed_indices = std::collections::HashSet::new();
+        let mut consumed_indices = HashSet::new();
 
         // Process each marker in the sequence
         for (marker_idx, &global_marker_index) in sequence.marker_indices.iter().enumerate() {
@@ -860,71 +741,85 @@ impl<'a> ListDetectionRule<'a> {
                 continue;
             }
 
-            // Determine content end point for this marker
-            let content_end_index = if marker_idx + 1 < sequence.marker_indices.len() {
+            let content_indices = match sequence.marker_indices.get(marker_idx + 1) {
+                // Next item continues on another page/column: only the text
+                // flowing on from this marker belongs to it
+                Some(&next_marker_index)
+                    if self.crosses_flow_boundary(&elements[global_marker_index], &elements[next_marker_index]) =>
+                {
+                    self.collect_content_before_break(
+                        elements,
+                        global_marker_index,
+                        next_marker_index,
+                        &consumed_indices,
+                    )
+                }
                 // Not the last marker - content goes until next marker
-                sequence.marker_indices[marker_idx + 1]
-            } else {
+                Some(&next_marker_index) => self.collect_content_between_indices(
+                    elements,
+                    global_marker_index,
+                    next_marker_index,
+                    &consumed_indices,
+                ),
                 // Last marker - use enhanced boundary detection with y_gap analysis
-                self.find_last_item_boundary(elements, global_marker_index, sequence.end_index)
+                None => {
+                    let content_end_index =
+                        self.find_last_item_boundary(elements, global_marker_index, sequence.end_index);
+                    self.collect_content_between_indices(
+                        elements,
+                        global_marker_index,
+                        content_end_index,
+                        &consumed_indices,
+                    )
+                }
             };
 
-            // Collect content for this list item
-            let content_indices = self.collect_content_between_indices(
-                elements, 
-                global_marker_index, 
-                content_end_index, 
-                &consumed_indices
-            );
-
             // Mark indices as consumed
-            for &idx in &content_indices {
-                consumed_indices.insert(idx);
-            }
+            consumed_indices.extend(content_indices.iter().copied());
             consumed_indices.insert(global_marker_index);
 
             // Create complete list item
             let mut list_item = self.create_list_item(elements, global_marker_index, &content_indices);
-            
+
             // SANITY CHECK: If list item contains only marker, try to merge with next paragraph
-            // OWNERSHIP: Strategic cloning for error recovery - acceptable performance trade-off
             if self.is_marker_only_list_item(&list_item) {
                 if let Some(enhanced_item) = self.try_merge_with_adjacent_content(
-                    elements, 
-                    global_marker_index, 
-                    &mut consumed_indices, 
-                    &list_item
+                    elements,
+                    global_marker_index,
+                    &mut consumed_indices,
+                    &list_item,
                 ) {
                     list_item = enhanced_item;
                 }
             }
-            
+
             result.push(list_item);
         }
 
-        result
+        let leftover = (sequence.start_index..=sequence.end_index)
+            .filter(|idx| !consumed_indices.contains(idx))
+            .collect();
+        (result, leftover)
     }
 
     /// Helper: Find boundary for last list item using y_gap analysis
-    fn find_last_item_boundary(&self, elements: &[ParsedElement], marker_index: usize, sequence_end: usize) -> usize {
+    fn find_last_item_boundary(&self, elements: &[ParsedPdfElement], marker_index: usize, sequence_end: usize) -> usize {
         let marker_element = &elements[marker_index];
         let mut last_valid_index = marker_index;
 
-        for i in (marker_index + 1)..=sequence_end {
-            if i >= elements.len() {
-                break;
-            }
-            
-            let candidate = &elements[i];
-            
+        for (i, candidate) in elements
+            .iter()
+            .enumerate()
+            .take(sequence_end + 1)
+            .skip(marker_index + 1)
+        {
             // Check y-gap for boundary detection
-            if let (Some(marker_bbox), Some(candidate_bbox)) = (&marker_element.bounding_box, &candidate.bounding_box) {
-                let y_gap = (candidate_bbox.y - (marker_bbox.y + marker_bbox.height)).abs();
-                if y_gap > self.config.last_item_boundary_gap {
-                    break; // Found boundary
-                }
+            let marker_bbox = &marker_element.bounding_box;
+            let y_gap = (candidate.bounding_box.y - (marker_bbox.y + marker_bbox.height)).abs();
+            if y_gap > self.config.last_item_boundary_gap {
+                break; // Found boundary
             }
-            
+
             // Check if on same horizontal line (no horizontal tolerance needed)
             if self.are_on_same_horizontal_line(marker_element, candidate) {
                 last_valid_index = i;
@@ -939,162 +834,152 @@ impl<'a> ListDetectionRule<'a> {
     /// Helper: Collect content indices between start and end, respecting consumed indices
     fn collect_content_between_indices(
         &self,
-        elements: &[ParsedElement],
+        elements: &[ParsedPdfElement],
         start_index: usize,
         end_index: usize,
-        consumed_indices: &std::collections::HashSet<usize>
+        consumed_indices: &HashSet<usize>,
     ) -> Vec<usize> {
-        let mut content_indices = Vec::new();
-        
-        for i in (start_index + 1)..=end_index {
-            if i >= elements.len() || consumed_indices.contains(&i) {
-                continue;
-            }
-            
+        ((start_index + 1)..=end_index.min(elements.len().saturating_sub(1)))
+            .filter(|i| !consumed_indices.contains(i))
             // Skip other list markers
-            if self.is_potential_list_item(&elements[i].text) {
+            .filter(|&i| !self.is_potential_list_item(&elements[i].text))
+            .collect()
+    }
+
+    /// Helper: Content of an item whose successor is past a page/column break
+    ///
+    /// Takes the elements that flow on from the marker on its own page and
+    /// column, each starting within `y_tolerance` of the one above; the first
+    /// larger jump (a footer, a page number) ends the item.
+    fn collect_content_before_break(
+        &self,
+        elements: &[ParsedPdfElement],
+        marker_index: usize,
+        next_marker_index: usize,
+        consumed_indices: &HashSet<usize>,
+    ) -> Vec<usize> {
+        let marker = &elements[marker_index];
+        let mut bottom = marker.bounding_box.y + marker.bounding_box.height;
+        let mut content = Vec::new();
+
+        for (i, candidate) in elements
+            .iter()
+            .enumerate()
+            .take(next_marker_index)
+            .skip(marker_index + 1)
+        {
+            if consumed_indices.contains(&i) || self.is_potential_list_item(&candidate.text) {
                 continue;
             }
-            
-            content_indices.push(i);
+            if candidate.page_number != marker.page_number || self.crosses_flow_boundary(marker, candidate) {
+                break;
+            }
+            let bbox = &candidate.bounding_box;
+            if bbox.y - bottom > self.config.y_tolerance && !self.are_on_same_horizontal_line(marker, candidate) {
+                break;
+            }
+            bottom = bottom.max(bbox.y + bbox.height);
+            content.push(i);
         }
-        
-        content_indices
+
+        content
     }
 
     /// Enhanced list detection with three-phase processing for proper element order preservation
-    /// OWNERSHIP phase: Clear ownership patterns with strategic cloning only where needed
-    fn detect_and_group_lists(&self, elements: Vec<ParsedElement>) -> Vec<ParsedElement> {
+    fn detect_and_group_lists(&self, elements: Vec<ParsedPdfElement>) -> Vec<ParsedPdfElement> {
         // PHASE 1: Find possible list sequences using regex-based detection
         let sequences = self.find_possible_list_sequences(&elements);
-        
+
         if sequences.is_empty() {
-            // OWNERSHIP: No sequences found - return original elements (moved, no clone)
             return elements;
         }
-        
+
         // PHASE 2: Process sequences to create new list elements
+        let validator = ListValidator::new(&self.config.validation);
         let mut processed_results = Vec::new();
-        let mut consumed_ranges = Vec::new();
-        
+
         for sequence in sequences {
             // Process list sequence using spatial validation
-            let list_items = self.process_list_sequence(&elements, &sequence);
-            
+            let (list_items, leftover) = self.process_list_sequence(&elements, &sequence);
+
             // PHASE 2.5: List Validation - eliminate false positives
-            let validator = ListValidator::new(&self.config.validation);
-            let is_valid_list = validator.validate_list(&list_items);
-            
-            // Only proceed if list passes validation
-            if !list_items.is_empty() && is_valid_list {
-                let mut list_group = list_items;
-                let mut sequence_result = Vec::new();
-                self.finalize_list_group(&mut sequence_result, &mut list_group);
-                
-                // Track the range consumed by this sequence
-                consumed_ranges.push((sequence.start_index, sequence.end_index.min(elements.len() - 1)));
-                
-                // Add the processed results (could be one List container or multiple ListItems)
-                processed_results.extend(sequence_result);
+            if list_items.is_empty() || !validator.validate_list(&list_items) {
+                continue;
             }
+
+            let mut sequence_result = self.finalize_list_group(list_items);
+            // Elements in the range that aren't list text follow the list
+            sequence_result.extend(leftover.iter().map(|&idx| elements[idx].clone()));
+
+            let range = (sequence.start_index, sequence.end_index.min(elements.len() - 1));
+            processed_results.push((range, sequence_result));
         }
-        
+
         // PHASE 3: Reconstruct element stream in proper document order
-        self.preserve_element_order(&elements, processed_results, &consumed_ranges)
+        self.preserve_element_order(elements, processed_results)
     }
 
-    /// Helper function to finalize a group of list items without cloning
-    fn finalize_list_group(
-        &self,
-        result: &mut Vec<ParsedElement>,
-        current_list_items: &mut Vec<ParsedElement>,
-    ) {
-        if self.config.create_list_containers {
-            // Take ownership to avoid cloning, then create container
-            let items = std::mem::take(current_list_items);
-            let list_container = self.create_list_container(items);
-            result.push(list_container);
-
-            // If we need to preserve individual items, we would need to clone here
-            // But this is a rare configuration, so the optimization is still worthwhile
-            if self.config.preserve_list_items {
-                // In this case, we do need to clone since we consumed the items above
-                // This could be optimized further by restructuring the container creation
-                // For now, this is better than the previous version which always cloned
-                result.extend(
-                    self.create_individual_list_items_from_container(result.last().unwrap()),
-                );
-            }
+    /// Turn validated items into one List container, or keep them as ListItems
+    fn finalize_list_group(&self, list_items: Vec<ParsedPdfElement>) -> Vec<ParsedPdfElement> {
+        if !self.config.create_list_containers {
+            return list_items;
+        }
+        if self.config.preserve_list_items {
+            let mut result = vec![self.create_list_container(list_items.clone())];
+            result.extend(list_items);
+            result
         } else {
-            // Just move individual list items without cloning
-            result.append(current_list_items);
+            vec![self.create_list_container(list_items)]
         }
     }
 
-    /// Helper to extract individual items from a container (used only when preserve_list_items = true)
-    fn create_individual_list_items_from_container(
-        &self,
-        container: &ParsedElement,
-    ) -> Vec<ParsedElement> {
-        // This is a fallback for the rare preserve_list_items case
-        // In practice, most configs won't use this
-        container
-            .text
-            .split('\n')
-            .filter(|line| !line.trim().is_empty())
-            .map(|line| ParsedElement {
-                element_type: ParsedElementType::ListItem,
-                text: line.trim().to_string(),
-                hierarchy_level: container.hierarchy_level,
-                position: container.position,
-                style_info: container.style_info.clone(), // Still need clone here for rare case
-                bounding_box: container.bounding_box.clone(), // Still need clone here for rare case
-                page_number: container.page_number,
-            })
-            .collect()
-    }
-
     /// PHASE 3: Preserve element order by reconstructing the stream in proper document order
-    /// Following the established pattern from Element_Ordering_Design_Patterns.md
+    ///
+    /// Each consumed (start, end) range of the original stream is replaced by
+    /// the elements produced for it.
     fn preserve_element_order(
         &self,
-        original_elements: &[ParsedElement],
-        processed_results: Vec<ParsedElement>,
-        consumed_ranges: &[(usize, usize)]
-    ) -> Vec<ParsedElement> {
-        let mut result = Vec::new();
-        let mut original_idx = 0;
-        let mut processed_idx = 0;
-        
-        for &(range_start, range_end) in consumed_ranges {
-            // Add non-consumed elements before this range
-            while original_idx < range_start {
-                result.push(original_elements[original_idx].clone()); // Strategic clone - needed for order reconstruction
-                original_idx += 1;
+        original_elements: Vec<ParsedPdfElement>,
+        processed_results: Vec<((usize, usize), Vec<ParsedPdfElement>)>,
+    ) -> Vec<ParsedPdfElement> {
+        let mut result = Vec::with_capacity(original_elements.len());
+        let mut processed = processed_results.into_iter().peekable();
+        let mut skip_until = None;
+
+        for (idx, element) in original_elements.into_iter().enumerate() {
+            if skip_until.is_some_and(|range_end| idx <= range_end) {
+                continue;
             }
-            
-            // Add processed element(s) for this range
-            if processed_idx < processed_results.len() {
-                result.push(processed_results[processed_idx].clone()); // Strategic clone - could be optimized with Vec ownership redesign
-                processed_idx += 1;
+            if let Some(((range_start, range_end), replacement)) =
+                processed.next_if(|((range_start, _), _)| *range_start == idx)
+            {
+                result.extend(replacement);
+                skip_until = Some(range_end.max(range_start));
+                continue;
             }
-            
-            // Skip consumed original elements
-            original_idx = range_end + 1;
+            result.push(element);
         }
-        
-        // Add remaining non-consumed elements
-        while original_idx < original_elements.len() {
-            result.push(original_elements[original_idx].clone()); // Strategic clone - needed for order reconstruction
-            original_idx += 1;
-        }
-        
+
         result
     }
 }
 
-impl<'a> ParseRule for ListDetectionRule<'a> {
-    fn apply(&self, elements: Vec<ParsedElement>) -> Result<Vec<ParsedElement>> {
+/// Smallest box containing both
+fn merge_bounding_boxes(a: &BoundingBox, b: &BoundingBox) -> BoundingBox {
+    let min_x = a.x.min(b.x);
+    let min_y = a.y.min(b.y);
+    let max_x = (a.x + a.width).max(b.x + b.width);
+    let max_y = (a.y + a.height).max(b.y + b.height);
+    BoundingBox {
+        x: min_x,
+        y: min_y,
+        width: max_x - min_x,
+        height: max_y - min_y,
+    }
+}
+
+impl ParseRule for ListDetectionRule<'_> {
+    fn apply(&self, elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
         if !self.config.enabled {
             return Ok(elements);
         }
@@ -1134,3 +1019,62 @@ impl<'a> ParseRule for ListDetectionRule<'a> {
         "EnhancedListDetection"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{bbox, ParsedElementBuilder};
+
+    fn element(text: &str, page_number: u32, y: f32) -> ParsedPdfElement {
+        ParsedElementBuilder::new(text).bbox(bbox(72.0, y, 400.0, 12.0)).page(page_number).build()
+    }
+
+    #[test]
+    fn numbered_list_continues_across_page_break() {
+        let elements = vec![
+            element("Steps", 1, 560.0),
+            element("1. First step", 1, 600.0),
+            element("2. Second step", 1, 620.0),
+            element("that wraps onto a second line", 1, 634.0),
+            element("1", 1, 760.0),
+            element("Annual Report", 2, 30.0),
+            element("3. Third step", 2, 72.0),
+            element("After the list.", 2, 200.0),
+        ];
+        // Lookahead too short to bridge the footer and header on its own
+        let config = ListDetectionConfig {
+            sequence_lookahead_elements: 2,
+            ..ListDetectionConfig::default()
+        };
+
+        let result = ListDetectionRule::new(&config).apply(elements.clone()).unwrap();
+        let texts: Vec<(&ParsedElementType, &str)> =
+            result.iter().map(|e| (&e.element_type, e.text.as_str())).collect();
+        assert_eq!(
+            texts,
+            vec![
+                (&ParsedElementType::Paragraph, "Steps"),
+                (
+                    &ParsedElementType::List,
+                    "1. First step\n2. Second step that wraps onto a second line\n3. Third step"
+                ),
+                (&ParsedElementType::Paragraph, "1"),
+                (&ParsedElementType::Paragraph, "Annual Report"),
+                (&ParsedElementType::Paragraph, "After the list."),
+            ]
+        );
+        // The container stays anchored to the page the list starts on
+        assert_eq!(result[1].page_number, 1);
+        assert_eq!(result[1].bounding_box.y, 600.0);
+
+        // Without continuation the third item is a lone (rejected) list
+        let config = ListDetectionConfig {
+            continue_across_breaks: false,
+            ..config
+        };
+        let result = ListDetectionRule::new(&config).apply(elements).unwrap();
+        let list = result.iter().find(|e| e.element_type == ParsedElementType::List).unwrap();
+        assert!(!list.text.contains("Third step"));
+        assert!(result.iter().any(|e| e.text == "3. Third step"));
+    }
+}
@@ -1,21 +1,47 @@
 // Main rules module - delegates to semantic sub-modules
 // This file coordinates the rule system but actual implementations are in:
 // - engine.rs: RuleEngine and shared utilities
+// - explain.rs: Per-rule element diffs for pipeline dry runs
+// - boilerplate.rs: Text blocks repeated on many pages (notices, watermarks)
+// - bookmark_hierarchy.rs: PDF outline (bookmarks) as the section skeleton
+// - caption_association.rs: "Figure 3:" / "Table 2." captions attached to figures and tables
+// - header_footer_detection.rs: Running headers, footers and page numbers
+// - inline_styles.rs: Bold and italic runs kept inside merged text
 // - section_detection.rs: Font-based section detection
+// - size_enforcer.rs: Splits oversized elements into bounded chunks
+// - sentence_segmentation.rs: Abbreviation-aware sentence splitting, sentences as paragraph children
+// - list_detection.rs: Bullet and numbered list detection
+// - key_value_detection.rs: "Term: definition" and label/value pairs
+// - quote_detection.rs: Block quotes and callout boxes
 // - pattern_detection.rs: Pattern-based section promotion
+// - provenance.rs: Source elements and rules recorded on each element
 // - spatial_clustering.rs: Spatial clustering and style analysis
+// - spatial_index.rs: Per-page spatial index for neighbour queries
+// - table_detection.rs: Tables from row/column alignment of text runs
 // - validation.rs: Final validation and cleanup
 
 // Import sub-modules directly - they are in the rules/ directory
+pub mod boilerplate;
+pub mod bookmark_hierarchy;
+pub mod caption_association;
 pub mod engine;
+pub mod explain;
+pub mod header_footer_detection;
+pub mod inline_styles;
+pub mod key_value_detection;
+pub mod list_detection;
+pub mod provenance;
+pub mod quote_detection;
 pub mod section_detection;
+pub mod sentence_segmentation;
+pub mod size_enforcer;
 pub mod spatial_clustering;
+pub mod spatial_index;
+pub mod table_detection;
 pub mod validation;
 
 // Disabled modules (will be rewritten):
-// pub mod list_detection;
 // pub mod pattern_detection;
-// pub mod size_enforcer;
 
 // Re-export everything for backwards compatibility
 pub use engine::*;
\ No newline at end of file
@@ -0,0 +1,150 @@
+//! Per-element provenance
+//!
+//! With `provenance: true`, every element entering the rules records the
+//! TextElements it was converted from (every row, for a table built by base
+//! conversion). After each rule the elements before and after it are matched
+//! as `rules::explain` matches them, and provenance is carried forward:
+//!
+//! - an element the rule left alone keeps its own;
+//! - a merged element unites its sources' elements, pages and rules;
+//! - the parts of a split, and retyped or rewritten elements, inherit their
+//!   source's;
+//! - an element with no source starts empty.
+//!
+//! Every element the rule touched also gets the rule's name appended. The
+//! graph builder copies the result onto the node built from the element, so
+//! a node that looks wrong can be traced to the rules that shaped it and
+//! the TextElements (as written by `--dump-stages`) it came from.
+
+use super::explain::{diff_elements, ElementChange};
+use crate::types::{ParsedPdfElement, PdfTextElement, Provenance};
+
+/// Record the source TextElements of each base-conversion element
+pub fn seed_provenance(elements: &mut [ParsedPdfElement], text_elements: &[PdfTextElement]) {
+    // Base conversion keeps the TextElements' order and `position` is an
+    // element's first source. The non-blank ones up to the next element's
+    // position are table rows folded into it.
+    let starts: Vec<usize> = elements.iter().map(|e| e.position).collect();
+    for (k, element) in elements.iter_mut().enumerate() {
+        let end = starts.get(k + 1).copied().unwrap_or(text_elements.len());
+        let source_elements: Vec<usize> = (element.position..end)
+            .filter(|&i| i == element.position || !text_elements[i].text.trim().is_empty())
+            .collect();
+        let mut pages: Vec<u32> = source_elements.iter().map(|&i| text_elements[i].page_number).collect();
+        pages.sort_unstable();
+        pages.dedup();
+        element.provenance = Some(Provenance {
+            source_elements,
+            pages,
+            rules: Vec::new(),
+        });
+    }
+}
+
+/// Carry provenance across `rule` from the elements before it to those after
+pub fn trace_provenance(rule: &str, before: &[ParsedPdfElement], after: &mut [ParsedPdfElement]) {
+    let (changes, unchanged) = diff_elements(before, after);
+    for (i, j) in unchanged {
+        after[j].provenance = before[i].provenance.clone();
+    }
+    for change in changes {
+        match change {
+            ElementChange::Created { after: created } => {
+                after[created.index].provenance = Some(derived(rule, []));
+            }
+            ElementChange::Removed { .. } => {}
+            ElementChange::Merged { before: sources, after: merged } => {
+                after[merged.index].provenance = Some(derived(rule, sources.iter().map(|s| &before[s.index])));
+            }
+            ElementChange::Split { before: source, after: parts } => {
+                let provenance = derived(rule, [&before[source.index]]);
+                for part in parts {
+                    after[part.index].provenance = Some(provenance.clone());
+                }
+            }
+            ElementChange::Retyped { before: source, after: changed }
+            | ElementChange::Modified { before: source, after: changed } => {
+                after[changed.index].provenance = Some(derived(rule, [&before[source.index]]));
+            }
+        }
+    }
+}
+
+/// The provenance of an element `rule` built from `sources`
+fn derived<'a>(rule: &str, sources: impl IntoIterator<Item = &'a ParsedPdfElement>) -> Provenance {
+    let mut provenance = Provenance::default();
+    for source in sources.into_iter().filter_map(|s| s.provenance.as_ref()) {
+        provenance.source_elements.extend(&source.source_elements);
+        provenance.pages.extend(&source.pages);
+        // The first source's history, then rules only the others went through
+        for name in &source.rules {
+            if !provenance.rules.contains(name) {
+                provenance.rules.push(name.clone());
+            }
+        }
+    }
+    provenance.source_elements.sort_unstable();
+    provenance.source_elements.dedup();
+    provenance.pages.sort_unstable();
+    provenance.pages.dedup();
+    if provenance.rules.last().map(String::as_str) != Some(rule) {
+        provenance.rules.push(rule.to_string());
+    }
+    provenance
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{ParsingConfig, PipelineConfig};
+    use crate::rules::{ParseRule, RuleContext, RuleEngine};
+    use crate::test_utils::text_elements;
+    use crate::types::{ParsedElementType, ParsedPdfElement, StyleData};
+    use anyhow::Result;
+
+    struct MergeFirstTwo;
+
+    impl ParseRule for MergeFirstTwo {
+        fn apply(&self, mut elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+            let second = elements.remove(1);
+            elements[0].text = format!("{} {}", elements[0].text, second.text);
+            elements[0].bounding_box.height += 14.0;
+            elements[2].element_type = ParsedElementType::Section;
+            Ok(elements)
+        }
+
+        fn name(&self) -> &str {
+            "MergeFirstTwo"
+        }
+    }
+
+    #[test]
+    fn elements_record_their_sources_and_rules() {
+        let mut engine = RuleEngine::new().unwrap();
+        engine
+            .register_rule("MergeFirstTwo", |_: &RuleContext| Ok(Box::new(MergeFirstTwo) as Box<dyn ParseRule>))
+            .unwrap();
+        let config = ParsingConfig {
+            pipeline: PipelineConfig::from_rule_names(&["MergeFirstTwo"]),
+            provenance: true,
+            ..ParsingConfig::default()
+        };
+
+        let mut elements = text_elements(&["Introduction", "to the terms.", "", "Body text.", "Heading"]);
+        elements[3].page_number = 2;
+        elements[4].page_number = 2;
+        let report = engine.explain(&elements, &StyleData::default(), &config).unwrap();
+        let provenance: Vec<_> = report.elements.iter().map(|e| e.provenance.clone().unwrap()).collect();
+
+        assert_eq!(provenance[0].source_elements, [0, 1]);
+        assert_eq!(provenance[0].rules, ["MergeFirstTwo"]);
+        assert_eq!((provenance[1].source_elements.as_slice(), provenance[1].rules.len()), (&[3][..], 0));
+        assert_eq!(provenance[2].pages, [2]);
+        assert_eq!(provenance[2].rules, ["MergeFirstTwo"]);
+
+        // Off by default
+        let report = engine
+            .explain(&elements, &StyleData::default(), &ParsingConfig { provenance: false, ..config })
+            .unwrap();
+        assert!(report.elements.iter().all(|e| e.provenance.is_none()));
+    }
+}
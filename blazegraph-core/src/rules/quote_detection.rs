@@ -0,0 +1,298 @@
+use super::engine::ParseRule;
+use crate::config::QuoteDetectionConfig;
+use crate::types::{BoundingBox, ParsedElementType, ParsedPdfElement};
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashMap;
+
+const OPENING_QUOTES: [char; 6] = ['"', '“', '„', '«', '‘', '\''];
+
+/// Block quote and callout detection
+///
+/// Indented blocks are measured against each page's body margins. An indented
+/// block set in italics or opening with a quotation mark becomes a `Quote`
+/// (a trailing "— Name" line is kept as its attribution). An indented block
+/// that is inset on both sides and set apart by blank space (boxed text), or
+/// that opens with a label such as "Note:", becomes a `Callout`. Without this,
+/// both merge into the surrounding paragraphs during spatial clustering.
+pub struct QuoteDetectionRule<'a> {
+    config: &'a QuoteDetectionConfig,
+    attribution_patterns: Vec<Regex>,
+}
+
+/// Left and right edges of a page's body text
+#[derive(Debug, Clone, Copy)]
+struct BodyMargins {
+    left: f32,
+    right: f32,
+}
+
+impl<'a> QuoteDetectionRule<'a> {
+    pub fn new(config: &'a QuoteDetectionConfig) -> Self {
+        let attribution_patterns = config
+            .attribution_patterns
+            .iter()
+            .filter_map(|pattern| Regex::new(pattern).ok())
+            .collect();
+        Self {
+            config,
+            attribution_patterns,
+        }
+    }
+
+    /// Most common left edge (to the point) and 90th-percentile right edge per page
+    fn body_margins(elements: &[ParsedPdfElement]) -> HashMap<u32, BodyMargins> {
+        let mut lefts: HashMap<u32, HashMap<i32, usize>> = HashMap::new();
+        let mut rights: HashMap<u32, Vec<f32>> = HashMap::new();
+        for element in elements {
+            if element.element_type != ParsedElementType::Paragraph {
+                continue;
+            }
+            let bbox = &element.bounding_box;
+            *lefts
+                .entry(element.page_number)
+                .or_default()
+                .entry(bbox.x.round() as i32)
+                .or_default() += 1;
+            rights.entry(element.page_number).or_default().push(bbox.x + bbox.width);
+        }
+
+        lefts
+            .into_iter()
+            .filter_map(|(page, counts)| {
+                let (left, _) = counts.into_iter().max_by_key(|&(x, count)| (count, -x))?;
+                let mut page_rights = rights.remove(&page)?;
+                page_rights.sort_by(f32::total_cmp);
+                let right = page_rights[(page_rights.len() - 1) * 9 / 10];
+                Some((page, BodyMargins { left: left as f32, right }))
+            })
+            .collect()
+    }
+
+    fn is_indented(&self, element: &ParsedPdfElement, margins: Option<&BodyMargins>) -> bool {
+        element.element_type == ParsedElementType::Paragraph
+            && margins.is_some_and(|m| element.bounding_box.x - m.left >= self.config.min_indent)
+    }
+
+    /// Next line of the same block: same page, at most a line's height below
+    fn continues_block(previous: &ParsedPdfElement, next: &ParsedPdfElement) -> bool {
+        let (a, b) = (&previous.bounding_box, &next.bounding_box);
+        next.page_number == previous.page_number && b.y - (a.y + a.height) <= a.height.max(1.0)
+    }
+
+    fn attribution(&self, line: &str) -> Option<String> {
+        self.attribution_patterns.iter().find_map(|regex| {
+            let captures = regex.captures(line.trim())?;
+            captures.get(1).map(|name| name.as_str().trim().to_string())
+        })
+    }
+
+    fn callout_label(&self, line: &str) -> Option<String> {
+        let line = line.trim();
+        self.config.callout_labels.iter().find_map(|label| {
+            let head = line.get(..label.len())?;
+            let rest = &line[label.len()..];
+            (head.eq_ignore_ascii_case(label) && (rest.is_empty() || rest.starts_with(':'))).then(|| label.clone())
+        })
+    }
+
+    fn classify_block(
+        &self,
+        elements: &[ParsedPdfElement],
+        start: usize,
+        end: usize,
+        margins: &BodyMargins,
+    ) -> Option<ParsedElementType> {
+        let block = &elements[start..end];
+        let first_line = block[0].text.trim_start();
+
+        if self.config.detect_quotes {
+            let italic_lines = block
+                .iter()
+                .filter(|e| e.style_info.font_style.to_lowercase().contains("italic"))
+                .count();
+            if first_line.starts_with(OPENING_QUOTES) || italic_lines * 2 > block.len() {
+                let attribution = (block.len() > 1)
+                    .then(|| self.attribution(&block[block.len() - 1].text))
+                    .flatten();
+                return Some(ParsedElementType::Quote { attribution });
+            }
+        }
+
+        if self.config.detect_callouts {
+            let label = self.callout_label(first_line);
+            let inset_both_sides = block
+                .iter()
+                .all(|e| margins.right - (e.bounding_box.x + e.bounding_box.width) >= self.config.min_indent);
+            let gap_above = start
+                .checked_sub(1)
+                .map(|i| &elements[i])
+                .filter(|e| e.page_number == block[0].page_number)
+                .map(|e| block[0].bounding_box.y - (e.bounding_box.y + e.bounding_box.height));
+            let last = &block[block.len() - 1];
+            let gap_below = elements
+                .get(end)
+                .filter(|e| e.page_number == last.page_number)
+                .map(|e| e.bounding_box.y - (last.bounding_box.y + last.bounding_box.height));
+            let set_apart = [gap_above, gap_below]
+                .iter()
+                .all(|gap| gap.is_none_or(|gap| gap >= self.config.isolation_gap));
+            if label.is_some() || (inset_both_sides && set_apart) {
+                return Some(ParsedElementType::Callout { label });
+            }
+        }
+
+        None
+    }
+
+    fn merge_block(&self, block: &[ParsedPdfElement], element_type: ParsedElementType) -> ParsedPdfElement {
+        let first = &block[0];
+        let text = block
+            .iter()
+            .map(|e| e.text.trim())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let bounding_box = block[1..]
+            .iter()
+            .fold(first.bounding_box.clone(), |bbox, e| merge_bounding_boxes(&bbox, &e.bounding_box));
+
+        ParsedPdfElement {
+            element_type,
+            text,
+            bounding_box,
+            token_count: block.iter().map(|e| e.token_count).sum(),
+            ..first.clone()
+        }
+    }
+
+    fn detect_blocks(&self, elements: Vec<ParsedPdfElement>) -> Vec<ParsedPdfElement> {
+        let margins = Self::body_margins(&elements);
+        let mut result = Vec::with_capacity(elements.len());
+        let mut index = 0;
+
+        while index < elements.len() {
+            let page_margins = margins.get(&elements[index].page_number);
+            let Some(page_margins) = page_margins.filter(|_| self.is_indented(&elements[index], page_margins)) else {
+                result.push(elements[index].clone());
+                index += 1;
+                continue;
+            };
+
+            // Extend over the following indented lines of the same block
+            let mut end = index + 1;
+            while end < elements.len()
+                && self.is_indented(&elements[end], Some(page_margins))
+                && Self::continues_block(&elements[end - 1], &elements[end])
+            {
+                end += 1;
+            }
+
+            match self.classify_block(&elements, index, end, page_margins) {
+                Some(element_type) => result.push(self.merge_block(&elements[index..end], element_type)),
+                None => result.extend(elements[index..end].iter().cloned()),
+            }
+            index = end;
+        }
+
+        result
+    }
+}
+
+/// Smallest box containing both
+fn merge_bounding_boxes(a: &BoundingBox, b: &BoundingBox) -> BoundingBox {
+    let min_x = a.x.min(b.x);
+    let min_y = a.y.min(b.y);
+    let max_x = (a.x + a.width).max(b.x + b.width);
+    let max_y = (a.y + a.height).max(b.y + b.height);
+    BoundingBox {
+        x: min_x,
+        y: min_y,
+        width: max_x - min_x,
+        height: max_y - min_y,
+    }
+}
+
+impl ParseRule for QuoteDetectionRule<'_> {
+    fn apply(&self, elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+        if !self.config.enabled || !(self.config.detect_quotes || self.config.detect_callouts) {
+            return Ok(elements);
+        }
+
+        println!("💬 APPLYING QUOTE/CALLOUT DETECTION...");
+        let processed = self.detect_blocks(elements);
+        let quotes = processed
+            .iter()
+            .filter(|e| matches!(e.element_type, ParsedElementType::Quote { .. }))
+            .count();
+        let callouts = processed
+            .iter()
+            .filter(|e| matches!(e.element_type, ParsedElementType::Callout { .. }))
+            .count();
+        println!("   ✅ Detected {} quotes and {} callouts", quotes, callouts);
+
+        Ok(processed)
+    }
+
+    fn name(&self) -> &str {
+        "QuoteDetection"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{bbox, FontClassBuilder, ParsedElementBuilder};
+
+    fn line(text: &str, x: f32, y: f32, width: f32) -> ParsedPdfElement {
+        ParsedElementBuilder::new(text).bbox(bbox(x, y, width, 12.0)).build()
+    }
+
+    fn italic(mut element: ParsedPdfElement) -> ParsedPdfElement {
+        element.style_info = FontClassBuilder::new("f2").italic().build();
+        element
+    }
+
+    #[test]
+    fn detects_quotes_with_attribution_and_boxed_callouts() {
+        let elements = vec![
+            line("Body text runs the full width of the column here,", 72.0, 100.0, 468.0),
+            line("and continues on the next line before a quotation.", 72.0, 114.0, 468.0),
+            italic(line("Imagination is more important than knowledge,", 108.0, 132.0, 396.0)),
+            italic(line("for knowledge is limited.", 108.0, 146.0, 200.0)),
+            line("— Albert Einstein", 300.0, 160.0, 100.0),
+            line("The discussion resumes at full width after the quote,", 72.0, 180.0, 468.0),
+            line("Keep backups of the configuration directory before", 108.0, 220.0, 380.0),
+            line("upgrading between major versions.", 108.0, 234.0, 240.0),
+            line("More body text follows the boxed aside at full width.", 72.0, 270.0, 468.0),
+            line("Final body line, also running across the whole column.", 72.0, 284.0, 468.0),
+        ];
+        let config = QuoteDetectionConfig::default();
+        let result = QuoteDetectionRule::new(&config).apply(elements.clone()).unwrap();
+
+        let types: Vec<&ParsedElementType> = result.iter().map(|e| &e.element_type).collect();
+        assert_eq!(result.len(), 7);
+        assert_eq!(
+            types[2],
+            &ParsedElementType::Quote {
+                attribution: Some("Albert Einstein".to_string())
+            }
+        );
+        assert_eq!(
+            result[2].text,
+            "Imagination is more important than knowledge, for knowledge is limited. — Albert Einstein"
+        );
+        assert_eq!(types[4], &ParsedElementType::Callout { label: None });
+        assert!(types
+            .iter()
+            .enumerate()
+            .all(|(i, t)| i == 2 || i == 4 || **t == ParsedElementType::Paragraph));
+
+        // Profiles can turn callouts off (indented legal clauses stay paragraphs)
+        let config = QuoteDetectionConfig {
+            detect_callouts: false,
+            ..QuoteDetectionConfig::default()
+        };
+        let result = QuoteDetectionRule::new(&config).apply(elements).unwrap();
+        assert_eq!(result.len(), 8);
+    }
+}
@@ -1,8 +1,10 @@
 use super::engine::{FontSizeAnalysis, ParseRule, RuleEngine};
 use crate::config::{ParsingConfig, SectionAndHierarchyConfig};
+use crate::pattern_packs::SectionPatterns;
 use crate::types::*;
 use crate::types::{DocumentAnalysis, PdfTextElement, StyleData};
 use anyhow::Result;
+use std::collections::{BTreeSet, HashMap};
 
 // SectionAndHierarchyDetectionRule - detects sections and assigns contextual hierarchy levels to all elements
 pub struct SectionAndHierarchyDetectionRule<'a> {
@@ -12,6 +14,10 @@ pub struct SectionAndHierarchyDetectionRule<'a> {
     document_analysis: &'a DocumentAnalysis,
     font_size_analysis: &'a FontSizeAnalysis,
     _style_data: &'a StyleData,
+    /// The configured `pattern_pack`, if any
+    pack: Option<SectionPatterns>,
+    /// Pack levels used in this document, renumbered from 1
+    pack_levels: HashMap<u32, u32>,
 }
 
 impl<'a> SectionAndHierarchyDetectionRule<'a> {
@@ -22,15 +28,24 @@ impl<'a> SectionAndHierarchyDetectionRule<'a> {
         document_analysis: &'a DocumentAnalysis,
         font_size_analysis: &'a FontSizeAnalysis,
         style_data: &'a StyleData,
-    ) -> Self {
-        Self {
+    ) -> Result<Self> {
+        let pack = SectionPatterns::from_config(config)?;
+        let used_levels: BTreeSet<u32> = pack
+            .iter()
+            .flat_map(|pack| text_elements.iter().filter_map(|e| pack.heading_level(&e.text).flatten()))
+            .collect();
+        let pack_levels = used_levels.into_iter().zip(1..).collect();
+
+        Ok(Self {
             _engine: engine,
             text_elements,
             config,
             document_analysis,
             font_size_analysis,
             _style_data: style_data,
-        }
+            pack,
+            pack_levels,
+        })
     }
 }
 
@@ -57,6 +72,8 @@ impl<'a> ParseRule for SectionAndHierarchyDetectionRule<'a> {
                         reading_order: text_element.reading_order,
                         bookmark_match: text_element.bookmark_match.clone(),
                         token_count: text_element.token_count, // Use pre-calculated token count
+                        provenance: None,
+                        style_runs: Vec::new(),
                     }
                 })
                 .collect()
@@ -69,8 +86,20 @@ impl<'a> ParseRule for SectionAndHierarchyDetectionRule<'a> {
         let mut processed_elements = Vec::new();
 
         for element in input_elements {
-            // Find corresponding TextElement for style analysis
-            let text_element = self.text_elements.get(element.position);
+            // Find corresponding TextElement for style analysis (a bold
+            // header row doesn't make a table a section, nor a bold running
+            // title or caption label a heading; figures have no text style at all)
+            let text_element = self.text_elements.get(element.position).filter(|_| {
+                !matches!(
+                    element.element_type,
+                    ParsedElementType::Table(_)
+                        | ParsedElementType::Figure { .. }
+                        | ParsedElementType::Caption
+                        | ParsedElementType::Header
+                        | ParsedElementType::Footer
+                        | ParsedElementType::Sentence
+                )
+            });
 
             if let Some(text_elem) = text_element {
                 let (new_element_type, new_hierarchy_level) = self
@@ -121,6 +150,46 @@ impl<'a> SectionAndHierarchyDetectionRule<'a> {
         current_element: &ParsedPdfElement,
         hierarchy_context: &mut HierarchyContext,
     ) -> (ParsedElementType, u32) {
+        // Headings whose level the source format declares (e.g. DOCX heading
+        // styles) skip the font-size heuristics entirely
+        if element.paragraph_role() == Some("heading") {
+            if let Some(level) = element.paragraph_level() {
+                let contextual_level = hierarchy_context.update_for_explicit_level(
+                    level,
+                    element.style_info.font_size,
+                    &self.config.section_and_hierarchy,
+                );
+                return (ParsedElementType::Section, contextual_level);
+            }
+            // Level-less heading hints (e.g. color_rules) still make a
+            // section; the level comes from font size as usual
+            let contextual_level = hierarchy_context
+                .update_for_section(element.style_info.font_size, &self.config.section_and_hierarchy);
+            return (ParsedElementType::Section, contextual_level);
+        }
+
+        // Text tagged as a link is never a header, however it is styled
+        if element.paragraph_role() == Some("link") {
+            return (current_element.element_type.clone(), hierarchy_context.get_content_level());
+        }
+
+        // Localized pattern packs name headings outright; packs that rank
+        // their patterns also fix the level
+        if element.style_info.font_size >= self.config.section_and_hierarchy.min_header_size {
+            if let Some(level) = self.pack.as_ref().and_then(|pack| pack.heading_level(&element.text)) {
+                let font_size = element.style_info.font_size;
+                let contextual_level = match level.and_then(|level| self.pack_levels.get(&level)) {
+                    Some(&rank) => hierarchy_context.update_for_explicit_level(
+                        rank,
+                        font_size,
+                        &self.config.section_and_hierarchy,
+                    ),
+                    None => hierarchy_context.update_for_section(font_size, &self.config.section_and_hierarchy),
+                };
+                return (ParsedElementType::Section, contextual_level);
+            }
+        }
+
         // Check if this element is a header based on font size and style
         let is_header = {
             let font_size = element.style_info.font_size;
@@ -274,6 +343,26 @@ impl HierarchyContext {
         new_level
     }
 
+    /// Update context for a section with a declared heading level (1-based),
+    /// mapped relative to `starting_section_level`
+    pub fn update_for_explicit_level(
+        &mut self,
+        level: u32,
+        font_size: f32,
+        config: &SectionAndHierarchyConfig,
+    ) -> u32 {
+        let mut new_level = (config.starting_section_level + level.max(1) - 1).max(1);
+        if config.enforce_max_depth {
+            new_level = new_level.min(config.max_depth.max(1));
+        }
+
+        self.current_level = new_level;
+        self.level_font_sizes.resize(new_level as usize, 0.0);
+        self.level_font_sizes[new_level as usize - 1] = font_size;
+        self.previous_section_font_size = Some(font_size);
+        new_level
+    }
+
     /// Find the appropriate level for a font size when stepping back up
     fn find_appropriate_level_for_font_size(
         &self,
@@ -0,0 +1,270 @@
+//! Sentence segmentation
+//!
+//! Some consumers (citation, claim extraction, embedding at sentence level)
+//! need finer pieces than paragraphs. The SentenceSegmentation rule keeps
+//! each paragraph as it is and emits its sentences right after it, one
+//! hierarchy level below, so the graph builder makes them the paragraph's
+//! children.
+//!
+//! A `.`, `!` or `?` ends a sentence only when the next word starts like
+//! one (a capital, a digit, an opening quote or bracket), and a period not
+//! when it belongs to a known abbreviation ("Dr.", "Fig.", "e.g."), an
+//! initial ("J.") or an acronym ("U.S."). Decimals and section numbers
+//! ("1.5", "4.2.1") have no space after the period and never split. SizeEnforcer uses the
+//! same tokenizer when it splits between sentences.
+
+use super::engine::ParseRule;
+use crate::config::SentenceSegmentationConfig;
+use crate::preprocessors::pdf::xhtml_parser::estimate_token_count;
+use crate::types::{BoundingBox, ParsedElementType, ParsedPdfElement};
+use anyhow::Result;
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// Abbreviations that don't end a sentence, lowercase
+const ABBREVIATIONS: &[&str] = &[
+    "mr.", "mrs.", "ms.", "dr.", "prof.", "sr.", "jr.", "st.", "rev.", "hon.", "gen.", "col.", "capt.", "lt.",
+    "fig.", "figs.", "no.", "nos.", "vol.", "vols.", "p.", "pp.", "ch.", "sec.", "art.", "para.", "ref.", "eq.",
+    "e.g.", "i.e.", "cf.", "vs.", "al.", "approx.", "est.", "inc.", "ltd.", "co.", "corp.", "jan.", "feb.",
+    "mar.", "apr.", "jun.", "jul.", "aug.", "sep.", "sept.", "oct.", "nov.", "dec.",
+];
+
+/// Closing punctuation that may follow a sentence's final stop
+const CLOSERS: &[char] = &['"', '\'', ')', ']', '”', '’', '»'];
+
+/// Abbreviation-aware sentence boundaries
+pub struct SentenceTokenizer {
+    abbreviations: HashSet<String>,
+}
+
+impl Default for SentenceTokenizer {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+impl SentenceTokenizer {
+    /// Tokenizer knowing `extra` abbreviations ("Sched.") besides the built-in ones
+    pub fn new(extra: &[String]) -> Self {
+        let abbreviations = ABBREVIATIONS
+            .iter()
+            .map(|a| a.to_string())
+            .chain(extra.iter().map(|a| a.to_lowercase()))
+            .collect();
+        Self { abbreviations }
+    }
+
+    /// Byte offsets in `text` where a new sentence starts (after the previous
+    /// sentence's trailing whitespace)
+    pub fn boundaries(&self, text: &str) -> Vec<usize> {
+        let words: Vec<(usize, &str)> = text
+            .split_whitespace()
+            .map(|word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+            .collect();
+        words
+            .windows(2)
+            .filter(|pair| self.ends_sentence(pair[0].1, pair[1].1))
+            .map(|pair| pair[1].0)
+            .collect()
+    }
+
+    /// The sentences of `text`, as byte ranges covering it exactly
+    pub fn sentences(&self, text: &str) -> Vec<Range<usize>> {
+        let mut start = 0;
+        let mut sentences = Vec::new();
+        for end in self.boundaries(text) {
+            sentences.push(start..end);
+            start = end;
+        }
+        sentences.push(start..text.len());
+        sentences
+    }
+
+    fn ends_sentence(&self, word: &str, next: &str) -> bool {
+        let starts_sentence = next
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_uppercase() || c.is_ascii_digit() || "\"'“‘«([".contains(c));
+        if !starts_sentence {
+            return false;
+        }
+        let stripped = word.trim_end_matches(CLOSERS);
+        if stripped.ends_with(['!', '?', '…']) {
+            return true;
+        }
+        let bare = stripped.trim_start_matches(|c: char| !c.is_alphanumeric());
+        stripped.ends_with('.') && !self.abbreviations.contains(&bare.to_lowercase()) && !is_initialism(bare)
+    }
+}
+
+/// "J." or "U.S.": single letters each followed by a period
+fn is_initialism(word: &str) -> bool {
+    let mut chars = word.chars();
+    loop {
+        match (chars.next(), chars.next()) {
+            (Some(letter), Some('.')) if letter.is_alphabetic() => {}
+            (None, _) => return !word.is_empty(),
+            _ => return false,
+        }
+    }
+}
+
+/// Sentence segmentation
+///
+/// Emits a `Sentence` element after every paragraph of at least
+/// `min_sentences` sentences. Sentences keep the paragraph's page,
+/// paragraph, reading order and style; bounding boxes are the paragraph's,
+/// sliced top to bottom in proportion to the text, and token counts are
+/// recomputed.
+pub struct SentenceSegmentationRule<'a> {
+    config: &'a SentenceSegmentationConfig,
+    tokenizer: SentenceTokenizer,
+}
+
+impl<'a> SentenceSegmentationRule<'a> {
+    pub fn new(config: &'a SentenceSegmentationConfig) -> Self {
+        Self {
+            config,
+            tokenizer: SentenceTokenizer::new(&config.abbreviations),
+        }
+    }
+
+    fn sentences(&self, paragraph: &ParsedPdfElement) -> Vec<ParsedPdfElement> {
+        let ranges: Vec<Range<usize>> = self
+            .tokenizer
+            .sentences(&paragraph.text)
+            .into_iter()
+            .filter(|range| !paragraph.text[range.clone()].trim().is_empty())
+            .collect();
+        if ranges.len() < self.config.min_sentences.max(2) {
+            return Vec::new();
+        }
+        let total_len = paragraph.text.len() as f32;
+        ranges
+            .into_iter()
+            .map(|range| {
+                let text = paragraph.text[range.clone()].trim().to_string();
+                ParsedPdfElement {
+                    element_type: ParsedElementType::Sentence,
+                    hierarchy_level: paragraph.hierarchy_level + 1,
+                    position: paragraph.position,
+                    style_info: paragraph.style_info.clone(),
+                    bounding_box: slice(&paragraph.bounding_box, range.start as f32 / total_len, range.end as f32 / total_len),
+                    page_number: paragraph.page_number,
+                    paragraph_number: paragraph.paragraph_number,
+                    reading_order: paragraph.reading_order,
+                    bookmark_match: None,
+                    token_count: estimate_token_count(&text),
+                    provenance: paragraph.provenance.clone(),
+                    style_runs: Vec::new(),
+                    text,
+                }
+            })
+            .collect()
+    }
+}
+
+fn slice(bbox: &BoundingBox, start_ratio: f32, end_ratio: f32) -> BoundingBox {
+    BoundingBox {
+        x: bbox.x,
+        y: bbox.y + bbox.height * start_ratio,
+        width: bbox.width,
+        height: bbox.height * (end_ratio - start_ratio),
+    }
+}
+
+impl<'a> ParseRule for SentenceSegmentationRule<'a> {
+    fn apply(&self, elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+        if !self.config.enabled {
+            return Ok(elements);
+        }
+        let mut result = Vec::with_capacity(elements.len());
+        let mut split = 0;
+        for element in elements {
+            let sentences = if element.element_type == ParsedElementType::Paragraph {
+                self.sentences(&element)
+            } else {
+                Vec::new()
+            };
+            split += usize::from(!sentences.is_empty());
+            result.push(element);
+            result.extend(sentences);
+        }
+        println!("✂️  Split {} paragraphs into sentences", split);
+        Ok(result)
+    }
+
+    fn name(&self) -> &str {
+        "SentenceSegmentation"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::builder::GraphBuilder;
+    use crate::test_utils::{bbox, FontClassBuilder};
+
+    #[test]
+    fn abbreviations_do_not_end_sentences() {
+        let tokenizer = SentenceTokenizer::new(&["Sched.".to_string()]);
+        let text = "Dr. Smith saw Fig. 3 on p. 4 (see Sched. B) of the U.S. filing. It cost $1.5 million, i.e. more than planned! \"Why?\" she asked. 2 weeks passed.";
+        let sentences: Vec<&str> = tokenizer.sentences(text).into_iter().map(|r| text[r].trim()).collect();
+        assert_eq!(
+            sentences,
+            [
+                "Dr. Smith saw Fig. 3 on p. 4 (see Sched. B) of the U.S. filing.",
+                "It cost $1.5 million, i.e. more than planned!",
+                "\"Why?\" she asked.",
+                "2 weeks passed.",
+            ]
+        );
+
+        let config = SentenceSegmentationConfig::default();
+        let paragraph = ParsedPdfElement {
+            element_type: ParsedElementType::Paragraph,
+            text: "Dr. Smith saw Fig. 3. It was clear.".to_string(),
+            hierarchy_level: 2,
+            position: 0,
+            style_info: FontClassBuilder::new("f1").build(),
+            bounding_box: bbox(72.0, 100.0, 400.0, 40.0),
+            page_number: 1,
+            paragraph_number: 1,
+            reading_order: 0,
+            bookmark_match: None,
+            token_count: 9,
+            provenance: None,
+            style_runs: Vec::new(),
+        };
+        let short = ParsedPdfElement {
+            text: "One sentence only.".to_string(),
+            ..paragraph.clone()
+        };
+        let elements = SentenceSegmentationRule::new(&config).apply(vec![paragraph, short]).unwrap();
+        let types: Vec<&ParsedElementType> = elements.iter().map(|e| &e.element_type).collect();
+        assert_eq!(
+            types,
+            [
+                &ParsedElementType::Paragraph,
+                &ParsedElementType::Sentence,
+                &ParsedElementType::Sentence,
+                &ParsedElementType::Paragraph,
+            ]
+        );
+        assert_eq!(elements[2].hierarchy_level, 3);
+        assert!(elements[2].bounding_box.y > elements[1].bounding_box.y);
+
+        // Sentences become the paragraph's children, placed inside its text span
+        let mut graph = GraphBuilder::new().build_graph(elements).unwrap();
+        graph.compute_text_spans();
+        let paragraph = graph.nodes.values().find(|n| n.node_type == "Paragraph" && n.content.text.starts_with("Dr.")).unwrap();
+        assert_eq!(paragraph.children.len(), 2);
+        let second = &graph.nodes[&paragraph.children[1]];
+        assert_eq!(second.node_type, "Sentence");
+        let document = graph.document_text();
+        let span = second.location.text_span.as_ref().unwrap();
+        let text: String = document.chars().skip(span.start).take(span.end - span.start).collect();
+        assert_eq!(text, "It was clear.");
+        assert_eq!(document, "Dr. Smith saw Fig. 3. It was clear.\n\nOne sentence only.");
+    }
+}
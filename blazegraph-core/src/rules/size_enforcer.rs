@@ -1,35 +1,98 @@
-use crate::config::{ParsingConfig, SizeEnforcerConfig};
-use crate::rules::engine::{ParseRule, ParsedElement, ParsedElementType};
-use crate::types::BoundingBox;
+use super::engine::ParseRule;
+use crate::config::SizeEnforcerConfig;
+use super::sentence_segmentation::SentenceTokenizer;
+use crate::tokenizer::{EstimateTokenizer, Tokenizer};
+use crate::types::{BoundingBox, ParsedElementType, ParsedPdfElement, StyleRun};
 use anyhow::Result;
-use regex::Regex;
+use std::ops::Range;
+use std::sync::{Arc, LazyLock};
+
+static SENTENCES: LazyLock<SentenceTokenizer> = LazyLock::new(SentenceTokenizer::default);
+
+/// Size enforcement
+///
+/// Splits elements whose text is larger than `max_size` (in `size_unit`s)
+/// so RAG consumers get bounded chunks. Lists split between lines; other
+/// text between sentences when `preserve_sentences` is set, otherwise
+/// between words. With `recursive`, a piece still too large (one very long
+/// sentence) is split again at the next finer boundary: sentences, then
+/// words, then characters. Pieces smaller than `min_split_size_ratio` of the
+/// limit are folded into their neighbour when that fits, and no text is ever
+/// dropped. Chunks keep the original's page, paragraph and style; bounding
+/// boxes are sliced in proportion to the text, style runs cut to each
+/// chunk's text and token counts recomputed. Every chunk keeps the
+/// original's `position` (the TextElement it starts at), so it never claims
+/// the next element's; chunks of one element stay in reading order.
+/// The `tokens` unit and the recomputed counts use the rule's tokenizer
+/// (the config's `tokenizer`, when run by the engine).
+///
+/// Section headings and structured elements (tables, figures, key-value
+/// pairs) are never split.
+pub struct SizeEnforcerRule<'a> {
+    config: &'a SizeEnforcerConfig,
+    tokenizer: Arc<dyn Tokenizer>,
+}
 
-pub struct SizeEnforcerRule {
-    config: SizeEnforcerConfig, // Optimized: stores by value for lifetime simplicity
+/// Boundaries to split at, coarsest first
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Boundary {
+    Lines,
+    Sentences,
+    Words,
+    Characters,
 }
 
-impl SizeEnforcerRule {
-    pub fn new(config: &ParsingConfig) -> Self {
+impl<'a> SizeEnforcerRule<'a> {
+    pub fn new(config: &'a SizeEnforcerConfig) -> Self {
         Self {
-            config: config.size_enforcer.clone(), // Optimized: one-time clone at construction, avoids lifetime complexity
+            config,
+            tokenizer: Arc::new(EstimateTokenizer),
         }
     }
 
+    /// Measure `tokens` and count chunk tokens with `tokenizer` instead of the estimate
+    pub fn with_tokenizer(self, tokenizer: Arc<dyn Tokenizer>) -> Self {
+        Self { tokenizer, ..self }
+    }
+
     fn calculate_size(&self, text: &str) -> usize {
         match self.config.size_unit.as_str() {
-            "characters" => text.chars().count(),
+            "tokens" => self.tokenizer.count(text),
             "words" => text.split_whitespace().count(),
             "bytes" => text.len(),
-            _ => text.chars().count(), // fallback to characters
+            _ => text.chars().count(), // "characters", and the fallback
         }
     }
 
+    /// Size of a piece of text, ignoring the whitespace around it
+    fn range_size(&self, text: &str, range: Range<usize>) -> usize {
+        self.calculate_size(text[range].trim())
+    }
+
     fn min_split_size(&self) -> usize {
         ((self.config.max_size as f32) * self.config.min_split_size_ratio) as usize
     }
 
-    fn needs_splitting(&self, element: &ParsedElement) -> bool {
-        self.config.enabled && self.calculate_size(&element.text) > self.config.max_size
+    fn needs_splitting(&self, element: &ParsedPdfElement) -> bool {
+        let splittable = matches!(
+            element.element_type,
+            ParsedElementType::Paragraph
+                | ParsedElementType::List
+                | ParsedElementType::ListItem
+                | ParsedElementType::Quote { .. }
+                | ParsedElementType::Callout { .. }
+                | ParsedElementType::Caption
+        );
+        splittable && self.calculate_size(&element.text) > self.config.max_size
+    }
+
+    fn finer(&self, boundary: Boundary) -> Option<Boundary> {
+        match boundary {
+            Boundary::Lines if self.config.preserve_sentences => Some(Boundary::Sentences),
+            Boundary::Lines | Boundary::Sentences => Some(Boundary::Words),
+            Boundary::Words => Some(Boundary::Characters),
+            Boundary::Characters => None,
+        }
     }
 
     fn calculate_split_bounding_box(
@@ -51,7 +114,7 @@ impl SizeEnforcerRule {
                     height: original_bbox.height,
                 }
             }
-            "vertical" | _ => {
+            _ => {
                 // Split vertically - each chunk gets a top-to-bottom slice
                 // This represents separate text blocks stacking like paragraphs
                 let chunk_height = original_bbox.height * (chunk_end_ratio - chunk_start_ratio);
@@ -66,308 +129,140 @@ impl SizeEnforcerRule {
         }
     }
 
-    fn split_element(&self, element: ParsedElement) -> Result<Vec<ParsedElement>> {
+    fn split_element(&self, element: ParsedPdfElement) -> Vec<ParsedPdfElement> {
         if !self.needs_splitting(&element) {
-            return Ok(vec![element]);
+            return vec![element];
         }
 
-        // OWNERSHIP_DESIGN phase: Pass element by value to avoid cloning text
-        let target_size = self.config.max_size;
-
-        match element.element_type {
-            ParsedElementType::List => self.split_list(element, target_size),
-            ParsedElementType::Paragraph => self.split_paragraph(element, target_size),
-            ParsedElementType::Section => self.split_section(element, target_size),
-            ParsedElementType::ListItem => self.split_list_item(element, target_size),
-        }
-    }
-
-    fn split_list(&self, element: ParsedElement, target_size: usize) -> Result<Vec<ParsedElement>> {
-        // For lists, we try to split by lines (list items)
-        let lines: Vec<&str> = element.text.lines().collect();
-        if lines.len() <= 1 {
-            // Single line list - treat as paragraph
-            return self.split_paragraph(element, target_size);
+        let boundary = if element.element_type == ParsedElementType::List {
+            Boundary::Lines
+        } else if self.config.preserve_sentences {
+            Boundary::Sentences
+        } else {
+            Boundary::Words
+        };
+        let ranges = self.split_range(&element.text, 0..element.text.len(), boundary, 0);
+        let ranges = self.fold_small_chunks(&element.text, ranges);
+        if ranges.len() <= 1 {
+            return vec![element];
         }
 
-        let total_lines = lines.len();
-        let mut result = Vec::new();
-        let mut current_chunk = Vec::new();
-        let mut current_size = 0;
-        let mut lines_processed = 0;
-
-        for line in lines {
-            let line_size = self.calculate_size(line);
-
-            // If adding this line would exceed target, flush current chunk
-            if current_size + line_size > target_size && !current_chunk.is_empty() {
-                let chunk_text = current_chunk.join("\n");
-                let lines_in_chunk = current_chunk.len();
-                let start_ratio = (lines_processed - lines_in_chunk) as f32 / total_lines as f32;
-                let end_ratio = lines_processed as f32 / total_lines as f32;
-
-                result.push(ParsedElement {
-                    element_type: element.element_type.clone(),
-                    text: chunk_text,
+        let total_len = element.text.len() as f32;
+        let last = ranges.len() - 1;
+        ranges
+            .iter()
+            .enumerate()
+            .map(|(index, range)| {
+                let piece = &element.text[range.clone()];
+                let start = range.start + (piece.len() - piece.trim_start().len());
+                let text = piece.trim().to_string();
+                let chars_before = element.text[..start].chars().count();
+                let chars = chars_before..chars_before + text.chars().count();
+                ParsedPdfElement {
+                    element_type: chunk_type(&element.element_type, index == 0, index == last),
                     hierarchy_level: element.hierarchy_level,
-                    position: element.position + result.len(),
+                    position: element.position,
                     style_info: element.style_info.clone(),
-                    bounding_box: element.bounding_box.as_ref().map(|bbox| {
-                        self.calculate_split_bounding_box(bbox, start_ratio, end_ratio)
-                    }),
+                    bounding_box: self.calculate_split_bounding_box(
+                        &element.bounding_box,
+                        range.start as f32 / total_len,
+                        range.end as f32 / total_len,
+                    ),
                     page_number: element.page_number,
-                });
-                current_chunk.clear();
-                current_size = 0;
-            }
-
-            current_chunk.push(line);
-            current_size += line_size;
-            lines_processed += 1;
-        }
-
-        // Add remaining chunk - consume element to avoid partial moves
-        if !current_chunk.is_empty() {
-            let chunk_text = current_chunk.join("\n");
-            let lines_in_chunk = current_chunk.len();
-            let start_ratio = (lines_processed - lines_in_chunk) as f32 / total_lines as f32;
-            let end_ratio = 1.0; // Final chunk goes to the end
-
-            result.push(ParsedElement {
-                element_type: element.element_type,
-                text: chunk_text,
-                hierarchy_level: element.hierarchy_level,
-                position: element.position + result.len(),
-                style_info: element.style_info,
-                bounding_box: element
-                    .bounding_box
-                    .map(|bbox| self.calculate_split_bounding_box(&bbox, start_ratio, end_ratio)),
-                page_number: element.page_number,
-            });
-        }
-
-        Ok(result)
-    }
-
-    fn split_paragraph(
-        &self,
-        element: ParsedElement,
-        target_size: usize,
-    ) -> Result<Vec<ParsedElement>> {
-        if self.config.preserve_sentences {
-            self.split_by_sentences(element, target_size)
-        } else {
-            self.split_by_position(element, target_size)
-        }
-    }
-
-    fn split_section(
-        &self,
-        element: ParsedElement,
-        target_size: usize,
-    ) -> Result<Vec<ParsedElement>> {
-        // Sections are treated like paragraphs for splitting purposes
-        self.split_paragraph(element, target_size)
-    }
-
-    fn split_list_item(
-        &self,
-        element: ParsedElement,
-        target_size: usize,
-    ) -> Result<Vec<ParsedElement>> {
-        // List items are treated like paragraphs for splitting purposes
-        self.split_paragraph(element, target_size)
+                    paragraph_number: element.paragraph_number,
+                    reading_order: element.reading_order,
+                    bookmark_match: if index == 0 { element.bookmark_match.clone() } else { None },
+                    token_count: self.tokenizer.count(&text),
+                    provenance: element.provenance.clone(),
+                    style_runs: slice_style_runs(&element.style_runs, chars),
+                    text,
+                }
+            })
+            .collect()
     }
 
-    fn split_by_sentences(
-        &self,
-        mut element: ParsedElement,
-        target_size: usize,
-    ) -> Result<Vec<ParsedElement>> {
-        // Simple sentence boundary detection - EXPLORE phase: basic implementation
-        let sentence_regex = Regex::new(r"[.!?]+\s+").unwrap();
-        let mut sentences = Vec::new();
-        let mut sentence_positions = Vec::new();
-        let mut start = 0;
-
-        for mat in sentence_regex.find_iter(&element.text) {
-            let end = mat.end();
-            sentences.push(&element.text[start..end]);
-            sentence_positions.push((start, end));
-            start = end;
-        }
-
-        // Add remaining text if any
-        if start < element.text.len() {
-            sentences.push(&element.text[start..]);
-            sentence_positions.push((start, element.text.len()));
+    /// Byte ranges of `text` covering `range`, each within the limit where
+    /// the boundaries allow it
+    fn split_range(&self, text: &str, range: Range<usize>, boundary: Boundary, depth: usize) -> Vec<Range<usize>> {
+        let chunks = self.pack(text, units(text, range, boundary));
+        if !self.config.recursive || depth + 1 >= self.config.max_iterations {
+            return chunks;
         }
-
-        if sentences.is_empty() || sentences.len() == 1 {
-            // No sentence boundaries or single sentence - split by position
-            return self.split_by_position(element, target_size);
-        }
-
-        let total_text_len = element.text.len();
-        let mut result = Vec::new();
-        let mut current_chunk = Vec::new();
-        let mut current_size = 0;
-        let mut chunk_start_pos = 0;
-        let mut sentence_idx = 0;
-
-        for sentence in sentences {
-            let sentence_size = self.calculate_size(sentence);
-
-            // If adding this sentence would exceed target, flush current chunk
-            if current_size + sentence_size > target_size && !current_chunk.is_empty() {
-                let chunk_text = current_chunk.join("").trim().to_string();
-                if self.calculate_size(&chunk_text) >= self.min_split_size() {
-                    let chunk_end_pos = sentence_positions[sentence_idx - 1].1;
-                    let start_ratio = chunk_start_pos as f32 / total_text_len as f32;
-                    let end_ratio = chunk_end_pos as f32 / total_text_len as f32;
-
-                    result.push(ParsedElement {
-                        element_type: element.element_type.clone(),
-                        text: chunk_text,
-                        hierarchy_level: element.hierarchy_level,
-                        position: element.position + result.len(),
-                        style_info: element.style_info.clone(),
-                        bounding_box: element.bounding_box.as_ref().map(|bbox| {
-                            self.calculate_split_bounding_box(bbox, start_ratio, end_ratio)
-                        }),
-                        page_number: element.page_number,
-                    });
+        let Some(finer) = self.finer(boundary) else {
+            return chunks;
+        };
+        chunks
+            .into_iter()
+            .flat_map(|chunk| {
+                if self.range_size(text, chunk.clone()) > self.config.max_size {
+                    self.split_range(text, chunk, finer, depth + 1)
+                } else {
+                    vec![chunk]
                 }
-                current_chunk.clear();
-                current_size = 0;
-                chunk_start_pos = sentence_positions[sentence_idx].0;
-            }
-
-            current_chunk.push(sentence);
-            current_size += sentence_size;
-            sentence_idx += 1;
-        }
-
-        // Add remaining chunk (consume element here to avoid partial move)
-        if !current_chunk.is_empty() {
-            let chunk_text = current_chunk.join("").trim().to_string();
-            if self.calculate_size(&chunk_text) >= self.min_split_size() {
-                let start_ratio = chunk_start_pos as f32 / total_text_len as f32;
-                let end_ratio = 1.0; // Final chunk goes to the end
-
-                element.text = chunk_text;
-                element.position += result.len();
-                element.bounding_box = element
-                    .bounding_box
-                    .map(|bbox| self.calculate_split_bounding_box(&bbox, start_ratio, end_ratio));
-                result.push(element);
-                return Ok(result);
-            }
-        }
-
-        // Fallback to position-based splitting if sentence splitting didn't work well
-        if result.is_empty() {
-            return self.split_by_position(element, target_size);
-        }
-
-        Ok(result)
+            })
+            .collect()
     }
 
-    fn split_by_position(
-        &self,
-        element: ParsedElement,
-        target_size: usize,
-    ) -> Result<Vec<ParsedElement>> {
-        let mut result = Vec::new();
-        let chars: Vec<char> = element.text.chars().collect();
-        let mut start = 0;
-
-        while start < chars.len() {
-            let mut end = start + target_size;
-            if end >= chars.len() {
-                end = chars.len();
-            } else {
-                // Try to find a good break point (space, punctuation)
-                for i in (start + (target_size / 2)..end).rev() {
-                    if chars[i].is_whitespace() || chars[i].is_ascii_punctuation() {
-                        end = i + 1;
-                        break;
-                    }
+    /// Greedily group consecutive units into chunks within the limit
+    ///
+    /// Characters, bytes and words add up, so a chunk's size is kept as a
+    /// running count: its text after leading whitespace, plus the next unit
+    /// without its trailing whitespace, is the trimmed size of the two
+    /// together. Token counts don't add up and are measured on the whole
+    /// chunk.
+    fn pack(&self, text: &str, units: Vec<Range<usize>>) -> Vec<Range<usize>> {
+        let additive = self.config.size_unit != "tokens";
+        let mut chunks: Vec<Range<usize>> = Vec::new();
+        let mut size = 0;
+        for unit in units {
+            let piece = &text[unit.clone()];
+            let fits = match chunks.last() {
+                Some(_) if additive => size + self.calculate_size(piece.trim_end()) <= self.config.max_size,
+                Some(chunk) => self.range_size(text, chunk.start..unit.end) <= self.config.max_size,
+                None => false,
+            };
+            match chunks.last_mut() {
+                Some(chunk) if fits => {
+                    chunk.end = unit.end;
+                    size += if additive { self.calculate_size(piece) } else { 0 };
+                }
+                _ => {
+                    size = if additive { self.calculate_size(piece.trim_start()) } else { 0 };
+                    chunks.push(unit);
                 }
             }
-
-            let chunk_text: String = chars[start..end]
-                .iter()
-                .collect::<String>()
-                .trim()
-                .to_string();
-            if !chunk_text.is_empty() && self.calculate_size(&chunk_text) >= self.min_split_size() {
-                let total_chars = chars.len();
-                let start_ratio = start as f32 / total_chars as f32;
-                let end_ratio = end as f32 / total_chars as f32;
-
-                result.push(ParsedElement {
-                    element_type: element.element_type.clone(),
-                    text: chunk_text,
-                    hierarchy_level: element.hierarchy_level,
-                    position: element.position + result.len(),
-                    style_info: element.style_info.clone(),
-                    bounding_box: element.bounding_box.as_ref().map(|bbox| {
-                        self.calculate_split_bounding_box(bbox, start_ratio, end_ratio)
-                    }),
-                    page_number: element.page_number,
-                });
-            }
-
-            start = end;
-        }
-
-        // Fallback: keep original element even if oversized
-        if result.is_empty() {
-            result.push(element);
         }
-
-        Ok(result)
+        chunks
     }
 
-    fn apply_recursive_splitting(
-        &self,
-        elements: Vec<ParsedElement>,
-    ) -> Result<Vec<ParsedElement>> {
-        let mut result = elements;
-        let mut iteration = 0;
-
-        while iteration < self.config.max_iterations {
-            let mut has_oversized = false;
-            let mut new_result = Vec::new();
-
-            for element in result {
-                let split_elements = self.split_element(element)?;
-
-                // Check if any resulting elements are still oversized
-                for split_element in &split_elements {
-                    if self.needs_splitting(split_element) {
-                        has_oversized = true;
-                    }
+    /// Fold chunks below the minimum size into a neighbour when the result
+    /// stays within the limit; blank chunks are dropped
+    fn fold_small_chunks(&self, text: &str, chunks: Vec<Range<usize>>) -> Vec<Range<usize>> {
+        let mut chunks: Vec<Range<usize>> = chunks
+            .into_iter()
+            .filter(|range| !text[range.clone()].trim().is_empty())
+            .collect();
+        let (min_size, max_size) = (self.min_split_size(), self.config.max_size);
+        let mut index = 0;
+        while chunks.len() > 1 && index < chunks.len() {
+            if self.range_size(text, chunks[index].clone()) < min_size {
+                let neighbour = if index > 0 { index - 1 } else { 1 };
+                let (start, end) = (chunks[index.min(neighbour)].start, chunks[index.max(neighbour)].end);
+                if self.range_size(text, start..end) <= max_size {
+                    chunks[index.min(neighbour)] = start..end;
+                    chunks.remove(index.max(neighbour));
+                    continue;
                 }
-
-                new_result.extend(split_elements);
-            }
-
-            result = new_result;
-            iteration += 1;
-
-            if !has_oversized {
-                break;
             }
+            index += 1;
         }
-
-        Ok(result)
+        chunks
     }
+
 }
 
-impl ParseRule for SizeEnforcerRule {
-    fn apply(&self, elements: Vec<ParsedElement>) -> Result<Vec<ParsedElement>> {
+impl<'a> ParseRule for SizeEnforcerRule<'a> {
+    fn apply(&self, elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
         if !self.config.enabled {
             return Ok(elements);
         }
@@ -383,16 +278,10 @@ impl ParseRule for SizeEnforcerRule {
 
         let input_count = elements.len();
         let oversized_count = elements.iter().filter(|e| self.needs_splitting(e)).count();
-
-        let result = if self.config.recursive {
-            self.apply_recursive_splitting(elements)?
-        } else {
-            let mut result = Vec::new();
-            for element in elements {
-                result.extend(self.split_element(element)?);
-            }
-            result
-        };
+        let result: Vec<ParsedPdfElement> = elements
+            .into_iter()
+            .flat_map(|element| self.split_element(element))
+            .collect();
 
         let output_count = result.len();
         println!("   ✅ Split {oversized_count} oversized elements into {output_count} total elements ({input_count}→{output_count})");
@@ -404,3 +293,176 @@ impl ParseRule for SizeEnforcerRule {
         "SizeEnforcer"
     }
 }
+
+/// The pieces of `range` between consecutive boundaries, covering it exactly
+fn units(text: &str, range: Range<usize>, boundary: Boundary) -> Vec<Range<usize>> {
+    let slice = &text[range.clone()];
+    let ends: Vec<usize> = match boundary {
+        Boundary::Lines => slice.match_indices('\n').map(|(at, _)| at + 1).collect(),
+        Boundary::Sentences => SENTENCES.boundaries(slice),
+        Boundary::Words => slice
+            .char_indices()
+            .zip(slice.chars().skip(1))
+            .filter(|((_, c), next)| c.is_whitespace() && !next.is_whitespace())
+            .map(|((at, c), _)| at + c.len_utf8())
+            .collect(),
+        Boundary::Characters => slice.char_indices().skip(1).map(|(at, _)| at).collect(),
+    };
+
+    let mut start = range.start;
+    let mut units = Vec::with_capacity(ends.len() + 1);
+    for end in ends.into_iter().map(|end| range.start + end) {
+        if end > start && end < range.end {
+            units.push(start..end);
+            start = end;
+        }
+    }
+    units.push(start..range.end);
+    units
+}
+
+/// The parts of `runs` within the character range `chars`, relative to its start
+fn slice_style_runs(runs: &[StyleRun], chars: Range<usize>) -> Vec<StyleRun> {
+    runs.iter()
+        .filter(|run| run.start < chars.end && run.end > chars.start)
+        .map(|run| StyleRun {
+            start: run.start.max(chars.start) - chars.start,
+            end: run.end.min(chars.end) - chars.start,
+            ..run.clone()
+        })
+        .collect()
+}
+
+/// Quote attributions stay with the last chunk, callout labels with the first
+fn chunk_type(element_type: &ParsedElementType, first: bool, last: bool) -> ParsedElementType {
+    match element_type {
+        ParsedElementType::Quote { attribution } => ParsedElementType::Quote {
+            attribution: attribution.clone().filter(|_| last),
+        },
+        ParsedElementType::Callout { label } => ParsedElementType::Callout {
+            label: label.clone().filter(|_| first),
+        },
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessors::pdf::xhtml_parser::estimate_token_count;
+    use crate::test_utils::{bbox, ParsedElementBuilder};
+
+    fn paragraph(text: &str) -> ParsedPdfElement {
+        ParsedElementBuilder::new(text)
+            .level(2)
+            .bbox(bbox(72.0, 100.0, 400.0, 100.0))
+            .page(3)
+            .paragraph(7)
+            .tokens(estimate_token_count(text))
+            .build()
+    }
+
+    #[test]
+    fn splits_oversized_paragraphs_without_losing_text() {
+        let config = SizeEnforcerConfig {
+            max_size: 60,
+            ..Default::default()
+        };
+        let rule = SizeEnforcerRule::new(&config);
+        let long_sentence = "This one sentence keeps going without any full stop until it is far too long to fit";
+        let text = format!(
+            "First sentence is short. Second sentence is also short. {}. Ok.",
+            long_sentence
+        );
+        let heading = ParsedPdfElement {
+            element_type: ParsedElementType::Section,
+            ..paragraph(&text)
+        };
+
+        let result = rule.apply(vec![paragraph(&text), heading]).unwrap();
+        let chunks = &result[..result.len() - 1];
+        assert!(chunks.len() > 2, "{:?}", chunks);
+        assert!(chunks.iter().all(|c| (15..=60).contains(&c.text.chars().count())), "{:?}", chunks);
+        assert_eq!(chunks[0].text, "First sentence is short. Second sentence is also short.");
+        // The long sentence is split between words, and "Ok." folded into its last piece
+        assert!(chunks.last().unwrap().text.ends_with("to fit. Ok."), "{:?}", chunks);
+        let rejoined: Vec<&str> = chunks.iter().flat_map(|c| c.text.split_whitespace()).collect();
+        assert_eq!(rejoined, text.split_whitespace().collect::<Vec<_>>());
+
+        for chunk in chunks {
+            assert_eq!(chunk.token_count, estimate_token_count(&chunk.text));
+            assert_eq!((chunk.page_number, chunk.paragraph_number), (3, 7));
+        }
+        assert!(chunks.windows(2).all(|w| w[0].bounding_box.y < w[1].bounding_box.y));
+        assert_eq!(result.last().unwrap().element_type, ParsedElementType::Section);
+        assert_eq!(result.last().unwrap().text, text, "headings are never split");
+    }
+
+    fn words(count: usize) -> String {
+        (0..count).map(|i| format!("word{i:02}")).collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn chunks_keep_the_style_runs_of_their_text() {
+        let config = SizeEnforcerConfig {
+            max_size: 30,
+            ..Default::default()
+        };
+        let text = words(12);
+        let mut element = paragraph(&text);
+        // "word02 word03 word04" in bold, across the first split; "word10" in italics
+        element.style_runs = vec![
+            StyleRun { start: 14, end: 34, bold: true, italic: false },
+            StyleRun { start: 70, end: 76, bold: false, italic: true },
+        ];
+
+        let chunks = SizeEnforcerRule::new(&config).apply(vec![element]).unwrap();
+        assert!(chunks.len() > 2, "{:?}", chunks);
+        let styled = |bold: bool| -> Vec<String> {
+            chunks
+                .iter()
+                .flat_map(|chunk| {
+                    let chars: Vec<char> = chunk.text.chars().collect();
+                    chunk
+                        .style_runs
+                        .iter()
+                        .filter(|run| run.bold == bold)
+                        .map(move |run| chars[run.start..run.end].iter().collect::<String>())
+                        .collect::<Vec<_>>()
+                })
+                .collect()
+        };
+        assert_eq!(styled(true), ["word02 word03", "word04"]);
+        assert_eq!(styled(false), ["word10"]);
+    }
+
+    #[test]
+    fn chunks_keep_the_position_of_their_element() {
+        let config = SizeEnforcerConfig {
+            max_size: 30,
+            ..Default::default()
+        };
+        let long = ParsedElementBuilder::new(&words(12)).order(5).build();
+        let next = ParsedElementBuilder::new("The next paragraph.").order(6).build();
+
+        let result = SizeEnforcerRule::new(&config).apply(vec![long, next]).unwrap();
+        let (chunks, next) = result.split_at(result.len() - 1);
+        assert!(chunks.len() > 2, "{:?}", chunks);
+        assert!(chunks.iter().all(|chunk| chunk.position == 5), "{:?}", chunks);
+        assert_eq!(next[0].position, 6);
+    }
+
+    #[test]
+    fn splits_long_unsegmentable_text_between_characters() {
+        let config = SizeEnforcerConfig {
+            max_size: 1000,
+            ..Default::default()
+        };
+        let text: String = "abcdéfghij".repeat(20_000);
+
+        let chunks = SizeEnforcerRule::new(&config).apply(vec![paragraph(&text)]).unwrap();
+        assert_eq!(chunks.len(), 200);
+        assert!(chunks.iter().all(|chunk| chunk.text.chars().count() == 1000));
+        assert_eq!(chunks.iter().map(|chunk| chunk.text.as_str()).collect::<String>(), text);
+    }
+}
@@ -1,8 +1,119 @@
 use super::engine::ParseRule;
+use super::spatial_index::PageSpatialIndex;
 use crate::config::{ElementClusteringConfig, ParsingConfig};
+use crate::reading_direction::{joiner, transpose, ReadingDirection};
 use crate::types::BoundingBox;
 use crate::types::*;
 use anyhow::Result;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Vertical gaps larger than this many line heights are layout jumps
+/// (columns, figures, page regions), not line or paragraph spacing
+const MAX_GAP_LINE_HEIGHTS: f32 = 10.0;
+
+/// Vertical adjacency thresholds, either fixed from config or estimated from the document
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpatialThresholds {
+    pub line_height: f32,
+    pub max_vertical_gap: f32,
+}
+
+impl SpatialThresholds {
+    /// Estimate thresholds from element geometry.
+    ///
+    /// Line height is the median element height. Each element's nearest
+    /// neighbour below it in the same column gives a vertical gap, split into
+    /// line spacing (same paragraph) and paragraph spacing (paragraph
+    /// changes); the threshold sits midway between the two medians. Returns
+    /// None with fewer than `min_samples` measurable elements.
+    pub fn estimate(elements: &[&ParsedPdfElement], min_samples: usize) -> Option<Self> {
+        let heights: Vec<f32> = elements
+            .iter()
+            .map(|e| e.bounding_box.height)
+            .filter(|h| h.is_finite() && *h > 0.0)
+            .collect();
+        if heights.len() < min_samples.max(1) {
+            return None;
+        }
+        let line_height = median(heights)?;
+
+        // Nearest-below via the index keeps multi-column pages from pairing
+        // the bottom of one column with the top of the next
+        let index =
+            PageSpatialIndex::new(elements.iter().map(|e| (e.page_number, &e.bounding_box)));
+        let max_gap = line_height * MAX_GAP_LINE_HEIGHTS;
+
+        let mut line_gaps = Vec::new();
+        let mut paragraph_gaps = Vec::new();
+        for (i, element) in elements.iter().enumerate() {
+            let Some((below, gap)) =
+                index.nearest_below(element.page_number, &element.bounding_box, max_gap, i)
+            else {
+                continue;
+            };
+            if elements[below].paragraph_number == element.paragraph_number {
+                line_gaps.push(gap);
+            } else {
+                paragraph_gaps.push(gap);
+            }
+        }
+
+        let line_gap = median(line_gaps);
+        let paragraph_gap = median(paragraph_gaps);
+        let max_vertical_gap = match (line_gap, paragraph_gap) {
+            (Some(line), Some(paragraph)) if paragraph > line => (line + paragraph) / 2.0,
+            (Some(line), _) => line + line_height * 0.5,
+            (None, Some(paragraph)) => paragraph * 0.5,
+            (None, None) => line_height * 0.5,
+        };
+
+        Some(Self {
+            line_height,
+            max_vertical_gap,
+        })
+    }
+}
+
+fn median(mut values: Vec<f32>) -> Option<f32> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f32::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 1 {
+        values[mid]
+    } else {
+        (values[mid - 1] + values[mid]) / 2.0
+    })
+}
+
+/// Thresholds in effect for one run of the rule, with optional per-page overrides
+struct ThresholdTable {
+    document: SpatialThresholds,
+    pages: HashMap<u32, SpatialThresholds>,
+    /// Pages of vertical text, measured across lines instead of down the page
+    vertical_pages: HashSet<u32>,
+}
+
+impl ThresholdTable {
+    fn for_page(&self, page_number: u32) -> SpatialThresholds {
+        self.pages
+            .get(&page_number)
+            .copied()
+            .unwrap_or(self.document)
+    }
+
+    /// A box as the thresholds measure it: vertical pages are turned so
+    /// their lines stack top to bottom
+    fn oriented(&self, page_number: u32, bbox: &BoundingBox) -> BoundingBox {
+        if self.vertical_pages.contains(&page_number) {
+            transpose(bbox)
+        } else {
+            bbox.clone()
+        }
+    }
+}
 
 pub struct SpatialClusteringRule<'a> {
     config: &'a ParsingConfig,
@@ -26,6 +137,27 @@ impl<'a> ParseRule for SpatialClusteringRule<'a> {
         }
 
         let original_count = elements.len();
+        // Estimate from the unmerged spans, before paragraph merging grows the boxes
+        let vertical_pages = self.vertical_pages(&elements);
+        let geometry: Cow<[ParsedPdfElement]> = if vertical_pages.is_empty() {
+            Cow::Borrowed(&elements)
+        } else {
+            Cow::Owned(
+                elements
+                    .iter()
+                    .map(|e| ParsedPdfElement {
+                        bounding_box: if vertical_pages.contains(&e.page_number) {
+                            transpose(&e.bounding_box)
+                        } else {
+                            e.bounding_box.clone()
+                        },
+                        ..e.clone()
+                    })
+                    .collect(),
+            )
+        };
+        let mut thresholds = self.resolve_thresholds(&geometry);
+        thresholds.vertical_pages = vertical_pages;
         let mut clustered_elements = elements;
 
         // Step 1: Paragraph merging (if enabled)
@@ -39,7 +171,7 @@ impl<'a> ParseRule for SpatialClusteringRule<'a> {
         // Step 2: Spatial adjacency clustering (if enabled)
         if self.config.spatial_clustering.enable_spatial_adjacency {
             println!("   🧩 Step 2: Spatial adjacency clustering enabled");
-            clustered_elements = self.cluster_adjacent_elements(clustered_elements)?;
+            clustered_elements = self.cluster_adjacent_elements(clustered_elements, &thresholds)?;
         } else {
             println!("   ⏭️  Step 2: Spatial adjacency clustering disabled");
         }
@@ -59,6 +191,90 @@ impl<'a> ParseRule for SpatialClusteringRule<'a> {
 }
 
 impl<'a> SpatialClusteringRule<'a> {
+    fn fixed_thresholds(&self) -> SpatialThresholds {
+        let spatial = &self.config.spatial_clustering;
+        SpatialThresholds {
+            line_height: spatial.min_line_height,
+            max_vertical_gap: spatial.min_line_height * spatial.vertical_gap_threshold_multiplier,
+        }
+    }
+
+    /// Fixed thresholds from config, or adaptive estimates when enabled
+    fn resolve_thresholds(&self, elements: &[ParsedPdfElement]) -> ThresholdTable {
+        let adaptive = &self.config.spatial_clustering.adaptive_thresholds;
+        let fixed = self.fixed_thresholds();
+        if !adaptive.enabled {
+            return ThresholdTable {
+                document: fixed,
+                pages: HashMap::new(),
+                vertical_pages: HashSet::new(),
+            };
+        }
+
+        let all: Vec<&ParsedPdfElement> = elements.iter().collect();
+        let document = match SpatialThresholds::estimate(&all, adaptive.min_samples) {
+            Some(estimated) => {
+                println!(
+                    "   📐 Adaptive thresholds: line height {:.2}pt, max vertical gap {:.2}pt (fixed: {:.2}pt / {:.2}pt)",
+                    estimated.line_height,
+                    estimated.max_vertical_gap,
+                    fixed.line_height,
+                    fixed.max_vertical_gap
+                );
+                estimated
+            }
+            None => {
+                println!("   ⚠️  Too few elements for adaptive thresholds - using fixed values");
+                fixed
+            }
+        };
+
+        let mut pages = HashMap::new();
+        if adaptive.per_page {
+            let mut by_page: HashMap<u32, Vec<&ParsedPdfElement>> = HashMap::new();
+            for element in elements {
+                by_page.entry(element.page_number).or_default().push(element);
+            }
+            let mut page_numbers: Vec<u32> = by_page.keys().copied().collect();
+            page_numbers.sort_unstable();
+            for page_number in page_numbers {
+                if let Some(estimated) =
+                    SpatialThresholds::estimate(&by_page[&page_number], adaptive.min_samples)
+                {
+                    println!(
+                        "      Page {}: line height {:.2}pt, max vertical gap {:.2}pt",
+                        page_number, estimated.line_height, estimated.max_vertical_gap
+                    );
+                    pages.insert(page_number, estimated);
+                }
+            }
+        }
+
+        ThresholdTable {
+            document,
+            pages,
+            vertical_pages: HashSet::new(),
+        }
+    }
+
+    /// Pages read as vertical text, detected or forced by `reading_direction`
+    fn vertical_pages(&self, elements: &[ParsedPdfElement]) -> HashSet<u32> {
+        let mut by_page: HashMap<u32, Vec<&ParsedPdfElement>> = HashMap::new();
+        for element in elements {
+            by_page.entry(element.page_number).or_default().push(element);
+        }
+        by_page
+            .into_iter()
+            .filter(|(_, page)| {
+                self.config
+                    .reading_direction
+                    .resolve(page.iter().map(|e| (e.text.as_str(), &e.bounding_box)))
+                    == ReadingDirection::VerticalRl
+            })
+            .map(|(page_number, _)| page_number)
+            .collect()
+    }
+
     fn cluster_paragraphs_elements(
         &self,
         elements: Vec<ParsedPdfElement>,
@@ -69,9 +285,9 @@ impl<'a> SpatialClusteringRule<'a> {
             return Ok(elements);
         }
 
-        // Group elements by (page_number, paragraph_number)
-        let mut paragraph_groups: std::collections::HashMap<(u32, u32), Vec<ParsedPdfElement>> =
-            std::collections::HashMap::new();
+        // Group elements by (page_number, paragraph_number). Ordered so that merge
+        // order, and the tie order of the final sort, is the same on every run.
+        let mut paragraph_groups: BTreeMap<(u32, u32), Vec<ParsedPdfElement>> = BTreeMap::new();
 
         for element in elements {
             let key = (element.page_number, element.paragraph_number);
@@ -102,8 +318,11 @@ impl<'a> SpatialClusteringRule<'a> {
 
                 // Merge all subsequent elements into the first one
                 for element in group_iter {
-                    // Merge text with space separator
-                    merged_element.text = format!("{} {}", merged_element.text, element.text);
+                    // Merge text with a space separator, none between CJK characters
+                    // (appending in place keeps large groups linear)
+                    let separator = joiner(&merged_element.text, &element.text);
+                    merged_element.text.push_str(separator);
+                    merged_element.text.push_str(&element.text);
 
                     // Expand bounding box to encompass all segments
                     merged_element.bounding_box = self
@@ -142,6 +361,7 @@ impl<'a> SpatialClusteringRule<'a> {
     fn cluster_adjacent_elements(
         &self,
         elements: Vec<ParsedPdfElement>,
+        thresholds: &ThresholdTable,
     ) -> Result<Vec<ParsedPdfElement>> {
         let mut clustered = Vec::new();
         let mut current_cluster: Option<ParsedPdfElement> = None;
@@ -154,7 +374,7 @@ impl<'a> SpatialClusteringRule<'a> {
                 }
                 Some(cluster) => {
                     // Check if this element can be merged with current cluster
-                    if self.can_merge_elements(cluster, &element) {
+                    if self.can_merge_elements(cluster, &element, thresholds) {
                         // Merge element into current cluster
                         self.merge_elements(cluster, element);
                     } else {
@@ -175,12 +395,25 @@ impl<'a> SpatialClusteringRule<'a> {
     }
 
     /// Check if two elements can be merged (same type, hierarchy level, page, and spatially adjacent)
-    fn can_merge_elements(&self, cluster: &ParsedPdfElement, element: &ParsedPdfElement) -> bool {
+    fn can_merge_elements(
+        &self,
+        cluster: &ParsedPdfElement,
+        element: &ParsedPdfElement,
+        thresholds: &ThresholdTable,
+    ) -> bool {
         // Must be same type
         if cluster.element_type != element.element_type {
             return false;
         }
 
+        // Tables and figures arrive whole (base conversion or TableDetection)
+        if matches!(
+            cluster.element_type,
+            ParsedElementType::Table(_) | ParsedElementType::Figure { .. }
+        ) {
+            return false;
+        }
+
         // Must be same hierarchy level
         if cluster.hierarchy_level != element.hierarchy_level {
             return false;
@@ -200,7 +433,12 @@ impl<'a> SpatialClusteringRule<'a> {
         }
 
         // CRITICAL FIX: Check spatial proximity - elements must be spatially adjacent to merge
-        if !self.are_spatially_adjacent(cluster, element) {
+        let page = cluster.page_number;
+        if !self.are_spatially_adjacent(
+            &thresholds.oriented(page, &cluster.bounding_box),
+            &thresholds.oriented(page, &element.bounding_box),
+            thresholds.for_page(page),
+        ) {
             return false;
         }
 
@@ -209,8 +447,10 @@ impl<'a> SpatialClusteringRule<'a> {
 
     /// Merge element into cluster, updating text and bounding box
     fn merge_elements(&self, cluster: &mut ParsedPdfElement, element: ParsedPdfElement) {
-        // Merge text with space separator
-        cluster.text = format!("{} {}", cluster.text, element.text);
+        // Merge text with space separator, none between CJK characters
+        let separator = joiner(&cluster.text, &element.text);
+        cluster.text.push_str(separator);
+        cluster.text.push_str(&element.text);
 
         // Merge bounding boxes (both elements always have bounding boxes now)
         cluster.bounding_box =
@@ -231,7 +471,16 @@ impl<'a> SpatialClusteringRule<'a> {
             ParsedElementType::Section => &self.config.spatial_clustering.sections,
             ParsedElementType::Paragraph
             | ParsedElementType::List
-            | ParsedElementType::ListItem => &self.config.spatial_clustering.paragraphs,
+            | ParsedElementType::ListItem
+            | ParsedElementType::KeyValue { .. }
+            | ParsedElementType::Quote { .. }
+            | ParsedElementType::Callout { .. }
+            | ParsedElementType::Table(_)
+            | ParsedElementType::Figure { .. }
+            | ParsedElementType::Caption
+            | ParsedElementType::Header
+            | ParsedElementType::Footer
+            | ParsedElementType::Sentence => &self.config.spatial_clustering.paragraphs,
         }
     }
 
@@ -250,15 +499,13 @@ impl<'a> SpatialClusteringRule<'a> {
         }
     }
 
-    /// Check if two elements are spatially adjacent (close enough to merge)
+    /// Check if two elements' boxes are spatially adjacent (close enough to merge)
     fn are_spatially_adjacent(
         &self,
-        cluster: &ParsedPdfElement,
-        element: &ParsedPdfElement,
+        cluster_bbox: &BoundingBox,
+        element_bbox: &BoundingBox,
+        thresholds: SpatialThresholds,
     ) -> bool {
-        // Both elements always have bounding boxes now
-        let cluster_bbox = &cluster.bounding_box;
-        let element_bbox = &element.bounding_box;
 
         // Calculate vertical distance between elements
         let cluster_bottom = cluster_bbox.y + cluster_bbox.height;
@@ -278,16 +525,8 @@ impl<'a> SpatialClusteringRule<'a> {
             0.0
         };
 
-        // Calculate maximum allowed vertical gap using config
-        let min_line_height = self.config.spatial_clustering.min_line_height;
-        let gap_multiplier = self
-            .config
-            .spatial_clustering
-            .vertical_gap_threshold_multiplier;
-        let max_vertical_gap = min_line_height * gap_multiplier;
-
-        // Check if vertical gap is within acceptable range
-        if vertical_gap > max_vertical_gap {
+        // Check if vertical gap is within acceptable range (fixed or adaptive)
+        if vertical_gap > thresholds.max_vertical_gap {
             return false;
         }
 
@@ -313,3 +552,43 @@ impl<'a> SpatialClusteringRule<'a> {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_utils::{bbox, ParsedElementBuilder};
+
+    fn element(y: f32, height: f32, paragraph: u32, order: u32) -> ParsedPdfElement {
+        ParsedElementBuilder::new("text").bbox(bbox(72.0, y, 400.0, height)).paragraph(paragraph).order(order).build()
+    }
+
+    /// Two 3-line paragraphs: lines 12pt tall with 2pt leading, 12pt between paragraphs
+    fn layout(scale: f32) -> Vec<ParsedPdfElement> {
+        let mut elements = Vec::new();
+        let mut y = 0.0;
+        for order in 0..6 {
+            let paragraph = order / 3;
+            elements.push(element(y * scale, 12.0 * scale, paragraph, order));
+            y += 12.0 + if order == 2 { 12.0 } else { 2.0 };
+        }
+        elements
+    }
+
+    #[test]
+    fn estimates_scale_with_document_resolution() {
+        let base = layout(1.0);
+        let refs: Vec<&ParsedPdfElement> = base.iter().collect();
+        let thresholds = SpatialThresholds::estimate(&refs, 5).unwrap();
+        assert_eq!(thresholds.line_height, 12.0);
+        assert_eq!(thresholds.max_vertical_gap, 7.0);
+
+        let scaled = layout(2.0);
+        let refs: Vec<&ParsedPdfElement> = scaled.iter().collect();
+        let doubled = SpatialThresholds::estimate(&refs, 5).unwrap();
+        assert_eq!(doubled.line_height, 24.0);
+        assert_eq!(doubled.max_vertical_gap, 14.0);
+
+        assert!(SpatialThresholds::estimate(&refs, 10).is_none());
+    }
+}
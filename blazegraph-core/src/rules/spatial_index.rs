@@ -0,0 +1,144 @@
+//! Per-page spatial index over element bounding boxes
+//!
+//! Elements are grouped by page and sorted by the top of their box, so
+//! vertical interval queries ("what overlaps this band?", "what is directly
+//! below this box?") are a binary search plus a short scan instead of a pass
+//! over every element on the page.
+
+use crate::types::BoundingBox;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    top: f32,
+    bottom: f32,
+    left: f32,
+    right: f32,
+    index: usize,
+}
+
+#[derive(Debug, Default)]
+struct PageEntries {
+    /// Sorted by `top`
+    entries: Vec<Entry>,
+    /// Tallest box on the page; bounds how far above a band an overlapping box can start
+    max_height: f32,
+}
+
+/// Vertical interval index of element boxes, one sorted list per page
+#[derive(Debug, Default)]
+pub struct PageSpatialIndex {
+    pages: BTreeMap<u32, PageEntries>,
+}
+
+impl PageSpatialIndex {
+    /// Index `(page_number, bounding_box)` pairs; query results are positions in this sequence
+    pub fn new<'a>(boxes: impl IntoIterator<Item = (u32, &'a BoundingBox)>) -> Self {
+        let mut pages: BTreeMap<u32, PageEntries> = BTreeMap::new();
+        for (index, (page_number, bbox)) in boxes.into_iter().enumerate() {
+            let page = pages.entry(page_number).or_default();
+            page.max_height = page.max_height.max(bbox.height);
+            page.entries.push(Entry {
+                top: bbox.y,
+                bottom: bbox.y + bbox.height,
+                left: bbox.x,
+                right: bbox.x + bbox.width,
+                index,
+            });
+        }
+        for page in pages.values_mut() {
+            page.entries
+                .sort_by(|a, b| a.top.total_cmp(&b.top).then(a.index.cmp(&b.index)));
+        }
+        Self { pages }
+    }
+
+    /// Page numbers in ascending order
+    pub fn pages(&self) -> impl Iterator<Item = u32> + '_ {
+        self.pages.keys().copied()
+    }
+
+    /// Elements on a page, top to bottom
+    pub fn on_page(&self, page_number: u32) -> impl Iterator<Item = usize> + '_ {
+        self.entries(page_number).iter().map(|e| e.index)
+    }
+
+    /// Elements on a page whose vertical extent intersects `[top, bottom]`
+    pub fn query_vertical(
+        &self,
+        page_number: u32,
+        top: f32,
+        bottom: f32,
+    ) -> impl Iterator<Item = usize> + '_ {
+        let (entries, max_height) = match self.pages.get(&page_number) {
+            Some(page) => (page.entries.as_slice(), page.max_height),
+            None => (&[][..], 0.0),
+        };
+        // Nothing starting more than max_height above the band can reach into it
+        let start = entries.partition_point(|e| e.top < top - max_height);
+        entries[start..]
+            .iter()
+            .take_while(move |e| e.top <= bottom)
+            .filter(move |e| e.bottom >= top)
+            .map(|e| e.index)
+    }
+
+    /// Closest element starting below `bbox` that overlaps it horizontally,
+    /// within `max_distance`; returns its position and the vertical gap
+    pub fn nearest_below(
+        &self,
+        page_number: u32,
+        bbox: &BoundingBox,
+        max_distance: f32,
+        exclude: usize,
+    ) -> Option<(usize, f32)> {
+        let bottom = bbox.y + bbox.height;
+        let (left, right) = (bbox.x, bbox.x + bbox.width);
+        let entries = self.entries(page_number);
+        let start = entries.partition_point(|e| e.top < bottom);
+
+        entries[start..]
+            .iter()
+            .take_while(|e| e.top - bottom <= max_distance)
+            .find(|e| e.index != exclude && e.left < right && left < e.right)
+            .map(|e| (e.index, e.top - bottom))
+    }
+
+    fn entries(&self, page_number: u32) -> &[Entry] {
+        self.pages
+            .get(&page_number)
+            .map(|p| p.entries.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::bbox;
+
+    #[test]
+    fn queries_bands_and_neighbors_per_page() {
+        // Two columns on page 1, one box on page 2
+        let boxes = [
+            (1, bbox(50.0, 100.0, 200.0, 12.0)),  // 0: left column, line 1
+            (1, bbox(300.0, 100.0, 200.0, 12.0)), // 1: right column, line 1
+            (1, bbox(50.0, 116.0, 200.0, 12.0)),  // 2: left column, line 2
+            (1, bbox(300.0, 150.0, 200.0, 12.0)), // 3: right column, after a gap
+            (2, bbox(50.0, 100.0, 200.0, 12.0)),  // 4: next page
+        ];
+        let index = PageSpatialIndex::new(boxes.iter().map(|(p, b)| (*p, b)));
+
+        assert_eq!(index.pages().collect::<Vec<_>>(), vec![1, 2]);
+
+        let mut band: Vec<usize> = index.query_vertical(1, 105.0, 110.0).collect();
+        band.sort_unstable();
+        assert_eq!(band, vec![0, 1]);
+
+        // The right column's nearest neighbour is in its own column, not the left one
+        assert_eq!(index.nearest_below(1, &boxes[1].1, 100.0, 1), Some((3, 38.0)));
+        assert_eq!(index.nearest_below(1, &boxes[0].1, 100.0, 0), Some((2, 4.0)));
+        assert_eq!(index.nearest_below(1, &boxes[1].1, 20.0, 1), None);
+        assert_eq!(index.on_page(2).collect::<Vec<_>>(), vec![4]);
+    }
+}
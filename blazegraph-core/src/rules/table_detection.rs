@@ -0,0 +1,381 @@
+use super::engine::ParseRule;
+use crate::config::TableDetectionConfig;
+use crate::types::{join_table_cells, BoundingBox, ParsedElementType, ParsedPdfElement, TableData};
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+/// Table detection from spatial layout
+///
+/// PDFs carry no table markup: a table arrives as short text runs laid out
+/// on a grid. Elements on a page are grouped into lines; a line with text
+/// runs separated by at least `min_column_gap` is a candidate row. A block
+/// of candidate rows (close together vertically) is a table when vertical
+/// gutters, empty in every row, split it into at least `min_columns`
+/// columns. Lines inside a block that only fill later columns continue the
+/// previous row (wrapped cell text).
+///
+/// Each table becomes one `Table` element, with cells in `TableData` and
+/// the rows as text, placed where its first element was. Works on raw
+/// lines, so it belongs before `SpatialClustering`.
+pub struct TableDetectionRule<'a> {
+    config: &'a TableDetectionConfig,
+}
+
+/// Text on one line between gutters: neighbouring elements closer than
+/// `min_column_gap`
+struct Segment {
+    x0: f32,
+    x1: f32,
+    text: String,
+}
+
+/// Elements sharing a baseline
+struct Line {
+    elements: Vec<usize>,
+    segments: Vec<Segment>,
+    top: f32,
+    bottom: f32,
+}
+
+/// A detected table: its lines, and column x-ranges
+struct Block {
+    lines: Vec<usize>,
+    columns: Vec<(f32, f32)>,
+}
+
+impl<'a> TableDetectionRule<'a> {
+    pub fn new(config: &'a TableDetectionConfig) -> Self {
+        Self { config }
+    }
+
+    /// Group a page's paragraph elements into lines, top to bottom
+    fn lines(&self, elements: &[ParsedPdfElement], indices: &[usize]) -> Vec<Line> {
+        let mut sorted = indices.to_vec();
+        sorted.sort_by(|&a, &b| center_y(&elements[a]).total_cmp(&center_y(&elements[b])));
+
+        let mut lines: Vec<Line> = Vec::new();
+        for index in sorted {
+            let bbox = &elements[index].bounding_box;
+            let same_line = lines.last().is_some_and(|line| {
+                let tolerance = (line.bottom - line.top).min(bbox.height) / 2.0;
+                (center_y(&elements[index]) - (line.top + line.bottom) / 2.0).abs() <= tolerance
+            });
+            if same_line {
+                let line = lines.last_mut().unwrap();
+                line.elements.push(index);
+                line.top = line.top.min(bbox.y);
+                line.bottom = line.bottom.max(bbox.y + bbox.height);
+            } else {
+                lines.push(Line {
+                    elements: vec![index],
+                    segments: Vec::new(),
+                    top: bbox.y,
+                    bottom: bbox.y + bbox.height,
+                });
+            }
+        }
+
+        for line in &mut lines {
+            line.elements
+                .sort_by(|&a, &b| elements[a].bounding_box.x.total_cmp(&elements[b].bounding_box.x));
+            for &index in &line.elements {
+                let element = &elements[index];
+                let (x0, x1) = (element.bounding_box.x, element.bounding_box.x + element.bounding_box.width);
+                let text = element.text.trim();
+                match line.segments.last_mut() {
+                    Some(segment) if x0 - segment.x1 < self.config.min_column_gap => {
+                        segment.x1 = segment.x1.max(x1);
+                        segment.text.push(' ');
+                        segment.text.push_str(text);
+                    }
+                    _ => line.segments.push(Segment {
+                        x0,
+                        x1,
+                        text: text.to_string(),
+                    }),
+                }
+            }
+        }
+        lines
+    }
+
+    /// Find table blocks among a page's lines
+    fn blocks(&self, lines: &[Line]) -> Vec<Block> {
+        let line_height = median(lines.iter().map(|line| line.bottom - line.top).collect());
+        let max_gap = line_height * self.config.max_row_gap;
+        let is_row = |line: &Line| line.segments.len() >= self.config.min_columns;
+
+        let mut blocks = Vec::new();
+        let mut start = 0;
+        while start < lines.len() {
+            if !is_row(&lines[start]) {
+                start += 1;
+                continue;
+            }
+            let mut rows = vec![start];
+            let mut columns = gutter_columns(&[&lines[start]]);
+            let mut next = start + 1;
+            // Lines that aren't rows are kept only when a row follows them
+            let mut pending = Vec::new();
+            while next < lines.len() && lines[next].top - lines[next - 1].bottom <= max_gap {
+                if !is_row(&lines[next]) {
+                    pending.push(next);
+                    next += 1;
+                    continue;
+                }
+                let mut candidate: Vec<&Line> = rows.iter().chain(&pending).map(|&i| &lines[i]).collect();
+                candidate.push(&lines[next]);
+                let extended = gutter_columns(&candidate);
+                if extended.len() < self.config.min_columns {
+                    break;
+                }
+                rows.append(&mut pending);
+                rows.push(next);
+                columns = extended;
+                next += 1;
+            }
+            // Trailing lines stay only as wrapped text of the last row
+            for line in pending {
+                let continues = columns.len() > 1
+                    && lines[line].segments.iter().all(|segment| {
+                        segment.x0 >= columns[1].0 && columns.iter().any(|c| segment.x0 >= c.0 && segment.x1 <= c.1)
+                    });
+                if !continues {
+                    break;
+                }
+                rows.push(line);
+            }
+
+            let row_count = rows.iter().filter(|&&i| is_row(&lines[i])).count();
+            let cells: Vec<&Segment> = rows.iter().flat_map(|&i| &lines[i].segments).collect();
+            let average_cell_chars =
+                cells.iter().map(|cell| cell.text.chars().count()).sum::<usize>() as f32 / cells.len() as f32;
+            if row_count >= self.config.min_rows && average_cell_chars <= self.config.max_cell_chars {
+                start = rows.last().unwrap() + 1;
+                blocks.push(Block { lines: rows, columns });
+            } else {
+                start += 1;
+            }
+        }
+        blocks
+    }
+
+    /// Build the Table element for a block
+    fn table(&self, elements: &[ParsedPdfElement], lines: &[Line], block: &Block) -> ParsedPdfElement {
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        let mut bold_rows = Vec::new();
+        for &line_index in &block.lines {
+            let line = &lines[line_index];
+            let mut cells = vec![String::new(); block.columns.len()];
+            for segment in &line.segments {
+                let column = column_of(&block.columns, segment);
+                if !cells[column].is_empty() {
+                    cells[column].push(' ');
+                }
+                cells[column].push_str(&segment.text);
+            }
+            match rows.last_mut() {
+                // Wrapped cell text: nothing in the first column
+                Some(previous) if cells[0].is_empty() => {
+                    for (cell, more) in previous.iter_mut().zip(cells) {
+                        if !more.is_empty() {
+                            if !cell.is_empty() {
+                                cell.push(' ');
+                            }
+                            cell.push_str(&more);
+                        }
+                    }
+                }
+                _ => {
+                    rows.push(cells);
+                    bold_rows.push(
+                        line.elements
+                            .iter()
+                            .all(|&i| elements[i].style_info.font_weight.eq_ignore_ascii_case("bold")),
+                    );
+                }
+            }
+        }
+        for row in &mut rows {
+            while row.last().is_some_and(|cell| cell.is_empty()) {
+                row.pop();
+            }
+        }
+
+        let members: Vec<&ParsedPdfElement> = block
+            .lines
+            .iter()
+            .flat_map(|&i| &lines[i].elements)
+            .map(|&i| &elements[i])
+            .collect();
+        let first = members.iter().min_by_key(|element| element.position).unwrap();
+        let header_rows = usize::from(bold_rows.len() > 1 && bold_rows[0] && !bold_rows[1..].iter().all(|&b| b));
+
+        ParsedPdfElement {
+            element_type: ParsedElementType::Table(TableData {
+                rows: rows.clone(),
+                header_rows,
+                caption: None,
+            }),
+            text: rows.iter().map(|row| join_table_cells(row)).collect::<Vec<_>>().join("\n"),
+            bounding_box: union(members.iter().map(|element| &element.bounding_box)),
+            token_count: members.iter().map(|element| element.token_count).sum(),
+            ..(*first).clone()
+        }
+    }
+}
+
+/// Column x-ranges: the spans covered by text in any of `lines`, split by
+/// gutters no line crosses
+fn gutter_columns(lines: &[&Line]) -> Vec<(f32, f32)> {
+    let mut spans: Vec<(f32, f32)> = lines
+        .iter()
+        .flat_map(|line| line.segments.iter().map(|segment| (segment.x0, segment.x1)))
+        .collect();
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut columns: Vec<(f32, f32)> = Vec::new();
+    for (x0, x1) in spans {
+        match columns.last_mut() {
+            Some(column) if x0 <= column.1 => column.1 = column.1.max(x1),
+            _ => columns.push((x0, x1)),
+        }
+    }
+    columns
+}
+
+/// Column holding most of a segment
+fn column_of(columns: &[(f32, f32)], segment: &Segment) -> usize {
+    let overlap = |&(x0, x1): &(f32, f32)| segment.x1.min(x1) - segment.x0.max(x0);
+    (0..columns.len())
+        .max_by(|&a, &b| overlap(&columns[a]).total_cmp(&overlap(&columns[b])))
+        .unwrap_or(0)
+}
+
+fn center_y(element: &ParsedPdfElement) -> f32 {
+    element.bounding_box.y + element.bounding_box.height / 2.0
+}
+
+fn median(mut values: Vec<f32>) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(f32::total_cmp);
+    values[values.len() / 2]
+}
+
+fn union<'b>(boxes: impl Iterator<Item = &'b BoundingBox>) -> BoundingBox {
+    let (mut x0, mut y0, mut x1, mut y1) = (f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for bbox in boxes {
+        x0 = x0.min(bbox.x);
+        y0 = y0.min(bbox.y);
+        x1 = x1.max(bbox.x + bbox.width);
+        y1 = y1.max(bbox.y + bbox.height);
+    }
+    BoundingBox {
+        x: x0,
+        y: y0,
+        width: x1 - x0,
+        height: y1 - y0,
+    }
+}
+
+impl ParseRule for TableDetectionRule<'_> {
+    fn apply(&self, elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+        if !self.config.enabled || elements.is_empty() {
+            return Ok(elements);
+        }
+
+        println!("🧮 APPLYING TABLE DETECTION...");
+        let mut pages: BTreeMap<u32, Vec<usize>> = BTreeMap::new();
+        for (index, element) in elements.iter().enumerate() {
+            if element.element_type == ParsedElementType::Paragraph {
+                pages.entry(element.page_number).or_default().push(index);
+            }
+        }
+
+        // Table per first member index; every member is dropped
+        let mut tables: BTreeMap<usize, ParsedPdfElement> = BTreeMap::new();
+        let mut consumed = vec![false; elements.len()];
+        for indices in pages.values() {
+            let lines = self.lines(&elements, indices);
+            for block in self.blocks(&lines) {
+                let members: Vec<usize> = block.lines.iter().flat_map(|&i| lines[i].elements.clone()).collect();
+                let table = self.table(&elements, &lines, &block);
+                for &index in &members {
+                    consumed[index] = true;
+                }
+                tables.insert(*members.iter().min().unwrap(), table);
+            }
+        }
+
+        let table_count = tables.len();
+        let mut result = Vec::with_capacity(elements.len());
+        for (index, element) in elements.into_iter().enumerate() {
+            if let Some(table) = tables.remove(&index) {
+                result.push(table);
+            } else if !consumed[index] {
+                result.push(element);
+            }
+        }
+
+        println!(
+            "   ✅ Found {} tables ({} elements absorbed)",
+            table_count,
+            consumed.iter().filter(|&&c| c).count()
+        );
+        Ok(result)
+    }
+
+    fn name(&self) -> &str {
+        "TableDetection"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{bbox, ParsedElementBuilder};
+
+    fn run(text: &str, x: f32, y: f32, width: f32, position: usize) -> ParsedPdfElement {
+        ParsedElementBuilder::new(text).level(1).bbox(bbox(x, y, width, 10.0)).order(position as u32).build()
+    }
+
+    #[test]
+    fn grid_of_short_runs_becomes_one_table() {
+        let mut elements = vec![run(
+            "Quarterly results are summarised in the table below for each region.",
+            72.0,
+            100.0,
+            400.0,
+            0,
+        )];
+        let rows = [
+            ["Region", "Q1", "Q2"],
+            ["North", "1,200", "1,350"],
+            ["South", "980", "1,010"],
+        ];
+        for (r, row) in rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                // Tika often emits cells out of reading order
+                let position = elements.len();
+                elements.push(run(cell, 72.0 + c as f32 * 120.0, 130.0 + r as f32 * 14.0, 40.0, position));
+            }
+        }
+        // Wrapped text in the second column continues the last row
+        elements.push(run("(est.)", 192.0, 172.0, 30.0, elements.len()));
+        elements.push(run("After the table, body text continues across the full width.", 72.0, 190.0, 400.0, 11));
+
+        let config = TableDetectionConfig::default();
+        let result = TableDetectionRule::new(&config).apply(elements).unwrap();
+
+        assert_eq!(result.len(), 3);
+        let ParsedElementType::Table(table) = &result[1].element_type else {
+            panic!("expected a table, got {:?}", result[1].element_type);
+        };
+        assert_eq!(table.rows[0], vec!["Region", "Q1", "Q2"]);
+        assert_eq!(table.rows[2], vec!["South", "980 (est.)", "1,010"]);
+        assert_eq!(result[1].text.lines().next(), Some("Region | Q1 | Q2"));
+        assert_eq!(result[1].bounding_box.y, 130.0);
+        assert_eq!(result[2].position, 11);
+    }
+}
@@ -1,67 +1,73 @@
 use super::engine::ParseRule;
-use crate::config::ParsingConfig;
+use crate::config::{ParsingConfig, ValidationPolicy};
+use crate::preprocessors::pdf::bookmark_matching::BookmarkMatcher;
 use crate::types::*;
-use anyhow::Result;
+use anyhow::{bail, Result};
+use std::collections::{HashMap, HashSet};
+
+/// 1.0 = perfect, 0.0 = an issue per element or more
+fn quality_score(issues: usize, total_elements: usize) -> f32 {
+    if total_elements == 0 {
+        1.0
+    } else {
+        (1.0 - (issues as f32 / total_elements as f32)).max(0.0)
+    }
+}
+
+/// Issue kinds `auto_repair` can fix
+const REPAIRABLE: &[&str] = &["hierarchy_jump", "reading_order_inconsistency", "invalid_position"];
 
 // ValidationRule - structural validation and consistency checks
 pub struct ValidationRule<'a> {
     config: &'a ParsingConfig,
 }
 
-#[derive(Debug, Clone)]
-pub struct ValidationReport {
-    pub issues: Vec<ValidationIssue>,
-    pub quality_score: f32,
-    pub total_elements: usize,
-}
-
-#[derive(Debug, Clone)]
-pub enum ValidationIssue {
-    HierarchyJump {
-        from_level: u32,
-        to_level: u32,
-        from_pos: usize,
-        to_pos: usize,
-    },
-    OrphanedElement {
-        level: u32,
-        position: usize,
-        text_preview: String,
-    },
-    SuspiciousSection {
-        position: usize,
-        text: String,
-        reason: String,
-    },
-    ReadingOrderInconsistency {
-        position: usize,
-        expected_order: u32,
-        actual_order: u32,
-    },
-    PageInconsistency {
-        position: usize,
-        page: u32,
-        issue: String,
-    },
-    InvalidPosition {
-        position: usize,
-        coordinates: String,
-    },
-}
-
 impl<'a> ValidationRule<'a> {
     pub fn new(config: &'a ParsingConfig) -> Self {
         Self { config }
     }
-}
 
-impl<'a> ParseRule for ValidationRule<'a> {
-    fn apply(&self, elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+    /// The policy for one issue kind: as configured, else repair (with
+    /// `fix`, when the kind can be repaired) or report
+    fn policy(&self, kind: &str) -> ValidationPolicy {
+        let validation = &self.config.validation;
+        match validation.policies.get(kind) {
+            Some(policy) => *policy,
+            None if validation.fix && REPAIRABLE.contains(&kind) => ValidationPolicy::AutoRepair,
+            None => ValidationPolicy::Warn,
+        }
+    }
+
+    fn check_policies(&self) -> Result<()> {
+        for (kind, policy) in &self.config.validation.policies {
+            if !ValidationIssueKind::NAMES.contains(&kind.as_str()) {
+                bail!(
+                    "Unknown validation issue kind '{}' in validation.policies (kinds: {})",
+                    kind,
+                    ValidationIssueKind::NAMES.join(", ")
+                );
+            }
+            if *policy == ValidationPolicy::AutoRepair && !REPAIRABLE.contains(&kind.as_str()) {
+                bail!(
+                    "'{}' issues can't be auto-repaired (repairable: {})",
+                    kind,
+                    REPAIRABLE.join(", ")
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate (and repair the kinds whose policy is `auto_repair`) the
+    /// elements, returning the report the rule engine puts in the graph's
+    /// structural profile. Fails when an issue's policy is `fail_run`
+    pub fn validate(&self, elements: Vec<ParsedPdfElement>) -> Result<(Vec<ParsedPdfElement>, ValidationReport)> {
         println!("🔍 APPLYING STRUCTURAL VALIDATION...");
         println!(
             "   🔍 Validating {} elements for structural consistency",
             elements.len()
         );
+        self.check_policies()?;
 
         // Perform validation checks and generate report
         let validation_report = self.validate_structure(&elements);
@@ -69,9 +75,36 @@ impl<'a> ParseRule for ValidationRule<'a> {
         // Print validation results
         self.print_validation_report(&validation_report);
 
-        // For now, return elements unchanged (pure validation)
-        // In the future, we could optionally fix some issues if needed
-        Ok(elements)
+        let repairs = validation_report
+            .issues
+            .iter()
+            .any(|issue| self.policy(issue.kind.name()) == ValidationPolicy::AutoRepair);
+        let (elements, report) = if repairs {
+            let (elements, fixes) = self.fix_structure(elements, &validation_report);
+            println!(
+                "   🔧 Fixed: {} zero-area elements dropped, {} hierarchy levels clamped, {} reading orders renumbered",
+                fixes.dropped, fixes.clamped, fixes.renumbered
+            );
+            let mut remaining = self.validate_structure(&elements);
+            println!(
+                "   📈 Quality Score after fixes: {:.2}/1.00 ({} issues left)",
+                remaining.quality_score,
+                remaining.issues.len()
+            );
+            remaining.fixes = Some(fixes);
+            (elements, remaining)
+        } else {
+            (elements, validation_report)
+        };
+
+        self.fail_on_policy(&report)?;
+        Ok((elements, report))
+    }
+}
+
+impl<'a> ParseRule for ValidationRule<'a> {
+    fn apply(&self, elements: Vec<ParsedPdfElement>) -> Result<Vec<ParsedPdfElement>> {
+        Ok(self.validate(elements)?.0)
     }
 
     fn name(&self) -> &str {
@@ -80,6 +113,69 @@ impl<'a> ParseRule for ValidationRule<'a> {
 }
 
 impl<'a> ValidationRule<'a> {
+    /// Repair the issue kinds whose policy is `auto_repair`. Other issues
+    /// (orphans beyond max depth, suspicious sections, page jumps) are only
+    /// reported
+    fn fix_structure(
+        &self,
+        elements: Vec<ParsedPdfElement>,
+        report: &ValidationReport,
+    ) -> (Vec<ParsedPdfElement>, ValidationFixes) {
+        let mut fixes = ValidationFixes::default();
+
+        let repair = |kind: &str| self.policy(kind) == ValidationPolicy::AutoRepair;
+
+        // Zero-area boxes are degenerate extraction output (clipped or hidden text)
+        let before = elements.len();
+        let mut elements: Vec<ParsedPdfElement> = elements
+            .into_iter()
+            .filter(|e| !repair("invalid_position") || (e.bounding_box.width > 0.0 && e.bounding_box.height > 0.0))
+            .collect();
+        fixes.dropped = before - elements.len();
+
+        // Clamp each level to one below the previous element's. Content sits
+        // one level below its section, so it moves with a clamped section
+        let mut shift = 0;
+        let mut previous: Option<u32> = None;
+        for element in elements.iter_mut().filter(|_| repair("hierarchy_jump")) {
+            let is_section = element.element_type == ParsedElementType::Section;
+            let mut level = if is_section {
+                element.hierarchy_level
+            } else {
+                element.hierarchy_level.saturating_sub(shift)
+            };
+            if let Some(previous) = previous {
+                level = level.min(previous + 1);
+            }
+            if is_section {
+                shift = element.hierarchy_level - level;
+            }
+            if level != element.hierarchy_level {
+                element.hierarchy_level = level;
+                fixes.clamped += 1;
+            }
+            previous = Some(level);
+        }
+
+        // The element sequence is the order the graph is built in, so
+        // reading_order follows it
+        let reading_order_issues = repair("reading_order_inconsistency")
+            && report
+                .issues
+                .iter()
+                .any(|issue| matches!(issue.kind, ValidationIssueKind::ReadingOrderInconsistency { .. }));
+        if reading_order_issues {
+            for (order, element) in elements.iter_mut().enumerate() {
+                if element.reading_order != order as u32 {
+                    element.reading_order = order as u32;
+                    fixes.renumbered += 1;
+                }
+            }
+        }
+
+        (elements, fixes)
+    }
+
     /// Perform comprehensive structural validation
     fn validate_structure(&self, elements: &[ParsedPdfElement]) -> ValidationReport {
         let mut issues = Vec::new();
@@ -100,18 +196,128 @@ impl<'a> ValidationRule<'a> {
         // 5. Check for suspicious sections
         self.validate_section_quality(elements, &mut issues);
 
-        // Calculate quality score (1.0 = perfect, 0.0 = many issues)
-        let quality_score = if total_elements == 0 {
-            1.0
-        } else {
-            (1.0 - (issues.len() as f32 / total_elements as f32)).max(0.0)
-        };
-
+        self.apply_policies(&mut issues);
         ValidationReport {
+            quality_score: quality_score(issues.len(), total_elements),
             issues,
-            quality_score,
             total_elements,
+            fixes: None,
+            outline: None,
+        }
+    }
+
+    /// Drop ignored kinds; fail_run ones are errors
+    fn apply_policies(&self, issues: &mut Vec<ValidationIssue>) {
+        issues.retain(|issue| self.policy(issue.kind.name()) != ValidationPolicy::Ignore);
+        for issue in issues {
+            if self.policy(issue.kind.name()) == ValidationPolicy::FailRun {
+                issue.severity = ValidationSeverity::Error;
+            }
+        }
+    }
+
+    fn fail_on_policy(&self, report: &ValidationReport) -> Result<()> {
+        let failing: Vec<&str> = report
+            .issues
+            .iter()
+            .filter(|issue| self.policy(issue.kind.name()) == ValidationPolicy::FailRun)
+            .map(|issue| issue.kind.name())
+            .collect();
+        if !failing.is_empty() {
+            bail!(
+                "Validation failed: {} issue(s) with policy fail_run ({})",
+                failing.len(),
+                failing.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Compare the detected sections in `elements` (the pipeline's final
+    /// output) with the PDF outline: entries and sections are paired by
+    /// fuzzy title match, best scores first, and the leftovers are added to
+    /// `report` as `missing_section` and `extra_section` issues, under their
+    /// policies
+    pub fn check_outline(
+        &self,
+        elements: &[ParsedPdfElement],
+        bookmarks: &BookmarkData,
+        report: &mut ValidationReport,
+    ) -> Result<()> {
+        let matcher = BookmarkMatcher::new(&bookmarks.sections);
+        if matcher.is_empty() {
+            return Ok(());
+        }
+        let sections: Vec<(usize, &ParsedPdfElement)> = elements
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.element_type == ParsedElementType::Section)
+            .collect();
+
+        // Every (score, section, bookmark order) candidate, best first
+        let mut pairs: Vec<(f32, usize, u32)> = sections
+            .iter()
+            .enumerate()
+            .flat_map(|(index, (_, section))| {
+                matcher
+                    .matches(&section.text, section.page_number)
+                    .into_iter()
+                    .map(move |(bookmark, score)| (score, index, bookmark.order))
+            })
+            .collect();
+        pairs.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let mut matched_sections = HashSet::new();
+        let mut matched_bookmarks = HashSet::new();
+        let mut total_score = 0.0;
+        for &(score, section, order) in &pairs {
+            if !matched_sections.contains(&section) && !matched_bookmarks.contains(&order) {
+                matched_sections.insert(section);
+                matched_bookmarks.insert(order);
+                total_score += score;
+            }
+        }
+
+        let titles: HashMap<u32, &str> = bookmarks.sections.iter().map(|b| (b.order, b.title.trim())).collect();
+        let outline: Vec<&BookmarkSection> = bookmarks.sections.iter().filter(|b| !b.title.trim().is_empty()).collect();
+        let mut issues = Vec::new();
+        for bookmark in outline.iter().filter(|b| !matched_bookmarks.contains(&b.order)) {
+            let closest = pairs.iter().find(|pair| pair.2 == bookmark.order);
+            issues.push(ValidationIssue::new(ValidationIssueKind::MissingSection {
+                bookmark: bookmark.title.trim().to_string(),
+                level: bookmark.level,
+                page: bookmark.page,
+                closest: closest.map(|pair| sections[pair.1].1.text.trim().to_string()),
+                score: closest.map_or(0.0, |pair| pair.0),
+            }));
+        }
+        for (index, (position, section)) in sections.iter().enumerate() {
+            if matched_sections.contains(&index) {
+                continue;
+            }
+            let closest = pairs.iter().find(|pair| pair.1 == index);
+            issues.push(ValidationIssue::new(ValidationIssueKind::ExtraSection {
+                position: *position,
+                text: section.text.chars().take(50).collect(),
+                closest: closest.map(|pair| titles[&pair.2].to_string()),
+                score: closest.map_or(0.0, |pair| pair.0),
+            }));
         }
+
+        let check = OutlineCheck {
+            bookmarks: outline.len(),
+            sections: sections.len(),
+            matched: matched_sections.len(),
+            mean_score: if matched_sections.is_empty() { 0.0 } else { total_score / matched_sections.len() as f32 },
+        };
+        println!(
+            "   📑 Outline check: {}/{} bookmarks matched to {} sections (mean score {:.2})",
+            check.matched, check.bookmarks, check.sections, check.mean_score
+        );
+        self.apply_policies(&mut issues);
+        report.issues.extend(issues);
+        report.quality_score = quality_score(report.issues.len(), report.total_elements);
+        report.outline = Some(check);
+        self.fail_on_policy(report)
     }
 
     /// Check for hierarchy jumps and orphaned elements
@@ -125,11 +331,11 @@ impl<'a> ValidationRule<'a> {
         for (i, element) in elements.iter().enumerate() {
             // Check for hierarchy exceeding max depth
             if element.hierarchy_level > max_depth {
-                issues.push(ValidationIssue::OrphanedElement {
+                issues.push(ValidationIssue::new(ValidationIssueKind::OrphanedElement {
                     level: element.hierarchy_level,
                     position: i,
                     text_preview: element.text.chars().take(50).collect(),
-                });
+                }));
             }
 
             // Check for hierarchy jumps (skipping levels)
@@ -139,12 +345,12 @@ impl<'a> ValidationRule<'a> {
 
                 // Flag jumps of more than 1 level
                 if curr_level > prev_level + 1 {
-                    issues.push(ValidationIssue::HierarchyJump {
+                    issues.push(ValidationIssue::new(ValidationIssueKind::HierarchyJump {
                         from_level: prev_level,
                         to_level: curr_level,
                         from_pos: i - 1,
                         to_pos: i,
-                    });
+                    }));
                 }
             }
         }
@@ -163,11 +369,11 @@ impl<'a> ValidationRule<'a> {
             if element.reading_order < expected_order.saturating_sub(5)
                 || element.reading_order > expected_order + 10
             {
-                issues.push(ValidationIssue::ReadingOrderInconsistency {
+                issues.push(ValidationIssue::new(ValidationIssueKind::ReadingOrderInconsistency {
                     position: i,
                     expected_order,
                     actual_order: element.reading_order,
-                });
+                }));
             }
             expected_order = element.reading_order + 1;
         }
@@ -184,13 +390,13 @@ impl<'a> ValidationRule<'a> {
 
             // Check for impossible coordinates
             if bbox.x < 0.0 || bbox.y < 0.0 || bbox.width <= 0.0 || bbox.height <= 0.0 {
-                issues.push(ValidationIssue::InvalidPosition {
+                issues.push(ValidationIssue::new(ValidationIssueKind::InvalidPosition {
                     position: i,
                     coordinates: format!(
                         "x:{:.1}, y:{:.1}, w:{:.1}, h:{:.1}",
                         bbox.x, bbox.y, bbox.width, bbox.height
                     ),
-                });
+                }));
             }
         }
     }
@@ -204,11 +410,11 @@ impl<'a> ValidationRule<'a> {
         for (i, element) in elements.iter().enumerate() {
             // Check for reasonable page numbers
             if element.page_number == 0 {
-                issues.push(ValidationIssue::PageInconsistency {
+                issues.push(ValidationIssue::new(ValidationIssueKind::PageInconsistency {
                     position: i,
                     page: element.page_number,
                     issue: "Page number is 0 (should start from 1)".to_string(),
-                });
+                }));
             }
 
             // Check for huge page number jumps (might indicate parsing issues)
@@ -217,11 +423,11 @@ impl<'a> ValidationRule<'a> {
                 let curr_page = element.page_number;
                 if curr_page > prev_page + 5 {
                     // Allow some tolerance
-                    issues.push(ValidationIssue::PageInconsistency {
+                    issues.push(ValidationIssue::new(ValidationIssueKind::PageInconsistency {
                         position: i,
                         page: curr_page,
                         issue: format!("Large page jump from {} to {}", prev_page, curr_page),
-                    });
+                    }));
                 }
             }
         }
@@ -239,20 +445,20 @@ impl<'a> ValidationRule<'a> {
 
                 // Flag very short sections
                 if text.len() < 3 {
-                    issues.push(ValidationIssue::SuspiciousSection {
+                    issues.push(ValidationIssue::new(ValidationIssueKind::SuspiciousSection {
                         position: i,
                         text: text.to_string(),
                         reason: "Section text too short (< 3 characters)".to_string(),
-                    });
+                    }));
                 }
 
                 // Flag sections that are too long (might be misclassified paragraphs)
                 if text.len() > 200 {
-                    issues.push(ValidationIssue::SuspiciousSection {
+                    issues.push(ValidationIssue::new(ValidationIssueKind::SuspiciousSection {
                         position: i,
                         text: text.chars().take(50).collect::<String>() + "...",
                         reason: "Section text unusually long (> 200 characters)".to_string(),
-                    });
+                    }));
                 }
             }
         }
@@ -269,69 +475,219 @@ impl<'a> ValidationRule<'a> {
         } else {
             println!("      ⚠️  Issues detected:");
             for issue in &report.issues {
-                match issue {
-                    ValidationIssue::HierarchyJump {
+                let severity = match issue.severity {
+                    ValidationSeverity::Info => "info ",
+                    ValidationSeverity::Warn => "warn ",
+                    ValidationSeverity::Error => "error",
+                };
+                print!("         [{}] ", severity);
+                match &issue.kind {
+                    ValidationIssueKind::HierarchyJump {
                         from_level,
                         to_level,
                         from_pos,
                         to_pos,
                     } => {
                         println!(
-                            "         📊 Hierarchy jump: Level {} → {} (positions {}-{})",
+                            "📊 Hierarchy jump: Level {} → {} (positions {}-{})",
                             from_level, to_level, from_pos, to_pos
                         );
                     }
-                    ValidationIssue::OrphanedElement {
+                    ValidationIssueKind::OrphanedElement {
                         level,
                         position,
                         text_preview,
                     } => {
                         println!(
-                            "         🏝️  Orphaned element: Level {} at position {} (\"{}\")",
+                            "🏝️  Orphaned element: Level {} at position {} (\"{}\")",
                             level, position, text_preview
                         );
                     }
-                    ValidationIssue::SuspiciousSection {
+                    ValidationIssueKind::SuspiciousSection {
                         position,
                         text,
                         reason,
                     } => {
                         println!(
-                            "         🤔 Suspicious section at {}: \"{}\" ({})",
+                            "🤔 Suspicious section at {}: \"{}\" ({})",
                             position, text, reason
                         );
                     }
-                    ValidationIssue::ReadingOrderInconsistency {
+                    ValidationIssueKind::ReadingOrderInconsistency {
                         position,
                         expected_order,
                         actual_order,
                     } => {
                         println!(
-                            "         📖 Reading order issue at {}: expected ~{}, got {}",
+                            "📖 Reading order issue at {}: expected ~{}, got {}",
                             position, expected_order, actual_order
                         );
                     }
-                    ValidationIssue::PageInconsistency {
+                    ValidationIssueKind::PageInconsistency {
                         position,
                         page,
                         issue,
                     } => {
                         println!(
-                            "         📄 Page issue at {} (page {}): {}",
+                            "📄 Page issue at {} (page {}): {}",
                             position, page, issue
                         );
                     }
-                    ValidationIssue::InvalidPosition {
+                    ValidationIssueKind::InvalidPosition {
                         position,
                         coordinates,
                     } => {
                         println!(
-                            "         📍 Invalid coordinates at {}: {}",
+                            "📍 Invalid coordinates at {}: {}",
                             position, coordinates
                         );
                     }
+                    ValidationIssueKind::MissingSection {
+                        bookmark,
+                        closest,
+                        score,
+                        ..
+                    } => {
+                        println!(
+                            "🔖 Bookmark without a section: \"{}\" (closest: {})",
+                            bookmark,
+                            closest.as_ref().map_or("none".to_string(), |c| format!("\"{}\" at {:.2}", c, score))
+                        );
+                    }
+                    ValidationIssueKind::ExtraSection {
+                        position,
+                        text,
+                        closest,
+                        score,
+                    } => {
+                        println!(
+                            "➕ Section without a bookmark at {}: \"{}\" (closest: {})",
+                            position,
+                            text,
+                            closest.as_ref().map_or("none".to_string(), |c| format!("\"{}\" at {:.2}", c, score))
+                        );
+                    }
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ValidationConfig, ValidationPolicy};
+    use crate::test_utils::{bbox, ParsedElementBuilder};
+
+    fn element(element_type: ParsedElementType, text: &str, level: u32, order: u32) -> ParsedPdfElement {
+        ParsedElementBuilder::new(text)
+            .element_type(element_type)
+            .level(level)
+            .bbox(bbox(72.0, 100.0 + order as f32 * 14.0, 400.0, 12.0))
+            .paragraph(order)
+            .order(order)
+            .build()
+    }
+
+    #[test]
+    fn fix_mode_repairs_structure() {
+        use ParsedElementType::{Paragraph, Section};
+        let mut elements = vec![
+            element(Section, "1 Scope", 1, 0),
+            element(Section, "1.1.1 Terms", 3, 1),
+            element(Paragraph, "Defined here.", 4, 2),
+            element(Paragraph, "", 2, 3),
+            element(Section, "2 Fees", 1, 40),
+        ];
+        elements[3].bounding_box.width = 0.0;
+
+        let report_only = ParsingConfig::default();
+        let unchanged = ValidationRule::new(&report_only).apply(elements.clone()).unwrap();
+        assert_eq!(unchanged.len(), elements.len());
+
+        let config = ParsingConfig {
+            validation: ValidationConfig {
+                fix: true,
+                ..ValidationConfig::default()
+            },
+            ..ParsingConfig::default()
+        };
+        let rule = ValidationRule::new(&config);
+        let (fixed, report) = rule.validate(elements.clone()).unwrap();
+        assert_eq!(report.fixes, Some(ValidationFixes { dropped: 1, clamped: 2, renumbered: 1 }));
+        assert_eq!((report.issues.len(), report.quality_score), (0, 1.0));
+        let levels: Vec<u32> = fixed.iter().map(|e| e.hierarchy_level).collect();
+        assert_eq!(levels, [1, 2, 3, 1], "the skipped level is closed up, with its content");
+        assert_eq!(fixed.iter().map(|e| e.reading_order).collect::<Vec<_>>(), [0, 1, 2, 3]);
+        assert!(rule.validate_structure(&fixed).issues.is_empty());
+
+        // Per-kind policies: repair only the hierarchy, ignore reading order,
+        // fail on bad boxes
+        let policies = |entries: &[(&str, ValidationPolicy)]| ParsingConfig {
+            validation: ValidationConfig {
+                fix: false,
+                policies: entries.iter().map(|(kind, policy)| (kind.to_string(), *policy)).collect(),
+            },
+            ..ParsingConfig::default()
+        };
+        let config = policies(&[
+            ("hierarchy_jump", ValidationPolicy::AutoRepair),
+            ("reading_order_inconsistency", ValidationPolicy::Ignore),
+        ]);
+        let (repaired, report) = ValidationRule::new(&config).validate(elements.clone()).unwrap();
+        assert_eq!(repaired.len(), elements.len());
+        assert_eq!(report.fixes, Some(ValidationFixes { dropped: 0, clamped: 3, renumbered: 0 }));
+        let kinds: Vec<(&str, ValidationSeverity)> = report.issues.iter().map(|i| (i.kind.name(), i.severity)).collect();
+        assert_eq!(kinds, [("invalid_position", ValidationSeverity::Error)]);
+
+        let config = policies(&[("invalid_position", ValidationPolicy::FailRun)]);
+        let error = ValidationRule::new(&config).validate(elements.clone()).unwrap_err();
+        assert!(error.to_string().contains("invalid_position"));
+        let config = policies(&[("suspicious_section", ValidationPolicy::AutoRepair)]);
+        assert!(ValidationRule::new(&config).validate(elements).is_err());
+    }
+
+    #[test]
+    fn outline_check_reports_missing_and_extra_sections() {
+        use ParsedElementType::{Paragraph, Section};
+        let bookmark = |title: &str, order: u32| BookmarkSection {
+            title: title.to_string(),
+            order,
+            level: 1,
+            parent: None,
+            page: Some(1),
+            confidence: None,
+        };
+        let bookmarks = BookmarkData {
+            sections: vec![bookmark("1 Introduction", 0), bookmark("2 Methods", 1), bookmark("3 Results", 2)],
+        };
+        let elements = vec![
+            element(Section, "Introduction", 1, 0),
+            element(Paragraph, "We study channels.", 2, 1),
+            element(Section, "2. METHODS", 1, 2),
+            element(Section, "Acknowledgements", 1, 3),
+        ];
+
+        let config = ParsingConfig::default();
+        let rule = ValidationRule::new(&config);
+        let (elements, mut report) = rule.validate(elements).unwrap();
+        rule.check_outline(&elements, &bookmarks, &mut report).unwrap();
+        let outline = report.outline.as_ref().unwrap();
+        assert_eq!((outline.bookmarks, outline.sections, outline.matched), (3, 3, 2));
+        assert!(outline.mean_score > 0.9);
+        let kinds: Vec<&str> = report.issues.iter().map(|i| i.kind.name()).collect();
+        assert_eq!(kinds, ["missing_section", "extra_section"]);
+        assert!(matches!(&report.issues[0].kind, ValidationIssueKind::MissingSection { bookmark, .. } if bookmark == "3 Results"));
+        assert_eq!(report.quality_score, 0.5);
+
+        let strict = ParsingConfig {
+            validation: ValidationConfig {
+                policies: [("missing_section".to_string(), ValidationPolicy::FailRun)].into(),
+                ..ValidationConfig::default()
+            },
+            ..ParsingConfig::default()
+        };
+        let mut report = ValidationRule::new(&strict).validate(elements.clone()).unwrap().1;
+        assert!(ValidationRule::new(&strict).check_outline(&elements, &bookmarks, &mut report).is_err());
+    }
+}
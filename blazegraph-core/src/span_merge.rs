@@ -0,0 +1,250 @@
+//! Letter-spaced and word-per-span text merging
+//!
+//! Decorative titles are often set with wide tracking, and PDF producers then
+//! emit one span per letter ("I", "N", "T", ...) or per word. Each fragment
+//! becomes its own element and is too short to pass section detection, so a
+//! title like "I N T R O D U C T I O N" turns into ten one-letter paragraphs.
+//!
+//! Runs between page filtering and rule processing. On each line, runs of
+//! single-token spans with identical style are merged into one element:
+//! letters are joined directly, words with a space, and a gap noticeably
+//! wider than the run's letter spacing starts a new word. Spans whose own
+//! text is spaced out ("I N T R O") are collapsed too. Wide gaps (table
+//! columns) and style changes end a run.
+
+use crate::config::SpanMergeConfig;
+use crate::preprocessors::pdf::xhtml_parser::estimate_token_count;
+use crate::types::*;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Two or more whitespace characters separate words in spaced-out text
+static WORD_GAP_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s{2,}").unwrap());
+
+/// A gap this many times the run's typical letter gap separates words
+const WORD_GAP_FACTOR: f32 = 1.8;
+/// ...and must also exceed the letter gap by this fraction of the font size
+const MIN_WORD_GAP_EM: f32 = 0.15;
+
+/// Merge letter-spaced and word-per-span runs in place; returns how many
+/// elements were merged away
+pub fn merge_spans(text_elements: &mut Vec<PdfTextElement>, config: &SpanMergeConfig) -> usize {
+    if !config.enabled {
+        return 0;
+    }
+
+    if config.collapse_spaced_letters {
+        // Table rows carry cell text, which is never decorative
+        for element in text_elements.iter_mut().filter(|e| e.paragraph_role() != Some("table-row")) {
+            if let Some(text) = collapse_spaced_letters(&element.text, config.min_spaced_letters) {
+                element.token_count = estimate_token_count(&text);
+                element.text = text;
+            }
+        }
+    }
+
+    let input_count = text_elements.len();
+    let elements = std::mem::take(text_elements);
+    let mut index = 0;
+    while index < elements.len() {
+        let end = run_end(&elements, index, config);
+        let fragments = elements[index..end]
+            .iter()
+            .filter(|e| !e.text.trim().is_empty())
+            .count();
+        if fragments >= config.min_spans.max(2) {
+            text_elements.push(merge_run(&elements[index..end]));
+        } else {
+            text_elements.extend(elements[index..end].iter().cloned());
+        }
+        index = end;
+    }
+
+    let merged = input_count - text_elements.len();
+    if merged > 0 {
+        println!("🔠 Merged {} letter-spaced/word-per-span fragment(s) into whole lines", merged);
+    }
+    merged
+}
+
+/// "I N T R O D U C T I O N" → "INTRODUCTION"; double spaces separate words
+fn collapse_spaced_letters(text: &str, min_letters: usize) -> Option<String> {
+    let text = text.trim();
+    let words: Vec<Vec<&str>> = WORD_GAP_REGEX
+        .split(text)
+        .map(|word| word.split_whitespace().collect())
+        .collect();
+    let letters: usize = words.iter().map(Vec::len).sum();
+    let spaced_out = letters >= min_letters.max(2)
+        && words
+            .iter()
+            .flatten()
+            .all(|piece| piece.chars().count() == 1 && piece.chars().all(char::is_alphanumeric));
+    spaced_out.then(|| {
+        words
+            .iter()
+            .map(|word| word.concat())
+            .collect::<Vec<_>>()
+            .join(" ")
+    })
+}
+
+/// Single token (no inner whitespace) or pure whitespace; never a figure
+fn is_fragment(element: &PdfTextElement) -> bool {
+    !element.is_figure() && !element.text.trim().contains(char::is_whitespace)
+}
+
+fn same_style(a: &FontClass, b: &FontClass) -> bool {
+    a.font_family == b.font_family
+        && a.font_size == b.font_size
+        && a.font_style == b.font_style
+        && a.font_weight == b.font_weight
+}
+
+/// Right of `previous` on the same line, close enough not to be another column
+fn continues_line(previous: &PdfTextElement, next: &PdfTextElement, config: &SpanMergeConfig) -> bool {
+    let (a, b) = (&previous.bounding_box, &next.bounding_box);
+    let gap = b.x - (a.x + a.width);
+    next.page_number == previous.page_number
+        && (a.y - b.y).abs() <= config.y_tolerance
+        && b.x >= a.x
+        && gap <= config.max_gap_em * previous.style_info.font_size
+}
+
+/// End (exclusive) of the run of mergeable spans starting at `start`
+fn run_end(elements: &[PdfTextElement], start: usize, config: &SpanMergeConfig) -> usize {
+    if !is_fragment(&elements[start]) || elements[start].text.trim().is_empty() {
+        return start + 1;
+    }
+    // Whitespace spans may sit between fragments; style is compared across them
+    let mut styled = start;
+    let mut end = start + 1;
+    while let Some(next) = elements.get(end) {
+        if !is_fragment(next) || !continues_line(&elements[end - 1], next, config) {
+            break;
+        }
+        if !next.text.trim().is_empty() {
+            if !same_style(&elements[styled].style_info, &next.style_info) {
+                break;
+            }
+            styled = end;
+        }
+        end += 1;
+    }
+    // Don't swallow trailing whitespace spans
+    while end > start + 1 && elements[end - 1].text.trim().is_empty() {
+        end -= 1;
+    }
+    end
+}
+
+fn merge_run(run: &[PdfTextElement]) -> PdfTextElement {
+    // (token, gap before it, explicit whitespace span before it)
+    let mut tokens: Vec<(&str, f32, bool)> = Vec::new();
+    let mut explicit_space = false;
+    let mut previous_right: Option<f32> = None;
+    for element in run {
+        let bbox = &element.bounding_box;
+        let gap = previous_right.map_or(0.0, |right| bbox.x - right);
+        previous_right = Some(bbox.x + bbox.width);
+        let token = element.text.trim();
+        if token.is_empty() {
+            explicit_space = true;
+            continue;
+        }
+        tokens.push((token, gap, std::mem::take(&mut explicit_space)));
+    }
+
+    let is_letter = |token: &str| token.chars().count() == 1 && token.chars().all(char::is_alphanumeric);
+    let mut letter_gaps: Vec<f32> = tokens
+        .windows(2)
+        .filter(|pair| is_letter(pair[0].0) && is_letter(pair[1].0) && !pair[1].2)
+        .map(|pair| pair[1].1.max(0.0))
+        .collect();
+    letter_gaps.sort_by(f32::total_cmp);
+    let font_size = run[0].style_info.font_size;
+    let word_gap = letter_gaps
+        .get(letter_gaps.len() / 2)
+        .map(|&median| (median * WORD_GAP_FACTOR).max(median + MIN_WORD_GAP_EM * font_size));
+
+    let mut text = String::new();
+    for (index, &(token, gap, explicit_space)) in tokens.iter().enumerate() {
+        if index > 0 {
+            let previous = tokens[index - 1].0;
+            let letters = is_letter(previous) && is_letter(token);
+            let closing = token.starts_with(['.', ',', ';', ':', '!', '?', ')']);
+            if explicit_space
+                || (!letters && !closing)
+                || word_gap.is_some_and(|word_gap| gap > word_gap)
+            {
+                text.push(' ');
+            }
+        }
+        text.push_str(token);
+    }
+
+    let min_x = run.iter().map(|e| e.bounding_box.x).fold(f32::MAX, f32::min);
+    let min_y = run.iter().map(|e| e.bounding_box.y).fold(f32::MAX, f32::min);
+    let max_x = run.iter().map(|e| e.bounding_box.x + e.bounding_box.width).fold(f32::MIN, f32::max);
+    let max_y = run.iter().map(|e| e.bounding_box.y + e.bounding_box.height).fold(f32::MIN, f32::max);
+
+    PdfTextElement {
+        token_count: estimate_token_count(&text),
+        text,
+        bounding_box: BoundingBox {
+            x: min_x,
+            y: min_y,
+            width: max_x - min_x,
+            height: max_y - min_y,
+        },
+        ..run[0].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{bbox, FontClassBuilder, TextElementBuilder};
+
+    fn span(text: &str, x: f32, y: f32, width: f32) -> PdfTextElement {
+        TextElementBuilder::new(text)
+            .style(FontClassBuilder::new("f2").size(18.0).bold().build())
+            .bbox(bbox(x, y, width, 18.0))
+            .build()
+    }
+
+    #[test]
+    fn merges_letter_spaced_titles_but_not_table_cells() {
+        // "THE END" with 4pt tracking and a 16pt word gap
+        let mut x = 72.0;
+        let mut elements = Vec::new();
+        for (index, letter) in ["T", "H", "E", "E", "N", "D"].into_iter().enumerate() {
+            if index == 3 {
+                x += 12.0;
+            }
+            elements.push(span(letter, x, 100.0, 10.0));
+            x += 14.0;
+        }
+        // Word-per-span subtitle, including a whitespace span
+        elements.push(span("Part", 72.0, 130.0, 40.0));
+        elements.push(span(" ", 112.0, 130.0, 4.0));
+        elements.push(span("One", 116.0, 130.0, 30.0));
+        elements.push(span("Overview", 150.0, 130.0, 80.0));
+        // Table cells: same style but far apart
+        for (index, cell) in ["1", "2", "3"].into_iter().enumerate() {
+            elements.push(span(cell, 72.0 + index as f32 * 120.0, 160.0, 10.0));
+        }
+        elements.push(TextElementBuilder::new("I N T R O D U C T I O N").at(72.0, 200.0).build());
+        elements.push(TextElementBuilder::new("A full sentence of body text.").at(72.0, 220.0).build());
+
+        let merged = merge_spans(&mut elements, &SpanMergeConfig::default());
+        let texts: Vec<&str> = elements.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["THE END", "Part One Overview", "1", "2", "3", "INTRODUCTION", "A full sentence of body text."]
+        );
+        assert_eq!(merged, 8);
+        assert_eq!(elements[0].bounding_box.width, 92.0);
+        assert_eq!(elements[0].style_info.font_size, 18.0);
+    }
+}
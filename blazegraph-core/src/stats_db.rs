@@ -0,0 +1,353 @@
+//! Corpus statistics database and trend reports
+//!
+//! With a stats database, every processed document appends one row: its
+//! structural profile (type, node and token counts, token percentiles),
+//! warnings, a quality score, or the failure. Rows accumulate across
+//! ingestion runs, so operations keep a durable quality record and can see
+//! drift: `TrendReport` summarizes them per day, week or month, per
+//! document type and per input format.
+//!
+//! The database is an append-only JSONL file like the audit log, so it
+//! needs no server and survives crashes mid-run; DuckDB and SQLite read it
+//! directly (`read_json_auto('stats.jsonl')`) for ad-hoc queries.
+//!
+//! The quality score is a coarse operational signal in [0, 1], not a
+//! structural judgement: it starts at 1, loses 0.1 per processing warning
+//! (at most 0.5), 0.25 when a multi-node graph has no Section, and up to
+//! 0.25 for the share of nodes over `OVERSIZED_NODE_TOKENS`.
+
+use crate::audit::AuditStatus;
+use crate::graphs::GraphAnalytics;
+use crate::types::{DocumentGraph, DocumentNode};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Nodes above this many tokens count against the quality score
+pub const OVERSIZED_NODE_TOKENS: usize = 2_000;
+
+/// One processed document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileRow {
+    pub timestamp: DateTime<Utc>,
+    /// How the document arrived: "cli" or "serve"
+    pub mode: String,
+    pub input: String,
+    /// Input format (file extension), known even when processing failed
+    pub format: String,
+    pub status: AuditStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Classified document type; None when processing failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    #[serde(default)]
+    pub page_count: u32,
+    #[serde(default)]
+    pub nodes: usize,
+    #[serde(default)]
+    pub sections: usize,
+    #[serde(default)]
+    pub total_tokens: usize,
+    #[serde(default)]
+    pub p50_tokens: usize,
+    #[serde(default)]
+    pub p90_tokens: usize,
+    #[serde(default)]
+    pub warnings: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality_score: Option<f32>,
+    pub blazegraph_version: String,
+}
+
+impl ProfileRow {
+    fn new(mode: &str, input: &str, status: AuditStatus) -> Self {
+        let format = Path::new(input)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .unwrap_or_default();
+        Self {
+            timestamp: Utc::now(),
+            mode: mode.to_string(),
+            input: input.to_string(),
+            format,
+            status,
+            error: None,
+            document_type: None,
+            content_hash: None,
+            page_count: 0,
+            nodes: 0,
+            sections: 0,
+            total_tokens: 0,
+            p50_tokens: 0,
+            p90_tokens: 0,
+            warnings: 0,
+            quality_score: None,
+            blazegraph_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Row for a finished graph
+    pub fn succeeded(mode: &str, input: &str, graph: &DocumentGraph) -> Self {
+        let nodes: Vec<&DocumentNode> = graph.nodes.values().collect();
+        let percentiles = GraphAnalytics::token_percentiles(&nodes, &[50.0, 90.0]);
+        let mut row = Self::new(mode, input, AuditStatus::Succeeded);
+        row.document_type = Some(format!("{:?}", graph.structural_profile.document_type));
+        row.content_hash = graph.document_info.content_hash.clone();
+        row.page_count = graph.document_info.document_metadata.page_count;
+        row.nodes = nodes.len();
+        row.sections = nodes.iter().filter(|n| n.node_type == "Section").count();
+        row.total_tokens = nodes.iter().map(|n| n.token_count).sum();
+        row.p50_tokens = percentiles[0];
+        row.p90_tokens = percentiles[1];
+        row.warnings = graph.document_info.warnings.len();
+        row.quality_score = Some(quality_score(&nodes, row.sections, row.warnings));
+        row
+    }
+
+    pub fn failed(mode: &str, input: &str, error: &str) -> Self {
+        let mut row = Self::new(mode, input, AuditStatus::Failed);
+        row.error = Some(error.to_string());
+        row
+    }
+}
+
+fn quality_score(nodes: &[&DocumentNode], sections: usize, warnings: usize) -> f32 {
+    let mut score = 1.0 - (0.1 * warnings as f32).min(0.5);
+    if sections == 0 && nodes.len() > 1 {
+        score -= 0.25;
+    }
+    if !nodes.is_empty() {
+        let oversized = nodes.iter().filter(|n| n.token_count > OVERSIZED_NODE_TOKENS).count();
+        score -= 0.25 * oversized as f32 / nodes.len() as f32;
+    }
+    score.max(0.0)
+}
+
+/// A JSONL stats database, opened for appending
+pub struct StatsDb {
+    path: PathBuf,
+    file: File,
+}
+
+impl StatsDb {
+    /// Open (or create) the database; existing rows are never rewritten
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create stats database directory {}", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open stats database {}", path.display()))?;
+        Ok(Self { path, file })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every row of an existing database, oldest first
+    pub fn read(path: impl AsRef<Path>) -> Result<Vec<ProfileRow>> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read stats database {}", path.display()))?;
+        contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("Invalid stats row at {}:{}", path.display(), index + 1))
+            })
+            .collect()
+    }
+
+    /// Append one row as a single line and flush it to disk
+    pub fn record(&self, row: &ProfileRow) -> Result<()> {
+        let mut line = serde_json::to_string(row)?;
+        line.push('\n');
+        let mut file = &self.file;
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.sync_data())
+            .with_context(|| format!("Failed to write stats database {}", self.path.display()))
+    }
+}
+
+/// Trend bucket size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+}
+
+impl Period {
+    pub fn parse(period: &str) -> Result<Self> {
+        match period {
+            "day" => Ok(Period::Day),
+            "week" => Ok(Period::Week),
+            "month" => Ok(Period::Month),
+            other => bail!("Unknown period '{}' (use day, week or month)", other),
+        }
+    }
+
+    fn key(self, timestamp: &DateTime<Utc>) -> String {
+        match self {
+            Period::Day => timestamp.format("%Y-%m-%d"),
+            Period::Week => timestamp.format("%G-W%V"),
+            Period::Month => timestamp.format("%Y-%m"),
+        }
+        .to_string()
+    }
+}
+
+/// Totals over a group of rows
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Aggregate {
+    pub documents: usize,
+    pub failed: usize,
+    /// Mean quality score of the documents that succeeded
+    pub avg_quality: Option<f32>,
+    /// Tokens per node over every succeeded document
+    pub tokens_per_node: Option<f32>,
+    /// Mean of the documents' 90th-percentile node sizes
+    pub avg_p90_tokens: Option<f32>,
+}
+
+impl Aggregate {
+    fn of(rows: &[&ProfileRow]) -> Self {
+        let succeeded: Vec<&&ProfileRow> = rows.iter().filter(|r| r.status == AuditStatus::Succeeded).collect();
+        let mean = |values: Vec<f32>| (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32);
+        let nodes: usize = succeeded.iter().map(|r| r.nodes).sum();
+        Self {
+            documents: rows.len(),
+            failed: rows.len() - succeeded.len(),
+            avg_quality: mean(succeeded.iter().filter_map(|r| r.quality_score).collect()),
+            tokens_per_node: (nodes > 0)
+                .then(|| succeeded.iter().map(|r| r.total_tokens).sum::<usize>() as f32 / nodes as f32),
+            avg_p90_tokens: mean(succeeded.iter().map(|r| r.p90_tokens as f32).collect()),
+        }
+    }
+
+    pub fn failure_rate(&self) -> f32 {
+        if self.documents == 0 {
+            0.0
+        } else {
+            self.failed as f32 / self.documents as f32
+        }
+    }
+}
+
+/// One row of a grouped report
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Group {
+    pub key: String,
+    #[serde(flatten)]
+    pub totals: Aggregate,
+    /// Relative change in tokens per node against the first period (trend
+    /// rows only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_drift: Option<f32>,
+}
+
+/// Quality and failure trends over a stats database
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrendReport {
+    pub overall: Aggregate,
+    /// Oldest period first
+    pub periods: Vec<Group>,
+    /// Classified type; documents that failed before classification are
+    /// grouped as "unclassified"
+    pub by_document_type: Vec<Group>,
+    pub by_format: Vec<Group>,
+}
+
+impl TrendReport {
+    /// Summarize rows newer than `since` (all rows when None)
+    pub fn build(rows: &[ProfileRow], period: Period, since: Option<DateTime<Utc>>) -> Self {
+        let rows: Vec<&ProfileRow> = rows.iter().filter(|r| since.is_none_or(|since| r.timestamp >= since)).collect();
+        let group = |key: &dyn Fn(&ProfileRow) -> String| -> Vec<Group> {
+            let mut groups: BTreeMap<String, Vec<&ProfileRow>> = BTreeMap::new();
+            for row in &rows {
+                groups.entry(key(row)).or_default().push(row);
+            }
+            groups
+                .into_iter()
+                .map(|(key, rows)| Group {
+                    key,
+                    totals: Aggregate::of(&rows),
+                    token_drift: None,
+                })
+                .collect()
+        };
+
+        let mut periods = group(&|row| period.key(&row.timestamp));
+        let baseline = periods.iter().find_map(|p| p.totals.tokens_per_node).filter(|&b| b > 0.0);
+        for period in &mut periods {
+            period.token_drift = baseline.zip(period.totals.tokens_per_node).map(|(base, now)| now / base - 1.0);
+        }
+
+        Self {
+            overall: Aggregate::of(&rows),
+            periods,
+            by_document_type: group(&|row| row.document_type.clone().unwrap_or_else(|| "unclassified".to_string())),
+            by_format: group(&|row| if row.format.is_empty() { "unknown".to_string() } else { row.format.clone() }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn trends_track_quality_failures_and_drift() {
+        let mut graph = DocumentGraph::new();
+        for tokens in [100, 100, 3_000, 100] {
+            let mut node = DocumentNode::new("Paragraph", "text".to_string());
+            node.token_count = tokens;
+            graph.nodes.insert(node.id, node);
+        }
+        let row = ProfileRow::succeeded("cli", "contracts/a.PDF", &graph);
+        assert_eq!((row.format.as_str(), row.nodes, row.sections), ("pdf", 4, 0));
+        // No sections (-0.25) and 1 of 4 nodes oversized (-0.0625)
+        assert_eq!(row.quality_score, Some(0.6875));
+
+        let at = |day: u32, mut row: ProfileRow| {
+            row.timestamp = Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap();
+            row
+        };
+        let mut larger = row.clone();
+        larger.total_tokens *= 2;
+        let rows = vec![
+            at(2, row.clone()),
+            at(2, ProfileRow::failed("cli", "b.docx", "boom")),
+            at(3, larger),
+            at(9, row),
+        ];
+
+        let report = TrendReport::build(&rows, Period::Day, None);
+        assert_eq!((report.overall.documents, report.overall.failed), (4, 1));
+        assert_eq!(report.periods.len(), 3);
+        assert_eq!(report.periods[0].totals.failure_rate(), 0.5);
+        assert_eq!(report.periods[1].token_drift, Some(1.0));
+        let types: Vec<&str> = report.by_document_type.iter().map(|g| g.key.as_str()).collect();
+        assert_eq!(types, ["Unknown", "unclassified"]);
+
+        let weekly = TrendReport::build(&rows, Period::Week, Some(Utc.with_ymd_and_hms(2026, 3, 3, 0, 0, 0).unwrap()));
+        let weeks: Vec<(&str, usize)> = weekly.periods.iter().map(|g| (g.key.as_str(), g.totals.documents)).collect();
+        assert_eq!(weeks, [("2026-W10", 1), ("2026-W11", 1)]);
+    }
+}
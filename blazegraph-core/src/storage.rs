@@ -1,9 +1,14 @@
 use crate::types::{TikaOutput, PreprocessorOutput};
-use crate::cache::{GraphCacheKey, GraphCacheValue};
+use crate::cache::{GraphCacheKey, GraphCacheValue, ParsedElementsCacheKey, ParsedElementsCacheValue};
+use crate::config::ParsingConfig;
+use crate::encryption::Cipher;
+use crate::retention::{CacheArea, PurgeReport, RetentionPolicy};
 use anyhow::{anyhow, Result};
 use sha2::{Digest, Sha256};
 use std::fs;
+use std::io::Read;
 use std::path::Path;
+use std::time::{Duration, SystemTime};
 
 /// Storage abstraction for caching PDF processing results
 pub trait DocumentStorage {
@@ -11,7 +16,7 @@ pub trait DocumentStorage {
     fn _get_pdf(&self, hash: &str) -> Result<Option<Vec<u8>>>;
     fn _store_pdf(&self, hash: &str, data: &[u8]) -> Result<()>;
 
-    // Level 1: Tika processing cache (PDF → XHTML) - Legacy
+    // Level 1: Extraction cache (document → TextElements, with the XHTML hash)
     fn get_tika_output(&self, pdf_hash: &str) -> Result<Option<TikaOutput>>;
     fn store_tika_output(&self, pdf_hash: &str, output: &TikaOutput) -> Result<()>;
 
@@ -19,7 +24,11 @@ pub trait DocumentStorage {
     fn get_preprocessor_output(&self, pdf_hash: &str) -> Result<Option<PreprocessorOutput>>;
     fn store_preprocessor_output(&self, pdf_hash: &str, output: &PreprocessorOutput) -> Result<()>;
 
-    // Level 2: Graph processing cache (XHTML + Config → Graph) - NEW
+    // Level 2: Rules cache (XHTML + rule config → ParsedElements)
+    fn get_parsed_elements(&self, cache_key: &ParsedElementsCacheKey) -> Result<Option<ParsedElementsCacheValue>>;
+    fn store_parsed_elements(&self, cache_key: &ParsedElementsCacheKey, cache_value: &ParsedElementsCacheValue) -> Result<()>;
+
+    // Level 3: Graph processing cache (PDF + Config → Graph)
     fn get_graph_output(&self, cache_key: &GraphCacheKey) -> Result<Option<GraphCacheValue>>;
     fn store_graph_output(&self, cache_key: &GraphCacheKey, cache_value: &GraphCacheValue) -> Result<()>;
 }
@@ -27,6 +36,8 @@ pub trait DocumentStorage {
 /// File-based storage implementation using local cache directory
 pub struct FileStorage {
     cache_dir: String,
+    cipher: Option<Cipher>,
+    retention: RetentionPolicy,
 }
 
 impl FileStorage {
@@ -36,13 +47,104 @@ impl FileStorage {
         fs::create_dir_all(format!("{cache_dir}/pdfs"))?;
         fs::create_dir_all(format!("{cache_dir}/tika"))?;
         fs::create_dir_all(format!("{cache_dir}/preprocessor"))?; // NEW: Generalized preprocessor cache
-        fs::create_dir_all(format!("{cache_dir}/graph"))?; // NEW: Level 2 cache directory
+        fs::create_dir_all(format!("{cache_dir}/parsed"))?; // Level 2: post-rules elements
+        fs::create_dir_all(format!("{cache_dir}/graph"))?; // Level 3: graphs
 
         Ok(Self {
             cache_dir: cache_dir.to_string(),
+            cipher: None,
+            retention: RetentionPolicy::default(),
         })
     }
 
+    /// Encrypt every entry written from now on
+    ///
+    /// Plaintext entries left from before are deleted when read (a cache
+    /// miss), and entries that cannot be decrypted with this key are misses
+    /// that get overwritten.
+    pub fn with_encryption(mut self, cipher: Cipher) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    /// Expire entries by age per `policy` (its ages must already be valid)
+    pub fn with_retention(mut self, policy: RetentionPolicy) -> Self {
+        self.retention = policy;
+        self
+    }
+
+    /// Delete every entry older than the retention policy allows
+    ///
+    /// With `dry_run`, only reports what would be deleted.
+    pub fn purge(&self, dry_run: bool) -> Result<PurgeReport> {
+        let mut report = PurgeReport::default();
+        for area in CacheArea::ALL {
+            let Some(max_age) = self.retention.max_age(area)? else {
+                continue;
+            };
+            let mut removed = 0;
+            for dir in area.dirs() {
+                let Ok(entries) = fs::read_dir(format!("{}/{}", self.cache_dir, dir)) else {
+                    continue;
+                };
+                for entry in entries {
+                    let entry = entry?;
+                    let metadata = entry.metadata()?;
+                    if metadata.is_file() && is_expired(&metadata, max_age) {
+                        if !dry_run {
+                            fs::remove_file(entry.path())?;
+                        }
+                        removed += 1;
+                        report.bytes += metadata.len();
+                    }
+                }
+            }
+            report.removed.push((area, removed));
+        }
+        Ok(report)
+    }
+
+    /// Read an entry's bytes, decrypting if needed; None is a cache miss
+    fn read_entry(&self, area: CacheArea, path: &str) -> Result<Option<Vec<u8>>> {
+        if !Path::new(path).exists() {
+            return Ok(None);
+        }
+        if let Some(max_age) = self.retention.max_age(area)? {
+            if is_expired(&fs::metadata(path)?, max_age) {
+                fs::remove_file(path)?;
+                return Ok(None);
+            }
+        }
+        let data = fs::read(path)?;
+        match (&self.cipher, Cipher::is_sealed(&data)) {
+            (Some(cipher), true) => match cipher.open(&data) {
+                Ok(plaintext) => Ok(Some(plaintext)),
+                Err(e) => {
+                    println!("⚠️  Ignoring cache entry {}: {}", path, e);
+                    Ok(None)
+                }
+            },
+            (Some(_), false) => {
+                // Written before encryption was enabled; don't leave it behind
+                fs::remove_file(path)?;
+                Ok(None)
+            }
+            (None, true) => {
+                println!("⚠️  Ignoring encrypted cache entry {} (no key configured)", path);
+                Ok(None)
+            }
+            (None, false) => Ok(Some(data)),
+        }
+    }
+
+    fn write_entry(&self, path: &str, data: &[u8]) -> Result<()> {
+        match &self.cipher {
+            Some(cipher) => fs::write(path, cipher.seal(data)?)?,
+            None => fs::write(path, data)?,
+        }
+        Ok(())
+    }
+
     fn pdf_path(&self, hash: &str) -> String {
         format!("{}/pdfs/{}.pdf", self.cache_dir, hash)
     }
@@ -55,6 +157,10 @@ impl FileStorage {
         format!("{}/preprocessor/{}.json", self.cache_dir, hash)
     }
 
+    fn parsed_path(&self, cache_key: &ParsedElementsCacheKey) -> String {
+        format!("{}/parsed/{}.json", self.cache_dir, cache_key.to_cache_hash())
+    }
+
     fn graph_path(&self, cache_key: &GraphCacheKey) -> String {
         format!("{}/graph/{}.json", self.cache_dir, cache_key.to_cache_hash())
     }
@@ -62,29 +168,22 @@ impl FileStorage {
 
 impl DocumentStorage for FileStorage {
     fn _get_pdf(&self, hash: &str) -> Result<Option<Vec<u8>>> {
-        let path = self.pdf_path(hash);
-        if Path::new(&path).exists() {
-            Ok(Some(fs::read(path)?))
-        } else {
-            Ok(None)
-        }
+        self.read_entry(CacheArea::Documents, &self.pdf_path(hash))
     }
 
     fn _store_pdf(&self, hash: &str, data: &[u8]) -> Result<()> {
-        let path = self.pdf_path(hash);
-        fs::write(path, data)?;
-        Ok(())
+        self.write_entry(&self.pdf_path(hash), data)
     }
 
     fn get_tika_output(&self, pdf_hash: &str) -> Result<Option<TikaOutput>> {
         let path = self.tika_path(pdf_hash);
-        if Path::new(&path).exists() {
-            let json_str = fs::read_to_string(path)?;
-            let output: TikaOutput = serde_json::from_str(&json_str)
-                .map_err(|e| anyhow!("Failed to deserialize cached TikaOutput: {}", e))?;
-            Ok(Some(output))
-        } else {
-            Ok(None)
+        match self.read_entry(CacheArea::Extraction, &path)? {
+            Some(json) => {
+                let output: TikaOutput = serde_json::from_slice(&json)
+                    .map_err(|e| anyhow!("Failed to deserialize cached TikaOutput: {}", e))?;
+                Ok(Some(output))
+            }
+            None => Ok(None),
         }
     }
 
@@ -92,19 +191,18 @@ impl DocumentStorage for FileStorage {
         let path = self.tika_path(pdf_hash);
         let json_str = serde_json::to_string_pretty(output)
             .map_err(|e| anyhow!("Failed to serialize TikaOutput: {}", e))?;
-        fs::write(path, json_str)?;
-        Ok(())
+        self.write_entry(&path, json_str.as_bytes())
     }
 
     fn get_preprocessor_output(&self, pdf_hash: &str) -> Result<Option<PreprocessorOutput>> {
         let path = self.preprocessor_path(pdf_hash);
-        if Path::new(&path).exists() {
-            let json_str = fs::read_to_string(path)?;
-            let output: PreprocessorOutput = serde_json::from_str(&json_str)
-                .map_err(|e| anyhow!("Failed to deserialize cached PreprocessorOutput: {}", e))?;
-            Ok(Some(output))
-        } else {
-            Ok(None)
+        match self.read_entry(CacheArea::Extraction, &path)? {
+            Some(json) => {
+                let output: PreprocessorOutput = serde_json::from_slice(&json)
+                    .map_err(|e| anyhow!("Failed to deserialize cached PreprocessorOutput: {}", e))?;
+                Ok(Some(output))
+            }
+            None => Ok(None),
         }
     }
 
@@ -112,20 +210,39 @@ impl DocumentStorage for FileStorage {
         let path = self.preprocessor_path(pdf_hash);
         let json_str = serde_json::to_string_pretty(output)
             .map_err(|e| anyhow!("Failed to serialize PreprocessorOutput: {}", e))?;
-        fs::write(path, json_str)?;
-        Ok(())
+        self.write_entry(&path, json_str.as_bytes())
+    }
+
+    // Level 2: Rules cache implementation
+    fn get_parsed_elements(&self, cache_key: &ParsedElementsCacheKey) -> Result<Option<ParsedElementsCacheValue>> {
+        let path = self.parsed_path(cache_key);
+        match self.read_entry(CacheArea::Parsed, &path)? {
+            Some(json) => {
+                let cache_value: ParsedElementsCacheValue = serde_json::from_slice(&json)
+                    .map_err(|e| anyhow!("Failed to deserialize cached ParsedElementsCacheValue: {}", e))?;
+                Ok(Some(cache_value))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn store_parsed_elements(&self, cache_key: &ParsedElementsCacheKey, cache_value: &ParsedElementsCacheValue) -> Result<()> {
+        let path = self.parsed_path(cache_key);
+        let json_str = serde_json::to_string(cache_value)
+            .map_err(|e| anyhow!("Failed to serialize ParsedElementsCacheValue: {}", e))?;
+        self.write_entry(&path, json_str.as_bytes())
     }
 
-    // Level 2: Graph processing cache implementation
+    // Level 3: Graph processing cache implementation
     fn get_graph_output(&self, cache_key: &GraphCacheKey) -> Result<Option<GraphCacheValue>> {
         let path = self.graph_path(cache_key);
-        if Path::new(&path).exists() {
-            let json_str = fs::read_to_string(path)?;
-            let cache_value: GraphCacheValue = serde_json::from_str(&json_str)
-                .map_err(|e| anyhow!("Failed to deserialize cached GraphCacheValue: {}", e))?;
-            Ok(Some(cache_value))
-        } else {
-            Ok(None)
+        match self.read_entry(CacheArea::Graph, &path)? {
+            Some(json) => {
+                let cache_value: GraphCacheValue = serde_json::from_slice(&json)
+                    .map_err(|e| anyhow!("Failed to deserialize cached GraphCacheValue: {}", e))?;
+                Ok(Some(cache_value))
+            }
+            None => Ok(None),
         }
     }
 
@@ -133,11 +250,19 @@ impl DocumentStorage for FileStorage {
         let path = self.graph_path(cache_key);
         let json_str = serde_json::to_string_pretty(cache_value)
             .map_err(|e| anyhow!("Failed to serialize GraphCacheValue: {}", e))?;
-        fs::write(path, json_str)?;
-        Ok(())
+        self.write_entry(&path, json_str.as_bytes())
     }
 }
 
+/// Whether a file was last written more than `max_age` ago
+fn is_expired(metadata: &fs::Metadata, max_age: Duration) -> bool {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > max_age)
+}
+
 /// Calculate a fast hash for PDF content using start + end chunks
 pub fn calculate_pdf_hash(pdf_bytes: &[u8]) -> String {
     let chunk_size = 1024; // 1KB from start and end
@@ -169,6 +294,21 @@ pub fn calculate_config_hash<T: serde::Serialize>(config: &T) -> Result<String>
     Ok(format!("{:x}", hasher.finalize()))
 }
 
+/// Calculate hash for the rule-stage part of a config (for Level 2 cache key)
+///
+/// Fields listed in `ParsingConfig::GRAPH_STAGE_FIELDS` only affect graph
+/// building and output, so changing them keeps cached ParsedElements valid.
+pub fn calculate_rules_hash(config: &ParsingConfig) -> Result<String> {
+    let mut value = serde_json::to_value(config)
+        .map_err(|e| anyhow!("Failed to serialize config for hashing: {}", e))?;
+    if let Some(fields) = value.as_object_mut() {
+        for field in ParsingConfig::GRAPH_STAGE_FIELDS {
+            fields.remove(*field);
+        }
+    }
+    calculate_config_hash(&value)
+}
+
 /// Calculate hash for XHTML content (for Level 2 cache key)
 pub fn calculate_xhtml_hash(xhtml: &str) -> String {
     let mut hasher = Sha256::new();
@@ -176,6 +316,35 @@ pub fn calculate_xhtml_hash(xhtml: &str) -> String {
     format!("{:x}", hasher.finalize())
 }
 
+/// Reader computing `calculate_xhtml_hash` of the markup read through it,
+/// for markup that is parsed as it streams and never held whole
+pub struct XhtmlHashReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> XhtmlHashReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    /// The hash of everything read so far
+    pub fn finish(self) -> String {
+        format!("{:x}", self.hasher.finalize())
+    }
+}
+
+impl<R: Read> Read for XhtmlHashReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
 /// No-op storage implementation that disables all caching
 pub struct NoOpStorage;
 
@@ -216,6 +385,14 @@ impl DocumentStorage for NoOpStorage {
         Ok(()) // No-op
     }
 
+    fn get_parsed_elements(&self, _cache_key: &ParsedElementsCacheKey) -> Result<Option<ParsedElementsCacheValue>> {
+        Ok(None) // Always cache miss
+    }
+
+    fn store_parsed_elements(&self, _cache_key: &ParsedElementsCacheKey, _cache_value: &ParsedElementsCacheValue) -> Result<()> {
+        Ok(()) // No-op
+    }
+
     fn get_graph_output(&self, _cache_key: &GraphCacheKey) -> Result<Option<GraphCacheValue>> {
         Ok(None) // Always cache miss
     }
@@ -262,4 +439,77 @@ mod tests {
         // Clean up
         std::fs::remove_dir_all(temp_dir).ok();
     }
+
+    #[test]
+    fn encrypted_storage_keeps_no_plaintext_on_disk() {
+        let temp_dir = std::env::temp_dir().join(format!("blazegraph_test_encrypted_cache_{}", std::process::id()));
+        let cache_dir = temp_dir.to_str().unwrap();
+        let plain = FileStorage::new(cache_dir).unwrap();
+        plain._store_pdf("old", b"written before encryption").unwrap();
+
+        let storage = FileStorage::new(cache_dir).unwrap().with_encryption(Cipher::new(&[1u8; 32]));
+        storage._store_pdf("doc", b"confidential").unwrap();
+        let on_disk = std::fs::read(storage.pdf_path("doc")).unwrap();
+        assert!(Cipher::is_sealed(&on_disk));
+        assert_eq!(storage._get_pdf("doc").unwrap(), Some(b"confidential".to_vec()));
+
+        // Plaintext from before is dropped; other keys and no key are misses
+        assert_eq!(storage._get_pdf("old").unwrap(), None);
+        assert!(!Path::new(&storage.pdf_path("old")).exists());
+        let other_key = FileStorage::new(cache_dir).unwrap().with_encryption(Cipher::new(&[2u8; 32]));
+        assert_eq!(other_key._get_pdf("doc").unwrap(), None);
+        assert_eq!(plain._get_pdf("doc").unwrap(), None);
+
+        std::fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn retention_expires_entries_on_read_and_purge() {
+        let temp_dir = std::env::temp_dir().join(format!("blazegraph_test_retention_{}", std::process::id()));
+        let cache_dir = temp_dir.to_str().unwrap();
+        let policy = RetentionPolicy {
+            max_age: crate::retention::RetentionAges {
+                documents: Some("0s".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let storage = FileStorage::new(cache_dir).unwrap().with_retention(policy);
+        storage._store_pdf("a", b"x").unwrap();
+        storage._store_pdf("b", b"y").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+
+        assert_eq!(storage._get_pdf("a").unwrap(), None);
+        assert!(!Path::new(&storage.pdf_path("a")).exists());
+
+        let report = storage.purge(true).unwrap();
+        assert_eq!(report.total(), 1);
+        assert!(Path::new(&storage.pdf_path("b")).exists());
+        storage.purge(false).unwrap();
+        assert!(!Path::new(&storage.pdf_path("b")).exists());
+
+        std::fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn rules_hash_ignores_graph_stage_fields() {
+        let config = ParsingConfig::default();
+        let graph_only = ParsingConfig {
+            include_raw_tika: true,
+            ..ParsingConfig::default()
+        };
+        let rules_changed = ParsingConfig {
+            section_patterns: vec!["chapter".to_string()],
+            ..ParsingConfig::default()
+        };
+
+        let hash = calculate_rules_hash(&config).unwrap();
+        assert_eq!(hash, calculate_rules_hash(&graph_only).unwrap());
+        assert_ne!(hash, calculate_rules_hash(&rules_changed).unwrap());
+        // The full config hash (Level 3) still sees the difference
+        assert_ne!(
+            calculate_config_hash(&config).unwrap(),
+            calculate_config_hash(&graph_only).unwrap()
+        );
+    }
 }
@@ -0,0 +1,518 @@
+//! Conformance suite for Blazegraph XHTML producers and consumers.
+//!
+//! Every `PdfBackend` must emit the same XHTML intermediate format, and every
+//! XHTML-consuming `Preprocessor` must turn it into the same
+//! `PreprocessorOutput`. This module pins both sides down so backends stay
+//! interchangeable:
+//!
+//! - [`run_markup_conformance`] feeds fixture XHTML through a markup parser and
+//!   checks bbox parsing, font resolution (including CSS variations and
+//!   multiple/page-scoped style blocks), paragraph data-* hints, metadata
+//!   mapping, bookmark extraction (flat and nested), empty-page behavior and
+//!   superscript/subscript detection.
+//! - [`check_backend_xhtml`] validates raw backend output against the format
+//!   contract (page divs, span attributes, image regions, style block, page
+//!   count).
+//!
+//! ```ignore
+//! use blazegraph_io_core::test_utils::conformance;
+//!
+//! #[test]
+//! fn my_preprocessor_conforms() {
+//!     conformance::assert_preprocessor_conformance(&MyPreprocessor::new());
+//! }
+//! ```
+
+use crate::preprocessors::{PdfBackend, Preprocessor};
+use crate::types::*;
+use anyhow::Result;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Two pages, two font classes, full metadata, one line split into two segments,
+/// and a heading paragraph carrying data-* hints
+pub const BASIC_XHTML: &str = include_str!("fixtures/basic.xhtml");
+/// Bookmark list whose titles match spans on the page
+pub const BOOKMARKS_XHTML: &str = include_str!("fixtures/bookmarks.xhtml");
+/// Three-level outline with page targets from data-page and #page= anchors
+pub const NESTED_BOOKMARKS_XHTML: &str = include_str!("fixtures/nested_bookmarks.xhtml");
+/// Three pages where only the middle one has text (plus a whitespace-only span)
+pub const EMPTY_PAGES_XHTML: &str = include_str!("fixtures/empty_pages.xhtml");
+/// Inferred subscript and superscript spans next to small baseline text, and
+/// an explicit data-script
+pub const SCRIPTS_XHTML: &str = include_str!("fixtures/scripts.xhtml");
+/// Font classes written with shorthand, rgb() colors, reordered and missing properties
+pub const CSS_VARIATIONS_XHTML: &str = include_str!("fixtures/css_variations.xhtml");
+/// Global style blocks in head and body plus a page-scoped block on page 2
+pub const MULTIPLE_STYLES_XHTML: &str = include_str!("fixtures/multiple_styles.xhtml");
+/// A captioned figure, an inline image inside a paragraph, and an image
+/// without a data-bbox
+pub const FIGURES_XHTML: &str = include_str!("fixtures/figures.xhtml");
+
+/// A single fixture and the expectations its parsed output must meet
+pub struct ConformanceCase {
+    pub name: &'static str,
+    pub markup: &'static str,
+    pub check: fn(&PreprocessorOutput) -> Result<(), String>,
+}
+
+/// A case that failed, either while parsing or in its checks
+#[derive(Debug, Clone)]
+pub struct ConformanceFailure {
+    pub case: &'static str,
+    pub message: String,
+}
+
+/// All conformance cases, in a stable order
+pub fn cases() -> Vec<ConformanceCase> {
+    vec![
+        ConformanceCase {
+            name: "bbox_parsing",
+            markup: BASIC_XHTML,
+            check: check_bbox_parsing,
+        },
+        ConformanceCase {
+            name: "font_resolution",
+            markup: BASIC_XHTML,
+            check: check_font_resolution,
+        },
+        ConformanceCase {
+            name: "css_variations",
+            markup: CSS_VARIATIONS_XHTML,
+            check: check_css_variations,
+        },
+        ConformanceCase {
+            name: "multiple_style_blocks",
+            markup: MULTIPLE_STYLES_XHTML,
+            check: check_multiple_style_blocks,
+        },
+        ConformanceCase {
+            name: "paragraph_attributes",
+            markup: BASIC_XHTML,
+            check: check_paragraph_attributes,
+        },
+        ConformanceCase {
+            name: "metadata_mapping",
+            markup: BASIC_XHTML,
+            check: check_metadata_mapping,
+        },
+        ConformanceCase {
+            name: "bookmark_extraction",
+            markup: BOOKMARKS_XHTML,
+            check: check_bookmark_extraction,
+        },
+        ConformanceCase {
+            name: "nested_bookmarks",
+            markup: NESTED_BOOKMARKS_XHTML,
+            check: check_nested_bookmarks,
+        },
+        ConformanceCase {
+            name: "empty_pages",
+            markup: EMPTY_PAGES_XHTML,
+            check: check_empty_pages,
+        },
+        ConformanceCase {
+            name: "script_positions",
+            markup: SCRIPTS_XHTML,
+            check: check_script_positions,
+        },
+        ConformanceCase {
+            name: "figures",
+            markup: FIGURES_XHTML,
+            check: check_figures,
+        },
+    ]
+}
+
+/// Run every case through a markup parser, returning all failures
+pub fn run_markup_conformance<F>(parse: F) -> Vec<ConformanceFailure>
+where
+    F: Fn(&str) -> Result<PreprocessorOutput>,
+{
+    cases()
+        .into_iter()
+        .filter_map(|case| {
+            let result = parse(case.markup)
+                .map_err(|e| format!("parse failed: {}", e))
+                .and_then(|output| (case.check)(&output));
+            result.err().map(|message| ConformanceFailure {
+                case: case.name,
+                message,
+            })
+        })
+        .collect()
+}
+
+/// Run the suite against a preprocessor's markup stage
+pub fn run_preprocessor_conformance(preprocessor: &dyn Preprocessor) -> Vec<ConformanceFailure> {
+    run_markup_conformance(|markup| preprocessor.parse_markup_to_preprocessor_output(markup))
+}
+
+/// Panic listing every conformance failure for the preprocessor
+pub fn assert_preprocessor_conformance(preprocessor: &dyn Preprocessor) {
+    assert_no_failures(preprocessor.name(), &run_preprocessor_conformance(preprocessor));
+}
+
+/// Panic listing every conformance failure for a bare markup parser
+pub fn assert_markup_conformance<F>(name: &str, parse: F)
+where
+    F: Fn(&str) -> Result<PreprocessorOutput>,
+{
+    assert_no_failures(name, &run_markup_conformance(parse));
+}
+
+fn assert_no_failures(name: &str, failures: &[ConformanceFailure]) {
+    assert!(
+        failures.is_empty(),
+        "{} failed {} conformance case(s):\n  {}",
+        name,
+        failures.len(),
+        failures
+            .iter()
+            .map(|f| format!("{}: {}", f.case, f.message))
+            .collect::<Vec<_>>()
+            .join("\n  ")
+    );
+}
+
+// ============================================================================
+// Backend output contract
+// ============================================================================
+
+static PAGE_OPEN_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<div class="page"[^>]*>"#).unwrap());
+
+static SPAN_TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<span\b([^>]*)>").unwrap());
+
+static FIGURE_TAG_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(?:img|figure)\b([^>]*)>").unwrap());
+
+static ATTR_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"([\w-]+)="([^"]*)""#).unwrap());
+
+static NPAGES_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<meta\s+name="xmpTPg:NPages"[^>]*content="(\d+)""#).unwrap());
+
+/// Check raw backend XHTML against the Blazegraph XHTML format contract,
+/// returning every violation found
+pub fn check_backend_xhtml(xhtml: &str) -> Vec<String> {
+    let mut violations = Vec::new();
+
+    let page_count = PAGE_OPEN_REGEX.find_iter(xhtml).count();
+    if page_count == 0 {
+        violations.push("No <div class=\"page\"> elements".to_string());
+    }
+
+    if let Some(declared) = NPAGES_REGEX
+        .captures(xhtml)
+        .and_then(|c| c[1].parse::<usize>().ok())
+    {
+        if declared != page_count {
+            violations.push(format!(
+                "xmpTPg:NPages declares {} pages but {} page divs were emitted",
+                declared, page_count
+            ));
+        }
+    }
+
+    if !xhtml.contains("<style") {
+        violations.push("No <style> block with font classes".to_string());
+    }
+
+    for (index, span) in SPAN_TAG_REGEX.captures_iter(xhtml).enumerate() {
+        let attrs: Vec<(&str, &str)> = ATTR_REGEX
+            .captures_iter(&span[1])
+            .map(|c| (c.get(1).unwrap().as_str(), c.get(2).unwrap().as_str()))
+            .collect();
+        let attr = |name: &str| attrs.iter().find(|(k, _)| *k == name).map(|(_, v)| *v);
+
+        for required in ["class", "data-bbox", "data-line", "data-segment"] {
+            if attr(required).is_none() {
+                violations.push(format!("Span {} is missing {}", index, required));
+            }
+        }
+
+        if let Some(bbox) = attr("data-bbox") {
+            if !is_valid_bbox(bbox) {
+                violations.push(format!("Span {} has malformed data-bbox \"{}\"", index, bbox));
+            }
+        }
+
+        if let Some(script) = attr("data-script") {
+            if ScriptPosition::from_attribute(script).is_none() {
+                violations.push(format!("Span {} has unknown data-script \"{}\"", index, script));
+            }
+        }
+
+        for numeric in ["data-line", "data-segment"] {
+            if let Some(value) = attr(numeric) {
+                if value.parse::<u32>().is_err() {
+                    violations.push(format!("Span {} has non-numeric {} \"{}\"", index, numeric, value));
+                }
+            }
+        }
+    }
+
+    // Image regions are optional, but a declared region must be well formed
+    for (index, figure) in FIGURE_TAG_REGEX.captures_iter(xhtml).enumerate() {
+        let bbox = ATTR_REGEX
+            .captures_iter(&figure[1])
+            .find(|c| &c[1] == "data-bbox")
+            .map(|c| c.get(2).unwrap().as_str());
+        if let Some(bbox) = bbox.filter(|bbox| !is_valid_bbox(bbox)) {
+            violations.push(format!("Image {} has malformed data-bbox \"{}\"", index, bbox));
+        }
+    }
+
+    violations
+}
+
+/// "x,y,width,height" with finite numbers and no negative size
+fn is_valid_bbox(bbox: &str) -> bool {
+    let parts: Vec<Option<f32>> = bbox.split(',').map(|p| p.trim().parse().ok()).collect();
+    parts.len() == 4
+        && parts.iter().all(|p| p.is_some_and(|v: f32| v.is_finite()))
+        && parts[2].unwrap_or(-1.0) >= 0.0
+        && parts[3].unwrap_or(-1.0) >= 0.0
+}
+
+/// Extract a PDF with the backend and check its output against the format
+/// contract and the shared XHTML parser
+pub fn assert_backend_conformance(backend: &dyn PdfBackend, pdf_bytes: &[u8]) {
+    let xhtml = backend
+        .extract_to_xhtml(pdf_bytes)
+        .unwrap_or_else(|e| panic!("{} failed to extract PDF: {}", backend.name(), e));
+
+    let violations = check_backend_xhtml(&xhtml);
+    assert!(
+        violations.is_empty(),
+        "{} emitted non-conforming XHTML:\n  {}",
+        backend.name(),
+        violations.join("\n  ")
+    );
+
+    let output = crate::preprocessors::pdf::xhtml_parser::parse_xhtml(&xhtml)
+        .unwrap_or_else(|e| panic!("{} output did not parse: {}", backend.name(), e));
+    assert!(
+        !output.text_elements.is_empty(),
+        "{} produced no text elements",
+        backend.name()
+    );
+}
+
+// ============================================================================
+// Case checks
+// ============================================================================
+
+fn find<'a>(output: &'a PreprocessorOutput, text: &str) -> Result<&'a PdfTextElement, String> {
+    output
+        .text_elements
+        .iter()
+        .find(|e| e.text == text)
+        .ok_or_else(|| format!("no text element \"{}\"", text))
+}
+
+fn expect<T: PartialEq + std::fmt::Debug>(what: &str, actual: T, expected: T) -> Result<(), String> {
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(format!("{}: expected {:?}, got {:?}", what, expected, actual))
+    }
+}
+
+fn check_bbox_parsing(output: &PreprocessorOutput) -> Result<(), String> {
+    expect("text element count", output.text_elements.len(), 4)?;
+
+    let heading = find(output, "Conformance Heading")?;
+    let b = &heading.bounding_box;
+    expect("heading bbox", (b.x, b.y, b.width, b.height), (72.0, 60.5, 200.25, 18.0))?;
+
+    let right = find(output, "Body text right.")?;
+    expect("segment number", (right.line_number, right.segment_number), (0, 1))?;
+    expect("paragraph shared by segments", right.paragraph_number, find(output, "Body text left.")?.paragraph_number)?;
+
+    let second = find(output, "Second page text.")?;
+    expect("page number", second.page_number, 2)?;
+
+    // Reading order runs top-to-bottom, left-to-right, and continues across pages
+    let order: Vec<&str> = {
+        let mut elements: Vec<&PdfTextElement> = output.text_elements.iter().collect();
+        elements.sort_by_key(|e| e.reading_order);
+        elements.iter().map(|e| e.text.as_str()).collect()
+    };
+    expect(
+        "reading order",
+        order,
+        vec!["Conformance Heading", "Body text left.", "Body text right.", "Second page text."],
+    )
+}
+
+fn check_font_resolution(output: &PreprocessorOutput) -> Result<(), String> {
+    expect("font class count", output.style_data.font_classes.len(), 2)?;
+
+    let style = &find(output, "Conformance Heading")?.style_info;
+    expect("class name", style.class_name.as_str(), "f2")?;
+    expect("font family", style.font_family.as_str(), "Times-Bold")?;
+    expect("font size", style.font_size, 18.0)?;
+    expect("font weight", style.font_weight.as_str(), "bold")?;
+    expect("color", style.color.as_str(), "#1a1a1a")
+}
+
+fn check_css_variations(output: &PreprocessorOutput) -> Result<(), String> {
+    let heading = &find(output, "Shorthand Heading")?.style_info;
+    expect("shorthand family", heading.font_family.as_str(), "Source Serif Pro")?;
+    expect("shorthand size", heading.font_size, 20.0)?;
+    expect("shorthand style", heading.font_style.as_str(), "italic")?;
+    expect("numeric weight", heading.font_weight.as_str(), "bold")?;
+    expect("rgb color", heading.color.as_str(), "#333333")?;
+
+    let body = &find(output, "Reordered body text.")?.style_info;
+    expect("reordered family", body.font_family.as_str(), "Helvetica")?;
+    expect("reordered size", body.font_size, 10.0)?;
+    expect("missing style defaults", body.font_style.as_str(), "normal")
+}
+
+fn check_multiple_style_blocks(output: &PreprocessorOutput) -> Result<(), String> {
+    let size = |text: &str| find(output, text).map(|e| e.style_info.font_size);
+
+    // Head and trailing body blocks are merged into the global classes
+    expect("global f1 on page 1", size("Page one body.")?, 12.0)?;
+    expect("trailing-block f2 on page 1", size("Page one footnote.")?, 10.0)?;
+    expect("trailing-block f2 on page 2", size("Page two footnote.")?, 10.0)?;
+
+    // The page 2 block overrides f1 and defines f3 for that page only
+    expect("page-scoped f1 on page 2", size("Page two redefined f1.")?, 16.0)?;
+    expect("page-scoped f3 on page 2", size("Page two scoped f3.")?, 9.0)?;
+    expect(
+        "f3 outside its page falls back",
+        find(output, "Page one unscoped f3.")?.style_info.font_family.as_str(),
+        "unknown",
+    )
+}
+
+fn check_paragraph_attributes(output: &PreprocessorOutput) -> Result<(), String> {
+    let heading = find(output, "Conformance Heading")?;
+    expect("heading role", heading.paragraph_role(), Some("heading"))?;
+    expect(
+        "heading level hint",
+        heading.paragraph_attributes.get("level").map(String::as_str),
+        Some("1"),
+    )?;
+    expect(
+        "plain paragraph has no hints",
+        find(output, "Body text left.")?.paragraph_attributes.is_empty(),
+        true,
+    )
+}
+
+fn check_script_positions(output: &PreprocessorOutput) -> Result<(), String> {
+    let script = |text: &str| find(output, text).map(|e| e.script);
+    expect("lowered small span", script("2")?, ScriptPosition::Subscript)?;
+    expect("raised footnote marker", script("3")?, ScriptPosition::Superscript)?;
+    expect("small text on the baseline", script("(fine print)")?, ScriptPosition::Baseline)?;
+    expect("full-size text", script("Water is H")?, ScriptPosition::Baseline)?;
+    let explicit = output
+        .text_elements
+        .iter()
+        .find(|e| e.text == "2" && e.style_info.font_size == 12.0)
+        .ok_or("missing explicit superscript span")?;
+    expect("explicit data-script", explicit.script, ScriptPosition::Superscript)
+}
+
+fn check_metadata_mapping(output: &PreprocessorOutput) -> Result<(), String> {
+    let m = &output.metadata;
+    expect("title", m.title.as_deref(), Some("Conformance Basics"))?;
+    expect("author", m.author.as_deref(), Some("Blazegraph"))?;
+    expect("language", m.language.as_deref(), Some("en"))?;
+    expect("producer", m.producer.as_deref(), Some("Conformance Producer 1.0"))?;
+    expect("pdf version", m.pdf_version.as_deref(), Some("1.7"))?;
+    expect("created", m.created.as_deref(), Some("2024-01-02T03:04:05Z"))?;
+    expect("encrypted", m.encrypted, Some(false))?;
+    expect("page count", m.page_count, 2)
+}
+
+fn check_bookmark_extraction(output: &PreprocessorOutput) -> Result<(), String> {
+    let sections = output
+        .bookmark_data
+        .as_ref()
+        .map(|b| {
+            b.sections
+                .iter()
+                .map(|s| (s.title.as_str(), s.order, s.level))
+                .collect::<Vec<_>>()
+        })
+        .ok_or("no bookmark data")?;
+    expect("bookmarks", sections, vec![("Introduction", 0, 1), ("Methods", 1, 1)])?;
+
+    let matched = find(output, "Methods")?
+        .bookmark_match
+        .as_ref()
+        .map(|b| (b.order, b.confidence));
+    expect("Methods bookmark match", matched, Some((1, Some(1.0))))?;
+    expect(
+        "body text bookmark match",
+        find(output, "Opening paragraph.")?.bookmark_match.is_some(),
+        false,
+    )
+}
+
+fn check_nested_bookmarks(output: &PreprocessorOutput) -> Result<(), String> {
+    let bookmarks = output.bookmark_data.as_ref().ok_or("no bookmark data")?;
+    let flattened: Vec<_> = bookmarks
+        .sections
+        .iter()
+        .map(|s| (s.title.as_str(), s.order, s.level, s.parent, s.page))
+        .collect();
+    expect(
+        "flattened outline",
+        flattened,
+        vec![
+            ("Part One", 0, 1, None, Some(1)),
+            ("Chapter 1", 1, 2, Some(0), Some(1)),
+            ("Section 1.1", 2, 3, Some(1), Some(1)),
+            ("Chapter 2", 3, 2, Some(0), Some(2)),
+            ("Part Two", 4, 1, None, Some(2)),
+        ],
+    )?;
+
+    let tree = bookmarks.tree();
+    let shape: Vec<(&str, usize)> = tree
+        .iter()
+        .map(|n| (n.section.title.as_str(), n.children.len()))
+        .collect();
+    expect("outline roots", shape, vec![("Part One", 2), ("Part Two", 0)])?;
+    expect("outline depth", bookmarks.max_level(), 3)?;
+
+    let matched = find(output, "Chapter 1")?.bookmark_match.as_ref().map(|b| (b.level, b.parent));
+    expect("nested bookmark match level", matched, Some((2, Some(0))))
+}
+
+fn check_empty_pages(output: &PreprocessorOutput) -> Result<(), String> {
+    // Whitespace-only spans are dropped; empty pages still count toward numbering
+    expect("text element count", output.text_elements.len(), 1)?;
+    expect("page number", find(output, "Only text, on page two.")?.page_number, 2)?;
+    expect("page count", output.metadata.page_count, 3)
+}
+
+fn check_figures(output: &PreprocessorOutput) -> Result<(), String> {
+    // Caption spans belong to the figure; the unplaced image is ignored
+    let figures: Vec<&str> = output
+        .text_elements
+        .iter()
+        .filter(|e| e.is_figure())
+        .map(|e| e.text.as_str())
+        .collect();
+    expect("figures", figures, vec!["Trend icon", "Figure 1: Revenue by region"])?;
+    expect("text element count", output.text_elements.len(), 4)?;
+
+    let figure = find(output, "Figure 1: Revenue by region")?;
+    expect("figure height", figure.bounding_box.height, 200.0)?;
+    expect(
+        "caption",
+        figure.paragraph_attributes.get("caption").map(String::as_str),
+        Some("Figure 1: Revenue by region"),
+    )?;
+    // Figures are paragraphs of their own, even when the image sat inside one
+    let paragraphs: Vec<u32> = output.text_elements.iter().map(|e| e.paragraph_number).collect();
+    expect("paragraph numbers", paragraphs, vec![0, 1, 2, 3])
+}
@@ -0,0 +1,497 @@
+//! Builders and contract assertions for testing pipeline stages.
+//!
+//! Downstream crates that implement custom rules or preprocessors can build
+//! stage inputs without hand-writing every field, and check their output
+//! against the same contracts the core pipeline is held to:
+//!
+//! - Tree invariants: one parentless root, parent/child links agree, every
+//!   node reachable from the root, children deeper than their parent
+//! - Text conservation: every non-whitespace character that entered the
+//!   pipeline ends up in exactly one graph node
+//!
+//! Backend and preprocessor authors should also run the [`conformance`] suite.
+//!
+//! The module is only compiled with the `test-utils` feature, so release
+//! builds don't carry the builders or the conformance fixtures. Enable it for
+//! tests only:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! blazegraph-io-core = { version = "0.1", features = ["test-utils"] }
+//! ```
+
+pub mod conformance;
+
+use crate::preprocessors::pdf::xhtml_parser::parse_xhtml;
+use crate::preprocessors::Preprocessor;
+use crate::processor::DocumentProcessor;
+use crate::storage::NoOpStorage;
+use crate::types::*;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Build a BoundingBox in one call
+pub fn bbox(x: f32, y: f32, width: f32, height: f32) -> BoundingBox {
+    BoundingBox {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+/// Builder for FontClass with body-text defaults (12pt, normal, black)
+#[derive(Debug, Clone)]
+pub struct FontClassBuilder {
+    font: FontClass,
+}
+
+impl FontClassBuilder {
+    pub fn new(class_name: &str) -> Self {
+        Self {
+            font: FontClass {
+                class_name: class_name.to_string(),
+                font_family: "Times-Roman".to_string(),
+                font_size: 12.0,
+                font_style: "normal".to_string(),
+                font_weight: "normal".to_string(),
+                color: "#000000".to_string(),
+            },
+        }
+    }
+
+    pub fn family(mut self, family: &str) -> Self {
+        self.font.font_family = family.to_string();
+        self
+    }
+
+    pub fn size(mut self, size: f32) -> Self {
+        self.font.font_size = size;
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.font.font_weight = "bold".to_string();
+        self
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.font.font_style = "italic".to_string();
+        self
+    }
+
+    pub fn color(mut self, color: &str) -> Self {
+        self.font.color = color.to_string();
+        self
+    }
+
+    pub fn build(self) -> FontClass {
+        self.font
+    }
+}
+
+/// Builder for PdfTextElement. Token count is estimated from the text the
+/// same way the XHTML parser does it (~4 characters per token).
+#[derive(Debug, Clone)]
+pub struct TextElementBuilder {
+    element: PdfTextElement,
+}
+
+impl TextElementBuilder {
+    pub fn new(text: &str) -> Self {
+        Self {
+            element: PdfTextElement {
+                text: text.to_string(),
+                style_info: FontClassBuilder::new("f1").build(),
+                bounding_box: bbox(72.0, 72.0, text.len() as f32 * 6.0, 12.0),
+                page_number: 1,
+                paragraph_number: 0,
+                line_number: 0,
+                segment_number: 0,
+                reading_order: 0,
+                bookmark_match: None,
+                token_count: text.len() / 4,
+                paragraph_attributes: HashMap::new(),
+                script: ScriptPosition::Baseline,
+            },
+        }
+    }
+
+    pub fn style(mut self, style: FontClass) -> Self {
+        self.element.style_info = style;
+        self
+    }
+
+    pub fn bbox(mut self, bounding_box: BoundingBox) -> Self {
+        self.element.bounding_box = bounding_box;
+        self
+    }
+
+    /// Move the element without changing its size
+    pub fn at(mut self, x: f32, y: f32) -> Self {
+        self.element.bounding_box.x = x;
+        self.element.bounding_box.y = y;
+        self
+    }
+
+    pub fn page(mut self, page_number: u32) -> Self {
+        self.element.page_number = page_number;
+        self
+    }
+
+    pub fn paragraph(mut self, paragraph_number: u32) -> Self {
+        self.element.paragraph_number = paragraph_number;
+        self
+    }
+
+    pub fn line(mut self, line_number: u32, segment_number: u32) -> Self {
+        self.element.line_number = line_number;
+        self.element.segment_number = segment_number;
+        self
+    }
+
+    pub fn reading_order(mut self, reading_order: u32) -> Self {
+        self.element.reading_order = reading_order;
+        self
+    }
+
+    pub fn bookmark(mut self, title: &str, order: u32) -> Self {
+        self.element.bookmark_match = Some(BookmarkSection {
+            title: title.to_string(),
+            order,
+            level: 1,
+            parent: None,
+            page: Some(self.element.page_number),
+            confidence: Some(1.0),
+        });
+        self
+    }
+
+    /// Set a backend hint as if it came from `data-<name>` on the <p>
+    pub fn paragraph_attribute(mut self, name: &str, value: &str) -> Self {
+        self.element
+            .paragraph_attributes
+            .insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Mark the span as superscript/subscript, as `data-script` would
+    pub fn script(mut self, script: ScriptPosition) -> Self {
+        self.element.script = script;
+        self
+    }
+
+    pub fn build(self) -> PdfTextElement {
+        self.element
+    }
+}
+
+/// Lay out lines top-to-bottom on page 1, one paragraph per line, with
+/// reading order assigned — a quick stage-1 input for rule tests.
+pub fn text_elements(lines: &[&str]) -> Vec<PdfTextElement> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            TextElementBuilder::new(line)
+                .at(72.0, 72.0 + i as f32 * 14.0)
+                .paragraph(i as u32)
+                .line(i as u32, 0)
+                .reading_order(i as u32)
+                .build()
+        })
+        .collect()
+}
+
+/// Builder for ParsedPdfElement, the elements rules pass along: a page-1
+/// paragraph at hierarchy level 0 in body text, token count estimated from
+/// the text as in [`TextElementBuilder`].
+#[derive(Debug, Clone)]
+pub struct ParsedElementBuilder {
+    element: ParsedPdfElement,
+}
+
+impl ParsedElementBuilder {
+    pub fn new(text: &str) -> Self {
+        Self {
+            element: ParsedPdfElement {
+                element_type: ParsedElementType::Paragraph,
+                text: text.to_string(),
+                hierarchy_level: 0,
+                position: 0,
+                style_info: FontClassBuilder::new("f1").build(),
+                bounding_box: bbox(72.0, 72.0, text.len() as f32 * 6.0, 12.0),
+                page_number: 1,
+                paragraph_number: 0,
+                reading_order: 0,
+                bookmark_match: None,
+                token_count: text.len() / 4,
+                provenance: None,
+                style_runs: Vec::new(),
+            },
+        }
+    }
+
+    pub fn element_type(mut self, element_type: ParsedElementType) -> Self {
+        self.element.element_type = element_type;
+        self
+    }
+
+    pub fn level(mut self, hierarchy_level: u32) -> Self {
+        self.element.hierarchy_level = hierarchy_level;
+        self
+    }
+
+    pub fn style(mut self, style: FontClass) -> Self {
+        self.element.style_info = style;
+        self
+    }
+
+    pub fn bbox(mut self, bounding_box: BoundingBox) -> Self {
+        self.element.bounding_box = bounding_box;
+        self
+    }
+
+    pub fn page(mut self, page_number: u32) -> Self {
+        self.element.page_number = page_number;
+        self
+    }
+
+    pub fn paragraph(mut self, paragraph_number: u32) -> Self {
+        self.element.paragraph_number = paragraph_number;
+        self
+    }
+
+    /// Set both the position and the reading order, which rules keep in step
+    pub fn order(mut self, order: u32) -> Self {
+        self.element.position = order as usize;
+        self.element.reading_order = order;
+        self
+    }
+
+    pub fn bookmark(mut self, bookmark: BookmarkSection) -> Self {
+        self.element.bookmark_match = Some(bookmark);
+        self
+    }
+
+    pub fn tokens(mut self, token_count: usize) -> Self {
+        self.element.token_count = token_count;
+        self
+    }
+
+    pub fn build(self) -> ParsedPdfElement {
+        self.element
+    }
+}
+
+/// Preprocessor whose input files are already Blazegraph XHTML (e.g. a
+/// `stage1a_xhtml.html` snapshot), so the full pipeline runs without a backend.
+pub struct MarkupPreprocessor;
+
+impl Preprocessor for MarkupPreprocessor {
+    fn parse_pdf_to_markup_language(&self, bytes: &[u8]) -> Result<String> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    fn parse_markup_to_preprocessor_output(&self, markup: &str) -> Result<PreprocessorOutput> {
+        parse_xhtml(markup)
+    }
+
+    fn name(&self) -> &str {
+        "markup"
+    }
+
+    fn supports_file_type(&self, path: &Path) -> bool {
+        matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("html" | "xhtml")
+        )
+    }
+}
+
+/// DocumentProcessor over [`MarkupPreprocessor`] with caching disabled
+pub fn markup_processor() -> Result<DocumentProcessor> {
+    DocumentProcessor::new_with_dependencies(Box::new(MarkupPreprocessor), Box::new(NoOpStorage))
+}
+
+/// Check structural tree invariants, returning every violation found
+pub fn check_tree_invariants(graph: &DocumentGraph) -> Vec<String> {
+    let mut violations = Vec::new();
+    let root_id = graph.document_info.root_id;
+
+    match graph.nodes.get(&root_id) {
+        Some(root) if root.parent.is_some() => {
+            violations.push(format!("Root {} has a parent", root_id));
+        }
+        Some(_) => {}
+        None => {
+            violations.push(format!("Root {} is missing from nodes", root_id));
+            return violations;
+        }
+    }
+
+    for node in graph.nodes.values() {
+        if node.id != root_id {
+            match node.parent.and_then(|p| graph.nodes.get(&p)) {
+                None => violations.push(format!("Node {} has a missing or dangling parent", node.id)),
+                Some(parent) => {
+                    if !parent.children.contains(&node.id) {
+                        violations.push(format!(
+                            "Node {} is not listed in its parent's children",
+                            node.id
+                        ));
+                    }
+                    if parent.id != root_id
+                        && node.location.semantic.depth <= parent.location.semantic.depth
+                    {
+                        violations.push(format!(
+                            "Node {} (depth {}) is not deeper than its parent (depth {})",
+                            node.id, node.location.semantic.depth, parent.location.semantic.depth
+                        ));
+                    }
+                }
+            }
+        }
+
+        for child_id in &node.children {
+            match graph.nodes.get(child_id) {
+                None => violations.push(format!("Node {} has dangling child {}", node.id, child_id)),
+                Some(child) if child.parent != Some(node.id) => violations.push(format!(
+                    "Child {} of {} points at a different parent",
+                    child_id, node.id
+                )),
+                Some(_) => {}
+            }
+        }
+    }
+
+    // Every node must be reachable from the root exactly once
+    let mut seen = HashSet::new();
+    let mut stack = vec![root_id];
+    while let Some(id) = stack.pop() {
+        if !seen.insert(id) {
+            violations.push(format!("Node {} is reachable more than once (cycle?)", id));
+            continue;
+        }
+        if let Some(node) = graph.nodes.get(&id) {
+            stack.extend(node.children.iter().copied());
+        }
+    }
+    if seen.len() != graph.nodes.len() {
+        violations.push(format!(
+            "{} of {} nodes are unreachable from the root",
+            graph.nodes.len().saturating_sub(seen.len()),
+            graph.nodes.len()
+        ));
+    }
+
+    violations
+}
+
+/// Panic with every tree invariant violation in the graph
+pub fn assert_tree_invariants(graph: &DocumentGraph) {
+    let violations = check_tree_invariants(graph);
+    assert!(
+        violations.is_empty(),
+        "Tree invariants violated:\n  {}",
+        violations.join("\n  ")
+    );
+}
+
+/// Compare non-whitespace characters going in against those in the graph's
+/// nodes (the synthetic Document root is ignored). Whitespace is excluded
+/// because rules are free to re-join spans and lines.
+pub fn check_text_conservation(
+    input: &[PdfTextElement],
+    graph: &DocumentGraph,
+) -> Result<(), String> {
+    let expected = char_counts(input.iter().map(|e| e.text.as_str()));
+    let actual = char_counts(
+        graph
+            .nodes
+            .values()
+            .filter(|n| n.id != graph.document_info.root_id)
+            .map(|n| n.content.text.as_str()),
+    );
+
+    if expected == actual {
+        return Ok(());
+    }
+
+    let mut diffs: Vec<String> = expected
+        .keys()
+        .chain(actual.keys())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .filter_map(|c| {
+            let want = expected.get(c).copied().unwrap_or(0);
+            let got = actual.get(c).copied().unwrap_or(0);
+            (want != got).then(|| format!("{:?}: input {} vs graph {}", c, want, got))
+        })
+        .collect();
+    diffs.sort();
+
+    Err(format!(
+        "Text not conserved ({} characters differ):\n  {}",
+        diffs.len(),
+        diffs.join("\n  ")
+    ))
+}
+
+/// Panic if any input text was lost or invented on the way into the graph
+pub fn assert_text_conserved(input: &[PdfTextElement], graph: &DocumentGraph) {
+    if let Err(message) = check_text_conservation(input, graph) {
+        panic!("{}", message);
+    }
+}
+
+fn char_counts<'a>(texts: impl Iterator<Item = &'a str>) -> HashMap<char, usize> {
+    let mut counts = HashMap::new();
+    for text in texts {
+        for c in text.chars().filter(|c| !c.is_whitespace()) {
+            *counts.entry(c).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph_with_child(text: &str) -> (DocumentGraph, NodeId) {
+        let mut graph = DocumentGraph::new();
+        let mut root = DocumentNode::new("Document", "Document".to_string());
+        root.id = graph.document_info.root_id;
+
+        let mut child = DocumentNode::new("Paragraph", text.to_string());
+        child.parent = Some(root.id);
+        child.location.semantic.depth = 1;
+        root.children.push(child.id);
+
+        let child_id = child.id;
+        graph.nodes.insert(root.id, root);
+        graph.nodes.insert(child_id, child);
+        (graph, child_id)
+    }
+
+    #[test]
+    fn detects_broken_parent_links() {
+        let (mut graph, child_id) = graph_with_child("Hello world");
+        assert!(check_tree_invariants(&graph).is_empty());
+
+        graph.nodes.get_mut(&child_id).unwrap().parent = None;
+        assert!(!check_tree_invariants(&graph).is_empty());
+    }
+
+    #[test]
+    fn text_conservation_ignores_whitespace_but_not_content() {
+        let input = text_elements(&["Hello", "world"]);
+        let (graph, _) = graph_with_child("Hello world");
+        assert!(check_text_conservation(&input, &graph).is_ok());
+
+        let (lossy, _) = graph_with_child("Hello");
+        assert!(check_text_conservation(&input, &lossy).is_err());
+    }
+}
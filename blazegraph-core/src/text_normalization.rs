@@ -0,0 +1,97 @@
+//! Unicode normalization of extracted text
+//!
+//! PDF text layers keep the typesetter's glyphs: "ﬁnancial" is spelled with
+//! the U+FB01 ligature, so a keyword search for "financial" misses it, and
+//! the same accented letter can arrive precomposed or as a base letter plus
+//! combining mark. Before anything else looks at the text (page filters,
+//! span merging, rules), each span is normalized:
+//!
+//! - Latin ligatures (ﬀ ﬁ ﬂ ﬃ ﬄ ﬅ ﬆ) are expanded to their letters;
+//! - soft hyphens (U+00AD), invisible hyphenation hints, are removed;
+//! - curly quotes are folded to `'` and `"` (off by default: it changes
+//!   the text as printed);
+//! - the result is put in Unicode NFC.
+//!
+//! Token counts of changed spans are recomputed.
+
+use crate::config::TextNormalizationConfig;
+use crate::preprocessors::pdf::xhtml_parser::estimate_token_count;
+use crate::types::PdfTextElement;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+const SOFT_HYPHEN: char = '\u{ad}';
+
+/// Normalize every span's text in place; returns how many spans changed
+pub fn normalize_text(text_elements: &mut [PdfTextElement], config: &TextNormalizationConfig) -> usize {
+    if !config.enabled {
+        return 0;
+    }
+    let mut changed = 0;
+    for element in text_elements.iter_mut() {
+        if let Some(text) = normalize(&element.text, config) {
+            element.token_count = estimate_token_count(&text);
+            element.text = text;
+            changed += 1;
+        }
+    }
+    if changed > 0 {
+        println!("🔤 Normalized text in {} span(s)", changed);
+    }
+    changed
+}
+
+/// `text` normalized per `config`, or `None` when it is already normal
+pub fn normalize(text: &str, config: &TextNormalizationConfig) -> Option<String> {
+    let mut normalized = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            SOFT_HYPHEN if config.remove_soft_hyphens => {}
+            '\u{fb00}'..='\u{fb06}' if config.expand_ligatures => normalized.push_str(expand_ligature(c)),
+            '‘' | '’' | '‚' | '‛' if config.fold_quotes => normalized.push('\''),
+            '“' | '”' | '„' | '‟' if config.fold_quotes => normalized.push('"'),
+            _ => normalized.push(c),
+        }
+    }
+    if config.nfc && is_nfc_quick(normalized.chars()) != IsNormalized::Yes {
+        normalized = normalized.nfc().collect();
+    }
+    (normalized != text).then_some(normalized)
+}
+
+fn expand_ligature(ligature: char) -> &'static str {
+    match ligature {
+        '\u{fb00}' => "ff",
+        '\u{fb01}' => "fi",
+        '\u{fb02}' => "fl",
+        '\u{fb03}' => "ffi",
+        '\u{fb04}' => "ffl",
+        _ => "st",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TextElementBuilder;
+
+    #[test]
+    fn expands_ligatures_and_normalizes() {
+        let config = TextNormalizationConfig::default();
+        let mut elements = vec![
+            TextElementBuilder::new("The ﬁnal ﬂow ofﬁce e\u{301}tude").build(),
+            TextElementBuilder::new("hyphen\u{ad}ation “as printed”").build(),
+            TextElementBuilder::new("Already plain").build(),
+        ];
+        assert_eq!(normalize_text(&mut elements, &config), 2);
+        assert_eq!(elements[0].text, "The final flow office \u{e9}tude");
+        assert_eq!(elements[1].text, "hyphenation “as printed”");
+        assert_eq!(elements[0].token_count, estimate_token_count(&elements[0].text));
+
+        let folding = TextNormalizationConfig {
+            fold_quotes: true,
+            ..config
+        };
+        assert_eq!(normalize("“don’t”", &folding).unwrap(), "\"don't\"");
+        assert_eq!(normalize("plain 'text'", &folding), None);
+    }
+}
@@ -0,0 +1,123 @@
+//! Token counting
+//!
+//! Node `token_count`s are what RAG consumers budget chunks by, so the
+//! graph's counts come from a `Tokenizer` chosen by the config's
+//! `tokenizer` name:
+//!
+//! - `estimate` (default): a quarter of the UTF-8 length, with no
+//!   vocabulary to load. It runs 30% or more off on code and non-English
+//!   text;
+//! - `cl100k_base`, `o200k_base`: the BPE encodings of OpenAI's models,
+//!   counted exactly (`tiktoken` feature);
+//! - any name registered with `RuleEngine::register_tokenizer` (a
+//!   Hugging Face tokenizer, a model provider's counting endpoint).
+//!
+//! Rules estimate as they merge and split elements; once the graph is built
+//! every node is counted again with the configured tokenizer, and
+//! SizeEnforcer measures with it when `size_unit` is `tokens`.
+
+use crate::preprocessors::pdf::xhtml_parser::estimate_token_count;
+use anyhow::{bail, Result};
+use std::sync::Arc;
+
+/// Counts the tokens a model would see for a piece of text
+pub trait Tokenizer: Send + Sync {
+    fn count(&self, text: &str) -> usize;
+}
+
+/// Built-in tokenizer names a config can use
+pub const BUILT_IN_TOKENIZERS: &[&str] = &["estimate", "cl100k_base", "o200k_base"];
+
+/// Roughly four bytes per token
+pub struct EstimateTokenizer;
+
+impl Tokenizer for EstimateTokenizer {
+    fn count(&self, text: &str) -> usize {
+        estimate_token_count(text)
+    }
+}
+
+/// An OpenAI BPE encoding; the vocabulary is loaded once per process
+#[cfg(feature = "tiktoken")]
+pub struct TiktokenTokenizer {
+    bpe: &'static tiktoken_rs::CoreBPE,
+}
+
+#[cfg(feature = "tiktoken")]
+impl TiktokenTokenizer {
+    pub fn cl100k_base() -> Self {
+        Self {
+            bpe: tiktoken_rs::cl100k_base_singleton(),
+        }
+    }
+
+    pub fn o200k_base() -> Self {
+        Self {
+            bpe: tiktoken_rs::o200k_base_singleton(),
+        }
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+impl Tokenizer for TiktokenTokenizer {
+    fn count(&self, text: &str) -> usize {
+        // Special-token text in a document ("<|endoftext|>") is counted as
+        // the model would encode it from user input: as ordinary text
+        self.bpe.encode_ordinary(text).len()
+    }
+}
+
+/// The built-in tokenizer called `name`
+pub fn built_in_tokenizer(name: &str) -> Result<Arc<dyn Tokenizer>> {
+    match name {
+        "estimate" => Ok(Arc::new(EstimateTokenizer)),
+        #[cfg(feature = "tiktoken")]
+        "cl100k_base" => Ok(Arc::new(TiktokenTokenizer::cl100k_base())),
+        #[cfg(feature = "tiktoken")]
+        "o200k_base" => Ok(Arc::new(TiktokenTokenizer::o200k_base())),
+        #[cfg(not(feature = "tiktoken"))]
+        "cl100k_base" | "o200k_base" => bail!("Tokenizer '{}' needs the `tiktoken` feature", name),
+        _ => bail!(
+            "Unknown tokenizer '{}' (built-in: {}; others must be registered)",
+            name,
+            BUILT_IN_TOKENIZERS.join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::RuleEngine;
+
+    struct Words;
+
+    impl Tokenizer for Words {
+        fn count(&self, text: &str) -> usize {
+            text.split_whitespace().count()
+        }
+    }
+
+    #[test]
+    fn tokenizers_are_chosen_by_name() {
+        let estimate = built_in_tokenizer("estimate").unwrap();
+        assert_eq!(estimate.count("twelve chars"), 3);
+        assert!(built_in_tokenizer("words").is_err());
+
+        let mut engine = RuleEngine::new().unwrap();
+        engine.register_tokenizer("words", Words).unwrap();
+        assert!(engine.register_tokenizer("estimate", Words).is_err());
+        assert_eq!(engine.tokenizer("words").unwrap().count("three short words"), 3);
+        assert_eq!(engine.tokenizer("estimate").unwrap().count("three short words"), 4);
+
+        #[cfg(feature = "tiktoken")]
+        {
+            let cl100k = built_in_tokenizer("cl100k_base").unwrap();
+            assert_eq!(cl100k.count("hello world"), 2);
+            // Code and non-English text are where the estimate drifts
+            let code = "fn main() { let x: Vec<u8> = vec![1, 2, 3]; }";
+            assert_ne!(cl100k.count(code), estimate.count(code));
+            assert!(built_in_tokenizer("o200k_base").unwrap().count("日本語のテキスト") > 0);
+        }
+    }
+}
@@ -1,6 +1,7 @@
+use crate::page_range::PageRange;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
 pub type NodeId = Uuid;
@@ -8,17 +9,24 @@ pub type NodeId = Uuid;
 // ===== NODE LOCATION TYPES =====
 // These types implement the location model from 001-document-model.
 // SemanticLocation is always present (computed by GraphBuilder from tree structure).
-// PhysicalLocation is only present for fixed-flow formats (PDF).
+// PhysicalLocation is only present for fixed-flow formats (PDF, DOCX).
+// TextSpan places the node in DocumentGraph::document_text.
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct NodeLocation {
     /// Always present — computed by GraphBuilder from final tree structure
     pub semantic: SemanticLocation,
-    /// Only for fixed-flow formats (PDF) — passed through from channel
+    /// Only for fixed-flow formats (PDF, DOCX) — passed through from channel
     pub physical: Option<PhysicalLocation>,
+    /// Where the node's text sits in `DocumentGraph::document_text`.
+    /// Omitted for the root and for nodes without text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_span: Option<TextSpan>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SemanticLocation {
     /// Hierarchical position in the document tree (e.g. "2.3.4")
     pub path: String,
@@ -28,20 +36,34 @@ pub struct SemanticLocation {
     pub breadcrumbs: Vec<String>,
 }
 
+/// Character offsets (Unicode scalar values, end exclusive) into the
+/// reconstructed document text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TextSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct PhysicalLocation {
     /// Page number (1-indexed)
     pub page: u32,
     /// Bounding box on the page
     pub bounding_box: BoundingBox,
+    /// The number printed on the page, when it has one (see
+    /// `DocumentInfo::page_labels`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_label: Option<String>,
 }
 
 /// Signals whether physical location data is meaningful for this document
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum FlowType {
-    /// PDF — has physical layout, physical_location is present
+    /// PDF (and DOCX, with synthetic geometry) — physical_location is present
     Fixed,
-    /// Markdown, DOCX — reflows, physical_location is None
+    /// Markdown — reflows, physical_location is None
     Free,
 }
 
@@ -50,6 +72,7 @@ pub enum FlowType {
 /// Has proto-L1 character: one per document, invariant to tree structure.
 /// See 006-document-info-separation.md for design rationale.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct DocumentInfo {
     /// References the Document node in nodes[] (the tree root)
     pub root_id: NodeId,
@@ -57,12 +80,301 @@ pub struct DocumentInfo {
     pub document_metadata: DocumentMetadata,
     /// Analysis computed from text elements (font distributions, style stats)
     pub document_analysis: DocumentAnalysis,
+    /// The pages selected for processing (`page_range`); all of them when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_range: Option<PageRange>,
+    /// Pages dropped by content-based page filters before rule processing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_pages: Vec<SkippedPage>,
+    /// Pages found out of scan order, and the order they were meant in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_reordering: Option<PageReordering>,
+    /// Page numbers printed on the pages ("xiv", "12"), by physical page
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub page_labels: Vec<PageLabel>,
+    /// Section-level mapping to the previous revision, when warm-started from one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<RevisionMapping>,
+    /// Supplementary graphs grafted into this one, in the order they were grafted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub grafts: Vec<GraftRecord>,
+    /// Terms the document defines, in order of definition (`glossary` config)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub glossary: Vec<GlossaryEntry>,
+    /// Quality caveats raised by any pipeline stage while producing this graph
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ProcessingWarning>,
+    /// The extraction stack that produced the source elements
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extraction: Option<ExtractionInfo>,
+    /// How each page's text was obtained. Omitted when every page came from
+    /// the PDF text layer; listed in full for scanned and hybrid documents.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub page_extraction: Vec<PageExtraction>,
+    /// `DocumentGraph::content_hash`: equal for documents with the same
+    /// content and structure, whatever their file name or layout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+/// A defined term: its definition, the node defining it and the nodes using it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct GlossaryEntry {
+    pub term: String,
+    pub definition: String,
+    pub defined_in: NodeId,
+    /// Nodes mentioning the term, in document order
+    #[serde(default)]
+    pub used_in: Vec<NodeId>,
+}
+
+/// Per-page record of where the text came from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PageExtraction {
+    pub page_number: u32,
+    pub method: ExtractionMethod,
+    /// Non-whitespace characters the page contributed
+    pub text_chars: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ExtractionMethod {
+    /// Read from the PDF's text layer
+    TextLayer,
+    /// Recognized from the rendered page image
+    Ocr,
+    /// No usable text layer and nothing recovered; likely a scanned page
+    Missing,
+}
+
+/// Which backend (and which versions of it) extracted a document
+///
+/// Recorded at extraction time and cached with the Level 1 output, so a graph
+/// rebuilt from cache still names the stack that actually read the source.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ExtractionInfo {
+    /// Backend or preprocessor name, e.g. "TikaJniBackend" or "MarkdownPreprocessor"
+    pub backend: String,
+    /// Blazegraph crate version that ran the extraction
+    pub blazegraph_version: String,
+    /// Extraction JAR identity: file name plus a short content hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jar_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tika_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pdfbox_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jvm_version: Option<String>,
+}
+
+impl ExtractionInfo {
+    pub fn new(backend: impl Into<String>) -> Self {
+        Self {
+            backend: backend.into(),
+            blazegraph_version: env!("CARGO_PKG_VERSION").to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Something a stage worked around instead of failing on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ProcessingWarning {
+    pub code: WarningCode,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    /// Graph nodes the warning concerns, when it can be pinned to any
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub node_ids: Vec<NodeId>,
+}
+
+impl ProcessingWarning {
+    pub fn new(code: WarningCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            page: None,
+            node_ids: Vec::new(),
+        }
+    }
+
+    pub fn on_page(mut self, page: u32) -> Self {
+        self.page = Some(page);
+        self
+    }
+
+    pub fn with_nodes(mut self, node_ids: Vec<NodeId>) -> Self {
+        self.node_ids = node_ids;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningCode {
+    /// A span referenced a font class with no CSS definition; default styling was used
+    FallbackFont,
+    /// A span had an unparseable bounding box and was dropped
+    DroppedSpan,
+    /// A page produced no text elements
+    EmptyPage,
+    /// The XHTML carried no style block at all
+    MissingStyles,
+    /// Page filters matched every page, so none were skipped
+    PageFilterOverridden,
+    /// The pipeline named a rule the engine does not know; it was skipped
+    UnknownRule,
+    /// Pages had no text layer (scanned) and could not be recovered
+    ScannedPages,
+    /// Pages look out of order; a corrected order was suggested but not applied
+    PagesOutOfOrder,
+    /// A registered stage assertion annotated the run
+    Assertion,
+    /// The document looked simple enough that rule processing was skipped
+    /// (`auto_minimal_parse`)
+    MinimalParse,
+    /// A span held far more text than one line can (a whole page emitted as
+    /// one span) and was split into pieces
+    GiantSpan,
+}
+
+/// A page removed by a page filter, and why
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SkippedPage {
+    pub page_number: u32,
+    pub reason: PageSkipReason,
+    /// The config pattern that triggered the skip
+    pub pattern: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PageSkipReason {
+    /// The page's text matched a `skip_pages_matching` pattern
+    MatchedPattern,
+    /// A leading page matched a `cover_page_patterns` pattern
+    CoverPage,
+    /// The page follows a page matching a `skip_after_patterns` pattern
+    AfterBoundary,
+}
+
+/// A corrected page order for a document scanned out of order
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PageReordering {
+    /// Physical page numbers, in the order they should be read
+    pub suggested_order: Vec<u32>,
+    pub evidence: PageOrderEvidence,
+    /// Whether pages were renumbered into this order before graph building.
+    /// If so, node locations carry the new page numbers
+    pub applied: bool,
+}
+
+/// The number printed on a physical page
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PageLabel {
+    /// Physical page number (1-indexed), as in node locations
+    pub page: u32,
+    /// The printed number, e.g. "xiv" or "12"
+    pub label: String,
+    pub style: PageLabelStyle,
+    /// Not printed (or not found) on the page, but implied by the labels of
+    /// the pages around it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub inferred: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PageLabelStyle {
+    Arabic,
+    Roman,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PageOrderEvidence {
+    /// Page numbers printed at the top or bottom of the pages
+    PrintedPageNumbers,
+    /// Sentences broken off at the end of one page and picked up on another
+    SentenceContinuation,
+}
+
+/// How a graph relates to the previous revision it was warm-started from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RevisionMapping {
+    /// `content_hash` of the previous revision
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_content_hash: Option<String>,
+    /// Every section of either revision, in document order
+    pub sections: Vec<SectionRevision>,
+    /// Nodes that kept their id from the previous revision
+    pub preserved_ids: usize,
+}
+
+/// A supplementary graph grafted into this one (`DocumentGraph::graft`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct GraftRecord {
+    /// The supplement's document title
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// `content_hash` of the supplement as it was before grafting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// The node the supplement was attached under
+    pub anchor: NodeId,
+    /// Top-level grafted nodes: the heading section, or else the
+    /// supplement's top-level nodes
+    pub nodes: Vec<NodeId>,
+    /// Nodes added in all
+    pub node_count: usize,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SectionRevision {
+    pub status: RevisionStatus,
+    /// Id of the section; removed sections carry their previous id
+    pub node_id: NodeId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RevisionStatus {
+    /// Same title and same content, down to every nested node
+    Unchanged,
+    /// Same title, changed content
+    Modified,
+    /// Matched to a previous section whose title differs
+    Renamed,
+    /// New in this revision
+    Added,
+    /// Only in the previous revision
+    Removed,
+}
+
 /// The schema version stamped on every graph output.
 /// Bump this when the output shape changes.
 pub const SCHEMA_VERSION: &str = "0.2.0";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct DocumentGraph {
     pub nodes: HashMap<NodeId, DocumentNode>,
     pub document_info: DocumentInfo,
@@ -71,7 +383,11 @@ pub struct DocumentGraph {
 
 /// The serialization-ready output format. Carries a schema version
 /// so consumers can detect and handle shape changes.
+///
+/// Output types reject unknown fields when deserialized: reading a graph
+/// written by a newer schema fails loudly instead of silently dropping data.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SortedDocumentGraph {
     pub schema_version: String,
     pub nodes: Vec<DocumentNode>,
@@ -80,6 +396,7 @@ pub struct SortedDocumentGraph {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct DocumentNode {
     pub id: NodeId,
     pub node_type: String,
@@ -91,6 +408,19 @@ pub struct DocumentNode {
     pub token_count: usize,
     pub parent: Option<NodeId>,
     pub children: Vec<NodeId>,
+    /// Sections this node's text refers to ("see Section 4.2")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<NodeId>,
+    /// Hyperlinks anchored in this node's text, in document order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<Hyperlink>,
+    /// Enrichment results by namespace (entities, summary, pii, custom.*);
+    /// see `graphs::annotations`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub annotations: HashMap<String, serde_json::Value>,
+    /// Source elements and rules behind this node (`provenance: true` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
 }
 
 impl DocumentNode {
@@ -105,6 +435,7 @@ impl DocumentNode {
                     breadcrumbs: Vec::new(),
                 },
                 physical: None,
+                text_span: None,
             },
             text_order: Some(0),
             content: NodeContent::new(text),
@@ -112,6 +443,10 @@ impl DocumentNode {
             token_count: 0,
             parent: None,
             children: Vec::new(),
+            references: Vec::new(),
+            links: Vec::new(),
+            annotations: HashMap::new(),
+            provenance: None,
         }
     }
 
@@ -131,6 +466,7 @@ impl DocumentNode {
                     width: 0.0,
                     height: 0.0,
                 }),
+                page_label: None,
             });
         }
         node
@@ -138,22 +474,103 @@ impl DocumentNode {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct NodeContent {
     pub text: String,
+    /// KeyValue nodes: the label ("Effective Date"), without its colon
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// KeyValue nodes: the value ("1 March 2024")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Quote nodes: who is quoted, from a trailing "— Name" line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution: Option<String>,
+    /// Callout nodes: the leading label ("Note", "Warning"), when there is one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Table nodes: rows of cells, header rows first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table_data: Option<TableData>,
+    /// Figure and Table nodes: the caption, when the source has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    /// Bold and italic stretches of `text`, when the node mixes emphasis
+    /// with plain text (`inline_styles: spans`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub style_runs: Vec<StyleRun>,
     // Future: can add node-type-specific fields here
     // pub heading_level: Option<u32>, // for sections
     // pub image_path: Option<String>, // for images
-    // pub table_data: Option<TableData>, // for tables
 }
 
 impl NodeContent {
     pub fn new(text: String) -> Self {
         Self {
             text: text.trim().to_string(),
+            key: None,
+            value: None,
+            attribution: None,
+            label: None,
+            table_data: None,
+            caption: None,
+            style_runs: Vec::new(),
         }
     }
 }
 
+/// Row/column structure of a Table node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TableData {
+    /// Cells in reading order; rows can be shorter than the widest row
+    pub rows: Vec<Vec<String>>,
+    /// How many leading rows are column headers
+    #[serde(default)]
+    pub header_rows: usize,
+    /// Caption attached by `CaptionAssociation`; graph nodes carry it in
+    /// `content.caption`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+}
+
+/// Separator between cells when a table row travels as paragraph text
+pub const TABLE_CELL_SEPARATOR: &str = " | ";
+
+/// Join cells into row text; a literal `|` inside a cell is escaped as `\|`
+pub fn join_table_cells<S: AsRef<str>>(cells: &[S]) -> String {
+    cells
+        .iter()
+        .map(|cell| cell.as_ref().replace('\\', "\\\\").replace('|', "\\|"))
+        .collect::<Vec<_>>()
+        .join(TABLE_CELL_SEPARATOR)
+}
+
+/// Inverse of `join_table_cells`
+pub fn split_table_cells(row: &str) -> Vec<String> {
+    let mut cells = vec![String::new()];
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    cells.last_mut().expect("at least one cell").push(escaped);
+                }
+            }
+            '|' => {
+                let cell = cells.last_mut().expect("at least one cell");
+                if cell.ends_with(' ') {
+                    cell.pop();
+                }
+                chars.next_if_eq(&' ');
+                cells.push(String::new());
+            }
+            _ => cells.last_mut().expect("at least one cell").push(c),
+        }
+    }
+    cells
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum NodeType {
     Document,
@@ -161,13 +578,18 @@ pub enum NodeType {
     Paragraph,
     List,
     ListItem,
+    KeyValue,
+    Quote,
+    Callout,
     Table,
     Figure,
+    Caption,
     Header,
     Footer,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct StyleMetadata {
     pub font_class: String,
     pub font_size: Option<f32>,
@@ -181,6 +603,7 @@ pub struct StyleMetadata {
 /// Travels with graph.json. Describes the L0 tree's statistical properties.
 /// See AmplifyNotes/09-Profile-Types.md for design rationale.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct StructuralProfile {
     pub created_at: DateTime<Utc>,
     pub document_type: DocumentType,
@@ -192,6 +615,10 @@ pub struct StructuralProfile {
     pub token_distribution: TokenDistribution,
     pub node_type_distribution: NodeTypeDistribution,
     pub depth_distribution: DepthDistribution,
+    /// The Validation rule's report (issues and quality score), when the
+    /// pipeline ran it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation: Option<ValidationReport>,
 }
 
 impl Default for StructuralProfile {
@@ -205,10 +632,171 @@ impl Default for StructuralProfile {
             token_distribution: TokenDistribution::default(),
             node_type_distribution: NodeTypeDistribution::default(),
             depth_distribution: DepthDistribution::default(),
+            validation: None,
         }
     }
 }
 
+/// Structural issues the Validation rule found. Positions index the elements
+/// as the rule saw them (after the rules before it), not graph nodes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+    pub quality_score: f32,
+    pub total_elements: usize,
+    /// What `validation.fix` repaired; the issues and score are then the ones left
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixes: Option<ValidationFixes>,
+    /// How the detected sections line up with the PDF outline, when the
+    /// document has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outline: Option<OutlineCheck>,
+}
+
+/// A structural issue and how serious it is
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    #[serde(flatten)]
+    pub kind: ValidationIssueKind,
+    #[serde(default)]
+    pub severity: ValidationSeverity,
+}
+
+impl ValidationIssue {
+    /// The issue at its kind's default severity
+    pub fn new(kind: ValidationIssueKind) -> Self {
+        let severity = kind.default_severity();
+        Self { kind, severity }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationSeverity {
+    /// Worth knowing, rarely wrong output (a short heading, a reading order gap)
+    Info,
+    #[default]
+    Warn,
+    /// Output that is wrong as built (impossible coordinates)
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ValidationIssueKind {
+    HierarchyJump {
+        from_level: u32,
+        to_level: u32,
+        from_pos: usize,
+        to_pos: usize,
+    },
+    OrphanedElement {
+        level: u32,
+        position: usize,
+        text_preview: String,
+    },
+    SuspiciousSection {
+        position: usize,
+        text: String,
+        reason: String,
+    },
+    ReadingOrderInconsistency {
+        position: usize,
+        expected_order: u32,
+        actual_order: u32,
+    },
+    PageInconsistency {
+        position: usize,
+        page: u32,
+        issue: String,
+    },
+    InvalidPosition {
+        position: usize,
+        coordinates: String,
+    },
+    /// An outline entry no detected section matches
+    MissingSection {
+        bookmark: String,
+        level: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        page: Option<u32>,
+        /// Closest section, already matched to another entry
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        closest: Option<String>,
+        score: f32,
+    },
+    /// A detected section matching no outline entry
+    ExtraSection {
+        position: usize,
+        text: String,
+        /// Closest outline entry, already matched to another section
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        closest: Option<String>,
+        score: f32,
+    },
+}
+
+impl ValidationIssueKind {
+    /// Issue kinds, as named in reports and `validation.policies`
+    pub const NAMES: &'static [&'static str] = &[
+        "hierarchy_jump",
+        "orphaned_element",
+        "suspicious_section",
+        "reading_order_inconsistency",
+        "page_inconsistency",
+        "invalid_position",
+        "missing_section",
+        "extra_section",
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::HierarchyJump { .. } => "hierarchy_jump",
+            Self::OrphanedElement { .. } => "orphaned_element",
+            Self::SuspiciousSection { .. } => "suspicious_section",
+            Self::ReadingOrderInconsistency { .. } => "reading_order_inconsistency",
+            Self::PageInconsistency { .. } => "page_inconsistency",
+            Self::InvalidPosition { .. } => "invalid_position",
+            Self::MissingSection { .. } => "missing_section",
+            Self::ExtraSection { .. } => "extra_section",
+        }
+    }
+
+    pub fn default_severity(&self) -> ValidationSeverity {
+        match self {
+            Self::SuspiciousSection { .. } | Self::ReadingOrderInconsistency { .. } | Self::ExtraSection { .. } => {
+                ValidationSeverity::Info
+            }
+            Self::HierarchyJump { .. }
+            | Self::OrphanedElement { .. }
+            | Self::PageInconsistency { .. }
+            | Self::MissingSection { .. } => ValidationSeverity::Warn,
+            Self::InvalidPosition { .. } => ValidationSeverity::Error,
+        }
+    }
+}
+
+/// Detected sections against the PDF outline (bookmarks)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutlineCheck {
+    /// Outline entries
+    pub bookmarks: usize,
+    /// Detected sections
+    pub sections: usize,
+    /// Entries and sections paired up by fuzzy title match
+    pub matched: usize,
+    /// Mean match score (0.0–1.0) of the pairs
+    pub mean_score: f32,
+}
+
+/// What `fix: true` repaired
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidationFixes {
+    pub dropped: usize,
+    pub clamped: usize,
+    pub renumbered: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum DocumentType {
     LegalContract,
@@ -225,7 +813,7 @@ pub enum DocumentType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[derive(Default)]
 pub struct TokenDistribution {
-    pub by_node_type: HashMap<String, TokenHistogram>,
+    pub by_node_type: BTreeMap<String, TokenHistogram>,
     pub overall: TokenHistogram,
 }
 
@@ -268,15 +856,15 @@ pub struct HistogramBin {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[derive(Default)]
 pub struct NodeTypeDistribution {
-    pub counts: HashMap<String, usize>,
-    pub percentages: HashMap<String, f32>,
+    pub counts: BTreeMap<String, usize>,
+    pub percentages: BTreeMap<String, f32>,
 }
 
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DepthDistribution {
     pub max_depth: u32,
-    pub depth_counts: HashMap<u32, usize>,
+    pub depth_counts: BTreeMap<u32, usize>,
     pub avg_depth: f32,
 }
 
@@ -284,7 +872,7 @@ impl Default for DepthDistribution {
     fn default() -> Self {
         Self {
             max_depth: 0,
-            depth_counts: HashMap::new(),
+            depth_counts: BTreeMap::new(),
             avg_depth: 0.0,
         }
     }
@@ -296,6 +884,9 @@ impl Default for DepthDistribution {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TikaOutput {
+    /// Empty: the markup is parsed as it is extracted and not kept (caches
+    /// written before that hold it)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub xhtml_content: String,
     pub metadata: DocumentMetadata,
     pub text_elements: Vec<PdfTextElement>,
@@ -305,6 +896,39 @@ pub struct TikaOutput {
     pub style_data: StyleData, // CSS font classes (always present)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bookmark_data: Option<BookmarkData>, // PDF bookmarks/outline
+    /// Extraction-stage warnings, cached alongside the elements they describe
+    #[serde(default)]
+    pub warnings: Vec<ProcessingWarning>,
+    /// The stack that produced this output; absent in caches written before it was recorded
+    #[serde(default)]
+    pub extraction: Option<ExtractionInfo>,
+    /// Per-page extraction methods (empty when every page had a text layer)
+    #[serde(default)]
+    pub page_extraction: Vec<PageExtraction>,
+    /// Hyperlinks and where their anchors are
+    #[serde(default)]
+    pub links: Vec<PageLink>,
+}
+
+/// Where a hyperlink points, and the text it is anchored to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hyperlink {
+    pub href: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub text: String,
+}
+
+/// A hyperlink found during extraction: an `<a href>` around spans, or a
+/// link annotation's rectangle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageLink {
+    pub page_number: u32,
+    pub bounding_box: BoundingBox,
+    pub href: String,
+    /// The anchor text; empty when not known yet
+    #[serde(default)]
+    pub text: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -319,8 +943,83 @@ pub struct PdfTextElement {
     pub reading_order: u32,    // computed from line + segment
     pub bookmark_match: Option<BookmarkSection>, // Full bookmark section if this span matches
     pub token_count: usize,    // Pre-calculated token count for performance
+    /// Backend hints from `data-*` attributes on the enclosing <p>, keyed
+    /// without the prefix (e.g. "role" → "heading")
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub paragraph_attributes: HashMap<String, String>,
+    /// Superscript/subscript: from `data-script` on the span, or inferred
+    /// from size and baseline shift against the neighbouring span
+    #[serde(default, skip_serializing_if = "ScriptPosition::is_baseline")]
+    pub script: ScriptPosition,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+
+/// Vertical position of a span relative to the line it sits on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptPosition {
+    #[default]
+    Baseline,
+    /// Raised and smaller: footnote markers, exponents, ordinals
+    Superscript,
+    /// Lowered and smaller: chemical formulas, indices
+    Subscript,
+}
+
+impl ScriptPosition {
+    pub fn is_baseline(&self) -> bool {
+        *self == ScriptPosition::Baseline
+    }
+
+    /// Parse a `data-script` value ("super", "sub" or "baseline")
+    pub fn from_attribute(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "super" | "superscript" => Some(ScriptPosition::Superscript),
+            "sub" | "subscript" => Some(ScriptPosition::Subscript),
+            "baseline" | "" => Some(ScriptPosition::Baseline),
+            _ => None,
+        }
+    }
+
+    /// The `data-script` value for this position (`None` on the baseline)
+    pub fn attribute(&self) -> Option<&'static str> {
+        match self {
+            ScriptPosition::Baseline => None,
+            ScriptPosition::Superscript => Some("super"),
+            ScriptPosition::Subscript => Some("sub"),
+        }
+    }
+}
+
+impl PdfTextElement {
+    /// Backend-provided paragraph role (`data-role`), e.g. "heading", "caption", "footnote"
+    pub fn paragraph_role(&self) -> Option<&str> {
+        self.paragraph_attributes.get("role").map(String::as_str)
+    }
+
+    /// Table this row belongs to (`data-table`), for sources that declare
+    /// table structure; rows sharing an id form one Table element
+    pub fn table_id(&self) -> Option<&str> {
+        if self.paragraph_role() != Some("table-row") {
+            return None;
+        }
+        self.paragraph_attributes.get("table").map(String::as_str)
+    }
+
+    /// Image or figure region (`<img>`/`<figure>` in the XHTML) rather than text
+    pub fn is_figure(&self) -> bool {
+        self.paragraph_role() == Some("figure")
+    }
+
+    /// Backend-provided heading level (`data-level`), 1 = top-level heading
+    pub fn paragraph_level(&self) -> Option<u32> {
+        self.paragraph_attributes
+            .get("level")
+            .and_then(|level| level.trim().parse().ok())
+            .filter(|level| *level > 0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BoundingBox {
     pub x: f32,
     pub y: f32,
@@ -330,6 +1029,7 @@ pub struct BoundingBox {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
 pub struct DocumentMetadata {
     // Current fields
     pub title: Option<String>,
@@ -347,6 +1047,31 @@ pub struct DocumentMetadata {
     pub description: Option<String>,      // dc:description
     pub encrypted: Option<bool>,          // pdf:encrypted
     pub has_marked_content: Option<bool>, // pdf:hasMarkedContent
+
+    /// Files embedded in the document (PDF attachments)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+}
+
+/// A file embedded in a document: a PDF's embedded files and file
+/// attachment annotations
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Attachment {
+    /// File name as stored in the document
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// MIME type declared by the document ("application/xml")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// Size in bytes, decompressed
+    pub size: usize,
+    /// SHA-256 of the content, hex
+    pub sha256: String,
+    /// Page of the file attachment annotation; None for document-level files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
 }
 
 impl DocumentMetadata {
@@ -367,15 +1092,30 @@ impl DocumentMetadata {
         if extracted.description.is_some() { self.description = extracted.description; }
         if extracted.encrypted.is_some() { self.encrypted = extracted.encrypted; }
         if extracted.has_marked_content.is_some() { self.has_marked_content = extracted.has_marked_content; }
+        if !extracted.attachments.is_empty() { self.attachments = extracted.attachments; }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StyleData {
     pub font_classes: std::collections::HashMap<String, FontClass>,
+    /// Classes from `<style>` blocks inside a page; these override global
+    /// classes of the same name on that page only
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub page_font_classes: HashMap<u32, HashMap<String, FontClass>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl StyleData {
+    /// Look up a class as seen from the given page (page-scoped first, then global)
+    pub fn resolve(&self, class_name: &str, page_number: u32) -> Option<&FontClass> {
+        self.page_font_classes
+            .get(&page_number)
+            .and_then(|classes| classes.get(class_name))
+            .or_else(|| self.font_classes.get(class_name))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FontClass {
     pub class_name: String,  // "f1", "f2", "f3", etc. (kept for convenience)
     pub font_family: String, // "LiberationSerif-Italic"
@@ -387,13 +1127,66 @@ pub struct FontClass {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BookmarkData {
+    /// Outline entries flattened in document (pre-)order; `level` carries the nesting
     pub sections: Vec<BookmarkSection>,
 }
 
+impl BookmarkData {
+    /// Rebuild the outline tree from the flattened, level-annotated sections
+    pub fn tree(&self) -> Vec<BookmarkNode> {
+        fn attach(nodes: &mut Vec<BookmarkNode>, section: &BookmarkSection) {
+            match nodes.last_mut() {
+                Some(last) if section.level > last.section.level => {
+                    attach(&mut last.children, section)
+                }
+                _ => nodes.push(BookmarkNode {
+                    section: section.clone(),
+                    children: Vec::new(),
+                }),
+            }
+        }
+
+        let mut roots = Vec::new();
+        for section in &self.sections {
+            attach(&mut roots, section);
+        }
+        roots
+    }
+
+    /// Deepest nesting level in the outline (0 when empty)
+    pub fn max_level(&self) -> u32 {
+        self.sections.iter().map(|s| s.level).max().unwrap_or(0)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BookmarkSection {
     pub title: String,
     pub order: u32,
+    /// Nesting depth in the outline (1 = top level)
+    #[serde(default = "default_bookmark_level")]
+    pub level: u32,
+    /// `order` of the enclosing bookmark; None at the top level
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<u32>,
+    /// Page the bookmark points at, when the backend provides it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    /// Match confidence (0.0–1.0) when attached to a span as `bookmark_match`;
+    /// None on the entries in BookmarkData itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+}
+
+fn default_bookmark_level() -> u32 {
+    1
+}
+
+/// A bookmark with its nested child bookmarks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookmarkNode {
+    pub section: BookmarkSection,
+    pub children: Vec<BookmarkNode>,
 }
 
 #[derive(Debug, Clone)]
@@ -404,6 +1197,7 @@ pub struct ClassificationResult {
 
 // New output format structures
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SequentialDocument {
     pub format: String,
     pub segments: Vec<SequentialSegment>,
@@ -411,6 +1205,7 @@ pub struct SequentialDocument {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SequentialSegment {
     pub id: usize,
     pub node_type: String,
@@ -418,14 +1213,46 @@ pub struct SequentialSegment {
     pub location: NodeLocation,
     pub style: Option<StyleMetadata>,
     pub tokens: usize,
+    /// Citation id (`<document hash prefix>:<path>`), resolvable with
+    /// `DocumentGraph::resolve_citation`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citation: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct FlatDocument {
     pub format: String,
     pub chunks: Vec<String>,
 }
 
+/// One line of the "chunks" output format: a node's text with what a
+/// retrieval hit needs to be cited
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Chunk {
+    /// Citation id (`<document hash prefix>:<path>`): the same for the same
+    /// content on every run
+    pub id: String,
+    /// `DocumentInfo::content_hash` of the document
+    pub document_id: String,
+    pub node_id: NodeId,
+    pub node_type: String,
+    pub text: String,
+    /// Trail of section headings from the root to the node
+    pub breadcrumbs: Vec<String>,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bounding_box: Option<BoundingBox>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_span: Option<TextSpan>,
+    pub token_count: usize,
+}
+
 // Enhanced List Detection - Two-Phase Processing
 #[derive(Debug, Clone)]
 pub struct ListSequence {
@@ -452,9 +1279,9 @@ pub fn infer_title(elements: &[ParsedPdfElement]) -> Option<String> {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DocumentAnalysis {
     /// Count of each exact font size found in the document
-    pub font_size_counts: HashMap<String, usize>, // Use String for JSON compatibility
+    pub font_size_counts: BTreeMap<String, usize>, // Use String for JSON compatibility
     /// Count of each font family found in the document
-    pub font_family_counts: HashMap<String, usize>,
+    pub font_family_counts: BTreeMap<String, usize>,
     /// Count of bold vs non-bold text elements (bold_count, non_bold_count)
     pub bold_counts: (usize, usize),
     /// Count of italic vs non-italic text elements (italic_count, non_italic_count)
@@ -471,15 +1298,16 @@ pub struct DocumentAnalysis {
 impl DocumentAnalysis {
     /// Create document analysis from text elements
     pub fn analyze_text_elements(text_elements: &[PdfTextElement]) -> Self {
-        let mut font_size_counts: HashMap<String, usize> = HashMap::new();
-        let mut font_family_counts: HashMap<String, usize> = HashMap::new();
+        let mut font_size_counts: BTreeMap<String, usize> = BTreeMap::new();
+        let mut font_family_counts: BTreeMap<String, usize> = BTreeMap::new();
         let mut bold_count = 0;
         let mut non_bold_count = 0;
         let mut italic_count = 0;
         let mut non_italic_count = 0;
         let mut font_sizes = Vec::new();
 
-        for element in text_elements {
+        // Figures carry no text style of their own
+        for element in text_elements.iter().filter(|e| !e.is_figure()) {
             let style = &element.style_info;
 
             // Count font sizes
@@ -579,6 +1407,12 @@ pub struct PreprocessorOutput {
     pub style_data: StyleData,
     /// Document bookmarks/table of contents (if available)
     pub bookmark_data: Option<BookmarkData>,
+    /// Problems worked around during extraction (fallback fonts, dropped spans, ...)
+    pub warnings: Vec<ProcessingWarning>,
+    /// Per-page extraction methods (empty when every page had a text layer)
+    pub page_extraction: Vec<PageExtraction>,
+    /// Hyperlinks and where their anchors are
+    pub links: Vec<PageLink>,
 }
 
 // Rule engine structs
@@ -597,6 +1431,36 @@ pub struct ParsedPdfElement {
     pub reading_order: u32,                      // New: spatial reading order
     pub bookmark_match: Option<BookmarkSection>, // New: bookmark section data
     pub token_count: usize,                      // Pre-calculated token count for performance
+    /// Source elements and rules behind this element (`provenance: true` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+    /// Bold and italic stretches of `text` (`inline_styles: spans` only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub style_runs: Vec<StyleRun>,
+}
+
+/// A bold and/or italic stretch of text, in character offsets (`end`
+/// exclusive), as `TextSpan` counts them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StyleRun {
+    pub start: usize,
+    pub end: usize,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub bold: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub italic: bool,
+}
+
+/// Where an element (and the node built from it) came from; see `rules::provenance`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Indices of the TextElements the rules ran on, ascending
+    pub source_elements: Vec<usize>,
+    /// Pages of those elements, ascending
+    pub pages: Vec<u32>,
+    /// Rules that created, merged, split, retyped or rewrote the element, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -605,4 +1469,25 @@ pub enum ParsedElementType {
     Paragraph,
     List,
     ListItem,
+    /// "Term: definition" or label/value pair; `text` keeps the original wording
+    KeyValue { key: String, value: String },
+    /// Block quote; `text` includes the attribution line when there is one
+    Quote { attribution: Option<String> },
+    /// Boxed or labelled aside (note, tip, warning)
+    Callout { label: Option<String> },
+    /// Rows and cells from a source that declares table structure; `text`
+    /// is one row per line with cells joined by " | "
+    Table(TableData),
+    /// Image or figure region declared by the backend; `text` is the caption,
+    /// or the image's alt text when there is no caption
+    Figure { caption: Option<String> },
+    /// "Figure 3: ..." / "Table 2. ..." caption with no figure or table near it
+    Caption,
+    /// Running header repeated at the top of pages (title, chapter, page number)
+    Header,
+    /// Running footer repeated at the bottom of pages (page number, notice)
+    Footer,
+    /// One sentence of the paragraph before it, one level below it; the
+    /// paragraph keeps its full text
+    Sentence,
 }
@@ -0,0 +1,39 @@
+//! Preprocessor conformance — the shared XHTML parser and the reference
+//! Tika backend output must satisfy the same suite new backends are held to.
+//!
+//! No JVM required: backend output is checked via the committed snapshots.
+
+use blazegraph_io_core::preprocessors::pdf::xhtml_parser::parse_xhtml;
+use blazegraph_io_core::test_utils::conformance::{self, check_backend_xhtml};
+use std::path::PathBuf;
+
+fn snapshot_xhtml(fixture_name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("test_fixtures/snapshots")
+        .join(fixture_name)
+        .join("stage1a_xhtml.html");
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("Missing fixture: {}. Run `make test-generate-fixtures`", path.display()))
+}
+
+#[test]
+fn xhtml_parser_passes_conformance_suite() {
+    conformance::assert_markup_conformance("xhtml_parser", parse_xhtml);
+}
+
+#[test]
+fn tika_snapshots_satisfy_backend_contract() {
+    for fixture in ["claude_shannon_paper", "elements_of_euclid"] {
+        let violations = check_backend_xhtml(&snapshot_xhtml(fixture));
+        assert!(violations.is_empty(), "{fixture}: {violations:#?}");
+    }
+}
+
+#[test]
+fn backend_contract_flags_malformed_spans() {
+    let xhtml = conformance::BASIC_XHTML
+        .replace(r#"data-bbox="72.0,80.0,300.0,12.0""#, r#"data-bbox="72.0,80.0,-1""#)
+        .replace(r#"data-segment="1""#, r#"data-segment="x""#);
+    let violations = check_backend_xhtml(&xhtml);
+    assert_eq!(violations.len(), 2, "{violations:#?}");
+}
@@ -0,0 +1,172 @@
+//! Peak memory of PDF extraction — the preprocessor parses a backend's XHTML
+//! as it is read, so a huge document's markup is never held whole.
+//!
+//! The counting allocator sees every thread of this binary, so it holds a
+//! single test.
+
+use blazegraph_io_core::preprocessors::pdf::{PdfBackend, PdfBackendImpl, PdfPreprocessor};
+use blazegraph_io_core::preprocessors::Preprocessor;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::Read;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct PeakAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn grow(size: usize) {
+    let now = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(now, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+            grow(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: PeakAllocator = PeakAllocator;
+
+/// Bytes allocated at the peak of `f`, above what was allocated before it
+fn peak_during<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let base = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    let result = f();
+    (result, PEAK.load(Ordering::Relaxed) - base)
+}
+
+const PAGES: usize = 400;
+const SPANS_PER_PAGE: usize = 40;
+
+/// Blazegraph XHTML for a long document, written a page at a time
+struct GeneratedXhtml {
+    page: usize,
+    pending: Vec<u8>,
+    at: usize,
+}
+
+impl GeneratedXhtml {
+    fn new() -> Self {
+        Self {
+            page: 0,
+            pending: b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<html xmlns=\"http://www.w3.org/1999/xhtml\"><body>\n".to_vec(),
+            at: 0,
+        }
+    }
+
+    fn next_chunk(&mut self) -> Option<Vec<u8>> {
+        self.page += 1;
+        if self.page > PAGES + 1 {
+            return None;
+        }
+        if self.page == PAGES + 1 {
+            let style = ".f1 { font-family: Times-Roman; font-size: 12.0px; font-weight: normal; color: #000000; }";
+            return Some(format!("<style type=\"text/css\">{style}</style></body></html>\n").into_bytes());
+        }
+        let mut page = String::from("<div class=\"page\">\n");
+        for line in 0..SPANS_PER_PAGE {
+            let y = 72.0 + line as f32 * 14.0;
+            page.push_str(&format!(
+                "<p><span class=\"f1\" data-bbox=\"72.0,{y:.1},300.0,12.0\" data-line=\"0\" data-segment=\"0\">Page {} line {line}.</span></p>\n",
+                self.page
+            ));
+        }
+        page.push_str("</div>\n");
+        Some(page.into_bytes())
+    }
+}
+
+impl Read for GeneratedXhtml {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.at == self.pending.len() {
+            match self.next_chunk() {
+                Some(chunk) => (self.pending, self.at) = (chunk, 0),
+                None => return Ok(0),
+            }
+        }
+        let read = buf.len().min(self.pending.len() - self.at);
+        buf[..read].copy_from_slice(&self.pending[self.at..self.at + read]);
+        self.at += read;
+        Ok(read)
+    }
+}
+
+/// A backend that can only hand over its output as one string
+struct BufferedBackend;
+
+impl PdfBackend for BufferedBackend {
+    fn extract_to_xhtml(&self, _pdf_bytes: &[u8]) -> anyhow::Result<String> {
+        let mut xhtml = String::new();
+        GeneratedXhtml::new().read_to_string(&mut xhtml)?;
+        Ok(xhtml)
+    }
+
+    fn name(&self) -> &str {
+        "buffered"
+    }
+
+    fn is_healthy(&self) -> bool {
+        true
+    }
+}
+
+/// The same output, streamed as it is produced
+struct StreamingBackend;
+
+impl PdfBackend for StreamingBackend {
+    fn extract_to_xhtml(&self, pdf_bytes: &[u8]) -> anyhow::Result<String> {
+        BufferedBackend.extract_to_xhtml(pdf_bytes)
+    }
+
+    fn extract_to_reader(&self, _pdf_bytes: &[u8]) -> anyhow::Result<Box<dyn Read + '_>> {
+        Ok(Box::new(GeneratedXhtml::new()))
+    }
+
+    fn name(&self) -> &str {
+        "streaming"
+    }
+
+    fn is_healthy(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn streamed_extraction_never_holds_the_whole_markup() {
+    let markup_len = BufferedBackend.extract_to_xhtml(b"%PDF").unwrap().len();
+    let preprocessor = |backend: Box<dyn PdfBackend>| PdfPreprocessor::with_backends(vec![PdfBackendImpl::Custom(backend)]).unwrap();
+    let buffered = preprocessor(Box::new(BufferedBackend));
+    let streaming = preprocessor(Box::new(StreamingBackend));
+
+    let (buffered_extraction, buffered_peak) = peak_during(|| buffered.extract(b"%PDF").unwrap());
+    let (streamed_extraction, streamed_peak) = peak_during(|| streaming.extract(b"%PDF").unwrap());
+
+    assert_eq!(streamed_extraction.output.text_elements.len(), PAGES * SPANS_PER_PAGE);
+    assert_eq!(streamed_extraction.output.text_elements.len(), buffered_extraction.output.text_elements.len());
+    assert_eq!(streamed_extraction.markup_hash, buffered_extraction.markup_hash);
+    println!("markup: {markup_len} bytes, peak buffered: {buffered_peak} bytes, peak streamed: {streamed_peak} bytes");
+    assert!(
+        streamed_peak + markup_len <= buffered_peak,
+        "streaming should save at least the markup ({markup_len} bytes): {streamed_peak} vs {buffered_peak}"
+    );
+}
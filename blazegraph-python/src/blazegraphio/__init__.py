@@ -40,8 +40,10 @@ from blazegraphio.types import (
     PhysicalLocation,
     SemanticLocation,
     StructuralProfile,
+    TextSpan,
     TokenDistribution,
     TokenHistogram,
+    ValidationReport,
 )
 
 __all__ = [
@@ -57,6 +59,7 @@ __all__ = [
     "SemanticLocation",
     "PhysicalLocation",
     "BoundingBox",
+    "TextSpan",
     "NodeContent",
     # Document info types
     "DocumentInfo",
@@ -69,6 +72,7 @@ __all__ = [
     "HistogramBin",
     "NodeTypeDistribution",
     "DepthDistribution",
+    "ValidationReport",
     # Errors
     "BlazeGraphError",
     "BlazeGraphAuthError",
@@ -48,40 +48,125 @@ class PhysicalLocation:
 
     page: int
     bounding_box: BoundingBox
+    page_label: Optional[str] = None
+    """The page number printed on the page ("xiv", "12"), when detected."""
 
     @classmethod
     def from_dict(cls, d: Dict[str, Any]) -> "PhysicalLocation":
         return cls(
             page=d["page"],
             bounding_box=BoundingBox.from_dict(d["bounding_box"]),
+            page_label=d.get("page_label"),
         )
 
 
+@dataclass
+class TextSpan:
+    """Character offsets (end exclusive) into :meth:`BlazeGraph.document_text`."""
+
+    start: int
+    end: int
+
+    @classmethod
+    def from_dict(cls, d: Dict[str, Any]) -> "TextSpan":
+        return cls(start=d["start"], end=d["end"])
+
+
 @dataclass
 class NodeLocation:
     """Combined semantic + physical location."""
 
     semantic: SemanticLocation
     physical: Optional[PhysicalLocation]
+    text_span: Optional[TextSpan] = None
+    """Where the node's text sits in the document text. ``None`` for the root."""
 
     @classmethod
     def from_dict(cls, d: Dict[str, Any]) -> "NodeLocation":
         phys = d.get("physical")
+        span = d.get("text_span")
         return cls(
             semantic=SemanticLocation.from_dict(d["semantic"]),
             physical=PhysicalLocation.from_dict(phys) if phys else None,
+            text_span=TextSpan.from_dict(span) if span else None,
         )
 
 
+@dataclass
+class TableData:
+    """Rows of cell strings; the first ``header_rows`` rows are column headers."""
+
+    rows: List[List[str]]
+    header_rows: int = 0
+
+    @classmethod
+    def from_dict(cls, d: Dict[str, Any]) -> "TableData":
+        return cls(rows=d["rows"], header_rows=d.get("header_rows", 0))
+
+
+@dataclass
+class StyleRun:
+    """A bold and/or italic stretch of a node's text, in character offsets (end exclusive)."""
+
+    start: int
+    end: int
+    bold: bool = False
+    italic: bool = False
+
+    @classmethod
+    def from_dict(cls, d: Dict[str, Any]) -> "StyleRun":
+        return cls(start=d["start"], end=d["end"], bold=d.get("bold", False), italic=d.get("italic", False))
+
+
 @dataclass
 class NodeContent:
-    """Node text content."""
+    """Node text content.
+
+    ``key``/``value`` are set on KeyValue nodes, ``attribution`` on Quote
+    nodes, ``label`` on Callout nodes, ``table_data`` on Table nodes and
+    ``caption`` on Figure and Table nodes.
+    """
 
     text: str
+    key: Optional[str] = None
+    value: Optional[str] = None
+    attribution: Optional[str] = None
+    label: Optional[str] = None
+    table_data: Optional[TableData] = None
+    caption: Optional[str] = None
+    style_runs: List[StyleRun] = field(default_factory=list)
+    """Bold/italic runs in ``text`` (``inline_styles: spans``)."""
 
     @classmethod
     def from_dict(cls, d: Dict[str, Any]) -> "NodeContent":
-        return cls(text=d["text"])
+        table = d.get("table_data")
+        return cls(
+            text=d["text"],
+            key=d.get("key"),
+            value=d.get("value"),
+            attribution=d.get("attribution"),
+            label=d.get("label"),
+            table_data=TableData.from_dict(table) if table else None,
+            caption=d.get("caption"),
+            style_runs=[StyleRun.from_dict(r) for r in d.get("style_runs", [])],
+        )
+
+
+@dataclass
+class Provenance:
+    """The text elements and rules a node was built from (``provenance: true``)."""
+
+    source_elements: List[int] = field(default_factory=list)
+    pages: List[int] = field(default_factory=list)
+    rules: List[str] = field(default_factory=list)
+
+    @classmethod
+    def from_dict(cls, d: Dict[str, Any]) -> "Provenance":
+        return cls(
+            source_elements=list(d.get("source_elements", [])),
+            pages=list(d.get("pages", [])),
+            rules=list(d.get("rules", [])),
+        )
 
 
 # ---------------------------------------------------------------------------
@@ -101,9 +186,15 @@ class DocumentNode:
     token_count: int
     parent: Optional[str]
     children: List[str]
+    references: List[str] = field(default_factory=list)
+    links: List[Dict[str, str]] = field(default_factory=list)
+    """Hyperlinks anchored in the node's text: ``href`` and anchor ``text``."""
+    annotations: Dict[str, Any] = field(default_factory=dict)
+    provenance: Optional[Provenance] = None
 
     @classmethod
     def from_dict(cls, d: Dict[str, Any]) -> "DocumentNode":
+        provenance = d.get("provenance")
         return cls(
             id=d["id"],
             node_type=d["node_type"],
@@ -113,6 +204,10 @@ class DocumentNode:
             token_count=d["token_count"],
             parent=d.get("parent"),
             children=list(d.get("children", [])),
+            references=list(d.get("references", [])),
+            links=[dict(link) for link in d.get("links", [])],
+            annotations=dict(d.get("annotations", {})),
+            provenance=Provenance.from_dict(provenance) if provenance else None,
         )
 
     # -- Tree navigation helpers --
@@ -127,6 +222,10 @@ class DocumentNode:
         """Return resolved child nodes in text order."""
         return [graph.get_node(cid) for cid in self.children]
 
+    def get_references(self, graph: "BlazeGraph") -> List["DocumentNode"]:
+        """Return the sections this node's text refers to ("see Section 4.2")."""
+        return [graph.get_node(rid) for rid in self.references]
+
     # -- Render --
 
     def render(
@@ -251,6 +350,36 @@ class DocumentAnalysis:
         )
 
 
+@dataclass
+class PageLabel:
+    """The number printed on a physical page."""
+
+    page: int
+    label: str
+    style: str
+    """``arabic`` or ``roman``."""
+    inferred: bool = False
+    """True when the page carries no number and the label was filled in from its neighbours."""
+
+    @classmethod
+    def from_dict(cls, d: Dict[str, Any]) -> "PageLabel":
+        return cls(page=d["page"], label=d["label"], style=d["style"], inferred=d.get("inferred", False))
+
+
+@dataclass
+class GlossaryEntry:
+    """A term the document defines, and the nodes defining and using it."""
+
+    term: str
+    definition: str
+    defined_in: str
+    used_in: List[str] = field(default_factory=list)
+
+    @classmethod
+    def from_dict(cls, d: Dict[str, Any]) -> "GlossaryEntry":
+        return cls(term=d["term"], definition=d["definition"], defined_in=d["defined_in"], used_in=list(d.get("used_in", [])))
+
+
 @dataclass
 class DocumentInfo:
     """Document-level metadata — information *about* the document."""
@@ -258,6 +387,12 @@ class DocumentInfo:
     root_id: str
     document_metadata: DocumentMetadata
     document_analysis: DocumentAnalysis
+    content_hash: Optional[str] = None
+    """Same for documents with the same content and structure (deduplication)."""
+    page_labels: List[PageLabel] = field(default_factory=list)
+    """Printed page labels, one per labelled physical page."""
+    glossary: List[GlossaryEntry] = field(default_factory=list)
+    """Defined terms, in order of definition (``glossary`` config)."""
 
     @classmethod
     def from_dict(cls, d: Dict[str, Any]) -> "DocumentInfo":
@@ -265,6 +400,9 @@ class DocumentInfo:
             root_id=d["root_id"],
             document_metadata=DocumentMetadata.from_dict(d.get("document_metadata", {})),
             document_analysis=DocumentAnalysis.from_dict(d.get("document_analysis", {})),
+            content_hash=d.get("content_hash"),
+            page_labels=[PageLabel.from_dict(l) for l in d.get("page_labels", [])],
+            glossary=[GlossaryEntry.from_dict(g) for g in d.get("glossary", [])],
         )
 
 
@@ -368,6 +506,25 @@ class DepthDistribution:
         )
 
 
+@dataclass
+class ValidationReport:
+    """Structural issues found by the ``Validation`` rule."""
+
+    issues: List[Dict[str, Any]] = field(default_factory=list)
+    quality_score: float = 1.0
+    total_elements: int = 0
+    fixes: Optional[Dict[str, int]] = None
+
+    @classmethod
+    def from_dict(cls, d: Dict[str, Any]) -> "ValidationReport":
+        return cls(
+            issues=list(d.get("issues", [])),
+            quality_score=d.get("quality_score", 1.0),
+            total_elements=d.get("total_elements", 0),
+            fixes=d.get("fixes"),
+        )
+
+
 @dataclass
 class StructuralProfile:
     """Statistical properties of the document graph."""
@@ -380,12 +537,14 @@ class StructuralProfile:
     token_distribution: Optional[TokenDistribution] = None
     node_type_distribution: Optional[NodeTypeDistribution] = None
     depth_distribution: Optional[DepthDistribution] = None
+    validation: Optional[ValidationReport] = None
 
     @classmethod
     def from_dict(cls, d: Dict[str, Any]) -> "StructuralProfile":
         td = d.get("token_distribution")
         ntd = d.get("node_type_distribution")
         dd = d.get("depth_distribution")
+        vr = d.get("validation")
         return cls(
             created_at=d.get("created_at", ""),
             document_type=d.get("document_type", "Generic"),
@@ -395,6 +554,7 @@ class StructuralProfile:
             token_distribution=TokenDistribution.from_dict(td) if td else None,
             node_type_distribution=NodeTypeDistribution.from_dict(ntd) if ntd else None,
             depth_distribution=DepthDistribution.from_dict(dd) if dd else None,
+            validation=ValidationReport.from_dict(vr) if vr else None,
         )
 
 
@@ -472,6 +632,119 @@ class BlazeGraph:
         """Render the full document as human-readable text."""
         return self.root.render(self, breadcrumbs=breadcrumbs, node_types=node_types)
 
+    def document_text(self) -> str:
+        """Every node's text in document order, separated by blank lines.
+
+        Node ``location.text_span`` offsets index into this string. Sentence
+        nodes repeat their paragraph's text and aren't added again.
+        """
+        texts: List[str] = []
+        stack = [self.root]
+        while stack:
+            node = stack.pop()
+            if node is not self.root and node.content.text and node.node_type != "Sentence":
+                texts.append(node.content.text)
+            stack.extend(self._index[child] for child in reversed(node.children) if child in self._index)
+        return "\n\n".join(texts)
+
+    # -- Citations --
+
+    def citation_id(self, node: DocumentNode) -> str:
+        """Stable citation id for a node: ``<hash prefix>:<semantic path>``.
+
+        Matches the ``citation`` field of sequential output and the ids
+        accepted by ``blazegraph-io cite``.
+
+        Raises:
+            ValueError: If the graph has no content hash.
+        """
+        if not self.document_info.content_hash:
+            raise ValueError("graph has no content_hash; re-parse it to get citation ids")
+        prefix = self.document_info.content_hash[:8]
+        path = node.location.semantic.path
+        return f"{prefix}:{path}" if path else prefix
+
+    def resolve_citation(self, citation_id: str) -> DocumentNode:
+        """Look up the node a citation id names.
+
+        Raises:
+            KeyError: If the id belongs to another document or names no node.
+        """
+        prefix, _, path = citation_id.strip().partition(":")
+        if prefix.lower() != self.citation_id(self.root).lower():
+            raise KeyError(f"citation {citation_id!r} belongs to another document")
+        if not path:
+            return self.root
+        for node in self.nodes:
+            if node.location.semantic.path == path:
+                return node
+        raise KeyError(f"citation {citation_id!r} names no node in this graph")
+
+    # -- Prompting --
+
+    def pack_context(
+        self,
+        relevant: List[DocumentNode],
+        *,
+        token_budget: int = 2000,
+        breadcrumbs: bool = True,
+        ellipsis: str = "[…]",
+    ) -> str:
+        """Pack retrieved nodes into one prompt-ready string.
+
+        Takes ``relevant`` (most relevant first) while it fits ``token_budget``,
+        then writes the nodes taken in document order: each section's nodes
+        under a ``[Title > 4 Fees]`` trail, with ``ellipsis`` where siblings
+        were left out. Same layout as ``DocumentGraph::pack_context`` in Rust.
+        """
+        order = {node.id: index for index, node in enumerate(self._preorder())}
+        taken: List[DocumentNode] = []
+        packed = ""
+        for node in relevant:
+            if node.id not in order or node is self.root or any(n.id == node.id for n in taken):
+                continue
+            candidate = sorted(taken + [node], key=lambda n: order[n.id])
+            text, tokens = self._render_packed(candidate, breadcrumbs, ellipsis)
+            if tokens <= token_budget:
+                taken, packed = candidate, text
+        return packed
+
+    def _preorder(self) -> List[DocumentNode]:
+        nodes: List[DocumentNode] = []
+        stack = [self.root]
+        while stack:
+            node = stack.pop()
+            nodes.append(node)
+            stack.extend(self._index[child] for child in reversed(node.children) if child in self._index)
+        return nodes
+
+    def _render_packed(self, nodes: List[DocumentNode], breadcrumbs: bool, ellipsis: str) -> tuple:
+        blocks: List[str] = []
+        scaffolding = 0
+        node_tokens = 0
+        trail: Optional[List[str]] = None
+        previous: Optional[DocumentNode] = None
+        for node in nodes:
+            crumbs = node.location.semantic.breadcrumbs
+            context = crumbs[:-1] if node.node_type == "Section" and crumbs else crumbs
+            if breadcrumbs and context and context != trail:
+                blocks.append(f"[{' > '.join(context)}]")
+                scaffolding += len(blocks[-1].encode())
+            trail = context
+            parent = self._index.get(node.parent) if node.parent else None
+            if parent is not None and node.id in parent.children:
+                first_unseen = 0
+                if previous is not None and previous.parent == node.parent and previous.id in parent.children:
+                    first_unseen = parent.children.index(previous.id) + 1
+                if parent.children.index(node.id) > first_unseen:
+                    blocks.append(ellipsis)
+                    scaffolding += len(ellipsis.encode())
+            blocks.append(node.content.text)
+            node_tokens += node.token_count
+            previous = node
+        scaffolding += 2 * max(len(blocks) - 1, 0)
+        return "\n\n".join(blocks), node_tokens + scaffolding // 4
+
     # -- Serialization --
 
     def to_dict(self) -> Dict[str, Any]:
@@ -259,6 +259,30 @@ class TestRenderBoth:
         assert "shannon1948" not in output
 
 
+class TestPackContext:
+    """Verify budgeted packing of retrieved nodes."""
+
+    def test_trail_and_ellipsis(self) -> None:
+        graph = _make_mini_graph()
+        packed = graph.pack_context([graph.get_node("para-002")])
+        assert packed == (
+            "[shannon1948.dvi > A Mathematical Theory of Communication]\n\n"
+            "[…]\n\n"
+            "Frequently the messages have meaning; that is they refer to or are correlated "
+            "according to some system with certain physical or conceptual entities."
+        )
+
+    def test_document_order_within_budget(self) -> None:
+        graph = _make_mini_graph()
+        relevant = [graph.get_node("para-002"), graph.get_node("para-001")]
+        packed = graph.pack_context(relevant)
+        assert packed.index("The fundamental problem") < packed.index("Frequently")
+        assert "[…]" not in packed
+        # Only the most relevant node fits
+        assert graph.pack_context(relevant, token_budget=45).count("\n\n") == 2
+        assert graph.pack_context(relevant, token_budget=0) == ""
+
+
 class TestFullDocumentRender:
     """Test graph.render() on the full Shannon fixture."""
 
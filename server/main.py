@@ -13,7 +13,7 @@ import tempfile
 from pathlib import Path
 
 from fastapi import FastAPI, File, Query, UploadFile
-from fastapi.responses import JSONResponse
+from fastapi.responses import HTMLResponse, JSONResponse, Response
 
 app = FastAPI(
     title="Blazegraph IO — Self-Hosted",
@@ -35,8 +35,8 @@ async def health() -> dict:
 async def process_pdf(
     file: UploadFile = File(...),
     config: str | None = Query(None, description="Path to config YAML"),
-    output_format: str = Query("graph", description="graph, sequential, or flat"),
-) -> JSONResponse:
+    output_format: str = Query("graph", description="graph, sequential, flat, chunks, jsonld, ntriples or html"),
+) -> Response:
     # Validate file type
     if file.content_type and file.content_type != "application/pdf":
         if not (file.filename and file.filename.lower().endswith(".pdf")):
@@ -46,7 +46,7 @@ async def process_pdf(
             )
 
     # Validate output format
-    valid_formats = {"graph", "sequential", "flat"}
+    valid_formats = {"graph", "sequential", "flat", "chunks", "jsonld", "ntriples", "html"}
     if output_format not in valid_formats:
         return JSONResponse(
             status_code=400,
@@ -96,7 +96,15 @@ async def process_pdf(
                 content={"error": "CLI completed but no output file was produced"},
             )
 
+        if output_format == "html":
+            return HTMLResponse(content=output_path.read_text())
+        if output_format == "ntriples":
+            return Response(content=output_path.read_text(), media_type="application/n-triples")
+        if output_format == "chunks":
+            return Response(content=output_path.read_text(), media_type="application/jsonl")
         output_data = json.loads(output_path.read_text())
+        if output_format == "jsonld":
+            return JSONResponse(content=output_data, media_type="application/ld+json")
         return JSONResponse(content=output_data)
 
     except subprocess.TimeoutExpired: